@@ -7,6 +7,13 @@ fn main() {
     println!("cargo:rerun-if-changed=client/src");
     println!("cargo:rerun-if-changed=client/public");
     println!("cargo:rerun-if-changed=shared/src");
+    println!("cargo:rerun-if-changed=proto");
+    /* generate the server of the gRPC interface */
+    #[cfg(feature = "grpc")]
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/supervisor.proto"], &["proto"])
+        .expect("Could not compile the gRPC interface, is protoc installed?");
     /* set up some environmental variables */
     let out_dir = std::env::var_os("OUT_DIR").unwrap();
     let js_file = Path::new(&out_dir).join("client.js");
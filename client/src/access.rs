@@ -0,0 +1,18 @@
+use std::{cell::RefCell, collections::BTreeSet};
+use shared::access::{Category, Update};
+
+thread_local! {
+    /* the permissions of this client, everything is permitted until the supervisor has sent them */
+    static PERMISSIONS: RefCell<Option<BTreeSet<Category>>> = RefCell::new(None);
+}
+
+pub fn set_permissions(update: &Update) {
+    PERMISSIONS.with(|permissions| *permissions.borrow_mut() = Some(update.permissions.clone()));
+}
+
+/// Whether the role of this client permits requests of the given category. This is only used to hide and
+/// disable controls, the permissions are enforced by the supervisor
+pub fn permitted(category: Category) -> bool {
+    PERMISSIONS.with(|permissions| permissions.borrow().as_ref()
+        .map_or(true, |permissions| permissions.contains(&category)))
+}
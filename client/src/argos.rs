@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use yew::prelude::*;
+
+/* the maximum number of bytes of output from ARGoS that are kept for a robot */
+const CAPACITY: usize = 16384;
+
+/// The output of ARGoS while it runs an experiment on a robot, only the output that was received since the
+/// card was shown is available
+#[derive(Default)]
+pub struct Output {
+    chunks: VecDeque<(bool, String)>,
+    length: usize,
+    /* the experiment has finished, the output is replaced once ARGoS writes to it again */
+    finished: bool,
+}
+
+impl Output {
+    pub fn push(&mut self, error: bool, text: String) {
+        if std::mem::take(&mut self.finished) {
+            self.chunks.clear();
+            self.length = 0;
+        }
+        self.length += text.len();
+        self.chunks.push_back((error, text));
+        while self.length > CAPACITY {
+            match self.chunks.pop_front() {
+                Some((_, text)) => self.length -= text.len(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+/// Renders a collapsible panel with the output of ARGoS, where standard error is highlighted
+pub fn render(output: &Output, visible: bool, node_ref: NodeRef, ontoggle: Callback<MouseEvent>) -> Html {
+    let label = match visible {
+        true => t!("robot.argos.hide"),
+        false => t!("robot.argos.show"),
+    };
+    html! {
+        <>
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.argos") }</p>
+                </div>
+                <div class="level-right">
+                    <button class="level-item button is-small" onclick=ontoggle>{ label }</button>
+                </div>
+            </nav>
+            {
+                match visible {
+                    true => html! {
+                        <pre ref=node_ref style="max-height:240px;overflow-y:auto"> {
+                            match output.chunks.is_empty() {
+                                true => html! { { t!("robot.argos.none") } },
+                                false => output.chunks.iter().map(|(error, text)| html! {
+                                    <span class=classes!(error.then(|| "has-text-danger"))>{ text }</span>
+                                }).collect::<Html>(),
+                            }
+                        } </pre>
+                    },
+                    false => html! {},
+                }
+            }
+        </>
+    }
+}
+
+/// Scrolls the panel to the latest output
+pub fn follow(node_ref: &NodeRef) {
+    if let Some(element) = node_ref.cast::<web_sys::Element>() {
+        element.set_scroll_top(element.scroll_height());
+    }
+}
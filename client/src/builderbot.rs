@@ -1,14 +1,17 @@
 use std::{cell::RefCell, collections::HashMap, net::Ipv4Addr, rc::Rc};
-use shared::{BackEndRequest, builderbot::{Descriptor, Request, Update}};
+use shared::{BackEndRequest, access::Category, experiment::{Excerpt, Summary}, infrastructure::{Port, Station}, builderbot::{Descriptor, Request, Update}};
 use web_sys::HtmlInputElement;
-use yew::{prelude::*, web_sys::HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::{access, terminal::{Buffer, Shell, Terminal}};
 
 enum DuoVero {
     Connected {
         addr: Ipv4Addr,
         battery: Result<i32, String>,
         signal: Result<i32, String>,
-        terminal: String,
+        /* the amount of queued terminal input that has been sent to the robot */
+        terminal_input: Option<(usize, usize)>,
     },
     Disconnected,
 }
@@ -16,7 +19,44 @@ enum DuoVero {
 pub struct Instance {
     pub descriptor: Descriptor,
     pub optitrack_pos: [f32; 3],
+    /* the speed estimated by the tracking system in meters per second */
+    pub optitrack_speed: f32,
+    /* the name of the rigid body in Motive and a suggested identifier based on that name */
+    pub optitrack_name: Option<String>,
+    pub optitrack_suggestion: Option<i32>,
+    /* set via the command palette to open the terminal once the card is shown */
+    pub terminal_requested: bool,
+    /* the association and switch port of the robot as reported by the infrastructure (via SNMP) */
+    pub access_point: Option<Station>,
+    pub switch_port: Option<Port>,
+    resource_limit_violation: Option<String>,
+    /* the uptime of the robot and the duration of its association with the supervisor in seconds */
+    uptime: Option<(u64, u64)>,
+    firmware: Option<String>,
+    /* whether the camera streams are supported, as checked when the robot was associated */
+    cameras: Option<shared::camera::Availability>,
+    unexpected_reboot: Option<String>,
+    /* the interventions of the watchdog on the robot since the previous association */
+    watchdog: Vec<String>,
+    /* the actions that will be executed once the robot is associated again */
+    queued_actions: Vec<(u64, String)>,
+    /* the files collected from the robot after the last experiment */
+    artifacts: Option<Result<Vec<String>, String>>,
+    /* a summary of the last experiment, this is persisted by the supervisor across sessions */
+    pub last_experiment: Option<Summary>,
+    /* the last lines that ARGoS wrote during the last experiment, fetched from the journal on demand */
+    pub excerpt: Option<Excerpt>,
+    /* the output of ARGoS during the current experiment */
+    pub argos: crate::argos::Output,
+    /* the events of the robot as recorded by the supervisor */
+    events: crate::event_log::EventLog,
+    /* the recent readings of the robot, None until they have been requested from the supervisor */
+    pub telemetry: Option<shared::telemetry::History>,
+    /* the output of the Bash terminal and the commands entered into it, kept across reconnections */
+    bash: Buffer,
     duovero: DuoVero,
+    /* the last known address while the supervisor waits for the robot to reconnect */
+    reconnecting: Option<Ipv4Addr>,
     camera_stream: HashMap<String, Result<String, String>>,
 }
 
@@ -27,12 +67,83 @@ impl Instance {
         Self { 
             descriptor, 
             optitrack_pos: [0.0, 0.0, 0.0],
+            optitrack_speed: 0.0,
+            optitrack_name: None,
+            optitrack_suggestion: None,
+            terminal_requested: false,
+            access_point: None,
+            switch_port: None,
+            resource_limit_violation: None,
+            uptime: None,
+            firmware: None,
+            cameras: None,
+            unexpected_reboot: None,
+            watchdog: Vec::new(),
+            queued_actions: Vec::new(),
+            artifacts: None,
+            last_experiment: None,
+            excerpt: None,
+            argos: Default::default(),
+            events: Default::default(),
+            telemetry: None,
+            bash: Default::default(),
             duovero: DuoVero::Disconnected,
+            reconnecting: None,
             camera_stream: Default::default(),
         }
     }
 
+    /// The address of the DuoVero, from which ARGoS connects to the message router
+    pub fn addr(&self) -> Option<Ipv4Addr> {
+        match self.duovero {
+            DuoVero::Connected { addr, .. } => Some(addr),
+            DuoVero::Disconnected => None,
+        }
+    }
+
+    /// The request for the recent readings of the robot, which is only returned once so that the readings are
+    /// not requested again whenever the card is rendered
+    pub fn telemetry_request(&mut self) -> Option<BackEndRequest> {
+        match self.telemetry {
+            Some(_) => None,
+            None => {
+                self.telemetry = Some(Default::default());
+                Some(BackEndRequest::TelemetryRequest(self.descriptor.id.clone()))
+            }
+        }
+    }
+
+    /// Replaces the readings of the robot with those kept by the supervisor. The output of the terminals is
+    /// only restored if nothing has been received since the card was shown
+    pub fn restore(&mut self, history: shared::telemetry::History) {
+        if self.bash.is_empty() {
+            self.bash.push(&history.bash);
+        }
+        self.telemetry = Some(history);
+    }
+
+    /// The state of the robot that is aggregated into the statistics of the swarm
+    pub fn status(&self) -> crate::swarm::Status {
+        let (connected, battery, signal) = match &self.duovero {
+            DuoVero::Connected { battery, signal, .. } => (true, battery.as_ref().ok().copied(), signal.as_ref().ok().copied()),
+            DuoVero::Disconnected => (false, None, None),
+        };
+        crate::swarm::Status {
+            connected,
+            battery,
+            signal,
+            error: self.resource_limit_violation.is_some() || self.unexpected_reboot.is_some() || !self.watchdog.is_empty(),
+        }
+    }
+
     pub fn update(&mut self, update: Update) {
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            match &update {
+                Update::Battery(level) => crate::telemetry::record(&mut telemetry.battery, *level),
+                Update::FernbedienungSignal(strength) => crate::telemetry::record(&mut telemetry.signal, *strength),
+                _ => {}
+            }
+        }
         match update {
             Update::Battery(reading) => if let DuoVero::Connected { battery, ..} = &mut self.duovero {
                 *battery = Ok(reading);
@@ -42,22 +153,64 @@ impl Instance {
                     .insert(camera, result
                         .map(|bytes| base64::encode(bytes)));
             },
-            Update::FernbedienungConnected(addr) => 
+            Update::FernbedienungConnected(addr) => {
+                self.reconnecting = None;
                 self.duovero = DuoVero::Connected {
                     addr,
-                    battery: Err(String::from("Unknown")),
-                    signal: Err(String::from("Unknown")),
-                    terminal: Default::default(),
-                },
-            Update::FernbedienungDisconnected => 
-                self.duovero = DuoVero::Disconnected,
+                    battery: Err(t!("common.unknown")),
+                    signal: Err(t!("common.unknown")),
+                    terminal_input: None,
+                };
+            },
+            Update::FernbedienungDisconnected => {
+                self.duovero = DuoVero::Disconnected;
+                self.reconnecting = None;
+                self.uptime = None;
+                self.firmware = None;
+                self.cameras = None;
+            },
+            Update::FernbedienungReconnecting(addr) => {
+                self.duovero = DuoVero::Disconnected;
+                self.reconnecting = Some(addr);
+                self.uptime = None;
+                self.firmware = None;
+                self.cameras = None;
+            },
             Update::FernbedienungSignal(strength) => {
                 if let DuoVero::Connected { signal, ..} = &mut self.duovero {
                     *signal = Ok(strength);
                 }
             },
-            Update::Bash(response) => if let DuoVero::Connected { terminal, ..} = &mut self.duovero {
-                terminal.push_str(&response);
+            Update::ResourceLimitViolation(violation) =>
+                self.resource_limit_violation = Some(violation),
+            Update::Uptime { robot, association } =>
+                self.uptime = Some((robot, association)),
+            Update::Firmware(firmware) =>
+                self.firmware = Some(firmware),
+            Update::Cameras(availability) =>
+                self.cameras = Some(availability),
+            Update::UnexpectedReboot(reboot) =>
+                self.unexpected_reboot = Some(reboot),
+            Update::Watchdog(events) =>
+                self.watchdog = events,
+            Update::QueuedActions(actions) =>
+                self.queued_actions = actions,
+            Update::Artifacts(result) =>
+                self.artifacts = Some(result),
+            Update::ExperimentSummary(summary) => {
+                self.last_experiment = Some(summary);
+                self.excerpt = None;
+                self.argos.finish();
+            },
+            Update::Bash(response) => self.bash.push(&response),
+            Update::ArgosOutput { error, text } => self.argos.push(error, text),
+            Update::Event(entry) => self.events.push(entry),
+            Update::EventLog(entries) => self.events.replace(entries),
+            Update::BashProgress { sent, total } => if let DuoVero::Connected { terminal_input, ..} = &mut self.duovero {
+                *terminal_input = match sent < total {
+                    true => Some((sent, total)),
+                    false => None,
+                };
             },
         }
     }
@@ -67,9 +220,11 @@ pub struct Card {
     link: ComponentLink<Self>,
     props: Props,
     bash_terminal_visible: bool,
-    bash_textarea: NodeRef,
-    bash_input: NodeRef,
     camera_dialog_active: bool,
+    argos_visible: bool,
+    argos_output: NodeRef,
+    events_visible: bool,
+    results_input: NodeRef,
     error: Result<(), String>,
 }
 
@@ -83,7 +238,11 @@ pub enum Msg {
     SetError(Result<(), String>),
     ToggleBashTerminal,
     ToggleCameraStream,
-    SendBashCommand,
+    FetchResults,
+    ToggleExcerpt,
+    ToggleArgosOutput,
+    ToggleEvents,
+    SendBashCommand(String),
 }
 
 // is it possible to just add a callback to the update method
@@ -92,22 +251,29 @@ impl Component for Card {
     type Properties = Props;
 
     fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        let request = props.instance.borrow_mut().telemetry_request();
+        if let Some(request) = request {
+            props.parent.send_message(crate::Msg::SendRequest(request, None));
+        }
         // if props contains a closure, I could use that to communicate with the actual instance
+        if std::mem::take(&mut props.instance.borrow_mut().terminal_requested) {
+            link.send_message(Msg::ToggleBashTerminal);
+        }
         Card { 
             props,
             link,
             bash_terminal_visible: false,
-            bash_textarea: NodeRef::default(),
-            bash_input: NodeRef::default(),
             camera_dialog_active: false,
+            argos_visible: false,
+            argos_output: NodeRef::default(),
+            events_visible: false,
+            results_input: NodeRef::default(),
             error: Ok(())
         }
     }
 
     fn rendered(&mut self, _: bool) {
-        if let Some(textarea) = self.bash_textarea.cast::<HtmlTextAreaElement>() {
-            textarea.set_scroll_top(textarea.scroll_height());
-        }
+        crate::argos::follow(&self.argos_output);
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
@@ -117,23 +283,17 @@ impl Component for Card {
                 self.error = error;
                 true
             },
-            Msg::SendBashCommand => match self.bash_input.cast::<HtmlInputElement>() {
-                Some(input) => {
-                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
-                    let builderbot_request = Request::BashTerminalRun(input.value());
-                    input.set_value("");
-                    let request = BackEndRequest::BuilderBotRequest(builderbot.descriptor.id.clone(), builderbot_request);
-                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
-                    true
-                },
-                _ => false
+            Msg::SendBashCommand(command) => {
+                builderbot.bash.record(&command);
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let builderbot_request = Request::BashTerminalRun(command);
+                let request = BackEndRequest::BuilderBotRequest(builderbot.descriptor.id.clone(), builderbot_request);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                true
             },
             Msg::ToggleBashTerminal => {
                 match self.bash_terminal_visible {
                     false => {
-                        if let DuoVero::Connected { terminal, .. } = &mut builderbot.duovero {
-                            terminal.clear();
-                        }
                         let callback = Some(self.link.callback(|result| Msg::SetError(result)));
                         let builderbot_request = Request::BashTerminalStart;
                         let request = BackEndRequest::BuilderBotRequest(builderbot.descriptor.id.clone(), builderbot_request);
@@ -150,6 +310,37 @@ impl Component for Card {
                 }
                 true
             },
+            Msg::ToggleArgosOutput => {
+                self.argos_visible = !self.argos_visible;
+                true
+            },
+            Msg::ToggleEvents => {
+                self.events_visible = !self.events_visible;
+                true
+            },
+            Msg::ToggleExcerpt => match builderbot.excerpt.take() {
+                Some(_) => true,
+                None => {
+                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                    let request = BackEndRequest::ExcerptRequest(builderbot.descriptor.id.clone());
+                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                    false
+                }
+            },
+            Msg::FetchResults => match self.results_input.cast::<HtmlInputElement>() {
+                Some(input) => {
+                    let paths = input.value().split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+                    if !paths.is_empty() {
+                        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                        let builderbot_request = Request::FetchResults(paths);
+                        input.set_value("");
+                        let request = BackEndRequest::BuilderBotRequest(builderbot.descriptor.id.clone(), builderbot_request);
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                    }
+                    false
+                },
+                _ => false
+            },
             Msg::ToggleCameraStream => {
                 match self.camera_dialog_active {
                     false => {
@@ -175,13 +366,20 @@ impl Component for Card {
 
     // this fires when the parent changes the properties of this component
     fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        let request = self.props.instance.borrow_mut().telemetry_request();
+        if let Some(request) = request {
+            self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+        }
+        if std::mem::take(&mut self.props.instance.borrow_mut().terminal_requested) && !self.bash_terminal_visible {
+            self.link.send_message(Msg::ToggleBashTerminal);
+        }
         true
     }
 
     fn view(&self) -> Html {
         let builderbot = self.props.instance.borrow();
         let (batt_level, batt_info) = match &builderbot.duovero {
-            DuoVero::Disconnected => (0, String::from("Unknown")),
+            DuoVero::Disconnected => (0, t!("common.unknown")),
             DuoVero::Connected { battery, .. } => match battery {
                 Err(message) => (0, message.clone()),
                 Ok(level) => (match level {
@@ -210,7 +408,22 @@ impl Component for Card {
                 <div class="card-content">
                     <div class="content">
                         { self.render_duovero(&builderbot) }
+                        { self.render_infrastructure(&builderbot) }
+                        { self.render_uptime(&builderbot) }
+                        { self.render_firmware(&builderbot) }
+                        { crate::telemetry::render(builderbot.telemetry.as_ref()) }
                         { self.render_identifiers(&builderbot) }
+                        { self.render_resource_limit_violation(&builderbot) }
+                        { self.render_unexpected_reboot(&builderbot) }
+                        { self.render_watchdog(&builderbot) }
+                        { self.render_queued_actions(&builderbot) }
+                        { crate::event_log::render(&builderbot.events, self.events_visible,
+                            self.link.callback(|_| Msg::ToggleEvents)) }
+                        { crate::argos::render(&builderbot.argos, self.argos_visible, self.argos_output.clone(),
+                            self.link.callback(|_| Msg::ToggleArgosOutput)) }
+                        { self.render_last_experiment(&builderbot) }
+                        { self.render_artifacts(&builderbot) }
+                        { self.render_results() }
                     </div>
                 </div>
                 { self.render_menu(&builderbot) }
@@ -222,6 +435,278 @@ impl Component for Card {
 }
 
 impl Card {
+    fn render_infrastructure(&self, builderbot: &Instance) -> Html {
+        if builderbot.access_point.is_none() && builderbot.switch_port.is_none() {
+            return html! {};
+        }
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.infrastructure") }</p>
+                </div>
+                <div class="level-right">
+                    {
+                        builderbot.access_point.as_ref().map_or_else(|| html! {}, |station| html! {
+                            <p class="level-item">
+                                <span class="tag is-info">{
+                                    match station.rssi {
+                                        Some(rssi) => t!("robot.infrastructure.station.rssi", access_point = station.access_point, rssi = rssi),
+                                        None => t!("robot.infrastructure.station", access_point = station.access_point),
+                                    }
+                                }</span>
+                            </p>
+                        })
+                    }
+                    {
+                        builderbot.switch_port.as_ref().map_or_else(|| html! {}, |port| html! {
+                            <p class="level-item">
+                                <span class=classes!("tag", if port.up { "is-success" } else { "is-danger" })>
+                                    { t!("robot.infrastructure.port", switch = port.switch, port = port.port) }
+                                </span>
+                            </p>
+                        })
+                    }
+                </div>
+            </nav>
+        }
+    }
+
+    fn render_last_experiment(&self, builderbot: &Instance) -> Html {
+        match builderbot.last_experiment.as_ref() {
+            Some(summary) => html! {
+                <>
+                    <nav class="level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item">{ t!("robot.last_experiment") }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item">
+                                <span class=classes!("tag", if summary.failed { "is-danger" } else { "is-success" })
+                                      title=t!("robot.last_experiment.finished", time = summary.finished)>
+                                    { &summary.exit }
+                                </span>
+                            </p>
+                            <p class="level-item">
+                                <span class="tag" title=t!("robot.last_experiment.output")>
+                                    { crate::experiment::format_bytes(summary.output) }
+                                </span>
+                            </p>
+                            <p class="level-item">
+                                <span class="tag" title=t!("robot.last_experiment.messages")>
+                                    { t!("robot.last_experiment.message_count", count = summary.messages) }
+                                </span>
+                            </p>
+                            {
+                                match summary.energy.as_ref() {
+                                    Some(energy) => html! {
+                                        <p class="level-item">
+                                            <span class="tag" title=t!("robot.last_experiment.energy.description")>
+                                                { t!("robot.last_experiment.energy", consumed = energy.consumed()) }
+                                            </span>
+                                        </p>
+                                    },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                    </nav>
+                    {
+                        summary.events.iter().map(|event| html! {
+                            <div class="notification is-warning">{ event }</div>
+                        }).collect::<Html>()
+                    }
+                    {
+                        match summary.backtrace.as_ref() {
+                            Some(backtrace) => html! {
+                                <div class="notification is-danger">
+                                    <p>{ t!("robot.last_experiment.backtrace") }</p>
+                                    <pre>{ backtrace }</pre>
+                                </div>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    { self.render_excerpt(builderbot) }
+                </>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_excerpt(&self, builderbot: &Instance) -> Html {
+        let onclick = self.link.callback(|_| Msg::ToggleExcerpt);
+        match builderbot.excerpt.as_ref() {
+            Some(excerpt) => html! {
+                <div class="notification">
+                    <button class="delete" onclick=onclick></button>
+                    <p>{
+                        match excerpt.omitted {
+                            0 => t!("robot.last_experiment.excerpt"),
+                            omitted => t!("robot.last_experiment.excerpt.omitted", count = omitted),
+                        }
+                    }</p>
+                    <pre>{
+                        excerpt.lines.iter().map(|(error, line)| html! {
+                            <span class=classes!(error.then(|| "has-text-danger"))>{ line }{ "\n" }</span>
+                        }).collect::<Html>()
+                    }</pre>
+                </div>
+            },
+            None => html! {
+                <div class="buttons">
+                    <button class="button is-small" onclick=onclick>{ t!("robot.last_experiment.excerpt.show") }</button>
+                </div>
+            },
+        }
+    }
+
+    fn render_results(&self) -> Html {
+        let fetch_onclick = self.link.callback(|_| Msg::FetchResults);
+        let fetch_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
+            "Enter" => Some(Msg::FetchResults),
+            _ => None,
+        });
+        html! {
+            <div class="field has-addons">
+                <div class="control is-expanded">
+                    <input ref=self.results_input.clone()
+                           class="input is-family-monospace"
+                           type="text"
+                           placeholder=t!("robot.results.placeholder")
+                           onkeydown=fetch_onkeydown />
+                </div>
+                <div class="control">
+                    <button class="button" onclick=fetch_onclick disabled=!access::permitted(Category::Experiment)>
+                        { t!("robot.results.fetch") }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_artifacts(&self, builderbot: &Instance) -> Html {
+        match builderbot.artifacts.as_ref() {
+            Some(Ok(artifacts)) => html! {
+                <div class="notification is-info">{
+                    match artifacts.len() {
+                        0 => t!("robot.artifacts.none"),
+                        _ => t!("robot.artifacts.collected", artifacts = artifacts.join(", ")),
+                    }
+                }</div>
+            },
+            Some(Err(error)) => html! {
+                <div class="notification is-danger">{ t!("robot.artifacts.error", error = error) }</div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_firmware(&self, builderbot: &Instance) -> Html {
+        match builderbot.firmware.as_ref() {
+            Some(firmware) => html! {
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("robot.firmware") }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item">
+                            <span class="tag">{ firmware }</span>
+                        </p>
+                    </div>
+                </nav>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_uptime(&self, builderbot: &Instance) -> Html {
+        match builderbot.uptime {
+            Some((robot, association)) => html! {
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("robot.uptime") }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item">
+                            <span class="tag" title=t!("robot.uptime.robot.description")>
+                                { t!("robot.uptime.robot", duration = crate::format_duration(robot)) }
+                            </span>
+                        </p>
+                        <p class="level-item">
+                            <span class="tag" title=t!("robot.uptime.association.description")>
+                                { t!("robot.uptime.association", duration = crate::format_duration(association)) }
+                            </span>
+                        </p>
+                    </div>
+                </nav>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_unexpected_reboot(&self, builderbot: &Instance) -> Html {
+        match builderbot.unexpected_reboot.as_ref() {
+            Some(reboot) => html! {
+                <div class="notification is-danger">{ reboot }</div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_watchdog(&self, builderbot: &Instance) -> Html {
+        if builderbot.watchdog.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="notification is-warning">
+                <p>{ t!("robot.watchdog") }</p>
+                <ul> {
+                    builderbot.watchdog.iter().map(|event| html! {
+                        <li>{ event }</li>
+                    }).collect::<Html>()
+                } </ul>
+            </div>
+        }
+    }
+
+    fn render_queued_actions(&self, builderbot: &Instance) -> Html {
+        if builderbot.queued_actions.is_empty() {
+            return html! {};
+        }
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.queued") }</p>
+                </div>
+                <div class="level-right"> {
+                    builderbot.queued_actions.iter().map(|(id, action)| {
+                        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                        let request = BackEndRequest::BuilderBotRequest(builderbot.descriptor.id.clone(), Request::CancelQueuedAction(*id));
+                        let cancel_onclick =
+                            self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
+                        html! {
+                            <p class="level-item">
+                                <span class="tag is-warning">
+                                    { action }
+                                    <button class="delete is-small" title=t!("robot.queued.cancel") onclick=cancel_onclick />
+                                </span>
+                            </p>
+                        }
+                    }).collect::<Html>()
+                } </div>
+            </nav>
+        }
+    }
+
+    fn render_resource_limit_violation(&self, builderbot: &Instance) -> Html {
+        match builderbot.resource_limit_violation.as_ref() {
+            Some(violation) => html! {
+                <div class="notification is-warning">{ violation }</div>
+            },
+            None => html! {},
+        }
+    }
+
     fn render_camera_modal(&self, builderbot: &Instance) -> Html {
         if self.camera_dialog_active {
             let disable_onclick = self.link.callback(|_| Msg::ToggleCameraStream);
@@ -268,7 +753,7 @@ impl Card {
                     <div class="modal-background" onclick=clear_error_onclick />
                     <div class="modal-card">
                     <header class="modal-card-head">
-                      <p class="modal-card-title"> { "Error processing request" } </p>
+                      <p class="modal-card-title"> { t!("robot.error") } </p>
                     </header>
                     <section class="modal-card-body">
                       { error }
@@ -286,7 +771,7 @@ impl Card {
 
     fn render_duovero(&self, builderbot: &Instance) -> Html {
         let (wifi_signal_level, wifi_signal_info) = match &builderbot.duovero {
-            DuoVero::Disconnected => (0, String::from("Disconnected")),
+            DuoVero::Disconnected => (0, t!("common.disconnected")),
             DuoVero::Connected { signal, .. } => match signal {
                 Err(message) => (0, message.clone()),
                 Ok(level) => (match level + 90 {
@@ -297,62 +782,25 @@ impl Card {
                 }, format!("{}%", level + 90))
             }
         };
-        let (term_disabled, term_content) = match &builderbot.duovero {
-            DuoVero::Disconnected => (true, String::new()),
-            DuoVero::Connected { terminal, ..} => (false, terminal.clone())
+        let (term_disabled, term_input) = match &builderbot.duovero {
+            DuoVero::Disconnected => (true, None),
+            DuoVero::Connected { terminal_input, ..} => (false, *terminal_input)
         };
-        let mut term_classes = classes!("column", "is-full");
-        if !self.bash_terminal_visible {
-            term_classes.push("is-hidden");
-        }
-        let term_btn_onclick = self.link.callback(|_| Msg::ToggleBashTerminal);
-        let term_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
-            "Enter" => Some(Msg::SendBashCommand),
-            _ => None,
-        });
+        let term_disabled = term_disabled || !access::permitted(Category::Terminals);
+        let term_ontoggle = self.link.callback(|_| Msg::ToggleBashTerminal);
+        let term_oncommand = self.link.callback(Msg::SendBashCommand);
         html! {
             <>
-                <nav class="level is-mobile">
-                    <div class="level-left">
-                        <p class="level-item">{ "DuoVero" }</p>
-                    </div>
-                    <div class="level-right">
-                        <button class="level-item button" onclick=term_btn_onclick disabled=term_disabled> {
-                            if self.bash_terminal_visible {
-                                "Close Bash terminal"
-                            }
-                            else {
-                                "Open Bash terminal"
-                            }
-                        } </button>
-                    </div>
-                </nav>
-                
+                <Terminal title="DuoVero"
+                          shell=Shell::Bash
+                          visible=self.bash_terminal_visible
+                          ontoggle=term_ontoggle
+                          content=builderbot.bash.output().to_owned()
+                          history=builderbot.bash.history()
+                          disabled=term_disabled
+                          progress=term_input
+                          oncommand=term_oncommand />
                 <div class="columns is-multiline is-mobile">
-                    <div class=term_classes>
-                        <div>
-                            <div class="field">
-                                <div class="control">
-                                    <textarea ref=self.bash_textarea.clone()
-                                              class="textarea is-family-monospace"
-                                              readonly=false>
-                                              { term_content }
-                                    </textarea>
-                                </div>
-                            </div>
-                            <div class="field">
-                                <div class="control">
-                                    <input ref=self.bash_input.clone()
-                                           class="input is-family-monospace"
-                                           type="text" 
-                                           disabled=term_disabled
-                                           placeholder="Type a command and press enter"
-                                           onkeydown=term_onkeydown />
-                                </div>
-                            </div>
-                        </div>
-                    </div> 
-                    
                     <div class="column is-two-fifths">
                         <div class="notification has-text-centered">
                             <p style="line-height:32px"> {
@@ -365,7 +813,10 @@ impl Card {
                             <p style="line-height:32px"> {
                                 match builderbot.duovero {
                                     DuoVero::Connected { addr, .. } => addr.to_string(),
-                                    DuoVero::Disconnected => "Disconnected".to_owned()
+                                    DuoVero::Disconnected => match builderbot.reconnecting {
+                                        Some(addr) => t!("robot.reconnecting", addr = addr),
+                                        None => t!("common.disconnected"),
+                                    }
                                 }
                             } </p>
                         </div>
@@ -387,8 +838,22 @@ impl Card {
             <>
                 <nav class="level is-mobile">
                     <div class="level-left">
-                        <p class="level-item">{ "Optitrack" }</p>
-                    </div>    
+                        <p class="level-item">{ t!("robot.optitrack") }</p>
+                    </div>
+                    <div class="level-right">
+                        {
+                            builderbot.optitrack_name.as_ref().map_or_else(|| html! {}, |name| html! {
+                                <p class="level-item"><span class="tag is-info">{ name }</span></p>
+                            })
+                        }
+                        {
+                            builderbot.optitrack_suggestion.map_or_else(|| html! {}, |id| html! {
+                                <p class="level-item">
+                                    <span class="tag is-warning">{ t!("robot.optitrack.suggestion", id = id) }</span>
+                                </p>
+                            })
+                        }
+                    </div>
                 </nav>
                 <div class="columns is-multiline is-mobile">
                     <div class="column is-one-fifth">
@@ -400,7 +865,7 @@ impl Card {
                         </div>
                     </div>
                     <div class="column is-four-fifths">
-                        <div class="notification">
+                        <div class="notification" title=t!("robot.optitrack.speed", speed = format!("{:.3}", builderbot.optitrack_speed))>
                             <nav class="level is-mobile"> {
                                 builderbot.optitrack_pos.iter().map(|coord| html! {
                                     <p style="line-height:32px" class="level-item">{ format!("{:.3}", coord) }</p>
@@ -434,25 +899,27 @@ impl Card {
         let identify_onclick =
             self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
 
+        /* the menus for halting, rebooting, and powering the robot are hidden from roles without these permissions */
+        let power_hidden = (!access::permitted(Category::Power)).then(|| "is-hidden");
         html! {
             <footer class="card-footer">
                 {
                     match builderbot.duovero {
                         DuoVero::Connected {..} => html! {
                             <>
-                                <a class="card-footer-item" onclick=toggle_camera_stream_onclick>{ "Show cameras" }</a>
-                                <a class="card-footer-item" onclick=identify_onclick>{ "Identify" }</a>
+                                { crate::camera::render_toggle(builderbot.cameras.as_ref(), toggle_camera_stream_onclick) }
+                                <a class="card-footer-item" onclick=identify_onclick>{ t!("robot.menu.identify") }</a>
                             </>
                         },
                         DuoVero::Disconnected => html! {
                             <>
-                                <p class="card-footer-item has-text-grey-light">{ "Show cameras" }</p>
-                                <p class="card-footer-item has-text-grey-light">{ "Identify" }</p>
+                                <p class="card-footer-item has-text-grey-light">{ t!("robot.menu.cameras") }</p>
+                                <a class="card-footer-item" onclick=identify_onclick>{ t!("robot.menu.identify.queue") }</a>
                             </>
                         },
                     }
                 }
-                <div class="card-footer-item dropdown is-hoverable">
+                <div class=classes!("card-footer-item", "dropdown", "is-hoverable", power_hidden)>
                     <div class="dropdown-trigger">
                         <a>
                             <span>{ "DuoVero" }</span>
@@ -465,19 +932,19 @@ impl Card {
                         <div class="dropdown-content"> {
                             match builderbot.duovero {
                                 DuoVero::Connected {..} => html! {
-                                    <a class="dropdown-item" onclick=halt_duovero_onclick>{ "Halt" }</a>
+                                    <a class="dropdown-item" onclick=halt_duovero_onclick>{ t!("robot.menu.halt") }</a>
                                 },
                                 DuoVero::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Halt" }</p>
+                                    <a class="dropdown-item" onclick=halt_duovero_onclick>{ t!("robot.menu.halt.queue") }</a>
                                 },
                             }
                         } {
                             match builderbot.duovero {
                                 DuoVero::Connected {..} => html! {
-                                    <a class="dropdown-item" onclick=reboot_duovero_onclick>{ "Reboot" }</a>
+                                    <a class="dropdown-item" onclick=reboot_duovero_onclick>{ t!("robot.menu.reboot") }</a>
                                 },
                                 DuoVero::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Reboot" }</p>
+                                    <a class="dropdown-item" onclick=reboot_duovero_onclick>{ t!("robot.menu.reboot.queue") }</a>
                                 },
                             }
                         } </div>
@@ -0,0 +1,38 @@
+use shared::camera::{Availability, INSTALL_COMMAND};
+use yew::prelude::*;
+
+/// The reason why the cameras of a robot can not be shown, if any
+fn unsupported(availability: &Availability) -> Option<String> {
+    if !availability.streamer {
+        Some(t!("robot.cameras.no_streamer", command = INSTALL_COMMAND))
+    }
+    else if availability.cameras.is_empty() {
+        Some(t!("robot.cameras.none"))
+    }
+    else if !availability.supported() {
+        Some(t!("robot.cameras.missing", cameras = availability.missing().collect::<Vec<_>>().join(", ")))
+    }
+    else {
+        None
+    }
+}
+
+/// Renders the menu item for showing the cameras of a connected robot. The item is disabled with the reason
+/// as its tooltip if the cameras of the robot are not supported, while the cameras that are missing are
+/// listed in the tooltip otherwise. The item is enabled until the availability of the cameras is known
+pub fn render_toggle(availability: Option<&Availability>, onclick: Callback<MouseEvent>) -> Html {
+    match availability.and_then(unsupported) {
+        Some(reason) => html! {
+            <p class="card-footer-item has-text-grey-light" title=reason>{ t!("robot.menu.cameras") }</p>
+        },
+        None => {
+            let missing = availability
+                .map(|availability| availability.missing().collect::<Vec<_>>())
+                .filter(|missing| !missing.is_empty())
+                .map(|missing| t!("robot.cameras.partial", cameras = missing.join(", ")));
+            html! {
+                <a class="card-footer-item" title=missing.unwrap_or_default() onclick=onclick>{ t!("robot.menu.cameras") }</a>
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use yew::prelude::*;
+
+use yew::{html, Component, ComponentLink, Html, ShouldRender};
+
+use shared::tracking_system::Timestamps;
+
+/* the number of frames from the tracking system over which the percentiles are calculated */
+const SAMPLES: usize = 1000;
+
+/* the keys of the names and descriptions of the stages of the tracking pipeline, the network stage
+   includes the offset between the clock of the browser and the clock of the supervisor if they do not
+   run on the same machine */
+const STAGES: &[(&str, &str)] = &[
+    ("diagnostics.stage.optitrack", "diagnostics.stage.optitrack.description"),
+    ("diagnostics.stage.webui", "diagnostics.stage.webui.description"),
+    ("diagnostics.stage.network", "diagnostics.stage.network.description"),
+    ("diagnostics.stage.render", "diagnostics.stage.render.description"),
+    ("diagnostics.stage.total", "diagnostics.stage.total.description"),
+];
+
+#[derive(Default)]
+pub struct Latency {
+    /* the duration of each stage in milliseconds for the most recent frames */
+    samples: VecDeque<[f64; 5]>,
+    /* the timestamps of the last frame that was received, but not yet rendered */
+    pending: Option<(Timestamps, f64)>,
+}
+
+impl Latency {
+    /// Called when a frame from the tracking system is received from the back-end
+    pub fn receive(&mut self, timestamps: Timestamps) {
+        self.pending = Some((timestamps, js_sys::Date::now()));
+    }
+
+    /// Called after the user interface has been rendered, completes the measurement of the last frame
+    pub fn render(&mut self) {
+        if let Some((timestamps, received)) = self.pending.take() {
+            let rendered = js_sys::Date::now();
+            if self.samples.len() == SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples.push_back([
+                timestamps.broadcast - timestamps.received,
+                timestamps.sent - timestamps.broadcast,
+                received - timestamps.sent,
+                rendered - received,
+                rendered - timestamps.received,
+            ]);
+        }
+    }
+
+    /// Returns the 50th, 95th, and 99th percentiles and the maximum of a stage
+    fn percentiles(&self, stage: usize) -> Option<[f64; 4]> {
+        let mut durations = self.samples.iter()
+            .map(|sample| sample[stage])
+            .collect::<Vec<_>>();
+        durations.sort_by(|lhs, rhs| lhs.partial_cmp(rhs).unwrap_or(std::cmp::Ordering::Equal));
+        let percentile = |p: f64| {
+            let index = ((durations.len() - 1) as f64 * p).round() as usize;
+            durations[index]
+        };
+        match durations.len() {
+            0 => None,
+            _ => Some([percentile(0.50), percentile(0.95), percentile(0.99), durations[durations.len() - 1]]),
+        }
+    }
+}
+
+pub struct Card {
+    props: Props,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub latency: Rc<RefCell<Latency>>,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Props, _link: ComponentLink<Self>) -> Self {
+        Card { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let latency = self.props.latency.borrow();
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("diagnostics.title") }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item">{ t!("diagnostics.frames", count = latency.samples.len()) }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <table class="table is-fullwidth is-hoverable">
+                            <thead>
+                                <tr>
+                                    <th>{ t!("diagnostics.stage") }</th>
+                                    <th>{ "p50" }</th>
+                                    <th>{ "p95" }</th>
+                                    <th>{ "p99" }</th>
+                                    <th>{ t!("diagnostics.max") }</th>
+                                </tr>
+                            </thead>
+                            <tbody> {
+                                STAGES.iter().enumerate().map(|(stage, (name, description))| html! {
+                                    <tr title=t!(description)>
+                                        <td>{ t!(name) }</td>
+                                        {
+                                            match latency.percentiles(stage) {
+                                                Some(percentiles) => percentiles.iter()
+                                                    .map(|duration| html! {
+                                                        <td class="is-family-monospace">{ format!("{:.1} ms", duration) }</td>
+                                                    }).collect::<Html>(),
+                                                None => html! {
+                                                    <td colspan="4">{ t!("diagnostics.none") }</td>
+                                                }
+                                            }
+                                        }
+                                    </tr>
+                                }).collect::<Html>()
+                            } </tbody>
+                        </table>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
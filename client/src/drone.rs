@@ -1,23 +1,34 @@
 use std::{cell::RefCell, collections::HashMap, net::Ipv4Addr, rc::Rc};
-use shared::{BackEndRequest, drone::{Descriptor, Request, Update}};
+use shared::{BackEndRequest, access::Category, experiment::{Excerpt, Summary}, infrastructure::{Port, Station}, drone::{CELL_IMBALANCE_THRESHOLD_MV, ControlPath, Descriptor, FlightMode, FlightState, MavlinkCommand, MavlinkTemplate, Request, Update, XbeeAudit, cell_imbalance}};
 use web_sys::HtmlInputElement;
-use yew::{prelude::*, web_sys::HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::{access, terminal::{Buffer, Shell, Terminal}};
 
 enum Xbee {
     Connected {
         addr: Ipv4Addr,
         signal: Result<i32, String>,
         battery: Result<i32, String>,
-        terminal: String,
+        /* the voltages of the individual cells of the battery in millivolts */
+        battery_cells: Vec<u16>,
+        /* the state of the Pixhawk as reported by its heartbeats */
+        flight_state: Option<FlightState>,
+        /* the acknowledgements of the commands sent from the MAVLink console */
+        responses: Vec<String>,
     },
     Disconnected,
 }
 
+/* the number of acknowledgements that are shown in the MAVLink console */
+const MAVLINK_CONSOLE_RESPONSES: usize = 10;
+
 enum UpCore {
     Connected {
         addr: Ipv4Addr,
         signal: Result<i32, String>,
-        terminal: String,
+        /* the amount of queued terminal input that has been sent to the robot */
+        terminal_input: Option<(usize, usize)>,
     },
     Disconnected,
 }
@@ -25,10 +36,52 @@ enum UpCore {
 pub struct Instance {
     pub descriptor: Descriptor,
     pub optitrack_pos: [f32; 3],
+    /* the speed estimated by the tracking system in meters per second */
+    pub optitrack_speed: f32,
+    /* the name of the rigid body in Motive and a suggested identifier based on that name */
+    pub optitrack_name: Option<String>,
+    pub optitrack_suggestion: Option<i32>,
+    /* set via the command palette to open the terminal once the card is shown */
+    pub terminal_requested: bool,
+    /* the association and switch port of the robot as reported by the infrastructure (via SNMP) */
+    pub access_point: Option<Station>,
+    pub switch_port: Option<Port>,
+    resource_limit_violation: Option<String>,
+    /* the uptime of the robot and the duration of its association with the supervisor in seconds */
+    uptime: Option<(u64, u64)>,
+    firmware: Option<String>,
+    /* whether the camera streams are supported, as checked when the robot was associated */
+    cameras: Option<shared::camera::Availability>,
+    unexpected_reboot: Option<String>,
+    /* the interventions of the watchdog on the robot since the previous association */
+    watchdog: Vec<String>,
+    /* the actions that will be executed once the robot is associated again */
+    queued_actions: Vec<(u64, String)>,
+    /* the files collected from the robot after the last experiment */
+    artifacts: Option<Result<Vec<String>, String>>,
+    /* the parameters of the Xbee as read during the last audit */
+    pub xbee_audit: Option<XbeeAudit>,
+    /* a summary of the last experiment, this is persisted by the supervisor across sessions */
+    pub last_experiment: Option<Summary>,
+    /* the last lines that ARGoS wrote during the last experiment, fetched from the journal on demand */
+    pub excerpt: Option<Excerpt>,
+    /* the output of ARGoS during the current experiment */
+    pub argos: crate::argos::Output,
+    /* the events of the robot as recorded by the supervisor */
+    events: crate::event_log::EventLog,
+    /* the recent readings of the robot, None until they have been requested from the supervisor */
+    pub telemetry: Option<shared::telemetry::History>,
+    /* the output of the Bash and MAVLink terminals and the commands entered into them, kept across reconnections */
+    bash: Buffer,
+    mavlink: Buffer,
     upcore: UpCore,
+    /* the last known address while the supervisor waits for the robot to reconnect */
+    reconnecting: Option<Ipv4Addr>,
     upcore_power: bool,
     xbee: Xbee,
     pixhawk_power: bool,
+    /* the path over which safety-critical commands are sent to the Pixhawk */
+    control_path: ControlPath,
     camera_stream: HashMap<String, Result<String, String>>,
 }
 
@@ -39,32 +92,134 @@ impl Instance {
         Self { 
             descriptor, 
             optitrack_pos: [0.0, 0.0, 0.0],
+            optitrack_speed: 0.0,
+            optitrack_name: None,
+            optitrack_suggestion: None,
+            terminal_requested: false,
+            access_point: None,
+            switch_port: None,
+            resource_limit_violation: None,
+            uptime: None,
+            firmware: None,
+            cameras: None,
+            unexpected_reboot: None,
+            watchdog: Vec::new(),
+            queued_actions: Vec::new(),
+            artifacts: None,
+            xbee_audit: None,
+            last_experiment: None,
+            excerpt: None,
+            argos: Default::default(),
+            events: Default::default(),
+            telemetry: None,
+            bash: Default::default(),
+            mavlink: Default::default(),
             upcore: UpCore::Disconnected,
+            reconnecting: None,
             upcore_power: false,
             xbee: Xbee::Disconnected,
             pixhawk_power: false,
+            control_path: ControlPath::Unavailable,
             camera_stream: Default::default(),
         }
     }
 
+    /// The address of the UpCore, from which ARGoS connects to the message router
+    pub fn addr(&self) -> Option<Ipv4Addr> {
+        match self.upcore {
+            UpCore::Connected { addr, .. } => Some(addr),
+            UpCore::Disconnected => None,
+        }
+    }
+
+    /// The request for the recent readings of the robot, which is only returned once so that the readings are
+    /// not requested again whenever the card is rendered
+    pub fn telemetry_request(&mut self) -> Option<BackEndRequest> {
+        match self.telemetry {
+            Some(_) => None,
+            None => {
+                self.telemetry = Some(Default::default());
+                Some(BackEndRequest::TelemetryRequest(self.descriptor.id.clone()))
+            }
+        }
+    }
+
+    /// Replaces the readings of the robot with those kept by the supervisor. The output of the terminals is
+    /// only restored if nothing has been received since the card was shown
+    pub fn restore(&mut self, history: shared::telemetry::History) {
+        if self.bash.is_empty() {
+            self.bash.push(&history.bash);
+        }
+        if self.mavlink.is_empty() {
+            self.mavlink.push(&history.mavlink);
+        }
+        self.telemetry = Some(history);
+    }
+
+    /// The state of the robot that is aggregated into the statistics of the swarm, the battery is read via
+    /// the Xbee and an imbalanced battery counts as an error
+    pub fn status(&self) -> crate::swarm::Status {
+        let (battery, imbalanced) = match &self.xbee {
+            Xbee::Connected { battery, battery_cells, .. } =>
+                (battery.as_ref().ok().copied(), cell_imbalance(battery_cells) > CELL_IMBALANCE_THRESHOLD_MV),
+            Xbee::Disconnected => (None, false),
+        };
+        let (connected, signal) = match &self.upcore {
+            UpCore::Connected { signal, .. } => (true, signal.as_ref().ok().copied()),
+            UpCore::Disconnected => (false, None),
+        };
+        crate::swarm::Status {
+            connected,
+            battery,
+            signal,
+            error: imbalanced || self.resource_limit_violation.is_some() || self.unexpected_reboot.is_some() ||
+                !self.watchdog.is_empty(),
+        }
+    }
+
     pub fn update(&mut self, update: Update) {
+        if let Some(telemetry) = self.telemetry.as_mut() {
+            match &update {
+                Update::Battery(level) => crate::telemetry::record(&mut telemetry.battery, *level),
+                Update::FernbedienungSignal(strength) => crate::telemetry::record(&mut telemetry.signal, *strength),
+                Update::XbeeSignal(strength) => crate::telemetry::record(&mut telemetry.xbee_signal, *strength),
+                _ => {}
+            }
+        }
         match update {
             Update::Battery(reading) => if let Xbee::Connected { battery, ..} = &mut self.xbee {
                 *battery = Ok(reading);
             },
+            Update::BatteryCells(cells) => if let Xbee::Connected { battery_cells, ..} = &mut self.xbee {
+                *battery_cells = cells;
+            },
             Update::Camera { camera, result } => {
                 self.camera_stream
                     .insert(camera, result
                         .map(|bytes| base64::encode(bytes)));
             },
-            Update::FernbedienungConnected(addr) => 
+            Update::FernbedienungConnected(addr) => {
+                self.reconnecting = None;
                 self.upcore = UpCore::Connected {
                     addr,
-                    signal: Err(String::from("Unknown")),
-                    terminal: Default::default(),
-                },
-            Update::FernbedienungDisconnected => 
-                self.upcore = UpCore::Disconnected,
+                    signal: Err(t!("common.unknown")),
+                    terminal_input: None,
+                };
+            },
+            Update::FernbedienungDisconnected => {
+                self.upcore = UpCore::Disconnected;
+                self.reconnecting = None;
+                self.uptime = None;
+                self.firmware = None;
+                self.cameras = None;
+            },
+            Update::FernbedienungReconnecting(addr) => {
+                self.upcore = UpCore::Disconnected;
+                self.reconnecting = Some(addr);
+                self.uptime = None;
+                self.firmware = None;
+                self.cameras = None;
+            },
             Update::FernbedienungSignal(strength) => 
                 if let UpCore::Connected { signal, ..} = &mut self.upcore {
                     *signal = Ok(strength);
@@ -72,25 +227,66 @@ impl Instance {
             Update::XbeeConnected(addr) => 
                 self.xbee = Xbee::Connected {
                     addr,
-                    battery: Err(String::from("Unknown")),
-                    signal: Err(String::from("Unknown")),
-                    terminal: Default::default(),
+                    battery: Err(t!("common.unknown")),
+                    signal: Err(t!("common.unknown")),
+                    battery_cells: Vec::new(),
+                    flight_state: None,
+                    responses: Vec::new(),
                 },
             Update::XbeeDisconnected => 
                 self.xbee = Xbee::Disconnected,
             Update::XbeeSignal(strength) => if let Xbee::Connected { signal, ..} = &mut self.xbee {
                     *signal = Ok(strength);
             },
-            Update::Bash(response) => if let UpCore::Connected { terminal, ..} = &mut self.upcore {
-                terminal.push_str(&response);
+            Update::ControlPath(path) =>
+                self.control_path = path,
+            Update::ResourceLimitViolation(violation) =>
+                self.resource_limit_violation = Some(violation),
+            Update::Uptime { robot, association } =>
+                self.uptime = Some((robot, association)),
+            Update::Firmware(firmware) =>
+                self.firmware = Some(firmware),
+            Update::Cameras(availability) =>
+                self.cameras = Some(availability),
+            Update::XbeeAudit(audit) =>
+                self.xbee_audit = Some(audit),
+            Update::UnexpectedReboot(reboot) =>
+                self.unexpected_reboot = Some(reboot),
+            Update::Watchdog(events) =>
+                self.watchdog = events,
+            Update::QueuedActions(actions) =>
+                self.queued_actions = actions,
+            Update::Artifacts(result) =>
+                self.artifacts = Some(result),
+            Update::ExperimentSummary(summary) => {
+                self.last_experiment = Some(summary);
+                self.excerpt = None;
+                self.argos.finish();
+            },
+            Update::Bash(response) => self.bash.push(&response),
+            Update::ArgosOutput { error, text } => self.argos.push(error, text),
+            Update::Event(entry) => self.events.push(entry),
+            Update::EventLog(entries) => self.events.replace(entries),
+            Update::BashProgress { sent, total } => if let UpCore::Connected { terminal_input, ..} = &mut self.upcore {
+                *terminal_input = match sent < total {
+                    true => Some((sent, total)),
+                    false => None,
+                };
             },
-            Update::Mavlink(response) => if let Xbee::Connected { terminal, ..} = &mut self.xbee {
-                terminal.push_str(&response);
+            Update::Mavlink(response) => self.mavlink.push(&response),
+            Update::MavlinkResponse(response) => if let Xbee::Connected { responses, ..} = &mut self.xbee {
+                if responses.len() == MAVLINK_CONSOLE_RESPONSES {
+                    responses.remove(0);
+                }
+                responses.push(response);
             },
             Update::PowerState { upcore, pixhawk } => {
                 self.pixhawk_power = pixhawk;
                 self.upcore_power = upcore;
             },
+            Update::FlightState(state) => if let Xbee::Connected { flight_state, ..} = &mut self.xbee {
+                *flight_state = Some(state);
+            },
         }
     }
 }
@@ -98,17 +294,18 @@ impl Instance {
 pub struct Card {
     link: ComponentLink<Self>,
     props: Props,
-    // where multiple noderefs etc are present, perhaps
-    // this indicates when a component is necessary?
     bash_terminal_visible: bool,
-    bash_textarea: NodeRef,
-    bash_input: NodeRef,
-    // mavlink vs. bash also indicates that a component
-    // would be useful
     mavlink_terminal_visible: bool,
-    mavlink_textarea: NodeRef,
-    mavlink_input: NodeRef,
+    /* the template and the parameters of the command in the MAVLink console */
+    mavlink_console_visible: bool,
+    mavlink_template: MavlinkTemplate,
+    mavlink_params: Vec<String>,
+    mavlink_validation: Option<String>,
     camera_dialog_active: bool,
+    argos_visible: bool,
+    argos_output: NodeRef,
+    events_visible: bool,
+    results_input: NodeRef,
     error: Result<(), String>,
 }
 
@@ -124,8 +321,16 @@ pub enum Msg {
     ToggleBashTerminal,
     ToggleMavlinkTerminal,
     ToggleCameraStream,
-    SendBashCommand,
-    SendMavlinkCommand,
+    FetchResults,
+    ToggleExcerpt,
+    ToggleArgosOutput,
+    ToggleEvents,
+    SendBashCommand(String),
+    SendMavlinkCommand(String),
+    ToggleMavlinkConsole,
+    SetMavlinkTemplate(usize),
+    SetMavlinkParam(usize, String),
+    SendMavlinkConsoleCommand,
 }
 
 // is it possible to just add a callback to the update method
@@ -134,29 +339,35 @@ impl Component for Card {
     type Properties = Props;
 
     fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        let request = props.instance.borrow_mut().telemetry_request();
+        if let Some(request) = request {
+            props.parent.send_message(crate::Msg::SendRequest(request, None));
+        }
         // if props contains a closure, I could use that to communicate with the actual instance
+        if std::mem::take(&mut props.instance.borrow_mut().terminal_requested) {
+            link.send_message(Msg::ToggleBashTerminal);
+        }
         Card { 
             props,
             link,
             bash_terminal_visible: false,
-            bash_textarea: NodeRef::default(),
-            bash_input: NodeRef::default(),
             mavlink_terminal_visible: false,
-            mavlink_textarea: NodeRef::default(),
-            mavlink_input: NodeRef::default(),
+            mavlink_console_visible: false,
+            mavlink_template: MavlinkTemplate::ALL[0],
+            mavlink_params: vec![String::new(); MavlinkTemplate::ALL[0].params().len()],
+            mavlink_validation: None,
             camera_dialog_active: false,
+            argos_visible: false,
+            argos_output: NodeRef::default(),
+            events_visible: false,
+            results_input: NodeRef::default(),
             error: Ok(()),
         }
     }
 
 
     fn rendered(&mut self, _: bool) {
-        if let Some(textarea) = self.bash_textarea.cast::<HtmlTextAreaElement>() {
-            textarea.set_scroll_top(textarea.scroll_height());
-        }
-        if let Some(textarea) = self.mavlink_textarea.cast::<HtmlTextAreaElement>() {
-            textarea.set_scroll_top(textarea.scroll_height());
-        }
+        crate::argos::follow(&self.argos_output);
     }
 
 
@@ -168,34 +379,25 @@ impl Component for Card {
                 self.error = error;
                 true
             }
-            Msg::SendMavlinkCommand => match self.mavlink_input.cast::<HtmlInputElement>() {
-                Some(input) => {
-                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
-                    let drone_request = Request::MavlinkTerminalRun(input.value());
-                    input.set_value("");
-                    let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
-                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
-                    true
-                },
-                _ => false
+            Msg::SendMavlinkCommand(command) => {
+                drone.mavlink.record(&command);
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let drone_request = Request::MavlinkTerminalRun(command);
+                let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                true
             },
-            Msg::SendBashCommand => match self.bash_input.cast::<HtmlInputElement>() {
-                Some(input) => {
-                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
-                    let drone_request = Request::BashTerminalRun(input.value());
-                    input.set_value("");
-                    let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
-                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
-                    true
-                },
-                _ => false
+            Msg::SendBashCommand(command) => {
+                drone.bash.record(&command);
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let drone_request = Request::BashTerminalRun(command);
+                let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                true
             },
             Msg::ToggleBashTerminal => {
                 match self.bash_terminal_visible {
                     false => {
-                        if let UpCore::Connected { terminal, .. } = &mut drone.upcore {
-                            terminal.clear();
-                        }
                         let callback = Some(self.link.callback(|result| Msg::SetError(result)));
                         let drone_request = Request::BashTerminalStart;
                         let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
@@ -215,9 +417,6 @@ impl Component for Card {
             Msg::ToggleMavlinkTerminal => {
                 match self.mavlink_terminal_visible {
                     false => {
-                        if let Xbee::Connected { terminal, .. } = &mut drone.xbee {
-                            terminal.clear();
-                        }
                         let callback = Some(self.link.callback(|result| Msg::SetError(result)));
                         let drone_request = Request::MavlinkTerminalStart;
                         let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
@@ -234,6 +433,75 @@ impl Component for Card {
                 }
                 true
             },
+            Msg::ToggleMavlinkConsole => {
+                self.mavlink_console_visible = !self.mavlink_console_visible;
+                true
+            },
+            Msg::SetMavlinkTemplate(index) => match MavlinkTemplate::ALL.get(index) {
+                Some(&template) => {
+                    self.mavlink_template = template;
+                    self.mavlink_params = vec![String::new(); template.params().len()];
+                    self.mavlink_validation = None;
+                    true
+                },
+                None => false,
+            },
+            Msg::SetMavlinkParam(index, value) => {
+                if let Some(param) = self.mavlink_params.get_mut(index) {
+                    *param = value;
+                }
+                false
+            },
+            Msg::SendMavlinkConsoleCommand => {
+                let params = self.mavlink_params.iter()
+                    .map(|param| param.trim().parse::<f32>()
+                        .map_err(|_| t!("robot.mavlink_console.invalid", value = param)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .and_then(|params| self.mavlink_template.validate(&params).map(|_| params));
+                match params {
+                    Ok(params) => {
+                        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                        let command = MavlinkCommand { template: self.mavlink_template, params };
+                        let drone_request = Request::MavlinkCommand(command);
+                        let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                        self.mavlink_validation = None;
+                    },
+                    Err(error) => self.mavlink_validation = Some(error),
+                }
+                true
+            },
+            Msg::ToggleArgosOutput => {
+                self.argos_visible = !self.argos_visible;
+                true
+            },
+            Msg::ToggleEvents => {
+                self.events_visible = !self.events_visible;
+                true
+            },
+            Msg::ToggleExcerpt => match drone.excerpt.take() {
+                Some(_) => true,
+                None => {
+                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                    let request = BackEndRequest::ExcerptRequest(drone.descriptor.id.clone());
+                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                    false
+                }
+            },
+            Msg::FetchResults => match self.results_input.cast::<HtmlInputElement>() {
+                Some(input) => {
+                    let paths = input.value().split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+                    if !paths.is_empty() {
+                        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                        let drone_request = Request::FetchResults(paths);
+                        input.set_value("");
+                        let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                    }
+                    false
+                },
+                _ => false
+            },
             Msg::ToggleCameraStream => {
                 match self.camera_dialog_active {
                     false => {
@@ -259,13 +527,20 @@ impl Component for Card {
 
     // this fires when the parent changes the properties of this component
     fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        let request = self.props.instance.borrow_mut().telemetry_request();
+        if let Some(request) = request {
+            self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+        }
+        if std::mem::take(&mut self.props.instance.borrow_mut().terminal_requested) && !self.bash_terminal_visible {
+            self.link.send_message(Msg::ToggleBashTerminal);
+        }
         true
     }
 
     fn view(&self) -> Html {
         let drone = self.props.instance.borrow();
         let (batt_level, batt_info) = match &drone.xbee {
-            Xbee::Disconnected => (0, String::from("Unknown")),
+            Xbee::Disconnected => (0, t!("common.unknown")),
             Xbee::Connected { battery, .. } => match battery {
                 Err(message) => (0, message.clone()),
                 Ok(level) => (match level {
@@ -294,8 +569,28 @@ impl Component for Card {
                 <div class="card-content">
                     <div class="content">
                         { self.render_upcore(&drone) }
+                        { self.render_infrastructure(&drone) }
+                        { self.render_uptime(&drone) }
+                        { self.render_firmware(&drone) }
                         { self.render_xbee(&drone) }
+                        { self.render_xbee_audit(&drone) }
+                        { self.render_mavlink_console(&drone) }
+                        { self.render_battery_cells(&drone) }
+                        { crate::telemetry::render(drone.telemetry.as_ref()) }
+                        { self.render_control_path(&drone) }
+                        { self.render_flight_state(&drone) }
                         { self.render_identifiers(&drone) }
+                        { self.render_resource_limit_violation(&drone) }
+                        { self.render_unexpected_reboot(&drone) }
+                        { self.render_watchdog(&drone) }
+                        { self.render_queued_actions(&drone) }
+                        { crate::event_log::render(&drone.events, self.events_visible,
+                            self.link.callback(|_| Msg::ToggleEvents)) }
+                        { crate::argos::render(&drone.argos, self.argos_visible, self.argos_output.clone(),
+                            self.link.callback(|_| Msg::ToggleArgosOutput)) }
+                        { self.render_last_experiment(&drone) }
+                        { self.render_artifacts(&drone) }
+                        { self.render_results() }
                     </div>
                 </div>
                 { self.render_menu(&drone) }
@@ -307,6 +602,312 @@ impl Component for Card {
 }
 
 impl Card {
+    fn render_infrastructure(&self, drone: &Instance) -> Html {
+        if drone.access_point.is_none() && drone.switch_port.is_none() {
+            return html! {};
+        }
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.infrastructure") }</p>
+                </div>
+                <div class="level-right">
+                    {
+                        drone.access_point.as_ref().map_or_else(|| html! {}, |station| html! {
+                            <p class="level-item">
+                                <span class="tag is-info">{
+                                    match station.rssi {
+                                        Some(rssi) => t!("robot.infrastructure.station.rssi", access_point = station.access_point, rssi = rssi),
+                                        None => t!("robot.infrastructure.station", access_point = station.access_point),
+                                    }
+                                }</span>
+                            </p>
+                        })
+                    }
+                    {
+                        drone.switch_port.as_ref().map_or_else(|| html! {}, |port| html! {
+                            <p class="level-item">
+                                <span class=classes!("tag", if port.up { "is-success" } else { "is-danger" })>
+                                    { t!("robot.infrastructure.port", switch = port.switch, port = port.port) }
+                                </span>
+                            </p>
+                        })
+                    }
+                </div>
+            </nav>
+        }
+    }
+
+    fn render_last_experiment(&self, drone: &Instance) -> Html {
+        match drone.last_experiment.as_ref() {
+            Some(summary) => html! {
+                <>
+                    <nav class="level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item">{ t!("robot.last_experiment") }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item">
+                                <span class=classes!("tag", if summary.failed { "is-danger" } else { "is-success" })
+                                      title=t!("robot.last_experiment.finished", time = summary.finished)>
+                                    { &summary.exit }
+                                </span>
+                            </p>
+                            <p class="level-item">
+                                <span class="tag" title=t!("robot.last_experiment.output")>
+                                    { crate::experiment::format_bytes(summary.output) }
+                                </span>
+                            </p>
+                            <p class="level-item">
+                                <span class="tag" title=t!("robot.last_experiment.messages")>
+                                    { t!("robot.last_experiment.message_count", count = summary.messages) }
+                                </span>
+                            </p>
+                            {
+                                match summary.energy.as_ref() {
+                                    Some(energy) => html! {
+                                        <p class="level-item">
+                                            <span class="tag" title=t!("robot.last_experiment.energy.description")>
+                                                { t!("robot.last_experiment.energy", consumed = energy.consumed()) }
+                                            </span>
+                                        </p>
+                                    },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                    </nav>
+                    {
+                        summary.events.iter().map(|event| html! {
+                            <div class="notification is-warning">{ event }</div>
+                        }).collect::<Html>()
+                    }
+                    {
+                        match summary.backtrace.as_ref() {
+                            Some(backtrace) => html! {
+                                <div class="notification is-danger">
+                                    <p>{ t!("robot.last_experiment.backtrace") }</p>
+                                    <pre>{ backtrace }</pre>
+                                </div>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    { self.render_excerpt(drone) }
+                </>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_excerpt(&self, drone: &Instance) -> Html {
+        let onclick = self.link.callback(|_| Msg::ToggleExcerpt);
+        match drone.excerpt.as_ref() {
+            Some(excerpt) => html! {
+                <div class="notification">
+                    <button class="delete" onclick=onclick></button>
+                    <p>{
+                        match excerpt.omitted {
+                            0 => t!("robot.last_experiment.excerpt"),
+                            omitted => t!("robot.last_experiment.excerpt.omitted", count = omitted),
+                        }
+                    }</p>
+                    <pre>{
+                        excerpt.lines.iter().map(|(error, line)| html! {
+                            <span class=classes!(error.then(|| "has-text-danger"))>{ line }{ "\n" }</span>
+                        }).collect::<Html>()
+                    }</pre>
+                </div>
+            },
+            None => html! {
+                <div class="buttons">
+                    <button class="button is-small" onclick=onclick>{ t!("robot.last_experiment.excerpt.show") }</button>
+                </div>
+            },
+        }
+    }
+
+    fn render_results(&self) -> Html {
+        let fetch_onclick = self.link.callback(|_| Msg::FetchResults);
+        let fetch_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
+            "Enter" => Some(Msg::FetchResults),
+            _ => None,
+        });
+        html! {
+            <div class="field has-addons">
+                <div class="control is-expanded">
+                    <input ref=self.results_input.clone()
+                           class="input is-family-monospace"
+                           type="text"
+                           placeholder=t!("robot.results.placeholder")
+                           onkeydown=fetch_onkeydown />
+                </div>
+                <div class="control">
+                    <button class="button" onclick=fetch_onclick disabled=!access::permitted(Category::Experiment)>
+                        { t!("robot.results.fetch") }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_artifacts(&self, drone: &Instance) -> Html {
+        match drone.artifacts.as_ref() {
+            Some(Ok(artifacts)) => html! {
+                <div class="notification is-info">{
+                    match artifacts.len() {
+                        0 => t!("robot.artifacts.none"),
+                        _ => t!("robot.artifacts.collected", artifacts = artifacts.join(", ")),
+                    }
+                }</div>
+            },
+            Some(Err(error)) => html! {
+                <div class="notification is-danger">{ t!("robot.artifacts.error", error = error) }</div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_firmware(&self, drone: &Instance) -> Html {
+        match drone.firmware.as_ref() {
+            Some(firmware) => html! {
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("robot.firmware") }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item">
+                            <span class="tag">{ firmware }</span>
+                        </p>
+                    </div>
+                </nav>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_xbee_audit(&self, drone: &Instance) -> Html {
+        match drone.xbee_audit.as_ref() {
+            Some(audit) => html! {
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item" title=t!("drone.xbee_audit.time", time = audit.time.clone())>
+                            { t!("drone.xbee_audit") }
+                        </p>
+                    </div>
+                    <div class="level-right"> {
+                        audit.parameters.iter().map(|parameter| {
+                            let class = match parameter.discrepancy() {
+                                true => "is-danger",
+                                false => "is-success",
+                            };
+                            let value = match &parameter.value {
+                                Ok(value) => value.clone(),
+                                Err(_) => t!("common.unknown"),
+                            };
+                            html! {
+                                <p class="level-item">
+                                    <span class=classes!("tag", class) title=parameter.to_string()>
+                                        { format!("{} {}", parameter.name, value) }
+                                    </span>
+                                </p>
+                            }
+                        }).collect::<Html>()
+                    } </div>
+                </nav>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_uptime(&self, drone: &Instance) -> Html {
+        match drone.uptime {
+            Some((robot, association)) => html! {
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("robot.uptime") }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item">
+                            <span class="tag" title=t!("robot.uptime.robot.description")>
+                                { t!("robot.uptime.robot", duration = crate::format_duration(robot)) }
+                            </span>
+                        </p>
+                        <p class="level-item">
+                            <span class="tag" title=t!("robot.uptime.association.description")>
+                                { t!("robot.uptime.association", duration = crate::format_duration(association)) }
+                            </span>
+                        </p>
+                    </div>
+                </nav>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_unexpected_reboot(&self, drone: &Instance) -> Html {
+        match drone.unexpected_reboot.as_ref() {
+            Some(reboot) => html! {
+                <div class="notification is-danger">{ reboot }</div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_watchdog(&self, drone: &Instance) -> Html {
+        if drone.watchdog.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="notification is-warning">
+                <p>{ t!("robot.watchdog") }</p>
+                <ul> {
+                    drone.watchdog.iter().map(|event| html! {
+                        <li>{ event }</li>
+                    }).collect::<Html>()
+                } </ul>
+            </div>
+        }
+    }
+
+    fn render_queued_actions(&self, drone: &Instance) -> Html {
+        if drone.queued_actions.is_empty() {
+            return html! {};
+        }
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.queued") }</p>
+                </div>
+                <div class="level-right"> {
+                    drone.queued_actions.iter().map(|(id, action)| {
+                        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                        let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), Request::CancelQueuedAction(*id));
+                        let cancel_onclick =
+                            self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
+                        html! {
+                            <p class="level-item">
+                                <span class="tag is-warning">
+                                    { action }
+                                    <button class="delete is-small" title=t!("robot.queued.cancel") onclick=cancel_onclick />
+                                </span>
+                            </p>
+                        }
+                    }).collect::<Html>()
+                } </div>
+            </nav>
+        }
+    }
+
+    fn render_resource_limit_violation(&self, drone: &Instance) -> Html {
+        match drone.resource_limit_violation.as_ref() {
+            Some(violation) => html! {
+                <div class="notification is-warning">{ violation }</div>
+            },
+            None => html! {},
+        }
+    }
+
     fn render_camera_modal(&self, drone: &Instance) -> Html {
         if self.camera_dialog_active {
             let disable_onclick = self.link.callback(|_| Msg::ToggleCameraStream);
@@ -353,7 +954,7 @@ impl Card {
                     <div class="modal-background" onclick=clear_error_onclick />
                     <div class="modal-card">
                     <header class="modal-card-head">
-                      <p class="modal-card-title"> { "Error processing request" } </p>
+                      <p class="modal-card-title"> { t!("robot.error") } </p>
                     </header>
                     <section class="modal-card-body">
                       { error }
@@ -371,7 +972,7 @@ impl Card {
 
     fn render_upcore(&self, drone: &Instance) -> Html {
         let (wifi_signal_level, wifi_signal_info) = match &drone.upcore {
-            UpCore::Disconnected => (0, String::from("Disconnected")),
+            UpCore::Disconnected => (0, t!("common.disconnected")),
             UpCore::Connected { signal, .. } => match signal {
                 Err(message) => (0, message.clone()),
                 Ok(level) => (match level + 90 {
@@ -382,62 +983,25 @@ impl Card {
                 }, format!("{}%", level + 90))
             }
         };
-        let (term_disabled, term_content) = match &drone.upcore {
-            UpCore::Disconnected => (true, String::new()),
-            UpCore::Connected { terminal, ..} => (false, terminal.clone())
+        let (term_disabled, term_input) = match &drone.upcore {
+            UpCore::Disconnected => (true, None),
+            UpCore::Connected { terminal_input, ..} => (false, *terminal_input)
         };
-        let mut term_classes = classes!("column", "is-full");
-        if !self.bash_terminal_visible {
-            term_classes.push("is-hidden");
-        }
-        let term_btn_onclick = self.link.callback(|_| Msg::ToggleBashTerminal);
-        let term_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
-            "Enter" => Some(Msg::SendBashCommand),
-            _ => None,
-        });
+        let term_disabled = term_disabled || !access::permitted(Category::Terminals);
+        let term_ontoggle = self.link.callback(|_| Msg::ToggleBashTerminal);
+        let term_oncommand = self.link.callback(Msg::SendBashCommand);
         html! {
             <>
-                <nav class="level is-mobile">
-                    <div class="level-left">
-                        <p class="level-item">{ "Up Core" }</p>
-                    </div>
-                    <div class="level-right">
-                        <button class="level-item button" onclick=term_btn_onclick disabled=term_disabled> {
-                            if self.bash_terminal_visible {
-                                "Close Bash terminal"
-                            }
-                            else {
-                                "Open Bash terminal"
-                            }
-                        } </button>
-                    </div>
-                </nav>
-                
+                <Terminal title="Up Core"
+                          shell=Shell::Bash
+                          visible=self.bash_terminal_visible
+                          ontoggle=term_ontoggle
+                          content=drone.bash.output().to_owned()
+                          history=drone.bash.history()
+                          disabled=term_disabled
+                          progress=term_input
+                          oncommand=term_oncommand />
                 <div class="columns is-multiline is-mobile">
-                    <div class=term_classes>
-                        <div>
-                            <div class="field">
-                                <div class="control">
-                                    <textarea ref=self.bash_textarea.clone()
-                                              class="textarea is-family-monospace"
-                                              readonly=false>
-                                              { term_content }
-                                    </textarea>
-                                </div>
-                            </div>
-                            <div class="field">
-                                <div class="control">
-                                    <input ref=self.bash_input.clone()
-                                           class="input is-family-monospace"
-                                           type="text" 
-                                           disabled=term_disabled
-                                           placeholder="Type a command and press enter"
-                                           onkeydown=term_onkeydown />
-                                </div>
-                            </div>
-                        </div>
-                    </div> 
-                    
                     <div class="column is-two-fifths">
                         <div class="notification has-text-centered">
                             <p style="line-height:32px"> {
@@ -450,7 +1014,10 @@ impl Card {
                             <p style="line-height:32px"> {
                                 match drone.upcore {
                                     UpCore::Connected { addr, .. } => addr.to_string(),
-                                    UpCore::Disconnected => "Disconnected".to_owned()
+                                    UpCore::Disconnected => match drone.reconnecting {
+                                        Some(addr) => t!("robot.reconnecting", addr = addr),
+                                        None => t!("common.disconnected"),
+                                    }
                                 }
                             } </p>
                         </div>
@@ -469,7 +1036,7 @@ impl Card {
     
     fn render_xbee(&self, drone: &Instance) -> Html {
         let (wifi_signal_level, wifi_signal_info) = match &drone.xbee {
-            Xbee::Disconnected => (0, String::from("Disconnected")),
+            Xbee::Disconnected => (0, t!("common.disconnected")),
             Xbee::Connected { signal, .. } => match signal {
                 Err(message) => (0, message.clone()),
                 Ok(level) => (match level {
@@ -480,61 +1047,20 @@ impl Card {
                 }, format!("{}%", level))
             }
         };
-        let (term_disabled, term_content) = match &drone.xbee {
-            Xbee::Disconnected => (true, String::new()),
-            Xbee::Connected { terminal, ..} => (false, terminal.clone())
-        };
-        let mut term_classes = classes!("column", "is-full");
-        if !self.mavlink_terminal_visible {
-            term_classes.push("is-hidden");
-        }
-        let term_btn_onclick = self.link.callback(|_| Msg::ToggleMavlinkTerminal);
-        let term_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
-            "Enter" => Some(Msg::SendMavlinkCommand),
-            _ => None,
-        });
+        let term_disabled = matches!(drone.xbee, Xbee::Disconnected) || !access::permitted(Category::Terminals);
+        let term_ontoggle = self.link.callback(|_| Msg::ToggleMavlinkTerminal);
+        let term_oncommand = self.link.callback(Msg::SendMavlinkCommand);
         html! {
             <>
-                <nav class="level is-mobile">
-                    <div class="level-left">
-                        <p class="level-item">{ "Xbee" }</p>
-                    </div>
-                    <div class="level-right">
-                        <button class="level-item button" onclick=term_btn_onclick disabled=term_disabled> {
-                            if self.mavlink_terminal_visible {
-                                "Close Mavlink terminal"
-                            }
-                            else {
-                                "Open Mavlink terminal"
-                            }
-                        } </button>
-                    </div>
-                </nav>
-                
+                <Terminal title="Xbee"
+                          shell=Shell::Mavlink
+                          visible=self.mavlink_terminal_visible
+                          ontoggle=term_ontoggle
+                          content=drone.mavlink.output().to_owned()
+                          history=drone.mavlink.history()
+                          disabled=term_disabled
+                          oncommand=term_oncommand />
                 <div class="columns is-multiline is-mobile">
-                    <div class=term_classes>
-                        <div>
-                            <div class="field">
-                                <div class="control">
-                                    <textarea ref=self.mavlink_textarea.clone()
-                                            class="textarea is-family-monospace"
-                                            readonly=false>
-                                            { term_content }
-                                    </textarea>
-                                </div>
-                            </div>
-                            <div class="field">
-                                <div class="control">
-                                    <input ref=self.mavlink_input.clone()
-                                        class="input is-family-monospace"
-                                        type="text" 
-                                        disabled=term_disabled
-                                        placeholder="Type a command and press enter"
-                                        onkeydown=term_onkeydown />
-                                </div>
-                            </div>
-                        </div>
-                    </div>
                     <div class="column is-two-fifths">
                         <div class="notification has-text-centered">
                             <p style="line-height:32px"> {
@@ -547,7 +1073,7 @@ impl Card {
                             <p style="line-height:32px"> {
                                 match drone.xbee {
                                     Xbee::Connected { addr, .. } => addr.to_string(),
-                                    Xbee::Disconnected => "Disconnected".to_owned()
+                                    Xbee::Disconnected => t!("common.disconnected")
                                 }
                             } </p>
                         </div>
@@ -564,13 +1090,188 @@ impl Card {
         }
     }
 
+    fn render_mavlink_console(&self, drone: &Instance) -> Html {
+        let responses = match &drone.xbee {
+            Xbee::Connected { responses, .. } => responses,
+            Xbee::Disconnected => return html! {},
+        };
+        let toggle_onclick = self.link.callback(|_| Msg::ToggleMavlinkConsole);
+        let template_onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) => Msg::SetMavlinkTemplate(select.selected_index().max(0) as usize),
+            _ => Msg::SetMavlinkTemplate(0),
+        });
+        let send_onclick = self.link.callback(|_| Msg::SendMavlinkConsoleCommand);
+        html! {
+            <>
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("robot.mavlink_console") }</p>
+                    </div>
+                    <div class="level-right">
+                        <button class="level-item button" onclick=toggle_onclick
+                                disabled=!access::permitted(Category::Terminals)> {
+                            if self.mavlink_console_visible {
+                                t!("robot.mavlink_console.close")
+                            }
+                            else {
+                                t!("robot.mavlink_console.open")
+                            }
+                        } </button>
+                    </div>
+                </nav> {
+                    if self.mavlink_console_visible {
+                        html! {
+                            <div class="block">
+                                <div class="field">
+                                    <div class="control">
+                                        <div class="select is-fullwidth">
+                                            <select onchange=template_onchange> {
+                                                MavlinkTemplate::ALL.iter().map(|template| html! {
+                                                    <option selected={ *template == self.mavlink_template }>
+                                                        { template.name() }
+                                                    </option>
+                                                }).collect::<Html>()
+                                            } </select>
+                                        </div>
+                                    </div>
+                                </div> {
+                                    self.mavlink_template.params().iter().enumerate().map(|(index, name)| {
+                                        let oninput = self.link.callback(move |data: InputData| Msg::SetMavlinkParam(index, data.value));
+                                        html! {
+                                            <div class="field">
+                                                <label class="label is-small">{ name }</label>
+                                                <div class="control">
+                                                    <input class="input is-small is-family-monospace"
+                                                        type="text"
+                                                        value=self.mavlink_params.get(index).cloned().unwrap_or_default()
+                                                        oninput=oninput />
+                                                </div>
+                                            </div>
+                                        }
+                                    }).collect::<Html>()
+                                } {
+                                    match &self.mavlink_validation {
+                                        Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                        None => html! {},
+                                    }
+                                }
+                                <div class="field">
+                                    <div class="control">
+                                        <button class="button is-small" onclick=send_onclick>
+                                            { t!("robot.mavlink_console.send") }
+                                        </button>
+                                    </div>
+                                </div>
+                                <div class="field">
+                                    <label class="label is-small">{ t!("robot.mavlink_console.responses") }</label>
+                                    <div class="control">
+                                        <textarea class="textarea is-small is-family-monospace" readonly=true>
+                                            { responses.join("\n") }
+                                        </textarea>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                    }
+                    else {
+                        html! {}
+                    }
+                }
+            </>
+        }
+    }
+
+    fn render_battery_cells(&self, drone: &Instance) -> Html {
+        let cells = match &drone.xbee {
+            Xbee::Connected { battery_cells, .. } if !battery_cells.is_empty() => battery_cells,
+            _ => return html! {},
+        };
+        let imbalance = cell_imbalance(cells);
+        let class = match imbalance > CELL_IMBALANCE_THRESHOLD_MV {
+            true => "is-warning",
+            false => "is-success",
+        };
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("drone.battery_cells") }</p>
+                </div>
+                <div class="level-right" title=t!("drone.battery_cells.imbalance", imbalance = imbalance)> {
+                    cells.iter().map(|voltage| html! {
+                        <p class="level-item">
+                            <span class=classes!("tag", class)>{ format!("{:.2} V", *voltage as f32 / 1000.0) }</span>
+                        </p>
+                    }).collect::<Html>()
+                } </div>
+            </nav>
+        }
+    }
+
+    fn render_control_path(&self, drone: &Instance) -> Html {
+        let (class, text) = match drone.control_path {
+            ControlPath::Xbee => ("tag is-success", t!("drone.control_path.xbee")),
+            ControlPath::UpCore => ("tag is-warning", t!("drone.control_path.upcore")),
+            ControlPath::Unavailable => ("tag is-danger", t!("drone.control_path.unavailable")),
+        };
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("drone.control_path") }</p>
+                </div>
+                <div class="level-right">
+                    <p class="level-item"><span class=class>{ text }</span></p>
+                </div>
+            </nav>
+        }
+    }
+
+    fn render_flight_state(&self, drone: &Instance) -> Html {
+        let state = match &drone.xbee {
+            Xbee::Connected { flight_state: Some(state), .. } => state,
+            _ => return html! {},
+        };
+        let (class, text) = match state.armed {
+            true => ("tag is-danger", t!("drone.flight_state.armed")),
+            false => ("tag is-success", t!("drone.flight_state.disarmed")),
+        };
+        html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("drone.flight_state") }</p>
+                </div>
+                <div class="level-right">
+                    <p class="level-item"><span class=class>{ text }</span></p>
+                    <p class="level-item">
+                        <span class="tag is-info">{
+                            state.mode.map_or_else(|| t!("common.unknown"), |mode| mode.name().to_owned())
+                        }</span>
+                    </p>
+                </div>
+            </nav>
+        }
+    }
+
     fn render_identifiers(&self, drone: &Instance) -> Html {
         html! {
             <>
                 <nav class="level is-mobile">
                     <div class="level-left">
-                        <p class="level-item">{ "Optitrack" }</p>
-                    </div>    
+                        <p class="level-item">{ t!("robot.optitrack") }</p>
+                    </div>
+                    <div class="level-right">
+                        {
+                            drone.optitrack_name.as_ref().map_or_else(|| html! {}, |name| html! {
+                                <p class="level-item"><span class="tag is-info">{ name }</span></p>
+                            })
+                        }
+                        {
+                            drone.optitrack_suggestion.map_or_else(|| html! {}, |id| html! {
+                                <p class="level-item">
+                                    <span class="tag is-warning">{ t!("robot.optitrack.suggestion", id = id) }</span>
+                                </p>
+                            })
+                        }
+                    </div>
                 </nav>
                 <div class="columns is-multiline is-mobile">
                     <div class="column is-one-fifth">
@@ -582,7 +1283,7 @@ impl Card {
                         </div>
                     </div>
                     <div class="column is-four-fifths">
-                        <div class="notification">
+                        <div class="notification" title=t!("robot.optitrack.speed", speed = format!("{:.3}", drone.optitrack_speed))>
                             <nav class="level is-mobile"> {
                                 drone.optitrack_pos.iter().map(|coord| html! {
                                     <p style="line-height:32px" class="level-item">{ format!("{:.3}", coord) }</p>
@@ -610,6 +1311,33 @@ impl Card {
         let power_off_pixhawk_onclick =
             self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
 
+        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+        let drone_request = Request::Arm;
+        let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+        let arm_onclick =
+            self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
+
+        let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+        let drone_request = Request::Disarm;
+        let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+        let disarm_onclick =
+            self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
+
+        let flight_modes = match &drone.xbee {
+            Xbee::Connected { flight_state: Some(state), .. } => FlightMode::ALL.iter().map(|&mode| {
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let drone_request = Request::SetFlightMode(mode);
+                let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
+                let onclick =
+                    self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
+                let class = classes!("dropdown-item", (state.mode == Some(mode)).then(|| "is-active"));
+                html! {
+                    <a class=class onclick=onclick>{ t!("drone.flight_mode", mode = mode.name()) }</a>
+                }
+            }).collect::<Html>(),
+            _ => html! {},
+        };
+
         let callback = Some(self.link.callback(|result| Msg::SetError(result)));
         let drone_request = Request::UpCorePowerEnable(true);
         let request = BackEndRequest::DroneRequest(drone.descriptor.id.clone(), drone_request);
@@ -640,25 +1368,27 @@ impl Card {
         let identify_onclick =
             self.props.parent.callback(move |_| crate::Msg::SendRequest(request.clone(), callback.clone()));
 
+        /* the menus for halting, rebooting, and powering the robot are hidden from roles without these permissions */
+        let power_hidden = (!access::permitted(Category::Power)).then(|| "is-hidden");
         html! {
             <footer class="card-footer">
                 {
                     match drone.upcore {
                         UpCore::Connected {..} => html! {
                             <>
-                                <a class="card-footer-item" onclick=toggle_camera_stream_onclick>{ "Show cameras" }</a>
-                                <a class="card-footer-item" onclick=identify_onclick>{ "Identify" }</a>
+                                { crate::camera::render_toggle(drone.cameras.as_ref(), toggle_camera_stream_onclick) }
+                                <a class="card-footer-item" onclick=identify_onclick>{ t!("robot.menu.identify") }</a>
                             </>
                         },
                         UpCore::Disconnected => html! {
                             <>
-                                <p class="card-footer-item has-text-grey-light">{ "Show cameras" }</p>
-                                <p class="card-footer-item has-text-grey-light">{ "Identify" }</p>
+                                <p class="card-footer-item has-text-grey-light">{ t!("robot.menu.cameras") }</p>
+                                <a class="card-footer-item" onclick=identify_onclick>{ t!("robot.menu.identify.queue") }</a>
                             </>
                         },
                     }
                 }
-                <div class="card-footer-item dropdown is-hoverable">
+                <div class=classes!("card-footer-item", "dropdown", "is-hoverable", power_hidden)>
                     <div class="dropdown-trigger">
                         <a>
                             <span>{ "Up Core" }</span>
@@ -671,39 +1401,39 @@ impl Card {
                         <div class="dropdown-content"> {
                             match drone.upcore {
                                 UpCore::Connected {..} => html! {
-                                    <a class="dropdown-item" onclick=halt_upcore_onclick>{ "Halt" }</a>
+                                    <a class="dropdown-item" onclick=halt_upcore_onclick>{ t!("robot.menu.halt") }</a>
                                 },
                                 UpCore::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Halt" }</p>
+                                    <a class="dropdown-item" onclick=halt_upcore_onclick>{ t!("robot.menu.halt.queue") }</a>
                                 },
                             }
                         } {
                             match drone.upcore {
                                 UpCore::Connected {..} => html! {
-                                    <a class="dropdown-item" onclick=reboot_upcore_onclick>{ "Reboot" }</a>
+                                    <a class="dropdown-item" onclick=reboot_upcore_onclick>{ t!("robot.menu.reboot") }</a>
                                 },
                                 UpCore::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Reboot" }</p>
+                                    <a class="dropdown-item" onclick=reboot_upcore_onclick>{ t!("robot.menu.reboot.queue") }</a>
                                 },
                             }
                         } {
                             match drone.xbee {
                                 Xbee::Connected { .. } => match drone.upcore_power {
                                     true => html! {
-                                        <a class="dropdown-item" onclick=power_off_upcore_onclick>{ "Power Off" }</a>
+                                        <a class="dropdown-item" onclick=power_off_upcore_onclick>{ t!("robot.menu.power_off") }</a>
                                     },
                                     false => html! {
-                                        <a class="dropdown-item" onclick=power_on_upcore_onclick>{ "Power On" }</a>
+                                        <a class="dropdown-item" onclick=power_on_upcore_onclick>{ t!("robot.menu.power_on") }</a>
                                     }
                                 }
                                 Xbee::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Power On" }</p>
+                                    <p class="dropdown-item has-text-grey-light">{ t!("robot.menu.power_on") }</p>
                                 }
                             }
                         } </div>
                     </div>
                 </div>
-                <div class="card-footer-item dropdown is-hoverable">
+                <div class=classes!("card-footer-item", "dropdown", "is-hoverable", power_hidden)>
                     <div class="dropdown-trigger">
                         <a>
                             <span>{ "Pixhawk" }</span>
@@ -717,16 +1447,37 @@ impl Card {
                             match drone.xbee {
                                 Xbee::Connected { .. } => match drone.pixhawk_power {
                                     true => html! {
-                                        <a class="dropdown-item" onclick=power_off_pixhawk_onclick>{ "Power Off" }</a>
+                                        <a class="dropdown-item" onclick=power_off_pixhawk_onclick>{ t!("robot.menu.power_off") }</a>
                                     },
                                     false => html! {
-                                        <a class="dropdown-item" onclick=power_on_pixhawk_onclick>{ "Power On" }</a>
+                                        <a class="dropdown-item" onclick=power_on_pixhawk_onclick>{ t!("robot.menu.power_on") }</a>
                                     }
                                 }
                                 Xbee::Disconnected => html! {
-                                    <p class="dropdown-item has-text-grey-light">{ "Power On" }</p>
+                                    <p class="dropdown-item has-text-grey-light">{ t!("robot.menu.power_on") }</p>
                                 }
                             }
+                        } {
+                            /* the Pixhawk can only be armed once it has reported its state */
+                            match &drone.xbee {
+                                Xbee::Connected { flight_state: Some(state), .. } => html! {
+                                    <>
+                                        <hr class="dropdown-divider" /> {
+                                            match state.armed {
+                                                true => html! {
+                                                    <a class="dropdown-item" onclick=disarm_onclick>{ t!("drone.menu.disarm") }</a>
+                                                },
+                                                false => html! {
+                                                    <a class="dropdown-item" onclick=arm_onclick>{ t!("drone.menu.arm") }</a>
+                                                },
+                                            }
+                                        }
+                                        <hr class="dropdown-divider" />
+                                        { flight_modes }
+                                    </>
+                                },
+                                _ => html! {},
+                            }
                         } </div>
                     </div>
                 </div>
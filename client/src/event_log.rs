@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+use shared::event_log::{Category, Entry};
+use yew::prelude::*;
+
+/* the maximum number of events that are kept for a robot, which matches the event log of the supervisor */
+const CAPACITY: usize = 200;
+
+/// The events of a robot as recorded by the supervisor, the whole event log is received when the robot is
+/// subscribed to and the new events are appended to it
+#[derive(Default)]
+pub struct EventLog(VecDeque<Entry>);
+
+impl EventLog {
+    pub fn push(&mut self, entry: Entry) {
+        self.0.push_back(entry);
+        if self.0.len() > CAPACITY {
+            self.0.pop_front();
+        }
+    }
+
+    pub fn replace(&mut self, entries: Vec<Entry>) {
+        self.0 = entries.into();
+    }
+}
+
+/// Renders a collapsible panel with the events of a robot, where the most recent event is shown first
+pub fn render(event_log: &EventLog, visible: bool, ontoggle: Callback<MouseEvent>) -> Html {
+    let label = match visible {
+        true => t!("robot.events.hide"),
+        false => t!("robot.events.show"),
+    };
+    html! {
+        <>
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ t!("robot.events") }</p>
+                </div>
+                <div class="level-right">
+                    <button class="level-item button is-small" onclick=ontoggle>{ label }</button>
+                </div>
+            </nav>
+            {
+                match (visible, event_log.0.is_empty()) {
+                    (false, _) => html! {},
+                    (true, true) => html! { <p>{ t!("robot.events.none") }</p> },
+                    (true, false) => html! {
+                        <div style="max-height:240px;overflow-y:auto">
+                            <table class="table is-narrow is-fullwidth">
+                                <tbody> {
+                                    event_log.0.iter().rev().map(render_entry).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    },
+                }
+            }
+        </>
+    }
+}
+
+fn render_entry(entry: &Entry) -> Html {
+    let time = String::from(js_sys::Date::new(&entry.timestamp.as_str().into()).to_locale_time_string("default"));
+    let class = match entry.category {
+        Category::Connection => "is-info",
+        Category::Experiment => "is-success",
+        Category::Power => "is-warning",
+        Category::Error => "is-danger",
+    };
+    let category = match entry.category {
+        Category::Connection => t!("robot.events.connection"),
+        Category::Experiment => t!("robot.events.experiment"),
+        Category::Power => t!("robot.events.power"),
+        Category::Error => t!("robot.events.error"),
+    };
+    html! {
+        <tr>
+            <td class="is-family-monospace" title=entry.timestamp.clone()>{ time }</td>
+            <td><span class=classes!("tag", class)>{ category }</span></td>
+            <td>{ &entry.description }</td>
+        </tr>
+    }
+}
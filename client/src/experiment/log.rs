@@ -0,0 +1,207 @@
+use std::collections::{BTreeSet, VecDeque};
+use yew::prelude::*;
+
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+
+use shared::experiment::{LogEntry, LogSource};
+
+use super::timeline::format_timestamp;
+
+const SOURCES: [LogSource; 5] = [
+    LogSource::StandardOutput,
+    LogSource::StandardError,
+    LogSource::Tracking,
+    LogSource::Timeline,
+    LogSource::Participants,
+];
+
+/// The live log of the experiment, which combines the output of ARGoS on all robots with the events from the
+/// journal. The entries can be filtered by their source and by robot
+pub struct Card {
+    link: ComponentLink<Self>,
+    props: Props,
+    entry_list: NodeRef,
+    /* the sources that are hidden, the summaries of the tracking system are hidden by default */
+    hidden: BTreeSet<LogSource>,
+    /* only show the entries about this robot and the entries about the whole experiment */
+    robot: Option<String>,
+    /* the list follows the latest entry unless it has been paused */
+    paused: bool,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub entries: VecDeque<LogEntry>,
+}
+
+pub enum Msg {
+    Toggle(LogSource),
+    SelectRobot(Option<String>),
+    Pause(bool),
+}
+
+impl Component for Card {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Card {
+            link,
+            props,
+            entry_list: NodeRef::default(),
+            hidden: vec![LogSource::Tracking].into_iter().collect(),
+            robot: None,
+            paused: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Toggle(source) => {
+                if !self.hidden.remove(&source) {
+                    self.hidden.insert(source);
+                }
+            },
+            Msg::SelectRobot(robot) => self.robot = robot,
+            Msg::Pause(paused) => self.paused = paused,
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        !self.paused
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        if !self.paused {
+            if let Some(list) = self.entry_list.cast::<web_sys::Element>() {
+                list.set_scroll_top(list.scroll_height());
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let paused = self.paused;
+        let (pause_class, pause_label) = match paused {
+            true => ("is-info", t!("log.resume")),
+            false => ("is-light", t!("log.pause")),
+        };
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("log.title") }</p>
+                        </div>
+                        <div class="level-right">
+                            { self.render_robots() }
+                            <button class=classes!("level-item", "button", pause_class)
+                                    onclick=self.link.callback(move |_| Msg::Pause(!paused))>{ pause_label }</button>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <div class="buttons"> {
+                            SOURCES.iter().map(|source| {
+                                let source = *source;
+                                let class = match self.hidden.contains(&source) {
+                                    true => "is-light",
+                                    false => source_class(source),
+                                };
+                                html! {
+                                    <button class=classes!("button", "is-small", class)
+                                            onclick=self.link.callback(move |_| Msg::Toggle(source))>
+                                        { source_name(source) }
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        } </div>
+                        { self.render_entries() }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Card {
+    fn render_robots(&self) -> Html {
+        let robots = self.props.entries.iter()
+            .filter_map(|entry| entry.robot.as_ref())
+            .chain(self.robot.as_ref())
+            .collect::<BTreeSet<_>>();
+        let onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) if !select.value().is_empty() => Msg::SelectRobot(Some(select.value())),
+            _ => Msg::SelectRobot(None),
+        });
+        html! {
+            <div class="level-item select">
+                <select onchange=onchange>
+                    <option value="" selected=self.robot.is_none()>{ t!("log.robots.all") }</option> {
+                        robots.into_iter().map(|robot| html! {
+                            <option value=robot.clone() selected=self.robot.as_ref() == Some(robot)>{ robot }</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </div>
+        }
+    }
+
+    fn render_entries(&self) -> Html {
+        let entries = self.props.entries.iter()
+            .filter(|entry| !self.hidden.contains(&entry.source))
+            .filter(|entry| match (&self.robot, &entry.robot) {
+                (Some(selected), Some(robot)) => selected == robot,
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        html! {
+            <div ref=self.entry_list.clone() style="max-height:360px;overflow-y:auto">
+                <table class="table is-fullwidth is-narrow">
+                    <tbody> {
+                        match entries.is_empty() {
+                            true => html! {
+                                <tr><td>{ t!("log.none") }</td></tr>
+                            },
+                            false => entries.into_iter().map(|entry| html! {
+                                <tr>
+                                    <td class="is-family-monospace">{ format_timestamp(entry.timestamp) }</td>
+                                    <td>
+                                        <span class=classes!("tag", source_class(entry.source))>
+                                            { entry.robot.clone().unwrap_or_else(|| source_name(entry.source)) }
+                                        </span>
+                                    </td>
+                                    <td class=classes!("is-family-monospace",
+                                        (entry.source == LogSource::StandardError).then(|| "has-text-danger"))
+                                        style="white-space:pre-wrap">{ &entry.text }</td>
+                                </tr>
+                            }).collect::<Html>()
+                        }
+                    } </tbody>
+                </table>
+            </div>
+        }
+    }
+}
+
+fn source_class(source: LogSource) -> &'static str {
+    match source {
+        LogSource::StandardOutput => "is-dark",
+        LogSource::StandardError => "is-danger",
+        LogSource::Tracking => "is-link",
+        LogSource::Timeline => "is-info",
+        LogSource::Participants => "is-success",
+    }
+}
+
+fn source_name(source: LogSource) -> String {
+    match source {
+        LogSource::StandardOutput => t!("log.source.stdout"),
+        LogSource::StandardError => t!("log.source.stderr"),
+        LogSource::Tracking => t!("log.source.tracking"),
+        LogSource::Timeline => t!("log.source.timeline"),
+        LogSource::Participants => t!("log.source.participants"),
+    }
+}
@@ -1,36 +1,125 @@
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::rc::Rc;
 use yew::prelude::*;
 
-use yew::{html, Component, ComponentLink, Html, ShouldRender};
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
 
-use shared::experiment::{software::Software, Request};
+use shared::experiment::{fingerprint::Fingerprint, library::Bundle, profile::{self, CameraPolicy, Profile, Settings}, software::{Software, Source}, ChecklistItem, Energy, JournalStatistics, LogEntry, PartialStart, Readiness, Request, SmokeTest, State, Status};
 
-use shared::BackEndRequest;
+use shared::{BackEndRequest, access::Category, ambient, configuration::CameraShutdown, group::{self, Group}, router};
 
-use crate::UserInterface;
+use crate::{UserInterface, access};
 
 pub mod builderbot;
 pub mod drone;
+pub mod log;
+pub mod overrides;
 pub mod pipuck;
+pub mod timeline;
+
+/* the key under which the name of the operator is stored in the local storage of the browser */
+const OPERATOR_STORAGE_KEY: &'static str = "supervisor.operator";
+/* the key under which the choice to continue an experiment that only started on some robots is stored */
+const PARTIAL_START_STORAGE_KEY: &'static str = "supervisor.partial_start";
 
 pub struct Interface {
     link: ComponentLink<Self>,
     props: Props,
+    /* the fingerprint of a previous run (loaded from its .fingerprint.json file) */
+    reference: Option<Result<(String, Fingerprint), String>>,
+    reference_task: Option<ReaderTask>,
+    /* the name under which the items of the checklist are acknowledged */
+    operator: String,
+    /* whether the experiment is rolled back or continues if it can only be started on some robots */
+    partial_start: PartialStart,
+    /* the communication topology that is being edited and the error from parsing it */
+    topology: String,
+    topology_error: Option<String>,
+    topology_task: Option<ReaderTask>,
+    /* the communication range in meters that is being edited and the error from parsing it */
+    range: String,
+    range_error: Option<String>,
+    /* the result of the last check of the ARGoS configuration and the syntax of the Lua scripts */
+    software_check: Option<Result<(), String>>,
+    /* the selected profile and the error from resolving, saving, or deleting a profile */
+    profile: Option<String>,
+    profile_error: Option<String>,
+    /* the name under which the current choices are saved as a profile */
+    profile_name: String,
+    /* the group whose members participate in the experiment, all robots participate if None */
+    group: Option<String>,
+    /* the duration in seconds that is being edited, the experiment runs until it is stopped if empty */
+    duration: String,
+    settings: Settings,
 }
 
 // what if properties was just drone::Instance itself?
 #[derive(Clone, Properties)]
 pub struct Props {
     pub parent: ComponentLink<UserInterface>,
-    pub builderbot_software: Rc<RefCell<Software>>,
-    pub drone_software: Rc<RefCell<Software>>,
-    pub pipuck_software: Rc<RefCell<Software>>,
+    pub builderbot_software: Rc<RefCell<Source>>,
+    pub drone_software: Rc<RefCell<Source>>,
+    pub pipuck_software: Rc<RefCell<Source>>,
+    pub software_overrides: Rc<RefCell<BTreeMap<String, Software>>>,
+    pub journal_statistics: Option<JournalStatistics>,
+    /* the last entries of the live log of the experiment */
+    pub log: VecDeque<LogEntry>,
+    pub checklist: Vec<ChecklistItem>,
+    pub smoke_tests: BTreeMap<String, Option<SmokeTest>>,
+    /* the state of the experiment and the readiness of the participating robots */
+    pub status: Status,
+    pub groups: Vec<Group>,
+    /* the experiment profiles that are stored on the backend */
+    pub profiles: Vec<Profile>,
+    /* the bundles of control software that are stored in the library of the backend */
+    pub library: Vec<Bundle>,
+    /* the tags that are offered when annotating the timeline */
+    pub tags: Vec<String>,
+    pub ambient: ambient::Update,
+    /* the energy that each robot used during its last experiment */
+    pub energy: Vec<(String, Energy)>,
+    /* the links of the message router, labelled with the sending and the receiving robot */
+    pub traffic: Vec<(String, String, router::Link)>,
+    /* the topology and the range of the message router and the messages that it dropped */
+    pub topology: Option<router::Topology>,
+    pub range: Option<f32>,
+    pub dropped: u64,
+    /* the addresses through which the message router resolves the real robots */
+    pub routes: Vec<router::Route>,
 }
 
 pub enum Msg {
     StartExperiment,
     StopExperiment,
+    ReturnToBase,
+    CheckSoftware,
+    SetSoftwareCheck(Result<(), String>),
+    ReadReference(Vec<File>),
+    LoadReference(FileData),
+    SetOperator(String),
+    SetPartialStart(bool),
+    Acknowledge(usize, bool),
+    RunSmokeTest(Option<String>),
+    RunBandwidthTest(Option<String>),
+    StartGroupExperiment(String),
+    GroupAction(String, group::Action),
+    SetTopology(String),
+    ReadTopology(Vec<File>),
+    LoadTopology(FileData),
+    ApplyTopology,
+    ClearTopology,
+    SetRange(String),
+    ApplyRange,
+    SelectProfile(Option<String>),
+    SetGroup(Option<String>),
+    SetDuration(String),
+    SetCameras(String),
+    SetProfileName(String),
+    SaveProfile,
+    DeleteProfile,
+    SetProfileResult(Result<(), String>),
 }
 
 impl Component for Interface {
@@ -39,65 +128,1391 @@ impl Component for Interface {
 
     fn create(props: Props, link: ComponentLink<Self>) -> Self {
         props.parent.send_message(crate::Msg::SetControlConfigComp(link.clone()));
+        let topology = props.topology.as_ref()
+            .map_or_else(String::new, |topology| topology.to_string());
+        let range = props.range
+            .map_or_else(String::new, |range| range.to_string());
         Interface { 
             props,
             link,
+            reference: None,
+            reference_task: None,
+            operator: storage()
+                .and_then(|storage| storage.get_item(OPERATOR_STORAGE_KEY).ok().flatten())
+                .unwrap_or_default(),
+            partial_start: partial_start(),
+            topology,
+            topology_error: None,
+            topology_task: None,
+            range,
+            range_error: None,
+            software_check: None,
+            profile: None,
+            profile_error: None,
+            profile_name: String::new(),
+            group: None,
+            duration: String::new(),
+            settings: Settings::default(),
         }
     }
 
     fn update(&mut self, message: Self::Message) -> ShouldRender {
         match message {
             Msg::StartExperiment => {
-                let request = BackEndRequest::ExperimentRequest(Request::Start {
+                self.start(self.group.clone());
+                return true;
+            },
+            Msg::StopExperiment => {
+                let request = BackEndRequest::ExperimentRequest(Request::Stop);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+            },
+            Msg::ReturnToBase => {
+                let request = BackEndRequest::ReturnToBaseRequest;
+                self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+            },
+            Msg::CheckSoftware => {
+                let callback = self.link.callback(Msg::SetSoftwareCheck);
+                let request = BackEndRequest::ExperimentRequest(Request::Check {
                     builderbot_software: self.props.builderbot_software.borrow().clone(),
                     pipuck_software: self.props.pipuck_software.borrow().clone(),
                     drone_software: self.props.drone_software.borrow().clone(),
+                    overrides: self.props.software_overrides.borrow().clone(),
                 });
+                self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+                self.software_check = None;
+                return true;
+            },
+            Msg::SetSoftwareCheck(result) => {
+                self.software_check = Some(result);
+                return true;
+            },
+            Msg::ReadReference(files) => if let Some(file) = files.into_iter().next() {
+                let callback = self.link.callback(Msg::LoadReference);
+                self.reference_task = ReaderService::read_file(file, callback).ok();
+            },
+            Msg::LoadReference(FileData { name, content }) => {
+                self.reference_task = None;
+                self.reference = Some(match serde_json::from_slice::<Fingerprint>(&content) {
+                    Ok(fingerprint) => Ok((name, fingerprint)),
+                    Err(error) => Err(t!("experiment.fingerprint.error", name = name, error = error)),
+                });
+                return true;
+            },
+            Msg::SetOperator(operator) => {
+                if let Some(storage) = storage() {
+                    let _ = storage.set_item(OPERATOR_STORAGE_KEY, &operator);
+                }
+                self.operator = operator;
+                return true;
+            },
+            Msg::SetPartialStart(proceed) => {
+                self.partial_start = match proceed {
+                    true => PartialStart::Continue,
+                    false => PartialStart::Rollback,
+                };
+                if let Some(storage) = storage() {
+                    let _ = storage.set_item(PARTIAL_START_STORAGE_KEY, &format!("{:?}", self.partial_start));
+                }
+                return true;
+            },
+            Msg::Acknowledge(item, acknowledged) => {
+                let operator = Some(self.operator.trim().to_owned()).filter(|_| acknowledged);
+                let request = BackEndRequest::ExperimentRequest(Request::Acknowledge { item, operator });
                 self.props.parent.send_message(crate::Msg::SendRequest(request, None));
             },
-            Msg::StopExperiment => {
-                let request = BackEndRequest::ExperimentRequest(Request::Stop);
+            Msg::RunSmokeTest(robot) => {
+                let request = BackEndRequest::ExperimentRequest(Request::SmokeTest(robot));
+                self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+            },
+            Msg::RunBandwidthTest(robot) => {
+                let request = BackEndRequest::ExperimentRequest(Request::BandwidthTest(robot));
+                self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+            },
+            Msg::StartGroupExperiment(group) => {
+                self.start(Some(group));
+                return true;
+            },
+            Msg::GroupAction(group, action) => {
+                let request = BackEndRequest::GroupRequest(group, action);
                 self.props.parent.send_message(crate::Msg::SendRequest(request, None));
             },
+            Msg::SetTopology(topology) => {
+                self.topology = topology;
+            },
+            Msg::ReadTopology(files) => if let Some(file) = files.into_iter().next() {
+                let callback = self.link.callback(Msg::LoadTopology);
+                self.topology_task = ReaderService::read_file(file, callback).ok();
+            },
+            Msg::LoadTopology(FileData { name, content }) => {
+                self.topology_task = None;
+                match String::from_utf8(content) {
+                    Ok(topology) => {
+                        self.topology = topology;
+                        self.topology_error = None;
+                    },
+                    Err(error) => self.topology_error = Some(format!("{}: {}", name, error)),
+                }
+                return true;
+            },
+            Msg::ApplyTopology => {
+                match router::Topology::parse(&self.topology) {
+                    Ok(topology) => {
+                        self.topology_error = None;
+                        let request = BackEndRequest::TopologyRequest(Some(topology));
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+                    },
+                    Err(error) => self.topology_error = Some(error),
+                }
+                return true;
+            },
+            Msg::ClearTopology => {
+                self.topology_error = None;
+                let request = BackEndRequest::TopologyRequest(None);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+                return true;
+            },
+            Msg::SetRange(range) => {
+                self.range = range;
+            },
+            Msg::ApplyRange => {
+                /* an empty range lets the robots communicate regardless of their distance */
+                let range = match self.range.trim() {
+                    "" => Ok(None),
+                    range => match range.parse::<f32>() {
+                        Ok(range) if range > 0.0 => Ok(Some(range)),
+                        _ => Err(t!("experiment.topology.range.invalid", range = range)),
+                    }
+                };
+                match range {
+                    Ok(range) => {
+                        self.range_error = None;
+                        let request = BackEndRequest::RangeRequest(range);
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+                    },
+                    Err(error) => self.range_error = Some(error),
+                }
+                return true;
+            },
+            Msg::SelectProfile(Some(name)) => {
+                match profile::resolve(&self.props.profiles, &name) {
+                    Ok(profile) => self.apply(profile),
+                    Err(error) => self.profile_error = Some(error),
+                }
+                self.profile_name = name.clone();
+                self.profile = Some(name);
+                return true;
+            },
+            Msg::SelectProfile(None) => {
+                self.profile = None;
+                self.profile_error = None;
+                return true;
+            },
+            Msg::SetGroup(group) => {
+                self.group = group;
+                return true;
+            },
+            Msg::SetDuration(duration) => {
+                self.duration = duration;
+            },
+            Msg::SetCameras(policy) => {
+                self.settings.cameras = match policy.as_str() {
+                    "keep" => Some(CameraPolicy::Keep),
+                    /* the exempted robots of a profile are kept when switching back to suspending the streams */
+                    "suspend" => Some(match self.settings.cameras.take() {
+                        Some(CameraPolicy::Suspend(camera_shutdown)) => CameraPolicy::Suspend(camera_shutdown),
+                        _ => CameraPolicy::Suspend(CameraShutdown { restore: true, exempt: Vec::new() }),
+                    }),
+                    _ => None,
+                };
+                return true;
+            },
+            Msg::SetProfileName(name) => {
+                self.profile_name = name;
+            },
+            Msg::SaveProfile => {
+                match self.profile() {
+                    Ok(profile) => {
+                        let callback = self.link.callback(Msg::SetProfileResult);
+                        self.profile = Some(profile.name.clone());
+                        self.profile_error = None;
+                        let request = BackEndRequest::ProfileRequest(profile::Request::Save(profile));
+                        self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+                    },
+                    Err(error) => self.profile_error = Some(error),
+                }
+                return true;
+            },
+            Msg::DeleteProfile => if let Some(name) = self.profile.take() {
+                let callback = self.link.callback(Msg::SetProfileResult);
+                self.profile_error = None;
+                let request = BackEndRequest::ProfileRequest(profile::Request::Delete(name));
+                self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+                return true;
+            },
+            Msg::SetProfileResult(result) => {
+                self.profile_error = result.err();
+                return true;
+            },
         }
         false
     }
 
-    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
         true
     }
 
     fn view(&self) -> Html {
+        let permitted = access::permitted(Category::Experiment);
+        /* the experiment can only be started once every item of the checklist has been acknowledged, the
+           selected software is valid, and no other experiment is being set up or stopped */
+        let acknowledged = self.props.checklist.iter().all(|item| item.acknowledgement.is_some());
+        let start = match (permitted, acknowledged, self.software_valid(), self.props.status.state.startable()) {
+            (false, _, _, _) => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("access.forbidden")>{ t!("experiment.start") }</span>
+            },
+            (true, true, true, true) => html! {
+                <a class="card-footer-item"
+                   onclick=self.link.callback(|_| Msg::StartExperiment)>{ t!("experiment.start") }</a>
+            },
+            (true, false, _, _) => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("experiment.checklist.pending")>{ t!("experiment.start") }</span>
+            },
+            (true, true, false, _) => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("experiment.software.invalid")>{ t!("experiment.start") }</span>
+            },
+            (true, true, true, false) => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("experiment.status.busy")>{ t!("experiment.start") }</span>
+            },
+        };
+        let stop = match permitted {
+            true => html! {
+                <a class="card-footer-item"
+                   onclick=self.link.callback(|_| Msg::StopExperiment)>{ t!("experiment.stop") }</a>
+            },
+            false => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("access.forbidden")>{ t!("experiment.stop") }</span>
+            },
+        };
+        let return_to_base = match access::permitted(Category::Power) {
+            true => html! {
+                <a class="card-footer-item"
+                   title=t!("experiment.return_to_base.description")
+                   onclick=self.link.callback(|_| Msg::ReturnToBase)>{ t!("experiment.return_to_base") }</a>
+            },
+            false => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("access.forbidden")>{ t!("experiment.return_to_base") }</span>
+            },
+        };
+        let check = match permitted {
+            true => html! {
+                <a class="card-footer-item"
+                   onclick=self.link.callback(|_| Msg::CheckSoftware)>{ t!("experiment.software.check") }</a>
+            },
+            false => html! {
+                <span class="card-footer-item has-text-grey-light"
+                      title=t!("access.forbidden")>{ t!("experiment.software.check") }</span>
+            },
+        };
+        let timeline = self.props.journal_statistics.as_ref()
+            .map_or_else(Vec::new, |statistics| statistics.timeline.clone());
         html! {
             <>
                 <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                    <builderbot::ConfigCard software=self.props.builderbot_software.clone() />
+                    <builderbot::ConfigCard parent=self.props.parent.clone()
+                        software=self.props.builderbot_software.clone()
+                        library=self.props.library.clone() />
+                </div>
+                <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                    <drone::ConfigCard parent=self.props.parent.clone()
+                        software=self.props.drone_software.clone()
+                        library=self.props.library.clone() />
                 </div>
                 <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                    <drone::ConfigCard software=self.props.drone_software.clone() />
+                    <pipuck::ConfigCard parent=self.props.parent.clone()
+                        software=self.props.pipuck_software.clone()
+                        library=self.props.library.clone() />
                 </div>
                 <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                    <pipuck::ConfigCard software=self.props.pipuck_software.clone() />
+                    <overrides::ConfigCard overrides=self.props.software_overrides.clone() />
                 </div>
+                { self.render_profiles() }
                 <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
                     <div class="card">
                     <header class="card-header">
                         <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
                             <div class="level-left">
-                                <p class="level-item subtitle is-size-4">{ "Control Panel" }</p>
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.control") }</p>
                             </div>
                         </nav>
                     </header>
+                    { self.render_status() }
+                    { self.render_software_check() }
+                    { self.render_checklist() }
+                    { self.render_partial_start() }
                     <footer class="card-footer">
-                        <a class="card-footer-item" 
-                           onclick=self.link.callback(|_| Msg::StartExperiment)>{ "Start experiment" }</a>
-                        <a class="card-footer-item" 
-                           onclick=self.link.callback(|_| Msg::StopExperiment)>{ "Stop experiment" }</a>
+                        { start }
+                        { check }
+                        { stop }
+                        { return_to_base }
                     </footer>
                     </div>
                 </div>
+                <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                    { self.render_journal() }
+                </div>
+                <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                    { self.render_smoke_tests() }
+                </div>
+                { self.render_groups() }
+                { self.render_ambient() }
+                { self.render_energy() }
+                { self.render_traffic() }
+                { self.render_topology() }
+                <div class="column is-full">
+                    <timeline::Card parent=self.props.parent.clone() events=timeline tags=self.props.tags.clone() />
+                </div>
+                <div class="column is-full">
+                    <log::Card entries=self.props.log.clone() />
+                </div>
             </>
             
         }
     }
+}
+
+impl Interface {
+    /// Starts an experiment with the current choices, only the members of the given group participate
+    fn start(&mut self, group: Option<String>) {
+        if !self.software_valid() {
+            self.profile_error = Some(t!("experiment.software.invalid"));
+            return;
+        }
+        let settings = match self.settings() {
+            Ok(settings) => settings,
+            Err(error) => {
+                self.profile_error = Some(error);
+                return;
+            }
+        };
+        let builderbot_software = self.props.builderbot_software.borrow().clone();
+        let pipuck_software = self.props.pipuck_software.borrow().clone();
+        let drone_software = self.props.drone_software.borrow().clone();
+        let overrides = self.props.software_overrides.borrow().clone();
+        let partial_start = self.partial_start;
+        let request = match group {
+            Some(group) => BackEndRequest::GroupRequest(group, group::Action::StartExperiment {
+                builderbot_software, drone_software, pipuck_software, overrides, partial_start, settings
+            }),
+            None => BackEndRequest::ExperimentRequest(Request::Start {
+                builderbot_software, drone_software, pipuck_software, overrides, partial_start, settings
+            }),
+        };
+        self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+    }
+
+    /// Whether the software that was selected passes the validation. The software of a robot type without
+    /// any files is left to the supervisor, since it is only required if robots of that type participate,
+    /// while a bundle must still be in the library
+    fn software_valid(&self) -> bool {
+        let sources = [&self.props.builderbot_software, &self.props.drone_software, &self.props.pipuck_software];
+        let defaults = sources.iter().all(|source| match &*source.borrow() {
+            Source::Files(software) => software.0.is_empty() || software.validate().is_empty(),
+            Source::Repository(_) => true,
+            Source::Library(reference) => self.props.library.iter().any(|bundle| bundle.reference() == *reference),
+        });
+        defaults && self.props.software_overrides.borrow().values()
+            .all(|software| software.validate().is_empty())
+    }
+
+    fn settings(&self) -> Result<Settings, String> {
+        let duration = match self.duration.trim() {
+            "" => None,
+            duration => match duration.parse::<u64>() {
+                Ok(duration) if duration > 0 => Some(duration),
+                _ => return Err(t!("experiment.profile.duration.invalid", duration = duration)),
+            }
+        };
+        Ok(Settings { duration, ..self.settings.clone() })
+    }
+
+    /// Replaces the current choices with the choices of a resolved profile, the software is only replaced if
+    /// the profile chooses it
+    fn apply(&mut self, profile: Profile) {
+        if let Some(software) = profile.builderbot_software {
+            *self.props.builderbot_software.borrow_mut() = software;
+        }
+        if let Some(software) = profile.drone_software {
+            *self.props.drone_software.borrow_mut() = software;
+        }
+        if let Some(software) = profile.pipuck_software {
+            *self.props.pipuck_software.borrow_mut() = software;
+        }
+        if let Some(overrides) = profile.overrides {
+            *self.props.software_overrides.borrow_mut() = overrides;
+        }
+        if let Some(partial_start) = profile.partial_start {
+            self.partial_start = partial_start;
+        }
+        self.group = profile.group;
+        self.duration = profile.settings.duration.map_or_else(String::new, |duration| duration.to_string());
+        self.settings = profile.settings;
+        self.profile_error = None;
+    }
+
+    /// The current choices as a profile that inherits from the selected profile, or from the parent of the
+    /// selected profile when the selected profile is replaced, so that only the choices that differ are saved
+    fn profile(&self) -> Result<Profile, String> {
+        let name = self.profile_name.trim().to_owned();
+        if name.is_empty() {
+            return Err(t!("experiment.profile.name.missing"));
+        }
+        let mut profile = Profile {
+            name: name.clone(),
+            parent: None,
+            group: self.group.clone(),
+            builderbot_software: Some(self.props.builderbot_software.borrow().clone()),
+            drone_software: Some(self.props.drone_software.borrow().clone()),
+            pipuck_software: Some(self.props.pipuck_software.borrow().clone()),
+            overrides: Some(self.props.software_overrides.borrow().clone()),
+            partial_start: Some(self.partial_start),
+            settings: self.settings()?,
+        };
+        let parent = match &self.profile {
+            Some(selected) if *selected == name => self.props.profiles.iter()
+                .find(|candidate| candidate.name == name)
+                .and_then(|candidate| candidate.parent.clone()),
+            selected => selected.clone(),
+        };
+        if let Some(parent) = parent {
+            profile.relative_to(&profile::resolve(&self.props.profiles, &parent)?);
+        }
+        Ok(profile)
+    }
+
+    fn render_profiles(&self) -> Html {
+        let permitted = access::permitted(Category::Experiment);
+        let profile_onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) if !select.value().is_empty() => Msg::SelectProfile(Some(select.value())),
+            _ => Msg::SelectProfile(None),
+        });
+        let group_onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) if !select.value().is_empty() => Msg::SetGroup(Some(select.value())),
+            _ => Msg::SetGroup(None),
+        });
+        let cameras_onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) => Msg::SetCameras(select.value()),
+            _ => Msg::SetCameras(String::new()),
+        });
+        let duration_oninput = self.link.callback(|data: InputData| Msg::SetDuration(data.value));
+        let name_oninput = self.link.callback(|data: InputData| Msg::SetProfileName(data.value));
+        let cameras = match &self.settings.cameras {
+            None => "",
+            Some(CameraPolicy::Keep) => "keep",
+            Some(CameraPolicy::Suspend(_)) => "suspend",
+        };
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.profile") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("experiment.profile.description") }</p>
+                            <div class="field">
+                                <div class="control">
+                                    <div class="select is-fullwidth">
+                                        <select onchange=profile_onchange>
+                                            <option value="" selected=self.profile.is_none()>{ t!("experiment.profile.none") }</option> {
+                                                self.props.profiles.iter().map(|profile| {
+                                                    let label = match &profile.parent {
+                                                        Some(parent) => t!("experiment.profile.inherits", name = profile.name, parent = parent),
+                                                        None => profile.name.clone(),
+                                                    };
+                                                    html! {
+                                                        <option value=profile.name.clone()
+                                                                selected=self.profile.as_ref() == Some(&profile.name)>{ label }</option>
+                                                    }
+                                                }).collect::<Html>()
+                                            }
+                                        </select>
+                                    </div>
+                                </div> {
+                                    match &self.profile_error {
+                                        Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                            <div class="field">
+                                <label class="label">{ t!("experiment.profile.group") }</label>
+                                <div class="control">
+                                    <div class="select is-fullwidth">
+                                        <select onchange=group_onchange>
+                                            <option value="" selected=self.group.is_none()>{ t!("experiment.profile.group.all") }</option> {
+                                                self.props.groups.iter().map(|group| html! {
+                                                    <option value=group.name.clone()
+                                                            selected=self.group.as_ref() == Some(&group.name)>{ &group.name }</option>
+                                                }).collect::<Html>()
+                                            }
+                                        </select>
+                                    </div>
+                                </div>
+                            </div>
+                            <div class="field">
+                                <label class="label">{ t!("experiment.profile.duration") }</label>
+                                <div class="control">
+                                    <input class="input" type="number" min="1" value=self.duration.clone()
+                                        placeholder=t!("experiment.profile.duration.unlimited") oninput=duration_oninput />
+                                </div>
+                            </div>
+                            <div class="field">
+                                <label class="label">{ t!("experiment.profile.cameras") }</label>
+                                <div class="control">
+                                    <div class="select is-fullwidth">
+                                        <select onchange=cameras_onchange>
+                                            <option value="" selected=cameras.is_empty()>{ t!("experiment.profile.cameras.configured") }</option>
+                                            <option value="keep" selected=cameras == "keep">{ t!("experiment.profile.cameras.keep") }</option>
+                                            <option value="suspend" selected=cameras == "suspend">{ t!("experiment.profile.cameras.suspend") }</option>
+                                        </select>
+                                    </div>
+                                </div> {
+                                    match &self.settings.cameras {
+                                        Some(CameraPolicy::Suspend(camera_shutdown)) if !camera_shutdown.exempt.is_empty() => html! {
+                                            <p class="help">{ t!("experiment.profile.cameras.exempt", robots = camera_shutdown.exempt.join(", ")) }</p>
+                                        },
+                                        _ => html! {},
+                                    }
+                                }
+                            </div> {
+                                /* the battery thresholds can only be chosen in the stored profiles */
+                                match &self.settings.battery_alerts {
+                                    Some(battery_alerts) => {
+                                        let thresholds = battery_alerts.thresholds.iter()
+                                            .map(|threshold| format!("{} {}%/{}%", threshold.robot, threshold.warning, threshold.critical))
+                                            .collect::<Vec<_>>();
+                                        html! {
+                                            <p>{ t!("experiment.profile.battery", policy = battery_alerts.policy.name(), thresholds = thresholds.join(", ")) }</p>
+                                        }
+                                    },
+                                    None => html! {},
+                                }
+                            } {
+                                /* the decimation of the output can only be chosen in the stored profiles */
+                                match &self.settings.decimation {
+                                    Some(decimation) if !decimation.is_empty() => {
+                                        let rules = decimation.iter()
+                                            .map(|rule| format!("\"{}\" 1/{}", rule.pattern, rule.every))
+                                            .collect::<Vec<_>>();
+                                        html! {
+                                            <p>{ t!("experiment.profile.decimation", rules = rules.join(", ")) }</p>
+                                        }
+                                    },
+                                    _ => html! {},
+                                }
+                            }
+                            <div class="field has-addons">
+                                <div class="control is-expanded">
+                                    <input class="input" type="text" value=self.profile_name.clone()
+                                        placeholder=t!("experiment.profile.name") oninput=name_oninput />
+                                </div>
+                                <div class="control">
+                                    <button class="button" disabled=!permitted
+                                        onclick=self.link.callback(|_| Msg::SaveProfile)>{ t!("experiment.profile.save") }</button>
+                                </div>
+                            </div>
+                            <p class="help">{ t!("experiment.profile.save.description") }</p>
+                        </div>
+                    </div>
+                    <footer class="card-footer"> {
+                        match (permitted, self.profile.is_some()) {
+                            (true, true) => html! {
+                                <a class="card-footer-item"
+                                   onclick=self.link.callback(|_| Msg::DeleteProfile)>{ t!("experiment.profile.delete") }</a>
+                            },
+                            (true, false) => html! {
+                                <span class="card-footer-item has-text-grey-light">{ t!("experiment.profile.delete") }</span>
+                            },
+                            (false, _) => html! {
+                                <span class="card-footer-item has-text-grey-light"
+                                      title=t!("access.forbidden")>{ t!("experiment.profile.delete") }</span>
+                            },
+                        }
+                    } </footer>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_status(&self) -> Html {
+        let status = &self.props.status;
+        let (class, label) = match &status.state {
+            State::Idle => ("is-light", t!("experiment.status.idle")),
+            State::Uploading => ("is-info", t!("experiment.status.uploading")),
+            State::Ready => ("is-info", t!("experiment.status.ready")),
+            State::Running => ("is-success", t!("experiment.status.running")),
+            State::Stopping => ("is-warning", t!("experiment.status.stopping")),
+            State::Finished => ("is-light", t!("experiment.status.finished")),
+            State::Error(_) => ("is-danger", t!("experiment.status.error")),
+        };
+        html! {
+            <div class="card-content">
+                <div class="content">
+                    <p>
+                        <span class=classes!("tag", "is-medium", class)>{ label }</span>
+                    </p> {
+                        match &status.state {
+                            State::Error(error) => html! {
+                                <p class="help is-danger">{ error }</p>
+                            },
+                            _ => html! {},
+                        }
+                    } {
+                        match status.robots.is_empty() {
+                            true => html! {},
+                            false => html! {
+                                <table class="table is-fullwidth is-narrow">
+                                    <tbody> {
+                                        status.robots.iter().map(|(robot, readiness)| html! {
+                                            <tr>
+                                                <td>{ robot }</td>
+                                                <td>{ render_readiness(readiness) }</td>
+                                            </tr>
+                                        }).collect::<Html>()
+                                    } </tbody>
+                                </table>
+                            },
+                        }
+                    }
+                </div>
+            </div>
+        }
+    }
+
+    fn render_software_check(&self) -> Html {
+        match &self.software_check {
+            Some(Ok(())) => html! {
+                <div class="card-content">
+                    <p class="help is-success">{ t!("experiment.software.check.passed") }</p>
+                </div>
+            },
+            /* each line of the error refers to a file and, where possible, to a line in that file */
+            Some(Err(error)) => html! {
+                <div class="card-content">
+                    <p class="help is-danger">{ t!("experiment.software.check.failed") }</p>
+                    <pre class="is-size-7">{ error }</pre>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    fn render_checklist(&self) -> Html {
+        if self.props.checklist.is_empty() {
+            return html! {};
+        }
+        let operator_oninput = self.link.callback(|data: InputData| Msg::SetOperator(data.value));
+        let anonymous = self.operator.trim().is_empty();
+        html! {
+            <div class="card-content">
+                <div class="field">
+                    <label class="label">{ t!("experiment.checklist.operator") }</label>
+                    <div class="control">
+                        <input class="input" type="text" value=self.operator.clone() oninput=operator_oninput />
+                    </div>
+                    <p class="help">{ t!("experiment.checklist.description") }</p>
+                </div> {
+                    self.props.checklist.iter().enumerate().map(|(index, item)| {
+                        let acknowledged = item.acknowledgement.is_some();
+                        let onclick = self.link.callback(move |_| Msg::Acknowledge(index, !acknowledged));
+                        html! {
+                            <div class="field">
+                                <label class="checkbox">
+                                    <input type="checkbox"
+                                           checked=acknowledged
+                                           disabled=anonymous && !acknowledged
+                                           onclick=onclick />
+                                    { " " }{ &item.description }
+                                </label> {
+                                    match &item.acknowledgement {
+                                        Some(acknowledgement) => html! {
+                                            <p class="help">{
+                                                t!("experiment.checklist.acknowledged",
+                                                    operator = acknowledgement.operator,
+                                                    time = acknowledgement.time)
+                                            }</p>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    fn render_partial_start(&self) -> Html {
+        let proceed = self.partial_start == PartialStart::Continue;
+        let onclick = self.link.callback(move |_| Msg::SetPartialStart(!proceed));
+        html! {
+            <div class="card-content">
+                <div class="field">
+                    <label class="checkbox">
+                        <input type="checkbox" checked=proceed onclick=onclick />
+                        { " " }{ t!("experiment.partial_start") }
+                    </label>
+                    <p class="help">{ t!("experiment.partial_start.description") }</p>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_smoke_tests(&self) -> Html {
+        /* smoke tests drive the actuators of the robots and are hidden from roles that cannot run experiments */
+        let hidden = (!access::permitted(Category::Experiment)).then(|| "is-hidden");
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("experiment.smoke_test") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <p class="help">{ t!("experiment.smoke_test.description") }</p>
+                        <table class="table is-fullwidth is-hoverable">
+                            <tbody> {
+                                self.props.smoke_tests.iter().map(|(robot, smoke_test)| {
+                                    let id = robot.clone();
+                                    let onclick = self.link.callback(move |_| Msg::RunSmokeTest(Some(id.clone())));
+                                    let id = robot.clone();
+                                    let bandwidth_onclick = self.link.callback(move |_| Msg::RunBandwidthTest(Some(id.clone())));
+                                    html! {
+                                        <tr>
+                                            <td>{ robot }</td>
+                                            <td>{ render_smoke_test(smoke_test.as_ref()) }</td>
+                                            <td>
+                                                <a class=classes!(hidden) onclick=onclick>{ t!("experiment.smoke_test.run") }</a>
+                                            </td>
+                                            <td>
+                                                <a class=classes!(hidden) onclick=bandwidth_onclick>{ t!("experiment.smoke_test.bandwidth") }</a>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            } </tbody>
+                        </table>
+                    </div>
+                </div>
+                <footer class=classes!("card-footer", hidden)>
+                    <a class="card-footer-item"
+                       onclick=self.link.callback(|_| Msg::RunSmokeTest(None))>{ t!("experiment.smoke_test.run_all") }</a>
+                    <a class="card-footer-item"
+                       onclick=self.link.callback(|_| Msg::RunBandwidthTest(None))>{ t!("experiment.smoke_test.bandwidth_all") }</a>
+                </footer>
+            </div>
+        }
+    }
+
+    fn render_groups(&self) -> Html {
+        if self.props.groups.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.groups") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content"> {
+                            self.props.groups.iter().map(|group| {
+                                let onclick = |action: fn() -> group::Action| {
+                                    let name = group.name.clone();
+                                    self.link.callback(move |_| Msg::GroupAction(name.clone(), action()))
+                                };
+                                /* actions are disabled if the role of this client is not permitted to request them */
+                                let disabled = |action: group::Action| !action.category().map_or(true, access::permitted);
+                                let name = group.name.clone();
+                                let start = self.link.callback(move |_| Msg::StartGroupExperiment(name.clone()));
+                                html! {
+                                    <div class="block">
+                                        <p>
+                                            <strong>{ &group.name }</strong>
+                                            <br/>
+                                            { group.members.join(", ") }
+                                        </p>
+                                        <div class="buttons are-small">
+                                            <button class="button" onclick=start
+                                                    disabled=!access::permitted(Category::Experiment)>
+                                                { t!("experiment.groups.start") }
+                                            </button>
+                                            <button class="button" onclick=onclick(|| group::Action::StopExperiment)
+                                                    disabled=disabled(group::Action::StopExperiment)>
+                                                { t!("experiment.groups.stop") }
+                                            </button>
+                                            <button class="button" onclick=onclick(|| group::Action::Identify)>
+                                                { t!("experiment.groups.identify") }
+                                            </button>
+                                            <button class="button" onclick=onclick(|| group::Action::CameraStreamEnable(false))>
+                                                { t!("experiment.groups.cameras_off") }
+                                            </button>
+                                            <button class="button is-warning" onclick=onclick(|| group::Action::Halt)
+                                                    disabled=disabled(group::Action::Halt)>
+                                                { t!("experiment.groups.halt") }
+                                            </button>
+                                            <button class="button is-warning" onclick=onclick(|| group::Action::Reboot)
+                                                    disabled=disabled(group::Action::Reboot)>
+                                                { t!("experiment.groups.reboot") }
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        } </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_ambient(&self) -> Html {
+        if self.props.ambient.readings.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.ambient") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("experiment.ambient.description") }</p>
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    self.props.ambient.readings.iter().map(|reading| html! {
+                                        <tr>
+                                            <td>{ &reading.sensor }</td>
+                                            <td title=reading.time.clone()> {
+                                                match &reading.value {
+                                                    Ok(value) => html! {
+                                                        <span>{ format!("{:.1} {}", value, reading.unit) }</span>
+                                                    },
+                                                    Err(error) => html! {
+                                                        <span class="tag is-warning" title=error.clone()>
+                                                            { t!("experiment.ambient.unavailable") }
+                                                        </span>
+                                                    },
+                                                }
+                                            } </td>
+                                        </tr>
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_energy(&self) -> Html {
+        if self.props.energy.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.energy") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("experiment.energy.description") }</p>
+                            <table class="table is-fullwidth is-hoverable">
+                                <thead>
+                                    <tr>
+                                        <th>{ t!("experiment.overrides.robot") }</th>
+                                        <th>{ t!("experiment.energy.battery") }</th>
+                                        <th>{ t!("experiment.energy.consumed") }</th>
+                                        <th>{ t!("experiment.energy.voltage") }</th>
+                                    </tr>
+                                </thead>
+                                <tbody> {
+                                    self.props.energy.iter().map(|(robot, energy)| html! {
+                                        <tr>
+                                            <td>{ robot }</td>
+                                            <td>{ format!("{}% → {}%", energy.start, energy.end) }</td>
+                                            <td>{ format!("{}%", energy.consumed()) }</td>
+                                            <td> {
+                                                energy.voltage.map_or_else(|| "-".to_owned(), |(start, end)|
+                                                    format!("{:.2} V → {:.2} V", start as f32 / 1e3, end as f32 / 1e3))
+                                            } </td>
+                                        </tr>
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_traffic(&self) -> Html {
+        if self.props.traffic.is_empty() {
+            return html! {};
+        }
+        let peers = self.props.traffic.iter()
+            .flat_map(|(from, to, _)| vec![from, to])
+            .collect::<BTreeSet<_>>();
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-full-desktop is-two-thirds-widescreen is-half-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.traffic") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("experiment.traffic.description") }</p>
+                            <div class="table-container">
+                                <table class="table is-fullwidth is-hoverable is-narrow">
+                                    <thead>
+                                        <tr>
+                                            <th>{ t!("experiment.traffic.from") }</th>
+                                            { peers.iter().map(|to| html! { <th>{ to }</th> }).collect::<Html>() }
+                                        </tr>
+                                    </thead>
+                                    <tbody> {
+                                        peers.iter().map(|from| html! {
+                                            <tr>
+                                                <th>{ from }</th> {
+                                                    peers.iter().map(|to| {
+                                                        let link = self.props.traffic.iter()
+                                                            .find(|(sender, receiver, _)| sender == *from && receiver == *to)
+                                                            .map(|(_, _, link)| link);
+                                                        match link {
+                                                            Some(link) => html! {
+                                                                <td title=t!("experiment.traffic.latency",
+                                                                    mean = format!("{:.1}", link.mean_latency),
+                                                                    max = format!("{:.1}", link.max_latency))>
+                                                                    { t!("experiment.traffic.link",
+                                                                        messages = link.messages,
+                                                                        kilobytes = format!("{:.1}", link.bytes as f64 / 1e3)) }
+                                                                </td>
+                                                            },
+                                                            None => html! { <td>{ "-" }</td> },
+                                                        }
+                                                    }).collect::<Html>()
+                                                }
+                                            </tr>
+                                        }).collect::<Html>()
+                                    } </tbody>
+                                </table>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_topology(&self) -> Html {
+        let permitted = access::permitted(Category::Experiment);
+        let oninput = self.link.callback(|data: InputData| Msg::SetTopology(data.value));
+        let range_oninput = self.link.callback(|data: InputData| Msg::SetRange(data.value));
+        let onchange = self.link.callback(move |value| {
+            let mut result = Vec::new();
+            if let ChangeData::Files(files) = value {
+                let files = js_sys::try_iter(&files)
+                    .unwrap()
+                    .unwrap()
+                    .map(|v| File::from(v.unwrap()));
+                result.extend(files);
+            }
+            Msg::ReadTopology(result)
+        });
+        let status = match &self.props.topology {
+            Some(topology) => t!("experiment.topology.active", robots = topology.robots().len()),
+            None => t!("experiment.topology.inactive"),
+        };
+        let range_status = match self.props.range {
+            Some(range) => t!("experiment.topology.range.active", range = range),
+            None => t!("experiment.topology.range.inactive"),
+        };
+        let dropped = match (&self.props.topology, self.props.range) {
+            (None, None) => html! {},
+            _ => html! { <p>{ t!("experiment.topology.dropped", dropped = self.props.dropped) }</p> },
+        };
+        let routes = match self.props.routes.is_empty() {
+            true => html! {},
+            false => html! {
+                <table class="table is-fullwidth is-narrow">
+                    <thead>
+                        <tr>
+                            <th>{ t!("experiment.overrides.robot") }</th>
+                            <th>{ t!("experiment.topology.routes.address") }</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody> {
+                        self.props.routes.iter().map(|route| html! {
+                            <tr>
+                                <td>{ &route.robot }</td>
+                                <td>{ route.addr }</td>
+                                <td> {
+                                    match route.origin {
+                                        router::Origin::Static => html! {
+                                            <span class="tag is-info" title=t!("experiment.topology.routes.static.description")>
+                                                { t!("experiment.topology.routes.static") }
+                                            </span>
+                                        },
+                                        router::Origin::Learned => html! {
+                                            <span class="tag is-success" title=t!("experiment.topology.routes.learned.description")>
+                                                { t!("experiment.topology.routes.learned") }
+                                            </span>
+                                        },
+                                    }
+                                } </td>
+                            </tr>
+                        }).collect::<Html>()
+                    } </tbody>
+                </table>
+            },
+        };
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-half-desktop is-third-widescreen is-one-quarter-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("experiment.topology") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("experiment.topology.description") }</p>
+                            <p>{ status }</p>
+                            <p>{ range_status }</p>
+                            { dropped }
+                            { routes }
+                            <div class="field">
+                                <div class="control">
+                                    <textarea class="textarea is-family-monospace" rows="6" value=self.topology.clone()
+                                        placeholder="pipuck1: pipuck2 pipuck3" oninput=oninput />
+                                </div> {
+                                    match &self.topology_error {
+                                        Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                        None => html! {},
+                                    }
+                                }
+                            </div>
+                            <p class="help">{ t!("experiment.topology.range.description") }</p>
+                            <div class="field has-addons">
+                                <div class="control is-expanded">
+                                    <input class="input" type="number" min="0" step="0.1" value=self.range.clone()
+                                        placeholder=t!("experiment.topology.range") oninput=range_oninput />
+                                </div>
+                                <div class="control">
+                                    <button class="button" disabled=!permitted
+                                        onclick=self.link.callback(|_| Msg::ApplyRange)>{ t!("experiment.topology.apply") }</button>
+                                </div>
+                            </div> {
+                                match &self.range_error {
+                                    Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                    </div>
+                    <input id="experiment_upload_topology" class="is-hidden" type="file" onchange=onchange />
+                    <footer class="card-footer"> {
+                        match permitted {
+                            true => html! {
+                                <>
+                                    <label class="card-footer-item" for="experiment_upload_topology">{ t!("experiment.topology.upload") }</label>
+                                    <a class="card-footer-item" onclick=self.link.callback(|_| Msg::ApplyTopology)>{ t!("experiment.topology.apply") }</a>
+                                    <a class="card-footer-item" onclick=self.link.callback(|_| Msg::ClearTopology)>{ t!("experiment.topology.clear") }</a>
+                                </>
+                            },
+                            false => html! {
+                                <span class="card-footer-item has-text-grey-light"
+                                      title=t!("access.forbidden")>{ t!("experiment.topology.apply") }</span>
+                            },
+                        }
+                    } </footer>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_journal(&self) -> Html {
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("experiment.journal") }</p>
+                        </div> {
+                            match self.props.journal_statistics.as_ref().map_or(false, |statistics| statistics.encrypted) {
+                                true => html! {
+                                    <div class="level-right">
+                                        <span class="level-item tag is-success" title=t!("experiment.journal.encrypted.description")>
+                                            { t!("experiment.journal.encrypted") }
+                                        </span>
+                                    </div>
+                                },
+                                false => html! {},
+                            }
+                        }
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content"> {
+                        match &self.props.journal_statistics {
+                            None => html! {
+                                <p>{ t!("experiment.journal.none") }</p>
+                            },
+                            Some(statistics) => html! {
+                                <table class="table is-bordered is-hoverable">
+                                    <thead>
+                                        <tr>
+                                            <th>{ t!("experiment.journal.events") }</th>
+                                            <th>{ t!("experiment.journal.count") }</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody> {
+                                        statistics.events.iter()
+                                            .map(|(category, count)| html! {
+                                                <tr>
+                                                    <td>{ category }</td>
+                                                    <td>{ count }</td>
+                                                </tr>
+                                            }).collect::<Html>()
+                                    } </tbody>
+                                    <tfoot>
+                                        <tr>
+                                            <th>{ t!("experiment.journal.size") }</th>
+                                            <th>{ format_bytes(statistics.file_size) }</th>
+                                        </tr>
+                                        <tr>
+                                            <th>{ t!("experiment.journal.disk") }</th>
+                                            <th> {
+                                                statistics.disk_available
+                                                    .map_or_else(|| t!("common.unknown"), format_bytes)
+                                            } </th>
+                                        </tr>
+                                        <tr>
+                                            <th>{ t!("experiment.journal.tracking") }</th>
+                                            <th> {
+                                                t!("experiment.journal.tracking.gaps",
+                                                    dropped = statistics.tracking.dropped,
+                                                    frames = statistics.tracking.frames,
+                                                    gaps = statistics.tracking.gaps,
+                                                    longest = statistics.tracking.longest)
+                                            } </th>
+                                        </tr>
+                                    </tfoot>
+                                </table>
+                            }
+                        }
+                    }
+                    { self.render_fingerprint() }
+                    </div>
+                </div>
+                { self.render_fingerprint_menu() }
+            </div>
+        }
+    }
+
+    fn render_fingerprint(&self) -> Html {
+        let fingerprint = match self.props.journal_statistics.as_ref()
+            .and_then(|statistics| statistics.fingerprint.as_ref()) {
+            Some(fingerprint) => fingerprint,
+            None => return html! {},
+        };
+        let comparison = match &self.reference {
+            None => html! {},
+            Some(Err(error)) => html! {
+                <p class="has-text-danger">{ error }</p>
+            },
+            Some(Ok((name, reference))) => match &fingerprint.differences(reference)[..] {
+                [] => html! {
+                    <p class="has-text-success">{ t!("experiment.fingerprint.identical", name = name) }</p>
+                },
+                differences => html! {
+                    <p class="has-text-warning-dark">
+                        { t!("experiment.fingerprint.differs", name = name, differences = differences.join(", ")) }
+                    </p>
+                }
+            }
+        };
+        html! {
+            <>
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("experiment.fingerprint") }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item is-family-monospace"
+                           title=fingerprint.components.iter()
+                               .map(|(component, digest)| format!("{}: {}", component, digest))
+                               .collect::<Vec<_>>()
+                               .join("\n")>
+                            { fingerprint.digest() }
+                        </p>
+                    </div>
+                </nav>
+                { comparison }
+            </>
+        }
+    }
+
+    fn render_fingerprint_menu(&self) -> Html {
+        let compare_onchange = self.link.callback(move |value| {
+            let mut result = Vec::new();
+            if let ChangeData::Files(files) = value {
+                let files = js_sys::try_iter(&files)
+                    .unwrap()
+                    .unwrap()
+                    .map(|v| File::from(v.unwrap()));
+                result.extend(files);
+            }
+            Msg::ReadReference(result)
+        });
+        html! {
+            <>
+                <input id="journal_compare_fingerprint" class="is-hidden" type="file" accept=".json" onchange=compare_onchange />
+                <footer class="card-footer">
+                    <label class="card-footer-item" for="journal_compare_fingerprint">{ t!("experiment.fingerprint.compare") }</label>
+                </footer>
+            </>
+        }
+    }
+
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    yew::utils::window().local_storage().ok().flatten()
+}
+
+/// Whether the operator chose to continue an experiment that could only be started on some of the robots
+pub fn partial_start() -> PartialStart {
+    let partial_start = storage()
+        .and_then(|storage| storage.get_item(PARTIAL_START_STORAGE_KEY).ok().flatten());
+    match partial_start.as_deref() {
+        Some("Continue") => PartialStart::Continue,
+        _ => PartialStart::Rollback,
+    }
+}
+
+/// Renders the outcome of a smoke test, the measured values of all checks are shown in the tooltip and the
+/// checks that failed are listed below the outcome
+fn render_readiness(readiness: &Readiness) -> Html {
+    match readiness {
+        Readiness::Pending => html! {
+            <span class="tag">{ t!("experiment.status.robot.pending") }</span>
+        },
+        Readiness::Uploading => html! {
+            <span class="tag is-info">{ t!("experiment.status.robot.uploading") }</span>
+        },
+        Readiness::Ready => html! {
+            <span class="tag is-info">{ t!("experiment.status.robot.ready") }</span>
+        },
+        Readiness::Running => html! {
+            <span class="tag is-success">{ t!("experiment.status.robot.running") }</span>
+        },
+        Readiness::Failed(error) => html! {
+            <>
+                <span class="tag is-danger">{ t!("experiment.status.robot.failed") }</span>
+                <p class="help is-danger">{ error }</p>
+            </>
+        },
+    }
+}
+
+fn render_smoke_test(smoke_test: Option<&SmokeTest>) -> Html {
+    let smoke_test = match smoke_test {
+        Some(smoke_test) => smoke_test,
+        None => return html! {
+            <span class="tag">{ t!("experiment.smoke_test.untested") }</span>
+        },
+    };
+    match &smoke_test.result {
+        Err(error) => html! {
+            <>
+                <span class="tag is-danger" title=smoke_test.time.clone()>{ t!("experiment.smoke_test.error") }</span>
+                <p class="help">{ error }</p>
+            </>
+        },
+        Ok(checks) => {
+            let summary = checks.iter()
+                .map(|check| format!("{}: {}", check.name, check.value))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let (class, label) = match smoke_test.passed() {
+                true => ("is-success", t!("experiment.smoke_test.passed")),
+                false => ("is-danger", t!("experiment.smoke_test.failed")),
+            };
+            html! {
+                <>
+                    <span class=classes!("tag", class) title=format!("{}\n{}", smoke_test.time, summary)>{ label }</span> {
+                        checks.iter().filter(|check| !check.passed).map(|check| html! {
+                            <p class="help is-danger">{ format!("{}: {}", check.name, check.value) }</p>
+                        }).collect::<Html>()
+                    } {
+                        /* the throughput is also shown when it passed, since it quantifies the capacity of the wireless network */
+                        checks.iter().filter(|check| check.passed && check.name.starts_with("Throughput")).map(|check| html! {
+                            <p class="help">{ format!("{}: {}", check.name, check.value) }</p>
+                        }).collect::<Html>()
+                    }
+                </>
+            }
+        }
+    }
+}
+
+/// Formats a number of bytes using binary prefixes
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
 }
\ No newline at end of file
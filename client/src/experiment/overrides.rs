@@ -0,0 +1,184 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+
+use shared::experiment::software::Software;
+
+/// The software for individual robots, e.g., a drone that runs a different Lua script than the other drones.
+/// The files of an override replace the software for the robot type of that robot
+pub struct ConfigCard {
+    link: ComponentLink<Self>,
+    props: Props,
+    /* the robot whose software is overridden when files are added */
+    robot: String,
+    tasks: HashMap<String, ReaderTask>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub overrides: Rc<RefCell<BTreeMap<String, Software>>>,
+}
+
+pub enum Msg {
+    SetRobot(String),
+    ReadSoftware(Vec<File>),
+    AddSoftware(String, String, Vec<u8>),
+    RemoveOverride(String),
+    ClearOverrides,
+}
+
+impl Component for ConfigCard {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        ConfigCard {
+            props,
+            link,
+            robot: String::new(),
+            tasks: Default::default(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::SetRobot(robot) => {
+                self.robot = robot;
+                return false;
+            },
+            Msg::ReadSoftware(files) => {
+                let robot = self.robot.trim().to_owned();
+                if robot.is_empty() {
+                    return false;
+                }
+                let link = self.link.clone();
+                let tasks = files.into_iter()
+                    .filter_map(move |file| {
+                        let filename = file.name();
+                        let robot = robot.clone();
+                        let callback = link.callback(move |FileData {name, content}|
+                            Msg::AddSoftware(robot.clone(), name, content));
+                        match ReaderService::read_file(file, callback) {
+                            Ok(task) => Some((filename, task)),
+                            Err(_) => None,
+                        }
+                    });
+                self.tasks.extend(tasks);
+            },
+            Msg::AddSoftware(robot, name, content) =>
+                self.props.overrides.borrow_mut().entry(robot).or_default().add(name, content),
+            Msg::RemoveOverride(robot) => {
+                self.props.overrides.borrow_mut().remove(&robot);
+            },
+            Msg::ClearOverrides =>
+                self.props.overrides.borrow_mut().clear(),
+        }
+        true
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        true
+    }
+
+    fn view(&self) -> Html {
+        let robot_oninput = self.link.callback(|data: InputData| Msg::SetRobot(data.value));
+        let add_onchange = self.link.callback(move |value| {
+            let mut result = Vec::new();
+            if let ChangeData::Files(files) = value {
+                let files = js_sys::try_iter(&files)
+                    .unwrap()
+                    .unwrap()
+                    .map(|v| File::from(v.unwrap()));
+                result.extend(files);
+            }
+            Msg::ReadSoftware(result)
+        });
+        let clear_onclick = self.link.callback(|_| Msg::ClearOverrides);
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("experiment.overrides") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <div class="field">
+                            <label class="label">{ t!("experiment.overrides.robot") }</label>
+                            <div class="control">
+                                <input class="input" type="text" value=self.robot.clone() oninput=robot_oninput />
+                            </div>
+                            <p class="help">{ t!("experiment.overrides.description") }</p>
+                        </div>
+                        { self.render_overrides() }
+                    </div>
+                </div>
+                <input id="override_add_software" class="is-hidden" type="file" multiple=true onchange=add_onchange />
+                <footer class="card-footer">
+                    <label class="card-footer-item" for="override_add_software">{ t!("experiment.software.add") }</label>
+                    <a class="card-footer-item" onclick=clear_onclick>{ t!("experiment.software.clear") }</a>
+                </footer>
+            </div>
+        }
+    }
+}
+
+impl ConfigCard {
+    fn render_overrides(&self) -> Html {
+        let overrides = self.props.overrides.borrow();
+        if overrides.is_empty() {
+            return html! { <p>{ t!("experiment.overrides.none") }</p> };
+        }
+        html! {
+            <table class="table is-bordered is-hoverable">
+                <thead>
+                    <tr>
+                        <th>{ t!("experiment.overrides.robot") }</th>
+                        <th>{ t!("experiment.software.file") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody> {
+                    overrides.iter().map(|(robot, software)| {
+                        let remove_robot = robot.clone();
+                        let remove_onclick = self.link.callback(move |_| Msg::RemoveOverride(remove_robot.clone()));
+                        let files = software.0.iter()
+                            .map(|(filename, _)| filename.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let problems = software.validate();
+                        html! {
+                            <tr>
+                                <td>{ robot }</td>
+                                <td> {
+                                    match problems.is_empty() {
+                                        true => html! { { files } },
+                                        false => html! {
+                                            <>
+                                                <span class="has-text-danger">{ files }</span> {
+                                                    problems.iter()
+                                                        .map(|(_, error)| html! { <p class="help is-danger">{ error.to_string() }</p> })
+                                                        .collect::<Html>()
+                                                }
+                                            </>
+                                        },
+                                    }
+                                } </td>
+                                <td>
+                                    <button class="delete" onclick=remove_onclick />
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                } </tbody>
+            </table>
+        }
+    }
+}
@@ -7,23 +7,40 @@ use yew::prelude::*;
 use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
 use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
 
-use shared::experiment::{software::Software};
+use shared::{BackEndRequest, access::Category, experiment::{library::{Bundle, Reference, Request}, software::{Repository, Software, Source}}};
+
+use crate::{UserInterface, access};
 
 pub struct ConfigCard {
     link: ComponentLink<Self>,
     props: Props,
     tasks: HashMap<String, ReaderTask>,
+    /* the name under which the files are stored in the library and the error from storing or deleting a bundle */
+    bundle_name: String,
+    library_error: Option<String>,
 }
 
 #[derive(Clone, Properties)]
 pub struct Props {
-    pub software: Rc<RefCell<Software>>,
+    pub parent: ComponentLink<UserInterface>,
+    pub software: Rc<RefCell<Source>>,
+    /* the bundles in the library of the supervisor */
+    pub library: Vec<Bundle>,
 }
 
 pub enum Msg {
     ClearSoftware,
     AddSoftware(String, Vec<u8>),
     ReadSoftware(Vec<File>),
+    UseRepository,
+    SetRepositoryUrl(String),
+    SetRepositoryReference(String),
+    SetRepositoryDirectory(String),
+    UseBundle(Option<Reference>),
+    SetBundleName(String),
+    StoreBundle,
+    DeleteBundle(Reference),
+    SetLibraryResult(Result<(), String>),
 }
 
 // is it possible to just add a callback to the update method
@@ -37,6 +54,8 @@ impl Component for ConfigCard {
             props,
             link,
             tasks: Default::default(),
+            bundle_name: String::new(),
+            library_error: None,
         }
     }
 
@@ -57,15 +76,46 @@ impl Component for ConfigCard {
                 self.tasks.extend(tasks);
             },
             Msg::ClearSoftware =>
-                self.props.software.borrow_mut().clear(),
+                *self.props.software.borrow_mut() = Source::default(),
             Msg::AddSoftware(name, content) =>
-                self.props.software.borrow_mut().add(name, content),
+                self.props.software.borrow_mut().files().add(name, content),
+            Msg::UseRepository => {
+                self.props.software.borrow_mut().repository();
+            },
+            Msg::SetRepositoryUrl(url) =>
+                self.props.software.borrow_mut().repository().url = url,
+            Msg::SetRepositoryReference(reference) =>
+                self.props.software.borrow_mut().repository().reference = reference,
+            Msg::SetRepositoryDirectory(directory) =>
+                self.props.software.borrow_mut().repository().directory = directory,
+            Msg::UseBundle(Some(reference)) =>
+                *self.props.software.borrow_mut() = Source::Library(reference),
+            Msg::UseBundle(None) =>
+                *self.props.software.borrow_mut() = Source::default(),
+            Msg::SetBundleName(name) => self.bundle_name = name,
+            Msg::StoreBundle => {
+                let software = match &*self.props.software.borrow() {
+                    Source::Files(software) => Some(software.clone()),
+                    _ => None,
+                };
+                if let Some(software) = software {
+                    self.send(Request::Store(self.bundle_name.trim().to_owned(), software));
+                }
+            },
+            Msg::DeleteBundle(reference) => self.send(Request::Delete(reference)),
+            Msg::SetLibraryResult(result) => {
+                if result.is_ok() {
+                    self.bundle_name.clear();
+                }
+                self.library_error = result.err();
+            },
         }
         true
     }
 
     // this fires when the parent changes the properties of this component
-    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
         true
     }
 
@@ -78,13 +128,14 @@ impl Component for ConfigCard {
                 <header class="card-header">
                     <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
                         <div class="level-left">
-                            <p class="level-item subtitle is-size-4">{ "Pi-Puck Configuration" }</p>
+                            <p class="level-item subtitle is-size-4">{ t!("experiment.software.pipuck") }</p>
                         </div>
                     </nav>
                 </header>
                 <div class="card-content">
                     <div class="content">
                         { self.render_config() }
+                        { self.render_library() }
                     </div>
                 </div>
                 { self.render_menu() }
@@ -94,25 +145,41 @@ impl Component for ConfigCard {
 }
 
 impl ConfigCard {
+    fn send(&mut self, request: Request) {
+        let callback = self.link.callback(Msg::SetLibraryResult);
+        let request = BackEndRequest::LibraryRequest(request);
+        self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+        self.library_error = None;
+    }
+
     fn render_config(&self) -> Html {
+        match &*self.props.software.borrow() {
+            Source::Files(software) => self.render_files(software),
+            Source::Repository(repository) => self.render_repository(repository),
+            Source::Library(reference) => self.render_bundle(reference),
+        }
+    }
+
+    fn render_files(&self, software: &Software) -> Html {
+        let problems = software.validate();
         html! {
             <>
                 <nav class="level is-mobile">
                     <div class="level-left">
-                        <p class="level-item">{ "Control software" }</p>
+                        <p class="level-item">{ t!("experiment.software") }</p>
                     </div>
                     <div class="level-right"> {
-                        match &self.props.software.borrow().check_config() {
-                            Ok(_) => html! {
+                        match problems.len() {
+                            0 => html! {
                                 <span class="level-item">
                                     <span class="icon is-medium">
                                         <i class="mdi mdi-24px mdi-check has-text-success"/>
                                     </span>
                                 </span>
                             },
-                            Err(error) => html! {
+                            count => html! {
                                 <span class="level-item">
-                                    { error }
+                                    { t!("experiment.software.problems", count = count) }
                                     <span class="icon is-medium">
                                         <i class="mdi mdi-24px mdi-close has-text-danger" />
                                     </span>
@@ -121,19 +188,32 @@ impl ConfigCard {
                         }
                     } </div>
                 </nav>
-                
+                {
+                    /* the problems that do not concern a single file, e.g., a missing configuration file */
+                    problems.iter()
+                        .filter(|(filename, _)| filename.is_none())
+                        .map(|(_, error)| html! { <p class="help is-danger">{ error.to_string() }</p> })
+                        .collect::<Html>()
+                }
                 <table class="table is-bordered is-hoverable">
                     <thead>
                         <tr>
-                            <th>{ "File" }</th>
-                            <th>{ "Checksum" }</th>
+                            <th>{ t!("experiment.software.file") }</th>
+                            <th>{ t!("experiment.software.checksum") }</th>
                         </tr>
                     </thead>
                     <tbody> {
-                        self.props.software.borrow().checksums().iter()
+                        software.checksums().iter()
                             .map(|(name, checksum)| html! {
                                 <tr>
-                                    <td> { name } </td>
+                                    <td>
+                                        { name } {
+                                            problems.iter()
+                                                .filter(|(filename, _)| filename.as_ref() == Some(name))
+                                                .map(|(_, error)| html! { <p class="help is-danger">{ error.to_string() }</p> })
+                                                .collect::<Html>()
+                                        }
+                                    </td>
                                     <td> { format!("{:x}", checksum) } </td>
                                 </tr>
                             }).collect::<Html>()
@@ -143,8 +223,148 @@ impl ConfigCard {
         }
     }
 
+    fn render_bundle(&self, reference: &Reference) -> Html {
+        let bundle = match self.props.library.iter().find(|bundle| bundle.reference() == *reference) {
+            Some(bundle) => bundle,
+            None => return html! {
+                <p class="help is-danger">{ t!("experiment.software.library.missing", bundle = reference) }</p>
+            },
+        };
+        let stored = String::from(js_sys::Date::new(&bundle.stored.as_str().into()).to_locale_string("default", &Default::default()));
+        let reference = reference.clone();
+        let delete_onclick = self.link.callback(move |_| Msg::DeleteBundle(reference.clone()));
+        html! {
+            <>
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ t!("experiment.software.library.bundle", name = bundle.name, version = bundle.version) }</p>
+                    </div>
+                    <div class="level-right">
+                        <button class="level-item button is-small is-danger is-outlined"
+                                disabled=!access::permitted(Category::Experiment)
+                                onclick=delete_onclick>{ t!("experiment.software.library.delete") }</button>
+                    </div>
+                </nav>
+                <p class="help">{ t!("experiment.software.library.stored", time = stored) }</p>
+                <table class="table is-bordered is-hoverable">
+                    <thead>
+                        <tr>
+                            <th>{ t!("experiment.software.file") }</th>
+                            <th>{ t!("experiment.software.checksum") }</th>
+                        </tr>
+                    </thead>
+                    <tbody> {
+                        bundle.checksums.iter()
+                            .map(|(name, checksum)| html! {
+                                <tr>
+                                    <td>{ name }</td>
+                                    <td>{ checksum }</td>
+                                </tr>
+                            }).collect::<Html>()
+                    } </tbody>
+                </table>
+            </>
+        }
+    }
+
+    /// The bundles in the library that can be selected instead of uploading the software, and the name
+    /// under which the selected files are stored in the library
+    fn render_library(&self) -> Html {
+        let permitted = access::permitted(Category::Experiment);
+        let source = self.props.software.borrow();
+        let selected = match &*source {
+            Source::Library(reference) => Some(reference),
+            _ => None,
+        };
+        let library = self.props.library.clone();
+        let select_onchange = self.link.callback(move |data| match data {
+            ChangeData::Select(select) => Msg::UseBundle(select.value().parse::<usize>().ok()
+                .and_then(|index| library.get(index))
+                .map(Bundle::reference)),
+            _ => Msg::UseBundle(None),
+        });
+        let name_oninput = self.link.callback(|data: InputData| Msg::SetBundleName(data.value));
+        let store_onclick = self.link.callback(|_| Msg::StoreBundle);
+        html! {
+            <>
+                <div class="field">
+                    <label class="label">{ t!("experiment.software.library") }</label>
+                    <div class="control">
+                        <div class="select is-fullwidth">
+                            <select onchange=select_onchange disabled=self.props.library.is_empty()>
+                                <option value="" selected=selected.is_none()>{ t!("experiment.software.library.none") }</option> {
+                                    self.props.library.iter().enumerate().map(|(index, bundle)| html! {
+                                        <option value=index.to_string() selected=selected == Some(&bundle.reference())>
+                                            { t!("experiment.software.library.bundle", name = bundle.name, version = bundle.version) }
+                                        </option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                        </div>
+                    </div>
+                </div>
+                {
+                    match &*source {
+                        Source::Files(software) if !software.0.is_empty() => html! {
+                            <div class="field has-addons">
+                                <div class="control is-expanded">
+                                    <input class="input" type="text"
+                                           placeholder=t!("experiment.software.library.name")
+                                           value=self.bundle_name.clone()
+                                           oninput=name_oninput />
+                                </div>
+                                <div class="control">
+                                    <button class="button"
+                                            disabled=!permitted || self.bundle_name.trim().is_empty() || !software.validate().is_empty()
+                                            onclick=store_onclick>{ t!("experiment.software.library.store") }</button>
+                                </div>
+                            </div>
+                        },
+                        _ => html! {},
+                    }
+                }
+                {
+                    match &self.library_error {
+                        Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                        None => html! {},
+                    }
+                }
+            </>
+        }
+    }
+
+    fn render_repository(&self, repository: &Repository) -> Html {
+        let url_oninput = self.link.callback(|data: InputData| Msg::SetRepositoryUrl(data.value));
+        let reference_oninput = self.link.callback(|data: InputData| Msg::SetRepositoryReference(data.value));
+        let directory_oninput = self.link.callback(|data: InputData| Msg::SetRepositoryDirectory(data.value));
+        html! {
+            <>
+                <div class="field">
+                    <label class="label">{ t!("experiment.software.repository.url") }</label>
+                    <div class="control">
+                        <input class="input" type="text" value=repository.url.clone() oninput=url_oninput />
+                    </div>
+                </div>
+                <div class="field">
+                    <label class="label">{ t!("experiment.software.repository.reference") }</label>
+                    <div class="control">
+                        <input class="input" type="text" value=repository.reference.clone() oninput=reference_oninput />
+                    </div>
+                </div>
+                <div class="field">
+                    <label class="label">{ t!("experiment.software.repository.directory") }</label>
+                    <div class="control">
+                        <input class="input" type="text" value=repository.directory.clone() oninput=directory_oninput />
+                    </div>
+                    <p class="help">{ t!("experiment.software.repository.description") }</p>
+                </div>
+            </>
+        }
+    }
+
     fn render_menu(&self) -> Html {
         let clear_onclick = self.link.callback(|_| Msg::ClearSoftware);
+        let repository_onclick = self.link.callback(|_| Msg::UseRepository);
         let add_onchange = self.link.callback(move |value| {
             let mut result = Vec::new();
             if let ChangeData::Files(files) = value {
@@ -160,8 +380,9 @@ impl ConfigCard {
             <>
                 <input id="pipuck_add_software" class="is-hidden" type="file" multiple=true onchange=add_onchange />
                 <footer class="card-footer">
-                    <label class="card-footer-item" for="pipuck_add_software">{ "Add" }</label>
-                    <a class="card-footer-item" onclick=clear_onclick>{ "Clear" }</a>
+                    <label class="card-footer-item" for="pipuck_add_software">{ t!("experiment.software.add") }</label>
+                    <a class="card-footer-item" onclick=repository_onclick>{ t!("experiment.software.repository") }</a>
+                    <a class="card-footer-item" onclick=clear_onclick>{ t!("experiment.software.clear") }</a>
                 </footer>
             </>
         }
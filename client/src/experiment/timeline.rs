@@ -0,0 +1,323 @@
+use std::collections::BTreeSet;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+
+use shared::experiment::{Request, TimelineCategory, TimelineEvent};
+use shared::BackEndRequest;
+
+use crate::UserInterface;
+
+pub struct Card {
+    link: ComponentLink<Self>,
+    props: Props,
+    annotation_input: NodeRef,
+    event_list: NodeRef,
+    /* the event that was clicked on the timeline, the list follows the latest event if none */
+    selected: Option<usize>,
+    /* the tags from the palette that are attached to the next annotation */
+    tags: BTreeSet<String>,
+    /* only the events with this tag are shown if set */
+    filter: Option<String>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub parent: ComponentLink<UserInterface>,
+    pub events: Vec<TimelineEvent>,
+    /* the tag palette from the configuration */
+    pub tags: Vec<String>,
+}
+
+pub enum Msg {
+    Select(Option<usize>),
+    Annotate,
+    ToggleTag(String),
+    Filter(Option<String>),
+}
+
+impl Component for Card {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Card {
+            link,
+            props,
+            annotation_input: NodeRef::default(),
+            event_list: NodeRef::default(),
+            selected: None,
+            tags: BTreeSet::new(),
+            filter: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Select(selected) => {
+                self.selected = selected;
+                true
+            },
+            Msg::Annotate => match self.annotation_input.cast::<HtmlInputElement>() {
+                Some(input) if !input.value().trim().is_empty() => {
+                    let tags = std::mem::take(&mut self.tags).into_iter().collect();
+                    let request = BackEndRequest::ExperimentRequest(Request::Annotate { note: input.value(), tags });
+                    input.set_value("");
+                    self.props.parent.send_message(crate::Msg::SendRequest(request, None));
+                    true
+                },
+                _ => false
+            },
+            Msg::ToggleTag(tag) => {
+                if !self.tags.remove(&tag) {
+                    self.tags.insert(tag);
+                }
+                true
+            },
+            Msg::Filter(filter) => {
+                self.filter = filter;
+                self.selected = None;
+                true
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        /* a new experiment was started, the previous selection no longer applies */
+        if props.events.len() < self.props.events.len() {
+            self.selected = None;
+        }
+        /* the tags that were removed from the palette can no longer be attached */
+        self.tags.retain(|tag| props.tags.contains(tag));
+        self.props = props;
+        true
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        match self.selected {
+            Some(index) => {
+                let id = format!("timeline-event-{}", index);
+                if let Some(element) = yew::utils::document().get_element_by_id(&id) {
+                    element.scroll_into_view();
+                }
+            },
+            None => if let Some(list) = self.event_list.cast::<web_sys::Element>() {
+                list.set_scroll_top(list.scroll_height());
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let annotate_onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
+            "Enter" => Some(Msg::Annotate),
+            _ => None,
+        });
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("timeline.title") }</p>
+                        </div>
+                        <div class="level-right">
+                            <div class="level-item">
+                                { render_filter(&self.props.events, &self.props.tags, &self.filter,
+                                    self.link.callback(Msg::Filter)) }
+                            </div>
+                            <button class="level-item button"
+                                    disabled=self.selected.is_none()
+                                    onclick=self.link.callback(|_| Msg::Select(None))>{ t!("timeline.follow") }</button>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        { self.render_axis() }
+                        { self.render_events() }
+                        { self.render_palette() }
+                        <div class="field">
+                            <div class="control">
+                                <input ref=self.annotation_input.clone()
+                                       class="input"
+                                       type="text"
+                                       placeholder=t!("timeline.placeholder")
+                                       onkeydown=annotate_onkeydown />
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Card {
+    fn render_axis(&self) -> Html {
+        /* the axis spans from the start of the journal to the latest event */
+        let duration = self.props.events.iter()
+            .map(|event| event.timestamp)
+            .max()
+            .unwrap_or_default()
+            .max(1) as f64;
+        html! {
+            <div class="mb-4">
+                <div style="position:relative;height:32px;border-bottom:2px solid #dbdbdb"> {
+                    self.visible().map(|(index, event)| {
+                        let left = 100.0 * event.timestamp as f64 / duration;
+                        let mut classes = classes!("tag", "is-rounded", category_class(event.category));
+                        if self.selected == Some(index) {
+                            classes.push("is-medium");
+                        }
+                        html! {
+                            <span class=classes
+                                  style=format!("position:absolute;left:{:.2}%;top:4px;transform:translateX(-50%);cursor:pointer", left)
+                                  title=format!("{} {}", format_timestamp(event.timestamp), event.description)
+                                  onclick=self.link.callback(move |_| Msg::Select(Some(index)))>
+                            </span>
+                        }
+                    }).collect::<Html>()
+                } </div>
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item is-size-7">{ format_timestamp(0) }</p>
+                    </div>
+                    <div class="level-right">
+                        <p class="level-item is-size-7">{ format_timestamp(duration as i64) }</p>
+                    </div>
+                </nav>
+            </div>
+        }
+    }
+
+    fn render_events(&self) -> Html {
+        html! {
+            <div ref=self.event_list.clone() style="max-height:240px;overflow-y:auto" class="mb-4">
+                <table class="table is-fullwidth is-hoverable">
+                    <tbody> {
+                        match self.props.events.len() {
+                            0 => html! {
+                                <tr><td>{ t!("timeline.none") }</td></tr>
+                            },
+                            _ => self.visible().map(|(index, event)| {
+                                let mut classes = classes!();
+                                if self.selected == Some(index) {
+                                    classes.push("is-selected");
+                                }
+                                html! {
+                                    <tr id=format!("timeline-event-{}", index)
+                                        class=classes
+                                        onclick=self.link.callback(move |_| Msg::Select(Some(index)))>
+                                        <td class="is-family-monospace">{ format_timestamp(event.timestamp) }</td>
+                                        <td>
+                                            <span class=classes!("tag", category_class(event.category))>
+                                                { category_name(event.category) }
+                                            </span>
+                                        </td>
+                                        <td>{ &event.description }{ render_tags(&event.tags) }</td>
+                                    </tr>
+                                }
+                            }).collect::<Html>()
+                        }
+                    } </tbody>
+                </table>
+            </div>
+        }
+    }
+
+    /// The events that pass the filter together with their index on the whole timeline
+    fn visible(&self) -> impl Iterator<Item = (usize, &TimelineEvent)> {
+        let filter = self.filter.clone();
+        self.props.events.iter()
+            .enumerate()
+            .filter(move |(_, event)| has_tag(event, filter.as_deref()))
+    }
+
+    /// The tags of the palette, the selected tags are attached to the next annotation
+    fn render_palette(&self) -> Html {
+        if self.props.tags.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="tags mb-2"> {
+                self.props.tags.iter().map(|tag| {
+                    let mut classes = classes!("tag", "is-rounded");
+                    if self.tags.contains(tag) {
+                        classes.push("is-primary");
+                    }
+                    let toggle = tag.clone();
+                    html! {
+                        <a class=classes onclick=self.link.callback(move |_| Msg::ToggleTag(toggle.clone()))>
+                            { tag }
+                        </a>
+                    }
+                }).collect::<Html>()
+            } </div>
+        }
+    }
+}
+
+/// Whether an event is shown when the timeline is filtered by the given tag
+pub fn has_tag(event: &TimelineEvent, filter: Option<&str>) -> bool {
+    filter.map_or(true, |filter| event.tags.iter().any(|tag| tag == filter))
+}
+
+pub fn render_tags(tags: &[String]) -> Html {
+    tags.iter().map(|tag| html! {
+        <span class="tag is-light ml-1">{ tag }</span>
+    }).collect::<Html>()
+}
+
+/// Selects the tag by which the timeline is filtered among the tags of the palette and the tags of the
+/// recorded events, e.g., the tags of the runtime events or of an older palette
+pub fn render_filter(
+    events: &[TimelineEvent],
+    palette: &[String],
+    filter: &Option<String>,
+    callback: Callback<Option<String>>,
+) -> Html {
+    let tags = palette.iter()
+        .chain(events.iter().flat_map(|event| event.tags.iter()))
+        .collect::<BTreeSet<_>>();
+    if tags.is_empty() {
+        return html! {};
+    }
+    let onchange = callback.reform(|data: ChangeData| match data {
+        ChangeData::Select(select) if !select.value().is_empty() => Some(select.value()),
+        _ => None,
+    });
+    html! {
+        <div class="select">
+            <select onchange=onchange>
+                <option value="" selected=filter.is_none()>{ t!("timeline.filter.all") }</option> {
+                    tags.into_iter().map(|tag| html! {
+                        <option value=tag.clone() selected=filter.as_ref() == Some(tag)>{ tag }</option>
+                    }).collect::<Html>()
+                }
+            </select>
+        </div>
+    }
+}
+
+pub fn category_class(category: TimelineCategory) -> &'static str {
+    match category {
+        TimelineCategory::Phase => "is-info",
+        TimelineCategory::Safety => "is-danger",
+        TimelineCategory::Disconnect => "is-warning",
+        TimelineCategory::Annotation => "is-success",
+    }
+}
+
+pub fn category_name(category: TimelineCategory) -> String {
+    match category {
+        TimelineCategory::Phase => t!("timeline.category.phase"),
+        TimelineCategory::Safety => t!("timeline.category.safety"),
+        TimelineCategory::Disconnect => t!("timeline.category.disconnect"),
+        TimelineCategory::Annotation => t!("timeline.category.annotation"),
+    }
+}
+
+pub fn format_timestamp(milliseconds: i64) -> String {
+    let seconds = milliseconds / 1000;
+    format!("{:02}:{:02}:{:02}.{:01}", seconds / 3600, (seconds / 60) % 60, seconds % 60, (milliseconds % 1000) / 100)
+}
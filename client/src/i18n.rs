@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+/* the key under which the selected language is stored in the local storage of the browser */
+const STORAGE_KEY: &'static str = "supervisor.language";
+
+/* the catalogs that are compiled into the client, the first catalog is used for keys that are missing
+   from the other catalogs. A catalog is added by placing its file in client/locales and listing it here */
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.catalog")),
+    ("fr", include_str!("../locales/fr.catalog")),
+];
+
+/// Translates a key into the selected language, the arguments replace the `{name}` placeholders in
+/// the translated string, e.g., `t!("robot.uptime.robot", duration = uptime)`
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+struct Localizer {
+    language: &'static str,
+    catalog: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+thread_local! {
+    static LOCALIZER: Localizer = Localizer::new(&preferred_language());
+}
+
+impl Localizer {
+    fn new(language: &str) -> Self {
+        let &(language, source) = CATALOGS.iter()
+            .find(|(code, _)| *code == language)
+            .unwrap_or(&CATALOGS[0]);
+        Localizer {
+            language,
+            catalog: parse(source),
+            fallback: parse(CATALOGS[0].1),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&'static str> {
+        self.catalog.get(key).or_else(|| self.fallback.get(key)).copied()
+    }
+}
+
+/// Parses a catalog, i.e., lines of the form `key = value`. Empty lines and lines starting with `#`
+/// are ignored
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    yew::utils::window().local_storage().ok().flatten()
+}
+
+/// The language stored in the settings of the browser, otherwise the language of the browser if
+/// a catalog exists for it
+fn preferred_language() -> String {
+    storage()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .or_else(|| yew::utils::window().navigator().language()
+            .map(|language| language.split('-').next().unwrap_or_default().to_owned()))
+        .unwrap_or_default()
+}
+
+pub fn translate(key: &str, arguments: &[(&str, String)]) -> String {
+    LOCALIZER.with(|localizer| match localizer.get(key) {
+        Some(value) => arguments.iter().fold(value.to_owned(), |value, (name, argument)|
+            value.replace(&format!("{{{}}}", name), argument)),
+        None => key.to_owned(),
+    })
+}
+
+/// The code of the language that is currently used by the user interface
+pub fn language() -> &'static str {
+    LOCALIZER.with(|localizer| localizer.language)
+}
+
+/// The codes of the available languages and their names in their own language
+pub fn languages() -> Vec<(&'static str, &'static str)> {
+    CATALOGS.iter()
+        .map(|&(code, source)| (code, parse(source).get("language.name").copied().unwrap_or(code)))
+        .collect()
+}
+
+/// Stores the selected language in the settings of the browser, the user interface must be reloaded
+/// for the change to take effect
+pub fn set_language(language: &str) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(STORAGE_KEY, language);
+    }
+}
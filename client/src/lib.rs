@@ -1,53 +1,172 @@
-use std::{cell::RefCell, collections::HashMap, convert::AsRef, rc::Rc};
-use shared::experiment::software::Software;
-use shared::{DownMessage, UpMessage};
+use std::{cell::RefCell, collections::{BTreeMap, HashMap, VecDeque}, net::{IpAddr, Ipv4Addr, SocketAddr}, rc::Rc};
+use shared::experiment::{ChecklistItem, Energy, JournalStatistics, LogEntry, SmokeTest, software::{Software, Source}};
+use shared::{DownMessage, UpMessage, access::Category, ambient, association, configuration, identification::Identification, infrastructure, maintenance, router, trace::Trace, tracking_system::RigidBody};
 use strum::{EnumProperty, IntoEnumIterator};
 use strum_macros::{AsRefStr, EnumIter, EnumProperty};
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yew::services::keyboard::{KeyboardService, KeyListenerHandle};
+use yew::services::timeout::{TimeoutService, TimeoutTask};
 use yew::services::websocket::{WebSocketService, WebSocketStatus, WebSocketTask};
 use yew::services::ConsoleService;
 
+#[macro_use]
+mod i18n;
+
+mod access;
+mod argos;
 mod builderbot;
+mod camera;
+mod diagnostics;
 mod drone;
+mod event_log;
 mod pipuck;
 mod experiment;
+mod pairing;
+mod palette;
+mod quarantine;
+mod replay;
+mod ros;
+mod settings;
+mod simulation;
+mod swarm;
+mod telemetry;
+mod terminal;
 
 #[derive(AsRefStr, EnumProperty, EnumIter, Copy, Clone, PartialEq)]
 pub enum Tab {
-    #[strum(serialize = "BuilderBots", props(icon = "mdi-crop-square"))]
+    #[strum(serialize = "BuilderBots", props(label = "tab.builderbots", icon = "mdi-crop-square"))]
     BuilderBots,
-    #[strum(serialize = "Drones", props(icon = "mdi-quadcopter"))]
+    #[strum(serialize = "Drones", props(label = "tab.drones", icon = "mdi-quadcopter"))]
     Drones,
-    #[strum(serialize = "Pi-Pucks", props(icon = "mdi-circle-slice-8"))]
+    #[strum(serialize = "Pi-Pucks", props(label = "tab.pipucks", icon = "mdi-circle-slice-8"))]
     PiPucks,
-    #[strum(serialize = "Experiment", props(icon = "mdi-play"))]
+    #[strum(serialize = "Experiment", props(label = "tab.experiment", icon = "mdi-play"))]
     Experiment,
+    #[strum(serialize = "Settings", props(label = "tab.settings", icon = "mdi-cog"))]
+    Settings,
+    #[strum(serialize = "Diagnostics", props(label = "tab.diagnostics", icon = "mdi-speedometer"))]
+    Diagnostics,
+    #[strum(serialize = "Replay", props(label = "tab.replay", icon = "mdi-history"))]
+    Replay,
+    #[strum(serialize = "Quarantine", props(label = "tab.quarantine", icon = "mdi-shield-alert"))]
+    Quarantine,
+    /* the last tab so that hiding it does not change the shortcuts of the other tabs */
+    #[strum(serialize = "ROS robots", props(label = "tab.ros", icon = "mdi-robot"))]
+    RosRobots,
+}
+
+impl Tab {
+    /// The name of the tab in the language of the user interface
+    pub fn label(&self) -> String {
+        t!(self.get_str("label").unwrap())
+    }
 }
 
 pub struct UserInterface {
     link: ComponentLink<Self>,
     socket: Option<WebSocketTask>,
+    /* the address of the socket of the supervisor, including the token of the user */
+    service_addr: String,
+    /* the number of failed attempts to reconnect and the timeout before the next attempt */
+    reconnect_attempts: u32,
+    reconnect_task: Option<TimeoutTask>,
     active_tab: Tab,
     requests: HashMap<Uuid, Callback<Result<(), String>>>,
+    /* the most recent requests, their summaries, and their results, the identifiers of the requests are
+       used to look up their traces */
+    recent_requests: VecDeque<(Uuid, String, Option<Result<(), String>>)>,
+    trace_query: String,
+    trace: Option<Result<Trace, String>>,
+    /* the progress or the results of identifying the rigid bodies of the robots */
+    identification: Identification,
+    /* the Xbees and UpCores that are not assigned to any drone */
+    pairing: shared::pairing::Pairing,
+    /* the journal that is replayed in the replay tab */
+    replay: Rc<RefCell<replay::Replay>>,
     builderbots: HashMap<String, Rc<RefCell<builderbot::Instance>>>,
-    builderbot_software: Rc<RefCell<Software>>,
+    builderbot_software: Rc<RefCell<Source>>,
     builderbot_config_comp: Option<ComponentLink<experiment::builderbot::ConfigCard>>,
     drones: HashMap<String, Rc<RefCell<drone::Instance>>>,
-    drone_software: Rc<RefCell<Software>>,
+    drone_software: Rc<RefCell<Source>>,
     drone_config_comp: Option<ComponentLink<experiment::drone::ConfigCard>>,
     pipucks: HashMap<String, Rc<RefCell<pipuck::Instance>>>,
-    pipuck_software: Rc<RefCell<Software>>,
+    pipuck_software: Rc<RefCell<Source>>,
     pipuck_config_comp: Option<ComponentLink<experiment::pipuck::ConfigCard>>,
+    /* the software for individual robots, which replaces the software for their robot type */
+    software_overrides: Rc<RefCell<BTreeMap<String, Software>>>,
     control_config_comp: Option<ComponentLink<experiment::Interface>>,
+    journal_statistics: Option<JournalStatistics>,
+    /* the last entries of the live log of the experiment */
+    log: VecDeque<LogEntry>,
+    checklist: Vec<ChecklistItem>,
+    /* the last smoke test of each robot */
+    smoke_tests: BTreeMap<String, Option<SmokeTest>>,
+    /* the state of the experiment and the readiness of the participating robots */
+    status: shared::experiment::Status,
+    /* the simulated robots that participate in the experiment via the message router */
+    simulated_robots: Vec<shared::simulation::Robot>,
+    /* the ROS robots that are controlled via their rosbridge websockets */
+    ros_robots: BTreeMap<String, shared::ros::Robot>,
+    tracking_system_models: Vec<RigidBody>,
+    /* the latency of the updates from the tracking system */
+    tracking_system_latency: Rc<RefCell<diagnostics::Latency>>,
+    /* the stations and switch ports reported by the infrastructure monitor */
+    infrastructure: infrastructure::Update,
+    /* the most recent readings of the ambient sensors in the arena */
+    ambient: ambient::Update,
+    /* the messages forwarded by the message router between the peers that are connected to it */
+    router: router::Statistics,
+    /* the addresses at which a device could not be associated with a robot */
+    association_failures: BTreeMap<Ipv4Addr, association::Failure>,
+    /* the devices that did not match any robot and that have not been adopted or ignored */
+    quarantine: BTreeMap<macaddr::MacAddr6, association::UnknownDevice>,
+    /* the alerts about the robots that have not been dismissed, e.g., low batteries */
+    notifications: VecDeque<shared::alert::Notification>,
+    /* whether this supervisor is the primary or a standby supervisor, None if no standby is configured */
+    replication: Option<shared::replication::Role>,
+    /* the health of the backend subsystems that are restarted when they fail */
+    health: Vec<shared::health::Subsystem>,
+    /* the experiment profiles that are stored on the backend */
+    profiles: Vec<shared::experiment::profile::Profile>,
+    /* the bundles of control software that are stored in the library of the backend */
+    library: Vec<shared::experiment::library::Bundle>,
+    /* the paused addresses while maintenance mode is enabled */
+    maintenance: Option<Vec<Ipv4Addr>>,
+    maintenance_input: NodeRef,
+    maintenance_summary: Option<Vec<String>>,
+    configuration: Option<configuration::Configuration>,
+    configuration_restart_required: Vec<String>,
+    configuration_saved: Option<String>,
+    /* the user and the role of this client as authenticated by the supervisor */
+    access: Option<shared::access::Update>,
+    login_input: NodeRef,
+    login_error: Option<String>,
+    /* the clients that are connected to the supervisor, including this client */
+    sessions: Vec<shared::access::Session>,
+    /* keyboard shortcuts and the command palette */
+    _keyboard_listener: KeyListenerHandle,
+    palette: Option<palette::Palette>,
+    palette_input: NodeRef,
+    /* the id of the element to scroll into view after the next render */
+    focus: Option<String>,
 }
 
-
+/* the number of recent requests whose traces can be looked up from the diagnostics tab */
+const RECENT_REQUESTS: usize = 10;
+/* the number of alerts that are shown at once, older alerts are dismissed automatically */
+const NOTIFICATIONS: usize = 5;
+/* the number of entries of the live log of the experiment that are kept */
+const LOG_ENTRIES: usize = 500;
+/* the delay before reconnecting to the supervisor, which doubles with every failed attempt up to the maximum */
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+const RECONNECT_DELAY_MAX: std::time::Duration = std::time::Duration::from_secs(30);
 
 pub enum Msg {
-    // TODO: handle disconnects by matching against WebSocketStatus::Closed or WebSocketStatus::Error
     WebSocketNotifcation(WebSocketStatus),
+    Reconnect,
     WebSocketRxData(Result<Vec<u8>, anyhow::Error>),
     SetActiveTab(Tab),
     SendRequest(shared::BackEndRequest, Option<Callback<Result<(), String>>>),
@@ -55,6 +174,21 @@ pub enum Msg {
     SetDroneConfigComp(ComponentLink<experiment::drone::ConfigCard>),
     SetPiPuckConfigComp(ComponentLink<experiment::pipuck::ConfigCard>),
     SetControlConfigComp(ComponentLink<experiment::Interface>),
+    ToggleMaintenance,
+    DismissMaintenanceSummary,
+    DismissNotification(usize),
+    /* log in with the token in the login input or, if None, log out */
+    Login(bool),
+    SetLoginResult(Result<(), String>),
+    KeyDown(KeyboardEvent),
+    TogglePalette,
+    SetPaletteQuery(String),
+    ExecuteCommand(palette::Command),
+    SetTraceQuery(String),
+    ShowTrace,
+    RequestTrace(Uuid),
+    TraceFailed(String),
+    ApplyIdentification,
 }
 
 impl Component for UserInterface {
@@ -62,31 +196,40 @@ impl Component for UserInterface {
     type Properties = ();
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let service_addr = yew::utils::document()
+        let location = yew::utils::document()
             .location()
-            .unwrap()
-            .host()
             .unwrap();
-        let service_addr = format!("ws://{}/socket", service_addr);
-        let callback_data =
-            link.callback(|data| Msg::WebSocketRxData(data));
-        let callback_notification =
-            link.callback(|notification| Msg::WebSocketNotifcation(notification));
-        let socket =
-            WebSocketService::connect_binary(&service_addr,
-                                             callback_data,
-                                             callback_notification);
+        let search = location.search().unwrap_or_default();
+        let parameter = |name: &str| search.trim_start_matches('?')
+            .split('&')
+            .find_map(|parameter| parameter.strip_prefix(name)
+                .and_then(|parameter| parameter.strip_prefix('='))
+                .map(str::to_owned));
+        /* the desktop client serves this interface locally and passes the address of the supervisor */
+        let service_addr = parameter("supervisor")
+            .unwrap_or_else(|| location.host().unwrap());
+        /* the token that identifies the user is passed on to the supervisor */
+        let service_addr = match parameter("token") {
+            Some(token) => format!("ws://{}/socket?token={}", service_addr, token),
+            None => format!("ws://{}/socket", service_addr),
+        };
+        let socket = connect(&link, &service_addr);
+        let _keyboard_listener = KeyboardService::register_key_down(
+            &yew::utils::window(), link.callback(|event| Msg::KeyDown(event)));
         Self {
             link,
-            socket: match socket {
-                Ok(socket) => Some(socket),
-                Err(_) => {
-                    ConsoleService::log("Could not connect to socket");
-                    None
-                }
-            },
+            socket,
+            service_addr,
+            reconnect_attempts: 0,
+            reconnect_task: None,
             active_tab: Tab::Drones,
             requests: Default::default(),
+            recent_requests: Default::default(),
+            trace_query: Default::default(),
+            trace: None,
+            identification: Default::default(),
+            pairing: Default::default(),
+            replay: Default::default(),
             builderbots: Default::default(),
             drones: Default::default(),
             pipucks: Default::default(),
@@ -98,6 +241,40 @@ impl Component for UserInterface {
             builderbot_software: Default::default(),
             drone_software: Default::default(),
             pipuck_software: Default::default(),
+            software_overrides: Default::default(),
+            journal_statistics: None,
+            log: VecDeque::new(),
+            checklist: Vec::new(),
+            smoke_tests: BTreeMap::new(),
+            status: Default::default(),
+            simulated_robots: Vec::new(),
+            ros_robots: BTreeMap::new(),
+            tracking_system_models: Default::default(),
+            tracking_system_latency: Default::default(),
+            infrastructure: Default::default(),
+            ambient: Default::default(),
+            router: Default::default(),
+            association_failures: Default::default(),
+            quarantine: Default::default(),
+            notifications: Default::default(),
+            replication: None,
+            health: Default::default(),
+            profiles: Default::default(),
+            library: Default::default(),
+            maintenance: None,
+            maintenance_input: NodeRef::default(),
+            maintenance_summary: None,
+            configuration: None,
+            configuration_restart_required: Default::default(),
+            configuration_saved: None,
+            access: None,
+            login_input: NodeRef::default(),
+            login_error: None,
+            sessions: Default::default(),
+            _keyboard_listener,
+            palette: None,
+            palette_input: NodeRef::default(),
+            focus: None,
         }
     }
 
@@ -107,10 +284,36 @@ impl Component for UserInterface {
                 self.active_tab = tab;
                 true
             }
+            Msg::KeyDown(event) => self.key_down(event),
+            Msg::TogglePalette => {
+                self.palette = match self.palette {
+                    Some(_) => None,
+                    None => Some(palette::Palette::default()),
+                };
+                true
+            }
+            Msg::SetPaletteQuery(query) => {
+                if let Some(palette) = self.palette.as_mut() {
+                    palette.query = query;
+                    palette.selected = 0;
+                }
+                true
+            }
+            Msg::ExecuteCommand(command) => {
+                self.palette = None;
+                self.execute(command);
+                true
+            }
             Msg::SendRequest(request, callback) => {
                 match self.socket.as_mut() {
                     Some(websocket) => {
                         let id = Uuid::new_v4();
+                        if !matches!(request, shared::BackEndRequest::TraceRequest(_)) {
+                            if self.recent_requests.len() == RECENT_REQUESTS {
+                                self.recent_requests.pop_back();
+                            }
+                            self.recent_requests.push_front((id, request.summary(), None));
+                        }
                         let message = UpMessage::Request(id, request);
                         match bincode::serialize(&message) {
                             Ok(serialized) => {
@@ -120,12 +323,12 @@ impl Component for UserInterface {
                                 }
                             },
                             Err(error) => if let Some(callback) = callback {
-                                callback.emit(Err(format!("Could not serialize request: {}", error)));
+                                callback.emit(Err(t!("request.serialize", error = error)));
                             }
                         }
                     }
                     None => if let Some(callback) = callback {
-                        callback.emit(Err(String::from("Could not send request: Disconnected")));
+                        callback.emit(Err(t!("request.disconnected")));
                     }
                 }
                 false
@@ -134,9 +337,44 @@ impl Component for UserInterface {
                 Ok(data) => match bincode::deserialize::<DownMessage>(&data) {
                     Ok(decoded) => match decoded {
                         DownMessage::Request(_uuid, request) => match request {
-                            shared::FrontEndRequest::AddBuilderBot(desc) => {
-                                self.builderbots.entry(desc.id.clone())
-                                    .or_insert_with(|| Rc::new(RefCell::new(builderbot::Instance::new(desc))));
+                            shared::FrontEndRequest::Snapshot(snapshot) => {
+                                /* the snapshot replaces the state of the robots, which may be stale after a
+                                   reconnect, e.g., if a robot was removed from the configuration */
+                                self.builderbots = snapshot.builderbots.into_iter()
+                                    .map(|(desc, updates)| {
+                                        let id = desc.id.clone();
+                                        let mut builderbot = builderbot::Instance::new(desc);
+                                        for update in updates {
+                                            builderbot.update(update);
+                                        }
+                                        (id, Rc::new(RefCell::new(builderbot)))
+                                    })
+                                    .collect();
+                                self.drones = snapshot.drones.into_iter()
+                                    .map(|(desc, updates)| {
+                                        let id = desc.id.clone();
+                                        let mut drone = drone::Instance::new(desc);
+                                        for update in updates {
+                                            drone.update(update);
+                                        }
+                                        (id, Rc::new(RefCell::new(drone)))
+                                    })
+                                    .collect();
+                                self.pipucks = snapshot.pipucks.into_iter()
+                                    .map(|(desc, updates)| {
+                                        let id = desc.id.clone();
+                                        let mut pipuck = pipuck::Instance::new(desc);
+                                        for update in updates {
+                                            pipuck.update(update);
+                                        }
+                                        (id, Rc::new(RefCell::new(pipuck)))
+                                    })
+                                    .collect();
+                                self.checklist = snapshot.checklist;
+                                self.smoke_tests = snapshot.smoke_tests;
+                                self.status = snapshot.status;
+                                self.label_robots();
+                                self.observe_infrastructure();
                                 true
                             },
                             shared::FrontEndRequest::UpdateBuilderBot(id, update) => {
@@ -145,36 +383,194 @@ impl Component for UserInterface {
                                 }
                                 true
                             },
-                            shared::FrontEndRequest::AddDrone(desc) => {
-                                self.drones.entry(desc.id.clone())
-                                    .or_insert_with(|| Rc::new(RefCell::new(drone::Instance::new(desc))));
-                                true
-                            },
                             shared::FrontEndRequest::UpdateDrone(id, update) => {
                                 if let Some(drone) = self.drones.get(&id) {
                                     drone.borrow_mut().update(update);
                                 }
                                 true
                             },
-                            shared::FrontEndRequest::AddPiPuck(desc) => {
-                                self.pipucks.entry(desc.id.clone())
-                                    .or_insert_with(|| Rc::new(RefCell::new(pipuck::Instance::new(desc))));
-                                true
-                            },
                             shared::FrontEndRequest::UpdatePiPuck(id, update) => {
                                 if let Some(pipuck) = self.pipucks.get(&id) {
                                     pipuck.borrow_mut().update(update);
                                 }
                                 true
                             },
-                            shared::FrontEndRequest::UpdateExperiment(_) => todo!(),
-                            shared::FrontEndRequest::UpdateTrackingSystem(updates) => {
+                            shared::FrontEndRequest::UpdateExperiment(update) => match update {
+                                shared::experiment::Update::Journal(statistics) => {
+                                    self.journal_statistics = Some(statistics);
+                                    self.active_tab == Tab::Experiment
+                                },
+                                shared::experiment::Update::Checklist(checklist) => {
+                                    self.checklist = checklist;
+                                    self.active_tab == Tab::Experiment
+                                },
+                                shared::experiment::Update::SmokeTests(smoke_tests) => {
+                                    self.smoke_tests = smoke_tests;
+                                    self.active_tab == Tab::Experiment
+                                },
+                                shared::experiment::Update::Status(status) => {
+                                    self.status = status;
+                                    self.active_tab == Tab::Experiment
+                                },
+                                shared::experiment::Update::Log(entry) => {
+                                    /* the timestamps restart from zero when a new journal is started */
+                                    if self.log.back().map_or(false, |last| entry.timestamp < last.timestamp) {
+                                        self.log.clear();
+                                    }
+                                    if self.log.len() == LOG_ENTRIES {
+                                        self.log.pop_front();
+                                    }
+                                    self.log.push_back(entry);
+                                    self.active_tab == Tab::Experiment
+                                },
+                            },
+                            shared::FrontEndRequest::UpdateSimulation(update) => match update {
+                                shared::simulation::Update::Robots(robots) => {
+                                    self.simulated_robots = robots;
+                                    true
+                                }
+                            },
+                            shared::FrontEndRequest::UpdateRos(update) => match update {
+                                shared::ros::Update::Robot(robot) => {
+                                    self.ros_robots.insert(robot.descriptor.id.clone(), robot);
+                                    self.active_tab == Tab::RosRobots
+                                }
+                            },
+                            shared::FrontEndRequest::UpdateMaintenance(update) => {
+                                match update {
+                                    maintenance::Update::Enabled { paused_addrs } => {
+                                        self.maintenance = Some(paused_addrs);
+                                    },
+                                    maintenance::Update::Disabled { suppressed_events } => {
+                                        self.maintenance = None;
+                                        self.maintenance_summary = Some(suppressed_events);
+                                    }
+                                }
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateConfiguration(update) => match update {
+                                configuration::Update::Configuration { configuration, restart_required } => {
+                                    self.apply_identifiers(&configuration);
+                                    self.label_robots();
+                                    self.configuration = Some(configuration);
+                                    self.configuration_restart_required = restart_required;
+                                    true
+                                },
+                                configuration::Update::Saved(path) => {
+                                    self.configuration_saved = Some(path);
+                                    self.active_tab == Tab::Settings
+                                },
+                            },
+                            shared::FrontEndRequest::UpdateAccess(update) => {
+                                access::set_permissions(&update);
+                                self.access = Some(update);
+                                true
+                            },
+                            shared::FrontEndRequest::Notification(notification) => {
+                                self.notify(notification);
+                                true
+                            },
+                            shared::FrontEndRequest::UnknownDevice(device) => {
+                                self.notify_unknown_device(device);
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateSessions(sessions) => {
+                                self.sessions = sessions;
+                                self.active_tab == Tab::Diagnostics
+                            },
+                            shared::FrontEndRequest::UpdateHealth(health) => {
+                                self.health = health;
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateProfiles(profiles) => {
+                                self.profiles = profiles;
+                                self.active_tab == Tab::Experiment
+                            },
+                            shared::FrontEndRequest::UpdateLibrary(library) => {
+                                self.library = library;
+                                self.active_tab == Tab::Experiment
+                            },
+                            shared::FrontEndRequest::UpdateReplication(role) => {
+                                self.replication = Some(role);
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateAssociation(update) => {
+                                match update {
+                                    association::Update::Failed(addr, failure) => {
+                                        self.association_failures.insert(addr, failure);
+                                    },
+                                    association::Update::Cleared(addr) => {
+                                        self.association_failures.remove(&addr);
+                                    },
+                                    association::Update::Unknown(device) => self.notify_unknown_device(device),
+                                    association::Update::Quarantined(device) => {
+                                        self.quarantine.insert(device.macaddr, device);
+                                    },
+                                    association::Update::Released(macaddr) => {
+                                        self.quarantine.remove(&macaddr);
+                                    },
+                                }
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateInfrastructure(update) => {
+                                self.infrastructure = update;
+                                self.observe_infrastructure();
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateAmbient(update) => {
+                                self.ambient = update;
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateIdentification(identification) => {
+                                self.identification = identification;
+                                self.active_tab == Tab::Diagnostics
+                            },
+                            shared::FrontEndRequest::UpdateExcerpt(id, excerpt) => {
+                                if let Some(builderbot) = self.builderbots.get(&id) {
+                                    builderbot.borrow_mut().excerpt = Some(excerpt);
+                                } else if let Some(drone) = self.drones.get(&id) {
+                                    drone.borrow_mut().excerpt = Some(excerpt);
+                                } else if let Some(pipuck) = self.pipucks.get(&id) {
+                                    pipuck.borrow_mut().excerpt = Some(excerpt);
+                                }
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateTelemetry(id, history) => {
+                                if let Some(builderbot) = self.builderbots.get(&id) {
+                                    builderbot.borrow_mut().restore(history);
+                                } else if let Some(drone) = self.drones.get(&id) {
+                                    drone.borrow_mut().restore(history);
+                                } else if let Some(pipuck) = self.pipucks.get(&id) {
+                                    pipuck.borrow_mut().restore(history);
+                                }
+                                true
+                            },
+                            shared::FrontEndRequest::UpdatePairing(pairing) => {
+                                self.pairing = pairing;
+                                self.active_tab == Tab::Diagnostics
+                            },
+                            shared::FrontEndRequest::UpdateTrace(trace) => {
+                                self.trace = Some(Ok(trace));
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateRouter(statistics) => {
+                                self.router = statistics;
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateTrackingSystemModels(models) => {
+                                self.tracking_system_models = models;
+                                self.label_robots();
+                                true
+                            },
+                            shared::FrontEndRequest::UpdateTrackingSystem(updates, timestamps) => {
+                                self.tracking_system_latency.borrow_mut().receive(timestamps);
                                 for update in updates {
                                     for builderbot in self.builderbots.values() {
                                         let mut builderbot = builderbot.borrow_mut();
                                         if let Some(id) = builderbot.descriptor.optitrack_id {
                                             if update.id == id {
                                                 builderbot.optitrack_pos = update.position;
+                                                builderbot.optitrack_speed = update.speed();
                                             }
                                         }
                                     }
@@ -183,6 +579,7 @@ impl Component for UserInterface {
                                         if let Some(id) = drone.descriptor.optitrack_id {
                                             if update.id == id {
                                                 drone.optitrack_pos = update.position;
+                                                drone.optitrack_speed = update.speed();
                                             }
                                         }
                                     }
@@ -191,6 +588,7 @@ impl Component for UserInterface {
                                         if let Some(id) = pipuck.descriptor.optitrack_id {
                                             if update.id == id {
                                                 pipuck.optitrack_pos = update.position;
+                                                pipuck.optitrack_speed = update.speed();
                                             }
                                         }
                                     }
@@ -199,13 +597,22 @@ impl Component for UserInterface {
                             },
                         },
                         DownMessage::Response(uuid, result) => {
+                            let recent = self.recent_requests.iter_mut()
+                                .find(|(id, _, _)| *id == uuid);
+                            let updated = match recent {
+                                Some((_, _, recent_result)) => {
+                                    *recent_result = Some(result.clone());
+                                    true
+                                },
+                                None => false,
+                            };
                             if let Some(callback) = self.requests.remove(&uuid) {
                                 if let Err(error) = result.as_ref() {
-                                    ConsoleService::log(&format!("Error processing request: {}", error));
+                                    ConsoleService::log(&format!("Error processing request {}: {}", uuid, error));
                                 }
                                 callback.emit(result);
                             }
-                            false
+                            updated && self.active_tab == Tab::Diagnostics
                         }
                     },
                     Err(error) => {
@@ -220,7 +627,35 @@ impl Component for UserInterface {
             },
             Msg::WebSocketNotifcation(notification) => {
                 ConsoleService::log(&format!("Connection to backend: {:?}", notification));
-                false
+                match notification {
+                    WebSocketStatus::Opened => {
+                        /* the supervisor resends its state to a new connection, the state that is only sent
+                           while it is present, e.g., maintenance mode, is cleared here */
+                        if self.reconnect_attempts > 0 {
+                            self.maintenance = None;
+                            self.association_failures.clear();
+                            self.quarantine.clear();
+                            self.sessions.clear();
+                            self.replication = None;
+                        }
+                        self.reconnect_attempts = 0;
+                        true
+                    },
+                    /* an error is followed by the socket being closed */
+                    WebSocketStatus::Error | WebSocketStatus::Closed => {
+                        self.disconnected();
+                        true
+                    },
+                }
+            }
+            Msg::Reconnect => {
+                self.reconnect_task = None;
+                self.reconnect_attempts += 1;
+                self.socket = connect(&self.link, &self.service_addr);
+                if self.socket.is_none() {
+                    self.disconnected();
+                }
+                true
             }
             Msg::SetBuilderBotConfigComp(link) => {
                 self.builderbot_config_comp = Some(link);
@@ -238,6 +673,102 @@ impl Component for UserInterface {
                 self.control_config_comp = Some(link);
                 false
             },
+            Msg::ToggleMaintenance => {
+                let request = match self.maintenance {
+                    Some(_) => Some(maintenance::Request::Disable),
+                    None => self.maintenance_input.cast::<HtmlInputElement>()
+                        .and_then(|input| input.value()
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|addr| !addr.is_empty())
+                            .map(|addr| addr.parse::<Ipv4Addr>())
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|error| ConsoleService::log(&format!("Could not parse address: {}", error)))
+                            .ok())
+                        .map(|paused_addrs| maintenance::Request::Enable { paused_addrs }),
+                };
+                if let Some(request) = request {
+                    let request = shared::BackEndRequest::MaintenanceRequest(request);
+                    self.link.send_message(Msg::SendRequest(request, None));
+                }
+                false
+            },
+            Msg::DismissMaintenanceSummary => {
+                self.maintenance_summary = None;
+                true
+            },
+            Msg::DismissNotification(index) => {
+                self.notifications.remove(index);
+                true
+            },
+            Msg::SetTraceQuery(query) => {
+                self.trace_query = query;
+                false
+            },
+            Msg::ShowTrace => match Uuid::parse_str(self.trace_query.trim()) {
+                Ok(id) => self.update(Msg::RequestTrace(id)),
+                Err(_) => {
+                    self.trace = Some(Err(t!("diagnostics.trace.invalid")));
+                    true
+                }
+            },
+            Msg::RequestTrace(id) => {
+                self.trace_query = id.to_string();
+                let callback = self.link.batch_callback(|result: Result<(), String>| result.err().map(Msg::TraceFailed));
+                self.update(Msg::SendRequest(shared::BackEndRequest::TraceRequest(id), Some(callback)))
+            },
+            Msg::TraceFailed(error) => {
+                self.trace = Some(Err(error));
+                true
+            },
+            Msg::Login(login) => {
+                let token = match login {
+                    true => match self.login_input.cast::<HtmlInputElement>()
+                        .map(|input| input.value().trim().to_owned())
+                        .filter(|token| !token.is_empty()) {
+                        Some(token) => Some(token),
+                        None => return false,
+                    },
+                    false => None,
+                };
+                let callback = self.link.callback(Msg::SetLoginResult);
+                let request = shared::BackEndRequest::LoginRequest(token);
+                self.link.send_message(Msg::SendRequest(request, Some(callback)));
+                false
+            },
+            Msg::SetLoginResult(result) => {
+                if result.is_ok() {
+                    if let Some(input) = self.login_input.cast::<HtmlInputElement>() {
+                        input.set_value("");
+                    }
+                }
+                self.login_error = result.err();
+                true
+            },
+            Msg::ApplyIdentification => match self.configuration.clone() {
+                Some(mut configuration) => {
+                    /* only the identifiers of the robots that were identified differently are changed */
+                    for robot in &self.identification.robots {
+                        if let Some(proposal) = robot.proposal() {
+                            let builderbots = configuration.builderbots.iter_mut()
+                                .filter(|desc| desc.id == robot.id)
+                                .map(|desc| &mut desc.optitrack_id);
+                            let drones = configuration.drones.iter_mut()
+                                .filter(|desc| desc.id == robot.id)
+                                .map(|desc| &mut desc.optitrack_id);
+                            let pipucks = configuration.pipucks.iter_mut()
+                                .filter(|desc| desc.id == robot.id)
+                                .map(|desc| &mut desc.optitrack_id);
+                            for optitrack_id in builderbots.chain(drones).chain(pipucks) {
+                                *optitrack_id = Some(proposal);
+                            }
+                        }
+                    }
+                    let request = shared::BackEndRequest::ConfigurationRequest(configuration::Request::Update(configuration));
+                    self.update(Msg::SendRequest(request, None))
+                },
+                None => false,
+            },
         }
     }
 
@@ -246,42 +777,134 @@ impl Component for UserInterface {
         false
     }
 
+    fn rendered(&mut self, _first_render: bool) {
+        self.tracking_system_latency.borrow_mut().render();
+        if let Some(input) = self.palette_input.cast::<HtmlInputElement>() {
+            let _ = input.focus();
+        }
+        if let Some(id) = self.focus.take() {
+            if let Some(element) = yew::utils::document().get_element_by_id(&id) {
+                element.scroll_into_view();
+            }
+        }
+    }
+
     fn view(&self) -> Html {
         html! {
             <>
                 { self.render_hero() }
+                { self.render_maintenance_summary() }
+                { self.render_palette() }
                 { self.render_tabs() }
                 <section class="section">
                     <div class="container is-fluid">
+                        { self.render_connection() }
+                        { self.render_association_failures() }
+                        { self.render_notifications() }
                         <div class="columns is-multiline is-mobile"> {
                             match self.active_tab {
-                                Tab::BuilderBots => self.builderbots
-                                    .iter()
-                                    .map(|(id, builderbot)| html! {
-                                        <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                                            <builderbot::Card key=id.clone() instance=builderbot.clone() parent=self.link.clone() />
-                                        </div>
-                                    }).collect::<Html>(),
-                                Tab::Drones => self.drones
-                                    .iter()
-                                    .map(|(id, drone)| html! {
-                                        <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                                            <drone::Card key=id.clone() instance=drone.clone() parent=self.link.clone() />
-                                        </div>
-                                    }).collect::<Html>(),
-                                Tab::PiPucks => self.pipucks
-                                    .iter()
-                                    .map(|(id, pipuck)| html! {
-                                        <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
-                                            <pipuck::Card key=id.clone() instance=pipuck.clone() parent=self.link.clone() />
-                                        </div>
-                                    }).collect::<Html>(),
+                                Tab::BuilderBots => html! {
+                                    <>
+                                        { swarm::render(self.builderbots.values().map(|builderbot| builderbot.borrow().status())) }
+                                        { self.builderbots
+                                            .iter()
+                                            .map(|(id, builderbot)| html! {
+                                                <div id=palette::robot_element_id(Tab::BuilderBots, id)
+                                                     class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                                    <builderbot::Card key=id.clone() instance=builderbot.clone() parent=self.link.clone() />
+                                                </div>
+                                            }).collect::<Html>() }
+                                        { self.render_simulated_robots("builderbot") }
+                                    </>
+                                },
+                                Tab::Drones => html! {
+                                    <>
+                                        { swarm::render(self.drones.values().map(|drone| drone.borrow().status())) }
+                                        { self.drones
+                                            .iter()
+                                            .map(|(id, drone)| html! {
+                                                <div id=palette::robot_element_id(Tab::Drones, id)
+                                                     class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                                    <drone::Card key=id.clone() instance=drone.clone() parent=self.link.clone() />
+                                                </div>
+                                            }).collect::<Html>() }
+                                        { self.render_simulated_robots("drone") }
+                                    </>
+                                },
+                                Tab::PiPucks => html! {
+                                    <>
+                                        { swarm::render(self.pipucks.values().map(|pipuck| pipuck.borrow().status())) }
+                                        { self.pipucks
+                                            .iter()
+                                            .map(|(id, pipuck)| html! {
+                                                <div id=palette::robot_element_id(Tab::PiPucks, id)
+                                                     class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                                    <pipuck::Card key=id.clone() instance=pipuck.clone() parent=self.link.clone() />
+                                                </div>
+                                            }).collect::<Html>() }
+                                        { self.render_simulated_robots("pipuck") }
+                                    </>
+                                },
+                                Tab::RosRobots => html! {
+                                    { self.ros_robots
+                                        .iter()
+                                        .map(|(id, robot)| html! {
+                                            <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                                <ros::Card key=id.clone() robot=robot.clone() />
+                                            </div>
+                                        }).collect::<Html>() }
+                                },
                                 Tab::Experiment => html! {
                                     <experiment::Interface parent=self.link.clone()
                                         builderbot_software=self.builderbot_software.clone()
                                         drone_software=self.drone_software.clone()
-                                        pipuck_software=self.pipuck_software.clone() />
-                                }
+                                        pipuck_software=self.pipuck_software.clone()
+                                        software_overrides=self.software_overrides.clone()
+                                        journal_statistics=self.journal_statistics.clone()
+                                        log=self.log.clone()
+                                        checklist=self.checklist.clone()
+                                        smoke_tests=self.smoke_tests.clone()
+                                        status=self.status.clone()
+                                        ambient=self.ambient.clone()
+                                        energy=self.energy()
+                                        traffic=self.traffic()
+                                        topology=self.router.topology.clone()
+                                        range=self.router.range
+                                        dropped=self.router.dropped
+                                        routes=self.router.routes.clone()
+                                        groups=self.configuration.as_ref()
+                                            .map_or_else(Vec::new, |configuration| configuration.groups.clone())
+                                        tags=self.configuration.as_ref()
+                                            .map_or_else(Vec::new, |configuration| configuration.tags.clone())
+                                        profiles=self.profiles.clone()
+                                        library=self.library.clone() />
+                                },
+                                Tab::Settings => html! {
+                                    <settings::Interface parent=self.link.clone()
+                                        configuration=self.configuration.clone()
+                                        restart_required=self.configuration_restart_required.clone()
+                                        saved=self.configuration_saved.clone() />
+                                },
+                                Tab::Diagnostics => html! {
+                                    <>
+                                        <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                            <diagnostics::Card latency=self.tracking_system_latency.clone() />
+                                        </div>
+                                        { self.render_xbee_audit() }
+                                        { self.render_identification() }
+                                        { self.render_sessions() }
+                                        <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                                            <pairing::Card parent=self.link.clone()
+                                                pairing=self.pairing.clone()
+                                                configuration=self.configuration.clone() />
+                                        </div>
+                                        { self.render_traces() }
+                                    </>
+                                },
+                                Tab::Replay => html! {
+                                    <replay::Interface replay=self.replay.clone() />
+                                },
+                                Tab::Quarantine => self.render_quarantine(),
                             }
                         } </div>
                     </div>
@@ -292,9 +915,388 @@ impl Component for UserInterface {
 }
 
 impl UserInterface {
+    fn notify(&mut self, notification: shared::alert::Notification) {
+        /* a new alert replaces the previous alert about the same robot */
+        self.notifications.retain(|previous| previous.robot != notification.robot);
+        if self.notifications.len() == NOTIFICATIONS {
+            self.notifications.pop_front();
+        }
+        self.notifications.push_back(notification);
+    }
+
+    /// Alerts about a device whose MAC address does not match any robot, the alert is keyed by the MAC address
+    /// so that a device that is found again replaces its previous alert
+    fn notify_unknown_device(&mut self, device: association::UnknownDevice) {
+        let kind = match device.kind {
+            association::Kind::Xbee => "Xbee",
+            association::Kind::Fernbedienung => "Fernbedienung",
+        };
+        self.notify(shared::alert::Notification {
+            robot: device.macaddr.to_string(),
+            severity: shared::alert::Severity::Warning,
+            message: t!("association.unknown_device", kind = kind, addr = device.addr, macaddr = device.macaddr),
+        });
+    }
+
+    /// Handles the keyboard shortcuts: Ctrl+K toggles the command palette, Alt+1 to Alt+9 switch tabs,
+    /// Alt+S starts the experiment, and Alt+X stops the experiment
+    fn key_down(&mut self, event: KeyboardEvent) -> ShouldRender {
+        if event.ctrl_key() && event.code() == "KeyK" {
+            event.prevent_default();
+            self.link.send_message(Msg::TogglePalette);
+            return false;
+        }
+        if let Some(selected) = self.palette.as_ref().map(|palette| palette.selected) {
+            return match event.key().as_str() {
+                "Escape" => {
+                    self.palette = None;
+                    true
+                },
+                "ArrowDown" | "ArrowUp" => {
+                    event.prevent_default();
+                    let count = self.palette_commands().len();
+                    if let Some(palette) = self.palette.as_mut() {
+                        palette.selected = match event.key().as_str() {
+                            "ArrowDown" => (selected + 1).min(count.saturating_sub(1)),
+                            _ => selected.saturating_sub(1),
+                        };
+                    }
+                    true
+                },
+                "Enter" => {
+                    event.prevent_default();
+                    if let Some(command) = self.palette_commands().into_iter().nth(selected) {
+                        self.link.send_message(Msg::ExecuteCommand(command));
+                    }
+                    false
+                },
+                _ => false,
+            }
+        }
+        if event.alt_key() {
+            let command = match event.code().as_str() {
+                "KeyS" => Some(self.start_experiment_command()),
+                "KeyX" => Some(self.stop_experiment_command()),
+                code => code.strip_prefix("Digit")
+                    .and_then(|digit| digit.parse::<usize>().ok())
+                    .and_then(|digit| digit.checked_sub(1))
+                    .and_then(|index| Tab::iter().nth(index))
+                    .map(palette::Command::SetActiveTab),
+            };
+            if let Some(command) = command {
+                event.prevent_default();
+                self.execute(command);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn start_experiment_command(&self) -> palette::Command {
+        palette::Command::Request {
+            label: t!("experiment.start"),
+            shortcut: Some("Alt+S"),
+            request: shared::BackEndRequest::ExperimentRequest(shared::experiment::Request::Start {
+                builderbot_software: self.builderbot_software.borrow().clone(),
+                pipuck_software: self.pipuck_software.borrow().clone(),
+                drone_software: self.drone_software.borrow().clone(),
+                overrides: self.software_overrides.borrow().clone(),
+                partial_start: experiment::partial_start(),
+                settings: Default::default(),
+            }),
+        }
+    }
+
+    fn stop_experiment_command(&self) -> palette::Command {
+        palette::Command::Request {
+            label: t!("experiment.stop"),
+            shortcut: Some("Alt+X"),
+            request: shared::BackEndRequest::ExperimentRequest(shared::experiment::Request::Stop),
+        }
+    }
+
+    fn return_to_base_command(&self) -> palette::Command {
+        palette::Command::Request {
+            label: t!("experiment.return_to_base"),
+            shortcut: None,
+            request: shared::BackEndRequest::ReturnToBaseRequest,
+        }
+    }
+
+    fn xbee_audit_command(&self) -> palette::Command {
+        palette::Command::Request {
+            label: t!("diagnostics.xbee_audit.run"),
+            shortcut: None,
+            request: shared::BackEndRequest::XbeeAuditRequest,
+        }
+    }
+
+    /// Replaces the state of the robots and the experiment with a snapshot from the supervisor
+    fn sync_command(&self) -> palette::Command {
+        palette::Command::Request {
+            label: t!("connection.sync"),
+            shortcut: None,
+            request: shared::BackEndRequest::SyncRequest,
+        }
+    }
+
+    /// The commands in the command palette that match the current query
+    fn palette_commands(&self) -> Vec<palette::Command> {
+        let mut commands = Tab::iter()
+            .map(palette::Command::SetActiveTab)
+            .collect::<Vec<_>>();
+        if access::permitted(Category::Experiment) {
+            commands.push(self.start_experiment_command());
+            commands.push(self.stop_experiment_command());
+        }
+        if access::permitted(Category::Power) {
+            commands.push(self.return_to_base_command());
+        }
+        commands.push(self.xbee_audit_command());
+        commands.push(self.sync_command());
+        let terminals = access::permitted(Category::Terminals);
+        for (tab, mut ids) in vec![
+            (Tab::BuilderBots, self.builderbots.keys().collect::<Vec<_>>()),
+            (Tab::Drones, self.drones.keys().collect::<Vec<_>>()),
+            (Tab::PiPucks, self.pipucks.keys().collect::<Vec<_>>())] {
+            ids.sort();
+            for id in ids {
+                commands.push(palette::Command::FocusRobot(tab, id.clone()));
+                if terminals {
+                    commands.push(palette::Command::OpenTerminal(tab, id.clone()));
+                }
+            }
+        }
+        let query = self.palette.as_ref().map_or("", |palette| palette.query.as_str());
+        commands.into_iter()
+            .filter(|command| command.matches(query))
+            .collect()
+    }
+
+    fn execute(&mut self, command: palette::Command) {
+        match command {
+            palette::Command::SetActiveTab(tab) => self.active_tab = tab,
+            palette::Command::FocusRobot(tab, id) => {
+                self.active_tab = tab;
+                self.focus = Some(palette::robot_element_id(tab, &id));
+            },
+            palette::Command::OpenTerminal(tab, id) => {
+                let found = match tab {
+                    Tab::BuilderBots => self.builderbots.get(&id)
+                        .map(|builderbot| builderbot.borrow_mut().terminal_requested = true),
+                    Tab::Drones => self.drones.get(&id)
+                        .map(|drone| drone.borrow_mut().terminal_requested = true),
+                    Tab::PiPucks => self.pipucks.get(&id)
+                        .map(|pipuck| pipuck.borrow_mut().terminal_requested = true),
+                    _ => None,
+                };
+                if found.is_some() {
+                    self.active_tab = tab;
+                    self.focus = Some(palette::robot_element_id(tab, &id));
+                }
+            },
+            palette::Command::Request { request, .. } => {
+                self.link.send_message(Msg::SendRequest(request, None));
+            },
+        }
+    }
+
+    fn render_palette(&self) -> Html {
+        let palette = match self.palette.as_ref() {
+            Some(palette) => palette,
+            None => return html! {},
+        };
+        let commands = self.palette_commands();
+        let close_onclick = self.link.callback(|_| Msg::TogglePalette);
+        let query_oninput = self.link.callback(|data: InputData| Msg::SetPaletteQuery(data.value));
+        html! {
+            <div class="modal is-active">
+                <div class="modal-background" onclick=close_onclick></div>
+                <div class="modal-content">
+                    <nav class="panel has-background-white">
+                        <div class="panel-block">
+                            <p class="control has-icons-left">
+                                <input ref=self.palette_input.clone() class="input" type="text"
+                                       placeholder=t!("palette.placeholder")
+                                       value=palette.query.clone() oninput=query_oninput />
+                                <span class="icon is-left">
+                                    <i class="mdi mdi-magnify"></i>
+                                </span>
+                            </p>
+                        </div>
+                        {
+                            commands.into_iter()
+                                .enumerate()
+                                .map(|(index, command)| {
+                                    let active = (index == palette.selected).then(|| "is-active");
+                                    let label = command.label();
+                                    let shortcut = command.shortcut();
+                                    let onclick = self.link.callback(move |_| Msg::ExecuteCommand(command.clone()));
+                                    html! {
+                                        <a class=classes!("panel-block", active) onclick=onclick>
+                                            <span class="is-flex-grow-1">{ label }</span>
+                                            {
+                                                shortcut.map_or_else(|| html! {}, |shortcut| html! {
+                                                    <span class="tag">{ shortcut }</span>
+                                                })
+                                            }
+                                        </a>
+                                    }
+                                })
+                                .collect::<Html>()
+                        }
+                    </nav>
+                </div>
+            </div>
+        }
+    }
+
+    /// Updates the optitrack and apriltag identifiers of the robots from a configuration, these are the only
+    /// changes to the configuration that are applied without restarting the supervisor
+    fn apply_identifiers(&self, configuration: &configuration::Configuration) {
+        for desc in &configuration.builderbots {
+            if let Some(builderbot) = self.builderbots.get(&desc.id) {
+                let mut builderbot = builderbot.borrow_mut();
+                if builderbot.descriptor.duovero_macaddr == desc.duovero_macaddr {
+                    builderbot.descriptor.optitrack_id = desc.optitrack_id;
+                    builderbot.descriptor.apriltag_id = desc.apriltag_id;
+                }
+            }
+        }
+        for desc in &configuration.drones {
+            if let Some(drone) = self.drones.get(&desc.id) {
+                let mut drone = drone.borrow_mut();
+                if drone.descriptor.xbee_macaddr == desc.xbee_macaddr &&
+                   drone.descriptor.upcore_macaddr == desc.upcore_macaddr {
+                    drone.descriptor.optitrack_id = desc.optitrack_id;
+                }
+            }
+        }
+        for desc in &configuration.pipucks {
+            if let Some(pipuck) = self.pipucks.get(&desc.id) {
+                let mut pipuck = pipuck.borrow_mut();
+                if pipuck.descriptor.rpi_macaddr == desc.rpi_macaddr {
+                    pipuck.descriptor.optitrack_id = desc.optitrack_id;
+                    pipuck.descriptor.apriltag_id = desc.apriltag_id;
+                }
+            }
+        }
+    }
+
+    /// Labels the robots with the rigid body names from Motive and suggests optitrack identifiers for the
+    /// robots whose ids match the name of a rigid body
+    /// The energy that each robot used during its last experiment
+    fn energy(&self) -> Vec<(String, Energy)> {
+        let builderbots = self.builderbots.iter()
+            .filter_map(|(id, builderbot)| builderbot.borrow().last_experiment.as_ref()
+                .and_then(|summary| summary.energy.clone())
+                .map(|energy| (id.clone(), energy)));
+        let drones = self.drones.iter()
+            .filter_map(|(id, drone)| drone.borrow().last_experiment.as_ref()
+                .and_then(|summary| summary.energy.clone())
+                .map(|energy| (id.clone(), energy)));
+        let pipucks = self.pipucks.iter()
+            .filter_map(|(id, pipuck)| pipuck.borrow().last_experiment.as_ref()
+                .and_then(|summary| summary.energy.clone())
+                .map(|energy| (id.clone(), energy)));
+        builderbots.chain(drones).chain(pipucks).collect()
+    }
+
+    /// The links of the message router, labelled with the robots that the peers belong to. Simulated robots
+    /// are identified by the address of their connection and real robots by the address of the robot
+    fn traffic(&self) -> Vec<(String, String, router::Link)> {
+        let label = |addr: SocketAddr| {
+            let simulated = self.simulated_robots.iter()
+                .find(|robot| robot.registration.as_ref().map_or(false, |registration| registration.addr == addr))
+                .map(|robot| robot.descriptor.id.clone());
+            let real = || {
+                let builderbots = self.builderbots.iter()
+                    .filter(|(_, builderbot)| builderbot.borrow().addr().map(IpAddr::V4) == Some(addr.ip()));
+                let drones = self.drones.iter()
+                    .filter(|(_, drone)| drone.borrow().addr().map(IpAddr::V4) == Some(addr.ip()));
+                let pipucks = self.pipucks.iter()
+                    .filter(|(_, pipuck)| pipuck.borrow().addr().map(IpAddr::V4) == Some(addr.ip()));
+                builderbots.map(|(id, _)| id.clone())
+                    .chain(drones.map(|(id, _)| id.clone()))
+                    .chain(pipucks.map(|(id, _)| id.clone()))
+                    .next()
+            };
+            simulated.or_else(real).unwrap_or_else(|| addr.to_string())
+        };
+        self.router.links.iter()
+            .map(|link| (label(link.from), label(link.to), link.clone()))
+            .collect()
+    }
+
+    fn label_robots(&self) {
+        let label = |id: &str, optitrack_id: Option<i32>| {
+            let name = self.tracking_system_models.iter()
+                .find(|model| Some(model.id) == optitrack_id)
+                .map(|model| model.name.clone());
+            let suggestion = self.tracking_system_models.iter()
+                .find(|model| model.name == id && Some(model.id) != optitrack_id)
+                .map(|model| model.id);
+            (name, suggestion)
+        };
+        for builderbot in self.builderbots.values() {
+            let mut builderbot = builderbot.borrow_mut();
+            let (name, suggestion) = label(&builderbot.descriptor.id, builderbot.descriptor.optitrack_id);
+            builderbot.optitrack_name = name;
+            builderbot.optitrack_suggestion = suggestion;
+        }
+        for drone in self.drones.values() {
+            let mut drone = drone.borrow_mut();
+            let (name, suggestion) = label(&drone.descriptor.id, drone.descriptor.optitrack_id);
+            drone.optitrack_name = name;
+            drone.optitrack_suggestion = suggestion;
+        }
+        for pipuck in self.pipucks.values() {
+            let mut pipuck = pipuck.borrow_mut();
+            let (name, suggestion) = label(&pipuck.descriptor.id, pipuck.descriptor.optitrack_id);
+            pipuck.optitrack_name = name;
+            pipuck.optitrack_suggestion = suggestion;
+        }
+    }
+
+    /// Correlates the stations and switch ports reported by the infrastructure with the MAC addresses
+    /// of the robots
+    fn observe_infrastructure(&self) {
+        for builderbot in self.builderbots.values() {
+            let mut builderbot = builderbot.borrow_mut();
+            let macaddr = builderbot.descriptor.duovero_macaddr;
+            builderbot.access_point = self.infrastructure.station(macaddr).cloned();
+            builderbot.switch_port = self.infrastructure.port(macaddr).cloned();
+        }
+        for drone in self.drones.values() {
+            let mut drone = drone.borrow_mut();
+            let macaddr = drone.descriptor.upcore_macaddr;
+            drone.access_point = self.infrastructure.station(macaddr).cloned();
+            drone.switch_port = self.infrastructure.port(macaddr).cloned();
+        }
+        for pipuck in self.pipucks.values() {
+            let mut pipuck = pipuck.borrow_mut();
+            let macaddr = pipuck.descriptor.rpi_macaddr;
+            pipuck.access_point = self.infrastructure.station(macaddr).cloned();
+            pipuck.switch_port = self.infrastructure.port(macaddr).cloned();
+        }
+    }
+
     fn render_hero(&self) -> Html {
+        let (hero_class, subtitle) = match &self.maintenance {
+            Some(paused_addrs) => {
+                let paused_addrs = match paused_addrs.len() {
+                    0 => t!("header.maintenance.all"),
+                    _ => paused_addrs.iter().map(|addr| addr.to_string()).collect::<Vec<_>>().join(", "),
+                };
+                ("is-warning", html! {
+                    <p class="subtitle is-5">{ t!("header.maintenance", addresses = paused_addrs) }</p>
+                })
+            },
+            None => ("is-link", html! {}),
+        };
+        let toggle_maintenance_onclick = self.link.callback(|_| Msg::ToggleMaintenance);
         html!{
-            <section class="hero is-link">
+            <section class=classes!("hero", hero_class)>
                 <div class="hero-body">
                     <div class="columns is-vcentered">
                         <div class="column is-narrow">
@@ -303,19 +1305,579 @@ impl UserInterface {
                             </figure>
                         </div>
                         <div class="column">
-                            <p class="title is-2">{ "Supervisor" }</p>
+                            <p class="title is-2">{ t!("header.title") }</p>
+                            { subtitle }
+                        </div>
+                        { self.render_replication() }
+                        { self.render_access() }
+                        <div class=classes!("column", "is-narrow",
+                            (!access::permitted(Category::Configuration)).then(|| "is-hidden"))>
+                            <div class="field has-addons">
+                                <div class="control">
+                                    <input ref=self.maintenance_input.clone() class="input" type="text"
+                                        placeholder=t!("header.maintenance.placeholder")
+                                        disabled=self.maintenance.is_some() />
+                                </div>
+                                <div class="control">
+                                    <button class="button is-dark" onclick=toggle_maintenance_onclick> {
+                                        match self.maintenance {
+                                            Some(_) => t!("header.maintenance.exit"),
+                                            None => t!("header.maintenance.enter"),
+                                        }
+                                    } </button>
+                                </div>
+                            </div>
                         </div>
                     </div>
+                    { self.render_health() }
                 </div>
             </section>
         }
     }
 
+    /// The discrepancies between the Xbees of the drones and the profile in the configuration as found by the
+    /// last audit of each drone
+    fn render_xbee_audit(&self) -> Html {
+        let mut drones = self.drones.iter().collect::<Vec<_>>();
+        drones.sort_by(|(left, _), (right, _)| left.cmp(right));
+        let onclick = self.link.callback(|_| Msg::SendRequest(shared::BackEndRequest::XbeeAuditRequest, None));
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("diagnostics.xbee_audit") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("diagnostics.xbee_audit.description") }</p>
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    drones.into_iter().map(|(id, drone)| {
+                                        let drone = drone.borrow();
+                                        let result = match drone.xbee_audit.as_ref() {
+                                            None => html! {
+                                                <span class="tag">{ t!("diagnostics.xbee_audit.none") }</span>
+                                            },
+                                            Some(audit) => {
+                                                let discrepancies = audit.discrepancies()
+                                                    .map(ToString::to_string)
+                                                    .collect::<Vec<_>>();
+                                                match discrepancies.len() {
+                                                    0 => html! {
+                                                        <span class="tag is-success" title=audit.time.clone()>
+                                                            { t!("diagnostics.xbee_audit.matches") }
+                                                        </span>
+                                                    },
+                                                    _ => html! {
+                                                        <span class="has-text-danger" title=audit.time.clone()>
+                                                            { discrepancies.join(", ") }
+                                                        </span>
+                                                    },
+                                                }
+                                            },
+                                        };
+                                        html! {
+                                            <tr>
+                                                <td>{ id }</td>
+                                                <td>{ result }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                    <footer class="card-footer">
+                        <a class="card-footer-item" onclick=onclick>{ t!("diagnostics.xbee_audit.run") }</a>
+                    </footer>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_identification(&self) -> Html {
+        let start = self.link.callback(|_| Msg::SendRequest(shared::BackEndRequest::IdentificationRequest(None), None));
+        let apply = self.link.callback(|_| Msg::ApplyIdentification);
+        let proposals = self.identification.robots.iter().any(|robot| robot.proposal().is_some());
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("diagnostics.identification") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("diagnostics.identification.description") }</p> {
+                                match &self.identification.current {
+                                    Some(robot) => html! {
+                                        <p class="has-text-info">{ t!("diagnostics.identification.current", robot = robot) }</p>
+                                    },
+                                    None => html! {},
+                                }
+                            }
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    self.identification.robots.iter().map(|robot| {
+                                        let configured = robot.configured
+                                            .map_or_else(|| "-".to_owned(), |id| id.to_string());
+                                        let result = match (&robot.observed, &robot.error) {
+                                            (_, Some(error)) => html! {
+                                                <span class="tag is-warning" title=error.clone()>
+                                                    { t!("diagnostics.identification.failed") }
+                                                </span>
+                                            },
+                                            (Some((id, rotation)), None) => match robot.proposal() {
+                                                Some(_) => html! {
+                                                    <span class="tag is-danger" title=format!("{:.0}°", rotation)>
+                                                        { t!("diagnostics.identification.mismatch", id = id) }
+                                                    </span>
+                                                },
+                                                None => html! {
+                                                    <span class="tag is-success" title=format!("{:.0}°", rotation)>
+                                                        { t!("diagnostics.identification.matches") }
+                                                    </span>
+                                                },
+                                            },
+                                            (None, None) => html! {},
+                                        };
+                                        html! {
+                                            <tr>
+                                                <td>{ &robot.id }</td>
+                                                <td>{ configured }</td>
+                                                <td>{ result }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                    <footer class="card-footer"> {
+                        match (access::permitted(Category::Experiment), &self.identification.current) {
+                            (true, None) => html! {
+                                <a class="card-footer-item" onclick=start>{ t!("diagnostics.identification.run") }</a>
+                            },
+                            _ => html! {
+                                <span class="card-footer-item has-text-grey-light">{ t!("diagnostics.identification.run") }</span>
+                            },
+                        }
+                    } {
+                        match proposals && access::permitted(Category::Configuration) {
+                            true => html! {
+                                <a class="card-footer-item" onclick=apply>{ t!("diagnostics.identification.apply") }</a>
+                            },
+                            false => html! {},
+                        }
+                    } </footer>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_traces(&self) -> Html {
+        let oninput = self.link.callback(|data: InputData| Msg::SetTraceQuery(data.value));
+        let onclick = self.link.callback(|_| Msg::ShowTrace);
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("diagnostics.trace") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("diagnostics.trace.description") }</p>
+                            <div class="field has-addons">
+                                <div class="control is-expanded">
+                                    <input class="input is-family-monospace" type="text" value=self.trace_query.clone()
+                                        placeholder=t!("diagnostics.trace.placeholder") oninput=oninput />
+                                </div>
+                                <div class="control">
+                                    <button class="button is-info" onclick=onclick>{ t!("diagnostics.trace.show") }</button>
+                                </div>
+                            </div>
+                            { self.render_trace() }
+                            <table class="table is-fullwidth is-hoverable is-narrow">
+                                <tbody> {
+                                    self.recent_requests.iter().map(|(id, summary, result)| {
+                                        let id = *id;
+                                        let onclick = self.link.callback(move |_| Msg::RequestTrace(id));
+                                        let status = match result {
+                                            None => html! { <span class="tag">{ t!("diagnostics.trace.pending") }</span> },
+                                            Some(Ok(_)) => html! { <span class="tag is-success">{ t!("diagnostics.trace.ok") }</span> },
+                                            Some(Err(error)) => html! {
+                                                <span class="tag is-danger" title=error.clone()>{ t!("diagnostics.trace.error") }</span>
+                                            },
+                                        };
+                                        html! {
+                                            <tr>
+                                                <td><a class="is-family-monospace" onclick=onclick>{ id.to_string() }</a></td>
+                                                <td>{ summary }</td>
+                                                <td>{ status }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_trace(&self) -> Html {
+        match &self.trace {
+            None => html! {},
+            Some(Err(error)) => html! {
+                <p class="has-text-danger">{ error }</p>
+            },
+            Some(Ok(trace)) => html! {
+                <table class="table is-fullwidth is-hoverable is-narrow">
+                    <thead>
+                        <tr>
+                            <th colspan="3" title=trace.received.clone()>{ &trace.request }</th>
+                        </tr>
+                    </thead>
+                    <tbody> {
+                        trace.spans.iter().map(|span| html! {
+                            <tr>
+                                <td class="is-family-monospace">{ format!("+{:.1} ms", span.elapsed) }</td>
+                                <td>{ &span.component }</td>
+                                <td>{ &span.description }</td>
+                            </tr>
+                        }).collect::<Html>()
+                    } </tbody>
+                </table>
+            },
+        }
+    }
+
+    fn render_access(&self) -> Html {
+        let login_onclick = self.link.callback(|_| Msg::Login(true));
+        let logout_onclick = self.link.callback(|_| Msg::Login(false));
+        match &self.access {
+            Some(access) => html! {
+                <div class="column is-narrow">
+                    <div class="field is-grouped">
+                        <div class="control">
+                            <span class="tag is-medium is-light" title=t!("header.access.description")> {
+                                match &access.user {
+                                    Some(user) => t!("header.access.user", user = user, role = access.role.name()),
+                                    None => t!("header.access.role", role = access.role.name()),
+                                }
+                            } </span>
+                        </div> {
+                            match access.user {
+                                Some(_) => html! {
+                                    <div class="control">
+                                        <button class="button is-small is-dark" onclick=logout_onclick>
+                                            { t!("header.access.logout") }
+                                        </button>
+                                    </div>
+                                },
+                                None => html! {},
+                            }
+                        }
+                    </div>
+                    <div class="field has-addons">
+                        <div class="control">
+                            <input ref=self.login_input.clone() class="input is-small" type="password"
+                                placeholder=t!("header.access.token") />
+                        </div>
+                        <div class="control">
+                            <button class="button is-small is-dark" onclick=login_onclick>
+                                { t!("header.access.login") }
+                            </button>
+                        </div>
+                    </div> {
+                        match &self.login_error {
+                            Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                            None => html! {},
+                        }
+                    }
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    /// The clients that are connected to the supervisor, so that the operators know who else is observing or
+    /// controlling the experiment
+    fn render_quarantine(&self) -> Html {
+        let mut xbee_robots = self.drones.keys().cloned().collect::<Vec<_>>();
+        xbee_robots.sort();
+        let mut fernbedienung_robots = self.builderbots.keys()
+            .chain(self.drones.keys())
+            .chain(self.pipucks.keys())
+            .cloned()
+            .collect::<Vec<_>>();
+        fernbedienung_robots.sort();
+        html! {
+            <quarantine::Interface parent=self.link.clone()
+                devices=self.quarantine.clone()
+                xbee_robots=xbee_robots
+                fernbedienung_robots=fernbedienung_robots />
+        }
+    }
+
+    fn render_sessions(&self) -> Html {
+        html! {
+            <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("diagnostics.sessions") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("diagnostics.sessions.description") }</p>
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    self.sessions.iter().map(|session| {
+                                        let connected = js_sys::Date::new(&session.connected.as_str().into())
+                                            .to_locale_time_string("default");
+                                        html! {
+                                            <tr>
+                                                <td>{ session.user.as_deref().unwrap_or("-") }</td>
+                                                <td>
+                                                    <span class="tag is-light">{ session.role.name() }</span>
+                                                </td>
+                                                <td title=t!("diagnostics.sessions.connected")>{ String::from(connected) }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_replication(&self) -> Html {
+        use shared::{experiment::State, replication::Role};
+        let (class, label, description) = match &self.replication {
+            Some(Role::Primary { standbys }) => {
+                let standbys = standbys.iter().map(|addr| addr.to_string()).collect::<Vec<_>>();
+                let class = if standbys.is_empty() { "is-warning" } else { "is-success" };
+                let label = t!("header.replication.primary", standbys = standbys.len());
+                (class, label, t!("header.replication.primary.description", standbys = standbys.join(", ")))
+            },
+            Some(Role::Standby { primary, connected, unresponsive, status }) => {
+                let state = match &status.state {
+                    State::Idle => t!("experiment.status.idle"),
+                    State::Uploading => t!("experiment.status.uploading"),
+                    State::Ready => t!("experiment.status.ready"),
+                    State::Running => t!("experiment.status.running"),
+                    State::Stopping => t!("experiment.status.stopping"),
+                    State::Finished => t!("experiment.status.finished"),
+                    State::Error(_) => t!("experiment.status.error"),
+                };
+                /* the takeover is confirmed by the operator once the primary has been shut down */
+                if *unresponsive {
+                    let onclick = self.link.callback(|_| Msg::SendRequest(shared::BackEndRequest::TakeOverRequest, None));
+                    return html! {
+                        <div class="column is-narrow">
+                            <div class="field has-addons">
+                                <div class="control">
+                                    <span class="tag is-medium is-danger"
+                                        title=t!("header.replication.standby.description", state = state)>
+                                        { t!("header.replication.standby.unresponsive", primary = primary) }
+                                    </span>
+                                </div>
+                                <div class="control">
+                                    <button class="button is-small is-danger is-outlined" onclick=onclick
+                                        title=t!("header.replication.take_over.description")>
+                                        { t!("header.replication.take_over") }
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    };
+                }
+                let (class, label) = match connected {
+                    true => ("is-info", t!("header.replication.standby", primary = primary)),
+                    false => ("is-warning", t!("header.replication.standby.disconnected", primary = primary)),
+                };
+                (class, label, t!("header.replication.standby.description", state = state))
+            },
+            Some(Role::TakenOver { primary, time, .. }) => {
+                let time = String::from(js_sys::Date::new(&time.as_str().into()).to_locale_time_string("default"));
+                ("is-danger", t!("header.replication.taken_over", primary = primary, time = time),
+                    t!("header.replication.taken_over.description"))
+            },
+            Some(Role::Superseded { standby, time }) => {
+                let time = String::from(js_sys::Date::new(&time.as_str().into()).to_locale_time_string("default"));
+                ("is-danger", t!("header.replication.superseded", standby = standby, time = time),
+                    t!("header.replication.superseded.description"))
+            },
+            None => return html! {},
+        };
+        html! {
+            <div class="column is-narrow">
+                <span class=classes!("tag", "is-medium", class) title=description>{ label }</span>
+            </div>
+        }
+    }
+
+    /// A strip with the status of the backend subsystems, which is only shown once a subsystem has failed
+    fn render_health(&self) -> Html {
+        use shared::health::Status;
+        if self.health.iter().all(|subsystem| subsystem.status == Status::Running && subsystem.restarts == 0) {
+            return html! {};
+        }
+        html! {
+            <div class="tags"> {
+                self.health.iter().map(|subsystem| {
+                    let (class, status) = match &subsystem.status {
+                        Status::Running => ("is-success", t!("header.health.running")),
+                        Status::Restarting { error, delay } =>
+                            ("is-danger", t!("header.health.restarting", delay = delay, error = error)),
+                        Status::Stopped => ("is-dark", t!("header.health.stopped")),
+                    };
+                    let title = t!("header.health.description", status = status, restarts = subsystem.restarts);
+                    html! {
+                        <span class=classes!("tag", class) title=title>{ &subsystem.name }</span>
+                    }
+                }).collect::<Html>()
+            } </div>
+        }
+    }
+
+    fn render_maintenance_summary(&self) -> Html {
+        if let Some(suppressed_events) = self.maintenance_summary.as_ref() {
+            let dismiss_onclick = self.link.callback(|_| Msg::DismissMaintenanceSummary);
+            html! {
+                <div class="modal is-active">
+                    <div class="modal-background" onclick=dismiss_onclick.clone() />
+                    <div class="modal-card">
+                        <header class="modal-card-head">
+                            <p class="modal-card-title"> { t!("header.maintenance.summary") } </p>
+                        </header>
+                        <section class="modal-card-body">
+                            <ul> {
+                                suppressed_events.iter()
+                                    .map(|event| html! { <li>{ event }</li> })
+                                    .collect::<Html>()
+                            } </ul>
+                        </section>
+                        <footer class="modal-card-foot">
+                            <button class="button" onclick=dismiss_onclick>{ t!("common.dismiss") }</button>
+                        </footer>
+                    </div>
+                </div>
+            }
+        }
+        else {
+            html! {}
+        }
+    }
+
+    /// Renders the cards of the simulated robots of the given type, these are shown after the real robots
+    fn render_simulated_robots(&self, robot: &str) -> Html {
+        self.simulated_robots.iter()
+            .filter(|simulated| simulated.descriptor.robot == robot)
+            .map(|simulated| html! {
+                <div class="column is-full-mobile is-full-tablet is-full-desktop is-half-widescreen is-one-third-fullhd">
+                    <simulation::Card key=simulated.descriptor.id.clone() robot=simulated.clone() />
+                </div>
+            }).collect::<Html>()
+    }
+
+    /// Fails the pending requests and schedules an attempt to reconnect to the supervisor
+    fn disconnected(&mut self) {
+        self.socket = None;
+        for (_, callback) in self.requests.drain() {
+            callback.emit(Err(t!("request.disconnected")));
+        }
+        if self.reconnect_task.is_none() {
+            let delay = RECONNECT_DELAY.checked_mul(1u32 << self.reconnect_attempts.min(16))
+                .map_or(RECONNECT_DELAY_MAX, |delay| delay.min(RECONNECT_DELAY_MAX));
+            let callback = self.link.callback(|_| Msg::Reconnect);
+            self.reconnect_task = Some(TimeoutService::spawn(delay, callback));
+        }
+    }
+
+    fn render_connection(&self) -> Html {
+        match self.socket {
+            Some(_) => html! {},
+            None => {
+                let onclick = self.link.callback(|_| Msg::Reconnect);
+                html! {
+                    <div class="notification is-danger">
+                        <div class="level">
+                            <div class="level-left">
+                                <p class="level-item">
+                                    { t!("connection.lost", attempts = self.reconnect_attempts) }
+                                </p>
+                            </div>
+                            <div class="level-right">
+                                <button class="level-item button is-small" onclick=onclick>
+                                    { t!("connection.reconnect") }
+                                </button>
+                            </div>
+                        </div>
+                    </div>
+                }
+            },
+        }
+    }
+
+    fn render_association_failures(&self) -> Html {
+        self.association_failures.iter().map(|(addr, failure)| {
+            let message = match failure {
+                association::Failure::Authentication(reason) =>
+                    t!("association.authentication", addr = addr, reason = reason),
+                association::Failure::UnexpectedDevice { robot, macaddr } =>
+                    t!("association.unexpected_device", addr = addr, robot = robot, macaddr = macaddr),
+                association::Failure::UnexpectedAddress { robot, expected } =>
+                    t!("association.unexpected_address", addr = addr, robot = robot, expected = expected),
+            };
+            html! {
+                <div class="notification is-danger">{ message }</div>
+            }
+        }).collect::<Html>()
+    }
+
+    fn render_notifications(&self) -> Html {
+        self.notifications.iter().enumerate().map(|(index, notification)| {
+            let dismiss_onclick = self.link.callback(move |_| Msg::DismissNotification(index));
+            let class = match notification.severity {
+                shared::alert::Severity::Warning => "is-warning",
+                shared::alert::Severity::Critical => "is-danger",
+            };
+            html! {
+                <div class=classes!("notification", class)>
+                    <button class="delete" onclick=dismiss_onclick></button>
+                    { &notification.message }
+                </div>
+            }
+        }).collect::<Html>()
+    }
+
     fn render_tabs(&self) -> Html {
         html! {
             <div class="tabs is-centered is-boxed is-medium">
                 <ul> {
                     Tab::iter()
+                        /* the tab of the ROS robots is only shown if there are any */
+                        .filter(|tab| *tab != Tab::RosRobots || !self.ros_robots.is_empty())
                         .map(|tab| {
                             let li_classes = if self.active_tab == tab {
                                 Some("is-active")
@@ -324,7 +1886,7 @@ impl UserInterface {
                                 None
                             };
                             let i_classes = ["mdi", "mdi-24px", tab.get_str("icon").unwrap()];
-                            let tab_name = tab.as_ref();
+                            let tab_name = tab.label();
                             let onclick = self.link.callback(move |_| Msg::SetActiveTab(tab));
                             html! {
                                 <li class=classes!(li_classes)>
@@ -344,6 +1906,29 @@ impl UserInterface {
     }
 }
 
+/// Connects to the socket of the supervisor, which sends a snapshot of its state to every new connection
+fn connect(link: &ComponentLink<UserInterface>, service_addr: &str) -> Option<WebSocketTask> {
+    let callback_data =
+        link.callback(|data| Msg::WebSocketRxData(data));
+    let callback_notification =
+        link.callback(|notification| Msg::WebSocketNotifcation(notification));
+    match WebSocketService::connect_binary(service_addr, callback_data, callback_notification) {
+        Ok(socket) => Some(socket),
+        Err(_) => {
+            ConsoleService::log("Could not connect to socket");
+            None
+        }
+    }
+}
+
+/// Formats a duration in seconds as days, hours, and minutes for the robot cards
+pub fn format_duration(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86400, (seconds / 3600) % 24, (seconds / 60) % 60);
+    match days {
+        0 => format!("{}h {:02}m", hours, minutes),
+        _ => format!("{}d {}h {:02}m", days, hours, minutes),
+    }
+}
 
 #[wasm_bindgen]
 pub fn launch() -> Result<(), JsValue> {
@@ -0,0 +1,282 @@
+use macaddr::MacAddr6;
+use yew::prelude::*;
+
+use yew::{html, Component, ComponentLink, Html, ShouldRender};
+
+use shared::{BackEndRequest, access::Category, configuration::{self, Configuration}, drone, pairing::{Pairing, Request}};
+
+use crate::{UserInterface, access};
+
+/// A wizard that pairs an unassigned Xbee with an unassigned UpCore and adds the resulting drone to the
+/// configuration. The identifier of each Xbee is read from its pins, while the status LEDs of an UpCore can
+/// be flashed to find the drone that it belongs to
+pub struct Card {
+    link: ComponentLink<Self>,
+    props: Props,
+    xbee: Option<MacAddr6>,
+    upcore: Option<MacAddr6>,
+    id: String,
+    error: Option<String>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub parent: ComponentLink<UserInterface>,
+    pub pairing: Pairing,
+    pub configuration: Option<Configuration>,
+}
+
+pub enum Msg {
+    SelectXbee(MacAddr6),
+    SelectUpCore(MacAddr6),
+    Identify(MacAddr6, bool),
+    SetId(String),
+    Pair,
+    SetResult(Result<(), String>),
+}
+
+impl Component for Card {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Card { link, props, xbee: None, upcore: None, id: String::new(), error: None }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Msg::SelectXbee(macaddr) => {
+                /* suggest an identifier for the drone from the identifier that is wired to the Xbee */
+                let identifier = self.props.pairing.xbees.iter()
+                    .find(|xbee| xbee.macaddr == macaddr)
+                    .and_then(|xbee| xbee.identifier);
+                if let (true, Some(identifier)) = (self.id.is_empty(), identifier) {
+                    self.id = format!("drone{}", identifier);
+                }
+                self.xbee = Some(macaddr);
+            },
+            Msg::SelectUpCore(macaddr) => {
+                self.upcore = Some(macaddr);
+            },
+            Msg::Identify(macaddr, enable) => {
+                let callback = self.link.callback(Msg::SetResult);
+                let request = BackEndRequest::PairingRequest(Request::Identify(macaddr, enable));
+                self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+                return false;
+            },
+            Msg::SetId(id) => {
+                self.id = id;
+            },
+            Msg::Pair => match self.configuration() {
+                Ok(configuration) => {
+                    let callback = self.link.callback(Msg::SetResult);
+                    let request = BackEndRequest::ConfigurationRequest(configuration::Request::Update(configuration));
+                    self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+                    self.xbee = None;
+                    self.upcore = None;
+                    self.id.clear();
+                    self.error = None;
+                },
+                Err(error) => {
+                    self.error = Some(error);
+                }
+            },
+            Msg::SetResult(result) => {
+                self.error = result.err();
+            },
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        /* forget the selected devices once they are no longer visible */
+        if !props.pairing.xbees.iter().any(|xbee| Some(xbee.macaddr) == self.xbee) {
+            self.xbee = None;
+        }
+        if !props.pairing.fernbedienungs.iter().any(|device| Some(device.macaddr) == self.upcore) {
+            self.upcore = None;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let permitted = access::permitted(Category::Configuration);
+        let oninput = self.link.callback(|data: InputData| Msg::SetId(data.value));
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("diagnostics.pairing") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <p class="help">{ t!("diagnostics.pairing.description") }</p>
+                        { self.render_xbees() }
+                        { self.render_upcores(permitted) }
+                        <div class="field">
+                            <label class="label">{ t!("diagnostics.pairing.id") }</label>
+                            <div class="control">
+                                <input class="input" type="text" value=self.id.clone() oninput=oninput />
+                            </div>
+                        </div> {
+                            match (self.xbee, self.upcore) {
+                                (Some(xbee), Some(upcore)) => html! {
+                                    <pre>{
+                                        format!("<drone id=\"{}\" xbee_macaddr=\"{}\" upcore_macaddr=\"{}\" />",
+                                            self.id.trim(), xbee, upcore)
+                                    }</pre>
+                                },
+                                _ => html! {},
+                            }
+                        } {
+                            match &self.error {
+                                Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                None => html! {},
+                            }
+                        }
+                    </div>
+                </div>
+                <footer class="card-footer"> {
+                    match (permitted, self.xbee.is_some() && self.upcore.is_some()) {
+                        (true, true) => html! {
+                            <a class="card-footer-item" onclick=self.link.callback(|_| Msg::Pair)>
+                                { t!("diagnostics.pairing.add") }
+                            </a>
+                        },
+                        (false, _) => html! {
+                            <span class="card-footer-item has-text-grey-light"
+                                  title=t!("access.forbidden")>{ t!("diagnostics.pairing.add") }</span>
+                        },
+                        (true, false) => html! {
+                            <span class="card-footer-item has-text-grey-light"
+                                  title=t!("diagnostics.pairing.select")>{ t!("diagnostics.pairing.add") }</span>
+                        },
+                    }
+                } </footer>
+            </div>
+        }
+    }
+}
+
+impl Card {
+    /// The configuration with the paired drone added to it
+    fn configuration(&self) -> Result<Configuration, String> {
+        let mut configuration = self.props.configuration.clone()
+            .ok_or_else(|| t!("settings.none"))?;
+        let (xbee_macaddr, upcore_macaddr) = match (self.xbee, self.upcore) {
+            (Some(xbee), Some(upcore)) => (xbee, upcore),
+            _ => return Err(t!("diagnostics.pairing.select")),
+        };
+        let id = self.id.trim().to_owned();
+        let taken = configuration.builderbots.iter().any(|desc| desc.id == id) ||
+            configuration.drones.iter().any(|desc| desc.id == id) ||
+            configuration.pipucks.iter().any(|desc| desc.id == id) ||
+            configuration.ros_robots.iter().any(|desc| desc.id == id);
+        if id.is_empty() || taken {
+            return Err(t!("diagnostics.pairing.invalid", id = id));
+        }
+        configuration.drones.push(drone::Descriptor {
+            id,
+            xbee_macaddr,
+            upcore_macaddr,
+            upcore_addr: None,
+            optitrack_id: None,
+            xbee_configuration: None,
+        });
+        Ok(configuration)
+    }
+
+    fn render_xbees(&self) -> Html {
+        html! {
+            <>
+                <label class="label">{ t!("diagnostics.pairing.xbees") }</label> {
+                    match self.props.pairing.xbees.is_empty() {
+                        true => html! { <p>{ t!("diagnostics.pairing.none") }</p> },
+                        false => html! {
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    self.props.pairing.xbees.iter().map(|xbee| {
+                                        let macaddr = xbee.macaddr;
+                                        let onclick = self.link.callback(move |_| Msg::SelectXbee(macaddr));
+                                        let identifier = xbee.identifier
+                                            .map_or_else(|| "-".to_owned(), |identifier| identifier.to_string());
+                                        html! {
+                                            <tr>
+                                                <td>
+                                                    <input type="radio" checked=self.xbee == Some(macaddr) onclick=onclick />
+                                                </td>
+                                                <td>{ macaddr.to_string() }</td>
+                                                <td>{ xbee.addr.to_string() }</td>
+                                                <td title=t!("diagnostics.pairing.identifier")>{ identifier }</td>
+                                                <td>{ render_conflict(&xbee.conflict) }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        },
+                    }
+                }
+            </>
+        }
+    }
+
+    fn render_upcores(&self, permitted: bool) -> Html {
+        html! {
+            <>
+                <label class="label">{ t!("diagnostics.pairing.upcores") }</label> {
+                    match self.props.pairing.fernbedienungs.is_empty() {
+                        true => html! { <p>{ t!("diagnostics.pairing.none") }</p> },
+                        false => html! {
+                            <table class="table is-fullwidth is-hoverable">
+                                <tbody> {
+                                    self.props.pairing.fernbedienungs.iter().map(|device| {
+                                        let macaddr = device.macaddr;
+                                        let identifying = device.identifying;
+                                        let onclick = self.link.callback(move |_| Msg::SelectUpCore(macaddr));
+                                        let identify = self.link.callback(move |_| Msg::Identify(macaddr, !identifying));
+                                        let label = match identifying {
+                                            true => t!("diagnostics.pairing.identify.stop"),
+                                            false => t!("diagnostics.pairing.identify"),
+                                        };
+                                        html! {
+                                            <tr>
+                                                <td>
+                                                    <input type="radio" checked=self.upcore == Some(macaddr) onclick=onclick />
+                                                </td>
+                                                <td>{ macaddr.to_string() }</td>
+                                                <td>{ device.addr.to_string() }</td>
+                                                <td>
+                                                    <button class=classes!("button", "is-small", identifying.then(|| "is-info"))
+                                                            disabled=!permitted onclick=identify>{ label }</button>
+                                                </td>
+                                                <td>{ render_conflict(&device.conflict) }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                } </tbody>
+                            </table>
+                        },
+                    }
+                }
+            </>
+        }
+    }
+}
+
+/// Warns that a device claims the MAC address of a robot that is associated with another device, e.g., because
+/// the SD card of the robot was cloned
+fn render_conflict(conflict: &Option<String>) -> Html {
+    match conflict {
+        Some(robot) => html! {
+            <span class="tag is-warning" title=t!("diagnostics.pairing.conflict.description")>
+                { t!("diagnostics.pairing.conflict", id = robot) }
+            </span>
+        },
+        None => html! {},
+    }
+}
@@ -0,0 +1,60 @@
+use shared::BackEndRequest;
+use strum::IntoEnumIterator;
+
+use crate::Tab;
+
+/// A command that can be executed from the command palette (Ctrl+K) or via a keyboard shortcut
+#[derive(Clone)]
+pub enum Command {
+    SetActiveTab(Tab),
+    /* switch to the tab of a robot and scroll its card into view */
+    FocusRobot(Tab, String),
+    OpenTerminal(Tab, String),
+    /* any request that would otherwise be sent via Msg::SendRequest */
+    Request {
+        label: String,
+        shortcut: Option<&'static str>,
+        request: BackEndRequest,
+    },
+}
+
+impl Command {
+    pub fn label(&self) -> String {
+        match self {
+            Command::SetActiveTab(tab) => t!("palette.switch", tab = tab.label()),
+            Command::FocusRobot(_, id) => t!("palette.focus", robot = id),
+            Command::OpenTerminal(_, id) => t!("palette.terminal", robot = id),
+            Command::Request { label, .. } => label.clone(),
+        }
+    }
+
+    pub fn shortcut(&self) -> Option<String> {
+        match self {
+            Command::SetActiveTab(tab) => Tab::iter()
+                .position(|other| other == *tab)
+                .filter(|&index| index < 9)
+                .map(|index| format!("Alt+{}", index + 1)),
+            Command::Request { shortcut, .. } => shortcut.map(str::to_owned),
+            _ => None,
+        }
+    }
+
+    /// Checks whether all of the whitespace-separated terms in the query appear in the label
+    pub fn matches(&self, query: &str) -> bool {
+        let label = self.label().to_lowercase();
+        query.split_whitespace()
+            .all(|term| label.contains(&term.to_lowercase()))
+    }
+}
+
+/// The state of the command palette while it is open
+#[derive(Default)]
+pub struct Palette {
+    pub query: String,
+    pub selected: usize,
+}
+
+/// The id of the element that contains the card of a robot
+pub fn robot_element_id(tab: Tab, id: &str) -> String {
+    format!("{}-{}", tab.as_ref(), id)
+}
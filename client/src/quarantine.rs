@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap};
+use macaddr::MacAddr6;
+use yew::prelude::*;
+
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+
+use shared::{BackEndRequest, access::Category, association::{Kind, Request, UnknownDevice}};
+
+use crate::{UserInterface, access};
+
+/// Lists the devices that responded on the robot network but did not match any robot in the configuration.
+/// Each device can be adopted by a robot, which replaces the corresponding device of that robot until the
+/// supervisor is restarted, or ignored
+pub struct Interface {
+    link: ComponentLink<Self>,
+    props: Props,
+    /* the robot that is selected for adopting each device */
+    selected: HashMap<MacAddr6, String>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub parent: ComponentLink<UserInterface>,
+    pub devices: BTreeMap<MacAddr6, UnknownDevice>,
+    /* the robots that can adopt an Xbee, i.e., the drones */
+    pub xbee_robots: Vec<String>,
+    /* the robots that can adopt a device running the fernbedienung service */
+    pub fernbedienung_robots: Vec<String>,
+}
+
+pub enum Msg {
+    Select(MacAddr6, Option<String>),
+    Adopt(MacAddr6),
+    Ignore(MacAddr6),
+    SetResult(Result<(), String>),
+}
+
+impl Component for Interface {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Interface { link, props, selected: HashMap::new(), error: None }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Msg::Select(macaddr, Some(robot)) => {
+                self.selected.insert(macaddr, robot);
+            },
+            Msg::Select(macaddr, None) => {
+                self.selected.remove(&macaddr);
+            },
+            Msg::Adopt(macaddr) => match self.selected.get(&macaddr) {
+                Some(robot) => self.send(Request::Adopt(macaddr, robot.clone())),
+                None => self.error = Some(t!("quarantine.select")),
+            },
+            Msg::Ignore(macaddr) => self.send(Request::Ignore(macaddr)),
+            Msg::SetResult(result) => {
+                self.error = result.err();
+            },
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        /* forget the selections for the devices that are no longer quarantined */
+        self.selected.retain(|macaddr, _| props.devices.contains_key(macaddr));
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class="column is-full">
+                <div class="card">
+                    <header class="card-header">
+                        <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item subtitle is-size-4">{ t!("quarantine.title") }</p>
+                            </div>
+                        </nav>
+                    </header>
+                    <div class="card-content">
+                        <div class="content">
+                            <p class="help">{ t!("quarantine.description") }</p> {
+                                match self.props.devices.is_empty() {
+                                    true => html! { <p>{ t!("quarantine.none") }</p> },
+                                    false => self.render_devices(),
+                                }
+                            } {
+                                match &self.error {
+                                    Some(error) => html! { <p class="help is-danger">{ error }</p> },
+                                    None => html! {},
+                                }
+                            }
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Interface {
+    fn send(&mut self, request: Request) {
+        let callback = self.link.callback(Msg::SetResult);
+        let request = BackEndRequest::AssociationRequest(request);
+        self.props.parent.send_message(crate::Msg::SendRequest(request, Some(callback)));
+        self.error = None;
+    }
+
+    fn render_devices(&self) -> Html {
+        let permitted = access::permitted(Category::Configuration);
+        html! {
+            <table class="table is-fullwidth is-hoverable">
+                <thead>
+                    <tr>
+                        <th>{ t!("quarantine.macaddr") }</th>
+                        <th>{ t!("quarantine.kind") }</th>
+                        <th>{ t!("quarantine.addr") }</th>
+                        <th>{ t!("quarantine.robot") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody> {
+                    self.props.devices.values().map(|device| {
+                        let macaddr = device.macaddr;
+                        let (kind, robots) = match device.kind {
+                            Kind::Xbee => ("Xbee", &self.props.xbee_robots),
+                            Kind::Fernbedienung => ("Fernbedienung", &self.props.fernbedienung_robots),
+                        };
+                        let selected = self.selected.get(&macaddr);
+                        let onchange = self.link.callback(move |value| match value {
+                            ChangeData::Select(select) if !select.value().is_empty() => Msg::Select(macaddr, Some(select.value())),
+                            _ => Msg::Select(macaddr, None),
+                        });
+                        let adopt = self.link.callback(move |_| Msg::Adopt(macaddr));
+                        let ignore = self.link.callback(move |_| Msg::Ignore(macaddr));
+                        html! {
+                            <tr>
+                                <td>{ macaddr.to_string() }</td>
+                                <td>{ kind }</td>
+                                <td>{ device.addr.to_string() }</td>
+                                <td>
+                                    <div class="select is-small">
+                                        <select onchange=onchange disabled=!permitted>
+                                            <option value="" selected=selected.is_none()>{ t!("quarantine.select") }</option> {
+                                                robots.iter().map(|robot| html! {
+                                                    <option value=robot.clone() selected=selected == Some(robot)>{ robot }</option>
+                                                }).collect::<Html>()
+                                            }
+                                        </select>
+                                    </div>
+                                </td>
+                                <td>
+                                    <div class="buttons are-small">
+                                        <button class="button is-primary" disabled=!permitted || selected.is_none()
+                                                onclick=adopt>{ t!("quarantine.adopt") }</button>
+                                        <button class="button" disabled=!permitted
+                                                onclick=ignore>{ t!("quarantine.ignore") }</button>
+                                    </div>
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                } </tbody>
+            </table>
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use std::{cell::RefCell, collections::{BTreeMap, HashMap}, rc::Rc, time::Duration};
+use yew::prelude::*;
+
+use yew::services::interval::{IntervalService, IntervalTask};
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+use yew::{html, ChangeData, Component, ComponentLink, Html, ShouldRender};
+
+use shared::experiment::TimelineEvent;
+use shared::journal::{Entry, Event};
+
+use crate::experiment::timeline::{category_class, category_name, format_timestamp, has_tag, render_filter, render_tags};
+
+/* the interval at which the replay advances while it is playing */
+const TICK: Duration = Duration::from_millis(100);
+/* the playback speeds that can be selected */
+const SPEEDS: &[f64] = &[0.5, 1.0, 2.0, 5.0, 10.0];
+/* the margin around the tracked positions on the map and the radius of the markers in meters */
+const MARGIN: f64 = 0.25;
+const MARKER_RADIUS: f64 = 0.05;
+
+/// A robot as described by the header of the journal
+struct Robot {
+    id: String,
+    kind: &'static str,
+    optitrack_id: Option<i32>,
+}
+
+/// The position of a rigid body and its rotation around the vertical axis
+#[derive(Clone, Copy)]
+struct Pose {
+    position: [f64; 3],
+    yaw: f64,
+}
+
+/// The parts of a journal that can be replayed, each with the timestamps of the entries in milliseconds since
+/// the journal was started
+#[derive(Default)]
+pub struct Journal {
+    name: String,
+    supervisor_version: Option<String>,
+    robots: Vec<Robot>,
+    frames: Vec<(i64, BTreeMap<i32, Pose>)>,
+    /* the lines written by ARGoS on each robot and whether they were written to standard error */
+    output: HashMap<String, Vec<(i64, (bool, String))>>,
+    /* the voltages of the cells of the batteries of the drones in millivolts */
+    batteries: HashMap<String, Vec<(i64, Vec<u16>)>>,
+    messages: Vec<i64>,
+    timeline: Vec<TimelineEvent>,
+    duration: i64,
+    /* the bounds of the tracked positions as the minimum and the maximum x and y coordinates */
+    bounds: Option<([f64; 2], [f64; 2])>,
+    /* the entries that were recognized but could not be interpreted */
+    skipped: usize,
+}
+
+impl Journal {
+    /// Reads the entries of a journal that was recorded as a pickle or as JSON Lines with the decoder that is
+    /// shared with the supervisor
+    fn parse(name: String, content: &[u8]) -> Result<Journal, String> {
+        let decoded = match shared::journal::decode(content) {
+            Ok(decoded) => decoded,
+            Err(shared::journal::Error::Csv) => return Err(t!("replay.csv", name = name)),
+        };
+        if decoded.entries.is_empty() && decoded.skipped.is_empty() {
+            return Err(t!("replay.invalid", name = name));
+        }
+        let mut journal = Journal { name, skipped: decoded.skipped.len(), ..Default::default() };
+        journal.duration = decoded.skipped.into_iter().max().unwrap_or_default();
+        for entry in decoded.entries {
+            journal.record(entry);
+        }
+        Ok(journal)
+    }
+
+    fn record(&mut self, Entry { timestamp, event }: Entry) {
+        self.duration = self.duration.max(timestamp);
+        match event {
+            Event::Header(header) => {
+                self.supervisor_version = header.supervisor_version;
+                let builderbots = header.builderbots.into_iter()
+                    .map(|descriptor| Robot { id: descriptor.id, kind: "builderbot", optitrack_id: descriptor.optitrack_id });
+                let drones = header.drones.into_iter()
+                    .map(|descriptor| Robot { id: descriptor.id, kind: "drone", optitrack_id: descriptor.optitrack_id });
+                let pipucks = header.pipucks.into_iter()
+                    .map(|descriptor| Robot { id: descriptor.id, kind: "pipuck", optitrack_id: descriptor.optitrack_id });
+                self.robots.extend(builderbots.chain(drones).chain(pipucks));
+            },
+            Event::TrackingSystem { updates, .. } => {
+                let mut poses = BTreeMap::new();
+                for update in updates {
+                    let position = [update.position[0] as f64, update.position[1] as f64, update.position[2] as f64];
+                    let [w, x, y, z] = update.orientation;
+                    let (w, x, y, z) = (w as f64, x as f64, y as f64, z as f64);
+                    let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+                    let ([min_x, min_y], [max_x, max_y]) = self.bounds
+                        .get_or_insert(([position[0], position[1]], [position[0], position[1]]));
+                    *min_x = min_x.min(position[0]);
+                    *min_y = min_y.min(position[1]);
+                    *max_x = max_x.max(position[0]);
+                    *max_y = max_y.max(position[1]);
+                    poses.insert(update.id, Pose { position, yaw });
+                }
+                self.frames.push((timestamp, poses));
+            },
+            Event::ARGoS { robot, error, data } => {
+                let lines = self.output.entry(robot).or_default();
+                lines.extend(String::from_utf8_lossy(&data).lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| (timestamp, (error, line.to_owned()))));
+            },
+            Event::Message(_) => self.messages.push(timestamp),
+            Event::Timeline(category, description, tags) =>
+                self.timeline.push(TimelineEvent { timestamp, category, description, tags }),
+            Event::BatteryCells(robot, cells) =>
+                self.batteries.entry(robot).or_default().push((timestamp, cells)),
+            /* the other events describe the experiment as a whole and are not replayed */
+            Event::Other => {},
+        }
+    }
+
+    /// The last frame from the tracking system at the given time
+    fn frame(&self, time: i64) -> Option<&BTreeMap<i32, Pose>> {
+        let count = self.frames.partition_point(|(timestamp, _)| *timestamp <= time);
+        count.checked_sub(1).map(|index| &self.frames[index].1)
+    }
+}
+
+/// The entries that were recorded up to the given time
+fn until<T>(entries: &[(i64, T)], time: i64) -> &[(i64, T)] {
+    &entries[..entries.partition_point(|(timestamp, _)| *timestamp <= time)]
+}
+
+fn kind_color(kind: &str) -> &'static str {
+    match kind {
+        "builderbot" => "hsl(217, 71%, 53%)",
+        "drone" => "hsl(141, 71%, 48%)",
+        _ => "hsl(348, 100%, 61%)",
+    }
+}
+
+/// The loaded journal and the position of the replay, these are kept by the user interface so that they
+/// survive switching tabs
+pub struct Replay {
+    journal: Option<Result<Journal, String>>,
+    /* the position of the replay in milliseconds since the journal was started */
+    time: i64,
+    speed: f64,
+    /* only the events on the timeline with this tag are shown if set */
+    filter: Option<String>,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Replay { journal: None, time: 0, speed: 1.0, filter: None }
+    }
+}
+
+pub struct Interface {
+    link: ComponentLink<Self>,
+    props: Props,
+    reader_task: Option<ReaderTask>,
+    /* the replay advances while this task is running */
+    interval_task: Option<IntervalTask>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub replay: Rc<RefCell<Replay>>,
+}
+
+pub enum Msg {
+    ReadJournal(Vec<File>),
+    LoadJournal(FileData),
+    Play,
+    Pause,
+    Tick,
+    Seek(i64),
+    SetSpeed(f64),
+    Filter(Option<String>),
+}
+
+impl Component for Interface {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Interface {
+            link,
+            props,
+            reader_task: None,
+            interval_task: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let mut replay = self.props.replay.borrow_mut();
+        /* borrow the fields of the replay separately */
+        let replay = &mut *replay;
+        match msg {
+            Msg::ReadJournal(files) => if let Some(file) = files.into_iter().next() {
+                let callback = self.link.callback(Msg::LoadJournal);
+                self.reader_task = ReaderService::read_file(file, callback).ok();
+                self.interval_task = None;
+                return false;
+            },
+            Msg::LoadJournal(FileData { name, content }) => {
+                self.reader_task = None;
+                replay.journal = Some(Journal::parse(name, &content));
+                replay.time = 0;
+            },
+            Msg::Play => {
+                /* start from the beginning if the end has been reached */
+                if let Some(Ok(journal)) = &replay.journal {
+                    if replay.time >= journal.duration {
+                        replay.time = 0;
+                    }
+                }
+                let callback = self.link.callback(|_| Msg::Tick);
+                self.interval_task = Some(IntervalService::spawn(TICK, callback));
+            },
+            Msg::Pause => {
+                self.interval_task = None;
+            },
+            Msg::Tick => {
+                let duration = match &replay.journal {
+                    Some(Ok(journal)) => journal.duration,
+                    _ => 0,
+                };
+                replay.time += (TICK.as_millis() as f64 * replay.speed) as i64;
+                if replay.time >= duration {
+                    replay.time = duration;
+                    self.interval_task = None;
+                }
+            },
+            Msg::Seek(time) => {
+                replay.time = time.max(0);
+            },
+            Msg::SetSpeed(speed) => {
+                replay.speed = speed;
+            },
+            Msg::Filter(filter) => {
+                replay.filter = filter;
+            },
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let replay = self.props.replay.borrow();
+        match &replay.journal {
+            Some(Ok(journal)) => html! {
+                <>
+                    <div class="column is-full">
+                        { self.render_controls(journal, &replay) }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop">
+                        { self.render_map(journal, replay.time) }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop">
+                        { self.render_robots(journal, replay.time) }
+                        { self.render_timeline(journal, &replay) }
+                    </div>
+                </>
+            },
+            Some(Err(error)) => html! {
+                <div class="column is-full">
+                    { self.render_upload() }
+                    <div class="notification is-danger">{ error }</div>
+                </div>
+            },
+            None => html! {
+                <div class="column is-full">
+                    { self.render_upload() }
+                    <p class="help">{ t!("replay.description") }</p>
+                </div>
+            },
+        }
+    }
+}
+
+impl Interface {
+    fn render_upload(&self) -> Html {
+        let onchange = self.link.callback(move |value| {
+            let mut result = Vec::new();
+            if let ChangeData::Files(files) = value {
+                let files = js_sys::try_iter(&files)
+                    .unwrap()
+                    .unwrap()
+                    .map(|v| File::from(v.unwrap()));
+                result.extend(files);
+            }
+            Msg::ReadJournal(result)
+        });
+        html! {
+            <div class="file mb-4">
+                <label class="file-label">
+                    <input class="file-input" type="file" accept=".pkl,.jsonl" onchange=onchange />
+                    <span class="file-cta">
+                        <span class="file-icon"><i class="mdi mdi-upload"></i></span>
+                        <span class="file-label"> {
+                            match self.reader_task {
+                                Some(_) => t!("replay.loading"),
+                                None => t!("replay.upload"),
+                            }
+                        } </span>
+                    </span>
+                </label>
+            </div>
+        }
+    }
+
+    fn render_controls(&self, journal: &Journal, replay: &Replay) -> Html {
+        let seek = self.link.batch_callback(|data: InputData| data.value.parse().ok().map(Msg::Seek));
+        let speed = self.link.batch_callback(|data: ChangeData| match data {
+            ChangeData::Select(select) => select.value().parse().ok().map(Msg::SetSpeed),
+            _ => None,
+        });
+        let toggle = match self.interval_task {
+            Some(_) => html! {
+                <button class="button" onclick=self.link.callback(|_| Msg::Pause)>
+                    <span class="icon"><i class="mdi mdi-pause"></i></span>
+                    <span>{ t!("replay.pause") }</span>
+                </button>
+            },
+            None => html! {
+                <button class="button" onclick=self.link.callback(|_| Msg::Play)>
+                    <span class="icon"><i class="mdi mdi-play"></i></span>
+                    <span>{ t!("replay.play") }</span>
+                </button>
+            },
+        };
+        let skipped = match journal.skipped {
+            0 => html! {},
+            skipped => html! { <p class="help is-warning">{ t!("replay.skipped", count = skipped) }</p> },
+        };
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ &journal.name }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item is-size-7">{
+                                journal.supervisor_version.as_ref()
+                                    .map_or_else(String::new, |version| t!("replay.version", version = version))
+                            }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        { self.render_upload() }
+                        <div class="field is-grouped">
+                            <div class="control">{ toggle }</div>
+                            <div class="control">
+                                <div class="select">
+                                    <select onchange=speed> {
+                                        SPEEDS.iter().map(|&value| {
+                                            let selected = value == replay.speed;
+                                            html! {
+                                                <option value=value.to_string() selected=selected>{ format!("{}×", value) }</option>
+                                            }
+                                        }).collect::<Html>()
+                                    } </select>
+                                </div>
+                            </div>
+                            <div class="control is-expanded">
+                                <input class="slider is-fullwidth" style="width:100%" type="range" min="0"
+                                    max=journal.duration.to_string() step="100" value=replay.time.to_string() oninput=seek />
+                            </div>
+                            <div class="control">
+                                <p class="is-family-monospace">{
+                                    format!("{} / {}", format_timestamp(replay.time), format_timestamp(journal.duration))
+                                }</p>
+                            </div>
+                        </div>
+                        <p>{ t!("replay.messages", count = journal.messages.partition_point(|timestamp| *timestamp <= replay.time)) }</p>
+                        { skipped }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_map(&self, journal: &Journal, time: i64) -> Html {
+        let content = match (journal.bounds, journal.frame(time)) {
+            (Some(([min_x, min_y], [max_x, max_y])), Some(frame)) => {
+                /* the y axis of the arena points up while the y axis of the image points down */
+                let view_box = format!("{} {} {} {}", min_x - MARGIN, -(max_y + MARGIN),
+                    max_x - min_x + 2.0 * MARGIN, max_y - min_y + 2.0 * MARGIN);
+                html! {
+                    <svg viewBox=view_box style="width:100%;max-height:70vh;background-color:hsl(0, 0%, 96%)"> {
+                        frame.iter().map(|(id, pose)| {
+                            let robot = journal.robots.iter()
+                                .find(|robot| robot.optitrack_id == Some(*id));
+                            let (label, color) = match robot {
+                                Some(robot) => (robot.id.clone(), kind_color(robot.kind)),
+                                None => (id.to_string(), "hsl(0, 0%, 48%)"),
+                            };
+                            let [x, y, _] = pose.position;
+                            let heading = [x + 2.0 * MARKER_RADIUS * pose.yaw.cos(), y + 2.0 * MARKER_RADIUS * pose.yaw.sin()];
+                            html! {
+                                <g>
+                                    <circle cx=x.to_string() cy=(-y).to_string() r=MARKER_RADIUS.to_string() fill=color />
+                                    <line x1=x.to_string() y1=(-y).to_string()
+                                          x2=heading[0].to_string() y2=(-heading[1]).to_string()
+                                          stroke=color stroke-width="0.01" />
+                                    <text x=(x + 1.5 * MARKER_RADIUS).to_string() y=(-y - 1.5 * MARKER_RADIUS).to_string()
+                                          font-size="0.08">{ label }</text>
+                                </g>
+                            }
+                        }).collect::<Html>()
+                    } </svg>
+                }
+            },
+            _ => html! { <p>{ t!("replay.map.none") }</p> },
+        };
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("replay.map") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">{ content }</div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_robots(&self, journal: &Journal, time: i64) -> Html {
+        let frame = journal.frame(time);
+        html! {
+            <div class="card mb-4">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("replay.robots") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <table class="table is-fullwidth">
+                            <thead>
+                                <tr>
+                                    <th>{ t!("replay.robots.robot") }</th>
+                                    <th>{ t!("replay.robots.position") }</th>
+                                    <th>{ t!("replay.robots.battery") }</th>
+                                    <th>{ t!("replay.robots.output") }</th>
+                                </tr>
+                            </thead>
+                            <tbody> {
+                                journal.robots.iter().map(|robot| {
+                                    let position = robot.optitrack_id
+                                        .and_then(|id| frame.and_then(|frame| frame.get(&id)))
+                                        .map_or_else(|| "-".to_owned(), |pose| format!("{:.2}, {:.2}, {:.2}",
+                                            pose.position[0], pose.position[1], pose.position[2]));
+                                    let battery = journal.batteries.get(&robot.id)
+                                        .and_then(|batteries| until(batteries, time).last())
+                                        .map_or_else(|| "-".to_owned(), |(_, cells)| cells.iter()
+                                            .map(|cell| format!("{:.2}V", *cell as f64 / 1000.0))
+                                            .collect::<Vec<_>>()
+                                            .join(" "));
+                                    let output = journal.output.get(&robot.id)
+                                        .and_then(|output| until(output, time).last());
+                                    html! {
+                                        <tr>
+                                            <td>
+                                                <span class="tag" style=format!("background-color:{};color:white", kind_color(robot.kind))>
+                                                    { &robot.id }
+                                                </span>
+                                            </td>
+                                            <td class="is-family-monospace">{ position }</td>
+                                            <td class="is-family-monospace">{ battery }</td> {
+                                                match output {
+                                                    Some((_, (true, line))) => html! {
+                                                        <td class="is-family-monospace has-text-danger">{ line }</td>
+                                                    },
+                                                    Some((_, (false, line))) => html! {
+                                                        <td class="is-family-monospace">{ line }</td>
+                                                    },
+                                                    None => html! { <td>{ "-" }</td> },
+                                                }
+                                            }
+                                        </tr>
+                                    }
+                                }).collect::<Html>()
+                            } </tbody>
+                        </table>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_timeline(&self, journal: &Journal, replay: &Replay) -> Html {
+        let time = replay.time;
+        let filter = replay.filter.as_deref();
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("timeline.title") }</p>
+                        </div>
+                        <div class="level-right">
+                            <div class="level-item">
+                                { render_filter(&journal.timeline, &[], &replay.filter, self.link.callback(Msg::Filter)) }
+                            </div>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content" style="max-height:320px;overflow-y:auto">
+                        <table class="table is-fullwidth is-hoverable">
+                            <tbody> {
+                                match journal.timeline.len() {
+                                    0 => html! {
+                                        <tr><td>{ t!("timeline.none") }</td></tr>
+                                    },
+                                    _ => journal.timeline.iter().filter(|event| has_tag(event, filter)).map(|event| {
+                                        /* the events that have not happened yet at the position of the replay are greyed out */
+                                        let classes = match event.timestamp > time {
+                                            true => classes!("has-text-grey-light"),
+                                            false => classes!(),
+                                        };
+                                        let timestamp = event.timestamp;
+                                        html! {
+                                            <tr class=classes style="cursor:pointer"
+                                                onclick=self.link.callback(move |_| Msg::Seek(timestamp))>
+                                                <td class="is-family-monospace">{ format_timestamp(event.timestamp) }</td>
+                                                <td>
+                                                    <span class=classes!("tag", category_class(event.category))>
+                                                        { category_name(event.category) }
+                                                    </span>
+                                                </td>
+                                                <td>{ &event.description }{ render_tags(&event.tags) }</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            } </tbody>
+                        </table>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use yew::prelude::*;
+
+use yew::{html, Component, ComponentLink, Html, ShouldRender};
+
+use shared::ros::Robot;
+
+pub struct Card {
+    props: Props,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub robot: Robot,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Props, _link: ComponentLink<Self>) -> Self {
+        Card { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let robot = &self.props.robot;
+        let row = |label: String, value: Html| html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ label }</p>
+                </div>
+                <div class="level-right">
+                    <p class="level-item">{ value }</p>
+                </div>
+            </nav>
+        };
+        let connection = match robot.connected {
+            true => html! {
+                <span class="tag is-success" title=robot.descriptor.url.clone()>{ t!("robot.ros.connected") }</span>
+            },
+            false => html! {
+                <span class="tag is-warning" title=robot.descriptor.url.clone()>{ t!("robot.ros.disconnected") }</span>
+            },
+        };
+        let battery = match robot.battery {
+            Some(battery) => html! { { format!("{:.0}%", battery) } },
+            None => html! { { t!("robot.ros.unknown") } },
+        };
+        let pose = match &robot.pose {
+            Some(pose) => html! {
+                { format!("({:.2}, {:.2}, {:.2})", pose.position[0], pose.position[1], pose.position[2]) }
+            },
+            None => html! { { t!("robot.ros.unknown") } },
+        };
+        let launch = match robot.running {
+            true => html! { <span class="tag is-success">{ t!("robot.ros.running") }</span> },
+            false => html! { <span class="tag">{ t!("robot.ros.stopped") }</span> },
+        };
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ &robot.descriptor.id }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item">
+                                <span class="tag is-info" title=t!("robot.ros.description")>
+                                    { t!("robot.ros") }
+                                </span>
+                            </p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        { row(t!("robot.ros.rosbridge"), connection) }
+                        { row(t!("robot.ros.battery"), battery) }
+                        { row(t!("robot.ros.pose"), pose) }
+                        { row(t!("robot.ros.launch"), launch) } {
+                            match &robot.error {
+                                Some(error) => html! {
+                                    <p class="help is-danger">{ error }</p>
+                                },
+                                None => html! {},
+                            }
+                        }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,560 @@
+use std::{collections::BTreeMap, net::{Ipv4Addr, SocketAddr}, str::FromStr};
+use macaddr::MacAddr6;
+use yew::prelude::*;
+
+use shared::configuration::{Configuration, Request};
+use shared::{BackEndRequest, access::Category};
+
+use crate::{UserInterface, access};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RobotField {
+    Id,
+    MacAddr,
+    SecondaryMacAddr,
+    OptitrackId,
+    AprilTagId,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    RouterSocket,
+    WebUiSocket,
+    GrpcSocket,
+    RobotNetwork,
+    PauseDiscovery,
+    OptitrackVersion,
+    OptitrackBindAddr,
+    OptitrackBindPort,
+    OptitrackMulticastAddr,
+    OptitrackIfaceAddr,
+    OptitrackServerAddr,
+    OptitrackCommandPort,
+    BuilderBot(usize, RobotField),
+    Drone(usize, RobotField),
+    PiPuck(usize, RobotField),
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(String::new, |value| value.to_string())
+}
+
+fn parse_optional<T: FromStr>(value: &str) -> Result<Option<T>, T::Err> {
+    match value.trim() {
+        "" => Ok(None),
+        value => value.parse().map(Some),
+    }
+}
+
+/* checks whether the value entered into a field can be parsed */
+fn check(field: Field, value: &str) -> bool {
+    let value = value.trim();
+    match field {
+        Field::RouterSocket | Field::WebUiSocket | Field::GrpcSocket =>
+            parse_optional::<SocketAddr>(value).is_ok(),
+        Field::RobotNetwork | Field::OptitrackVersion =>
+            !value.is_empty(),
+        Field::OptitrackBindAddr | Field::OptitrackMulticastAddr | Field::OptitrackIfaceAddr =>
+            value.parse::<Ipv4Addr>().is_ok(),
+        Field::OptitrackServerAddr =>
+            parse_optional::<Ipv4Addr>(value).is_ok(),
+        Field::PauseDiscovery =>
+            value.parse::<bool>().is_ok(),
+        Field::OptitrackBindPort =>
+            value.parse::<u16>().is_ok(),
+        Field::OptitrackCommandPort =>
+            parse_optional::<u16>(value).is_ok(),
+        Field::BuilderBot(_, field) | Field::Drone(_, field) | Field::PiPuck(_, field) => match field {
+            RobotField::Id => !value.is_empty(),
+            RobotField::MacAddr | RobotField::SecondaryMacAddr => value.parse::<MacAddr6>().is_ok(),
+            RobotField::OptitrackId => parse_optional::<i32>(value).is_ok(),
+            RobotField::AprilTagId => parse_optional::<u8>(value).is_ok(),
+        }
+    }
+}
+
+fn fields(configuration: &Configuration) -> BTreeMap<Field, String> {
+    let mut fields = BTreeMap::new();
+    fields.insert(Field::RouterSocket, optional(configuration.router_socket));
+    fields.insert(Field::WebUiSocket, optional(configuration.webui_socket));
+    fields.insert(Field::GrpcSocket, optional(configuration.grpc_socket));
+    fields.insert(Field::RobotNetwork, configuration.robot_network.clone());
+    fields.insert(Field::PauseDiscovery, configuration.probing.pause_discovery.to_string());
+    if let Some(optitrack) = &configuration.optitrack {
+        fields.insert(Field::OptitrackVersion, optitrack.version.clone());
+        fields.insert(Field::OptitrackBindAddr, optitrack.bind_addr.to_string());
+        fields.insert(Field::OptitrackBindPort, optitrack.bind_port.to_string());
+        fields.insert(Field::OptitrackMulticastAddr, optitrack.multicast_addr.to_string());
+        fields.insert(Field::OptitrackIfaceAddr, optitrack.iface_addr.to_string());
+        fields.insert(Field::OptitrackServerAddr, optional(optitrack.server_addr));
+        fields.insert(Field::OptitrackCommandPort, optional(optitrack.command_port));
+    }
+    for (index, desc) in configuration.builderbots.iter().enumerate() {
+        fields.insert(Field::BuilderBot(index, RobotField::Id), desc.id.clone());
+        fields.insert(Field::BuilderBot(index, RobotField::MacAddr), desc.duovero_macaddr.to_string());
+        fields.insert(Field::BuilderBot(index, RobotField::OptitrackId), optional(desc.optitrack_id));
+        fields.insert(Field::BuilderBot(index, RobotField::AprilTagId), optional(desc.apriltag_id));
+    }
+    for (index, desc) in configuration.drones.iter().enumerate() {
+        fields.insert(Field::Drone(index, RobotField::Id), desc.id.clone());
+        fields.insert(Field::Drone(index, RobotField::MacAddr), desc.xbee_macaddr.to_string());
+        fields.insert(Field::Drone(index, RobotField::SecondaryMacAddr), desc.upcore_macaddr.to_string());
+        fields.insert(Field::Drone(index, RobotField::OptitrackId), optional(desc.optitrack_id));
+    }
+    for (index, desc) in configuration.pipucks.iter().enumerate() {
+        fields.insert(Field::PiPuck(index, RobotField::Id), desc.id.clone());
+        fields.insert(Field::PiPuck(index, RobotField::MacAddr), desc.rpi_macaddr.to_string());
+        fields.insert(Field::PiPuck(index, RobotField::OptitrackId), optional(desc.optitrack_id));
+        fields.insert(Field::PiPuck(index, RobotField::AprilTagId), optional(desc.apriltag_id));
+    }
+    fields
+}
+
+pub struct Interface {
+    link: ComponentLink<Self>,
+    props: Props,
+    fields: BTreeMap<Field, String>,
+    error: Result<(), String>,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub parent: ComponentLink<UserInterface>,
+    pub configuration: Option<Configuration>,
+    pub restart_required: Vec<String>,
+    pub saved: Option<String>,
+}
+
+pub enum Msg {
+    Edit(Field, String),
+    Apply,
+    Save,
+    Reload,
+    Reset,
+    SetError(Result<(), String>),
+    SetLanguage(String),
+    SetScrollback(String),
+}
+
+impl Component for Interface {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        let fields = props.configuration.as_ref().map(fields).unwrap_or_default();
+        Interface {
+            link,
+            props,
+            fields,
+            error: Ok(()),
+        }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Msg::Edit(field, value) => {
+                self.fields.insert(field, value);
+                true
+            },
+            Msg::Apply => match self.configuration() {
+                Ok(configuration) => {
+                    let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                    let request = BackEndRequest::ConfigurationRequest(Request::Update(configuration));
+                    self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                    false
+                },
+                Err(error) => {
+                    self.error = Err(error);
+                    true
+                }
+            },
+            Msg::Save => {
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let request = BackEndRequest::ConfigurationRequest(Request::Save);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                false
+            },
+            Msg::Reload => {
+                let callback = Some(self.link.callback(|result| Msg::SetError(result)));
+                let request = BackEndRequest::ConfigurationRequest(Request::Reload);
+                self.props.parent.send_message(crate::Msg::SendRequest(request, callback));
+                false
+            },
+            Msg::Reset => {
+                self.fields = self.props.configuration.as_ref().map(fields).unwrap_or_default();
+                self.error = Ok(());
+                true
+            },
+            Msg::SetError(error) => {
+                self.error = error;
+                true
+            },
+            Msg::SetLanguage(language) => {
+                /* the catalog is selected when the client starts, reload to apply the change */
+                crate::i18n::set_language(&language);
+                let _ = yew::utils::window().location().reload();
+                false
+            },
+            Msg::SetScrollback(limit) => match limit.trim().parse::<usize>() {
+                Ok(limit) if limit > 0 => {
+                    crate::terminal::set_scrollback(limit);
+                    false
+                },
+                _ => {
+                    self.error = Err(t!("settings.invalid", value = limit));
+                    true
+                }
+            },
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        /* discard the edits once the backend reports a new configuration */
+        if props.configuration != self.props.configuration {
+            self.fields = props.configuration.as_ref().map(fields).unwrap_or_default();
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        match &self.props.configuration {
+            None => html! {
+                <>
+                    <div class="column is-full">
+                        <div class="notification">{ t!("settings.none") }</div>
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_language() }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_terminal() }
+                    </div>
+                </>
+            },
+            Some(configuration) => html! {
+                <>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_network() }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_optitrack(configuration) }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_control() }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_language() }
+                    </div>
+                    <div class="column is-full-mobile is-full-tablet is-half-desktop is-one-third-widescreen">
+                        { self.render_terminal() }
+                    </div>
+                    <div class="column is-full">
+                        { self.render_robots(configuration) }
+                    </div>
+                </>
+            }
+        }
+    }
+}
+
+impl Interface {
+    /// Builds a configuration from the values entered into the form
+    fn configuration(&self) -> Result<Configuration, String> {
+        let mut configuration = self.props.configuration.clone()
+            .ok_or_else(|| t!("settings.none"))?;
+        if let Some((_, value)) = self.fields.iter().find(|(&field, value)| !check(field, value)) {
+            return Err(t!("settings.invalid", value = value));
+        }
+        let get = |field| self.fields.get(&field).map_or("", |value: &String| value.trim());
+        configuration.router_socket = parse_optional(get(Field::RouterSocket)).unwrap_or_default();
+        configuration.webui_socket = parse_optional(get(Field::WebUiSocket)).unwrap_or_default();
+        configuration.grpc_socket = parse_optional(get(Field::GrpcSocket)).unwrap_or_default();
+        configuration.robot_network = get(Field::RobotNetwork).to_owned();
+        configuration.probing.pause_discovery = get(Field::PauseDiscovery).parse()
+            .unwrap_or(configuration.probing.pause_discovery);
+        if let Some(optitrack) = configuration.optitrack.as_mut() {
+            optitrack.version = get(Field::OptitrackVersion).to_owned();
+            optitrack.bind_addr = get(Field::OptitrackBindAddr).parse().unwrap_or(optitrack.bind_addr);
+            optitrack.bind_port = get(Field::OptitrackBindPort).parse().unwrap_or(optitrack.bind_port);
+            optitrack.multicast_addr = get(Field::OptitrackMulticastAddr).parse().unwrap_or(optitrack.multicast_addr);
+            optitrack.iface_addr = get(Field::OptitrackIfaceAddr).parse().unwrap_or(optitrack.iface_addr);
+            optitrack.server_addr = parse_optional(get(Field::OptitrackServerAddr)).unwrap_or_default();
+            optitrack.command_port = parse_optional(get(Field::OptitrackCommandPort)).unwrap_or_default();
+        }
+        for (index, desc) in configuration.builderbots.iter_mut().enumerate() {
+            desc.id = get(Field::BuilderBot(index, RobotField::Id)).to_owned();
+            desc.duovero_macaddr = get(Field::BuilderBot(index, RobotField::MacAddr)).parse()
+                .unwrap_or(desc.duovero_macaddr);
+            desc.optitrack_id = parse_optional(get(Field::BuilderBot(index, RobotField::OptitrackId))).unwrap_or_default();
+            desc.apriltag_id = parse_optional(get(Field::BuilderBot(index, RobotField::AprilTagId))).unwrap_or_default();
+        }
+        for (index, desc) in configuration.drones.iter_mut().enumerate() {
+            desc.id = get(Field::Drone(index, RobotField::Id)).to_owned();
+            desc.xbee_macaddr = get(Field::Drone(index, RobotField::MacAddr)).parse()
+                .unwrap_or(desc.xbee_macaddr);
+            desc.upcore_macaddr = get(Field::Drone(index, RobotField::SecondaryMacAddr)).parse()
+                .unwrap_or(desc.upcore_macaddr);
+            desc.optitrack_id = parse_optional(get(Field::Drone(index, RobotField::OptitrackId))).unwrap_or_default();
+        }
+        for (index, desc) in configuration.pipucks.iter_mut().enumerate() {
+            desc.id = get(Field::PiPuck(index, RobotField::Id)).to_owned();
+            desc.rpi_macaddr = get(Field::PiPuck(index, RobotField::MacAddr)).parse()
+                .unwrap_or(desc.rpi_macaddr);
+            desc.optitrack_id = parse_optional(get(Field::PiPuck(index, RobotField::OptitrackId))).unwrap_or_default();
+            desc.apriltag_id = parse_optional(get(Field::PiPuck(index, RobotField::AprilTagId))).unwrap_or_default();
+        }
+        Ok(configuration)
+    }
+
+    fn render_input(&self, field: Field) -> Html {
+        let value = self.fields.get(&field).cloned().unwrap_or_default();
+        let invalid = (!check(field, &value)).then(|| "is-danger");
+        let oninput = self.link.callback(move |data: InputData| Msg::Edit(field, data.value));
+        html! {
+            <input class=classes!("input", invalid) type="text" value=value oninput=oninput />
+        }
+    }
+
+    fn render_field(&self, label: &str, field: Field) -> Html {
+        html! {
+            <div class="field">
+                <label class="label">{ label }</label>
+                <div class="control">{ self.render_input(field) }</div>
+            </div>
+        }
+    }
+
+    fn render_card(&self, title: &str, content: Html) -> Html {
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ title }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">{ content }</div>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_network(&self) -> Html {
+        self.render_card(&t!("settings.network"), html! {
+            <>
+                { self.render_field(&t!("settings.network.robots"), Field::RobotNetwork) }
+                { self.render_field(&t!("settings.network.router"), Field::RouterSocket) }
+                { self.render_field(&t!("settings.network.webui"), Field::WebUiSocket) }
+                { self.render_field(&t!("settings.network.grpc"), Field::GrpcSocket) }
+                { self.render_pause_discovery() }
+            </>
+        })
+    }
+
+    fn render_pause_discovery(&self) -> Html {
+        let checked = self.fields.get(&Field::PauseDiscovery).map_or(false, |value| value == "true");
+        let onclick = self.link.callback(move |_| Msg::Edit(Field::PauseDiscovery, (!checked).to_string()));
+        html! {
+            <div class="field">
+                <label class="checkbox">
+                    <input type="checkbox" checked=checked onclick=onclick />
+                    { " " }{ t!("settings.network.pause_discovery") }
+                </label>
+                <p class="help">{ t!("settings.network.pause_discovery.description") }</p>
+            </div>
+        }
+    }
+
+    fn render_optitrack(&self, configuration: &Configuration) -> Html {
+        let content = match configuration.optitrack {
+            None => html! { <p>{ t!("settings.optitrack.none") }</p> },
+            Some(_) => html! {
+                <>
+                    { self.render_field(&t!("settings.optitrack.version"), Field::OptitrackVersion) }
+                    { self.render_field(&t!("settings.optitrack.bind_addr"), Field::OptitrackBindAddr) }
+                    { self.render_field(&t!("settings.optitrack.bind_port"), Field::OptitrackBindPort) }
+                    { self.render_field(&t!("settings.optitrack.multicast_addr"), Field::OptitrackMulticastAddr) }
+                    { self.render_field(&t!("settings.optitrack.iface_addr"), Field::OptitrackIfaceAddr) }
+                    { self.render_field(&t!("settings.optitrack.server_addr"), Field::OptitrackServerAddr) }
+                    { self.render_field(&t!("settings.optitrack.command_port"), Field::OptitrackCommandPort) }
+                </>
+            }
+        };
+        self.render_card(&t!("settings.optitrack"), content)
+    }
+
+    fn render_control(&self) -> Html {
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ t!("settings.control") }</p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        {
+                            match &self.error {
+                                Err(error) => html! { <div class="notification is-danger">{ error }</div> },
+                                Ok(_) => html! {},
+                            }
+                        }
+                        {
+                            match self.props.restart_required.len() {
+                                0 => html! {
+                                    <p>{ t!("settings.control.applied") }</p>
+                                },
+                                _ => html! {
+                                    <div class="notification is-warning">
+                                        <p>{ t!("settings.control.restart") }</p>
+                                        <ul> {
+                                            self.props.restart_required.iter()
+                                                .map(|change| html! { <li>{ change }</li> })
+                                                .collect::<Html>()
+                                        } </ul>
+                                    </div>
+                                }
+                            }
+                        }
+                        {
+                            self.props.saved.as_ref().map_or_else(|| html! {}, |path| html! {
+                                <p>{ t!("settings.control.saved", path = path) }</p>
+                            })
+                        }
+                    </div>
+                </div>
+                <footer class="card-footer"> {
+                    match access::permitted(Category::Configuration) {
+                        true => html! {
+                            <>
+                                <a class="card-footer-item" onclick=self.link.callback(|_| Msg::Apply)>{ t!("settings.control.apply") }</a>
+                                <a class="card-footer-item" onclick=self.link.callback(|_| Msg::Save)>{ t!("settings.control.save") }</a>
+                                <a class="card-footer-item" onclick=self.link.callback(|_| Msg::Reload)>{ t!("settings.control.reload") }</a>
+                            </>
+                        },
+                        false => html! {
+                            <>
+                                <span class="card-footer-item has-text-grey-light"
+                                      title=t!("access.forbidden")>{ t!("settings.control.apply") }</span>
+                                <span class="card-footer-item has-text-grey-light"
+                                      title=t!("access.forbidden")>{ t!("settings.control.save") }</span>
+                                <span class="card-footer-item has-text-grey-light"
+                                      title=t!("access.forbidden")>{ t!("settings.control.reload") }</span>
+                            </>
+                        },
+                    }
+                }
+                    <a class="card-footer-item" onclick=self.link.callback(|_| Msg::Reset)>{ t!("settings.control.reset") }</a>
+                </footer>
+                <footer class="card-footer">
+                    <a class="card-footer-item" href="/inventory/csv" download="robots.csv">{ t!("settings.control.inventory.csv") }</a>
+                    <a class="card-footer-item" href="/inventory/json" download="robots.json">{ t!("settings.control.inventory.json") }</a>
+                </footer>
+            </div>
+        }
+    }
+
+    fn render_language(&self) -> Html {
+        let onchange = self.link.callback(|data| match data {
+            ChangeData::Select(select) => Msg::SetLanguage(select.value()),
+            _ => Msg::SetLanguage(crate::i18n::language().to_owned()),
+        });
+        let language = crate::i18n::language();
+        self.render_card(&t!("settings.language"), html! {
+            <>
+                <p>{ t!("settings.language.description") }</p>
+                <div class="select">
+                    <select onchange=onchange> {
+                        crate::i18n::languages().into_iter().map(|(code, name)| html! {
+                            <option value=code selected={ code == language }>{ name }</option>
+                        }).collect::<Html>()
+                    } </select>
+                </div>
+            </>
+        })
+    }
+
+    fn render_terminal(&self) -> Html {
+        let onchange = self.link.batch_callback(|data| match data {
+            ChangeData::Value(value) => Some(Msg::SetScrollback(value)),
+            _ => None,
+        });
+        self.render_card(&t!("settings.terminal"), html! {
+            <>
+                <p>{ t!("settings.terminal.description") }</p>
+                <div class="field">
+                    <label class="label">{ t!("settings.terminal.scrollback") }</label>
+                    <div class="control">
+                        <input class="input" type="number" min="1"
+                               value=crate::terminal::scrollback().to_string() onchange=onchange />
+                    </div>
+                </div>
+            </>
+        })
+    }
+
+    fn render_robot_row(&self, robot: &str, field: impl Fn(RobotField) -> Field, secondary_macaddr: bool, apriltag_id: bool) -> Html {
+        html! {
+            <tr>
+                <td>{ robot }</td>
+                <td>{ self.render_input(field(RobotField::Id)) }</td>
+                <td>{ self.render_input(field(RobotField::MacAddr)) }</td>
+                <td> {
+                    match secondary_macaddr {
+                        true => self.render_input(field(RobotField::SecondaryMacAddr)),
+                        false => html! { { "-" } },
+                    }
+                } </td>
+                <td>{ self.render_input(field(RobotField::OptitrackId)) }</td>
+                <td> {
+                    match apriltag_id {
+                        true => self.render_input(field(RobotField::AprilTagId)),
+                        false => html! { { "-" } },
+                    }
+                } </td>
+            </tr>
+        }
+    }
+
+    fn render_robots(&self, configuration: &Configuration) -> Html {
+        self.render_card(&t!("settings.robots"), html! {
+            <table class="table is-fullwidth">
+                <thead>
+                    <tr>
+                        <th>{ t!("settings.robots.type") }</th>
+                        <th>{ t!("settings.robots.id") }</th>
+                        <th>{ t!("settings.robots.macaddr") }</th>
+                        <th>{ t!("settings.robots.secondary_macaddr") }</th>
+                        <th>{ t!("settings.robots.optitrack_id") }</th>
+                        <th>{ t!("settings.robots.apriltag_id") }</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {
+                        (0..configuration.builderbots.len())
+                            .map(|index| self.render_robot_row(&t!("robot.builderbot"),
+                                |field| Field::BuilderBot(index, field), false, true))
+                            .collect::<Html>()
+                    }
+                    {
+                        (0..configuration.drones.len())
+                            .map(|index| self.render_robot_row(&t!("robot.drone"),
+                                |field| Field::Drone(index, field), true, false))
+                            .collect::<Html>()
+                    }
+                    {
+                        (0..configuration.pipucks.len())
+                            .map(|index| self.render_robot_row(&t!("robot.pipuck"),
+                                |field| Field::PiPuck(index, field), false, true))
+                            .collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        })
+    }
+}
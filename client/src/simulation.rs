@@ -0,0 +1,79 @@
+use yew::prelude::*;
+
+use yew::{html, Component, ComponentLink, Html, ShouldRender};
+
+use shared::simulation::Robot;
+
+pub struct Card {
+    props: Props,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub robot: Robot,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Props, _link: ComponentLink<Self>) -> Self {
+        Card { props }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let robot = &self.props.robot;
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <nav class="card-header-title is-shadowless has-background-white-ter level is-mobile">
+                        <div class="level-left">
+                            <p class="level-item subtitle is-size-4">{ &robot.descriptor.id }</p>
+                        </div>
+                        <div class="level-right">
+                            <p class="level-item">
+                                <span class="tag is-info" title=t!("robot.simulated.description")>
+                                    { t!("robot.simulated") }
+                                </span>
+                            </p>
+                        </div>
+                    </nav>
+                </header>
+                <div class="card-content">
+                    <div class="content">
+                        <nav class="level is-mobile">
+                            <div class="level-left">
+                                <p class="level-item">{ t!("robot.simulated.router") }</p>
+                            </div>
+                            <div class="level-right">
+                                <p class="level-item"> {
+                                    match &robot.registration {
+                                        Some(registration) => html! {
+                                            <span class="tag is-success">{
+                                                t!("robot.simulated.registered",
+                                                    addr = registration.addr,
+                                                    time = registration.time)
+                                            }</span>
+                                        },
+                                        None => html! {
+                                            <span class="tag is-warning">{ t!("robot.simulated.unregistered") }</span>
+                                        }
+                                    }
+                                } </p>
+                            </div>
+                        </nav>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
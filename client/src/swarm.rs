@@ -0,0 +1,67 @@
+use yew::prelude::*;
+
+/// The state of a robot that is aggregated into the statistics of the swarm
+pub struct Status {
+    pub connected: bool,
+    /* the battery level in percent, if known */
+    pub battery: Option<i32>,
+    /* the link strength of the Wi-Fi connection as reported by the robot, if known */
+    pub signal: Option<i32>,
+    /* the robot needs attention, e.g., after an unexpected reboot or an intervention of the watchdog */
+    pub error: bool,
+}
+
+fn render_item(heading: String, value: String, class: Option<&'static str>) -> Html {
+    html! {
+        <div class="level-item has-text-centered">
+            <div>
+                <p class="heading">{ heading }</p>
+                <p class=classes!("title", "is-5", class)>{ value }</p>
+            </div>
+        </div>
+    }
+}
+
+/// Renders the statistics of the robots of a tab above their cards, so that the readiness of the swarm can
+/// be assessed without scanning every card. Nothing is rendered if the tab has no robots
+pub fn render(statuses: impl Iterator<Item = Status>) -> Html {
+    let mut total = 0;
+    let mut connected = 0;
+    let mut errors = 0;
+    let mut battery: Option<i32> = None;
+    let mut signal: Option<i32> = None;
+    for status in statuses {
+        total += 1;
+        if status.connected {
+            connected += 1;
+        }
+        if status.error {
+            errors += 1;
+        }
+        battery = match (battery, status.battery) {
+            (Some(minimum), Some(level)) => Some(minimum.min(level)),
+            (minimum, level) => minimum.or(level),
+        };
+        signal = match (signal, status.signal) {
+            (Some(worst), Some(strength)) => Some(worst.min(strength)),
+            (worst, strength) => worst.or(strength),
+        };
+    }
+    if total == 0 {
+        return html! {};
+    }
+    html! {
+        <div class="column is-full">
+            <nav class="level box">
+                { render_item(t!("swarm.connected"), format!("{}/{}", connected, total),
+                    (connected < total).then(|| "has-text-warning-dark")) }
+                { render_item(t!("swarm.battery"), battery
+                    .map_or_else(|| t!("common.unknown"), |level| format!("{}%", level)), None) }
+                { render_item(t!("swarm.signal"), signal
+                    .map_or_else(|| t!("common.unknown"), |strength| format!("{}%", strength + 90)), None) }
+                { render_item(t!("swarm.errors"), errors.to_string(),
+                    (errors > 0).then(|| "has-text-danger")) }
+            </nav>
+        </div>
+    }
+}
@@ -0,0 +1,65 @@
+use shared::telemetry::History;
+use yew::prelude::*;
+
+/* the maximum number of readings of each kind that are kept for a robot */
+const CAPACITY: usize = 1024;
+
+/// Appends a reading that was received after the history of the robot was fetched
+pub fn record(readings: &mut Vec<(i64, i32)>, value: i32) {
+    readings.push((js_sys::Date::now() as i64, value));
+    if readings.len() > CAPACITY {
+        readings.remove(0);
+    }
+}
+
+/// Renders the battery level and the signal strengths of a robot since the oldest reading that the
+/// supervisor has kept. The signal strength of the Wi-Fi connection is reported in dBm, which is shown
+/// as a percentage on the cards by adding 90
+pub fn render(history: Option<&History>) -> Html {
+    let history = match history {
+        Some(history) => history,
+        None => return html! {},
+    };
+    let rows = vec![
+        (t!("robot.telemetry.battery"), &history.battery, 0),
+        (t!("robot.telemetry.signal"), &history.signal, 90),
+        (t!("robot.telemetry.xbee_signal"), &history.xbee_signal, 0),
+    ];
+    rows.into_iter()
+        .filter(|(_, readings, _)| readings.len() > 1)
+        .map(|(label, readings, offset)| html! {
+            <nav class="level is-mobile">
+                <div class="level-left">
+                    <p class="level-item">{ label }</p>
+                </div>
+                <div class="level-right">
+                    <p class="level-item">{ sparkline(readings, offset) }</p>
+                </div>
+            </nav>
+        })
+        .collect::<Html>()
+}
+
+/// Draws the readings as a line, where the readings are percentages after adding the offset
+fn sparkline(readings: &[(i64, i32)], offset: i32) -> Html {
+    let now = js_sys::Date::now() as i64;
+    let start = readings.first().map_or(now, |(time, _)| *time);
+    let duration = (now - start).max(1) as f64;
+    let points = readings.iter()
+        .map(|(time, value)| {
+            let x = (time - start) as f64 / duration * 100.0;
+            let y = 20.0 - (value + offset).max(0).min(100) as f64 / 5.0;
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = readings.last()
+        .map_or_else(String::new, |(_, value)| format!("{}%", value + offset));
+    html! {
+        <svg viewBox="0 0 100 20" preserveAspectRatio="none" style="width:8rem;height:1.5rem">
+            <title>{ title }</title>
+            <polyline points=points fill="none" stroke="hsl(204, 86%, 53%)" stroke-width="1.5"
+                      vector-effect="non-scaling-stroke" />
+        </svg>
+    }
+}
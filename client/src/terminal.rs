@@ -0,0 +1,278 @@
+use std::collections::VecDeque;
+use web_sys::HtmlInputElement;
+use yew::{prelude::*, web_sys::HtmlTextAreaElement};
+
+/* the key under which the scrollback limit is stored in the local storage of the browser */
+const STORAGE_KEY: &'static str = "supervisor.terminal.scrollback";
+/* the number of lines of output that are kept for each terminal unless configured otherwise */
+pub const DEFAULT_SCROLLBACK: usize = 1000;
+/* the maximum number of commands that are remembered for each terminal */
+const HISTORY_CAPACITY: usize = 100;
+
+fn storage() -> Option<web_sys::Storage> {
+    yew::utils::window().local_storage().ok().flatten()
+}
+
+/// The number of lines of output that are kept for each terminal, as stored in the settings of the browser
+pub fn scrollback() -> usize {
+    storage()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|limit| limit.parse().ok())
+        .filter(|&limit| limit > 0)
+        .unwrap_or(DEFAULT_SCROLLBACK)
+}
+
+/// Stores the number of lines of output that are kept for each terminal in the settings of the browser,
+/// the limit is applied to the terminals once they receive more output
+pub fn set_scrollback(limit: usize) {
+    if let Some(storage) = storage() {
+        let _ = storage.set_item(STORAGE_KEY, &limit.to_string());
+    }
+}
+
+/// The output of a terminal and the commands that were entered into it. The buffer is kept in the instance
+/// of a robot so that the output is not lost when the terminal is closed or the robot reconnects
+#[derive(Default)]
+pub struct Buffer {
+    output: String,
+    lines: usize,
+    history: VecDeque<String>,
+}
+
+impl Buffer {
+    pub fn push(&mut self, text: &str) {
+        self.output.push_str(text);
+        self.lines += text.matches('\n').count();
+        let limit = scrollback();
+        if self.lines > limit {
+            /* drop the oldest lines so that only the last lines of the output are kept */
+            let excess = self.lines - limit;
+            if let Some((index, _)) = self.output.match_indices('\n').nth(excess - 1) {
+                self.output.drain(..=index);
+                self.lines = limit;
+            }
+        }
+    }
+
+    /// Remembers a command so that it can be recalled with the arrow keys, repeated commands are only
+    /// remembered once
+    pub fn record(&mut self, command: &str) {
+        if !command.trim().is_empty() && self.history.back().map_or(true, |last| last != command) {
+            self.history.push_back(command.to_owned());
+            if self.history.len() > HISTORY_CAPACITY {
+                self.history.pop_front();
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.output.is_empty()
+    }
+
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub fn history(&self) -> Vec<String> {
+        self.history.iter().cloned().collect()
+    }
+}
+
+/// The shell that a terminal is connected to, which determines the labels of its button
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Mavlink,
+}
+
+impl Shell {
+    fn label(&self, visible: bool) -> String {
+        match (self, visible) {
+            (Shell::Bash, false) => t!("robot.terminal.bash.open"),
+            (Shell::Bash, true) => t!("robot.terminal.bash.close"),
+            (Shell::Mavlink, false) => t!("robot.terminal.mavlink.open"),
+            (Shell::Mavlink, true) => t!("robot.terminal.mavlink.close"),
+        }
+    }
+}
+
+/// A terminal with the output of a shell on a robot and an input for sending commands to it, headed by the
+/// title of the device that runs the shell and a button for opening and closing it. The up and down arrow
+/// keys recall the previous commands
+pub struct Terminal {
+    link: ComponentLink<Self>,
+    props: Props,
+    textarea: NodeRef,
+    input: NodeRef,
+    /* the command from the history that is shown in the input and what was typed before it was recalled */
+    position: Option<usize>,
+    draft: String,
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    /* the device that runs the shell, e.g., the Up Core of a drone */
+    pub title: &'static str,
+    pub shell: Shell,
+    /* whether the terminal is open, the card starts and stops the shell on the robot when it is toggled */
+    pub visible: bool,
+    pub ontoggle: Callback<MouseEvent>,
+    pub content: String,
+    pub history: Vec<String>,
+    pub disabled: bool,
+    /* the amount of queued input that has been sent to the robot */
+    #[prop_or_default]
+    pub progress: Option<(usize, usize)>,
+    pub oncommand: Callback<String>,
+}
+
+pub enum Msg {
+    Submit,
+    Previous,
+    Next,
+}
+
+impl Component for Terminal {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, link: ComponentLink<Self>) -> Self {
+        Terminal {
+            link,
+            props,
+            textarea: NodeRef::default(),
+            input: NodeRef::default(),
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    fn rendered(&mut self, _: bool) {
+        if let Some(textarea) = self.textarea.cast::<HtmlTextAreaElement>() {
+            textarea.set_scroll_top(textarea.scroll_height());
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let input = match self.input.cast::<HtmlInputElement>() {
+            Some(input) => input,
+            None => return false,
+        };
+        let history = &self.props.history;
+        match msg {
+            Msg::Submit => {
+                let command = input.value();
+                input.set_value("");
+                self.position = None;
+                self.props.oncommand.emit(command);
+            },
+            Msg::Previous => if !history.is_empty() {
+                let position = match self.position {
+                    Some(position) => position.saturating_sub(1),
+                    None => {
+                        self.draft = input.value();
+                        history.len() - 1
+                    }
+                };
+                input.set_value(&history[position]);
+                self.position = Some(position);
+            },
+            Msg::Next => if let Some(position) = self.position {
+                match history.get(position + 1) {
+                    Some(command) => {
+                        input.set_value(command);
+                        self.position = Some(position + 1);
+                    },
+                    None => {
+                        input.set_value(&self.draft);
+                        self.position = None;
+                    }
+                }
+            },
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props.history.len() != self.props.history.len() {
+            self.position = None;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let onkeydown = self.link.batch_callback(|event: KeyboardEvent| match event.key().as_ref() {
+            "Enter" => Some(Msg::Submit),
+            "ArrowUp" => {
+                event.prevent_default();
+                Some(Msg::Previous)
+            },
+            "ArrowDown" => {
+                event.prevent_default();
+                Some(Msg::Next)
+            },
+            _ => None,
+        });
+        html! {
+            <>
+                <nav class="level is-mobile">
+                    <div class="level-left">
+                        <p class="level-item">{ self.props.title }</p>
+                    </div>
+                    <div class="level-right">
+                        <button class="level-item button"
+                                onclick=self.props.ontoggle.clone()
+                                disabled=self.props.disabled>
+                            { self.props.shell.label(self.props.visible) }
+                        </button>
+                    </div>
+                </nav>
+                <div class=classes!("columns", "is-mobile", (!self.props.visible).then(|| "is-hidden"))>
+                    <div class="column is-full">
+                        { self.render_shell(onkeydown) }
+                    </div>
+                </div>
+            </>
+        }
+    }
+}
+
+impl Terminal {
+    fn render_shell(&self, onkeydown: Callback<KeyboardEvent>) -> Html {
+        html! {
+            <div>
+                <div class="field">
+                    <div class="control">
+                        <textarea ref=self.textarea.clone()
+                                  class="textarea is-family-monospace"
+                                  readonly=false>
+                                  { self.props.content.clone() }
+                        </textarea>
+                    </div>
+                </div>
+                <div class="field">
+                    <div class="control">
+                        <input ref=self.input.clone()
+                               class="input is-family-monospace"
+                               type="text"
+                               disabled=self.props.disabled
+                               placeholder=t!("robot.terminal.placeholder")
+                               onkeydown=onkeydown />
+                    </div>
+                </div>
+                {
+                    match self.props.progress {
+                        Some((sent, total)) => html! {
+                            <progress class="progress is-small is-info"
+                                      value=sent.to_string()
+                                      max=total.to_string()
+                                      title=t!("robot.terminal.progress", sent = sent, total = total) />
+                        },
+                        None => html! {}
+                    }
+                }
+            </div>
+        }
+    }
+}
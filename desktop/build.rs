@@ -0,0 +1,51 @@
+use std::path::Path;
+
+fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        }
+        else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    /* tell Cargo to rerun this build script if the given directories change */
+    println!("cargo:rerun-if-changed=../client/src");
+    println!("cargo:rerun-if-changed=../client/public");
+    println!("cargo:rerun-if-changed=../shared/src");
+    println!("cargo:rerun-if-changed=discover.html");
+    /* the bundled client is assembled in the dist directory, which is embedded by Tauri */
+    let dist_dir = Path::new("dist");
+    copy_dir(Path::new("../client/public"), dist_dir)
+        .expect("Could not copy the static files of the client");
+    std::fs::copy("discover.html", dist_dir.join("discover.html"))
+        .expect("Could not copy the discovery page");
+    /* build the Web-Assembly module */
+    let success = std::process::Command::new("wasm-pack")
+        .args(&[
+            "build",
+            "--target",
+            "web",
+            "--out-name",
+            "client",
+            "--out-dir"
+        ])
+        .arg(dist_dir.canonicalize().unwrap())
+        .arg("../client")
+        .spawn()
+        .expect("Could not execute wasm-pack, is it installed? https://rustwasm.github.io/wasm-pack/installer/")
+        .wait()
+        .expect("Could not execute wasm-pack, is it installed? https://rustwasm.github.io/wasm-pack/installer/")
+        .success();
+    assert!(success, "Failed to compile client");
+    assert!(dist_dir.join("client_bg.wasm").exists(), "WebAssembly module not generated");
+    assert!(dist_dir.join("client.js").exists(), "Javascript bindings not generated");
+    tauri_build::build()
+}
@@ -0,0 +1,72 @@
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+use std::{collections::BTreeMap, sync::{Arc, Mutex}};
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::Serialize;
+
+/* the service type under which the supervisor advertises its web interface */
+const SERVICE_TYPE: &'static str = "_supervisor._tcp.local.";
+
+#[derive(Clone, Debug, Serialize)]
+struct Supervisor {
+    name: String,
+    addr: String,
+    version: Option<String>,
+    /* the bundled client can only communicate with a supervisor that was built from the same version */
+    compatible: bool,
+}
+
+#[derive(Clone, Default)]
+struct Discovery(Arc<Mutex<BTreeMap<String, Supervisor>>>);
+
+/// Browses for supervisors on the local network and keeps the list of discovered supervisors up to date
+fn browse(discovery: Discovery) -> Result<(), String> {
+    let daemon = ServiceDaemon::new()
+        .map_err(|error| format!("Could not start mDNS daemon: {}", error))?;
+    let events = daemon.browse(SERVICE_TYPE)
+        .map_err(|error| format!("Could not browse for supervisors: {}", error))?;
+    while let Ok(event) = events.recv() {
+        match event {
+            ServiceEvent::ServiceResolved(info) => {
+                let addr = match info.get_addresses().iter().next() {
+                    Some(addr) => format!("{}:{}", addr, info.get_port()),
+                    None => continue,
+                };
+                let name = info.get_fullname()
+                    .trim_end_matches(SERVICE_TYPE)
+                    .trim_end_matches('.')
+                    .to_owned();
+                let version = info.get_properties().get("version").cloned();
+                let compatible = version.as_deref() == Some(env!("CARGO_PKG_VERSION"));
+                log::info!("Discovered supervisor \"{}\" at {}", name, addr);
+                let supervisor = Supervisor { name, addr, version, compatible };
+                discovery.0.lock().unwrap().insert(info.get_fullname().to_owned(), supervisor);
+            },
+            ServiceEvent::ServiceRemoved(_, fullname) => {
+                discovery.0.lock().unwrap().remove(&fullname);
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn discover(discovery: tauri::State<Discovery>) -> Vec<Supervisor> {
+    discovery.0.lock().unwrap().values().cloned().collect()
+}
+
+fn main() {
+    let environment = env_logger::Env::default().default_filter_or("desktop=info");
+    env_logger::Builder::from_env(environment).format_timestamp_millis().init();
+    let discovery = Discovery::default();
+    let browser_discovery = discovery.clone();
+    std::thread::spawn(move || if let Err(error) = browse(browser_discovery) {
+        log::error!("{}", error);
+    });
+    tauri::Builder::default()
+        .manage(discovery)
+        .invoke_handler(tauri::generate_handler![discover])
+        .run(tauri::generate_context!())
+        .expect("Could not run the desktop client");
+}
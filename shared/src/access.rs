@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// The roles of the users of the web interface. The permissions of each role are given by a matrix over
+/// the categories of requests, which can be adjusted in the configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Role {
+    Admin,
+    Operator,
+    Student,
+    Observer,
+}
+
+impl Role {
+    pub const ALL: &'static [Role] = &[Role::Admin, Role::Operator, Role::Student, Role::Observer];
+
+    /// The name of the role as used in the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Operator => "operator",
+            Role::Student => "student",
+            Role::Observer => "observer",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        Role::ALL.iter().copied().find(|role| role.name() == name)
+    }
+
+    /// The permissions of the role unless they are overridden in the configuration
+    pub fn default_permissions(&self) -> BTreeSet<Category> {
+        let categories: &[Category] = match self {
+            Role::Admin => Category::ALL,
+            Role::Operator => &[Category::Experiment, Category::Power, Category::Terminals],
+            Role::Student => &[Category::Experiment, Category::Terminals],
+            Role::Observer => &[],
+        };
+        categories.iter().copied().collect()
+    }
+}
+
+/// The categories of requests from the web interface. Requests that do not belong to a category, e.g.,
+/// identifying a robot or subscribing to updates, are permitted for all roles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Category {
+    /* starting and stopping experiments, the checklist, annotations, smoke tests, and fetching results */
+    Experiment,
+    /* halting, rebooting, and powering the robots and their components */
+    Power,
+    /* the Bash and MAVLink terminals and the MAVLink console */
+    Terminals,
+    /* editing and saving the configuration and maintenance mode */
+    Configuration,
+}
+
+impl Category {
+    pub const ALL: &'static [Category] =
+        &[Category::Experiment, Category::Power, Category::Terminals, Category::Configuration];
+
+    /// The name of the category as used in the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Experiment => "experiment",
+            Category::Power => "power",
+            Category::Terminals => "terminals",
+            Category::Configuration => "configuration",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Category> {
+        Category::ALL.iter().copied().find(|category| category.name() == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct User {
+    pub name: String,
+    /* the token that the client passes when connecting to the web interface */
+    pub token: String,
+    pub role: Role,
+}
+
+/// The users of the web interface and the permissions of their roles
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Access {
+    /* the role of clients that connect without a token or with an unknown token */
+    pub default: Role,
+    /* the permissions of the roles that differ from their default permissions */
+    pub roles: BTreeMap<Role, BTreeSet<Category>>,
+    pub users: Vec<User>,
+}
+
+impl Access {
+    /// The name and the role of the user with the given token, the default role is used if there is no such user
+    pub fn authenticate(&self, token: Option<&str>) -> (Option<String>, Role) {
+        token.and_then(|token| self.users.iter().find(|user| user.token == token))
+            .map_or((None, self.default), |user| (Some(user.name.clone()), user.role))
+    }
+
+    pub fn permissions(&self, role: Role) -> BTreeSet<Category> {
+        self.roles.get(&role).cloned().unwrap_or_else(|| role.default_permissions())
+    }
+}
+
+/// A client that is connected to the web interface, the sessions are sent to all clients so that the
+/// operators know who else is observing or controlling the experiment
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user: Option<String>,
+    pub role: Role,
+    /* the time at which the client connected */
+    pub connected: String,
+}
+
+/// The identity and the permissions of a client, this is sent once the client has connected and whenever
+/// the client logs in with a different token
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Update {
+    pub user: Option<String>,
+    pub role: Role,
+    pub permissions: BTreeSet<Category>,
+}
@@ -0,0 +1,15 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// An alert about a robot that is pushed to the user interface, e.g., when its battery runs low
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Notification {
+    pub robot: String,
+    pub severity: Severity,
+    pub message: String,
+}
@@ -0,0 +1,18 @@
+use serde::{Serialize, Deserialize};
+
+/// A reading of an ambient sensor in the arena, e.g., the temperature or the illuminance, which affect
+/// experiments that rely on the cameras of the robots
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Reading {
+    pub sensor: String,
+    pub unit: String,
+    /* the value or the reason why the sensor could not be read */
+    pub value: Result<f64, String>,
+    /* the time at which the sensor was read in RFC 3339 format */
+    pub time: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Update {
+    pub readings: Vec<Reading>,
+}
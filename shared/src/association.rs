@@ -0,0 +1,57 @@
+use std::net::Ipv4Addr;
+use serde::{Serialize, Deserialize};
+
+/// The reason why a device on the robot network could not be associated with a robot
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Failure {
+    /* the TLS handshake with the fernbedienung service failed, e.g., due to an untrusted certificate */
+    Authentication(String),
+    /* the device at the fixed address of the robot has a different MAC address */
+    UnexpectedDevice {
+        robot: String,
+        macaddr: macaddr::MacAddr6,
+    },
+    /* the robot was found at a different address than its fixed address */
+    UnexpectedAddress {
+        robot: String,
+        expected: Ipv4Addr,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Kind {
+    Xbee,
+    Fernbedienung,
+}
+
+/// A device on the robot network whose MAC address does not match any robot in the configuration, e.g., a
+/// rogue device or a robot whose MAC address was entered incorrectly
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct UnknownDevice {
+    pub kind: Kind,
+    pub addr: Ipv4Addr,
+    pub macaddr: macaddr::MacAddr6,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Update {
+    Failed(Ipv4Addr, Failure),
+    /* the device at this address has been associated or is no longer present */
+    Cleared(Ipv4Addr),
+    /* a device that is not part of the configuration was found, this is sent to the clients as an
+       UnknownDevice request */
+    Unknown(UnknownDevice),
+    /* a device that did not match any robot is held in quarantine until it is adopted or ignored */
+    Quarantined(UnknownDevice),
+    /* the device with this MAC address was adopted, ignored, or is no longer present */
+    Released(macaddr::MacAddr6),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* assign a quarantined device to the given robot until the supervisor is restarted, an Xbee replaces the
+       Xbee of a drone and a fernbedienung device replaces the UpCore, Raspberry Pi, or DuoVero of a robot */
+    Adopt(macaddr::MacAddr6, String),
+    /* stop listing a quarantined device until the supervisor is restarted */
+    Ignore(macaddr::MacAddr6),
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+/// The command that is suggested for installing mjpg-streamer on a robot, which is not packaged by the
+/// distributions that run on the robots
+pub const INSTALL_COMMAND: &str =
+    "git clone https://github.com/jacksonliam/mjpg-streamer.git && make -C mjpg-streamer/mjpg-streamer-experimental install";
+
+/// Whether the camera streams of a robot are supported, which is determined when the robot is associated
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Availability {
+    /* whether mjpg_streamer is installed on the robot */
+    pub streamer: bool,
+    /* the camera devices of the robot type and whether they are present on the robot */
+    pub cameras: BTreeMap<String, bool>,
+}
+
+impl Availability {
+    /// The camera devices that are present on the robot
+    pub fn available(&self) -> impl Iterator<Item = &str> {
+        self.cameras.iter()
+            .filter(|(_, &present)| present)
+            .map(|(camera, _)| camera.as_str())
+    }
+
+    /// The camera devices that are missing from the robot
+    pub fn missing(&self) -> impl Iterator<Item = &str> {
+        self.cameras.iter()
+            .filter(|(_, &present)| !present)
+            .map(|(camera, _)| camera.as_str())
+    }
+
+    /// Whether the given camera device can be streamed
+    pub fn is_available(&self, camera: &str) -> bool {
+        self.streamer && self.cameras.get(camera).copied().unwrap_or(false)
+    }
+
+    /// Whether at least one camera can be streamed
+    pub fn supported(&self) -> bool {
+        self.streamer && self.available().next().is_some()
+    }
+
+    /// The reason why the cameras can not be streamed, if any
+    pub fn reason(&self) -> Option<String> {
+        if !self.streamer {
+            Some(format!("mjpg_streamer is not installed on the robot, it can be installed with `{}`", INSTALL_COMMAND))
+        }
+        else if self.cameras.is_empty() {
+            Some(String::from("The robot has no cameras"))
+        }
+        else if !self.supported() {
+            Some(format!("None of the cameras are present on the robot: {}",
+                self.missing().collect::<Vec<_>>().join(", ")))
+        }
+        else {
+            None
+        }
+    }
+}
@@ -0,0 +1,717 @@
+use std::{collections::BTreeMap, fmt::Write, net::{Ipv4Addr, SocketAddr}};
+use serde::{Serialize, Deserialize};
+
+use crate::{access::Access, builderbot, drone, group::Group, pipuck, ros, router, simulation};
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Optitrack {
+    pub version: String,
+    pub bind_addr: Ipv4Addr,
+    pub bind_port: u16,
+    pub multicast_addr: Ipv4Addr,
+    pub iface_addr: Ipv4Addr,
+    pub server_addr: Option<Ipv4Addr>,
+    pub command_port: Option<u16>,
+    /* the number of seconds for which the raw packets are recorded once an experiment is started */
+    pub record: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AccessPoint {
+    pub addr: Ipv4Addr,
+    /* if not provided, the default table for MikroTik access points is used */
+    pub station_oid: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Infrastructure {
+    pub community: String,
+    /* the polling interval in seconds */
+    pub interval: u64,
+    pub access_points: Vec<AccessPoint>,
+    pub switches: Vec<Ipv4Addr>,
+}
+
+/// An ambient sensor that is either read via HTTP or from a device that emulates a serial port
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AmbientSensor {
+    pub name: String,
+    pub unit: String,
+    /* exactly one of the URL and the device is provided */
+    pub url: Option<String>,
+    pub device: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Ambient {
+    /* the polling interval in seconds */
+    pub interval: u64,
+    pub sensors: Vec<AmbientSensor>,
+}
+
+/// The PEM files that are used by the supervisor to authenticate itself to the fernbedienung service and
+/// to verify the certificates of the robots
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Tls {
+    pub authority: String,
+    pub certificate: String,
+    pub key: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ResourceLimits {
+    pub robot: String,
+    pub nice: Option<i8>,
+    pub ionice_class: Option<u8>,
+    pub ionice_level: Option<u8>,
+    pub memory_max: Option<String>,
+    pub cpu_quota: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ArtifactPattern {
+    pub robot: String,
+    pub pattern: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EnvironmentVariable {
+    pub robot: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// The watchdog that is deployed to the robots of a type to restart the fernbedienung service if it stops
+/// responding
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Watchdog {
+    pub robot: String,
+    /* the interval between two checks in seconds */
+    pub interval: u64,
+    pub attempts: u32,
+    pub service: String,
+}
+
+/// Disables the camera streams of the robots when an experiment is started since they saturate the
+/// wireless network and delay the traffic of the message router
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CameraShutdown {
+    /* whether the camera streams that were disabled are enabled again when the experiment is stopped */
+    pub restore: bool,
+    /* the identifiers of the robots whose camera streams are not disabled, e.g., observer drones */
+    pub exempt: Vec<String>,
+}
+
+/// The factors by which the intervals of the background polling of the robots are multiplied while an
+/// experiment is running, so that the polling does not compete with the traffic of the message router and
+/// the MAVLink connections of the drones. A factor of one keeps the normal interval
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PollingFactors {
+    /* the link strength of the Fernbedienung connections and the link margin of the Xbees */
+    pub link_strength: u32,
+    /* the pin states of the Xbees */
+    pub pin_states: u32,
+    /* the uptime of the robots */
+    pub uptime: u32,
+}
+
+impl Default for PollingFactors {
+    fn default() -> Self {
+        PollingFactors { link_strength: 4, pin_states: 4, uptime: 6 }
+    }
+}
+
+/// The keepalive of the connections to the Fernbedienung service. A robot whose heartbeat is not answered
+/// within the timeout is considered lost and its last known address is probed again until it reconnects or
+/// the reconnection window expires. All durations are in seconds
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Heartbeat {
+    pub interval: u64,
+    pub timeout: u64,
+    pub reconnect: u64,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat { interval: 1, timeout: 5, reconnect: 30 }
+    }
+}
+
+/// The time in milliseconds that a probe with one protocol waits for a response before it is abandoned and
+/// that an address waits before it is probed with that protocol again
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ProbeTiming {
+    pub timeout: u64,
+    pub retry_delay: u64,
+}
+
+impl Default for ProbeTiming {
+    fn default() -> Self {
+        ProbeTiming { timeout: 500, retry_delay: 0 }
+    }
+}
+
+/// How the addresses of the robot network are probed for Xbees and the Fernbedienung service. At most
+/// `concurrency` probes are made at the same time, the other addresses wait for their turn
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ProbeConfiguration {
+    pub xbee: ProbeTiming,
+    pub fernbedienung: ProbeTiming,
+    pub concurrency: usize,
+    /* whether only the robots whose connection was lost are probed while an experiment is running */
+    pub pause_discovery: bool,
+}
+
+impl Default for ProbeConfiguration {
+    fn default() -> Self {
+        ProbeConfiguration {
+            xbee: Default::default(),
+            fernbedienung: Default::default(),
+            concurrency: 256,
+            pause_discovery: true,
+        }
+    }
+}
+
+/// Probes only the addresses that the DHCP server leased to the MAC addresses of the configured robots or
+/// that the ARP table of the supervisor machine associates with them, instead of every host of the robot
+/// network. The interval at which the leases and the ARP table are read is in seconds
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TargetedProbing {
+    /* the lease file of the DHCP server, either of dnsmasq or of the ISC DHCP server */
+    pub leases: Option<String>,
+    /* whether the ARP table of the supervisor machine is read */
+    pub arp: bool,
+    pub interval: u64,
+}
+
+/// The readings of the robots that are kept in memory for the clients that open the card of a robot late
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Telemetry {
+    /* the duration in seconds for which the readings are kept */
+    pub retention: u64,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Telemetry { retention: 600 }
+    }
+}
+
+/// The format in which the journal of an experiment is recorded
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum JournalFormat {
+    /* a sequence of Python pickles in a single file */
+    Pickle,
+    /* a JSON object per line in a single file */
+    JsonLines,
+    /* a CSV file per type of event */
+    Csv,
+}
+
+impl JournalFormat {
+    /// The name of the format as used in the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            JournalFormat::Pickle => "pickle",
+            JournalFormat::JsonLines => "jsonl",
+            JournalFormat::Csv => "csv",
+        }
+    }
+
+    /// The extension of the file of the journal
+    pub fn extension(&self) -> &'static str {
+        match self {
+            JournalFormat::Pickle => "pkl",
+            JournalFormat::JsonLines => "jsonl",
+            JournalFormat::Csv => "csv",
+        }
+    }
+}
+
+impl Default for JournalFormat {
+    fn default() -> Self {
+        JournalFormat::Pickle
+    }
+}
+
+/// What the robots are told to do when the operators send them back to base, e.g., at the end of a demo.
+/// The drones leave autonomous mode and switch to the given flight mode, while the controllers of the ground
+/// robots receive the given command via the message router
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ReturnToBase {
+    /* either the return or the land mode of PX4 */
+    pub drone_mode: drone::FlightMode,
+    /* the value of the command that is broadcast under router::COMMAND_KEY */
+    pub command: String,
+}
+
+impl Default for ReturnToBase {
+    fn default() -> Self {
+        ReturnToBase { drone_mode: drone::FlightMode::Return, command: String::from("return_to_base") }
+    }
+}
+
+/// The boundary of the arena in the coordinates of the tracking system, where the z axis points up. The
+/// boundary is a polygon in the horizontal plane, which does not need to be convex, and an optional ceiling
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Geofence {
+    /* the vertices of the polygon in meters */
+    pub vertices: Vec<[f32; 2]>,
+    /* the maximum height in meters */
+    pub ceiling: Option<f32>,
+}
+
+impl Geofence {
+    /// Whether a position is inside the boundary
+    pub fn contains(&self, position: &[f32; 3]) -> bool {
+        let [x, y, z] = *position;
+        if self.ceiling.map_or(false, |ceiling| z > ceiling) {
+            return false;
+        }
+        /* a position is inside the polygon if a ray from it crosses an odd number of edges */
+        let mut inside = false;
+        for (index, &[x1, y1]) in self.vertices.iter().enumerate() {
+            let [x2, y2] = self.vertices[(index + 1) % self.vertices.len()];
+            if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// The battery levels in percent below which the operators are warned about the robots of a type and
+/// below which the level of these robots is considered critical
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BatteryThreshold {
+    pub robot: String,
+    pub warning: i32,
+    pub critical: i32,
+}
+
+/// What happens when a robot that participates in an experiment reaches a critical battery level
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum BatteryPolicy {
+    /* only notify the operators */
+    Notify,
+    /* stop the experiment */
+    Stop,
+}
+
+impl BatteryPolicy {
+    /// The name of the policy as used in the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatteryPolicy::Notify => "notify",
+            BatteryPolicy::Stop => "stop",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BatteryAlerts {
+    pub policy: BatteryPolicy,
+    pub thresholds: Vec<BatteryThreshold>,
+}
+
+/// The replication of the state of the arena to a standby supervisor that takes over the robots and the web
+/// interface when the primary supervisor stops responding
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Replication {
+    /* the socket on which the primary accepts connections from standby supervisors */
+    Primary {
+        socket: SocketAddr,
+    },
+    /* the primary that is mirrored and the time in seconds without a heartbeat after which the standby takes over */
+    Standby {
+        primary: SocketAddr,
+        timeout: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Configuration {
+    pub router_socket: Option<SocketAddr>,
+    /* the communication topology that the message router starts with */
+    pub router_topology: Option<router::Topology>,
+    /* the maximum distance in meters between two tracked robots for their messages to be forwarded */
+    pub router_range: Option<f32>,
+    pub webui_socket: Option<SocketAddr>,
+    /* the name under which the web interface is advertised via mDNS */
+    pub webui_advertise: Option<String>,
+    /* the socket of the optional gRPC interface */
+    pub grpc_socket: Option<SocketAddr>,
+    pub optitrack: Option<Optitrack>,
+    pub infrastructure: Option<Infrastructure>,
+    pub ambient: Option<Ambient>,
+    pub robot_network: String,
+    pub tls: Option<Tls>,
+    /* the timeouts, the retry delays, and the maximum number of simultaneous probes of the robot network */
+    pub probing: ProbeConfiguration,
+    /* the format in which the journal is recorded */
+    pub journal_format: JournalFormat,
+    /* the file with the key with which the journal is encrypted at rest */
+    pub journal_key: Option<String>,
+    /* the directory in which the library of control software is stored, next to the configuration if None */
+    pub library: Option<String>,
+    /* how long the readings of the robots are kept in memory */
+    pub telemetry: Telemetry,
+    /* the items that must be acknowledged before an experiment can be started */
+    pub checklist: Vec<String>,
+    /* the tags that are offered when annotating the timeline of an experiment */
+    pub tags: Vec<String>,
+    pub camera_shutdown: Option<CameraShutdown>,
+    /* the reduction of the background polling of the robots while an experiment is running */
+    pub polling: PollingFactors,
+    /* the keepalive of the connections to the Fernbedienung service */
+    pub heartbeat: Heartbeat,
+    /* only probe the addresses of the configured robots instead of every host of the robot network */
+    pub targeted_probing: Option<TargetedProbing>,
+    /* how the drones and the ground robots return to base */
+    pub return_to_base: ReturnToBase,
+    /* the boundary that the robots must not leave during an experiment */
+    pub geofence: Option<Geofence>,
+    /* the thresholds for alerting the operators about low batteries */
+    pub battery_alerts: Option<BatteryAlerts>,
+    /* whether this supervisor is the primary or a standby supervisor */
+    pub replication: Option<Replication>,
+    /* the users of the web interface and their roles, all clients have full access if not provided */
+    pub access: Option<Access>,
+    pub builderbots: Vec<builderbot::Descriptor>,
+    pub drones: Vec<drone::Descriptor>,
+    pub pipucks: Vec<pipuck::Descriptor>,
+    /* the robots that are simulated on the supervisor machine and register with the message router */
+    pub simulated_robots: Vec<simulation::Descriptor>,
+    /* the ROS-based robots that are controlled via their rosbridge websockets */
+    pub ros_robots: Vec<ros::Descriptor>,
+    pub groups: Vec<Group>,
+    pub limits: Vec<ResourceLimits>,
+    pub artifacts: Vec<ArtifactPattern>,
+    pub environment: Vec<EnvironmentVariable>,
+    pub watchdogs: Vec<Watchdog>,
+    /* the expected values of the parameters of the Xbees, which are checked by an audit */
+    pub xbee_profile: BTreeMap<String, String>,
+    /* named sets of parameters that are written to the Xbees of the drones that refer to them */
+    pub xbee_configurations: BTreeMap<String, Vec<(String, String)>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* validate the configuration and apply the changes that do not require a restart */
+    Update(Configuration),
+    /* write the current configuration back to disk */
+    Save,
+    /* read the configuration file again and add or remove robots without restarting */
+    Reload,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Update {
+    Configuration {
+        configuration: Configuration,
+        /* the parts of the configuration that only take effect after a restart */
+        restart_required: Vec<String>,
+    },
+    Saved(String),
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Configuration {
+    /// Generates the XML representation of the configuration in the format that is read by the supervisor
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" ?>\n<configuration>\n  <supervisor>\n");
+        if let Some(socket) = self.router_socket {
+            let _ = write!(xml, "    <router socket=\"{}\"", socket);
+            if let Some(range) = self.router_range {
+                let _ = write!(xml, " range=\"{}\"", range);
+            }
+            match &self.router_topology {
+                Some(topology) => {
+                    xml.push_str(">\n      <topology>\n");
+                    for (robot, neighbors) in &topology.neighbors {
+                        let neighbors = neighbors.iter().map(|id| escape(id)).collect::<Vec<_>>();
+                        let _ = writeln!(xml, "        <robot id=\"{}\" neighbors=\"{}\" />",
+                            escape(robot), neighbors.join(" "));
+                    }
+                    xml.push_str("      </topology>\n    </router>\n");
+                },
+                None => xml.push_str(" />\n"),
+            }
+        }
+        if let Some(socket) = self.webui_socket {
+            let _ = write!(xml, "    <webui socket=\"{}\"", socket);
+            if let Some(name) = &self.webui_advertise {
+                let _ = write!(xml, " advertise=\"{}\"", escape(name));
+            }
+            let _ = writeln!(xml, " />");
+        }
+        if let Some(socket) = self.grpc_socket {
+            let _ = writeln!(xml, "    <grpc socket=\"{}\" />", socket);
+        }
+        if let Some(optitrack) = &self.optitrack {
+            let _ = write!(xml, "    <optitrack version=\"{}\" bind_addr=\"{}\" bind_port=\"{}\" \
+                multicast_addr=\"{}\" iface_addr=\"{}\"",
+                escape(&optitrack.version), optitrack.bind_addr, optitrack.bind_port,
+                optitrack.multicast_addr, optitrack.iface_addr);
+            if let Some(server_addr) = optitrack.server_addr {
+                let _ = write!(xml, " server_addr=\"{}\"", server_addr);
+            }
+            if let Some(command_port) = optitrack.command_port {
+                let _ = write!(xml, " command_port=\"{}\"", command_port);
+            }
+            if let Some(record) = optitrack.record {
+                let _ = write!(xml, " record=\"{}\"", record);
+            }
+            xml.push_str(" />\n");
+        }
+        if let Some(infrastructure) = &self.infrastructure {
+            let _ = writeln!(xml, "    <infrastructure community=\"{}\" interval=\"{}\">",
+                escape(&infrastructure.community), infrastructure.interval);
+            for access_point in &infrastructure.access_points {
+                let _ = write!(xml, "      <access_point addr=\"{}\"", access_point.addr);
+                if let Some(station_oid) = &access_point.station_oid {
+                    let _ = write!(xml, " station_oid=\"{}\"", escape(station_oid));
+                }
+                xml.push_str(" />\n");
+            }
+            for switch in &infrastructure.switches {
+                let _ = writeln!(xml, "      <switch addr=\"{}\" />", switch);
+            }
+            xml.push_str("    </infrastructure>\n");
+        }
+        if let Some(ambient) = &self.ambient {
+            let _ = writeln!(xml, "    <ambient interval=\"{}\">", ambient.interval);
+            for sensor in &ambient.sensors {
+                let _ = write!(xml, "      <sensor name=\"{}\" unit=\"{}\"", escape(&sensor.name), escape(&sensor.unit));
+                if let Some(url) = &sensor.url {
+                    let _ = write!(xml, " url=\"{}\"", escape(url));
+                }
+                if let Some(device) = &sensor.device {
+                    let _ = write!(xml, " device=\"{}\"", escape(device));
+                }
+                xml.push_str(" />\n");
+            }
+            xml.push_str("    </ambient>\n");
+        }
+        if self.journal_format != JournalFormat::default() || self.journal_key.is_some() {
+            let _ = write!(xml, "    <journal format=\"{}\"", self.journal_format.name());
+            if let Some(key) = &self.journal_key {
+                let _ = write!(xml, " key=\"{}\"", escape(key));
+            }
+            xml.push_str(" />\n");
+        }
+        if let Some(library) = &self.library {
+            let _ = writeln!(xml, "    <library directory=\"{}\" />", escape(library));
+        }
+        if self.telemetry != Telemetry::default() {
+            let _ = writeln!(xml, "    <telemetry retention=\"{}\" />", self.telemetry.retention);
+        }
+        if !self.checklist.is_empty() {
+            xml.push_str("    <checklist>\n");
+            for item in &self.checklist {
+                let _ = writeln!(xml, "      <item description=\"{}\" />", escape(item));
+            }
+            xml.push_str("    </checklist>\n");
+        }
+        if !self.tags.is_empty() {
+            xml.push_str("    <tags>\n");
+            for tag in &self.tags {
+                let _ = writeln!(xml, "      <tag name=\"{}\" />", escape(tag));
+            }
+            xml.push_str("    </tags>\n");
+        }
+        if let Some(camera_shutdown) = &self.camera_shutdown {
+            let _ = writeln!(xml, "    <camera_shutdown restore=\"{}\">", camera_shutdown.restore);
+            for id in &camera_shutdown.exempt {
+                let _ = writeln!(xml, "      <exempt id=\"{}\" />", escape(id));
+            }
+            xml.push_str("    </camera_shutdown>\n");
+        }
+        if self.polling != PollingFactors::default() {
+            let _ = writeln!(xml, "    <polling link_strength=\"{}\" pin_states=\"{}\" uptime=\"{}\" />",
+                self.polling.link_strength, self.polling.pin_states, self.polling.uptime);
+        }
+        if self.heartbeat != Heartbeat::default() {
+            let _ = writeln!(xml, "    <heartbeat interval=\"{}\" timeout=\"{}\" reconnect=\"{}\" />",
+                self.heartbeat.interval, self.heartbeat.timeout, self.heartbeat.reconnect);
+        }
+        if let Some(targeted_probing) = &self.targeted_probing {
+            xml.push_str("    <targeted_probing");
+            if let Some(leases) = &targeted_probing.leases {
+                let _ = write!(xml, " leases=\"{}\"", escape(leases));
+            }
+            let _ = writeln!(xml, " arp=\"{}\" interval=\"{}\" />", targeted_probing.arp, targeted_probing.interval);
+        }
+        if self.return_to_base != ReturnToBase::default() {
+            let _ = writeln!(xml, "    <return_to_base drone_mode=\"{}\" command=\"{}\" />",
+                self.return_to_base.drone_mode.name().to_lowercase(), escape(&self.return_to_base.command));
+        }
+        if let Some(geofence) = &self.geofence {
+            xml.push_str("    <geofence");
+            if let Some(ceiling) = geofence.ceiling {
+                let _ = write!(xml, " ceiling=\"{}\"", ceiling);
+            }
+            xml.push_str(">\n");
+            for [x, y] in &geofence.vertices {
+                let _ = writeln!(xml, "      <vertex x=\"{}\" y=\"{}\" />", x, y);
+            }
+            xml.push_str("    </geofence>\n");
+        }
+        if let Some(battery_alerts) = &self.battery_alerts {
+            let _ = writeln!(xml, "    <battery_alerts policy=\"{}\">", battery_alerts.policy.name());
+            for threshold in &battery_alerts.thresholds {
+                let _ = writeln!(xml, "      <threshold robot=\"{}\" warning=\"{}\" critical=\"{}\" />",
+                    escape(&threshold.robot), threshold.warning, threshold.critical);
+            }
+            xml.push_str("    </battery_alerts>\n");
+        }
+        match &self.replication {
+            Some(Replication::Primary { socket }) =>
+                { let _ = writeln!(xml, "    <replication socket=\"{}\" />", socket); },
+            Some(Replication::Standby { primary, timeout }) =>
+                { let _ = writeln!(xml, "    <replication primary=\"{}\" timeout=\"{}\" />", primary, timeout); },
+            None => {},
+        }
+        if let Some(access) = &self.access {
+            let _ = writeln!(xml, "    <access default=\"{}\">", access.default.name());
+            for (role, permissions) in &access.roles {
+                let permissions = permissions.iter().map(|category| category.name()).collect::<Vec<_>>();
+                let _ = writeln!(xml, "      <role name=\"{}\" permissions=\"{}\" />", role.name(), permissions.join(" "));
+            }
+            for user in &access.users {
+                let _ = writeln!(xml, "      <user name=\"{}\" token=\"{}\" role=\"{}\" />",
+                    escape(&user.name), escape(&user.token), user.role.name());
+            }
+            xml.push_str("    </access>\n");
+        }
+        let _ = writeln!(xml, "  </supervisor>\n  <robots network=\"{}\">", escape(&self.robot_network));
+        if let Some(tls) = &self.tls {
+            let _ = writeln!(xml, "    <tls authority=\"{}\" certificate=\"{}\" key=\"{}\" />",
+                escape(&tls.authority), escape(&tls.certificate), escape(&tls.key));
+        }
+        if self.probing != ProbeConfiguration::default() {
+            let _ = writeln!(xml, "    <probing concurrency=\"{}\" pause_discovery=\"{}\">",
+                self.probing.concurrency, self.probing.pause_discovery);
+            for (name, timing) in &[("xbee", self.probing.xbee), ("fernbedienung", self.probing.fernbedienung)] {
+                let _ = writeln!(xml, "      <{} timeout=\"{}\" retry_delay=\"{}\" />", name, timing.timeout, timing.retry_delay);
+            }
+            xml.push_str("    </probing>\n");
+        }
+        for limits in &self.limits {
+            let _ = write!(xml, "    <limits robot=\"{}\"", escape(&limits.robot));
+            if let Some(nice) = limits.nice {
+                let _ = write!(xml, " nice=\"{}\"", nice);
+            }
+            if let Some(ionice_class) = limits.ionice_class {
+                let _ = write!(xml, " ionice_class=\"{}\"", ionice_class);
+            }
+            if let Some(ionice_level) = limits.ionice_level {
+                let _ = write!(xml, " ionice_level=\"{}\"", ionice_level);
+            }
+            if let Some(memory_max) = &limits.memory_max {
+                let _ = write!(xml, " memory_max=\"{}\"", escape(memory_max));
+            }
+            if let Some(cpu_quota) = &limits.cpu_quota {
+                let _ = write!(xml, " cpu_quota=\"{}\"", escape(cpu_quota));
+            }
+            xml.push_str(" />\n");
+        }
+        for artifact in &self.artifacts {
+            let _ = writeln!(xml, "    <artifact robot=\"{}\" pattern=\"{}\" />",
+                escape(&artifact.robot), escape(&artifact.pattern));
+        }
+        for variable in &self.environment {
+            let _ = writeln!(xml, "    <environment robot=\"{}\" name=\"{}\" value=\"{}\" />",
+                escape(&variable.robot), escape(&variable.name), escape(&variable.value));
+        }
+        for watchdog in &self.watchdogs {
+            let _ = writeln!(xml, "    <watchdog robot=\"{}\" interval=\"{}\" attempts=\"{}\" service=\"{}\" />",
+                escape(&watchdog.robot), watchdog.interval, watchdog.attempts, escape(&watchdog.service));
+        }
+        if !self.xbee_profile.is_empty() {
+            xml.push_str("    <xbee_profile>\n");
+            for (name, value) in &self.xbee_profile {
+                let _ = writeln!(xml, "      <parameter name=\"{}\" value=\"{}\" />", escape(name), escape(value));
+            }
+            xml.push_str("    </xbee_profile>\n");
+        }
+        for (name, parameters) in &self.xbee_configurations {
+            let _ = writeln!(xml, "    <xbee_configuration name=\"{}\">", escape(name));
+            for (name, value) in parameters {
+                let _ = writeln!(xml, "      <parameter name=\"{}\" value=\"{}\" />", escape(name), escape(value));
+            }
+            xml.push_str("    </xbee_configuration>\n");
+        }
+        for drone in &self.drones {
+            let _ = write!(xml, "    <drone id=\"{}\" xbee_macaddr=\"{}\" upcore_macaddr=\"{}\"",
+                escape(&drone.id), drone.xbee_macaddr, drone.upcore_macaddr);
+            if let Some(upcore_addr) = drone.upcore_addr {
+                let _ = write!(xml, " upcore_addr=\"{}\"", upcore_addr);
+            }
+            if let Some(optitrack_id) = drone.optitrack_id {
+                let _ = write!(xml, " optitrack_id=\"{}\"", optitrack_id);
+            }
+            if let Some(xbee_configuration) = &drone.xbee_configuration {
+                let _ = write!(xml, " xbee_configuration=\"{}\"", escape(xbee_configuration));
+            }
+            xml.push_str(" />\n");
+        }
+        for pipuck in &self.pipucks {
+            let _ = write!(xml, "    <pipuck id=\"{}\" rpi_macaddr=\"{}\"", escape(&pipuck.id), pipuck.rpi_macaddr);
+            if let Some(rpi_addr) = pipuck.rpi_addr {
+                let _ = write!(xml, " rpi_addr=\"{}\"", rpi_addr);
+            }
+            if let Some(optitrack_id) = pipuck.optitrack_id {
+                let _ = write!(xml, " optitrack_id=\"{}\"", optitrack_id);
+            }
+            if let Some(apriltag_id) = pipuck.apriltag_id {
+                let _ = write!(xml, " apriltag_id=\"{}\"", apriltag_id);
+            }
+            xml.push_str(" />\n");
+        }
+        for builderbot in &self.builderbots {
+            let _ = write!(xml, "    <builderbot id=\"{}\" duovero_macaddr=\"{}\"",
+                escape(&builderbot.id), builderbot.duovero_macaddr);
+            if let Some(duovero_addr) = builderbot.duovero_addr {
+                let _ = write!(xml, " duovero_addr=\"{}\"", duovero_addr);
+            }
+            if let Some(optitrack_id) = builderbot.optitrack_id {
+                let _ = write!(xml, " optitrack_id=\"{}\"", optitrack_id);
+            }
+            if let Some(apriltag_id) = builderbot.apriltag_id {
+                let _ = write!(xml, " apriltag_id=\"{}\"", apriltag_id);
+            }
+            xml.push_str(" />\n");
+        }
+        for robot in &self.simulated_robots {
+            let _ = writeln!(xml, "    <simulated_robot id=\"{}\" robot=\"{}\" />",
+                escape(&robot.id), escape(&robot.robot));
+        }
+        for robot in &self.ros_robots {
+            let _ = writeln!(xml, "    <ros_robot id=\"{}\" url=\"{}\">", escape(&robot.id), escape(&robot.url));
+            for topic in &robot.topics {
+                let _ = writeln!(xml, "      <topic name=\"{}\" type=\"{}\" mapping=\"{}\" />",
+                    escape(&topic.name), escape(&topic.message_type), topic.mapping.name());
+            }
+            let _ = writeln!(xml, "      <launch start=\"{}\" stop=\"{}\" />",
+                escape(&robot.start_service), escape(&robot.stop_service));
+            xml.push_str("    </ros_robot>\n");
+        }
+        for group in &self.groups {
+            let _ = writeln!(xml, "    <group name=\"{}\">", escape(&group.name));
+            for member in &group.members {
+                let _ = writeln!(xml, "      <member id=\"{}\" />", escape(member));
+            }
+            xml.push_str("    </group>\n");
+        }
+        xml.push_str("  </robots>\n</configuration>\n");
+        xml
+    }
+}
@@ -2,12 +2,18 @@ use std::{fmt::Display, net::Ipv4Addr};
 use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
+use crate::{access::Category, subscription::Capability};
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Descriptor {
     pub id: String,
     pub xbee_macaddr: macaddr::MacAddr6,
     pub upcore_macaddr: macaddr::MacAddr6,
+    /* the address reserved for the UpCore by the DHCP server, if any */
+    pub upcore_addr: Option<Ipv4Addr>,
     pub optitrack_id: Option<i32>,
+    /* the name of the Xbee configuration of the hardware revision of the drone */
+    pub xbee_configuration: Option<String>,
 }
 
 impl Display for Descriptor {
@@ -16,40 +22,349 @@ impl Display for Descriptor {
     }
 }
 
+/// The deviation in millivolts between the highest and lowest cell of the battery above which the battery
+/// is considered to be imbalanced, imbalanced batteries are likely to fail in flight
+pub const CELL_IMBALANCE_THRESHOLD_MV: u16 = 100;
+
+/// The difference in millivolts between the highest and the lowest cell voltage
+pub fn cell_imbalance(cells: &[u16]) -> u16 {
+    match (cells.iter().max(), cells.iter().min()) {
+        (Some(max), Some(min)) => max - min,
+        _ => 0,
+    }
+}
+
+/// The channel that is used for safety-critical commands such as disabling autonomous mode
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ControlPath {
+    Xbee,
+    /* the commands are relayed through the Up Core if the Xbee is not available */
+    UpCore,
+    Unavailable,
+}
+
+/// The templates of the MAVLink console, i.e., the commands that are sent to the Pixhawk in a COMMAND_LONG
+/// message and the SET_MODE message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MavlinkTemplate {
+    ComponentArmDisarm,
+    PreflightRebootShutdown,
+    PreflightCalibration,
+    DoSetMode,
+    DoMotorTest,
+    RequestMessage,
+    SetMessageInterval,
+    SetMode,
+}
+
+impl MavlinkTemplate {
+    pub const ALL: &'static [MavlinkTemplate] = &[
+        MavlinkTemplate::ComponentArmDisarm,
+        MavlinkTemplate::PreflightRebootShutdown,
+        MavlinkTemplate::PreflightCalibration,
+        MavlinkTemplate::DoSetMode,
+        MavlinkTemplate::DoMotorTest,
+        MavlinkTemplate::RequestMessage,
+        MavlinkTemplate::SetMessageInterval,
+        MavlinkTemplate::SetMode,
+    ];
+
+    /// The name of the command or message as in the MAVLink specification
+    pub fn name(&self) -> &'static str {
+        match self {
+            MavlinkTemplate::ComponentArmDisarm => "MAV_CMD_COMPONENT_ARM_DISARM",
+            MavlinkTemplate::PreflightRebootShutdown => "MAV_CMD_PREFLIGHT_REBOOT_SHUTDOWN",
+            MavlinkTemplate::PreflightCalibration => "MAV_CMD_PREFLIGHT_CALIBRATION",
+            MavlinkTemplate::DoSetMode => "MAV_CMD_DO_SET_MODE",
+            MavlinkTemplate::DoMotorTest => "MAV_CMD_DO_MOTOR_TEST",
+            MavlinkTemplate::RequestMessage => "MAV_CMD_REQUEST_MESSAGE",
+            MavlinkTemplate::SetMessageInterval => "MAV_CMD_SET_MESSAGE_INTERVAL",
+            MavlinkTemplate::SetMode => "SET_MODE",
+        }
+    }
+
+    /// The parameters of the command or message as in the MAVLink specification, the remaining parameters
+    /// of a COMMAND_LONG message are set to zero
+    pub fn params(&self) -> &'static [&'static str] {
+        match self {
+            MavlinkTemplate::ComponentArmDisarm => &["Arm (0: disarm, 1: arm)", "Force (21196: force)"],
+            MavlinkTemplate::PreflightRebootShutdown => &["Autopilot (0: none, 1: reboot, 2: shutdown)",
+                "Companion (0: none, 1: reboot, 2: shutdown)"],
+            MavlinkTemplate::PreflightCalibration => &["Gyroscope", "Magnetometer", "Ground pressure",
+                "Remote control", "Accelerometer", "Compass/motor interference", "Airspeed"],
+            MavlinkTemplate::DoSetMode => &["Mode", "Custom mode", "Custom sub-mode"],
+            MavlinkTemplate::DoMotorTest => &["Motor instance", "Throttle type", "Throttle", "Timeout (s)",
+                "Motor count", "Test order"],
+            MavlinkTemplate::RequestMessage => &["Message ID"],
+            MavlinkTemplate::SetMessageInterval => &["Message ID", "Interval (us, -1: disable, 0: default)"],
+            MavlinkTemplate::SetMode => &["Base mode (MAV_MODE)", "Custom mode"],
+        }
+    }
+
+    /// Checks the parameters against the ranges in the MAVLink specification
+    pub fn validate(&self, params: &[f32]) -> Result<(), String> {
+        if params.len() != self.params().len() {
+            return Err(format!("{} expects {} parameters", self.name(), self.params().len()));
+        }
+        if let Some(param) = params.iter().position(|param| !param.is_finite()) {
+            return Err(format!("{} is not a number", self.params()[param]));
+        }
+        let integer = |index: usize, min: f32, max: f32| match params[index] {
+            value if value.fract() == 0.0 && value >= min && value <= max => Ok(()),
+            _ => Err(format!("{} must be an integer between {} and {}", self.params()[index], min, max)),
+        };
+        match self {
+            MavlinkTemplate::ComponentArmDisarm => integer(0, 0.0, 1.0),
+            MavlinkTemplate::PreflightRebootShutdown => integer(0, 0.0, 3.0).and(integer(1, 0.0, 3.0)),
+            MavlinkTemplate::PreflightCalibration => (0..params.len()).try_for_each(|index| integer(index, 0.0, 4.0)),
+            MavlinkTemplate::DoSetMode => integer(0, 0.0, 255.0),
+            MavlinkTemplate::DoMotorTest => integer(0, 1.0, 255.0).and(integer(1, 0.0, 3.0)),
+            MavlinkTemplate::RequestMessage => integer(0, 0.0, 16777215.0),
+            MavlinkTemplate::SetMessageInterval => integer(0, 0.0, 16777215.0).and(integer(1, -1.0, f32::MAX)),
+            MavlinkTemplate::SetMode => integer(0, 0.0, 255.0).and(integer(1, 0.0, u32::MAX as f32)),
+        }
+    }
+}
+
+/// A command from the MAVLink console
+/// The flight modes of PX4 that can be selected from the user interface
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FlightMode {
+    Manual,
+    Stabilized,
+    Altitude,
+    Position,
+    Offboard,
+    Hold,
+    Return,
+    Land,
+}
+
+impl FlightMode {
+    pub const ALL: &'static [FlightMode] = &[
+        FlightMode::Manual,
+        FlightMode::Stabilized,
+        FlightMode::Altitude,
+        FlightMode::Position,
+        FlightMode::Offboard,
+        FlightMode::Hold,
+        FlightMode::Return,
+        FlightMode::Land,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlightMode::Manual => "Manual",
+            FlightMode::Stabilized => "Stabilized",
+            FlightMode::Altitude => "Altitude",
+            FlightMode::Position => "Position",
+            FlightMode::Offboard => "Offboard",
+            FlightMode::Hold => "Hold",
+            FlightMode::Return => "Return",
+            FlightMode::Land => "Land",
+        }
+    }
+
+    /// The main mode and the sub mode of PX4, the sub mode is only used by the auto modes
+    pub fn px4_mode(&self) -> (u8, u8) {
+        match self {
+            FlightMode::Manual => (1, 0),
+            FlightMode::Altitude => (2, 0),
+            FlightMode::Position => (3, 0),
+            FlightMode::Hold => (4, 3),
+            FlightMode::Return => (4, 5),
+            FlightMode::Land => (4, 6),
+            FlightMode::Offboard => (6, 0),
+            FlightMode::Stabilized => (7, 0),
+        }
+    }
+
+    /// Decodes the custom mode of a heartbeat from PX4, which contains the main mode in its third byte and
+    /// the sub mode in its fourth byte
+    pub fn from_custom_mode(custom_mode: u32) -> Option<FlightMode> {
+        let main_mode = (custom_mode >> 16) as u8;
+        let sub_mode = (custom_mode >> 24) as u8;
+        FlightMode::ALL.iter().copied().find(|mode| match mode.px4_mode() {
+            (4, expected) => main_mode == 4 && sub_mode == expected,
+            (expected, _) => main_mode == expected,
+        })
+    }
+}
+
+/// The state of the Pixhawk as reported by its heartbeats, the mode is None if it is not one of the modes
+/// that can be selected from the user interface
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FlightState {
+    pub armed: bool,
+    pub mode: Option<FlightMode>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MavlinkCommand {
+    pub template: MavlinkTemplate,
+    pub params: Vec<f32>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Update {
     Battery(i32),
+    /* the voltages of the individual cells of the battery in millivolts */
+    BatteryCells(Vec<u16>),
+    ControlPath(ControlPath),
     Camera {
         camera: String,
         result: Result<Bytes, String>
     },
     FernbedienungConnected(Ipv4Addr),
     FernbedienungDisconnected,
+    /* the heartbeat of the robot timed out and its last known address is probed again */
+    FernbedienungReconnecting(Ipv4Addr),
     FernbedienungSignal(i32),
+    ResourceLimitViolation(String),
+    /* the uptime of the robot and the duration of its association with the supervisor in seconds */
+    Uptime {
+        robot: u64,
+        association: u64,
+    },
+    UnexpectedReboot(String),
+    /* the interventions of the watchdog on the robot since the previous association */
+    Watchdog(Vec<String>),
+    /* the name and version of the operating system image on the robot */
+    Firmware(String),
+    /* whether the camera streams are supported by the robot */
+    Cameras(crate::camera::Availability),
+    /* the actions that will be executed once the robot is associated again */
+    QueuedActions(Vec<(u64, String)>),
+    Artifacts(Result<Vec<String>, String>),
+    /* a summary of the last experiment that ran on the robot */
+    ExperimentSummary(crate::experiment::Summary),
+    /* an event that was recorded in the event log of the robot and the whole event log, which is sent to
+       new subscribers */
+    Event(crate::event_log::Entry),
+    EventLog(Vec<crate::event_log::Entry>),
     XbeeConnected(Ipv4Addr),
     XbeeDisconnected,
     XbeeSignal(i32),
     Mavlink(String),
+    /* the acknowledgement of a command from the MAVLink console as received from the Pixhawk */
+    MavlinkResponse(String),
     Bash(String),
+    /* the progress of sending input to the Bash terminal in bytes */
+    BashProgress {
+        sent: usize,
+        total: usize,
+    },
+    /* the output of ARGoS while it runs an experiment on the robot */
+    ArgosOutput {
+        error: bool,
+        text: String,
+    },
     PowerState {
         pixhawk: bool,
         upcore: bool,
     },
+    /* the parameters of the Xbee as read during the last audit */
+    XbeeAudit(XbeeAudit),
+    /* the state of the Pixhawk, this is sent with every heartbeat of the Pixhawk */
+    FlightState(FlightState),
+}
+
+impl Update {
+    /// The stream to which this update belongs
+    pub fn capability(&self) -> Capability {
+        match self {
+            Update::Camera { .. } => Capability::Cameras,
+            Update::Bash(_) | Update::BashProgress { .. } | Update::ArgosOutput { .. } |
+            Update::Mavlink(_) | Update::MavlinkResponse(_) => Capability::Terminals,
+            _ => Capability::Telemetry,
+        }
+    }
+}
+
+/// The parameters of the Xbee that are read during every audit, i.e., the firmware and hardware versions
+pub const XBEE_AUDIT_PARAMETERS: &[&str] = &["VR", "HV"];
+
+/// A parameter of the Xbee as read during an audit and the value expected by the profile in the
+/// configuration. Values are formatted in hexadecimal without leading zeros as shown by XCTU
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XbeeParameter {
+    pub name: String,
+    pub value: Result<String, String>,
+    pub expected: Option<String>,
+}
+
+impl XbeeParameter {
+    /// Whether the parameter could not be read or differs from the profile
+    pub fn discrepancy(&self) -> bool {
+        match (&self.value, &self.expected) {
+            (Err(_), _) => true,
+            (Ok(value), Some(expected)) =>
+                !value.trim_start_matches('0').eq_ignore_ascii_case(expected.trim_start_matches('0')),
+            (Ok(_), None) => false,
+        }
+    }
+}
+
+impl Display for XbeeParameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.value, &self.expected) {
+            (Err(error), _) => write!(f, "{}: {}", self.name, error),
+            (Ok(value), Some(expected)) if self.discrepancy() =>
+                write!(f, "{} is {} (expected {})", self.name, value, expected),
+            (Ok(value), _) => write!(f, "{} is {}", self.name, value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XbeeAudit {
+    pub time: String,
+    pub parameters: Vec<XbeeParameter>,
+}
+
+impl XbeeAudit {
+    pub fn discrepancies(&self) -> impl Iterator<Item = &XbeeParameter> {
+        self.parameters.iter().filter(|parameter| parameter.discrepancy())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Request {
+    CancelQueuedAction(u64),
     BashTerminalStart,
     BashTerminalStop,
     BashTerminalRun(String),
     CameraStreamEnable(bool),
     Identify,
+    /* download the files at the given paths on the robot into a directory on the supervisor machine */
+    FetchResults(Vec<String>),
     PixhawkPowerEnable(bool),
     MavlinkTerminalStart,
     MavlinkTerminalStop,
     MavlinkTerminalRun(String),
+    MavlinkCommand(MavlinkCommand),
+    /* these requests succeed once the Pixhawk has acknowledged the command */
+    Arm,
+    Disarm,
+    SetFlightMode(FlightMode),
     UpCorePowerEnable(bool),
     UpCoreHalt,
     UpCoreReboot,
 }
 
+impl Request {
+    /// The category of the request for checking the permissions of the client
+    pub fn category(&self) -> Option<Category> {
+        match self {
+            Request::BashTerminalStart | Request::BashTerminalStop | Request::BashTerminalRun(_) |
+            Request::MavlinkTerminalStart | Request::MavlinkTerminalStop | Request::MavlinkTerminalRun(_) |
+            Request::MavlinkCommand(_) => Some(Category::Terminals),
+            Request::PixhawkPowerEnable(_) | Request::UpCorePowerEnable(_) |
+            Request::UpCoreHalt | Request::UpCoreReboot |
+            Request::Arm | Request::Disarm | Request::SetFlightMode(_) => Some(Category::Power),
+            Request::FetchResults(_) => Some(Category::Experiment),
+            _ => None,
+        }
+    }
+}
+
@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+
+/// The kind of an event in the event log of a robot, which determines how the event is highlighted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Category {
+    /* the robot or one of its devices connected, was lost, or disconnected */
+    Connection,
+    Experiment,
+    /* the robot or one of its devices was halted, rebooted, or switched on or off */
+    Power,
+    Error,
+}
+
+/// An event in the event log of a robot, which is kept by the supervisor so that the operators can see what
+/// happened to a robot without reading the logs of the supervisor
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Entry {
+    /* the time at which the event occurred in RFC 3339 format */
+    pub timestamp: String,
+    pub category: Category,
+    pub description: String,
+}
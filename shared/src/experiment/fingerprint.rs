@@ -0,0 +1,40 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::{Serialize, Deserialize};
+
+/// A fingerprint of everything that defines a run, i.e., the control software, the random seeds, the
+/// participating robots, and the settings that are applied to the robots. Each component is hashed
+/// separately so that the fingerprints of two runs can be compared component by component
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Fingerprint {
+    pub components: BTreeMap<String, String>,
+}
+
+impl Fingerprint {
+    pub fn add<C: AsRef<[u8]>>(&mut self, component: impl Into<String>, data: C) {
+        self.components.insert(component.into(), format!("{:x}", md5::compute(data)));
+    }
+
+    /// The digest over all components of the fingerprint
+    pub fn digest(&self) -> String {
+        let mut context = md5::Context::new();
+        for (component, digest) in &self.components {
+            context.consume(component);
+            context.consume(b"=");
+            context.consume(digest);
+            context.consume(b"\n");
+        }
+        format!("{:x}", context.compute())
+    }
+
+    /// The components that differ between two fingerprints, including the components that are only
+    /// present in one of them
+    pub fn differences<'a>(&'a self, other: &'a Fingerprint) -> Vec<&'a str> {
+        self.components.keys()
+            .chain(other.components.keys())
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|component| self.components.get(*component) != other.components.get(*component))
+            .collect()
+    }
+}
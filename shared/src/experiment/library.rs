@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+
+use super::software::Software;
+
+/// A version of a bundle of control software that is stored in the library of the supervisor. Only the
+/// names and the checksums of the files are sent to the clients, the contents stay on the supervisor
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Bundle {
+    pub name: String,
+    pub version: u32,
+    /* the time at which the version was stored in RFC 3339 format */
+    pub stored: String,
+    /* the names of the files and their checksums */
+    pub checksums: Vec<(String, String)>,
+}
+
+impl Bundle {
+    pub fn reference(&self) -> Reference {
+        Reference { name: self.name.clone(), version: self.version }
+    }
+}
+
+/// A version of a bundle in the library, which can be selected instead of uploading the software
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Reference {
+    pub name: String,
+    pub version: u32,
+}
+
+impl std::fmt::Display for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} v{}", self.name, self.version)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* store the software as the next version of the bundle with the given name */
+    Store(String, Software),
+    Delete(Reference),
+}
+
+/// Whether a name can be used for a bundle, i.e., whether it can be used as a directory name on the supervisor
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && name.chars()
+        .all(|character| character.is_ascii_alphanumeric() || matches!(character, '-' | '_' | '.'))
+}
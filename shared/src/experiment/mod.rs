@@ -1,23 +1,295 @@
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
+pub mod fingerprint;
+pub mod library;
+pub mod profile;
 pub mod software;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Request {
     Start {
-        builderbot_software: software::Software,
-        drone_software: software::Software,
-        pipuck_software: software::Software,
+        builderbot_software: software::Source,
+        drone_software: software::Source,
+        pipuck_software: software::Source,
+        /* the software for individual robots, which replaces the software for their robot type */
+        overrides: BTreeMap<String, software::Software>,
+        /* what to do if the experiment can only be started on some of the robots */
+        partial_start: PartialStart,
+        /* the duration, battery thresholds, and camera policy, e.g., from a profile */
+        #[serde(default)]
+        settings: profile::Settings,
     },
     Stop,
+    /* add a note to the timeline of the running experiment, the tags mark it for filtering the journal later */
+    Annotate {
+        note: String,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /* acknowledge an item of the checklist on behalf of an operator or withdraw the acknowledgement */
+    Acknowledge {
+        item: usize,
+        operator: Option<String>,
+    },
+    /* run the smoke test on a single robot or, if no robot is given, on all robots */
+    SmokeTest(Option<String>),
+    /* measure the throughput between the supervisor and a single robot or, if no robot is given, all robots */
+    BandwidthTest(Option<String>),
+    /* check the ARGoS configuration and the syntax of the Lua scripts without starting an experiment */
+    Check {
+        builderbot_software: software::Source,
+        drone_software: software::Source,
+        pipuck_software: software::Source,
+        overrides: BTreeMap<String, software::Software>,
+    },
+}
+
+/// How the arena handles an experiment that could only be set up or started on some of the participating robots
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum PartialStart {
+    /* stop all participating robots so that the swarm is not left in a mixed state */
+    Rollback,
+    /* continue the experiment with the robots that were set up and started successfully */
+    Continue,
+}
+
+impl Default for PartialStart {
+    fn default() -> Self {
+        PartialStart::Rollback
+    }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, Serialize)]
+/// The state of the experiment as tracked by the arena. The software is uploaded to all participating robots
+/// before any of them is started, the robots are only started once every robot has been set up successfully
+/// unless the operator chose to continue with the robots that could be set up
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum State {
-    Standby,
-    Active,
+    Idle,
+    Uploading,
+    Ready,
+    Running,
+    Stopping,
+    Finished,
+    /* the experiment could not be started or stopped */
+    Error(String),
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Idle
+    }
+}
+
+impl State {
+    /// Whether a new experiment can be started, i.e., no experiment is being set up or stopped
+    pub fn startable(&self) -> bool {
+        !matches!(self, State::Uploading | State::Ready | State::Stopping)
+    }
+}
+
+/// The readiness of a robot that participates in the experiment
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Readiness {
+    /* the robot has not been set up yet */
+    Pending,
+    Uploading,
+    Ready,
+    Running,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Status {
+    pub state: State,
+    /* the readiness of each participating robot */
+    pub robots: BTreeMap<String, Readiness>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum TimelineCategory {
+    Phase,
+    Safety,
+    Disconnect,
+    Annotation,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TimelineEvent {
+    /* milliseconds since the journal was started */
+    pub timestamp: i64,
+    pub category: TimelineCategory,
+    pub description: String,
+    /* e.g., `collision` or `battery`, journals recorded before schema version 3 have no tags */
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// The origin of an entry in the live log of an experiment
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum LogSource {
+    StandardOutput,
+    StandardError,
+    /* a summary of the poses from the tracking system, at most one per second */
+    Tracking,
+    Timeline,
+    /* the robots that participate in the experiment */
+    Participants,
+}
+
+/// An event from the journal as shown in the live log of an experiment. The log combines the output of ARGoS
+/// on all robots with the events that concern the whole experiment, the output of ARGoS is split into lines
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LogEntry {
+    /* milliseconds since the journal was started */
+    pub timestamp: i64,
+    pub source: LogSource,
+    /* the robot that the entry is about, None for entries about the whole experiment */
+    pub robot: Option<String>,
+    pub text: String,
+}
+
+/// An item of the checklist that must be acknowledged by an operator before an experiment can be started,
+/// the acknowledgements are recorded in the journal and are reset once the experiment has been started
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ChecklistItem {
+    pub description: String,
+    pub acknowledgement: Option<Acknowledgement>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Acknowledgement {
+    pub operator: String,
+    /* the time at which the item was acknowledged in RFC 3339 format */
+    pub time: String,
+}
+
+/// A single check of a smoke test, e.g., spinning a wheel or reading the proximity sensors
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SmokeTestCheck {
+    pub name: String,
+    pub passed: bool,
+    /* the measured value, e.g., the speed of a wheel */
+    pub value: String,
+}
+
+/// The outcome of the smoke test of a robot, smoke tests briefly exercise the sensors and actuators of a
+/// robot so that broken hardware is detected before an experiment is started
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SmokeTest {
+    /* the time at which the smoke test finished in RFC 3339 format */
+    pub time: String,
+    /* the checks that were made or why the smoke test could not be run */
+    pub result: Result<Vec<SmokeTestCheck>, String>,
+}
+
+impl SmokeTest {
+    /// Whether the smoke test could be run and every check passed
+    pub fn passed(&self) -> bool {
+        matches!(&self.result, Ok(checks) if checks.iter().all(|check| check.passed))
+    }
+}
+
+/// A compact summary of how the last experiment went on a single robot
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Summary {
+    /* the time at which ARGoS finished in RFC 3339 format */
+    pub finished: String,
+    /* how ARGoS finished and whether this is considered a failure */
+    pub exit: String,
+    pub failed: bool,
+    /* the number of bytes written by ARGoS to standard output and standard error */
+    pub output: u64,
+    /* the number of messages sent by the robot via the message router */
+    pub messages: u64,
+    /* the safety events and disconnects that occurred during the experiment */
+    pub events: Vec<String>,
+    /* the backtrace of ARGoS if it crashed and a core dump could be retrieved */
+    #[serde(default)]
+    pub backtrace: Option<String>,
+    /* the battery readings over the experiment, if the robot reported any */
+    #[serde(default)]
+    pub energy: Option<Energy>,
+}
+
+/// The last lines that ARGoS wrote to standard output and standard error on a robot during the last
+/// experiment, as recorded in the journal
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Excerpt {
+    /* whether each line was written to standard error and the line itself */
+    pub lines: Vec<(bool, String)>,
+    /* the number of earlier lines that are not included */
+    pub omitted: usize,
+}
+
+/// The energy that a robot used during an experiment, estimated from its battery readings. Since the
+/// robots only report the charge of their batteries, the energy is given as the fraction of the capacity
+/// of the battery that was used
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Energy {
+    /* the first and the last battery readings in percent */
+    pub start: i32,
+    pub end: i32,
+    /* the first and the last voltages of the battery in millivolts, if the robot reports them */
+    pub voltage: Option<(u32, u32)>,
+}
+
+impl Energy {
+    /// Adds a battery reading, the first reading is the reading at the start of the experiment
+    pub fn battery(energy: &mut Option<Energy>, reading: i32) {
+        let energy = energy.get_or_insert_with(|| Energy { start: reading, ..Default::default() });
+        energy.end = reading;
+    }
+
+    /// Adds a voltage reading in millivolts
+    pub fn voltage(&mut self, voltage: u32) {
+        let (start, _) = self.voltage.unwrap_or((voltage, voltage));
+        self.voltage = Some((start, voltage));
+    }
+
+    /// The percentage of the capacity of the battery that was used, charging is not counted
+    pub fn consumed(&self) -> i32 {
+        (self.start - self.end).max(0)
+    }
+}
+
+/// Statistics about the frames received from the tracking system while recording a journal. Frames are
+/// considered to be missing when the frame numbers assigned by Motive are not consecutive
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TrackingGaps {
+    /* the number of frames that were received */
+    pub frames: u64,
+    /* the number of frames that were missing and the number of gaps in which they were missing */
+    pub dropped: u64,
+    pub gaps: u64,
+    /* the largest number of consecutive frames that were missing */
+    pub longest: u64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct JournalStatistics {
+    /* number of events recorded for each category */
+    pub events: BTreeMap<String, u64>,
+    /* number of bytes written to the journal */
+    pub file_size: u64,
+    /* number of bytes remaining on the disk holding the journal */
+    pub disk_available: Option<u64>,
+    /* notable events, e.g., experiment phases and safety events, in the order they were recorded */
+    pub timeline: Vec<TimelineEvent>,
+    /* the fingerprint of the run that is being recorded */
+    pub fingerprint: Option<fingerprint::Fingerprint>,
+    /* the frames that were dropped between the tracking system and the journal */
+    pub tracking: TrackingGaps,
+    /* whether the journal is encrypted at rest */
+    pub encrypted: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Update {
-    State(State),
+    Status(Status),
+    Journal(JournalStatistics),
+    Checklist(Vec<ChecklistItem>),
+    /* the last smoke test of each robot, robots that have not been tested yet map to None */
+    SmokeTests(BTreeMap<String, Option<SmokeTest>>),
+    /* an entry of the live log, only sent to the connections that are subscribed to the log */
+    Log(LogEntry),
 }
\ No newline at end of file
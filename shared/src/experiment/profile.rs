@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+use crate::configuration::{BatteryAlerts, CameraShutdown};
+use super::{PartialStart, software::{Software, Source}};
+
+/* the maximum number of profiles that a profile can inherit from, which also guards against cycles */
+const MAXIMUM_DEPTH: usize = 16;
+
+/// What happens to the camera streams of the robots while an experiment is running
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum CameraPolicy {
+    /* the camera streams keep running regardless of the configuration */
+    Keep,
+    /* the camera streams are suspended, except for the exempted robots */
+    Suspend(CameraShutdown),
+}
+
+/// A rule that reduces the amount of standard output of ARGoS that is journaled, e.g., for controllers that
+/// log at every tick. Of the lines that match the pattern (a regular expression), only every `every`th line
+/// is recorded, starting with the first
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Decimation {
+    pub pattern: String,
+    pub every: u32,
+}
+
+/// The settings of an experiment that are otherwise taken from the configuration of the supervisor
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Settings {
+    /* the experiment is stopped after this many seconds, it runs until it is stopped if None */
+    pub duration: Option<u64>,
+    /* replace the battery thresholds of the configuration while the experiment is running */
+    pub battery_alerts: Option<BatteryAlerts>,
+    /* replace the camera shutdown of the configuration while the experiment is running */
+    pub cameras: Option<CameraPolicy>,
+    /* the standard output that is journaled at a reduced rate, all other output is journaled at full rate */
+    pub decimation: Option<Vec<Decimation>>,
+}
+
+/// A named set of choices for starting an experiment, e.g., "flight-demo" or "ground-only". A profile only
+/// contains the choices that it makes, every other choice is inherited from its parent
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Profile {
+    pub name: String,
+    pub parent: Option<String>,
+    /* the group whose members participate in the experiment, all robots participate if None */
+    pub group: Option<String>,
+    pub builderbot_software: Option<Source>,
+    pub drone_software: Option<Source>,
+    pub pipuck_software: Option<Source>,
+    pub overrides: Option<BTreeMap<String, Software>>,
+    pub partial_start: Option<PartialStart>,
+    pub settings: Settings,
+}
+
+impl Profile {
+    /// Fills the choices that this profile does not make with the choices of the given profile
+    fn inherit(&mut self, parent: &Profile) {
+        fn inherit<T: Clone>(child: &mut Option<T>, parent: &Option<T>) {
+            if child.is_none() {
+                *child = parent.clone();
+            }
+        }
+        inherit(&mut self.group, &parent.group);
+        inherit(&mut self.builderbot_software, &parent.builderbot_software);
+        inherit(&mut self.drone_software, &parent.drone_software);
+        inherit(&mut self.pipuck_software, &parent.pipuck_software);
+        inherit(&mut self.overrides, &parent.overrides);
+        inherit(&mut self.partial_start, &parent.partial_start);
+        inherit(&mut self.settings.duration, &parent.settings.duration);
+        inherit(&mut self.settings.battery_alerts, &parent.settings.battery_alerts);
+        inherit(&mut self.settings.cameras, &parent.settings.cameras);
+        inherit(&mut self.settings.decimation, &parent.settings.decimation);
+    }
+
+    /// Removes the choices that this profile shares with the given resolved profile, so that this profile
+    /// can inherit from it and only makes the choices in which it differs
+    pub fn relative_to(&mut self, parent: &Profile) {
+        fn relative<T: PartialEq>(child: &mut Option<T>, parent: &Option<T>) {
+            if child == parent {
+                *child = None;
+            }
+        }
+        relative(&mut self.group, &parent.group);
+        relative(&mut self.builderbot_software, &parent.builderbot_software);
+        relative(&mut self.drone_software, &parent.drone_software);
+        relative(&mut self.pipuck_software, &parent.pipuck_software);
+        relative(&mut self.overrides, &parent.overrides);
+        relative(&mut self.partial_start, &parent.partial_start);
+        relative(&mut self.settings.duration, &parent.settings.duration);
+        relative(&mut self.settings.battery_alerts, &parent.settings.battery_alerts);
+        relative(&mut self.settings.cameras, &parent.settings.cameras);
+        relative(&mut self.settings.decimation, &parent.settings.decimation);
+        self.parent = Some(parent.name.clone());
+    }
+}
+
+/// Resolves the profile with the given name against its ancestors. The resolved profile makes every choice
+/// that the profile or one of its ancestors makes and has no parent
+pub fn resolve(profiles: &[Profile], name: &str) -> Result<Profile, String> {
+    let find = |name: &str| profiles.iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| format!("Profile \"{}\" does not exist", name));
+    let mut resolved = find(name)?.clone();
+    let mut ancestors = vec![name];
+    while let Some(parent) = resolved.parent.take() {
+        let profile = find(&parent)?;
+        if ancestors.contains(&profile.name.as_str()) {
+            return Err(format!("Profile \"{}\" inherits from itself via \"{}\"", name, parent));
+        }
+        if ancestors.len() > MAXIMUM_DEPTH {
+            return Err(format!("Profile \"{}\" inherits from more than {} profiles", name, MAXIMUM_DEPTH));
+        }
+        ancestors.push(&profile.name);
+        resolved.inherit(profile);
+        resolved.parent = profile.parent.clone();
+    }
+    Ok(resolved)
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* add a profile or replace the profile with the same name */
+    Save(Profile),
+    Delete(String),
+}
@@ -1,5 +1,12 @@
 use serde::{Serialize, Deserialize};
 
+use super::library::Reference;
+
+/// The maximum size of a file of the software in bytes
+pub const MAX_FILE_SIZE: usize = 4 * 1024 * 1024;
+/// The maximum size of all files of the software in bytes, since the software is uploaded to every robot
+pub const MAX_SIZE: usize = 16 * 1024 * 1024;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("ARGoS configuration file missing")]
@@ -11,20 +18,78 @@ pub enum Error {
     #[error("Could not find referenced file {0}")]
     MissingReferencedFile(String),
 
+    #[error("{0} is larger than {1} bytes")]
+    FileTooLarge(String, usize),
+
+    #[error("The files are larger than {0} bytes in total")]
+    TooLarge(usize),
+
     #[error("Configuration file was not valid UTF-8")]
     DecodeError(#[from] std::str::Utf8Error),
 
-    #[error("Configuration file was not valid XML")]
-    ParseError(#[from] roxmltree::Error),
+    /* the error includes the line and the column at which the file could not be parsed */
+    #[error("{0} is not valid XML: {1}")]
+    ParseError(String, roxmltree::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub type Checksums = Vec<(String, md5::Digest)>;
 
-#[derive(Clone, Deserialize, Default, Debug, Serialize)]
+#[derive(Clone, Deserialize, Default, Debug, PartialEq, Serialize)]
 pub struct Software(pub Vec<(String, Vec<u8>)>);
 
+/// A git repository from which the control software for a robot type is deployed
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Repository {
+    pub url: String,
+    /* a branch, tag, or commit */
+    pub reference: String,
+    /* the directory inside of the repository that contains the control software */
+    pub directory: String,
+}
+
+/// The source of the control software for a robot type
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Source {
+    /* files that were selected in the user interface */
+    Files(Software),
+    /* the software is built from the repository by the supervisor when the experiment is started */
+    Repository(Repository),
+    /* a bundle that is stored in the library of the supervisor */
+    Library(Reference),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Files(Software::default())
+    }
+}
+
+impl Source {
+    /// The files of the software, any repository or bundle is discarded when switching to files
+    pub fn files(&mut self) -> &mut Software {
+        if !matches!(self, Source::Files(_)) {
+            *self = Source::Files(Software::default());
+        }
+        match self {
+            Source::Files(software) => software,
+            Source::Repository(_) | Source::Library(_) => unreachable!(),
+        }
+    }
+
+    /// The repository of the software, any files or bundle are discarded when switching to a repository
+    pub fn repository(&mut self) -> &mut Repository {
+        if !matches!(self, Source::Repository(_)) {
+            *self = Source::Repository(Repository::default());
+        }
+        match self {
+            Source::Repository(repository) => repository,
+            Source::Files(_) | Source::Library(_) => unreachable!(),
+        }
+    }
+}
+
 impl Software {
     pub fn add<F: Into<String>, C: Into<Vec<u8>>>(&mut self, new_filename: F, new_contents: C) {
         let new_filename = new_filename.into();
@@ -48,6 +113,28 @@ impl Software {
             .collect()
     }
 
+    /// A textual summary of the files and their checksums that does not depend on the order of the files
+    pub fn summary(&self) -> String {
+        let mut checksums = self.checksums();
+        checksums.sort_by(|(a, _), (b, _)| a.cmp(b));
+        checksums.into_iter()
+            .map(|(filename, checksum)| format!("{} {:x}\n", filename, checksum))
+            .collect()
+    }
+
+    /// The random seed from the experiment node of the ARGoS configuration file (if specified)
+    pub fn random_seed(&self) -> Result<Option<String>> {
+        let (filename, config) = self.argos_config()?;
+        let config = std::str::from_utf8(&config[..])?;
+        let config = roxmltree::Document::parse(&config)
+            .map_err(|error| Error::ParseError(filename.clone(), error))?;
+        let seed = config.root().descendants()
+            .find(|node| node.tag_name().name() == "experiment")
+            .and_then(|node| node.attribute("random_seed"))
+            .map(str::to_owned);
+        Ok(seed)
+    }
+
     pub fn argos_config(&self) -> Result<&(String, Vec<u8>)> {
         let config = self.0.iter()
             .filter(|entry| {
@@ -61,10 +148,44 @@ impl Software {
         }
     }
    
+    /// Checks the ARGoS configuration file and the sizes of the files, returning the first problem
     pub fn check_config(&self) -> Result<()> {
-        let config = self.argos_config()?;
-        let config = std::str::from_utf8(&config.1[..])?;
-        let config = roxmltree::Document::parse(&config)?;
+        match self.validate().into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that there is exactly one ARGoS configuration file, that it is well-formed XML, that the Lua
+    /// scripts that it references are part of the software, and that the files do not exceed the size
+    /// limits. Every problem is returned together with the file that it concerns, if any
+    pub fn validate(&self) -> Vec<(Option<String>, Error)> {
+        let mut problems = self.0.iter()
+            .filter(|(_, contents)| contents.len() > MAX_FILE_SIZE)
+            .map(|(filename, _)| (Some(filename.clone()), Error::FileTooLarge(filename.clone(), MAX_FILE_SIZE)))
+            .collect::<Vec<_>>();
+        if self.0.iter().map(|(_, contents)| contents.len()).sum::<usize>() > MAX_SIZE {
+            problems.push((None, Error::TooLarge(MAX_SIZE)));
+        }
+        match self.argos_config() {
+            Ok((filename, config)) => problems.extend(self.check_references(filename, config)
+                .into_iter()
+                .map(|error| (Some(filename.clone()), error))),
+            Err(error) => problems.push((None, error)),
+        }
+        problems
+    }
+
+    /* checks that the configuration file can be parsed and that the Lua scripts that it references exist */
+    fn check_references(&self, filename: &str, config: &[u8]) -> Vec<Error> {
+        let config = match std::str::from_utf8(config) {
+            Ok(config) => config,
+            Err(error) => return vec![error.into()],
+        };
+        let config = match roxmltree::Document::parse(config) {
+            Ok(config) => config,
+            Err(error) => return vec![Error::ParseError(filename.to_owned(), error)],
+        };
         /* extract lua scripts */
         config.root().descendants()
             .filter_map(|node| match node.tag_name().name() {
@@ -83,10 +204,8 @@ impl Software {
                 "script" => Some(attr.value()),
                 _ => None,
             })
-            .map(|script| self.0.iter()
-                .find(|(filename, _)| filename == script )
-                .ok_or(Error::MissingReferencedFile(script.to_owned())))
-            .collect::<Result<Vec<_>>>()?;
-        Ok(())
+            .filter(|script| !self.0.iter().any(|(filename, _)| filename == script))
+            .map(|script| Error::MissingReferencedFile(script.to_owned()))
+            .collect()
     }
 }
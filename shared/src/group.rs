@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+use crate::{access::Category, experiment::{profile, software, PartialStart}};
+
+/// A named group of robots that is defined in the configuration, e.g., the drones on the left side of the
+/// arena. An action that is issued to a group is executed on all members of the group at once
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Group {
+    pub name: String,
+    /* the identifiers of the robots in the group */
+    pub members: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Action {
+    Identify,
+    Halt,
+    Reboot,
+    CameraStreamEnable(bool),
+    /* start an experiment in which only the members of the group participate */
+    StartExperiment {
+        builderbot_software: software::Source,
+        drone_software: software::Source,
+        pipuck_software: software::Source,
+        overrides: BTreeMap<String, software::Software>,
+        partial_start: PartialStart,
+        #[serde(default)]
+        settings: profile::Settings,
+    },
+    StopExperiment,
+}
+
+impl Action {
+    /// The category of the action for checking the permissions of the client
+    pub fn category(&self) -> Option<Category> {
+        match self {
+            Action::StartExperiment { .. } | Action::StopExperiment => Some(Category::Experiment),
+            Action::Halt | Action::Reboot => Some(Category::Power),
+            Action::Identify | Action::CameraStreamEnable(_) => None,
+        }
+    }
+}
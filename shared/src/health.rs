@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+
+/// The state of a backend subsystem that is restarted when it fails
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Status {
+    Running,
+    /* the subsystem failed with the error and is restarted after the delay in seconds */
+    Restarting {
+        error: String,
+        delay: u64,
+    },
+    /* the subsystem completed, e.g., because the supervisor is shutting down */
+    Stopped,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Subsystem {
+    pub name: String,
+    pub status: Status,
+    /* the number of times that the subsystem has been restarted */
+    pub restarts: u32,
+}
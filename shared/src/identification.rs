@@ -0,0 +1,34 @@
+use serde::{Serialize, Deserialize};
+
+/// The rigid body that moved while a robot was spinning in place compared to the rigid body that is
+/// configured for the robot
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Robot {
+    pub id: String,
+    pub configured: Option<i32>,
+    /* the rigid body that rotated while the robot was spinning and the rotation in degrees */
+    pub observed: Option<(i32, f32)>,
+    /* the reason why the rigid body of the robot could not be identified */
+    pub error: Option<String>,
+}
+
+impl Robot {
+    /// The identifier of the rigid body that should be configured for this robot, if it differs from the
+    /// current configuration
+    pub fn proposal(&self) -> Option<i32> {
+        self.observed
+            .map(|(id, _)| id)
+            .filter(|id| self.configured != Some(*id))
+    }
+}
+
+/// The progress and the results of identifying the rigid bodies of the robots. The robots spin in place
+/// one at a time while the rotations of all rigid bodies are observed
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Identification {
+    /* the time at which the identification was started */
+    pub started: String,
+    /* the robot that is currently spinning, None once the identification has finished */
+    pub current: Option<String>,
+    pub robots: Vec<Robot>,
+}
@@ -0,0 +1,37 @@
+use std::net::Ipv4Addr;
+use macaddr::MacAddr6;
+use serde::{Serialize, Deserialize};
+
+/// A wireless client as seen by an access point
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Station {
+    pub macaddr: MacAddr6,
+    pub access_point: Ipv4Addr,
+    /* the signal strength in dBm as reported by the access point */
+    pub rssi: Option<i32>,
+}
+
+/// A port of a switch and the MAC addresses that have been learned on it
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Port {
+    pub switch: Ipv4Addr,
+    pub port: u32,
+    pub up: bool,
+    pub macaddrs: Vec<MacAddr6>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Update {
+    pub stations: Vec<Station>,
+    pub ports: Vec<Port>,
+}
+
+impl Update {
+    pub fn station(&self, macaddr: MacAddr6) -> Option<&Station> {
+        self.stations.iter().find(|station| station.macaddr == macaddr)
+    }
+
+    pub fn port(&self, macaddr: MacAddr6) -> Option<&Port> {
+        self.ports.iter().find(|port| port.macaddrs.contains(&macaddr))
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{builderbot, drone, pipuck, experiment::TimelineCategory, tracking_system};
+
+/// The first entry of a journal, only the descriptors of the robots are recorded by all versions of the
+/// supervisor
+#[derive(Clone, Debug, Default)]
+pub struct Header {
+    pub schema_version: Option<i64>,
+    pub supervisor_version: Option<String>,
+    /* the local time at which the journal was started, which is only recorded since schema version 2 */
+    pub start: Option<String>,
+    pub builderbots: Vec<builderbot::Descriptor>,
+    pub drones: Vec<drone::Descriptor>,
+    pub pipucks: Vec<pipuck::Descriptor>,
+}
+
+/// The events of a journal that can be replayed
+#[derive(Clone, Debug)]
+pub enum Event {
+    Header(Header),
+    TrackingSystem {
+        frame: i32,
+        updates: Vec<tracking_system::Update>,
+    },
+    /* backtraces are replayed as output on standard error */
+    ARGoS {
+        robot: String,
+        error: bool,
+        data: Vec<u8>,
+    },
+    /* the address of the robot that sent the message */
+    Message(String),
+    Timeline(TimelineCategory, String, Vec<String>),
+    BatteryCells(String, Vec<u16>),
+    /* an event that describes the experiment as a whole, e.g., the fingerprint, which is not replayed */
+    Other,
+}
+
+/// An entry of a journal with its timestamp in milliseconds since the journal was started
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub timestamp: i64,
+    pub event: Event,
+}
+
+/// The entries of a journal in the order in which they were recorded
+#[derive(Clone, Debug, Default)]
+pub struct Journal {
+    pub entries: Vec<Entry>,
+    /* the timestamps of the entries that were recognized but could not be interpreted */
+    pub skipped: Vec<i64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Journals that were recorded as CSV files can not be replayed")]
+    Csv,
+}
+
+/// Reads the entries of a journal that was recorded as a pickle or as JSON Lines, the format is detected from
+/// the contents. Reading stops at the first entry that can not be decoded, which is the end of the journal
+/// unless it was not closed properly. Entries without a timestamp are ignored
+pub fn decode(contents: &[u8]) -> Result<Journal, Error> {
+    /* a CSV journal consists of one file per type of event, each of which starts with the names of the columns */
+    if contents.starts_with(b"timestamp,") {
+        return Err(Error::Csv);
+    }
+    match contents.iter().find(|byte| !byte.is_ascii_whitespace()) {
+        Some(b'{') => Ok(json_lines::decode(contents)),
+        _ => Ok(pickle::decode(contents)),
+    }
+}
+
+mod pickle {
+    use std::io::Cursor;
+    use serde::Deserialize;
+    use serde_pickle::{HashableValue, Value};
+
+    use crate::{experiment::TimelineCategory, tracking_system};
+    use super::{Entry, Event, Header, Journal};
+
+    /// Reads the entries of a journal, which are pickled one after the other
+    pub fn decode(contents: &[u8]) -> Journal {
+        let mut deserializer = serde_pickle::Deserializer::new(Cursor::new(contents), true);
+        let mut journal = Journal::default();
+        while let Ok(entry) = Value::deserialize(&mut deserializer) {
+            let timestamp = match field(&entry, "timestamp").and_then(integer) {
+                Some(timestamp) => timestamp,
+                None => continue,
+            };
+            match field(&entry, "event").and_then(event) {
+                Some(event) => journal.entries.push(Entry { timestamp, event }),
+                None => journal.skipped.push(timestamp),
+            }
+        }
+        journal
+    }
+
+    fn event(event: &Value) -> Option<Event> {
+        Some(match variant(event)? {
+            ("Header", Some(header)) => Event::Header(Header {
+                schema_version: field(header, "schema_version").and_then(integer),
+                supervisor_version: field(header, "supervisor_version").and_then(string).map(str::to_owned),
+                start: field(header, "start").and_then(string).map(str::to_owned),
+                builderbots: descriptors(field(header, "builderbots")?)?,
+                drones: descriptors(field(header, "drones")?)?,
+                pipucks: descriptors(field(header, "pipucks")?)?,
+            }),
+            /* journals recorded before the header was introduced contain the descriptors in this event */
+            ("Descriptors", Some(contents)) => match items(contents)? {
+                [builderbots, drones, pipucks] => Event::Header(Header {
+                    builderbots: descriptors(builderbots)?,
+                    drones: descriptors(drones)?,
+                    pipucks: descriptors(pipucks)?,
+                    ..Default::default()
+                }),
+                _ => return None,
+            },
+            ("TrackingSystem", Some(contents)) => tracking_system(contents)?,
+            ("ARGoS", Some(contents)) => argos(contents)?,
+            ("Message", Some(contents)) => Event::Message(string(items(contents)?.first()?)?.to_owned()),
+            ("Timeline", Some(contents)) => timeline(contents)?,
+            ("BatteryCells", Some(contents)) => match items(contents)? {
+                [robot, cells] => Event::BatteryCells(string(robot)?.to_owned(), items(cells)?.iter()
+                    .map(|cell| integer(cell).map(|cell| cell as u16))
+                    .collect::<Option<Vec<_>>>()?),
+                _ => return None,
+            },
+            _ => Event::Other,
+        })
+    }
+
+    fn descriptors<'de, D: Deserialize<'de>>(value: &Value) -> Option<Vec<D>> {
+        serde_pickle::from_value(value.clone()).ok()
+    }
+
+    /// Reads a frame from the tracking system, the velocities are only recorded by later versions of the supervisor
+    fn tracking_system(contents: &Value) -> Option<Event> {
+        let frame = integer(field(contents, "frame")?)? as i32;
+        let updates = items(field(contents, "updates")?)?.iter()
+            .map(|update| {
+                let vector = |name: &str| -> Option<[f32; 3]> {
+                    match items(field(update, name)?)? {
+                        [x, y, z] => Some([number(x)? as f32, number(y)? as f32, number(z)? as f32]),
+                        _ => None,
+                    }
+                };
+                let orientation = match items(field(update, "orientation")?)? {
+                    [w, x, y, z] => [number(w)? as f32, number(x)? as f32, number(y)? as f32, number(z)? as f32],
+                    _ => return None,
+                };
+                Some(tracking_system::Update {
+                    id: integer(field(update, "id")?)? as i32,
+                    position: vector("position")?,
+                    orientation,
+                    linear_velocity: vector("linear_velocity").unwrap_or_default(),
+                    angular_velocity: vector("angular_velocity").unwrap_or_default(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Event::TrackingSystem { frame, updates })
+    }
+
+    fn argos(contents: &Value) -> Option<Event> {
+        match items(contents)? {
+            [robot, output] => {
+                let (error, data) = match variant(output)? {
+                    ("StandardOutput", Some(Value::Bytes(data))) => (false, data.clone()),
+                    ("StandardError", Some(Value::Bytes(data))) => (true, data.clone()),
+                    ("Backtrace", Some(Value::String(backtrace))) => (true, backtrace.clone().into_bytes()),
+                    _ => return None,
+                };
+                Some(Event::ARGoS { robot: string(robot)?.to_owned(), error, data })
+            },
+            _ => None,
+        }
+    }
+
+    /// Reads an event on the timeline, the tags are only recorded since schema version 3
+    fn timeline(contents: &Value) -> Option<Event> {
+        let (category, description, tags) = match items(contents)? {
+            [category, description] => (category, description, Vec::new()),
+            [category, description, tags] => (category, description, items(tags)?.iter()
+                .map(|tag| string(tag).map(str::to_owned))
+                .collect::<Option<Vec<_>>>()?),
+            _ => return None,
+        };
+        let category = match variant(category)? {
+            ("Phase", None) => TimelineCategory::Phase,
+            ("Safety", None) => TimelineCategory::Safety,
+            ("Disconnect", None) => TimelineCategory::Disconnect,
+            ("Annotation", None) => TimelineCategory::Annotation,
+            _ => return None,
+        };
+        Some(Event::Timeline(category, string(description)?.to_owned(), tags))
+    }
+
+    /// The field of a struct, which is pickled as a dictionary with the names of the fields as keys
+    fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+        match value {
+            Value::Dict(fields) => fields.get(&HashableValue::String(name.to_owned())),
+            _ => None,
+        }
+    }
+
+    /// The name and the contents of a variant of an enumeration. Unit variants are pickled as strings and the
+    /// other variants either as a dictionary with a single entry or as a tuple of the name and the contents
+    fn variant(value: &Value) -> Option<(&str, Option<&Value>)> {
+        match value {
+            Value::String(name) => Some((name, None)),
+            Value::Dict(entries) if entries.len() == 1 => match entries.iter().next() {
+                Some((HashableValue::String(name), contents)) => Some((name, Some(contents))),
+                _ => None,
+            },
+            Value::Tuple(items) | Value::List(items) => match items.as_slice() {
+                [Value::String(name), contents] => Some((name, Some(contents))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn items(value: &Value) -> Option<&[Value]> {
+        match value {
+            Value::Tuple(items) | Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn string(value: &Value) -> Option<&str> {
+        match value {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn integer(value: &Value) -> Option<i64> {
+        match value {
+            Value::I64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn number(value: &Value) -> Option<f64> {
+        match value {
+            Value::I64(value) => Some(*value as f64),
+            Value::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+mod json_lines {
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    use super::{Entry, Event, Header, Journal};
+
+    /// Reads the entries of a journal, each of which is an object on its own line with the timestamp, the
+    /// name of the event, and the contents of the event as data
+    pub fn decode(contents: &[u8]) -> Journal {
+        let mut journal = Journal::default();
+        for line in contents.split(|byte| *byte == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let entry = match serde_json::from_slice::<Value>(line) {
+                Ok(entry) => entry,
+                Err(_) => break,
+            };
+            let timestamp = match entry.get("timestamp").and_then(Value::as_i64) {
+                Some(timestamp) => timestamp,
+                None => continue,
+            };
+            match event(&entry) {
+                Some(event) => journal.entries.push(Entry { timestamp, event }),
+                None => journal.skipped.push(timestamp),
+            }
+        }
+        journal
+    }
+
+    fn event(entry: &Value) -> Option<Event> {
+        let data = entry.get("data")?;
+        Some(match entry.get("event")?.as_str()? {
+            "Header" => Event::Header(Header {
+                schema_version: data.get("schema_version").and_then(Value::as_i64),
+                supervisor_version: data.get("supervisor_version").and_then(Value::as_str).map(str::to_owned),
+                start: data.get("start").and_then(Value::as_str).map(str::to_owned),
+                builderbots: field(data, "builderbots")?,
+                drones: field(data, "drones")?,
+                pipucks: field(data, "pipucks")?,
+            }),
+            "TrackingSystem" => Event::TrackingSystem {
+                frame: data.get("frame")?.as_i64()? as i32,
+                updates: field(data, "updates")?,
+            },
+            /* the output of ARGoS is recorded as text */
+            "ARGoS" => Event::ARGoS {
+                robot: field(data, "robot")?,
+                error: match data.get("stream")?.as_str()? {
+                    "stdout" => false,
+                    "stderr" | "backtrace" => true,
+                    _ => return None,
+                },
+                data: data.get("text")?.as_str()?.as_bytes().to_vec(),
+            },
+            "Message" => Event::Message(field(data, "addr")?),
+            "Timeline" => Event::Timeline(field(data, "category")?, field(data, "description")?, field(data, "tags")?),
+            "BatteryCells" => Event::BatteryCells(field(data, "robot")?, field(data, "cells")?),
+            _ => Event::Other,
+        })
+    }
+
+    fn field<T: DeserializeOwned>(data: &Value, name: &str) -> Option<T> {
+        serde_json::from_value(data.get(name)?.clone()).ok()
+    }
+}
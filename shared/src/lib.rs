@@ -1,10 +1,32 @@
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
+pub mod access;
+pub mod alert;
+pub mod ambient;
+pub mod association;
 pub mod builderbot;
+pub mod camera;
+pub mod configuration;
 pub mod drone;
+pub mod event_log;
 pub mod pipuck;
 pub mod experiment;
+pub mod group;
+pub mod health;
+pub mod identification;
+pub mod infrastructure;
+pub mod journal;
+pub mod maintenance;
+pub mod pairing;
+pub mod replication;
+pub mod ros;
+pub mod router;
+pub mod simulation;
+pub mod snapshot;
+pub mod subscription;
+pub mod telemetry;
+pub mod trace;
 
 pub mod tracking_system {
     use serde::{Serialize, Deserialize};
@@ -13,6 +35,34 @@ pub mod tracking_system {
         pub id: i32,
         pub position: [f32; 3],
         pub orientation: [f32; 4],
+        /* the filtered velocities of the rigid body in meters and radians per second */
+        pub linear_velocity: [f32; 3],
+        pub angular_velocity: [f32; 3],
+    }
+
+    impl Update {
+        /// The magnitude of the linear velocity in meters per second
+        pub fn speed(&self) -> f32 {
+            self.linear_velocity.iter().map(|v| v * v).sum::<f32>().sqrt()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+    pub struct RigidBody {
+        pub id: i32,
+        pub name: String,
+    }
+
+    /// The times (in milliseconds since the UNIX epoch) at which a frame from the tracking system passed
+    /// through each stage of the back-end. These are used to measure the latency of the arena map
+    #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+    pub struct Timestamps {
+        /* the frame was received and decoded by the optitrack task */
+        pub received: f64,
+        /* the frame was broadcast to the subscribers of the optitrack task */
+        pub broadcast: f64,
+        /* the frame was sent to the user interface by the webui */
+        pub sent: f64,
     }
 }
 
@@ -25,14 +75,44 @@ pub enum DownMessage {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum FrontEndRequest {
-    AddBuilderBot(builderbot::Descriptor),
+    /* the first message after the access update, the updates that follow are relative to this snapshot */
+    Snapshot(snapshot::Snapshot),
     UpdateBuilderBot(String, builderbot::Update),
-    AddDrone(drone::Descriptor),
     UpdateDrone(String, drone::Update),
-    AddPiPuck(pipuck::Descriptor),
     UpdatePiPuck(String, pipuck::Update),
     UpdateExperiment(experiment::Update),
-    UpdateTrackingSystem(Vec<tracking_system::Update>),
+    UpdateTrackingSystem(Vec<tracking_system::Update>, tracking_system::Timestamps),
+    UpdateTrackingSystemModels(Vec<tracking_system::RigidBody>),
+    UpdateMaintenance(maintenance::Update),
+    UpdateConfiguration(configuration::Update),
+    UpdateInfrastructure(infrastructure::Update),
+    UpdateAmbient(ambient::Update),
+    UpdateAssociation(association::Update),
+    /* a device whose MAC address does not match any robot in the configuration was found */
+    UnknownDevice(association::UnknownDevice),
+    UpdateSimulation(simulation::Update),
+    UpdateRos(ros::Update),
+    UpdateRouter(router::Statistics),
+    UpdateAccess(access::Update),
+    /* the response to a trace request */
+    UpdateTrace(trace::Trace),
+    UpdateIdentification(identification::Identification),
+    UpdatePairing(pairing::Pairing),
+    /* an alert about a robot, e.g., a low battery */
+    Notification(alert::Notification),
+    UpdateReplication(replication::Role),
+    /* the clients that are connected to the web interface */
+    UpdateSessions(Vec<access::Session>),
+    /* the health of the backend subsystems that are restarted when they fail */
+    UpdateHealth(Vec<health::Subsystem>),
+    /* the experiment profiles that are stored on the backend */
+    UpdateProfiles(Vec<experiment::profile::Profile>),
+    /* the bundles of control software that are stored in the library of the backend */
+    UpdateLibrary(Vec<experiment::library::Bundle>),
+    /* the response to an excerpt request */
+    UpdateExcerpt(String, experiment::Excerpt),
+    /* the response to a telemetry request */
+    UpdateTelemetry(String, telemetry::History),
 }
 
 // frontend to backend
@@ -48,5 +128,117 @@ pub enum BackEndRequest {
     DroneRequest(String, drone::Request),
     PiPuckRequest(String, pipuck::Request),
     ExperimentRequest(experiment::Request),
+    /* save or delete an experiment profile */
+    ProfileRequest(experiment::profile::Request),
+    /* store or delete a bundle of control software in the library */
+    LibraryRequest(experiment::library::Request),
+    /* an action that is executed on all members of a group */
+    GroupRequest(String, group::Action),
+    MaintenanceRequest(maintenance::Request),
+    ConfigurationRequest(configuration::Request),
+    SubscriptionRequest(subscription::Request),
+    /* read the firmware version and the parameters of the Xbees of all drones and compare them to the profile */
+    XbeeAuditRequest,
+    /* the path and timing of an earlier request, which is sent as an update */
+    TraceRequest(Uuid),
+    /* replace the communication topology of the message router, all robots communicate if None */
+    TopologyRequest(Option<router::Topology>),
+    /* limit the communication to robots within the given distance in meters, regardless of distance if None */
+    RangeRequest(Option<f32>),
+    /* spin the given robots, or all robots if None, one at a time to identify their rigid bodies */
+    IdentificationRequest(Option<Vec<String>>),
+    /* identify the unassigned devices on the robot network so that they can be paired into drones */
+    PairingRequest(pairing::Request),
+    /* adopt or ignore a device that did not match any robot */
+    AssociationRequest(association::Request),
+    /* the last lines that ARGoS wrote on the given robot during the last experiment, which are sent as an update */
+    ExcerptRequest(String),
+    /* authenticate the client with the given token, the client falls back to the default role if None */
+    LoginRequest(Option<String>),
+    /* a snapshot of the robots and the experiment that replaces the state of the client, which is sent as an update */
+    SyncRequest,
+    /* switch the drones out of autonomous mode and tell the ground robots to navigate to their home positions */
+    ReturnToBaseRequest,
+    /* confirm that the unresponsive primary supervisor has been shut down so that this standby takes over */
+    TakeOverRequest,
+    /* the recent readings and terminal output of the given robot, which are sent as an update */
+    TelemetryRequest(String),
 }
 
+impl BackEndRequest {
+    /// The category of the request for checking the permissions of the client
+    pub fn category(&self) -> Option<access::Category> {
+        match self {
+            BackEndRequest::BuilderBotRequest(_, request) => request.category(),
+            BackEndRequest::DroneRequest(_, request) => request.category(),
+            BackEndRequest::PiPuckRequest(_, request) => request.category(),
+            BackEndRequest::ExperimentRequest(_) | BackEndRequest::ProfileRequest(_) | BackEndRequest::LibraryRequest(_) |
+            BackEndRequest::TopologyRequest(_) | BackEndRequest::RangeRequest(_) | BackEndRequest::IdentificationRequest(_) =>
+                Some(access::Category::Experiment),
+            BackEndRequest::GroupRequest(_, action) => action.category(),
+            BackEndRequest::MaintenanceRequest(_) | BackEndRequest::ConfigurationRequest(_) |
+            BackEndRequest::PairingRequest(_) | BackEndRequest::AssociationRequest(_) =>
+                Some(access::Category::Configuration),
+            BackEndRequest::SubscriptionRequest(_) | BackEndRequest::XbeeAuditRequest |
+            BackEndRequest::TraceRequest(_) | BackEndRequest::ExcerptRequest(_) |
+            BackEndRequest::LoginRequest(_) | BackEndRequest::SyncRequest |
+            BackEndRequest::TelemetryRequest(_) => None,
+            BackEndRequest::ReturnToBaseRequest => Some(access::Category::Power),
+            BackEndRequest::TakeOverRequest => Some(access::Category::Configuration),
+        }
+    }
+
+    /// A short description of the request for its trace, the software and the configuration are omitted
+    pub fn summary(&self) -> String {
+        let summary = match self {
+            BackEndRequest::BuilderBotRequest(id, request) => format!("{}: {:?}", id, request),
+            BackEndRequest::DroneRequest(id, request) => format!("{}: {:?}", id, request),
+            BackEndRequest::PiPuckRequest(id, request) => format!("{}: {:?}", id, request),
+            BackEndRequest::ExperimentRequest(experiment::Request::Start { .. }) => "Start experiment".to_owned(),
+            BackEndRequest::ExperimentRequest(experiment::Request::Check { .. }) => "Check software".to_owned(),
+            BackEndRequest::ExperimentRequest(request) => format!("{:?}", request),
+            BackEndRequest::ProfileRequest(experiment::profile::Request::Save(profile)) =>
+                format!("Save profile \"{}\"", profile.name),
+            BackEndRequest::ProfileRequest(experiment::profile::Request::Delete(name)) =>
+                format!("Delete profile \"{}\"", name),
+            BackEndRequest::LibraryRequest(experiment::library::Request::Store(name, _)) =>
+                format!("Store \"{}\" in library", name),
+            BackEndRequest::LibraryRequest(experiment::library::Request::Delete(reference)) =>
+                format!("Delete {} from library", reference),
+            BackEndRequest::GroupRequest(group, group::Action::StartExperiment { .. }) =>
+                format!("{}: Start experiment", group),
+            BackEndRequest::GroupRequest(group, action) => format!("{}: {:?}", group, action),
+            BackEndRequest::MaintenanceRequest(request) => format!("{:?}", request),
+            BackEndRequest::ConfigurationRequest(_) => "Configuration request".to_owned(),
+            BackEndRequest::SubscriptionRequest(request) => format!("{:?}", request),
+            BackEndRequest::XbeeAuditRequest => "Xbee audit".to_owned(),
+            BackEndRequest::TraceRequest(id) => format!("Trace {}", id),
+            BackEndRequest::TopologyRequest(Some(topology)) =>
+                format!("Set topology of {} robots", topology.neighbors.len()),
+            BackEndRequest::TopologyRequest(None) => "Clear topology".to_owned(),
+            BackEndRequest::RangeRequest(Some(range)) => format!("Set communication range to {} m", range),
+            BackEndRequest::RangeRequest(None) => "Clear communication range".to_owned(),
+            BackEndRequest::IdentificationRequest(Some(robots)) => format!("Identify {}", robots.join(", ")),
+            BackEndRequest::IdentificationRequest(None) => "Identify all robots".to_owned(),
+            BackEndRequest::PairingRequest(request) => format!("{:?}", request),
+            BackEndRequest::AssociationRequest(request) => format!("{:?}", request),
+            BackEndRequest::ExcerptRequest(id) => format!("{}: Excerpt of the last experiment", id),
+            /* the token is not recorded in the trace */
+            BackEndRequest::LoginRequest(Some(_)) => "Log in".to_owned(),
+            BackEndRequest::LoginRequest(None) => "Log out".to_owned(),
+            BackEndRequest::SyncRequest => "Synchronize".to_owned(),
+            BackEndRequest::ReturnToBaseRequest => "Return to base".to_owned(),
+            BackEndRequest::TakeOverRequest => "Take over from primary".to_owned(),
+            BackEndRequest::TelemetryRequest(id) => format!("{}: Telemetry", id),
+        };
+        /* terminal input can be a pasted script */
+        match summary.char_indices().nth(SUMMARY_LENGTH) {
+            Some((index, _)) => format!("{}…", &summary[..index]),
+            None => summary,
+        }
+    }
+}
+
+/* the maximum number of characters in the summary of a request */
+const SUMMARY_LENGTH: usize = 120;
+
@@ -0,0 +1,21 @@
+use std::net::Ipv4Addr;
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* an empty list of addresses pauses the probing of all addresses */
+    Enable {
+        paused_addrs: Vec<Ipv4Addr>,
+    },
+    Disable,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Update {
+    Enabled {
+        paused_addrs: Vec<Ipv4Addr>,
+    },
+    Disabled {
+        suppressed_events: Vec<String>,
+    },
+}
@@ -0,0 +1,40 @@
+use std::net::Ipv4Addr;
+use macaddr::MacAddr6;
+use serde::{Serialize, Deserialize};
+
+/// An Xbee on the robot network that is not assigned to any drone in the configuration
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Xbee {
+    pub macaddr: MacAddr6,
+    pub addr: Ipv4Addr,
+    /* the identifier that is wired to the pins DIO0 to DIO3 of the Xbee, None if it could not be read */
+    pub identifier: Option<u8>,
+    /* the robot whose MAC address the Xbee claims while that robot is associated with another Xbee */
+    pub conflict: Option<String>,
+}
+
+/// A device running the fernbedienung service that is not assigned to any robot in the configuration, e.g.,
+/// the UpCore of a drone that has not been added to the configuration yet
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Fernbedienung {
+    pub macaddr: MacAddr6,
+    pub addr: Ipv4Addr,
+    /* whether the status LEDs of the device are flashing */
+    pub identifying: bool,
+    /* the robot whose MAC address the device claims while that robot is associated with another device */
+    pub conflict: Option<String>,
+}
+
+/// The unassigned devices that are currently visible on the robot network. An Xbee and an UpCore are
+/// paired by adding a drone with their MAC addresses to the configuration
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Pairing {
+    pub xbees: Vec<Xbee>,
+    pub fernbedienungs: Vec<Fernbedienung>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* start or stop flashing the status LEDs of an unassigned device running the fernbedienung service */
+    Identify(MacAddr6, bool),
+}
@@ -2,10 +2,14 @@ use std::{fmt::Display, net::Ipv4Addr};
 use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
+use crate::{access::Category, subscription::Capability};
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Descriptor {
     pub id: String,
     pub rpi_macaddr: macaddr::MacAddr6,
+    /* the address reserved for the robot by the DHCP server, if any */
+    pub rpi_addr: Option<Ipv4Addr>,
     pub optitrack_id: Option<i32>,
     pub apriltag_id: Option<u8>,
 }
@@ -25,18 +29,78 @@ pub enum Update {
     },
     FernbedienungConnected(Ipv4Addr),
     FernbedienungDisconnected,
+    /* the heartbeat of the robot timed out and its last known address is probed again */
+    FernbedienungReconnecting(Ipv4Addr),
     FernbedienungSignal(i32),
+    ResourceLimitViolation(String),
+    /* the uptime of the robot and the duration of its association with the supervisor in seconds */
+    Uptime {
+        robot: u64,
+        association: u64,
+    },
+    UnexpectedReboot(String),
+    /* the interventions of the watchdog on the robot since the previous association */
+    Watchdog(Vec<String>),
+    /* the name and version of the operating system image on the robot */
+    Firmware(String),
+    /* whether the camera streams are supported by the robot */
+    Cameras(crate::camera::Availability),
+    /* the actions that will be executed once the robot is associated again */
+    QueuedActions(Vec<(u64, String)>),
+    Artifacts(Result<Vec<String>, String>),
+    /* a summary of the last experiment that ran on the robot */
+    ExperimentSummary(crate::experiment::Summary),
+    /* an event that was recorded in the event log of the robot and the whole event log, which is sent to
+       new subscribers */
+    Event(crate::event_log::Entry),
+    EventLog(Vec<crate::event_log::Entry>),
     Bash(String),
+    /* the progress of sending input to the Bash terminal in bytes */
+    BashProgress {
+        sent: usize,
+        total: usize,
+    },
+    /* the output of ARGoS while it runs an experiment on the robot */
+    ArgosOutput {
+        error: bool,
+        text: String,
+    },
+}
+
+impl Update {
+    /// The stream to which this update belongs
+    pub fn capability(&self) -> Capability {
+        match self {
+            Update::Camera { .. } => Capability::Cameras,
+            Update::Bash(_) | Update::BashProgress { .. } | Update::ArgosOutput { .. } => Capability::Terminals,
+            _ => Capability::Telemetry,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Request {
+    CancelQueuedAction(u64),
     BashTerminalStart,
     BashTerminalStop,
     BashTerminalRun(String),
     CameraStreamEnable(bool),
     Identify,
+    /* download the files at the given paths on the robot into a directory on the supervisor machine */
+    FetchResults(Vec<String>),
     RaspberryPiHalt,
     RaspberryPiReboot,
 }
 
+impl Request {
+    /// The category of the request for checking the permissions of the client
+    pub fn category(&self) -> Option<Category> {
+        match self {
+            Request::BashTerminalStart | Request::BashTerminalStop | Request::BashTerminalRun(_) => Some(Category::Terminals),
+            Request::RaspberryPiHalt | Request::RaspberryPiReboot => Some(Category::Power),
+            Request::FetchResults(_) => Some(Category::Experiment),
+            _ => None,
+        }
+    }
+}
+
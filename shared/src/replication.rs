@@ -0,0 +1,34 @@
+use std::net::SocketAddr;
+use serde::{Serialize, Deserialize};
+
+use crate::experiment::Status;
+
+/// The role of this supervisor when a standby supervisor is configured
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Role {
+    /* the standby supervisors that are currently mirroring this supervisor */
+    Primary {
+        standbys: Vec<SocketAddr>,
+    },
+    /* this supervisor mirrors the primary and takes over once the primary stops responding and an operator
+       has confirmed the takeover */
+    Standby {
+        primary: SocketAddr,
+        connected: bool,
+        /* the primary has stopped sending heartbeats, the takeover can be confirmed */
+        unresponsive: bool,
+        /* the status of the experiment on the primary */
+        status: Status,
+    },
+    /* this supervisor has taken over from the primary, the status is the last status received from the primary */
+    TakenOver {
+        primary: SocketAddr,
+        time: String,
+        status: Status,
+    },
+    /* a standby supervisor has taken over from this supervisor, which has released the robots and stays passive */
+    Superseded {
+        standby: SocketAddr,
+        time: String,
+    },
+}
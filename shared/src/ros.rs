@@ -0,0 +1,77 @@
+use std::fmt::Display;
+use serde::{Serialize, Deserialize};
+
+/// The supervisor update to which the messages of a topic are mapped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Mapping {
+    /* a sensor_msgs/BatteryState message */
+    Battery,
+    /* a nav_msgs/Odometry, geometry_msgs/PoseStamped, or geometry_msgs/Pose message */
+    Pose,
+}
+
+impl Mapping {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mapping::Battery => "battery",
+            Mapping::Pose => "pose",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Topic {
+    pub name: String,
+    /* the type of the messages, e.g., sensor_msgs/BatteryState */
+    pub message_type: String,
+    pub mapping: Mapping,
+}
+
+/// A ROS-based ground robot, e.g., a Turtlebot, that is controlled via its rosbridge websocket. The robot
+/// must provide two services of type std_srvs/Trigger that start and stop the launch files of the experiment
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Descriptor {
+    pub id: String,
+    /* the address of the rosbridge websocket, e.g., ws://192.168.1.50:9090 */
+    pub url: String,
+    pub topics: Vec<Topic>,
+    pub start_service: String,
+    pub stop_service: String,
+}
+
+impl Display for Descriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Pose {
+    /* the position in meters and the orientation as a quaternion (w, x, y, z) in the frame of the robot */
+    pub position: [f32; 3],
+    pub orientation: [f32; 4],
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Robot {
+    pub descriptor: Descriptor,
+    pub connected: bool,
+    /* the charge of the battery in percent */
+    pub battery: Option<f32>,
+    pub pose: Option<Pose>,
+    /* whether the launch files of the experiment have been started */
+    pub running: bool,
+    /* the last error from the connection or from calling a service */
+    pub error: Option<String>,
+}
+
+impl Robot {
+    pub fn new(descriptor: Descriptor) -> Self {
+        Robot { descriptor, connected: false, battery: None, pose: None, running: false, error: None }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Update {
+    Robot(Robot),
+}
@@ -0,0 +1,103 @@
+use std::{collections::{BTreeMap, BTreeSet}, net::{IpAddr, SocketAddr}};
+use serde::{Serialize, Deserialize};
+
+/// The key of the messages that the message router broadcasts on behalf of the supervisor, i.e., the robots
+/// receive a table with a single entry whose key is this string and whose value is the command
+pub const COMMAND_KEY: &str = "supervisor_command";
+
+/// The messages that the message router forwarded from one peer to another since both peers connected
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Link {
+    pub from: SocketAddr,
+    pub to: SocketAddr,
+    pub messages: u64,
+    pub bytes: u64,
+    /* the time between receiving a message and handing it to the connection of the recipient in milliseconds */
+    pub mean_latency: f64,
+    pub max_latency: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Origin {
+    /* the address was reserved for the robot in the configuration */
+    Static,
+    /* the address was learned from the fernbedienung connection of the robot */
+    Learned,
+}
+
+/// An entry of the table that the message router uses to resolve its peers to robots
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Route {
+    pub robot: String,
+    pub addr: IpAddr,
+    pub origin: Origin,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Statistics {
+    pub links: Vec<Link>,
+    /* the addresses of the real robots, the learned addresses take precedence over the static ones */
+    pub routes: Vec<Route>,
+    /* the topology that restricts which peers can communicate, all peers communicate if None */
+    pub topology: Option<Topology>,
+    /* the maximum distance between two tracked robots for their messages to be forwarded in meters */
+    pub range: Option<f32>,
+    /* the messages that were not forwarded since the peers are not neighbors in the topology or out of range */
+    pub dropped: u64,
+}
+
+/// The communication topology of an experiment. The message router only forwards the messages of a robot
+/// to its neighbors, i.e., the neighbors of a robot are the robots that receive its messages. Robots that
+/// are not part of the topology neither send nor receive messages
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Topology {
+    pub neighbors: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Topology {
+    /// Whether the messages from one robot are forwarded to another robot
+    pub fn permits(&self, from: &str, to: &str) -> bool {
+        self.neighbors.get(from).map_or(false, |neighbors| neighbors.contains(to))
+    }
+
+    /// The robots that are part of the topology
+    pub fn robots(&self) -> BTreeSet<&str> {
+        self.neighbors.iter()
+            .flat_map(|(robot, neighbors)| std::iter::once(robot).chain(neighbors))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Parses a topology where each line contains the identifier of a robot, a colon, and the identifiers
+    /// of its neighbors separated by whitespace, e.g., `pipuck1: pipuck2 pipuck3`. Empty lines and lines
+    /// starting with `#` are ignored
+    pub fn parse(text: &str) -> Result<Topology, String> {
+        let mut topology = Topology::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (robot, neighbors) = line.split_once(':')
+                .ok_or_else(|| format!("Line {}: expected a robot followed by a colon", index + 1))?;
+            let robot = robot.trim();
+            if robot.is_empty() || robot.contains(char::is_whitespace) {
+                return Err(format!("Line {}: invalid robot \"{}\"", index + 1, robot));
+            }
+            topology.neighbors.entry(robot.to_owned())
+                .or_default()
+                .extend(neighbors.split_whitespace().map(str::to_owned));
+        }
+        Ok(topology)
+    }
+}
+
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (robot, neighbors) in &self.neighbors {
+            let neighbors = neighbors.iter().map(String::as_str).collect::<Vec<_>>();
+            writeln!(f, "{}: {}", robot, neighbors.join(" "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use std::{fmt::Display, net::SocketAddr};
+use serde::{Serialize, Deserialize};
+
+/// The key of the message that registers a simulated robot with the message router, i.e., the simulation
+/// sends a table with a single entry whose key is this string and whose value is the identifier of the robot
+pub const REGISTRATION_KEY: &str = "supervisor_register";
+
+/// A robot that is simulated by an ARGoS instance running on the supervisor machine. Simulated robots
+/// register with the message router so that they exchange messages with the real robots, which allows
+/// experiments with mixed swarms of real and simulated robots (hardware-in-the-loop)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Descriptor {
+    pub id: String,
+    /* the type of the simulated robot, i.e., builderbot, drone, or pipuck */
+    pub robot: String,
+}
+
+impl Display for Descriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Registration {
+    /* the address from which the simulation connected to the message router */
+    pub addr: SocketAddr,
+    /* the time at which the robot was registered in RFC 3339 format */
+    pub time: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Robot {
+    pub descriptor: Descriptor,
+    pub registration: Option<Registration>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Update {
+    Robots(Vec<Robot>),
+}
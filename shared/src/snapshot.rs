@@ -0,0 +1,18 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+use crate::{builderbot, drone, pipuck, experiment::{ChecklistItem, SmokeTest, Status}};
+
+/// The complete state of the robots and the experiment at the time that a client connected. The state of
+/// each robot is given as the sequence of updates that brings a new instance of that robot up to date
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub builderbots: Vec<(builderbot::Descriptor, Vec<builderbot::Update>)>,
+    pub drones: Vec<(drone::Descriptor, Vec<drone::Update>)>,
+    pub pipucks: Vec<(pipuck::Descriptor, Vec<pipuck::Update>)>,
+    pub checklist: Vec<ChecklistItem>,
+    /* the last smoke test of each robot, robots that have not been tested yet map to None */
+    pub smoke_tests: BTreeMap<String, Option<SmokeTest>>,
+    /* the state of the experiment and the readiness of the participating robots */
+    pub status: Status,
+}
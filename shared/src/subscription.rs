@@ -0,0 +1,35 @@
+use serde::{Serialize, Deserialize};
+
+/// The streams of updates that a connection to the web interface can subscribe to. The snapshot that is
+/// sent when a client connects and updates that do not belong to any of these streams (e.g., the journal
+/// statistics) are always sent
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Capability {
+    /* the state of the robots, e.g., their battery level, signal strength, and uptime */
+    Telemetry,
+    /* the output of the Bash and MAVLink terminals */
+    Terminals,
+    /* the frames from the cameras of the robots */
+    Cameras,
+    /* the poses and model definitions from the tracking system */
+    Tracking,
+    /* the live log of the experiment, i.e., the output of ARGoS and the events from the journal */
+    Log,
+}
+
+impl Capability {
+    /// All capabilities, a new connection is subscribed to all of them until it requests otherwise
+    pub const ALL: [Capability; 5] = [
+        Capability::Telemetry,
+        Capability::Terminals,
+        Capability::Cameras,
+        Capability::Tracking,
+        Capability::Log,
+    ];
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Request {
+    /* replaces the capabilities that the connection is subscribed to */
+    Subscribe(Vec<Capability>),
+}
@@ -0,0 +1,16 @@
+use serde::{Serialize, Deserialize};
+
+/// The recent readings of a robot that the supervisor keeps in memory, so that a client can show them as
+/// soon as it opens the card of the robot. The readings are pairs of the time in milliseconds since the Unix
+/// epoch and the value of the reading
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct History {
+    pub battery: Vec<(i64, i32)>,
+    /* the signal strength of the Wi-Fi connection to the fernbedienung service */
+    pub signal: Vec<(i64, i32)>,
+    /* the signal strength of the Xbee, only recorded for drones */
+    pub xbee_signal: Vec<(i64, i32)>,
+    /* the recent output of the terminals */
+    pub bash: String,
+    pub mavlink: String,
+}
@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// A step in the handling of a request by one of the components of the back-end
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Span {
+    /* the time since the request was received by the webui in milliseconds */
+    pub elapsed: f64,
+    /* the component that handled the request, e.g., the webui, the arena, or a robot */
+    pub component: String,
+    pub description: String,
+}
+
+/// The path of a request from the user interface through the back-end. The identifier of the trace is the
+/// identifier of the websocket request
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Trace {
+    pub id: Uuid,
+    pub request: String,
+    /* the time at which the request was received in RFC 3339 format */
+    pub received: String,
+    pub spans: Vec<Span>,
+}
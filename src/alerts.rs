@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use futures::StreamExt;
+use shared::alert::{Notification, Severity};
+use shared::configuration::{BatteryAlerts, BatteryPolicy};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+use crate::{arena, robot::{builderbot, drone, pipuck}};
+
+/* the amount in percent by which a battery must recover above the warning level before the operators are
+   alerted about it again, so that a reading that fluctuates around a threshold does not cause repeated alerts */
+const REARM_MARGIN: i32 = 5;
+
+pub enum Action {
+    /* note that only the alerts that are raised after subscribing are sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Notification>>),
+    /* an alert that was raised by another component, e.g., about a device that claims the identity of a robot */
+    Notify(Notification),
+    /* replace the configured thresholds while an experiment is running, the configured thresholds apply again if None */
+    Override(Option<BatteryAlerts>),
+}
+
+struct Alerts {
+    /* the thresholds that are in effect and the thresholds from the configuration */
+    configuration: Option<BatteryAlerts>,
+    configured: Option<BatteryAlerts>,
+    /* the severity of the last alert that was raised about each robot */
+    alerted: HashMap<String, Severity>,
+    updates_tx: broadcast::Sender<Notification>,
+    arena_tx: mpsc::Sender<arena::Action>,
+}
+
+impl Alerts {
+    /// Checks a battery reading against the thresholds for the type of the robot. An alert is raised when the
+    /// battery drops below a threshold and, if the policy is to stop the experiment, every critical reading is
+    /// reported to the arena, which only intervenes if the robot participates in the experiment
+    async fn battery(&mut self, robot: &str, id: &str, level: i32) {
+        let (policy, threshold) = match self.configuration.as_ref()
+            .and_then(|alerts| alerts.thresholds.iter()
+                .find(|threshold| threshold.robot == robot)
+                .map(|threshold| (alerts.policy, threshold))) {
+            Some(configuration) => configuration,
+            None => return,
+        };
+        let severity = match level {
+            level if level <= threshold.critical => Severity::Critical,
+            level if level <= threshold.warning => Severity::Warning,
+            level => {
+                if level > threshold.warning + REARM_MARGIN {
+                    self.alerted.remove(id);
+                }
+                return;
+            }
+        };
+        let escalated = match self.alerted.get(id) {
+            Some(Severity::Critical) => false,
+            Some(Severity::Warning) => severity == Severity::Critical,
+            None => true,
+        };
+        if escalated {
+            let message = match severity {
+                Severity::Warning => format!("The battery of {} is low ({}%)", id, level),
+                Severity::Critical => format!("The battery of {} is critically low ({}%)", id, level),
+            };
+            log::warn!("{}", message);
+            self.alerted.insert(id.to_owned(), severity);
+            let _ = self.updates_tx.send(Notification { robot: id.to_owned(), severity, message });
+        }
+        if severity == Severity::Critical && policy == BatteryPolicy::Stop {
+            let action = arena::Action::BatteryCritical { id: id.to_owned(), level };
+            if self.arena_tx.send(action).await.is_err() {
+                log::error!("Could not communicate with the arena");
+            }
+        }
+    }
+}
+
+/// Monitors the batteries of the robots and alerts the operators when they run low. The thresholds are
+/// configured per robot type and robots without a threshold are not monitored. The alerts that are raised by
+/// other components are forwarded to the operators in the same way
+pub async fn new(
+    configuration: Option<BatteryAlerts>,
+    arena_tx: mpsc::Sender<arena::Action>,
+    mut requests: mpsc::Receiver<Action>,
+) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut alerts = Alerts { configuration: configuration.clone(), configured: configuration, alerted: HashMap::new(), updates_tx, arena_tx };
+    /* the subscription is renewed when robots are added or removed so that their updates are received */
+    loop {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        let subscription = match alerts.arena_tx.send(arena::Action::Subscribe(callback_tx)).await {
+            Ok(_) => callback_rx.await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Could not subscribe to arena updates"))),
+            Err(_) => Err(anyhow::anyhow!("Could not communicate with arena")),
+        };
+        let arena::Subscription { snapshot, builderbots: builderbot_updates, drones: drone_updates, pipucks: pipuck_updates, mut roster, .. } =
+            match subscription {
+                Ok(subscription) => subscription,
+                Err(error) => {
+                    log::error!("Could not initialize alerts: {:#}", error);
+                    return;
+                }
+            };
+        /* check the last readings of the robots */
+        for (desc, updates) in snapshot.builderbots {
+            for update in updates {
+                if let builderbot::Update::Battery(level) = update {
+                    alerts.battery("builderbot", &desc.id, level).await;
+                }
+            }
+        }
+        for (desc, updates) in snapshot.drones {
+            for update in updates {
+                if let drone::Update::Battery(level) = update {
+                    alerts.battery("drone", &desc.id, level).await;
+                }
+            }
+        }
+        for (desc, updates) in snapshot.pipucks {
+            for update in updates {
+                if let pipuck::Update::Battery(level) = update {
+                    alerts.battery("pipuck", &desc.id, level).await;
+                }
+            }
+        }
+        tokio::pin!(builderbot_updates);
+        tokio::pin!(drone_updates);
+        tokio::pin!(pipuck_updates);
+        loop {
+            tokio::select! {
+                request = requests.recv() => match request {
+                    Some(Action::Subscribe(callback)) => {
+                        let _ = callback.send(alerts.updates_tx.subscribe());
+                    },
+                    Some(Action::Notify(notification)) => {
+                        log::warn!("{}", notification.message);
+                        let _ = alerts.updates_tx.send(notification);
+                    },
+                    Some(Action::Override(configuration)) => {
+                        alerts.configuration = configuration.or_else(|| alerts.configured.clone());
+                    },
+                    None => return,
+                },
+                Some((descriptor, update)) = builderbot_updates.next() => match update {
+                    Ok(builderbot::Update::Battery(level)) => alerts.battery("builderbot", &descriptor.id, level).await,
+                    Ok(_) => {},
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Alerts missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = drone_updates.next() => match update {
+                    Ok(drone::Update::Battery(level)) => alerts.battery("drone", &descriptor.id, level).await,
+                    Ok(_) => {},
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Alerts missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = pipuck_updates.next() => match update {
+                    Ok(pipuck::Update::Battery(level)) => alerts.battery("pipuck", &descriptor.id, level).await,
+                    Ok(_) => {},
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Alerts missed {} messages for {}", count, descriptor),
+                },
+                /* subscribe again to receive the updates of the added robots */
+                Ok(_) = roster.recv() => break,
+            }
+        }
+    }
+}
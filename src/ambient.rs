@@ -0,0 +1,114 @@
+use anyhow::Context;
+use std::{path::PathBuf, time::Duration};
+use tokio::{io::AsyncBufReadExt, sync::{broadcast, mpsc, oneshot}};
+use shared::ambient::{Reading, Update};
+
+use crate::journal;
+
+/* the time after which a sensor that has not responded is considered to be unavailable */
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+pub enum Source {
+    /* the reading is the body of the response to a GET request */
+    Http(String),
+    /* the reading is the next line written by a device, e.g., a USB sensor that emulates a serial port */
+    Serial(PathBuf),
+}
+
+#[derive(Debug)]
+pub struct Sensor {
+    pub name: String,
+    pub unit: String,
+    pub source: Source,
+}
+
+#[derive(Debug)]
+pub struct Configuration {
+    pub interval: Duration,
+    pub sensors: Vec<Sensor>,
+}
+
+pub enum Action {
+    /* note that upon subscribing, the most recent readings are sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Update>>),
+}
+
+/// Periodically reads the ambient sensors of the arena and records their readings in the journal. If no
+/// configuration is provided, this task only answers subscriptions so that the web interface does not
+/// need to handle its absence
+pub async fn new(
+    config: Option<Configuration>,
+    mut requests: mpsc::Receiver<Action>,
+    journal_requests_tx: mpsc::Sender<journal::Action>,
+) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut update = Update::default();
+    let mut interval = tokio::time::interval(config.as_ref()
+        .map_or(Duration::from_secs(10), |config| config.interval));
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send(updates_tx.subscribe());
+                    let _ = updates_tx.send(update.clone());
+                },
+                None => break,
+            },
+            _ = interval.tick(), if config.is_some() => if let Some(config) = config.as_ref() {
+                update = poll(config).await;
+                /* the readings are only recorded if an experiment is running */
+                let event = journal::Event::Ambient(update.readings.clone());
+                let _ = journal_requests_tx.send(journal::Action::Record(event)).await;
+                let _ = updates_tx.send(update.clone());
+            }
+        }
+    }
+}
+
+async fn poll(config: &Configuration) -> Update {
+    let mut update = Update::default();
+    for sensor in &config.sensors {
+        let value = tokio::time::timeout(TIMEOUT, read(&sensor.source)).await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("Timed out")));
+        if let Err(error) = &value {
+            log::warn!("Could not read ambient sensor {}: {:#}", sensor.name, error);
+        }
+        update.readings.push(Reading {
+            sensor: sensor.name.clone(),
+            unit: sensor.unit.clone(),
+            value: value.map_err(|error| format!("{:#}", error)),
+            time: chrono::Local::now().to_rfc3339(),
+        });
+    }
+    update
+}
+
+async fn read(source: &Source) -> anyhow::Result<f64> {
+    let text = match source {
+        Source::Http(url) => reqwest::get(url).await
+            .and_then(|response| response.error_for_status())
+            .context("Could not send request")?
+            .text().await
+            .context("Could not read response")?,
+        Source::Serial(device) => {
+            let device = tokio::fs::File::open(device).await
+                .context(format!("Could not open {}", device.display()))?;
+            let mut line = String::new();
+            /* the first line may have been partially written before the device was opened */
+            let mut reader = tokio::io::BufReader::new(device);
+            reader.read_line(&mut line).await.context("Could not read from device")?;
+            line.clear();
+            reader.read_line(&mut line).await.context("Could not read from device")?;
+            line
+        }
+    };
+    value(&text).ok_or(anyhow::anyhow!("Could not find a value in \"{}\"", text.trim()))
+}
+
+/// Extracts the first number from the output of a sensor, which allows sensors that also write their unit
+/// or a label, e.g., "T: 21.5 C"
+fn value(text: &str) -> Option<f64> {
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == ':' || c == '=')
+        .find_map(|token| token.parse::<f64>().ok())
+}
@@ -2,14 +2,31 @@
 use anyhow::Context;
 use futures::{StreamExt, TryStreamExt, stream::FuturesUnordered};
 use log;
-use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::{mpsc, oneshot};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio_stream::{StreamMap, wrappers::BroadcastStream};
 
-use crate::robot::{builderbot, drone, pipuck};
-use crate::journal;
-use crate::network::{xbee, fernbedienung};
-use shared::experiment::software::Software;
+use crate::robot::{builderbot, drone, pipuck, ros, Decimator, FernbedienungAction, Outbound, Polling, StatusLeds, XbeeAction};
+use crate::{alerts, journal, library, pairing, repository, router, syntax, trace};
+use crate::network::{self, xbee, fernbedienung, fernbedienung_ext::{Artifacts, ResourceLimits, Watchdog}};
+use shared::experiment::{Acknowledgement, ChecklistItem, PartialStart, Readiness, SmokeTest, SmokeTestCheck, State, Status, TimelineCategory, fingerprint::Fingerprint, profile::{CameraPolicy, Settings}, software::{Software, Source}};
+use shared::{association, configuration::{CameraShutdown, Heartbeat, PollingFactors, ReturnToBase}, group::{self, Group}, maintenance, simulation, snapshot::Snapshot};
+use shared::alert::{Notification, Severity};
+
+/* the time that ARGoS is given to exit after an experiment has been stopped before the robots are verified */
+const VERIFICATION_DELAY: Duration = Duration::from_secs(3);
+/* the time during which the message router must not forward any messages from the stopped robots */
+const VERIFICATION_TRAFFIC_WINDOW: Duration = Duration::from_secs(2);
+/* the number of times that the robots that fail the verification are stopped again */
+const VERIFICATION_RETRIES: usize = 2;
+/* the time after which a quarantined device that has not been found again is no longer listed, the pairing
+   task returns the devices that it holds to the network task every 30 seconds */
+const QUARANTINE_EXPIRY: Duration = Duration::from_secs(60);
 
 pub enum Action {
     /* BuilderBot actions */
@@ -21,108 +38,1339 @@ pub enum Action {
     /* Pi-Puck actions */
     ForwardPiPuckAction(String, pipuck::Action),
     GetPiPuckDescriptors(oneshot::Sender<Vec<Arc<pipuck::Descriptor>>>),
+    /* subscribe to the updates of the robots and the experiment, starting from a snapshot of their state */
+    Subscribe(oneshot::Sender<anyhow::Result<Subscription>>),
     /* Arena actions */
     AddXbee(xbee::Device, macaddr::MacAddr6),
     AddFernbedienung(fernbedienung::Device, macaddr::MacAddr6),
     /* Experiment actions */
     StartExperiment {
         callback: oneshot::Sender<anyhow::Result<()>>,
-        builderbot_software: Software,
-        drone_software: Software,
-        pipuck_software: Software,
+        builderbot_software: Source,
+        drone_software: Source,
+        pipuck_software: Source,
+        /* the software for individual robots, which replaces the software for their robot type */
+        overrides: BTreeMap<String, Software>,
+        /* only the given robots participate in the experiment, all robots participate if None */
+        robots: Option<Vec<String>>,
+        /* roll back or continue if the experiment can only be started on some of the robots */
+        partial_start: PartialStart,
+        /* the duration, battery thresholds, and camera policy that replace those of the configuration */
+        settings: Settings,
     },
     StopExperiment {
         callback: oneshot::Sender<anyhow::Result<()>>,
+        robots: Option<Vec<String>>,
+    },
+    /* a rigid body from the tracking system has left the boundary of the arena */
+    GeofenceViolation {
+        optitrack_id: i32,
+        position: [f32; 3],
+    },
+    /* the battery of a robot has reached a critical level and the policy is to stop the experiment */
+    BatteryCritical {
+        id: String,
+        level: i32,
+    },
+    AcknowledgeChecklistItem {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        item: usize,
+        operator: Option<String>,
+    },
+    /* run the smoke test on a single robot or, if no robot is given, on all robots */
+    RunSmokeTests {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        robot: Option<String>,
     },
+    /* measure the throughput of a single robot or, if no robot is given, of all robots one at a time, the
+       callback is called once all robots have been measured */
+    RunBandwidthTests {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        robot: Option<String>,
+    },
+    /* audit the Xbees of all drones against the profile, the callback reports the discrepancies */
+    AuditXbees(oneshot::Sender<anyhow::Result<()>>),
+    /* switch the drones out of autonomous mode and tell the ground robots to navigate to their home positions */
+    ReturnToBase(oneshot::Sender<anyhow::Result<()>>),
+    /* Group actions */
+    GroupAction {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        group: String,
+        action: group::Action,
+    },
+    /* Configuration actions */
+    /* whether the discovery of robots is paused while an experiment is running */
+    SetDiscoveryPause(bool),
+    UpdateIdentifiers {
+        callback: oneshot::Sender<()>,
+        builderbots: Vec<builderbot::Descriptor>,
+        drones: Vec<drone::Descriptor>,
+        pipucks: Vec<pipuck::Descriptor>,
+    },
+    /* add and remove robots after the configuration file has been reloaded, the robots whose devices have
+       changed are replaced and the identifiers of the other robots are updated */
+    ReloadRobots {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        builderbots: Vec<builderbot::Descriptor>,
+        drones: Vec<drone::Descriptor>,
+        pipucks: Vec<pipuck::Descriptor>,
+    },
+    /* Maintenance actions */
+    SetMaintenanceMode(oneshot::Sender<anyhow::Result<()>>, maintenance::Request),
+    /* the current maintenance mode is returned with the receiver if maintenance mode is enabled */
+    SubscribeMaintenance(oneshot::Sender<(Option<maintenance::Update>, broadcast::Receiver<maintenance::Update>)>),
+    /* Replication actions */
+    /* an operator confirmed that the primary supervisor, which stopped responding, has released the robots,
+       the status is the last status that the primary reported */
+    TakeOver(Status),
+    /* the primary supervisor starts probing the robot network once no standby has reported a takeover */
+    Activate,
+    /* a standby supervisor has taken over, the primary releases the robots and stays passive */
+    StepDown,
+    /* Association actions */
+    UpdateAssociation(association::Update),
+    /* an action that was caused by a request from the user interface and the trace of that request */
+    Traced(trace::Handle, Box<Action>),
+    /* adopt or ignore a quarantined device */
+    ResolveQuarantine(oneshot::Sender<anyhow::Result<()>>, association::Request),
+    /* the current association failures and quarantined devices are returned with the receiver as a sequence
+       of updates */
+    SubscribeAssociation(oneshot::Sender<(Vec<association::Update>, broadcast::Receiver<association::Update>)>),
+    /* Simulation actions */
+    RegisterSimulatedRobot {
+        callback: oneshot::Sender<anyhow::Result<()>>,
+        id: String,
+        addr: SocketAddr,
+    },
+    UnregisterSimulatedRobot(String),
+    /* the current state of the simulated robots is returned with the receiver */
+    SubscribeSimulation(oneshot::Sender<(Vec<simulation::Robot>, broadcast::Receiver<simulation::Update>)>),
+    /* the current state of the ROS robots is returned with the receiver */
+    SubscribeRos(oneshot::Sender<(Vec<ros::Robot>, broadcast::Receiver<ros::Update>)>),
+}
+
+impl Action {
+    /// Attaches the trace of a request to the action, if the action was caused by a request
+    pub fn traced(self, trace: Option<trace::Handle>) -> Self {
+        match trace {
+            Some(trace) => Action::Traced(trace, Box::new(self)),
+            None => self,
+        }
+    }
+}
+
+/// The state of the robots and the experiment at the time of subscribing and the updates that follow it.
+/// Since the state and the receivers are obtained together, no update is missed or received twice
+pub struct Subscription {
+    pub snapshot: Snapshot,
+    pub builderbots: StreamMap<Arc<builderbot::Descriptor>, BroadcastStream<builderbot::Update>>,
+    pub drones: StreamMap<Arc<drone::Descriptor>, BroadcastStream<drone::Update>>,
+    pub pipucks: StreamMap<Arc<pipuck::Descriptor>, BroadcastStream<pipuck::Update>>,
+    pub checklist: broadcast::Receiver<Vec<ChecklistItem>>,
+    pub smoke_tests: broadcast::Receiver<BTreeMap<String, Option<SmokeTest>>>,
+    pub status: broadcast::Receiver<Status>,
+    /* notified when robots have been added or removed, after which the subscription must be renewed to
+       receive the updates of the added robots */
+    pub roster: broadcast::Receiver<()>,
+}
+
+/// The settings of a robot type from the configuration, which apply to every robot of that type
+#[derive(Clone, Debug, Default)]
+pub struct RobotSettings {
+    pub limits: ResourceLimits,
+    /* the patterns of the files that are collected from the robots after an experiment */
+    pub artifacts: Vec<String>,
+    /* the variables that are added to the environment of ARGoS */
+    pub environment: BTreeMap<String, String>,
+    pub watchdog: Option<Watchdog>,
+}
+
+/* the software of a robot type for an experiment along with the settings of that robot type */
+struct RobotSetup<'a> {
+    software: &'a Software,
+    settings: &'a RobotSettings,
+}
+
+/// While maintenance mode is active, the supervisor stays connected to the robots but remains passive:
+/// the probing of the paused addresses is suspended and automatic safety interventions are suppressed.
+/// Suppressed events are queued and summarized when maintenance mode is exited
+struct Maintenance {
+    start: chrono::DateTime<chrono::Local>,
+    paused_addrs: Vec<Ipv4Addr>,
+    suppressed_events: Vec<String>,
+}
+
+impl Maintenance {
+    fn new(paused_addrs: Vec<Ipv4Addr>) -> Self {
+        Self { start: chrono::Local::now(), paused_addrs, suppressed_events: Vec::new() }
+    }
+
+    fn suppress(&mut self, event: String) {
+        log::warn!("Maintenance mode: {}", event);
+        let timestamp = chrono::Local::now().format("%H:%M:%S");
+        self.suppressed_events.push(format!("[{}] {}", timestamp, event));
+    }
+
+    fn summarize(mut self) -> Vec<String> {
+        let duration = chrono::Local::now().signed_duration_since(self.start);
+        let addresses = match self.paused_addrs.len() {
+            0 => String::from("all addresses"),
+            _ => self.paused_addrs.iter().map(|addr| addr.to_string()).collect::<Vec<_>>().join(", "),
+        };
+        self.suppressed_events.push(format!("Probing of {} was paused for {} seconds",
+            addresses, duration.num_seconds()));
+        self.suppressed_events
+    }
+}
+
+/// Tracks the state of the experiment and the readiness of the participating robots. The status is shared
+/// with the futures that set up the robots concurrently and every change is broadcast to the subscribers
+struct Experiment {
+    status: std::sync::Mutex<Status>,
+    updates_tx: broadcast::Sender<Status>,
+    /* the background polling of the robots is reduced while the experiment is running */
+    polling_tx: watch::Sender<Polling>,
+    polling: PollingFactors,
+    /* the discovery of robots is paused while the experiment is running unless this is disabled */
+    discovery_tx: watch::Sender<network::Discovery>,
+    pause_discovery: AtomicBool,
+}
+
+impl Experiment {
+    fn new(polling_tx: watch::Sender<Polling>,
+           polling: PollingFactors,
+           discovery_tx: watch::Sender<network::Discovery>,
+           pause_discovery: bool) -> Self {
+        let (updates_tx, _) = broadcast::channel(32);
+        Self {
+            status: Default::default(),
+            updates_tx,
+            polling_tx,
+            polling,
+            discovery_tx,
+            pause_discovery: AtomicBool::new(pause_discovery),
+        }
+    }
+
+    fn status(&self) -> Status {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn update(&self, update: impl FnOnce(&mut Status)) {
+        let mut status = self.status.lock().unwrap();
+        update(&mut status);
+        let polling = match status.state {
+            State::Running => Polling::Reduced(self.polling),
+            _ => Polling::Normal,
+        };
+        if *self.polling_tx.borrow() != polling {
+            log::info!("Background polling of the robots: {:?}", polling);
+            let _ = self.polling_tx.send(polling);
+        }
+        let discovery = match status.state {
+            State::Running if self.pause_discovery.load(Ordering::Relaxed) => network::Discovery::Paused,
+            _ => network::Discovery::Enabled,
+        };
+        if *self.discovery_tx.borrow() != discovery {
+            log::info!("Discovery of robots: {:?}", discovery);
+            let _ = self.discovery_tx.send(discovery);
+        }
+        let _ = self.updates_tx.send(status.clone());
+    }
+
+    fn transition(&self, state: State) {
+        log::info!("Experiment state: {:?}", state);
+        self.update(|status| status.state = state);
+    }
+
+    fn readiness(&self, id: &str, readiness: Readiness) {
+        self.update(|status| {
+            status.robots.insert(id.to_owned(), readiness);
+        });
+    }
 }
 
 pub async fn new(
     mut arena_action_rx: mpsc::Receiver<Action>,
     journal_action_tx: mpsc::Sender<journal::Action>,
+    probing_tx: watch::Sender<network::Probing>,
+    discovery_tx: watch::Sender<network::Discovery>,
+    pause_discovery: bool,
+    robots_tx: watch::Sender<network::Robots>,
+    pairing_tx: mpsc::Sender<pairing::Action>,
+    alerts_tx: mpsc::Sender<alerts::Action>,
+    library_tx: mpsc::Sender<library::Action>,
+    /* a standby supervisor does not probe the robot network or start experiments until it takes over, which
+       is also how a supervisor that replays a journal leaves the robots alone */
+    mut standby: bool,
+    checklist: Vec<String>,
+    camera_shutdown: Option<CameraShutdown>,
+    polling: PollingFactors,
+    heartbeat: Heartbeat,
+    return_to_base: ReturnToBase,
+    router_tx: mpsc::Sender<router::Action>,
     builderbots: Vec<builderbot::Descriptor>,
     drones: Vec<drone::Descriptor>,
-    pipucks: Vec<pipuck::Descriptor>
+    pipucks: Vec<pipuck::Descriptor>,
+    simulated_robots: Vec<simulation::Descriptor>,
+    ros_robots: Vec<ros::Descriptor>,
+    groups: Vec<Group>,
+    builderbot_settings: RobotSettings,
+    drone_settings: RobotSettings,
+    pipuck_settings: RobotSettings,
+    xbee_profile: BTreeMap<String, String>,
+    xbee_configurations: BTreeMap<String, Vec<(String, String)>>,
 ) {
-    let builderbots: HashMap<Arc<builderbot::Descriptor>, builderbot::Instance> = builderbots
+    let (polling_tx, polling_rx) = watch::channel(Polling::Normal);
+    let mut builderbots: HashMap<Arc<builderbot::Descriptor>, builderbot::Instance> = builderbots
         .into_iter()
-        .map(|descriptor| (Arc::new(descriptor), builderbot::Instance::default()))
+        .map(|descriptor| {
+            let instance = builderbot::Instance::new(&descriptor, builderbot_settings.limits.clone(),
+                builderbot_settings.watchdog.clone(), polling_rx.clone(), heartbeat);
+            (Arc::new(descriptor), instance)
+        })
         .collect();
-    let drones: HashMap<Arc<drone::Descriptor>, drone::Instance> = drones
+    let mut drones: HashMap<Arc<drone::Descriptor>, drone::Instance> = drones
         .into_iter()
-        .map(|descriptor| (Arc::new(descriptor), drone::Instance::default()))
+        .map(|descriptor| {
+            let xbee_configuration = descriptor.xbee_configuration.as_ref()
+                .and_then(|name| xbee_configurations.get(name))
+                .cloned();
+            let instance = drone::Instance::new(&descriptor, drone_settings.limits.clone(),
+                drone_settings.watchdog.clone(), polling_rx.clone(), heartbeat, xbee_configuration);
+            (Arc::new(descriptor), instance)
+        })
         .collect();
-    let pipucks: HashMap<Arc<pipuck::Descriptor>, pipuck::Instance> = pipucks
+    let mut pipucks: HashMap<Arc<pipuck::Descriptor>, pipuck::Instance> = pipucks
         .into_iter()
-        .map(|descriptor| (Arc::new(descriptor), pipuck::Instance::default()))
+        .map(|descriptor| {
+            let instance = pipuck::Instance::new(&descriptor, pipuck_settings.limits.clone(),
+                pipuck_settings.watchdog.clone(), polling_rx.clone(), heartbeat);
+            (Arc::new(descriptor), instance)
+        })
         .collect();
-    while let Some(action) = arena_action_rx.recv().await {
+    /* the settings that are applied to each robot type are part of the fingerprint of every run */
+    let mut settings = Fingerprint::default();
+    settings.add("BuilderBot settings", format!("{:?} {:?} {:?}",
+        builderbot_settings.limits, builderbot_settings.artifacts, builderbot_settings.environment));
+    settings.add("Drone settings", format!("{:?} {:?} {:?}",
+        drone_settings.limits, drone_settings.artifacts, drone_settings.environment));
+    settings.add("Pi-Puck settings", format!("{:?} {:?} {:?}",
+        pipuck_settings.limits, pipuck_settings.artifacts, pipuck_settings.environment));
+    let mut maintenance: Option<Maintenance> = None;
+    let (maintenance_updates_tx, _) = broadcast::channel(8);
+    let mut association_failures: BTreeMap<Ipv4Addr, association::Failure> = BTreeMap::new();
+    let (association_updates_tx, _) = broadcast::channel(32);
+    /* the devices that did not match any robot, each device is only reported once at each address since
+       the pairing task regularly returns the devices that it holds to the network task */
+    let mut unknown_devices: HashSet<(Ipv4Addr, macaddr::MacAddr6)> = HashSet::new();
+    /* the devices that did not match any robot and when they were last found, these are listed in the user
+       interface until they are adopted, ignored, or no longer found */
+    let mut quarantined: BTreeMap<macaddr::MacAddr6, (Instant, association::UnknownDevice)> = BTreeMap::new();
+    let mut ignored: HashSet<macaddr::MacAddr6> = HashSet::new();
+    let mut quarantine_expiry = tokio::time::interval(QUARANTINE_EXPIRY / 4);
+    let mut checklist: Vec<ChecklistItem> = checklist.into_iter()
+        .map(|description| ChecklistItem { description, acknowledgement: None })
+        .collect();
+    let (checklist_updates_tx, _) = broadcast::channel(8);
+    /* the last smoke test of each robot */
+    let mut smoke_tests: BTreeMap<String, SmokeTest> = BTreeMap::new();
+    let (smoke_tests_updates_tx, _) = broadcast::channel(8);
+    /* the throughput of the robots is measured in the background and added to their smoke tests */
+    let (bandwidth_tests_tx, mut bandwidth_tests_rx) = mpsc::channel::<(String, anyhow::Result<Vec<SmokeTestCheck>>)>(8);
+    /* the requested bandwidth tests are run by a single task, one robot at a time, so that the robots do not share
+       the capacity of the network */
+    let (bandwidth_queue_tx, mut bandwidth_queue_rx) =
+        mpsc::channel::<(Vec<(String, Tester)>, oneshot::Sender<anyhow::Result<()>>)>(8);
+    crate::instrument::spawn("arena/bandwidth", async move {
+        while let Some((robots, callback)) = bandwidth_queue_rx.recv().await {
+            for (id, tester) in robots {
+                let result = tester.run().await;
+                if bandwidth_tests_tx.send((id, result)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = callback.send(Ok(()));
+        }
+    });
+    let (roster_tx, _) = broadcast::channel(1);
+    /* the robots that are simulated on the supervisor machine are tracked alongside the real robots */
+    let mut simulated_robots: Vec<simulation::Robot> = simulated_robots.into_iter()
+        .map(|descriptor| simulation::Robot { descriptor, registration: None })
+        .collect();
+    let (simulation_updates_tx, _) = broadcast::channel(8);
+    /* the ROS robots are controlled via their rosbridge websockets and share a channel for their updates */
+    let (ros_updates_tx, _) = broadcast::channel(16);
+    let ros_robots: HashMap<Arc<ros::Descriptor>, ros::Instance> = ros_robots
+        .into_iter()
+        .map(|descriptor| {
+            let instance = ros::Instance::new(&descriptor, ros_updates_tx.clone());
+            (Arc::new(descriptor), instance)
+        })
+        .collect();
+    /* the repositories from which software has been deployed */
+    let mut repositories = repository::Cache::new(std::env::temp_dir().join("supervisor-repositories"));
+    /* the robots that are participating in the running experiment */
+    let mut participants: HashSet<String> = HashSet::new();
+    let experiment = Experiment::new(polling_tx, polling, discovery_tx, pause_discovery);
+    /* the times at which the running experiments are stopped in order and the robots that participate in them */
+    let mut deadlines: Vec<(tokio::time::Instant, Option<Vec<String>>)> = Vec::new();
+    /* the camera shutdown of the last experiment, which is either configured or chosen when it was started */
+    let mut cameras = camera_shutdown.clone();
+    loop {
+        let deadline = deadlines.first().map(|(deadline, _)| *deadline);
+        let action = tokio::select! {
+            action = arena_action_rx.recv() => match action {
+                Some(action) => action,
+                None => break,
+            },
+            /* stop the experiment once its duration has elapsed */
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)), if deadline.is_some() => {
+                let (_, robots) = deadlines.remove(0);
+                record_phase(&journal_action_tx, "Experiment stopped after its duration").await;
+                let (callback, _) = oneshot::channel();
+                Action::StopExperiment { callback, robots }
+            },
+            Some((id, result)) = bandwidth_tests_rx.recv() => {
+                match &result {
+                    Ok(checks) => log::info!("Bandwidth test of {}: {}", id, checks.iter()
+                        .map(|check| format!("{} {}", check.name, check.value))
+                        .collect::<Vec<_>>()
+                        .join(", ")),
+                    Err(error) => log::warn!("Bandwidth test of {} failed: {:#}", id, error),
+                }
+                let checks = result.unwrap_or_else(|error| vec![SmokeTestCheck {
+                    name: "Throughput".to_owned(),
+                    passed: false,
+                    value: format!("{:#}", error),
+                }]);
+                /* the throughput replaces the throughput of the last bandwidth test */
+                let smoke_test = smoke_tests.entry(id).or_insert_with(|| SmokeTest {
+                    time: chrono::Local::now().to_rfc3339(),
+                    result: Ok(Vec::new()),
+                });
+                smoke_test.time = chrono::Local::now().to_rfc3339();
+                if let Ok(previous) = smoke_test.result.as_mut() {
+                    previous.retain(|check| !check.name.starts_with("Throughput"));
+                    previous.extend(checks);
+                }
+                let _ = smoke_tests_updates_tx.send(smoke_tests_update(&builderbots, &drones, &pipucks, &smoke_tests));
+                continue;
+            },
+            _ = quarantine_expiry.tick() => {
+                let expired = quarantined.iter()
+                    .filter(|(_, (seen, _))| seen.elapsed() > QUARANTINE_EXPIRY)
+                    .map(|(macaddr, _)| *macaddr)
+                    .collect::<Vec<_>>();
+                for macaddr in expired {
+                    quarantined.remove(&macaddr);
+                    let _ = association_updates_tx.send(association::Update::Released(macaddr));
+                }
+                continue;
+            },
+        };
+        let (trace, action) = match action {
+            Action::Traced(trace, action) => (Some(trace), *action),
+            action => (None, action),
+        };
+        /* starting and stopping an experiment with a group is the same as with all robots, except that only the
+           members of the group participate */
+        let action = match action {
+            Action::GroupAction { callback, group, action } => {
+                let members = match groups.iter().find(|candidate| candidate.name == group) {
+                    Some(group) => group.members.clone(),
+                    None => {
+                        let _ = callback.send(Err(anyhow::anyhow!("Group \"{}\" does not exist", group)));
+                        continue;
+                    }
+                };
+                match action {
+                    group::Action::StartExperiment { builderbot_software, drone_software, pipuck_software, overrides, partial_start, settings } =>
+                        Action::StartExperiment {
+                            callback, builderbot_software, drone_software, pipuck_software, overrides, robots: Some(members), partial_start, settings
+                        },
+                    group::Action::StopExperiment =>
+                        Action::StopExperiment { callback, robots: Some(members) },
+                    action => {
+                        let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                        let result = group_action(&builderbots, &drones, &pipucks, &members, action).await
+                            .context(format!("Could not execute action on group \"{}\"", group));
+                        let _ = callback.send(result);
+                        continue;
+                    }
+                }
+            },
+            /* a drone that leaves the arena during an experiment must not continue to fly autonomously */
+            Action::GeofenceViolation { optitrack_id, position: [x, y, z] } => {
+                let id = builderbots.keys().filter(|desc| desc.optitrack_id == Some(optitrack_id)).map(|desc| &desc.id)
+                    .chain(drones.keys().filter(|desc| desc.optitrack_id == Some(optitrack_id)).map(|desc| &desc.id))
+                    .chain(pipucks.keys().filter(|desc| desc.optitrack_id == Some(optitrack_id)).map(|desc| &desc.id))
+                    .find(|id| participants.contains(*id))
+                    .cloned();
+                let id = match id {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let description = format!("{} left the geofence at ({:.2}, {:.2}, {:.2})", id, x, y, z);
+                log::warn!("{}", description);
+                record_timeline(&journal_action_tx, TimelineCategory::Safety, &description, &["geofence"]).await;
+                let drone = drones.iter().find(|(desc, _)| desc.id == id).map(|(_, instance)| instance);
+                match (drone, maintenance.as_mut()) {
+                    /* do not automatically stop the robots while they are being serviced */
+                    (Some(_), Some(maintenance)) => {
+                        maintenance.suppress(format!("Automatic stop after geofence violation ({})", description));
+                        continue;
+                    },
+                    (Some(instance), None) => {
+                        let disable = XbeeAction::SetAutonomousMode(false);
+                        if let Err(error) = execute(&instance.action_tx, drone::Action::ExecuteXbeeAction, disable).await {
+                            log::error!("Could not disable autonomous mode on {}: {}", id, error);
+                        }
+                        record_phase(&journal_action_tx, "Experiment aborted by the geofence").await;
+                        let (callback, _) = oneshot::channel();
+                        Action::StopExperiment { callback, robots: None }
+                    },
+                    /* ground robots are only recorded */
+                    (None, _) => continue,
+                }
+            },
+            Action::BatteryCritical { id, level } => {
+                if !participants.contains(&id) {
+                    continue;
+                }
+                let description = format!("The battery of {} reached a critical level ({}%)", id, level);
+                log::warn!("{}", description);
+                record_timeline(&journal_action_tx, TimelineCategory::Safety, &description, &["battery"]).await;
+                match maintenance.as_mut() {
+                    /* do not automatically stop the robots while they are being serviced */
+                    Some(maintenance) => {
+                        maintenance.suppress(format!("Automatic stop after critical battery level ({})", description));
+                        continue;
+                    },
+                    None => {
+                        record_phase(&journal_action_tx, "Experiment aborted due to a critical battery level").await;
+                        let (callback, _) = oneshot::channel();
+                        Action::StopExperiment { callback, robots: None }
+                    },
+                }
+            },
+            action => action,
+        };
         match action {
             Action::AddXbee(device, macaddr) => {
                 match &associate_xbee_device(macaddr, &drones)[..] {
-                    [instance] => {
-                        let request = drone::Action::AssociateXbee(device);
-                        let _ = instance.action_tx.send(request).await;
+                    [(desc, instance)] => {
+                        let (callback_tx, callback_rx) = oneshot::channel();
+                        let request = drone::Action::AssociateXbee(device, callback_tx);
+                        let _ = instance.action_tx.try_send(request);
+                        quarantine(callback_rx, &desc.id, macaddr, &pairing_tx, pairing::Action::AddXbee);
                     },
                     [_, _, ..] => log::error!("Xbee {} is associated with multiple drones", macaddr),
-                    [] => log::warn!("Xbee {} is not associated with any drone", macaddr),
+                    /* unassigned devices are held so that they can be paired from the user interface */
+                    [] => {
+                        log::warn!("Xbee {} is not associated with any drone", macaddr);
+                        if !ignored.contains(&macaddr) {
+                            let unknown = association::UnknownDevice { kind: association::Kind::Xbee, addr: device.addr, macaddr };
+                            if unknown_devices.insert((device.addr, macaddr)) {
+                                let _ = association_updates_tx.send(association::Update::Unknown(unknown.clone()));
+                            }
+                            hold_in_quarantine(&mut quarantined, &association_updates_tx, unknown);
+                        }
+                        let _ = pairing_tx.send(pairing::Action::AddXbee(device, macaddr, None)).await;
+                    },
                 }
             },
             Action::AddFernbedienung(device, macaddr) => {
+                /* devices that contradict the fixed addresses of the robots are rejected, dropping the device
+                   returns its address to the network task so that it is probed again */
+                let addr = device.addr;
+                if let Some(failure) = check_fixed_address(addr, macaddr, &builderbots, &drones, &pipucks) {
+                    if association_failures.get(&addr) != Some(&failure) {
+                        log::error!("Rejected fernbedienung {} at {}: {:?}", macaddr, addr, failure);
+                        association_failures.insert(addr, failure.clone());
+                        let _ = association_updates_tx.send(association::Update::Failed(addr, failure));
+                    }
+                    continue;
+                }
+                /* clear the failures that were resolved by this device, i.e., a different device at this
+                   address or the robot with this address at a different address */
+                let resolved = association_failures.iter()
+                    .filter(|(&failed_addr, failure)| match failure {
+                        association::Failure::UnexpectedDevice { .. } => failed_addr == addr,
+                        association::Failure::UnexpectedAddress { expected, .. } => *expected == addr,
+                        _ => false,
+                    })
+                    .map(|(&failed_addr, _)| failed_addr)
+                    .collect::<Vec<_>>();
+                for failed_addr in resolved {
+                    association_failures.remove(&failed_addr);
+                    let _ = association_updates_tx.send(association::Update::Cleared(failed_addr));
+                }
                 /* first: attempt to associate fernbedienung with a drone */
                 match &associate_fernbedienung_device_with_drone(macaddr, &drones)[..] {
-                    [instance] => {
-                        let request = drone::Action::AssociateFernbedienung(device);
-                        let _ = instance.action_tx.send(request).await;
+                    [(desc, instance)] => {
+                        let (callback_tx, callback_rx) = oneshot::channel();
+                        let request = drone::Action::AssociateFernbedienung(device, callback_tx);
+                        let _ = instance.action_tx.try_send(request);
+                        quarantine(callback_rx, &desc.id, macaddr, &pairing_tx, pairing::Action::AddFernbedienung);
+                        set_status_leds(&instance.action_tx, drone::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
                     },
                     [_, _, ..] => log::error!("Fernbedienung {} is associated with multiple drones", macaddr),
                     /* second: attempt to associate fernbedienung with a Pi-Puck */
                     [] => match &associate_fernbedienung_device_with_pipuck(macaddr, &pipucks)[..] {
-                        [instance] => {
-                            let request = pipuck::Action::AssociateFernbedienung(device);
-                            let _ = instance.action_tx.send(request).await;
+                        [(desc, instance)] => {
+                            let (callback_tx, callback_rx) = oneshot::channel();
+                            let request = pipuck::Action::AssociateFernbedienung(device, callback_tx);
+                            let _ = instance.action_tx.try_send(request);
+                            quarantine(callback_rx, &desc.id, macaddr, &pairing_tx, pairing::Action::AddFernbedienung);
+                            set_status_leds(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
                         },
                         [_, _, ..] => log::error!("Fernbedienung {} is associated with multiple Pi-Pucks", macaddr),
                         /* third: attempt to associate fernbedienung with a BuilderBot */
                         [] => match &associate_fernbedienung_device_with_builderbot(macaddr, &builderbots)[..] {
-                            [instance] => {
-                                let request = builderbot::Action::AssociateFernbedienung(device);
-                                let _ = instance.action_tx.send(request).await;
+                            [(desc, instance)] => {
+                                let (callback_tx, callback_rx) = oneshot::channel();
+                                let request = builderbot::Action::AssociateFernbedienung(device, callback_tx);
+                                let _ = instance.action_tx.try_send(request);
+                                quarantine(callback_rx, &desc.id, macaddr, &pairing_tx, pairing::Action::AddFernbedienung);
+                                set_status_leds(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
                             },
                             [_, _, ..] => log::error!("Fernbedienung {} is associated with multiple BuilderBots", macaddr),
-                            [] => log::warn!("Fernbedienung {} is not associated with any robot", macaddr),
+                            [] => {
+                                log::warn!("Fernbedienung {} is not associated with any robot", macaddr);
+                                if !ignored.contains(&macaddr) {
+                                    let unknown = association::UnknownDevice { kind: association::Kind::Fernbedienung, addr, macaddr };
+                                    if unknown_devices.insert((addr, macaddr)) {
+                                        let _ = association_updates_tx.send(association::Update::Unknown(unknown.clone()));
+                                    }
+                                    hold_in_quarantine(&mut quarantined, &association_updates_tx, unknown);
+                                }
+                                let _ = pairing_tx.send(pairing::Action::AddFernbedienung(device, macaddr, None)).await;
+                            },
                         },
                     }
                 }
             },
             /* Arena requests */
-            Action::StartExperiment { callback, builderbot_software, drone_software, pipuck_software } => {
-                let start_result = start_experiment(
-                    &builderbots,
-                    &builderbot_software,
-                    &drones,
-                    &drone_software,
-                    &pipucks,
-                    &pipuck_software,
-                    &journal_action_tx).await;
+            Action::StartExperiment { callback, builderbot_software, drone_software, pipuck_software, overrides, robots, partial_start, settings: choices } => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                if standby {
+                    let error = anyhow::anyhow!("This supervisor is on standby, experiments must be started on the primary");
+                    let _ = callback.send(Err(error));
+                    continue;
+                }
+                /* the experiment can only be started once every item of the checklist has been acknowledged */
+                let pending = checklist.iter()
+                    .filter(|item| item.acknowledgement.is_none())
+                    .map(|item| item.description.as_str())
+                    .collect::<Vec<_>>();
+                if !pending.is_empty() {
+                    let error = anyhow::anyhow!("Items of the checklist have not been acknowledged: {}", pending.join(", "));
+                    let _ = callback.send(Err(error));
+                    continue;
+                }
+                /* the software can only be overridden for robots that are defined in the configuration */
+                let unknown = overrides.keys()
+                    .filter(|id| !builderbots.keys().any(|desc| desc.id == **id) &&
+                        !drones.keys().any(|desc| desc.id == **id) &&
+                        !pipucks.keys().any(|desc| desc.id == **id))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                if !unknown.is_empty() {
+                    let error = anyhow::anyhow!("Software was overridden for unknown robots: {}", unknown.join(", "));
+                    let _ = callback.send(Err(error));
+                    continue;
+                }
+                let decimator = match Decimator::new(choices.decimation.as_deref().unwrap_or_default()) {
+                    Ok(decimator) => decimator,
+                    Err(error) => {
+                        let _ = callback.send(Err(error));
+                        continue;
+                    }
+                };
+                let participates = |id: &String| robots.as_ref().map_or(true, |robots| robots.contains(id));
+                let builderbots = select(&builderbots, |desc| participates(&desc.id));
+                let drones = select(&drones, |desc| participates(&desc.id));
+                let pipucks = select(&pipucks, |desc| participates(&desc.id));
+                let ros_robots = select(&ros_robots, |desc| participates(&desc.id));
+                let ids = builderbots.keys().map(|desc| desc.id.clone())
+                    .chain(drones.keys().map(|desc| desc.id.clone()))
+                    .chain(pipucks.keys().map(|desc| desc.id.clone()))
+                    .chain(ros_robots.keys().map(|desc| desc.id.clone()))
+                    .collect::<Vec<_>>();
+                /* a robot can only participate in one experiment at a time, e.g., when experiments are started
+                   with different groups */
+                let state = experiment.status().state;
+                let busy = ids.iter()
+                    .filter(|id| participants.contains(*id))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                if !state.startable() || !busy.is_empty() {
+                    let error = match busy.is_empty() {
+                        true => anyhow::anyhow!("The experiment can not be started while it is {:?}", state),
+                        false => anyhow::anyhow!("Robots are already participating in an experiment: {}", busy.join(", ")),
+                    };
+                    let _ = callback.send(Err(error));
+                    continue;
+                }
+                experiment.update(|status| {
+                    status.state = State::Uploading;
+                    /* forget the robots of the previous experiment, but keep those that are still running */
+                    status.robots.retain(|id, _| participants.contains(id));
+                    status.robots.extend(ids.iter().map(|id| (id.clone(), Readiness::Pending)));
+                });
+                /* the software for a robot type is only required by the robots without an override */
+                let builderbot_defaults = builderbots.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+                let drone_defaults = drones.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+                let pipuck_defaults = pipucks.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+                /* build the software from the repositories before setting up the robots */
+                let deployment = async {
+                    let builderbot = software(&mut repositories, &library_tx, "BuilderBot", builderbot_software, builderbot_defaults).await?;
+                    let drone = software(&mut repositories, &library_tx, "Drone", drone_software, drone_defaults).await?;
+                    let pipuck = software(&mut repositories, &library_tx, "Pi-Puck", pipuck_software, pipuck_defaults).await?;
+                    anyhow::Result::<_>::Ok((builderbot, drone, pipuck))
+                }.await;
+                let start_result = match deployment {
+                    Ok(((builderbot_software, builderbot_deployment),
+                        (drone_software, drone_deployment),
+                        (pipuck_software, pipuck_deployment))) => start_experiment(
+                        &builderbots,
+                        RobotSetup { software: &builderbot_software, settings: &builderbot_settings },
+                        &drones,
+                        RobotSetup { software: &drone_software, settings: &drone_settings },
+                        &pipucks,
+                        RobotSetup { software: &pipuck_software, settings: &pipuck_settings },
+                        &ros_robots,
+                        &overrides,
+                        partial_start,
+                        &decimator,
+                        run_fingerprint(&settings, &choices),
+                        vec![builderbot_deployment, drone_deployment, pipuck_deployment],
+                        &checklist,
+                        &smoke_tests,
+                        &experiment,
+                        &journal_action_tx).await,
+                    Err(error) => Err(error),
+                };
                 let result = match start_result {
-                    Ok(_) => Ok(()),
-                    Err(start_error) => match stop_experiment(&builderbots, &drones, &pipucks, &journal_action_tx).await {
-                        Ok(_) => Err(start_error),
-                        Err(stop_error) => Err(stop_error).context(start_error),
+                    Ok(started) => {
+                        /* the robots that could not be started are stopped, unless they are being serviced */
+                        let failed = |id: &String| !started.contains(id);
+                        let failed_builderbots = select_subset(&builderbots, |desc| failed(&desc.id));
+                        let failed_drones = select_subset(&drones, |desc| failed(&desc.id));
+                        let failed_pipucks = select_subset(&pipucks, |desc| failed(&desc.id));
+                        let failed_ros_robots = select_subset(&ros_robots, |desc| failed(&desc.id));
+                        if !(failed_builderbots.is_empty() && failed_drones.is_empty() &&
+                            failed_pipucks.is_empty() && failed_ros_robots.is_empty()) {
+                            match maintenance.as_mut() {
+                                Some(maintenance) =>
+                                    maintenance.suppress("Automatic stop of the robots that could not be started".to_owned()),
+                                None => if let Err(error) = stop_robots(&failed_builderbots,
+                                    &failed_drones, &failed_pipucks, &failed_ros_robots).await {
+                                    log::error!("{:#}", error);
+                                }
+                            }
+                        }
+                        experiment.transition(State::Running);
+                        /* tie the experiment to the request that started it */
+                        if let Some(id) = trace.as_ref().map(|trace| trace.id) {
+                            let event = journal::Event::Request(id, "Start experiment".to_owned());
+                            let _ = journal_action_tx.send(journal::Action::Record(event)).await;
+                        }
+                        participants.extend(started);
+                        /* the checklist must be completed again before the next experiment */
+                        for item in checklist.iter_mut() {
+                            item.acknowledgement = None;
+                        }
+                        let _ = checklist_updates_tx.send(checklist.clone());
+                        if let Some(duration) = choices.duration {
+                            deadlines.push((tokio::time::Instant::now() + Duration::from_secs(duration), robots.clone()));
+                            deadlines.sort_by_key(|(deadline, _)| *deadline);
+                        }
+                        /* the battery thresholds of the experiment apply until the last robot has been stopped */
+                        if let Some(battery_alerts) = choices.battery_alerts {
+                            if alerts_tx.send(alerts::Action::Override(Some(battery_alerts))).await.is_err() {
+                                log::error!("Could not communicate with the alerts");
+                            }
+                        }
+                        /* the camera streams saturate the wireless network while the experiment is running */
+                        cameras = match choices.cameras {
+                            Some(CameraPolicy::Keep) => None,
+                            Some(CameraPolicy::Suspend(camera_shutdown)) => Some(camera_shutdown),
+                            None => camera_shutdown.clone(),
+                        };
+                        if let Some(camera_shutdown) = cameras.as_ref() {
+                            suspend_camera_streams(&builderbots, &drones, &pipucks, &camera_shutdown.exempt, true);
+                            record_phase(&journal_action_tx, "Camera streams disabled").await;
+                        }
+                        Ok(())
+                    },
+                    Err(start_error) => {
+                        experiment.transition(State::Error(format!("{:#}", start_error)));
+                        match maintenance.as_mut() {
+                            /* do not automatically stop the robots while they are being serviced */
+                            Some(maintenance) => {
+                                maintenance.suppress(format!("Automatic stop after failed start ({})", start_error));
+                                Err(start_error)
+                            },
+                            /* roll back the robots that were already set up or started */
+                            None => {
+                                record_phase(&journal_action_tx, "Rolling back robots").await;
+                                match stop_experiment(&builderbots, &drones, &pipucks, &ros_robots, &journal_action_tx).await {
+                                    Ok(_) => Err(start_error),
+                                    Err(stop_error) => Err(stop_error).context(start_error),
+                                }
+                            }
+                        }
                     }
                 };
                 let _ = callback.send(result);
             },
-            Action::StopExperiment { callback } => {
-                let result = stop_experiment(&builderbots, &drones, &pipucks, &journal_action_tx).await;
+            Action::StopExperiment { callback, robots } => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                if let Some(id) = trace.as_ref().map(|trace| trace.id) {
+                    let event = journal::Event::Request(id, "Stop experiment".to_owned());
+                    let _ = journal_action_tx.send(journal::Action::Record(event)).await;
+                }
+                let participates = |id: &String| robots.as_ref().map_or(true, |robots| robots.contains(id));
+                let builderbots = select(&builderbots, |desc| participates(&desc.id));
+                let drones = select(&drones, |desc| participates(&desc.id));
+                let pipucks = select(&pipucks, |desc| participates(&desc.id));
+                let ros_robots = select(&ros_robots, |desc| participates(&desc.id));
+                experiment.transition(State::Stopping);
+                let result = stop_experiment(&builderbots, &drones, &pipucks, &ros_robots, &journal_action_tx).await;
+                participants.retain(|id| !participates(id));
+                /* the durations of the experiments that were stopped no longer apply */
+                deadlines.retain(|(_, robots)| match robots {
+                    Some(robots) => robots.iter().any(|id| participants.contains(id)),
+                    None => !participants.is_empty(),
+                });
+                if participants.is_empty() {
+                    if alerts_tx.send(alerts::Action::Override(None)).await.is_err() {
+                        log::error!("Could not communicate with the alerts");
+                    }
+                }
+                experiment.update(|status| {
+                    status.robots.retain(|id, _| participants.contains(id));
+                    status.state = match (&result, participants.is_empty()) {
+                        (Err(error), _) => State::Error(format!("{:#}", error)),
+                        (Ok(_), true) => State::Finished,
+                        /* the robots of other groups are still running */
+                        (Ok(_), false) => State::Running,
+                    };
+                });
+                /* ARGoS releases the LEDs once it has been stopped */
+                for instance in builderbots.values() {
+                    set_status_leds(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
+                }
+                for instance in drones.values() {
+                    set_status_leds(&instance.action_tx, drone::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
+                }
+                for instance in pipucks.values() {
+                    set_status_leds(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction, StatusLeds::Ready);
+                }
+                if let Some(camera_shutdown) = cameras.as_ref().filter(|camera_shutdown| camera_shutdown.restore) {
+                    suspend_camera_streams(&builderbots, &drones, &pipucks, &camera_shutdown.exempt, false);
+                }
+                /* verify that the robots are in a safe state once they had the time to stop */
+                let stopped = Stopped {
+                    builderbots: builderbots.iter()
+                        .map(|(desc, instance)| (desc.id.clone(), instance.action_tx.clone())).collect(),
+                    drones: drones.iter()
+                        .map(|(desc, instance)| (desc.id.clone(), instance.action_tx.clone())).collect(),
+                    pipucks: pipucks.iter()
+                        .map(|(desc, instance)| (desc.id.clone(), instance.action_tx.clone())).collect(),
+                };
+                /* the robots are not stopped again while they are being serviced, their state is only reported */
+                let retries = match maintenance.as_mut() {
+                    Some(maintenance) => {
+                        maintenance.suppress("Automatic stop of the robots that are not in a safe state".to_owned());
+                        0
+                    },
+                    None => VERIFICATION_RETRIES,
+                };
+                crate::instrument::spawn("arena/verification",
+                    verify_safe_state(stopped, retries, router_tx.clone(), alerts_tx.clone()));
                 let _ = callback.send(result.context("Could not stop experiment"));
             },
+            Action::AcknowledgeChecklistItem { callback, item, operator } => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let result = match checklist.get_mut(item) {
+                    Some(item) => match operator.as_deref().map(str::trim) {
+                        Some("") => Err(anyhow::anyhow!("The name of the operator must be provided")),
+                        Some(operator) => {
+                            log::info!("{} acknowledged \"{}\"", operator, item.description);
+                            item.acknowledgement = Some(Acknowledgement {
+                                operator: operator.to_owned(),
+                                time: chrono::Local::now().to_rfc3339(),
+                            });
+                            Ok(())
+                        },
+                        None => {
+                            item.acknowledgement = None;
+                            Ok(())
+                        },
+                    },
+                    None => Err(anyhow::anyhow!("Item {} of the checklist does not exist", item)),
+                };
+                if result.is_ok() {
+                    let _ = checklist_updates_tx.send(checklist.clone());
+                }
+                let _ = callback.send(result);
+            },
+            Action::RunSmokeTests { callback, robot } => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let selected = |id: &String| robot.as_ref().map_or(true, |robot| robot == id);
+                let builderbot_tests = builderbots.iter()
+                    .filter(|(desc, _)| selected(&desc.id))
+                    .map(|(desc, instance)| async move {
+                        (desc.id.clone(), smoke_test(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction).await)
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .collect::<Vec<_>>();
+                let drone_tests = drones.iter()
+                    .filter(|(desc, _)| selected(&desc.id))
+                    .map(|(desc, instance)| async move {
+                        (desc.id.clone(), smoke_test(&instance.action_tx, drone::Action::ExecuteFernbedienungAction).await)
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .collect::<Vec<_>>();
+                let pipuck_tests = pipucks.iter()
+                    .filter(|(desc, _)| selected(&desc.id))
+                    .map(|(desc, instance)| async move {
+                        (desc.id.clone(), smoke_test(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction).await)
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .collect::<Vec<_>>();
+                let (builderbot_results, drone_results, pipuck_results) =
+                    tokio::join!(builderbot_tests, drone_tests, pipuck_tests);
+                let results = builderbot_results.into_iter()
+                    .chain(drone_results)
+                    .chain(pipuck_results)
+                    .collect::<Vec<_>>();
+                let result = match robot {
+                    Some(robot) if results.is_empty() => Err(anyhow::anyhow!("Could not find robot {}", robot)),
+                    _ => Ok(()),
+                };
+                for (id, result) in results {
+                    let mut smoke_test = SmokeTest {
+                        time: chrono::Local::now().to_rfc3339(),
+                        result: result.map_err(|error| format!("{:#}", error)),
+                    };
+                    /* keep the throughput of the last bandwidth test */
+                    if let (Ok(checks), Some(Ok(previous))) = (smoke_test.result.as_mut(), smoke_tests.get(&id).map(|last| &last.result)) {
+                        checks.extend(previous.iter().filter(|check| check.name.starts_with("Throughput")).cloned());
+                    }
+                    match smoke_test.passed() {
+                        true => log::info!("Smoke test of {} passed", id),
+                        false => log::warn!("Smoke test of {} failed: {:?}", id, smoke_test.result),
+                    }
+                    smoke_tests.insert(id, smoke_test);
+                }
+                let _ = smoke_tests_updates_tx.send(smoke_tests_update(&builderbots, &drones, &pipucks, &smoke_tests));
+                let _ = callback.send(result);
+            },
+            Action::RunBandwidthTests { callback, robot } => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let selected = |id: &String| robot.as_ref().map_or(true, |robot| robot == id);
+                let mut robots = builderbots.iter()
+                    .filter(|(desc, _)| selected(&desc.id))
+                    .map(|(desc, instance)| (desc.id.clone(), Tester::BuilderBot(instance.action_tx.clone())))
+                    .chain(drones.iter()
+                        .filter(|(desc, _)| selected(&desc.id))
+                        .map(|(desc, instance)| (desc.id.clone(), Tester::Drone(instance.action_tx.clone()))))
+                    .chain(pipucks.iter()
+                        .filter(|(desc, _)| selected(&desc.id))
+                        .map(|(desc, instance)| (desc.id.clone(), Tester::PiPuck(instance.action_tx.clone()))))
+                    .collect::<Vec<_>>();
+                if let (Some(robot), true) = (robot, robots.is_empty()) {
+                    let _ = callback.send(Err(anyhow::anyhow!("Could not find robot {}", robot)));
+                    continue;
+                }
+                robots.sort_by(|(left, _), (right, _)| left.cmp(right));
+                /* the arena does not wait for the queue since the task sends the results back to the arena */
+                if let Err(error) = bandwidth_queue_tx.try_send((robots, callback)) {
+                    let (reason, callback) = match error {
+                        mpsc::error::TrySendError::Full((_, callback)) => ("Too many bandwidth tests are queued", callback),
+                        mpsc::error::TrySendError::Closed((_, callback)) => ("Bandwidth tests are not available", callback),
+                    };
+                    let _ = callback.send(Err(anyhow::anyhow!(reason)));
+                }
+            },
+            Action::AuditXbees(callback) => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let audits = drones.iter()
+                    .map(|(desc, instance)| {
+                        let action = XbeeAction::Audit(xbee_profile.clone());
+                        async move {
+                            (desc.id.clone(), execute(&instance.action_tx, drone::Action::ExecuteXbeeAction, action).await)
+                        }
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .collect::<Vec<_>>()
+                    .await;
+                let mut discrepancies = audits.into_iter()
+                    .filter_map(|(id, result)| result.err().map(|error| format!("{}: {:#}", id, error)))
+                    .collect::<Vec<_>>();
+                discrepancies.sort();
+                for discrepancy in &discrepancies {
+                    log::warn!("Xbee audit: {}", discrepancy);
+                }
+                let result = match discrepancies.len() {
+                    0 => Ok(()),
+                    _ => Err(anyhow::anyhow!("The Xbees differ from the profile: {}", discrepancies.join("; "))),
+                };
+                let _ = callback.send(result);
+            },
+            /* unlike stopping the experiment, ARGoS keeps running so that the ground robots can drive home */
+            Action::ReturnToBase(callback) => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                log::info!("Returning the robots to base");
+                record_timeline(&journal_action_tx, TimelineCategory::Safety, "Return to base", &[]).await;
+                let mut failures = Vec::new();
+                if router_tx.send(router::Action::Broadcast(return_to_base.command.clone())).await.is_err() {
+                    failures.push(String::from("Could not send the command to the message router"));
+                }
+                let drone_mode = return_to_base.drone_mode;
+                let results = drones.iter()
+                    .map(|(desc, instance)| async move {
+                        let result = async {
+                            let disable = XbeeAction::SetAutonomousMode(false);
+                            execute(&instance.action_tx, drone::Action::ExecuteXbeeAction, disable).await?;
+                            let mode = XbeeAction::SetFlightMode(drone_mode);
+                            execute(&instance.action_tx, drone::Action::ExecuteXbeeAction, mode).await
+                        }.await;
+                        (desc.id.clone(), result)
+                    })
+                    .collect::<FuturesUnordered<_>>()
+                    .collect::<Vec<_>>()
+                    .await;
+                let mut drone_failures = results.into_iter()
+                    .filter_map(|(id, result)| result.err().map(|error| format!("{}: {:#}", id, error)))
+                    .collect::<Vec<_>>();
+                drone_failures.sort();
+                failures.extend(drone_failures);
+                for failure in &failures {
+                    log::warn!("Return to base: {}", failure);
+                }
+                let result = match failures.len() {
+                    0 => Ok(()),
+                    _ => Err(anyhow::anyhow!("Not all robots could be returned to base: {}", failures.join("; "))),
+                };
+                let _ = callback.send(result);
+            },
+            Action::SetDiscoveryPause(pause_discovery) => {
+                experiment.pause_discovery.store(pause_discovery, Ordering::Relaxed);
+                experiment.update(|_| ());
+            },
+            Action::UpdateIdentifiers { callback, builderbots: builderbot_updates, drones: drone_updates, pipucks: pipuck_updates } => {
+                /* only the optitrack and apriltag identifiers and the reserved addresses of existing robots can be updated */
+                builderbots = builderbots.into_iter()
+                    .map(|(desc, instance)| match builderbot_updates.iter()
+                        .find(|update| update.id == desc.id && update.duovero_macaddr == desc.duovero_macaddr) {
+                        Some(update) if update != desc.as_ref() => (Arc::new(update.clone()), instance),
+                        _ => (desc, instance),
+                    })
+                    .collect();
+                drones = drones.into_iter()
+                    .map(|(desc, instance)| match drone_updates.iter()
+                        .find(|update| update.id == desc.id && update.xbee_macaddr == desc.xbee_macaddr &&
+                            update.upcore_macaddr == desc.upcore_macaddr) {
+                        Some(update) if update != desc.as_ref() => (Arc::new(update.clone()), instance),
+                        _ => (desc, instance),
+                    })
+                    .collect();
+                pipucks = pipucks.into_iter()
+                    .map(|(desc, instance)| match pipuck_updates.iter()
+                        .find(|update| update.id == desc.id && update.rpi_macaddr == desc.rpi_macaddr) {
+                        Some(update) if update != desc.as_ref() => (Arc::new(update.clone()), instance),
+                        _ => (desc, instance),
+                    })
+                    .collect();
+                let _ = callback.send(());
+            },
+            Action::ReloadRobots { callback, builderbots: builderbot_updates, drones: drone_updates, pipucks: pipuck_updates } => {
+                let builderbot_changes = Changes::new(&builderbots, builderbot_updates,
+                    |desc| desc.id.clone(), |desc, update| desc.duovero_macaddr == update.duovero_macaddr);
+                let drone_changes = Changes::new(&drones, drone_updates,
+                    |desc| desc.id.clone(), |desc, update| desc.xbee_macaddr == update.xbee_macaddr &&
+                        desc.upcore_macaddr == update.upcore_macaddr && desc.xbee_configuration == update.xbee_configuration);
+                let pipuck_changes = Changes::new(&pipucks, pipuck_updates,
+                    |desc| desc.id.clone(), |desc, update| desc.rpi_macaddr == update.rpi_macaddr);
+                /* the robots in an experiment can not be removed or replaced */
+                let busy = builderbot_changes.removed.iter().map(|desc| &desc.id)
+                    .chain(drone_changes.removed.iter().map(|desc| &desc.id))
+                    .chain(pipuck_changes.removed.iter().map(|desc| &desc.id))
+                    .filter(|id| participants.contains(*id))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                if !busy.is_empty() {
+                    let error = anyhow::anyhow!("Robots that participate in an experiment can not be removed or replaced: {}",
+                        busy.join(", "));
+                    let _ = callback.send(Err(error));
+                    continue;
+                }
+                let changed = !(builderbot_changes.is_empty() && drone_changes.is_empty() && pipuck_changes.is_empty());
+                builderbot_changes.apply(&mut builderbots, builderbot::Instance::shutdown, |desc| {
+                    builderbot::Instance::new(desc, builderbot_settings.limits.clone(), builderbot_settings.watchdog.clone(),
+                        polling_rx.clone(), heartbeat)
+                });
+                drone_changes.apply(&mut drones, drone::Instance::shutdown, |desc| {
+                    let xbee_configuration = desc.xbee_configuration.as_ref()
+                        .and_then(|name| xbee_configurations.get(name))
+                        .cloned();
+                    drone::Instance::new(desc, drone_settings.limits.clone(), drone_settings.watchdog.clone(),
+                        polling_rx.clone(), heartbeat, xbee_configuration)
+                });
+                pipuck_changes.apply(&mut pipucks, pipuck::Instance::shutdown, |desc| {
+                    pipuck::Instance::new(desc, pipuck_settings.limits.clone(), pipuck_settings.watchdog.clone(),
+                        polling_rx.clone(), heartbeat)
+                });
+                if changed {
+                    let _ = robots_tx.send(network_robots(&builderbots, &drones, &pipucks));
+                    let _ = smoke_tests_updates_tx.send(smoke_tests_update(&builderbots, &drones, &pipucks, &smoke_tests));
+                    let _ = roster_tx.send(());
+                }
+                let _ = callback.send(Ok(()));
+            },
+            Action::SetMaintenanceMode(callback, request) => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let result = match request {
+                    maintenance::Request::Enable { paused_addrs } => match maintenance {
+                        Some(_) => Err(anyhow::anyhow!("Maintenance mode is already enabled")),
+                        None => {
+                            log::info!("Entering maintenance mode");
+                            if !standby {
+                                let _ = probing_tx.send(network::Probing::Paused(paused_addrs.clone()));
+                            }
+                            let update = maintenance::Update::Enabled { paused_addrs: paused_addrs.clone() };
+                            let _ = maintenance_updates_tx.send(update);
+                            maintenance = Some(Maintenance::new(paused_addrs));
+                            Ok(())
+                        }
+                    },
+                    maintenance::Request::Disable => match maintenance.take() {
+                        None => Err(anyhow::anyhow!("Maintenance mode is not enabled")),
+                        Some(maintenance) => {
+                            log::info!("Exiting maintenance mode");
+                            if !standby {
+                                let _ = probing_tx.send(network::Probing::Enabled);
+                            }
+                            let suppressed_events = maintenance.summarize();
+                            for event in &suppressed_events {
+                                log::info!("Maintenance summary: {}", event);
+                            }
+                            let update = maintenance::Update::Disabled { suppressed_events };
+                            let _ = maintenance_updates_tx.send(update);
+                            Ok(())
+                        }
+                    }
+                };
+                let _ = callback.send(result);
+            },
+            Action::TakeOver(status) => {
+                if !standby {
+                    continue;
+                }
+                standby = false;
+                record_timeline(&journal_action_tx, TimelineCategory::Safety,
+                    "The primary supervisor stopped responding and an operator confirmed the takeover", &[]).await;
+                /* start associating the robots, respecting the addresses that are paused for maintenance */
+                let probing = match &maintenance {
+                    Some(maintenance) => network::Probing::Paused(maintenance.paused_addrs.clone()),
+                    None => network::Probing::Enabled,
+                };
+                let _ = probing_tx.send(probing);
+                /* adopt the experiment of the primary so that it can be stopped once the robots are associated */
+                match status.state {
+                    State::Uploading | State::Ready | State::Running | State::Stopping if !status.robots.is_empty() => {
+                        log::warn!("Adopting the experiment of the primary supervisor with {}",
+                            status.robots.keys().map(String::as_str).collect::<Vec<_>>().join(", "));
+                        participants = status.robots.keys().cloned().collect();
+                        experiment.update(|current| {
+                            current.robots = status.robots;
+                            current.state = State::Running;
+                        });
+                    },
+                    _ => {},
+                }
+            },
+            Action::Activate => {
+                if !standby {
+                    continue;
+                }
+                standby = false;
+                let probing = match &maintenance {
+                    Some(maintenance) => network::Probing::Paused(maintenance.paused_addrs.clone()),
+                    None => network::Probing::Enabled,
+                };
+                let _ = probing_tx.send(probing);
+            },
+            Action::StepDown => {
+                standby = true;
+                let _ = probing_tx.send(network::Probing::Paused(Vec::new()));
+                record_timeline(&journal_action_tx, TimelineCategory::Safety,
+                    "A standby supervisor took over and this supervisor released the robots", &[]).await;
+                /* restarting the instances closes the connections to the robots, which the standby now drives */
+                builderbots = builderbots.into_iter()
+                    .map(|(desc, instance)| {
+                        instance.shutdown();
+                        let instance = builderbot::Instance::new(&desc, builderbot_settings.limits.clone(),
+                            builderbot_settings.watchdog.clone(), polling_rx.clone(), heartbeat);
+                        (desc, instance)
+                    })
+                    .collect();
+                drones = drones.into_iter()
+                    .map(|(desc, instance)| {
+                        instance.shutdown();
+                        let xbee_configuration = desc.xbee_configuration.as_ref()
+                            .and_then(|name| xbee_configurations.get(name))
+                            .cloned();
+                        let instance = drone::Instance::new(&desc, drone_settings.limits.clone(),
+                            drone_settings.watchdog.clone(), polling_rx.clone(), heartbeat, xbee_configuration);
+                        (desc, instance)
+                    })
+                    .collect();
+                pipucks = pipucks.into_iter()
+                    .map(|(desc, instance)| {
+                        instance.shutdown();
+                        let instance = pipuck::Instance::new(&desc, pipuck_settings.limits.clone(),
+                            pipuck_settings.watchdog.clone(), polling_rx.clone(), heartbeat);
+                        (desc, instance)
+                    })
+                    .collect();
+                participants.clear();
+                experiment.update(|status| {
+                    status.robots.clear();
+                    status.state = State::Idle;
+                });
+                let _ = robots_tx.send(network_robots(&builderbots, &drones, &pipucks));
+                let _ = roster_tx.send(());
+            },
+            Action::SubscribeMaintenance(callback) => {
+                let current = maintenance.as_ref()
+                    .map(|maintenance| maintenance::Update::Enabled { paused_addrs: maintenance.paused_addrs.clone() });
+                let _ = callback.send((current, maintenance_updates_tx.subscribe()));
+            },
+            Action::UpdateAssociation(update) => {
+                match &update {
+                    association::Update::Failed(addr, failure) => {
+                        association_failures.insert(*addr, failure.clone());
+                    },
+                    association::Update::Cleared(addr) => {
+                        association_failures.remove(addr);
+                    },
+                    association::Update::Unknown(_) |
+                    association::Update::Quarantined(_) |
+                    association::Update::Released(_) => {},
+                }
+                let _ = association_updates_tx.send(update);
+            },
+            Action::ResolveQuarantine(callback, request) => {
+                let callback = trace::callback(trace.clone(), "arena".to_owned(), callback);
+                let (macaddr, result) = match request {
+                    association::Request::Adopt(macaddr, robot) => match quarantined.get(&macaddr) {
+                        None => (macaddr, Err(anyhow::anyhow!("Device {} is not in quarantine", macaddr))),
+                        /* the device of a robot can not be replaced while the robot is in an experiment */
+                        Some(_) if participants.contains(&robot) =>
+                            (macaddr, Err(anyhow::anyhow!("{} is participating in an experiment", robot))),
+                        Some((_, device)) => {
+                            let result = adopt(device, &robot, &mut builderbots, &mut drones, &mut pipucks);
+                            if result.is_ok() {
+                                log::info!("Device {} at {} was adopted by {}", macaddr, device.addr, robot);
+                                let _ = robots_tx.send(network_robots(&builderbots, &drones, &pipucks));
+                                /* the device is probed again and then associated with the robot */
+                                let _ = pairing_tx.send(pairing::Action::Release(macaddr)).await;
+                            }
+                            (macaddr, result)
+                        }
+                    },
+                    association::Request::Ignore(macaddr) => match quarantined.contains_key(&macaddr) {
+                        false => (macaddr, Err(anyhow::anyhow!("Device {} is not in quarantine", macaddr))),
+                        true => {
+                            log::info!("Device {} is ignored", macaddr);
+                            ignored.insert(macaddr);
+                            (macaddr, Ok(()))
+                        }
+                    },
+                };
+                if result.is_ok() {
+                    quarantined.remove(&macaddr);
+                    let _ = association_updates_tx.send(association::Update::Released(macaddr));
+                }
+                let _ = callback.send(result);
+            },
+            Action::SubscribeAssociation(callback) => {
+                let current = association_failures.iter()
+                    .map(|(addr, failure)| association::Update::Failed(*addr, failure.clone()))
+                    .chain(quarantined.values().map(|(_, device)| association::Update::Quarantined(device.clone())))
+                    .collect();
+                let _ = callback.send((current, association_updates_tx.subscribe()));
+            },
+            Action::RegisterSimulatedRobot { callback, id, addr } => {
+                let result = match simulated_robots.iter_mut().find(|robot| robot.descriptor.id == id) {
+                    Some(simulation::Robot { registration: Some(registration), .. }) if registration.addr != addr =>
+                        Err(anyhow::anyhow!("Simulated robot {} is already registered from {}", id, registration.addr)),
+                    Some(robot) => {
+                        robot.registration = Some(simulation::Registration {
+                            addr,
+                            time: chrono::Local::now().to_rfc3339(),
+                        });
+                        Ok(())
+                    },
+                    None => Err(anyhow::anyhow!("Simulated robot {} is not declared in the configuration", id)),
+                };
+                if result.is_ok() {
+                    record_phase(&journal_action_tx, &format!("Simulated robot {} registered from {}", id, addr)).await;
+                    let _ = simulation_updates_tx.send(simulation::Update::Robots(simulated_robots.clone()));
+                }
+                let _ = callback.send(result);
+            },
+            Action::UnregisterSimulatedRobot(id) => {
+                if let Some(robot) = simulated_robots.iter_mut().find(|robot| robot.descriptor.id == id) {
+                    robot.registration = None;
+                    let description = format!("Simulated robot {} disconnected from the message router", id);
+                    record_timeline(&journal_action_tx, TimelineCategory::Disconnect, &description, &[]).await;
+                    let _ = simulation_updates_tx.send(simulation::Update::Robots(simulated_robots.clone()));
+                }
+            },
+            Action::SubscribeSimulation(callback) => {
+                let _ = callback.send((simulated_robots.clone(), simulation_updates_tx.subscribe()));
+            },
+            Action::SubscribeRos(callback) => {
+                let ros_updates = ros_updates_tx.subscribe();
+                let robots = ros_robots.values()
+                    .map(|instance| instance.action_tx.clone())
+                    .collect::<Vec<_>>();
+                /* the robots are queried in a separate task so that the arena does not wait on their responses */
+                crate::instrument::spawn("arena/ros", async move {
+                    let state = robots.into_iter()
+                        .map(|action_tx| async move {
+                            let (callback_tx, callback_rx) = oneshot::channel();
+                            action_tx.try_send(ros::Action::GetState(callback_tx)).ok()?;
+                            callback_rx.await.ok()
+                        })
+                        .collect::<FuturesUnordered<_>>()
+                        .filter_map(futures::future::ready)
+                        .collect::<Vec<_>>()
+                        .await;
+                    let _ = callback.send((state, ros_updates));
+                });
+            },
             Action::ForwardBuilderBotAction(id, request) => {
                 match builderbots.iter().find(|&(desc, _)| desc.id == id) {
                     Some((_, instance)) => {
-                        let _ = instance.action_tx.send(request).await;
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Forwarded to {}", id));
+                        }
+                        let _ = instance.action_tx.try_send(request.traced(trace));
                     }
-                    None => log::warn!("Could not find BuilderBot with identifier {}", id),
+                    None => {
+                        log::warn!("Could not find BuilderBot with identifier {}", id);
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Could not find BuilderBot with identifier {}", id));
+                        }
+                    },
                 }
             }
             Action::GetBuilderBotDescriptors(callback) => {
@@ -131,9 +1379,17 @@ pub async fn new(
             Action::ForwardDroneAction(id, request) => {
                 match drones.iter().find(|&(desc, _)| desc.id == id) {
                     Some((_, instance)) => {
-                        let _ = instance.action_tx.send(request).await;
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Forwarded to {}", id));
+                        }
+                        let _ = instance.action_tx.try_send(request.traced(trace));
                     }
-                    None => log::warn!("Could not find drone with identifier {}", id),
+                    None => {
+                        log::warn!("Could not find drone with identifier {}", id);
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Could not find drone with identifier {}", id));
+                        }
+                    },
                 }
             }
             Action::GetDroneDescriptors(callback) => {
@@ -143,25 +1399,215 @@ pub async fn new(
             Action::ForwardPiPuckAction(id, request) => {
                 match pipucks.iter().find(|&(desc, _)| desc.id == id) {
                     Some((_, instance)) => {
-                        let _ = instance.action_tx.send(request).await;
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Forwarded to {}", id));
+                        }
+                        let _ = instance.action_tx.try_send(request.traced(trace));
                     }
-                    None => log::warn!("Could not find drone with identifier {}", id),
+                    None => {
+                        log::warn!("Could not find Pi-Puck with identifier {}", id);
+                        if let Some(trace) = trace.as_ref() {
+                            trace::record(trace, "arena", format!("Could not find Pi-Puck with identifier {}", id));
+                        }
+                    },
                 }
             },
             Action::GetPiPuckDescriptors(callback) => {
                 let _ = callback.send(pipucks.keys().cloned().collect::<Vec<_>>());
+            },
+            Action::Subscribe(callback) => {
+                let checklist_updates = checklist_updates_tx.subscribe();
+                let smoke_tests_updates = smoke_tests_updates_tx.subscribe();
+                let status_updates = experiment.updates_tx.subscribe();
+                let roster_updates = roster_tx.subscribe();
+                let mut snapshot = Snapshot {
+                    checklist: checklist.clone(),
+                    smoke_tests: smoke_tests_update(&builderbots, &drones, &pipucks, &smoke_tests),
+                    status: experiment.status(),
+                    ..Default::default()
+                };
+                let builderbots = builderbots.iter()
+                    .map(|(desc, instance)| (desc.clone(), instance.action_tx.clone()))
+                    .collect::<Vec<_>>();
+                let drones = drones.iter()
+                    .map(|(desc, instance)| (desc.clone(), instance.action_tx.clone()))
+                    .collect::<Vec<_>>();
+                let pipucks = pipucks.iter()
+                    .map(|(desc, instance)| (desc.clone(), instance.action_tx.clone()))
+                    .collect::<Vec<_>>();
+                /* the robots are queried in a separate task so that the arena does not wait on their responses */
+                crate::instrument::spawn("arena/subscription", async move {
+                    let subscriptions = async {
+                        let (builderbot_state, builderbot_updates) = subscribe(builderbots, builderbot::Action::Subscribe).await
+                            .context("Could not subscribe to BuilderBot updates")?;
+                        let (drone_state, drone_updates) = subscribe(drones, drone::Action::Subscribe).await
+                            .context("Could not subscribe to drone updates")?;
+                        let (pipuck_state, pipuck_updates) = subscribe(pipucks, pipuck::Action::Subscribe).await
+                            .context("Could not subscribe to Pi-Puck updates")?;
+                        snapshot.builderbots = builderbot_state;
+                        snapshot.drones = drone_state;
+                        snapshot.pipucks = pipuck_state;
+                        Ok::<_, anyhow::Error>(Subscription {
+                            snapshot,
+                            builderbots: builderbot_updates,
+                            drones: drone_updates,
+                            pipucks: pipuck_updates,
+                            checklist: checklist_updates,
+                            smoke_tests: smoke_tests_updates,
+                            status: status_updates,
+                            roster: roster_updates,
+                        })
+                    };
+                    let _ = callback.send(subscriptions.await);
+                });
+            }
+            /* these actions have been converted or handled before the dispatch */
+            Action::GroupAction { .. } | Action::GeofenceViolation { .. } | Action::BatteryCritical { .. } |
+            Action::Traced(..) => {}
+        }
+    }
+}
+
+/// The MAC addresses and the fixed addresses of the devices of the robots for the network module
+fn network_robots(
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+) -> network::Robots {
+    network::Robots::new(
+        builderbots.keys().map(Deref::deref),
+        drones.keys().map(Deref::deref),
+        pipucks.keys().map(Deref::deref))
+}
+
+/// The changes to the robots of one type after the configuration file has been reloaded. The robots are
+/// matched by their identifiers and a robot whose devices have changed is removed and added again
+struct Changes<D> {
+    /* the robots that are kept and their reloaded descriptors, which may differ in their identifiers */
+    kept: Vec<(Arc<D>, D)>,
+    added: Vec<D>,
+    removed: Vec<Arc<D>>,
+}
+
+impl<D: Clone + Eq + Hash> Changes<D> {
+    fn new<I>(
+        robots: &HashMap<Arc<D>, I>,
+        mut updates: Vec<D>,
+        id: impl Fn(&D) -> String,
+        same_devices: impl Fn(&D, &D) -> bool,
+    ) -> Self {
+        let mut changes = Changes { kept: Vec::new(), added: Vec::new(), removed: Vec::new() };
+        for desc in robots.keys() {
+            match updates.iter().position(|update| id(update) == id(desc)) {
+                Some(index) if same_devices(desc, &updates[index]) => {
+                    let update = updates.remove(index);
+                    changes.kept.push((desc.clone(), update));
+                },
+                Some(index) => {
+                    changes.removed.push(desc.clone());
+                    changes.added.push(updates.remove(index));
+                },
+                None => changes.removed.push(desc.clone()),
+            }
+        }
+        changes.added.extend(updates);
+        changes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() &&
+            self.kept.iter().all(|(desc, update)| desc.as_ref() == update)
+    }
+
+    fn apply<I>(self, robots: &mut HashMap<Arc<D>, I>, shutdown: impl Fn(I), mut create: impl FnMut(&D) -> I) {
+        for desc in self.removed {
+            if let Some(instance) = robots.remove(&desc) {
+                shutdown(instance);
+            }
+        }
+        for (desc, update) in self.kept {
+            if desc.as_ref() != &update {
+                if let Some(instance) = robots.remove(&desc) {
+                    robots.insert(Arc::new(update), instance);
+                }
             }
         }
+        for desc in self.added {
+            let instance = create(&desc);
+            robots.insert(Arc::new(desc), instance);
+        }
+    }
+}
+
+/// Lists a device that did not match any robot in the quarantine, the clients are only updated if the device
+/// was not listed before or was found at a different address
+fn hold_in_quarantine(
+    quarantined: &mut BTreeMap<macaddr::MacAddr6, (Instant, association::UnknownDevice)>,
+    association_updates_tx: &broadcast::Sender<association::Update>,
+    device: association::UnknownDevice,
+) {
+    let previous = quarantined.insert(device.macaddr, (Instant::now(), device.clone()));
+    if previous.map_or(true, |(_, previous)| previous != device) {
+        let _ = association_updates_tx.send(association::Update::Quarantined(device));
+    }
+}
+
+/// Replaces the descriptor of the robot that matches the predicate with an updated descriptor, returns
+/// false if no robot matches
+fn update_descriptor<D: Clone + Eq + Hash, I>(
+    robots: &mut HashMap<Arc<D>, I>,
+    predicate: impl Fn(&D) -> bool,
+    update: impl FnOnce(&mut D),
+) -> bool {
+    let desc = match robots.keys().find(|desc| predicate(desc)) {
+        Some(desc) => desc.clone(),
+        None => return false,
+    };
+    match robots.remove(&desc) {
+        Some(instance) => {
+            let mut desc = desc.as_ref().clone();
+            update(&mut desc);
+            robots.insert(Arc::new(desc), instance);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Assigns a quarantined device to a robot by replacing the MAC address of the corresponding device in the
+/// descriptor of the robot. The assignment only lasts until the supervisor is restarted
+fn adopt(
+    device: &association::UnknownDevice,
+    robot: &str,
+    builderbots: &mut HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
+    drones: &mut HashMap<Arc<drone::Descriptor>, drone::Instance>,
+    pipucks: &mut HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+) -> anyhow::Result<()> {
+    let macaddr = device.macaddr;
+    let adopted = match device.kind {
+        association::Kind::Xbee =>
+            update_descriptor(drones, |desc| desc.id == robot, |desc| desc.xbee_macaddr = macaddr),
+        association::Kind::Fernbedienung =>
+            update_descriptor(drones, |desc| desc.id == robot, |desc| desc.upcore_macaddr = macaddr) ||
+            update_descriptor(pipucks, |desc| desc.id == robot, |desc| desc.rpi_macaddr = macaddr) ||
+            update_descriptor(builderbots, |desc| desc.id == robot, |desc| desc.duovero_macaddr = macaddr),
+    };
+    match adopted {
+        true => Ok(()),
+        false => match device.kind {
+            association::Kind::Xbee => Err(anyhow::anyhow!("Drone {} does not exist", robot)),
+            association::Kind::Fernbedienung => Err(anyhow::anyhow!("Robot {} does not exist", robot)),
+        }
     }
 }
 
 fn associate_xbee_device(
     macaddr: macaddr::MacAddr6,
     drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
-) -> Vec<&drone::Instance> {
+) -> Vec<(&Arc<drone::Descriptor>, &drone::Instance)> {
     drones.into_iter().filter_map(|(desc, instance)| {
         if desc.xbee_macaddr == macaddr {
-            Some(instance)
+            Some((desc, instance))
         }
         else {
             None
@@ -172,10 +1618,10 @@ fn associate_xbee_device(
 fn associate_fernbedienung_device_with_builderbot(
     macaddr: macaddr::MacAddr6,
     pipucks: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
-) -> Vec<&builderbot::Instance> {
+) -> Vec<(&Arc<builderbot::Descriptor>, &builderbot::Instance)> {
     pipucks.into_iter().filter_map(|(desc, instance)| {
         if desc.duovero_macaddr == macaddr {
-            Some(instance)
+            Some((desc, instance))
         }
         else {
             None
@@ -183,13 +1629,37 @@ fn associate_fernbedienung_device_with_builderbot(
     }).collect::<Vec<_>>()
 }
 
+/// Checks a device against the fixed addresses of the robots, a device at the fixed address of a robot must
+/// have the MAC address of that robot and a robot with a fixed address must be found at that address
+fn check_fixed_address(
+    addr: Ipv4Addr,
+    macaddr: macaddr::MacAddr6,
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+) -> Option<association::Failure> {
+    let fixed = builderbots.keys().map(|desc| (&desc.id, desc.duovero_macaddr, desc.duovero_addr))
+        .chain(drones.keys().map(|desc| (&desc.id, desc.upcore_macaddr, desc.upcore_addr)))
+        .chain(pipucks.keys().map(|desc| (&desc.id, desc.rpi_macaddr, desc.rpi_addr)))
+        .filter_map(|(id, fixed_macaddr, fixed_addr)| fixed_addr.map(|fixed_addr| (id, fixed_macaddr, fixed_addr)));
+    for (id, fixed_macaddr, fixed_addr) in fixed {
+        if fixed_addr == addr && fixed_macaddr != macaddr {
+            return Some(association::Failure::UnexpectedDevice { robot: id.clone(), macaddr });
+        }
+        if fixed_addr != addr && fixed_macaddr == macaddr {
+            return Some(association::Failure::UnexpectedAddress { robot: id.clone(), expected: fixed_addr });
+        }
+    }
+    None
+}
+
 fn associate_fernbedienung_device_with_drone(
     macaddr: macaddr::MacAddr6,
     drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
-) -> Vec<&drone::Instance> {
+) -> Vec<(&Arc<drone::Descriptor>, &drone::Instance)> {
     drones.into_iter().filter_map(|(desc, instance)| {
         if desc.upcore_macaddr == macaddr {
-            Some(instance)
+            Some((desc, instance))
         }
         else {
             None
@@ -200,10 +1670,10 @@ fn associate_fernbedienung_device_with_drone(
 fn associate_fernbedienung_device_with_pipuck(
     macaddr: macaddr::MacAddr6,
     pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
-) -> Vec<&pipuck::Instance> {
+) -> Vec<(&Arc<pipuck::Descriptor>, &pipuck::Instance)> {
     pipucks.into_iter().filter_map(|(desc, instance)| {
         if desc.rpi_macaddr == macaddr {
-            Some(instance)
+            Some((desc, instance))
         }
         else {
             None
@@ -211,17 +1681,29 @@ fn associate_fernbedienung_device_with_pipuck(
     }).collect::<Vec<_>>()
 }
 
+/// Stops the journal and the participating robots
 async fn stop_experiment(
-    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
-    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
-    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, &builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, &drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, &pipuck::Instance>,
+    ros_robots: &HashMap<Arc<ros::Descriptor>, &ros::Instance>,
     journal_action_tx: &mpsc::Sender<journal::Action>
 ) -> anyhow::Result<()> {
     let _ = journal_action_tx.send(journal::Action::Stop).await;
+    stop_robots(builderbots, drones, pipucks, ros_robots).await
+}
+
+/// Stops ARGoS on the robots and disables the autonomous mode of the drones
+async fn stop_robots(
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, &builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, &drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, &pipuck::Instance>,
+    ros_robots: &HashMap<Arc<ros::Descriptor>, &ros::Instance>,
+) -> anyhow::Result<()> {
     let builderbot_requests = builderbots
         .iter()
         .map(|(desc, instance)| async move {
-            (desc.id.clone(), instance.action_tx.send(builderbot::Action::StopExperiment).await)
+            (desc.id.clone(), instance.action_tx.try_send(builderbot::Action::StopExperiment))
         })
         .collect::<FuturesUnordered<_>>()
         // do not use try_collect, it aborts before completing all futures
@@ -229,7 +1711,7 @@ async fn stop_experiment(
     let drone_requests = drones
         .iter()
         .map(|(desc, instance)| async move {
-            (desc.id.clone(), instance.action_tx.send(drone::Action::StopExperiment).await)
+            (desc.id.clone(), instance.action_tx.try_send(drone::Action::StopExperiment))
         })
         .collect::<FuturesUnordered<_>>()
         // do not use try_collect, it aborts before completing all futures
@@ -237,13 +1719,28 @@ async fn stop_experiment(
     let pipuck_requests = pipucks
         .iter()
         .map(|(desc, instance)| async move {
-            (desc.id.clone(), instance.action_tx.send(pipuck::Action::StopExperiment).await)
+            (desc.id.clone(), instance.action_tx.try_send(pipuck::Action::StopExperiment))
         })
         .collect::<FuturesUnordered<_>>()
         // do not use try_collect, it aborts before completing all futures
         .collect::<Vec<_>>();
-    let (builderbot_results, drone_results, pipuck_results) =
-        tokio::join!(builderbot_requests, drone_requests, pipuck_requests);
+    /* the launch files of the ROS robots are stopped by a service call whose result is awaited */
+    let ros_requests = ros_robots
+        .iter()
+        .map(|(desc, instance)| async move {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            let result = match instance.action_tx.try_send(ros::Action::StopExperiment(callback_tx)) {
+                Ok(_) => callback_rx.await
+                    .map_err(|_| log::error!("No response from {}", desc.id))
+                    .and_then(|result| result.map_err(|error| log::error!("Could not stop {}: {:#}", desc.id, error))),
+                Err(_) => Err(()),
+            };
+            (desc.id.clone(), result)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let (builderbot_results, drone_results, pipuck_results, ros_results) =
+        tokio::join!(builderbot_requests, drone_requests, pipuck_requests, ros_requests);
     let errors: Vec<String> = builderbot_results
         .into_iter()
         .filter_map(|(id, result)| match result {
@@ -264,6 +1761,13 @@ async fn stop_experiment(
                 Ok(_) => None,
             })
         )
+        .chain(ros_results
+            .into_iter()
+            .filter_map(|(id, result)| match result {
+                Err(_) => Some(id),
+                Ok(_) => None,
+            })
+        )
         .collect::<Vec<_>>();
     match errors.len() {
         0 => Ok(()),
@@ -271,33 +1775,180 @@ async fn stop_experiment(
     }
 }
 
+/// The robots whose safe state is verified after an experiment has been stopped
+struct Stopped {
+    builderbots: Vec<(String, builderbot::Sender)>,
+    drones: Vec<(String, drone::Sender)>,
+    pipucks: Vec<(String, pipuck::Sender)>,
+}
+
+impl Stopped {
+    fn ids(&self) -> impl Iterator<Item = &String> {
+        self.builderbots.iter().map(|(id, _)| id)
+            .chain(self.drones.iter().map(|(id, _)| id))
+            .chain(self.pipucks.iter().map(|(id, _)| id))
+    }
+}
+
+/// Verifies that the robots are in a safe state after an experiment has been stopped, i.e., ARGoS is no
+/// longer running, the autonomous mode of the drones is disabled, and the message router does not forward
+/// messages from the robots anymore. The robots that fail the verification are stopped again up to the given
+/// number of retries, where ARGoS is killed if it is still running, and the robots that still fail are reported
+/// to the operators
+async fn verify_safe_state(
+    stopped: Stopped,
+    retries: usize,
+    router_tx: mpsc::Sender<router::Action>,
+    alerts_tx: mpsc::Sender<alerts::Action>,
+) {
+    if stopped.ids().next().is_none() {
+        return;
+    }
+    let mut failures = BTreeMap::new();
+    for attempt in 0..=retries {
+        tokio::time::sleep(VERIFICATION_DELAY).await;
+        failures = verify(&stopped, &router_tx).await;
+        if failures.is_empty() || attempt == retries {
+            break;
+        }
+        let robots = failures.keys().map(String::as_str).collect::<Vec<_>>();
+        log::warn!("Stopping {} again since they are not in a safe state", robots.join(", "));
+        let failed = |id: &String| failures.contains_key(id);
+        for (_, action_tx) in stopped.builderbots.iter().filter(|(id, _)| failed(id)) {
+            let _ = action_tx.try_send(builderbot::Action::StopExperiment);
+            let _ = execute(action_tx, builderbot::Action::ExecuteFernbedienungAction, FernbedienungAction::KillArgos).await;
+        }
+        for (_, action_tx) in stopped.drones.iter().filter(|(id, _)| failed(id)) {
+            let _ = action_tx.try_send(drone::Action::StopExperiment);
+            let _ = execute(action_tx, drone::Action::ExecuteFernbedienungAction, FernbedienungAction::KillArgos).await;
+        }
+        for (_, action_tx) in stopped.pipucks.iter().filter(|(id, _)| failed(id)) {
+            let _ = action_tx.try_send(pipuck::Action::StopExperiment);
+            let _ = execute(action_tx, pipuck::Action::ExecuteFernbedienungAction, FernbedienungAction::KillArgos).await;
+        }
+    }
+    if failures.is_empty() {
+        log::info!("Verified that the stopped robots are in a safe state");
+    }
+    for (robot, reasons) in failures {
+        let message = format!("{} is not in a safe state after the experiment was stopped: {}", robot, reasons.join(", "));
+        let notification = Notification { robot, severity: Severity::Critical, message };
+        if alerts_tx.send(alerts::Action::Notify(notification)).await.is_err() {
+            log::error!("Could not communicate with alerts");
+        }
+    }
+}
+
+/// Checks the safe state of the stopped robots and returns the reasons why each robot failed the verification
+async fn verify(stopped: &Stopped, router_tx: &mpsc::Sender<router::Action>) -> BTreeMap<String, Vec<String>> {
+    let builderbot_checks = stopped.builderbots.iter()
+        .map(|(id, action_tx)| async move {
+            (id, execute(action_tx, builderbot::Action::ExecuteFernbedienungAction, FernbedienungAction::VerifyStopped).await)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let drone_checks = stopped.drones.iter()
+        .map(|(id, action_tx)| async move {
+            (id, execute(action_tx, drone::Action::ExecuteFernbedienungAction, FernbedienungAction::VerifyStopped).await)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let autonomous_mode_checks = stopped.drones.iter()
+        .map(|(id, action_tx)| async move {
+            (id, execute(action_tx, drone::Action::ExecuteXbeeAction, XbeeAction::VerifyAutonomousModeDisabled).await)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let pipuck_checks = stopped.pipucks.iter()
+        .map(|(id, action_tx)| async move {
+            (id, execute(action_tx, pipuck::Action::ExecuteFernbedienungAction, FernbedienungAction::VerifyStopped).await)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let (builderbot_results, drone_results, autonomous_mode_results, pipuck_results, traffic) = tokio::join!(
+        builderbot_checks, drone_checks, autonomous_mode_checks, pipuck_checks, router_traffic(router_tx));
+    let mut failures: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let results = builderbot_results.into_iter()
+        .chain(drone_results)
+        .chain(autonomous_mode_results)
+        .chain(pipuck_results);
+    for (id, result) in results {
+        if let Err(error) = result {
+            failures.entry(id.clone()).or_default().push(format!("{:#}", error));
+        }
+    }
+    match traffic {
+        Ok(senders) => for id in stopped.ids().filter(|id| senders.contains(*id)) {
+            failures.entry(id.clone()).or_default().push("The message router still forwards its messages".to_owned());
+        },
+        Err(error) => log::warn!("Could not verify the traffic of the stopped robots: {:#}", error),
+    }
+    failures
+}
+
+/// The robots from which the message router forwarded messages during the traffic window
+async fn router_traffic(router_tx: &mpsc::Sender<router::Action>) -> anyhow::Result<BTreeSet<String>> {
+    let statistics = || async {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        router_tx.send(router::Action::GetStatistics(callback_tx)).await
+            .map_err(|_| anyhow::anyhow!("Could not communicate with message router"))?;
+        callback_rx.await
+            .map_err(|_| anyhow::anyhow!("No response from message router"))
+    };
+    let before = statistics().await?;
+    tokio::time::sleep(VERIFICATION_TRAFFIC_WINDOW).await;
+    let after = statistics().await?;
+    let forwarded = |statistics: &shared::router::Statistics, addr| statistics.links.iter()
+        .filter(|link| link.from.ip() == addr)
+        .map(|link| link.messages)
+        .sum::<u64>();
+    Ok(after.routes.iter()
+        .filter(|route| forwarded(&after, route.addr) > forwarded(&before, route.addr))
+        .map(|route| route.robot.clone())
+        .collect())
+}
+
 async fn start_experiment(
-    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
-    builderbot_software: &Software,
-    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
-    drone_software: &Software,
-    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
-    pipuck_software: &Software,
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, &builderbot::Instance>,
+    builderbot_setup: RobotSetup<'_>,
+    drones: &HashMap<Arc<drone::Descriptor>, &drone::Instance>,
+    drone_setup: RobotSetup<'_>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, &pipuck::Instance>,
+    pipuck_setup: RobotSetup<'_>,
+    ros_robots: &HashMap<Arc<ros::Descriptor>, &ros::Instance>,
+    overrides: &BTreeMap<String, Software>,
+    partial_start: PartialStart,
+    decimator: &Decimator,
+    mut fingerprint: Fingerprint,
+    deployments: Vec<Option<String>>,
+    checklist: &[ChecklistItem],
+    smoke_tests: &BTreeMap<String, SmokeTest>,
+    experiment: &Experiment,
     journal_requests_tx: &mpsc::Sender<journal::Action>
-) -> anyhow::Result<()> {
-    /* check software validity before starting */
-    if builderbots.len() > 0 {
-        builderbot_software.check_config()?;
+) -> anyhow::Result<Vec<String>> {
+    /* the participating robots whose software has been overridden */
+    let overrides = builderbots.keys().map(|desc| &desc.id)
+        .chain(drones.keys().map(|desc| &desc.id))
+        .chain(pipucks.keys().map(|desc| &desc.id))
+        .filter_map(|id| overrides.get(id).map(|software| (id, software)))
+        .collect::<BTreeMap<_, _>>();
+    let builderbot_defaults = builderbots.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+    let drone_defaults = drones.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+    let pipuck_defaults = pipucks.keys().filter(|desc| !overrides.contains_key(&desc.id)).count();
+    /* check the ARGoS configuration and the syntax of the Lua scripts before touching any robot */
+    if builderbot_defaults > 0 {
+        syntax::check(builderbot_setup.software).await.context("Invalid BuilderBot software")?;
     }
-    if drones.len() > 0 {
-        drone_software.check_config()?;
+    if drone_defaults > 0 {
+        syntax::check(drone_setup.software).await.context("Invalid drone software")?;
     }
-    if pipucks.len() > 0 {
-        pipuck_software.check_config()?;
+    if pipuck_defaults > 0 {
+        syntax::check(pipuck_setup.software).await.context("Invalid Pi-Puck software")?;
     }
-    /* start an experiment journal to record events during the experiment */
-    let (callback_tx, callback_rx) = oneshot::channel();
-    journal_requests_tx
-        .send(journal::Action::Start(callback_tx)).await
-        .map_err(|_| anyhow::anyhow!("Could not start journal"))?;
-    callback_rx.await
-        .map_err(|_| anyhow::anyhow!("No response from journal"))??;
-    /* send all descriptors */
+    for (id, software) in overrides.iter() {
+        syntax::check(software).await.context(format!("Invalid software for {}", id))?;
+    }
+    /* the descriptors of the participating robots */
     let builderbot_descriptors = builderbots
         .keys()
         .map(|desc| builderbot::Descriptor::clone(desc))
@@ -310,107 +1961,752 @@ async fn start_experiment(
         .keys()
         .map(|desc| pipuck::Descriptor::clone(desc))
         .collect::<Vec<_>>();
-    let descriptor_event = journal::Event::Descriptors(builderbot_descriptors, drone_descriptors, pipuck_descriptors);
-    journal_requests_tx.send(journal::Action::Record(descriptor_event)).await
-        .map_err(|_| anyhow::anyhow!("Could not send robot descriptors to journal"))?;
+    /* complete the fingerprint with the participating robots and their software */
+    let mut robots = builderbot_descriptors.iter().map(|desc| format!("{:?}\n", desc))
+        .chain(drone_descriptors.iter().map(|desc| format!("{:?}\n", desc)))
+        .chain(pipuck_descriptors.iter().map(|desc| format!("{:?}\n", desc)))
+        .chain(ros_robots.keys().map(|desc| format!("{:?}\n", desc)))
+        .collect::<Vec<_>>();
+    robots.sort();
+    fingerprint.add("Robots", robots.concat());
+    let software = [
+        ("BuilderBot", builderbot_setup.software, builderbot_defaults),
+        ("Drone", drone_setup.software, drone_defaults),
+        ("Pi-Puck", pipuck_setup.software, pipuck_defaults),
+    ];
+    let overridden = overrides.iter()
+        .map(|(id, software)| (id.as_str(), *software, 1));
+    let mut checksums = Vec::new();
+    for (robot, software, count) in software.iter().cloned().chain(overridden) {
+        if count > 0 {
+            fingerprint.add(format!("{} software", robot), software.summary());
+            let seed = software.random_seed()?.unwrap_or_default();
+            fingerprint.add(format!("{} random seed", robot), seed);
+            checksums.extend(software.checksums().into_iter()
+                .map(|(filename, checksum)| (robot.to_owned(), filename, format!("{:x}", checksum))));
+        }
+    }
+    /* start an experiment journal to record events during the experiment */
+    let header = journal::Header::new(builderbot_descriptors, drone_descriptors, pipuck_descriptors, checksums);
+    let (callback_tx, callback_rx) = oneshot::channel();
+    journal_requests_tx
+        .send(journal::Action::Start(header, callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not start journal"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from journal"))??;
+    /* artifacts are only collected from robot types with at least one pattern */
+    let artifacts = |patterns: &[String]| match patterns.len() {
+        0 => None,
+        _ => Some(Artifacts { patterns: patterns.to_vec() }),
+    };
+    journal_requests_tx.send(journal::Action::Record(journal::Event::Fingerprint(fingerprint))).await
+        .map_err(|_| anyhow::anyhow!("Could not send fingerprint to journal"))?;
+    /* record who acknowledged the items of the checklist and when */
+    for item in checklist {
+        if let Some(acknowledgement) = item.acknowledgement.clone() {
+            let event = journal::Event::Checklist(item.description.clone(), acknowledgement);
+            journal_requests_tx.send(journal::Action::Record(event)).await
+                .map_err(|_| anyhow::anyhow!("Could not send checklist to journal"))?;
+        }
+    }
+    /* record the last smoke test of the participating robots */
+    let participants = builderbots.keys().map(|desc| &desc.id)
+        .chain(drones.keys().map(|desc| &desc.id))
+        .chain(pipucks.keys().map(|desc| &desc.id));
+    for id in participants {
+        if let Some(smoke_test) = smoke_tests.get(id) {
+            let event = journal::Event::SmokeTest(id.clone(), smoke_test.clone());
+            journal_requests_tx.send(journal::Action::Record(event)).await
+                .map_err(|_| anyhow::anyhow!("Could not send smoke test to journal"))?;
+        }
+    }
+    /* tie the run to the commits from which the software was deployed */
+    for deployment in deployments.into_iter().flatten() {
+        log::info!("{}", deployment);
+        record_phase(journal_requests_tx, &deployment).await;
+    }
+    record_phase(journal_requests_tx, "Setting up robots").await;
+    /* the robots that could not be set up or started and why */
+    let mut failures = BTreeMap::new();
+    let count = builderbots.len() + drones.len() + pipucks.len() + ros_robots.len();
     /* set up the experiment on the builderbots */
-    builderbots.iter()
+    let results = builderbots.iter()
         .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = builderbot::Action::SetupExperiment(
                 callback_tx, 
                 desc.id.clone(),
-                builderbot_software.clone(),
-                journal_requests_tx.clone()
+                overrides.get(&desc.id).map_or(builderbot_setup.software, |software| *software).clone(),
+                journal_requests_tx.clone(),
+                artifacts(&builderbot_setup.settings.artifacts),
+                builderbot_setup.settings.environment.clone(),
+                decimator.clone()
             );
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to BuilderBot"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from BuilderBot"))?
+                set_status_leds(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction, StatusLeds::Setup);
+                experiment.readiness(&desc.id, Readiness::Uploading);
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to BuilderBot"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from BuilderBot"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Ready,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                let status = if result.is_ok() { StatusLeds::Ready } else { StatusLeds::Error };
+                set_status_leds(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction, status);
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
     /* set up the experiment on the pi-pucks */
-    pipucks.iter()
+    let results = pipucks.iter()
         .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = pipuck::Action::SetupExperiment(
                 callback_tx,
                 desc.id.clone(),
-                pipuck_software.clone(),
-                journal_requests_tx.clone()
+                overrides.get(&desc.id).map_or(pipuck_setup.software, |software| *software).clone(),
+                journal_requests_tx.clone(),
+                artifacts(&pipuck_setup.settings.artifacts),
+                pipuck_setup.settings.environment.clone(),
+                decimator.clone()
             );
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to Pi-Puck"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from Pi-Puck"))?
+                set_status_leds(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction, StatusLeds::Setup);
+                experiment.readiness(&desc.id, Readiness::Uploading);
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to Pi-Puck"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from Pi-Puck"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Ready,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                let status = if result.is_ok() { StatusLeds::Ready } else { StatusLeds::Error };
+                set_status_leds(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction, status);
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
     /* set up the experiment on the drones */
-    drones.iter()
+    let results = drones.iter()
         .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = drone::Action::SetupExperiment(
                 callback_tx, 
                 desc.id.clone(),
-                drone_software.clone(),
-                journal_requests_tx.clone()
+                overrides.get(&desc.id).map_or(drone_setup.software, |software| *software).clone(),
+                journal_requests_tx.clone(),
+                artifacts(&drone_setup.settings.artifacts),
+                drone_setup.settings.environment.clone(),
+                decimator.clone()
             );
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to drone"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from drone"))?
+                set_status_leds(&instance.action_tx, drone::Action::ExecuteFernbedienungAction, StatusLeds::Setup);
+                experiment.readiness(&desc.id, Readiness::Uploading);
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to drone"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from drone"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Ready,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                let status = if result.is_ok() { StatusLeds::Ready } else { StatusLeds::Error };
+                set_status_leds(&instance.action_tx, drone::Action::ExecuteFernbedienungAction, status);
+                (desc.id.clone(), result)
+            }
+        })
+        .collect::<FuturesUnordered<_>>()
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
+    /* the launch files of the ROS robots are already on the robots, they only need to be connected */
+    let results = ros_robots.iter()
+        .map(|(desc, instance)| {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            let action = ros::Action::SetupExperiment(callback_tx);
+            async move {
+                experiment.readiness(&desc.id, Readiness::Uploading);
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to ROS robot"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from ROS robot"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Ready,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
+    /* all robots that could be set up are ready */
+    experiment.transition(State::Ready);
+    record_phase(journal_requests_tx, "Starting robots").await;
     /* start the pipucks */
-    pipucks.iter()
-        .map(|(_, instance)| {
+    let results = pipucks.iter()
+        /* the robots that could not be set up are not started */
+        .filter(|(desc, _)| !failures.contains_key(&desc.id))
+        .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = pipuck::Action::StartExperiment(callback_tx);
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to Pi-Puck"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from Pi-Puck"))?
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to Pi-Puck"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from Pi-Puck"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Running,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
     /* start the builderbots */
-    builderbots.iter()
-        .map(|(_, instance)| {
+    let results = builderbots.iter()
+        .filter(|(desc, _)| !failures.contains_key(&desc.id))
+        .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = builderbot::Action::StartExperiment(callback_tx);
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to BuilderBot"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from BuilderBot"))?
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to BuilderBot"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from BuilderBot"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Running,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
     /* start the drones */
-    drones.iter()
-        .map(|(_, instance)| {
+    let results = drones.iter()
+        .filter(|(desc, _)| !failures.contains_key(&desc.id))
+        .map(|(desc, instance)| {
             let (callback_tx, callback_rx) = oneshot::channel();
             let action = drone::Action::StartExperiment(callback_tx);
             async move {
-                instance.action_tx.send(action).await
-                    .map_err(|_| anyhow::anyhow!("Could not send action to drone"))?;
-                callback_rx.await
-                    .map_err(|_| anyhow::anyhow!("No response from drone"))?
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to drone"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from drone"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Running,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                (desc.id.clone(), result)
+            }
+        })
+        .collect::<FuturesUnordered<_>>()
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
+    /* start the launch files of the ROS robots */
+    let results = ros_robots.iter()
+        .filter(|(desc, _)| !failures.contains_key(&desc.id))
+        .map(|(desc, instance)| {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            let action = ros::Action::StartExperiment(callback_tx);
+            async move {
+                let result = async {
+                    instance.action_tx.try_send(action)
+                        .map_err(|_| anyhow::anyhow!("Could not send action to ROS robot"))?;
+                    callback_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from ROS robot"))?
+                }.await;
+                experiment.readiness(&desc.id, match &result {
+                    Ok(_) => Readiness::Running,
+                    Err(error) => Readiness::Failed(format!("{:#}", error)),
+                });
+                (desc.id.clone(), result)
             }
         })
         .collect::<FuturesUnordered<_>>()
-        .try_collect::<Vec<_>>().await?;
-    Ok(())
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>().await;
+    check_partial_start(&mut failures, results, partial_start, count)?;
+    /* record which robots actually participate, i.e., without those that could not be set up or started */
+    let mut started = builderbots.keys().map(|desc| &desc.id)
+        .chain(drones.keys().map(|desc| &desc.id))
+        .chain(pipucks.keys().map(|desc| &desc.id))
+        .chain(ros_robots.keys().map(|desc| &desc.id))
+        .filter(|id| !failures.contains_key(*id))
+        .cloned()
+        .collect::<Vec<_>>();
+    started.sort();
+    if !failures.is_empty() {
+        let failed = failures.keys().map(String::as_str).collect::<Vec<_>>();
+        let description = format!("Continuing without {}", failed.join(", "));
+        record_timeline(journal_requests_tx, TimelineCategory::Safety, &description, &[]).await;
+    }
+    journal_requests_tx.send(journal::Action::Record(journal::Event::Participants(started.clone()))).await
+        .map_err(|_| anyhow::anyhow!("Could not send participants to journal"))?;
+    record_phase(journal_requests_tx, "Experiment started").await;
+    Ok(started)
+}
+
+/// Adds the robots for which setting up or starting the experiment failed to the failures, returning an error
+/// if the experiment must be rolled back. The experiment only continues after a failure if the operator chose
+/// so and at least one of the participating robots remains
+fn check_partial_start(
+    failures: &mut BTreeMap<String, String>,
+    results: Vec<(String, anyhow::Result<()>)>,
+    partial_start: PartialStart,
+    participants: usize,
+) -> anyhow::Result<()> {
+    for (id, result) in results {
+        if let Err(error) = result {
+            log::error!("Could not start the experiment on {}: {:#}", id, error);
+            failures.insert(id, format!("{:#}", error));
+        }
+    }
+    match (partial_start, failures.len()) {
+        (_, 0) => Ok(()),
+        (PartialStart::Continue, failed) if failed < participants => Ok(()),
+        _ => {
+            let failures = failures.iter()
+                .map(|(id, error)| format!("{}: {}", id, error))
+                .collect::<Vec<_>>();
+            Err(anyhow::anyhow!("Could not start the experiment on {}", failures.join("; ")))
+        }
+    }
+}
+
+/// The fingerprint of a run, which includes the decimation of the output since it changes what is journaled
+fn run_fingerprint(settings: &Fingerprint, choices: &Settings) -> Fingerprint {
+    let mut fingerprint = settings.clone();
+    if let Some(decimation) = &choices.decimation {
+        fingerprint.add("Decimation", format!("{:?}", decimation));
+    }
+    fingerprint
+}
+
+/// Selects the robots whose descriptors satisfy the predicate, e.g., the members of a group
+fn select<'a, D: Eq + Hash, I>(
+    robots: &'a HashMap<Arc<D>, I>,
+    selected: impl Fn(&D) -> bool,
+) -> HashMap<Arc<D>, &'a I> {
+    robots.iter()
+        .filter(|(desc, _)| selected(desc))
+        .map(|(desc, instance)| (desc.clone(), instance))
+        .collect()
+}
+
+/// Selects a subset of robots that have already been selected, e.g., the participants that could not be started
+fn select_subset<'a, D: Eq + Hash, I>(
+    robots: &HashMap<Arc<D>, &'a I>,
+    selected: impl Fn(&D) -> bool,
+) -> HashMap<Arc<D>, &'a I> {
+    robots.iter()
+        .filter(|(desc, _)| selected(desc))
+        .map(|(desc, instance)| (desc.clone(), *instance))
+        .collect()
+}
+
+/// Executes an action on all members of a group at once
+async fn group_action(
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+    members: &[String],
+    action: group::Action,
+) -> anyhow::Result<()> {
+    let fernbedienung_action = || match &action {
+        group::Action::Identify => Ok(FernbedienungAction::Identify),
+        group::Action::Halt => Ok(FernbedienungAction::Halt),
+        group::Action::Reboot => Ok(FernbedienungAction::Reboot),
+        group::Action::CameraStreamEnable(enable) => Ok(FernbedienungAction::SetCameraStream(*enable)),
+        group::Action::StartExperiment { .. } | group::Action::StopExperiment =>
+            Err(anyhow::anyhow!("Experiments are not started or stopped on individual robots")),
+    };
+    /* check that the action can be executed before sending it to any robot */
+    fernbedienung_action()?;
+    let builderbot_requests = builderbots
+        .iter()
+        .filter(|(desc, _)| members.contains(&desc.id))
+        .map(|(desc, instance)| async move {
+            let action = fernbedienung_action()?;
+            execute(&instance.action_tx, builderbot::Action::ExecuteFernbedienungAction, action).await
+                .context(desc.id.clone())
+        })
+        .collect::<FuturesUnordered<_>>()
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>();
+    let drone_requests = drones
+        .iter()
+        .filter(|(desc, _)| members.contains(&desc.id))
+        .map(|(desc, instance)| async move {
+            let action = fernbedienung_action()?;
+            execute(&instance.action_tx, drone::Action::ExecuteFernbedienungAction, action).await
+                .context(desc.id.clone())
+        })
+        .collect::<FuturesUnordered<_>>()
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>();
+    let pipuck_requests = pipucks
+        .iter()
+        .filter(|(desc, _)| members.contains(&desc.id))
+        .map(|(desc, instance)| async move {
+            let action = fernbedienung_action()?;
+            execute(&instance.action_tx, pipuck::Action::ExecuteFernbedienungAction, action).await
+                .context(desc.id.clone())
+        })
+        .collect::<FuturesUnordered<_>>()
+        // do not use try_collect, it aborts before completing all futures
+        .collect::<Vec<_>>();
+    let (builderbot_results, drone_results, pipuck_results) =
+        tokio::join!(builderbot_requests, drone_requests, pipuck_requests);
+    let errors = builderbot_results.into_iter()
+        .chain(drone_results)
+        .chain(pipuck_results)
+        .filter_map(|result| result.err())
+        .map(|error| format!("{:#}", error))
+        .collect::<Vec<_>>();
+    match errors.len() {
+        0 => Ok(()),
+        _ => Err(anyhow::anyhow!("Could not execute action on: {}", errors.join(", ")))
+    }
+}
+
+/// Hands a device to the pairing task if the robot refused it because it is associated with another device
+/// that claims the same MAC address, e.g., after the SD card of the robot was cloned. The robot is not
+/// waited for since it may be busy, e.g., while an experiment is being set up
+fn quarantine<D: Send + 'static>(
+    callback: oneshot::Receiver<Result<(), D>>,
+    id: &str,
+    macaddr: macaddr::MacAddr6,
+    pairing_tx: &mpsc::Sender<pairing::Action>,
+    action: fn(D, macaddr::MacAddr6, Option<String>) -> pairing::Action,
+) {
+    let id = id.to_owned();
+    let pairing_tx = pairing_tx.clone();
+    crate::instrument::spawn(format!("arena/quarantine/{}", macaddr), async move {
+        if let Ok(Err(device)) = callback.await {
+            log::error!("Another device with the MAC address {} of {} was found, quarantining it", macaddr, id);
+            let _ = pairing_tx.send(action(device, macaddr, Some(id))).await;
+        }
+    });
+}
+
+/// Sets the status LEDs of a robot without waiting for the result, since the LEDs are only an indication
+fn set_status_leds<A>(
+    action_tx: &Outbound<A>,
+    action: fn(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction) -> A,
+    status: StatusLeds
+) {
+    let (callback_tx, _) = oneshot::channel();
+    let _ = action_tx.try_send(action(callback_tx, FernbedienungAction::SetStatusLeds(status)));
+}
+
+/// Suspends or resumes the camera streams of all robots that are not exempt. Only the streams that were
+/// enabled when they were suspended are resumed
+fn suspend_camera_streams(
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, &builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, &drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, &pipuck::Instance>,
+    exempt: &[String],
+    suspend: bool,
+) {
+    let included = |id: &String| !exempt.contains(id);
+    for (_, instance) in builderbots.iter().filter(|(desc, _)| included(&desc.id)) {
+        let (callback_tx, _) = oneshot::channel();
+        let action = FernbedienungAction::SuspendCameraStream(suspend);
+        let _ = instance.action_tx.try_send(builderbot::Action::ExecuteFernbedienungAction(callback_tx, action));
+    }
+    for (_, instance) in drones.iter().filter(|(desc, _)| included(&desc.id)) {
+        let (callback_tx, _) = oneshot::channel();
+        let action = FernbedienungAction::SuspendCameraStream(suspend);
+        let _ = instance.action_tx.try_send(drone::Action::ExecuteFernbedienungAction(callback_tx, action));
+    }
+    for (_, instance) in pipucks.iter().filter(|(desc, _)| included(&desc.id)) {
+        let (callback_tx, _) = oneshot::channel();
+        let action = FernbedienungAction::SuspendCameraStream(suspend);
+        let _ = instance.action_tx.try_send(pipuck::Action::ExecuteFernbedienungAction(callback_tx, action));
+    }
+}
+
+/// Runs the smoke test on a robot and returns the checks that were made
+async fn smoke_test<A>(
+    action_tx: &Outbound<A>,
+    action: fn(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction) -> A,
+) -> anyhow::Result<Vec<SmokeTestCheck>> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let (checks_tx, checks_rx) = oneshot::channel();
+    action_tx.try_send(action(callback_tx, FernbedienungAction::SmokeTest(checks_tx)))
+        .map_err(|_| anyhow::anyhow!("Could not send action to robot"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from robot"))??;
+    checks_rx.await
+        .map_err(|_| anyhow::anyhow!("No checks were reported"))
+}
+
+/// The robots whose throughput is measured in the background, by their type
+enum Tester {
+    BuilderBot(builderbot::Sender),
+    Drone(drone::Sender),
+    PiPuck(pipuck::Sender),
+}
+
+impl Tester {
+    /// Runs the bandwidth test on the robot and returns the throughput in each direction as checks
+    async fn run(&self) -> anyhow::Result<Vec<SmokeTestCheck>> {
+        match self {
+            Tester::BuilderBot(action_tx) => bandwidth_test(action_tx, builderbot::Action::ExecuteFernbedienungAction).await,
+            Tester::Drone(action_tx) => bandwidth_test(action_tx, drone::Action::ExecuteFernbedienungAction).await,
+            Tester::PiPuck(action_tx) => bandwidth_test(action_tx, pipuck::Action::ExecuteFernbedienungAction).await,
+        }
+    }
+}
+
+/// Runs the bandwidth test on a robot and returns the checks that were made
+async fn bandwidth_test<A>(
+    action_tx: &Outbound<A>,
+    action: fn(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction) -> A,
+) -> anyhow::Result<Vec<SmokeTestCheck>> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let (checks_tx, checks_rx) = oneshot::channel();
+    action_tx.try_send(action(callback_tx, FernbedienungAction::BandwidthTest(checks_tx)))
+        .map_err(|_| anyhow::anyhow!("Could not send action to robot"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from robot"))??;
+    checks_rx.await
+        .map_err(|_| anyhow::anyhow!("No throughput was reported"))
+}
+
+/// Executes an action on a robot and waits for the result
+async fn execute<A, B>(
+    action_tx: &Outbound<A>,
+    action: fn(oneshot::Sender<anyhow::Result<()>>, B) -> A,
+    inner: B,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    action_tx.try_send(action(callback_tx, inner))
+        .map_err(|_| anyhow::anyhow!("Could not send action to robot"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from robot"))?
+}
+
+/// Subscribes to the updates of the robots of one type and returns the current state of each robot
+async fn subscribe<D, U, A>(
+    robots: Vec<(Arc<D>, Outbound<A>)>,
+    action: fn(oneshot::Sender<(Vec<U>, broadcast::Receiver<U>)>) -> A,
+) -> anyhow::Result<(Vec<(D, Vec<U>)>, StreamMap<Arc<D>, BroadcastStream<U>>)>
+where D: Clone + Eq + Hash, U: 'static + Clone + Send {
+    let subscriptions = robots.into_iter()
+        .map(|(desc, action_tx)| async move {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            action_tx.try_send(action(callback_tx))
+                .map_err(|_| anyhow::anyhow!("Could not send action to robot"))?;
+            let (state, updates) = callback_rx.await
+                .map_err(|_| anyhow::anyhow!("No response from robot"))?;
+            Ok::<_, anyhow::Error>((desc, state, updates))
+        })
+        .collect::<FuturesUnordered<_>>()
+        .try_collect::<Vec<_>>()
+        .await?;
+    let mut state = Vec::new();
+    let mut updates = StreamMap::new();
+    for (desc, robot_state, robot_updates) in subscriptions {
+        state.push((desc.deref().clone(), robot_state));
+        updates.insert(desc, BroadcastStream::new(robot_updates));
+    }
+    Ok((state, updates))
+}
+
+/// The last smoke test of each robot, robots that have not been tested yet map to None
+fn smoke_tests_update(
+    builderbots: &HashMap<Arc<builderbot::Descriptor>, builderbot::Instance>,
+    drones: &HashMap<Arc<drone::Descriptor>, drone::Instance>,
+    pipucks: &HashMap<Arc<pipuck::Descriptor>, pipuck::Instance>,
+    smoke_tests: &BTreeMap<String, SmokeTest>,
+) -> BTreeMap<String, Option<SmokeTest>> {
+    builderbots.keys().map(|desc| &desc.id)
+        .chain(drones.keys().map(|desc| &desc.id))
+        .chain(pipucks.keys().map(|desc| &desc.id))
+        .map(|id| (id.clone(), smoke_tests.get(id).cloned()))
+        .collect()
+}
+
+/// Returns the software for a robot type from its source. Software is only built from a repository or loaded
+/// from the library if the robot type participates in the experiment, in which case a description of the
+/// deployment is returned
+async fn software(
+    repositories: &mut repository::Cache,
+    library_tx: &mpsc::Sender<library::Action>,
+    robot: &str,
+    source: Source,
+    count: usize,
+) -> anyhow::Result<(Software, Option<String>)> {
+    match source {
+        Source::Files(software) => Ok((software, None)),
+        Source::Repository(_) | Source::Library(_) if count == 0 => Ok((Software::default(), None)),
+        Source::Library(reference) => {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            library_tx.send(library::Action::Load(callback_tx, reference.clone())).await
+                .map_err(|_| anyhow::anyhow!("Could not send action to library task"))?;
+            let software = callback_rx.await
+                .map_err(|_| anyhow::anyhow!("No response from library task"))?
+                .context(format!("Could not deploy {} software from the library", robot))?;
+            let deployment = format!("{} software deployed from {} in the library", robot, reference);
+            Ok((software, Some(deployment)))
+        },
+        Source::Repository(repository) => {
+            let (commit, software) = repositories.checkout(&repository).await
+                .context(format!("Could not deploy {} software from {}", robot, repository.url))?;
+            let deployment = format!("{} software deployed from {} at {} (directory \"{}\")",
+                robot, repository.url, commit, repository.directory);
+            Ok((software, Some(deployment)))
+        }
+    }
+}
+
+async fn record_phase(journal_requests_tx: &mpsc::Sender<journal::Action>, phase: &str) {
+    record_timeline(journal_requests_tx, TimelineCategory::Phase, phase, &[]).await;
+}
+
+/// Records an event on the timeline of the journal, the tags mark events that are of interest for the
+/// analysis, e.g., `battery` for critical battery levels
+async fn record_timeline(
+    journal_requests_tx: &mpsc::Sender<journal::Action>,
+    category: TimelineCategory,
+    description: &str,
+    tags: &[&str],
+) {
+    let tags = tags.iter().map(|&tag| tag.to_owned()).collect();
+    let event = journal::Event::Timeline(category, description.to_owned(), tags);
+    let _ = journal_requests_tx.send(journal::Action::Record(event)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    struct Robot {
+        id: &'static str,
+        macaddr: u8,
+        /* an identifier that can change without restarting the robot, e.g., of its rigid body */
+        marker: u8,
+    }
+
+    fn robot(id: &'static str, macaddr: u8, marker: u8) -> Robot {
+        Robot { id, macaddr, marker }
+    }
+
+    /* the instances are numbered so that it can be checked whether a robot was restarted */
+    fn robots(descs: &[Robot]) -> HashMap<Arc<Robot>, usize> {
+        descs.iter()
+            .cloned()
+            .enumerate()
+            .map(|(instance, desc)| (Arc::new(desc), instance))
+            .collect()
+    }
+
+    fn changes(robots: &HashMap<Arc<Robot>, usize>, updates: Vec<Robot>) -> Changes<Robot> {
+        Changes::new(robots, updates, |desc| desc.id.to_owned(), |desc, update| desc.macaddr == update.macaddr)
+    }
+
+    /* applies the changes and returns the instances that were shut down, new instances are numbered from 100 */
+    fn apply(changes: Changes<Robot>, robots: &mut HashMap<Arc<Robot>, usize>) -> Vec<usize> {
+        let shutdown = RefCell::new(Vec::new());
+        let mut created = 100;
+        changes.apply(robots, |instance| shutdown.borrow_mut().push(instance), |_| {
+            created += 1;
+            created - 1
+        });
+        let mut shutdown = shutdown.into_inner();
+        shutdown.sort();
+        shutdown
+    }
+
+    fn find(robots: &HashMap<Arc<Robot>, usize>, id: &str) -> Option<(Robot, usize)> {
+        robots.iter()
+            .find(|(desc, _)| desc.id == id)
+            .map(|(desc, instance)| (desc.as_ref().clone(), *instance))
+    }
+
+    #[test]
+    fn unchanged_robots_are_kept() {
+        let mut current = robots(&[robot("a", 1, 1), robot("b", 2, 2)]);
+        let changes = changes(&current, vec![robot("b", 2, 2), robot("a", 1, 1)]);
+        assert!(changes.is_empty());
+        assert_eq!(changes.kept.len(), 2);
+        assert!(apply(changes, &mut current).is_empty());
+        assert_eq!(find(&current, "a"), Some((robot("a", 1, 1), 0)));
+        assert_eq!(find(&current, "b"), Some((robot("b", 2, 2), 1)));
+    }
+
+    #[test]
+    fn robots_with_the_same_devices_are_updated_in_place() {
+        let mut current = robots(&[robot("a", 1, 1)]);
+        let changes = changes(&current, vec![robot("a", 1, 5)]);
+        assert!(!changes.is_empty());
+        assert_eq!(changes.kept.len(), 1);
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+        assert!(apply(changes, &mut current).is_empty());
+        assert_eq!(current.len(), 1);
+        assert_eq!(find(&current, "a"), Some((robot("a", 1, 5), 0)));
+    }
+
+    #[test]
+    fn robots_with_other_devices_are_replaced() {
+        let mut current = robots(&[robot("a", 1, 1), robot("b", 2, 2)]);
+        let changes = changes(&current, vec![robot("a", 3, 1), robot("b", 2, 2)]);
+        assert!(!changes.is_empty());
+        assert_eq!(changes.removed.iter().map(|desc| desc.as_ref().clone()).collect::<Vec<_>>(), vec![robot("a", 1, 1)]);
+        assert_eq!(changes.added, vec![robot("a", 3, 1)]);
+        assert_eq!(apply(changes, &mut current), vec![0]);
+        assert_eq!(current.len(), 2);
+        assert_eq!(find(&current, "a"), Some((robot("a", 3, 1), 100)));
+        assert_eq!(find(&current, "b"), Some((robot("b", 2, 2), 1)));
+    }
+
+    #[test]
+    fn robots_are_added_and_removed() {
+        let mut current = robots(&[robot("a", 1, 1), robot("b", 2, 2)]);
+        let changes = changes(&current, vec![robot("b", 2, 2), robot("c", 3, 3), robot("d", 4, 4)]);
+        assert!(!changes.is_empty());
+        assert_eq!(changes.removed.iter().map(|desc| desc.as_ref().clone()).collect::<Vec<_>>(), vec![robot("a", 1, 1)]);
+        assert_eq!(changes.added, vec![robot("c", 3, 3), robot("d", 4, 4)]);
+        assert_eq!(apply(changes, &mut current), vec![0]);
+        assert_eq!(current.len(), 3);
+        assert_eq!(find(&current, "a"), None);
+        assert_eq!(find(&current, "b"), Some((robot("b", 2, 2), 1)));
+        assert_eq!(find(&current, "c"), Some((robot("c", 3, 3), 100)));
+        assert_eq!(find(&current, "d"), Some((robot("d", 4, 4), 101)));
+    }
 }
@@ -0,0 +1,298 @@
+use std::{path::{Path, PathBuf}, time::{Duration, SystemTime}};
+use anyhow::Context;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use shared::configuration::{Configuration, ProbeConfiguration, Update};
+
+use crate::arena;
+
+pub enum Action {
+    /* note that upon subscribing, the current configuration is sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Update>>),
+    Update(oneshot::Sender<anyhow::Result<()>>, Configuration),
+    Save(oneshot::Sender<anyhow::Result<()>>),
+    /* read the configuration file again and add or remove robots accordingly */
+    Reload(oneshot::Sender<anyhow::Result<()>>),
+}
+
+/* the interval at which the modification time of the configuration file is checked when watching it */
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// This function represents the main task of the configuration module. It holds the configuration that the
+/// supervisor was started with and the configuration as edited from the web interface. Changes to the
+/// optitrack and apriltag identifiers of existing robots are applied immediately via the arena, all
+/// other changes only take effect once the configuration has been saved and the supervisor restarted. The
+/// exception are the robots themselves, which are added or removed when the configuration file is reloaded,
+/// either on request or, if the file is watched, whenever it is modified. Robots can not be reloaded while
+/// a journal is being replayed since the robots are then taken from the journal
+pub async fn new(
+    path: PathBuf,
+    mut startup: Configuration,
+    watch: bool,
+    replay: bool,
+    arena_tx: mpsc::Sender<arena::Action>,
+    mut requests: mpsc::Receiver<Action>
+) {
+    let mut current = startup.clone();
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    let mut modified = modified_time(&path).await;
+    loop {
+        let action = tokio::select! {
+            action = requests.recv() => match action {
+                Some(action) => action,
+                None => break,
+            },
+            _ = interval.tick(), if watch && !replay => {
+                let last_modified = modified_time(&path).await;
+                if last_modified == modified {
+                    continue;
+                }
+                modified = last_modified;
+                log::info!("Configuration file {:?} was modified, reloading robots", path);
+                let (callback_tx, _) = oneshot::channel();
+                Action::Reload(callback_tx)
+            }
+        };
+        match action {
+            Action::Subscribe(callback) => {
+                let _ = callback.send(updates_tx.subscribe());
+                let _ = updates_tx.send(Update::Configuration {
+                    configuration: current.clone(),
+                    restart_required: restart_required(&startup, &current),
+                });
+            },
+            Action::Update(callback, configuration) => {
+                let result = async {
+                    /* validate the configuration by parsing it in the same way as the configuration file */
+                    crate::parse_config_str(&configuration.to_xml())?;
+                    let (result_tx, result_rx) = oneshot::channel();
+                    let action = arena::Action::UpdateIdentifiers {
+                        callback: result_tx,
+                        builderbots: configuration.builderbots.clone(),
+                        drones: configuration.drones.clone(),
+                        pipucks: configuration.pipucks.clone(),
+                    };
+                    arena_tx.send(action).await
+                        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+                    result_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from arena"))?;
+                    /* pausing the discovery of robots during experiments does not require a restart */
+                    arena_tx.send(arena::Action::SetDiscoveryPause(configuration.probing.pause_discovery)).await
+                        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+                    anyhow::Result::<()>::Ok(())
+                };
+                let result = result.await.context("Invalid configuration");
+                if result.is_ok() {
+                    current = configuration;
+                    let _ = updates_tx.send(Update::Configuration {
+                        configuration: current.clone(),
+                        restart_required: restart_required(&startup, &current),
+                    });
+                }
+                let _ = callback.send(result);
+            },
+            Action::Save(callback) => {
+                /* write to a temporary file first so that the configuration is never left half written */
+                let temp_path = path.with_extension("xml.tmp");
+                let result = async {
+                    tokio::fs::write(&temp_path, current.to_xml()).await?;
+                    tokio::fs::rename(&temp_path, &path).await
+                };
+                let result = result.await
+                    .context(format!("Could not write configuration to {:?}", path));
+                if result.is_ok() {
+                    log::info!("Configuration written to {:?}", path);
+                    /* do not reload the configuration that was just written */
+                    modified = modified_time(&path).await;
+                    let _ = updates_tx.send(Update::Saved(path.to_string_lossy().into_owned()));
+                }
+                let _ = callback.send(result);
+            },
+            Action::Reload(callback) => {
+                let result = async {
+                    if replay {
+                        anyhow::bail!("Robots can not be reloaded while replaying a journal");
+                    }
+                    let config = tokio::fs::read_to_string(&path).await
+                        .context(format!("Could not read configuration file {:?}", path))?;
+                    let reloaded = crate::parse_config_str(&config)
+                        .context(format!("Could not parse configuration file {:?}", path))?
+                        .export();
+                    let (result_tx, result_rx) = oneshot::channel();
+                    let action = arena::Action::ReloadRobots {
+                        callback: result_tx,
+                        builderbots: reloaded.builderbots.clone(),
+                        drones: reloaded.drones.clone(),
+                        pipucks: reloaded.pipucks.clone(),
+                    };
+                    arena_tx.send(action).await
+                        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+                    result_rx.await
+                        .map_err(|_| anyhow::anyhow!("No response from arena"))??;
+                    anyhow::Result::<_>::Ok(reloaded)
+                };
+                let result = match result.await {
+                    Ok(reloaded) => {
+                        /* the robots are now running as configured, i.e., they do not require a restart. Only
+                           the robots are taken from the file so that the other changes made from the web
+                           interface are kept until they are saved */
+                        startup.builderbots = reloaded.builderbots.clone();
+                        startup.drones = reloaded.drones.clone();
+                        startup.pipucks = reloaded.pipucks.clone();
+                        current.builderbots = reloaded.builderbots;
+                        current.drones = reloaded.drones;
+                        current.pipucks = reloaded.pipucks;
+                        let _ = updates_tx.send(Update::Configuration {
+                            configuration: current.clone(),
+                            restart_required: restart_required(&startup, &current),
+                        });
+                        Ok(())
+                    },
+                    Err(error) => {
+                        log::error!("Could not reload robots: {:#}", error);
+                        Err(error)
+                    }
+                };
+                let _ = callback.send(result);
+            }
+        }
+    }
+}
+
+/* the modification time of the configuration file, if available */
+async fn modified_time(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn restart_required(startup: &Configuration, current: &Configuration) -> Vec<String> {
+    let mut changes = Vec::new();
+    if startup.router_socket != current.router_socket {
+        changes.push(String::from("Message router socket"));
+    }
+    if startup.router_topology != current.router_topology {
+        changes.push(String::from("Message router topology"));
+    }
+    if startup.router_range != current.router_range {
+        changes.push(String::from("Message router range"));
+    }
+    if startup.webui_socket != current.webui_socket {
+        changes.push(String::from("Web interface socket"));
+    }
+    if startup.webui_advertise != current.webui_advertise {
+        changes.push(String::from("Web interface advertisement"));
+    }
+    if startup.grpc_socket != current.grpc_socket {
+        changes.push(String::from("gRPC socket"));
+    }
+    if startup.optitrack != current.optitrack {
+        changes.push(String::from("Optitrack"));
+    }
+    if startup.infrastructure != current.infrastructure {
+        changes.push(String::from("Infrastructure monitoring"));
+    }
+    if startup.ambient != current.ambient {
+        changes.push(String::from("Ambient sensors"));
+    }
+    if startup.robot_network != current.robot_network {
+        changes.push(String::from("Robot network"));
+    }
+    /* pausing the discovery of robots during experiments is applied while the supervisor is running */
+    let probing = ProbeConfiguration { pause_discovery: current.probing.pause_discovery, ..startup.probing };
+    if probing != current.probing {
+        changes.push(String::from("Probing"));
+    }
+    if startup.tls != current.tls {
+        changes.push(String::from("TLS"));
+    }
+    if startup.journal_format != current.journal_format {
+        changes.push(String::from("Journal format"));
+    }
+    if startup.journal_key != current.journal_key {
+        changes.push(String::from("Journal key"));
+    }
+    if startup.library != current.library {
+        changes.push(String::from("Library"));
+    }
+    if startup.telemetry != current.telemetry {
+        changes.push(String::from("Telemetry"));
+    }
+    if startup.checklist != current.checklist {
+        changes.push(String::from("Checklist"));
+    }
+    if startup.camera_shutdown != current.camera_shutdown {
+        changes.push(String::from("Camera shutdown"));
+    }
+    if startup.polling != current.polling {
+        changes.push(String::from("Polling"));
+    }
+    if startup.heartbeat != current.heartbeat {
+        changes.push(String::from("Heartbeat"));
+    }
+    if startup.targeted_probing != current.targeted_probing {
+        changes.push(String::from("Targeted probing"));
+    }
+    if startup.return_to_base != current.return_to_base {
+        changes.push(String::from("Return to base"));
+    }
+    if startup.geofence != current.geofence {
+        changes.push(String::from("Geofence"));
+    }
+    if startup.battery_alerts != current.battery_alerts {
+        changes.push(String::from("Battery alerts"));
+    }
+    if startup.replication != current.replication {
+        changes.push(String::from("Replication"));
+    }
+    if startup.access != current.access {
+        changes.push(String::from("Access"));
+    }
+    if startup.simulated_robots != current.simulated_robots {
+        changes.push(String::from("Simulated robots"));
+    }
+    if startup.ros_robots != current.ros_robots {
+        changes.push(String::from("ROS robots"));
+    }
+    if startup.groups != current.groups {
+        changes.push(String::from("Groups"));
+    }
+    if startup.limits != current.limits {
+        changes.push(String::from("Resource limits"));
+    }
+    if startup.artifacts != current.artifacts {
+        changes.push(String::from("Artifact patterns"));
+    }
+    if startup.environment != current.environment {
+        changes.push(String::from("Environment variables"));
+    }
+    if startup.watchdogs != current.watchdogs {
+        changes.push(String::from("Watchdogs"));
+    }
+    if startup.xbee_profile != current.xbee_profile {
+        changes.push(String::from("Xbee profile"));
+    }
+    if startup.xbee_configurations != current.xbee_configurations {
+        changes.push(String::from("Xbee configurations"));
+    }
+    /* the identifiers of existing robots are applied immediately, ignore them here */
+    let builderbots = |configuration: &Configuration| configuration.builderbots.iter()
+        .map(|desc| (desc.id.clone(), desc.duovero_macaddr))
+        .collect::<Vec<_>>();
+    if builderbots(startup) != builderbots(current) {
+        changes.push(String::from("BuilderBots"));
+    }
+    let drones = |configuration: &Configuration| configuration.drones.iter()
+        .map(|desc| (desc.id.clone(), desc.xbee_macaddr, desc.upcore_macaddr, desc.xbee_configuration.clone()))
+        .collect::<Vec<_>>();
+    if drones(startup) != drones(current) {
+        changes.push(String::from("Drones"));
+    }
+    let pipucks = |configuration: &Configuration| configuration.pipucks.iter()
+        .map(|desc| (desc.id.clone(), desc.rpi_macaddr))
+        .collect::<Vec<_>>();
+    if pipucks(startup) != pipucks(current) {
+        changes.push(String::from("Pi-Pucks"));
+    }
+    changes
+}
@@ -0,0 +1,293 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use aes_gcm::aead::{Aead, NewAead, Payload};
+use anyhow::Context;
+
+/// The bytes at the start of an encrypted file, which distinguish it from the formats of the journal
+const MAGIC: &[u8] = b"SUPERVISOR-AES256GCM\n";
+/* the amount of plaintext that is collected before it is encrypted and written as a segment */
+const SEGMENT_SIZE: usize = 65536;
+const NONCE_SIZE: usize = 12;
+const KEY_SIZE: usize = 32;
+/* each segment starts with the length of its ciphertext, whether it is the final segment, and its nonce */
+const HEADER_SIZE: usize = 4 + 1 + NONCE_SIZE;
+
+/// The key with which the journal is encrypted at rest (AES-256-GCM)
+#[derive(Clone)]
+pub struct Cipher(Aes256Gcm);
+
+impl Cipher {
+    pub fn new(key: &[u8]) -> anyhow::Result<Cipher> {
+        if key.len() != KEY_SIZE {
+            anyhow::bail!("The key must be {} bytes long, not {}", KEY_SIZE, key.len());
+        }
+        Ok(Cipher(Aes256Gcm::new(Key::from_slice(key))))
+    }
+
+    /// Loads the key from a file that contains the key encoded in base64, such a file can be generated with
+    /// `head -c 32 /dev/urandom | base64 > journal.key`
+    pub fn load(path: &Path) -> anyhow::Result<Cipher> {
+        let contents = std::fs::read_to_string(path)
+            .context(format!("Could not read {}", path.display()))?;
+        let key = base64::decode(contents.trim())
+            .context(format!("Could not decode the key in {}", path.display()))?;
+        Cipher::new(&key)
+            .context(format!("Could not load the key in {}", path.display()))
+    }
+
+    /// Decrypts the contents of a file that was written by an `Encryptor`. Since the index of each segment
+    /// and whether it is the final segment are authenticated, segments that were removed, reordered, or
+    /// copied from another file are rejected. A file that ends before its final segment, e.g., because the
+    /// supervisor was terminated, is decrypted up to its last complete segment
+    pub fn decrypt(&self, contents: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut remaining = contents.strip_prefix(MAGIC)
+            .ok_or(anyhow::anyhow!("The contents are not encrypted"))?;
+        let mut plaintext = Vec::new();
+        let mut index = 0u64;
+        loop {
+            if remaining.len() < HEADER_SIZE {
+                log::warn!("An encrypted file ends before its final segment, it may have been truncated");
+                break;
+            }
+            let (header, rest) = remaining.split_at(HEADER_SIZE);
+            let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+            let last = match header[4] {
+                0 => false,
+                1 => true,
+                _ => anyhow::bail!("Segment {} is malformed", index),
+            };
+            if rest.len() < length {
+                log::warn!("Ignoring the incomplete last segment of an encrypted file");
+                break;
+            }
+            let (ciphertext, rest) = rest.split_at(length);
+            let payload = Payload { msg: ciphertext, aad: &associated_data(index, last) };
+            let segment = self.0.decrypt(Nonce::from_slice(&header[5..]), payload)
+                .map_err(|_| anyhow::anyhow!("Could not decrypt segment {}, the key may be wrong or the file was modified", index))?;
+            plaintext.extend_from_slice(&segment);
+            remaining = rest;
+            index += 1;
+            if last {
+                if !remaining.is_empty() {
+                    anyhow::bail!("The contents continue after the final segment");
+                }
+                break;
+            }
+        }
+        Ok(plaintext)
+    }
+}
+
+/* the index of a segment and whether it is the final segment are authenticated with each segment */
+fn associated_data(index: u64, last: bool) -> [u8; 9] {
+    let mut data = [0; 9];
+    data[..8].copy_from_slice(&index.to_be_bytes());
+    data[8] = last as u8;
+    data
+}
+
+/// Whether the contents of a file were written by an `Encryptor`
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(MAGIC)
+}
+
+/// Decrypts the contents of a file of the journal if they are encrypted, otherwise they are returned unchanged
+pub fn reveal(path: &Path, contents: Vec<u8>, cipher: Option<&Cipher>) -> anyhow::Result<Vec<u8>> {
+    match (is_encrypted(&contents), cipher) {
+        (false, _) => Ok(contents),
+        (true, Some(cipher)) => cipher.decrypt(&contents)
+            .context(format!("Could not decrypt {}", path.display())),
+        (true, None) => anyhow::bail!("{} is encrypted but no key is configured for the journal", path.display()),
+    }
+}
+
+/// Creates a file that is encrypted at rest if a key has been configured, this is how every file that is
+/// written next to the journal is created
+pub fn open(path: &Path, cipher: Option<&Cipher>) -> anyhow::Result<Box<dyn Write + Send>> {
+    let file = File::create(path)
+        .context(format!("Could not create {}", path.display()))?;
+    Ok(match cipher {
+        Some(cipher) => Box::new(Encryptor::new(file, cipher.clone())
+            .context(format!("Could not write to {}", path.display()))?),
+        None => Box::new(file),
+    })
+}
+
+/// Writes a file at once, the contents are encrypted if a key has been configured
+pub fn write(path: &Path, contents: &[u8], cipher: Option<&Cipher>) -> anyhow::Result<()> {
+    let file = File::create(path)
+        .context(format!("Could not create {}", path.display()))?;
+    let result = match cipher {
+        Some(cipher) => Encryptor::new(file, cipher.clone())
+            .and_then(|mut encryptor| {
+                encryptor.write_all(contents)?;
+                encryptor.finish()
+            }),
+        None => (&file).write_all(contents),
+    };
+    result.context(format!("Could not write to {}", path.display()))
+}
+
+/// Encrypts the data that is written to it in segments, each of which is preceded by its length, whether
+/// it is the final segment, and its nonce. The data is only written to the inner writer once a segment is
+/// complete or when the encryptor is flushed. The final segment, which may be empty, is written when the
+/// encryptor is finished or dropped
+pub struct Encryptor<W: Write> {
+    inner: W,
+    cipher: Cipher,
+    buffer: Vec<u8>,
+    index: u64,
+    finished: bool,
+}
+
+impl<W: Write> Encryptor<W> {
+    pub fn new(mut inner: W, cipher: Cipher) -> io::Result<Self> {
+        inner.write_all(MAGIC)?;
+        Ok(Encryptor { inner, cipher, buffer: Vec::with_capacity(SEGMENT_SIZE), index: 0, finished: false })
+    }
+
+    /// Writes the final segment, after which no more data can be written
+    pub fn finish(&mut self) -> io::Result<()> {
+        if !self.finished {
+            self.seal(true)?;
+            self.finished = true;
+        }
+        self.inner.flush()
+    }
+
+    fn seal(&mut self, last: bool) -> io::Result<()> {
+        if self.finished {
+            return match self.buffer.is_empty() {
+                true => Ok(()),
+                false => Err(io::Error::new(io::ErrorKind::Other, "The final segment has already been written")),
+            };
+        }
+        if self.buffer.is_empty() && !last {
+            return Ok(());
+        }
+        /* the nonce is random since the key is reused across the journals of many experiments */
+        let nonce: [u8; NONCE_SIZE] = rand::random();
+        let payload = Payload { msg: self.buffer.as_slice(), aad: &associated_data(self.index, last) };
+        let ciphertext = self.cipher.0.encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Could not encrypt segment"))?;
+        self.inner.write_all(&(ciphertext.len() as u32).to_be_bytes())?;
+        self.inner.write_all(&[last as u8])?;
+        self.inner.write_all(&nonce)?;
+        self.inner.write_all(&ciphertext)?;
+        self.buffer.clear();
+        self.index += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Encryptor<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        /* each complete segment holds exactly SEGMENT_SIZE bytes of plaintext */
+        let length = data.len().min(SEGMENT_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&data[..length]);
+        if self.buffer.len() == SEGMENT_SIZE {
+            self.seal(false)?;
+        }
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.seal(false)?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for Encryptor<W> {
+    fn drop(&mut self) {
+        if let Err(error) = self.finish() {
+            log::error!("Could not write the last segment of an encrypted file: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> Cipher {
+        Cipher::new(&[7; KEY_SIZE]).unwrap()
+    }
+
+    /* more than two segments of entries, written in chunks as the journal writes its entries */
+    fn entries() -> Vec<u8> {
+        (0..20000u32).flat_map(|entry| format!("entry {}\n", entry).into_bytes()).collect()
+    }
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let mut contents = Vec::new();
+        let mut encryptor = Encryptor::new(&mut contents, cipher()).unwrap();
+        for chunk in plaintext.chunks(1000) {
+            encryptor.write_all(chunk).unwrap();
+        }
+        drop(encryptor);
+        contents
+    }
+
+    #[test]
+    fn round_trip() {
+        let plaintext = entries();
+        let contents = encrypt(&plaintext);
+        assert!(is_encrypted(&contents));
+        assert_eq!(cipher().decrypt(&contents).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn round_trip_through_file() {
+        let path = std::env::temp_dir().join(format!("supervisor-encryption-{}.pkl", std::process::id()));
+        let plaintext = entries();
+        let mut file = open(&path, Some(&cipher())).unwrap();
+        file.write_all(&plaintext).unwrap();
+        drop(file);
+        let contents = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reveal(&path, contents, Some(&cipher())).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn truncated_last_segment() {
+        let plaintext = entries();
+        let mut contents = encrypt(&plaintext);
+        /* only the complete segments before the truncated segment are decrypted */
+        contents.truncate(contents.len() - 10);
+        let complete = plaintext.len() / SEGMENT_SIZE * SEGMENT_SIZE;
+        assert_eq!(cipher().decrypt(&contents).unwrap(), &plaintext[..complete]);
+    }
+
+    #[test]
+    fn missing_final_segment() {
+        let plaintext = entries();
+        let mut contents = Vec::new();
+        let mut encryptor = Encryptor::new(&mut contents, cipher()).unwrap();
+        encryptor.write_all(&plaintext[..SEGMENT_SIZE]).unwrap();
+        /* a file whose final segment is missing is decrypted up to the last complete segment */
+        let decrypted = cipher().decrypt(&encryptor.inner[..]).unwrap();
+        assert_eq!(decrypted, &plaintext[..SEGMENT_SIZE]);
+    }
+
+    #[test]
+    fn reordered_segments() {
+        let plaintext = entries();
+        let contents = encrypt(&plaintext);
+        let first = MAGIC.len();
+        let second = first + HEADER_SIZE + SEGMENT_SIZE + 16;
+        let third = second + HEADER_SIZE + SEGMENT_SIZE + 16;
+        let mut reordered = contents[..first].to_vec();
+        reordered.extend_from_slice(&contents[second..third]);
+        reordered.extend_from_slice(&contents[first..second]);
+        reordered.extend_from_slice(&contents[third..]);
+        assert!(cipher().decrypt(&reordered).is_err());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let contents = encrypt(&entries());
+        assert!(Cipher::new(&[8; KEY_SIZE]).unwrap().decrypt(&contents).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+use futures::StreamExt;
+use shared::configuration::Geofence;
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
+
+use crate::{arena, optitrack};
+
+/// Checks the positions from the tracking system against the boundary of the arena. The arena is informed
+/// when a rigid body leaves the boundary and decides whether to intervene. A rigid body is only reported
+/// again after it has returned inside the boundary
+pub async fn new(
+    geofence: Geofence,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    arena_tx: mpsc::Sender<arena::Action>,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    optitrack_tx.send(optitrack::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with the optitrack task"))?;
+    let frames = callback_rx.await
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates"))?;
+    let mut frames = BroadcastStream::new(frames);
+    /* the rigid bodies that are currently outside of the boundary */
+    let mut outside = HashSet::new();
+    while let Some(frame) = frames.next().await {
+        match frame {
+            Ok((_, updates, _)) => for update in updates {
+                if geofence.contains(&update.position) {
+                    outside.remove(&update.id);
+                }
+                else if outside.insert(update.id) {
+                    let action = arena::Action::GeofenceViolation {
+                        optitrack_id: update.id,
+                        position: update.position,
+                    };
+                    arena_tx.send(action).await
+                        .map_err(|_| anyhow::anyhow!("Could not communicate with the arena"))?;
+                }
+            },
+            Err(BroadcastStreamRecvError::Lagged(count)) =>
+                log::warn!("Geofence missed {} frames from the tracking system", count),
+        }
+    }
+    Ok(())
+}
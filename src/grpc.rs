@@ -0,0 +1,238 @@
+use std::{collections::BTreeSet, net::SocketAddr};
+use anyhow::Context;
+use futures::StreamExt;
+use shared::{BackEndRequest, FrontEndRequest, access::Access, experiment, subscription::Capability};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream, errors::BroadcastStreamRecvError};
+use uuid::Uuid;
+
+use crate::{arena, instrument, journal, optitrack, trace, webui};
+
+mod proto {
+    tonic::include_proto!("supervisor");
+}
+
+use proto::supervisor_server::{Supervisor, SupervisorServer};
+
+/* the number of updates that are buffered for a subscriber that does not keep up */
+const SUBSCRIPTION_CAPACITY: usize = 256;
+
+type Update = Result<proto::FrontEndRequest, tonic::Status>;
+
+struct Service {
+    components: webui::Components,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    access: Option<Access>,
+}
+
+#[tonic::async_trait]
+impl Supervisor for Service {
+    async fn request(
+        &self,
+        request: tonic::Request<proto::BackEndRequest>,
+    ) -> Result<tonic::Response<proto::Response>, tonic::Status> {
+        /* the token is passed as metadata since the requests are not made over a persistent connection */
+        let token = request.metadata().get("token")
+            .map(|token| token.to_str()
+                .map_err(|_| tonic::Status::unauthenticated("The token must only contain ASCII characters")))
+            .transpose()?;
+        let client = webui::authenticate(self.access.as_ref(), token);
+        let request = serde_json::from_str::<BackEndRequest>(&request.get_ref().json)
+            .map_err(|error| tonic::Status::invalid_argument(format!("Could not decode request: {}", error)))?;
+        if let Some(category) = request.category().filter(|category| !client.permissions.contains(category)) {
+            return Err(tonic::Status::permission_denied(format!("The role \"{}\" is not permitted to make {} requests",
+                client.role.name(), category.name())));
+        }
+        let uuid = Uuid::new_v4();
+        let trace = trace::Handle::new(uuid, self.components.trace_tx.clone());
+        if !matches!(request, BackEndRequest::TraceRequest(_)) {
+            trace::begin(&trace, request.summary());
+        }
+        let result = trace::scope(trace.clone(), webui::dispatch(&self.components, request)).await;
+        match result {
+            Ok(update) => {
+                trace::record(&trace, "grpc", "Responded");
+                let update = update.as_ref().map(encode).transpose()?;
+                Ok(tonic::Response::new(proto::Response { update }))
+            },
+            Err(error) => {
+                log::warn!("Error processing request {}: {}", uuid, error);
+                trace::record(&trace, "grpc", format!("Responded with error: {}", error));
+                Err(tonic::Status::unknown(format!("{:#}", error)))
+            }
+        }
+    }
+
+    type SubscribeStream = ReceiverStream<Update>;
+
+    async fn subscribe(
+        &self,
+        request: tonic::Request<proto::Subscription>,
+    ) -> Result<tonic::Response<Self::SubscribeStream>, tonic::Status> {
+        let capabilities = request.get_ref().capabilities.iter()
+            .map(|&capability| proto::Capability::from_i32(capability)
+                .map(|capability| match capability {
+                    proto::Capability::Telemetry => Capability::Telemetry,
+                    proto::Capability::Terminals => Capability::Terminals,
+                    proto::Capability::Cameras => Capability::Cameras,
+                    proto::Capability::Tracking => Capability::Tracking,
+                    proto::Capability::Log => Capability::Log,
+                })
+                .ok_or_else(|| tonic::Status::invalid_argument(format!("Unknown capability {}", capability))))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        let (updates_tx, updates_rx) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+        let components = self.components.clone();
+        let optitrack_tx = self.optitrack_tx.clone();
+        instrument::spawn("grpc/subscription", async move {
+            if let Err(error) = forward(components, optitrack_tx, capabilities, &updates_tx).await {
+                log::warn!("Could not forward updates to gRPC client: {:#}", error);
+                let _ = updates_tx.send(Err(tonic::Status::unavailable(format!("{:#}", error)))).await;
+            }
+        });
+        Ok(tonic::Response::new(ReceiverStream::new(updates_rx)))
+    }
+}
+
+fn encode(update: &FrontEndRequest) -> Result<proto::FrontEndRequest, tonic::Status> {
+    serde_json::to_string(update)
+        .map(|json| proto::FrontEndRequest { json })
+        .map_err(|error| tonic::Status::internal(format!("Could not encode update: {}", error)))
+}
+
+/// Forwards a snapshot of the robots and the experiment followed by the updates for the given capabilities to
+/// a client until it disconnects. The updates about the experiment itself are always forwarded
+async fn forward(
+    components: webui::Components,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    capabilities: BTreeSet<Capability>,
+    updates_tx: &mpsc::Sender<Update>,
+) -> anyhow::Result<()> {
+    let summaries = webui::summaries(&components.inventory_tx).await;
+    let (callback_tx, callback_rx) = oneshot::channel();
+    components.arena_tx.send(arena::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))?;
+    let arena::Subscription { mut snapshot, builderbots, drones, pipucks, checklist, smoke_tests, status, mut roster } =
+        callback_rx.await.map_err(|_| anyhow::anyhow!("Could not subscribe to arena updates"))??;
+    webui::append_summaries(&mut snapshot, &summaries);
+    let (callback_tx, callback_rx) = oneshot::channel();
+    optitrack_tx.send(optitrack::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with tracking system"))?;
+    let tracking = callback_rx.await
+        .context("Could not subscribe to tracking system updates")?;
+    let (callback_tx, callback_rx) = oneshot::channel();
+    components.journal_tx.send(journal::Action::SubscribeLog(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with journal"))?;
+    let log_entries = callback_rx.await
+        .context("Could not subscribe to journal log")?;
+    let mut checklist = BroadcastStream::new(checklist);
+    let mut smoke_tests = BroadcastStream::new(smoke_tests);
+    let mut status = BroadcastStream::new(status);
+    let mut tracking = BroadcastStream::new(tracking);
+    let mut log_entries = BroadcastStream::new(log_entries);
+    tokio::pin!(builderbots);
+    tokio::pin!(drones);
+    tokio::pin!(pipucks);
+    if updates_tx.send(encode(&FrontEndRequest::Snapshot(snapshot))).await.is_err() {
+        return Ok(());
+    }
+    loop {
+        let update = tokio::select! {
+            Some((desc, update)) = builderbots.next() => match update {
+                Ok(update) if capabilities.contains(&update.capability()) =>
+                    FrontEndRequest::UpdateBuilderBot(desc.id.clone(), update),
+                Ok(_) => continue,
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} messages for {}", count, desc);
+                    continue;
+                }
+            },
+            Some((desc, update)) = drones.next() => match update {
+                Ok(update) if capabilities.contains(&update.capability()) =>
+                    FrontEndRequest::UpdateDrone(desc.id.clone(), update),
+                Ok(_) => continue,
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} messages for {}", count, desc);
+                    continue;
+                }
+            },
+            Some((desc, update)) = pipucks.next() => match update {
+                Ok(update) if capabilities.contains(&update.capability()) =>
+                    FrontEndRequest::UpdatePiPuck(desc.id.clone(), update),
+                Ok(_) => continue,
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} messages for {}", count, desc);
+                    continue;
+                }
+            },
+            Some(item) = checklist.next() => match item {
+                Ok(checklist) => FrontEndRequest::UpdateExperiment(experiment::Update::Checklist(checklist)),
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} checklist messages", count);
+                    continue;
+                }
+            },
+            Some(item) = smoke_tests.next() => match item {
+                Ok(smoke_tests) => FrontEndRequest::UpdateExperiment(experiment::Update::SmokeTests(smoke_tests)),
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} smoke test messages", count);
+                    continue;
+                }
+            },
+            Some(item) = status.next() => match item {
+                Ok(status) => FrontEndRequest::UpdateExperiment(experiment::Update::Status(status)),
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} experiment status messages", count);
+                    continue;
+                }
+            },
+            /* the updates of the tracking system and the log are only forwarded if subscribed to */
+            Some(item) = tracking.next() => match item {
+                Ok((_, update, mut timestamps)) if capabilities.contains(&Capability::Tracking) => {
+                    timestamps.sent = optitrack::timestamp();
+                    FrontEndRequest::UpdateTrackingSystem(update, timestamps)
+                },
+                Ok(_) => continue,
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} tracking system messages", count);
+                    continue;
+                }
+            },
+            Some(item) = log_entries.next() => match item {
+                Ok(entry) if capabilities.contains(&Capability::Log) =>
+                    FrontEndRequest::UpdateExperiment(experiment::Update::Log(entry)),
+                Ok(_) => continue,
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("gRPC client missed {} log messages", count);
+                    continue;
+                }
+            },
+            /* the updates of added robots are only forwarded to new subscriptions */
+            Ok(_) = roster.recv() =>
+                break Err(anyhow::anyhow!("Robots have been added or removed, please subscribe again")),
+            /* the client has disconnected */
+            _ = updates_tx.closed() => break Ok(()),
+            else => break Ok(()),
+        };
+        if updates_tx.send(encode(&update)).await.is_err() {
+            break Ok(());
+        }
+    }
+}
+
+/// Runs the gRPC interface, which mirrors the requests and the updates of the web interface so that
+/// experiments can be scripted. Requests are authenticated with the token in their metadata in the same
+/// way as the clients of the web interface
+pub async fn new(
+    socket: SocketAddr,
+    components: webui::Components,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    access: Option<Access>,
+) -> anyhow::Result<()> {
+    let service = Service { components, optitrack_tx, access };
+    log::info!("Serving the gRPC interface on {}", socket);
+    tonic::transport::Server::builder()
+        .add_service(SupervisorServer::new(service))
+        .serve(socket)
+        .await
+        .context("Could not serve the gRPC interface")
+}
@@ -0,0 +1,85 @@
+use std::{collections::BTreeMap, panic::AssertUnwindSafe, time::Duration};
+use futures::{future::BoxFuture, FutureExt};
+use shared::health::{Status, Subsystem};
+use tokio::{sync::{broadcast, mpsc, oneshot}, time::Instant};
+
+/* the delay before a failed subsystem is restarted, which doubles with each consecutive failure */
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAXIMUM_BACKOFF: Duration = Duration::from_secs(60);
+/* a subsystem that has run for this long before failing is restarted after the initial delay again */
+const STABLE_PERIOD: Duration = Duration::from_secs(300);
+
+pub enum Action {
+    /* the current health is returned together with the receiver for the later updates */
+    Subscribe(oneshot::Sender<(Vec<Subsystem>, broadcast::Receiver<Vec<Subsystem>>)>),
+    /* the name and the status of a subsystem and whether it was restarted */
+    Report(&'static str, Status, bool),
+}
+
+/// Keeps the health of the subsystems, which is sent to the subscribers whenever a subsystem starts, fails,
+/// or stops
+pub async fn new(mut requests: mpsc::Receiver<Action>) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut subsystems: BTreeMap<&'static str, Subsystem> = BTreeMap::new();
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Subscribe(callback) => {
+                let _ = callback.send((subsystems.values().cloned().collect(), updates_tx.subscribe()));
+            },
+            Action::Report(name, status, restart) => {
+                let subsystem = subsystems.entry(name).or_insert_with(|| Subsystem {
+                    name: name.to_owned(),
+                    status: Status::Running,
+                    restarts: 0,
+                });
+                subsystem.status = status;
+                if restart {
+                    subsystem.restarts += 1;
+                }
+                let _ = updates_tx.send(subsystems.values().cloned().collect());
+            },
+        }
+    }
+}
+
+/// Runs a subsystem and restarts it with an exponential backoff whenever it returns an error or panics. The
+/// state, e.g., the receiver for the requests to the subsystem, is kept across restarts so that the senders
+/// that were handed out to the other subsystems remain valid. The health of the subsystem is reported to the
+/// health task. Returns once the subsystem completes
+pub async fn supervise<S, F>(name: &'static str, health_tx: mpsc::Sender<Action>, mut state: S, mut task: F) -> anyhow::Result<()>
+    where F: for<'a> FnMut(&'a mut S) -> BoxFuture<'a, anyhow::Result<()>> {
+    let report = |status: Status, restart: bool| {
+        let health_tx = health_tx.clone();
+        async move {
+            let _ = health_tx.send(Action::Report(name, status, restart)).await;
+        }
+    };
+    let mut backoff = INITIAL_BACKOFF;
+    let mut restart = false;
+    loop {
+        report(Status::Running, std::mem::replace(&mut restart, true)).await;
+        let started = Instant::now();
+        let error = match AssertUnwindSafe(task(&mut state)).catch_unwind().await {
+            Ok(Ok(_)) => {
+                log::info!("Subsystem {} completed", name);
+                report(Status::Stopped, false).await;
+                break Ok(());
+            },
+            Ok(Err(error)) => format!("{:#}", error),
+            Err(panic) => match panic.downcast_ref::<&str>() {
+                Some(message) => format!("Panicked: {}", message),
+                None => match panic.downcast_ref::<String>() {
+                    Some(message) => format!("Panicked: {}", message),
+                    None => String::from("Panicked"),
+                }
+            },
+        };
+        if started.elapsed() > STABLE_PERIOD {
+            backoff = INITIAL_BACKOFF;
+        }
+        log::error!("Subsystem {} failed, restarting in {}s: {}", name, backoff.as_secs(), error);
+        report(Status::Restarting { error, delay: backoff.as_secs() }, false).await;
+        tokio::time::sleep(backoff).await;
+        backoff = std::cmp::min(backoff * 2, MAXIMUM_BACKOFF);
+    }
+}
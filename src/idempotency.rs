@@ -0,0 +1,141 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+use shared::FrontEndRequest;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+/* the duration for which the outcome of a request is kept, retries that arrive later are executed again */
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// The outcome of a request, i.e., its response and the update with which it was answered, if any
+pub type Outcome = (Result<(), String>, Option<FrontEndRequest>);
+
+pub enum Action {
+    /* claims a request by its identifier, duplicates of a request that is still being processed, e.g., a retry
+       from another connection after a timeout, are answered once the original request has been completed */
+    Claim(Uuid, oneshot::Sender<Claim>),
+    /* completes a request with its outcome, or releases it without an outcome if it was abandoned */
+    Complete(Uuid, Option<Outcome>),
+}
+
+/// Whether a request is new or a duplicate of a request that has already been received
+pub enum Claim {
+    /// The request must be processed and its outcome completed with the given ticket
+    New(Ticket),
+    /// The request is a duplicate and the outcome of the original request is to be sent instead
+    Duplicate(Outcome),
+}
+
+/// The ticket of a request that is being processed. If the ticket is dropped without being completed,
+/// e.g., because the connection was closed, the request is released and may be processed again
+pub struct Ticket {
+    id: Uuid,
+    idempotency_tx: Option<mpsc::Sender<Action>>,
+}
+
+impl Ticket {
+    /// Records the outcome of the request. Only successful outcomes are kept for the duplicates that are
+    /// received within the window, a request that failed is executed again when it is retried
+    pub async fn complete(mut self, outcome: Outcome) {
+        if let Some(idempotency_tx) = self.idempotency_tx.take() {
+            let _ = idempotency_tx.send(Action::Complete(self.id, Some(outcome))).await;
+        }
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        if let Some(idempotency_tx) = self.idempotency_tx.take() {
+            let id = self.id;
+            crate::instrument::spawn("idempotency/release", async move {
+                let _ = idempotency_tx.send(Action::Complete(id, None)).await;
+            });
+        }
+    }
+}
+
+enum State {
+    /* the request is being processed, the duplicates that were received in the meantime wait for its outcome */
+    Pending(Vec<oneshot::Sender<Claim>>),
+    /* the instant at which the request was completed and its outcome */
+    Completed(Instant, Outcome),
+}
+
+/* hands a request to a claimant, returns false if the claimant is no longer waiting */
+fn hand_over(id: Uuid, callback: oneshot::Sender<Claim>, idempotency_tx: &mpsc::Sender<Action>) -> bool {
+    let ticket = Ticket { id, idempotency_tx: Some(idempotency_tx.clone()) };
+    match callback.send(Claim::New(ticket)) {
+        Ok(_) => true,
+        Err(claim) => {
+            /* the ticket is discarded without releasing the request, since it is handed to the next claimant */
+            if let Claim::New(mut ticket) = claim {
+                ticket.idempotency_tx = None;
+            }
+            false
+        }
+    }
+}
+
+/// Keeps the requests that are being processed and the outcomes of the requests that were completed within
+/// the window. The sender of the requests is used to create the tickets
+pub async fn new(idempotency_tx: mpsc::Sender<Action>, mut requests: mpsc::Receiver<Action>) {
+    let mut recent: HashMap<Uuid, State> = HashMap::new();
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Claim(id, callback) => {
+                recent.retain(|_, state| match state {
+                    State::Pending(_) => true,
+                    State::Completed(instant, _) => instant.elapsed() < WINDOW,
+                });
+                match recent.get_mut(&id) {
+                    Some(State::Completed(_, outcome)) => {
+                        let _ = callback.send(Claim::Duplicate(outcome.clone()));
+                    },
+                    Some(State::Pending(waiting)) => {
+                        log::info!("Request {} is a duplicate of a request that is still being processed", id);
+                        waiting.push(callback);
+                    },
+                    None => if hand_over(id, callback, &idempotency_tx) {
+                        recent.insert(id, State::Pending(Vec::new()));
+                    },
+                }
+            },
+            Action::Complete(id, outcome) => {
+                let waiting = match recent.remove(&id) {
+                    Some(State::Pending(waiting)) => waiting,
+                    Some(state) => {
+                        recent.insert(id, state);
+                        continue;
+                    },
+                    None => continue,
+                };
+                match outcome {
+                    Some(outcome @ (Ok(_), _)) => {
+                        for callback in waiting {
+                            let _ = callback.send(Claim::Duplicate(outcome.clone()));
+                        }
+                        recent.insert(id, State::Completed(Instant::now(), outcome));
+                    },
+                    /* failed and abandoned requests are forgotten, the first duplicate that is still waiting
+                       processes the request again */
+                    _ => {
+                        let mut waiting = waiting.into_iter();
+                        while let Some(callback) = waiting.next() {
+                            if hand_over(id, callback, &idempotency_tx) {
+                                recent.insert(id, State::Pending(waiting.collect()));
+                                break;
+                            }
+                        }
+                    },
+                }
+            },
+        }
+    }
+}
+
+/// Claims a request by its identifier via the idempotency task, a request is processed as new if the task
+/// is not available
+pub async fn claim(idempotency_tx: &mpsc::Sender<Action>, id: Uuid) -> Option<Claim> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    idempotency_tx.send(Action::Claim(id, callback_tx)).await.ok()?;
+    callback_rx.await.ok()
+}
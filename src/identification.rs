@@ -0,0 +1,234 @@
+use std::{collections::HashMap, f32::consts::PI, time::Duration};
+use futures::StreamExt;
+use shared::identification::{Identification, Robot};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
+
+use crate::robot::{builderbot, drone, pipuck, FernbedienungAction};
+use crate::{arena, optitrack};
+
+/* the time during which the rotations of the rigid bodies are observed after a robot started spinning */
+const WINDOW: Duration = Duration::from_secs(8);
+/* the minimum rotation of a rigid body for it to be identified as the spinning robot */
+const MIN_ROTATION: f32 = PI / 2.0;
+/* the rotation of the identified rigid body must exceed the rotation of all other rigid bodies by this factor */
+const DOMINANCE: f32 = 3.0;
+
+pub enum Action {
+    /* spin the given robots, or all robots if None, one at a time */
+    Start(oneshot::Sender<anyhow::Result<()>>, Option<Vec<String>>),
+    /* note that upon subscribing, the progress or the results of the last identification are sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Identification>>),
+}
+
+enum Kind {
+    BuilderBot,
+    Drone,
+    PiPuck,
+}
+
+/// Identifies the rigid bodies of the robots by spinning the robots in place one at a time and observing which
+/// rigid body rotates. The ground robots spin by themselves, while drones only blink their LEDs and must be
+/// turned by hand. The results are compared against the configured optitrack identifiers so that swapped
+/// identifiers are detected before they ruin the data of an experiment
+pub async fn new(
+    arena_tx: mpsc::Sender<arena::Action>,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    mut requests: mpsc::Receiver<Action>,
+) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let (progress_tx, mut progress_rx) = mpsc::channel(8);
+    let mut identification = Identification::default();
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Start(callback, robots)) => {
+                    if let Some(current) = identification.current.as_ref() {
+                        let error = anyhow::anyhow!("The identification is still running ({} is spinning)", current);
+                        let _ = callback.send(Err(error));
+                        continue;
+                    }
+                    let arena_tx = arena_tx.clone();
+                    let optitrack_tx = optitrack_tx.clone();
+                    let progress_tx = progress_tx.clone();
+                    match robots_to_identify(&arena_tx, robots).await {
+                        Ok(robots) => {
+                            /* mark the identification as running so that it can not be started twice */
+                            identification.current = robots.first().map(|(id, ..)| id.clone());
+                            crate::instrument::spawn("identification/run",
+                                identify(arena_tx, optitrack_tx, robots, progress_tx));
+                            let _ = callback.send(Ok(()));
+                        },
+                        Err(error) => {
+                            let _ = callback.send(Err(error));
+                        }
+                    }
+                },
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send(updates_tx.subscribe());
+                    let _ = updates_tx.send(identification.clone());
+                },
+                None => break,
+            },
+            Some(progress) = progress_rx.recv() => {
+                identification = progress;
+                let _ = updates_tx.send(identification.clone());
+            }
+        }
+    }
+}
+
+/// The robots in the order in which they are spun, the ground robots go first since they do not need an operator
+async fn robots_to_identify(
+    arena_tx: &mpsc::Sender<arena::Action>,
+    robots: Option<Vec<String>>,
+) -> anyhow::Result<Vec<(String, Kind, Option<i32>)>> {
+    let (builderbots_tx, builderbots_rx) = oneshot::channel();
+    let (drones_tx, drones_rx) = oneshot::channel();
+    let (pipucks_tx, pipucks_rx) = oneshot::channel();
+    for action in vec![
+        arena::Action::GetBuilderBotDescriptors(builderbots_tx),
+        arena::Action::GetDroneDescriptors(drones_tx),
+        arena::Action::GetPiPuckDescriptors(pipucks_tx),
+    ] {
+        arena_tx.send(action).await
+            .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    }
+    let no_response = |_| anyhow::anyhow!("No response from arena");
+    let mut builderbots = builderbots_rx.await.map_err(no_response)?.into_iter()
+        .map(|desc| (desc.id.clone(), Kind::BuilderBot, desc.optitrack_id))
+        .collect::<Vec<_>>();
+    let mut pipucks = pipucks_rx.await.map_err(no_response)?.into_iter()
+        .map(|desc| (desc.id.clone(), Kind::PiPuck, desc.optitrack_id))
+        .collect::<Vec<_>>();
+    let mut drones = drones_rx.await.map_err(no_response)?.into_iter()
+        .map(|desc| (desc.id.clone(), Kind::Drone, desc.optitrack_id))
+        .collect::<Vec<_>>();
+    builderbots.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+    pipucks.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+    drones.sort_by(|(left, ..), (right, ..)| left.cmp(right));
+    let mut selected = builderbots.into_iter()
+        .chain(pipucks)
+        .chain(drones)
+        .collect::<Vec<_>>();
+    if let Some(robots) = robots {
+        if let Some(unknown) = robots.iter().find(|id| !selected.iter().any(|(robot, ..)| robot == *id)) {
+            return Err(anyhow::anyhow!("Could not find robot {}", unknown));
+        }
+        selected.retain(|(id, ..)| robots.contains(id));
+    }
+    match selected.is_empty() {
+        true => Err(anyhow::anyhow!("There are no robots to identify")),
+        false => Ok(selected),
+    }
+}
+
+async fn identify(
+    arena_tx: mpsc::Sender<arena::Action>,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    robots: Vec<(String, Kind, Option<i32>)>,
+    progress_tx: mpsc::Sender<Identification>,
+) {
+    let mut identification = Identification {
+        started: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    };
+    for (id, kind, configured) in robots {
+        identification.current = Some(id.clone());
+        let _ = progress_tx.send(identification.clone()).await;
+        let result = async {
+            spin(&arena_tx, &id, kind).await?;
+            let rotations = observe(&optitrack_tx).await?;
+            assign(rotations)
+        };
+        let robot = match result.await {
+            Ok(observed) => Robot { id, configured, observed: Some(observed), error: None },
+            Err(error) => Robot { id, configured, observed: None, error: Some(format!("{:#}", error)) },
+        };
+        match (robot.proposal(), robot.error.as_ref()) {
+            (Some(proposal), _) => log::warn!("Robot {} was identified as rigid body {} instead of {:?}",
+                robot.id, proposal, robot.configured),
+            (None, Some(error)) => log::warn!("Could not identify robot {}: {}", robot.id, error),
+            (None, None) => log::info!("Robot {} was identified as rigid body {:?}", robot.id, robot.configured),
+        }
+        identification.robots.push(robot);
+    }
+    identification.current = None;
+    let _ = progress_tx.send(identification).await;
+}
+
+/// Starts the spinning of a ground robot or the blinking of the LEDs of a drone, this returns once ARGoS has
+/// been started on the robot
+async fn spin(arena_tx: &mpsc::Sender<arena::Action>, id: &str, kind: Kind) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let action = match kind {
+        Kind::BuilderBot => arena::Action::ForwardBuilderBotAction(id.to_owned(),
+            builderbot::Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Spin)),
+        Kind::PiPuck => arena::Action::ForwardPiPuckAction(id.to_owned(),
+            pipuck::Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Spin)),
+        Kind::Drone => arena::Action::ForwardDroneAction(id.to_owned(),
+            drone::Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Identify)),
+    };
+    arena_tx.send(action).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from robot"))?
+}
+
+/// The rotation of each rigid body around the vertical axis during the observation window in radians
+async fn observe(optitrack_tx: &mpsc::Sender<optitrack::Action>) -> anyhow::Result<HashMap<i32, f32>> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    optitrack_tx.send(optitrack::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with the optitrack task"))?;
+    let frames = callback_rx.await
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates"))?;
+    let mut frames = BroadcastStream::new(frames);
+    /* the last yaw and the accumulated rotation of each rigid body */
+    let mut rotations: HashMap<i32, (f32, f32)> = HashMap::new();
+    let window = tokio::time::sleep(WINDOW);
+    tokio::pin!(window);
+    loop {
+        tokio::select! {
+            Some(frame) = frames.next() => match frame {
+                Ok((_, updates, _)) => for update in updates {
+                    let [w, x, y, z] = update.orientation;
+                    let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+                    let (last, rotation) = rotations.entry(update.id).or_insert((yaw, 0.0));
+                    /* wrap the difference into [-pi, pi] so that crossing the discontinuity is not a rotation */
+                    let mut delta = yaw - *last;
+                    if delta > PI {
+                        delta -= 2.0 * PI;
+                    }
+                    else if delta < -PI {
+                        delta += 2.0 * PI;
+                    }
+                    *rotation += delta;
+                    *last = yaw;
+                },
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Identification missed {} frames from the tracking system", count),
+            },
+            _ = &mut window => break,
+        }
+    }
+    /* the direction of the rotation is irrelevant, the jitter of stationary rigid bodies cancels out */
+    Ok(rotations.into_iter()
+        .map(|(id, (_, rotation))| (id, rotation.abs()))
+        .collect())
+}
+
+/// The rigid body that rotated the most and its rotation in degrees, provided that no other rigid body
+/// rotated by a comparable amount
+fn assign(rotations: HashMap<i32, f32>) -> anyhow::Result<(i32, f32)> {
+    let mut rotations = rotations.into_iter().collect::<Vec<_>>();
+    rotations.sort_by(|(_, left), (_, right)| right.partial_cmp(left).unwrap_or(std::cmp::Ordering::Equal));
+    match rotations.as_slice() {
+        [] => Err(anyhow::anyhow!("No rigid bodies were tracked")),
+        [(_, rotation), ..] if *rotation < MIN_ROTATION =>
+            Err(anyhow::anyhow!("No rigid body rotated by more than {:.0}°", MIN_ROTATION.to_degrees())),
+        [(first, rotation), (second, other), ..] if *rotation < DOMINANCE * other =>
+            Err(anyhow::anyhow!("Rigid bodies {} and {} both rotated ({:.0}° and {:.0}°)",
+                first, second, rotation.to_degrees(), other.to_degrees())),
+        [(id, rotation), ..] => Ok((*id, rotation.to_degrees())),
+    }
+}
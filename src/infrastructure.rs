@@ -0,0 +1,144 @@
+use anyhow::Context;
+use macaddr::MacAddr6;
+use std::{collections::HashMap, convert::TryFrom, net::Ipv4Addr, time::Duration};
+use tokio::{process::Command, sync::{broadcast, mpsc, oneshot}};
+use shared::infrastructure::{Port, Station, Update};
+
+/* MIKROTIK-MIB::mtxrWlRtabStrength, indexed by the MAC address of the station and the interface */
+pub const DEFAULT_STATION_OID: &'static str = "1.3.6.1.4.1.14988.1.1.1.2.1.3";
+/* BRIDGE-MIB::dot1dTpFdbPort, indexed by MAC address */
+const DOT1D_TP_FDB_PORT_OID: &'static str = "1.3.6.1.2.1.17.4.3.1.2";
+/* BRIDGE-MIB::dot1dBasePortIfIndex, indexed by bridge port */
+const DOT1D_BASE_PORT_IF_INDEX_OID: &'static str = "1.3.6.1.2.1.17.1.4.1.2";
+/* IF-MIB::ifOperStatus, indexed by interface, 1 means up */
+const IF_OPER_STATUS_OID: &'static str = "1.3.6.1.2.1.2.2.1.8";
+
+#[derive(Debug)]
+pub struct AccessPoint {
+    pub addr: Ipv4Addr,
+    /* a table that is indexed by the MAC address of the associated stations and contains their RSSI */
+    pub station_oid: String,
+}
+
+#[derive(Debug)]
+pub struct Configuration {
+    pub community: String,
+    pub interval: Duration,
+    pub access_points: Vec<AccessPoint>,
+    pub switches: Vec<Ipv4Addr>,
+}
+
+pub enum Action {
+    /* note that upon subscribing, the most recent update is sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Update>>),
+}
+
+/// Polls the access points and switches of the arena via SNMP. If no configuration is provided, this
+/// task only answers subscriptions so that the web interface does not need to handle its absence
+pub async fn new(config: Option<Configuration>, mut requests: mpsc::Receiver<Action>) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut update = Update::default();
+    let mut interval = tokio::time::interval(config.as_ref()
+        .map_or(Duration::from_secs(5), |config| config.interval));
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send(updates_tx.subscribe());
+                    let _ = updates_tx.send(update.clone());
+                },
+                None => break,
+            },
+            _ = interval.tick(), if config.is_some() => if let Some(config) = config.as_ref() {
+                let next = poll(config).await;
+                if next != update {
+                    update = next;
+                    let _ = updates_tx.send(update.clone());
+                }
+            }
+        }
+    }
+}
+
+async fn poll(config: &Configuration) -> Update {
+    let mut update = Update::default();
+    for access_point in &config.access_points {
+        match walk(access_point.addr, &config.community, &access_point.station_oid).await {
+            Ok(table) => update.stations.extend(table.into_iter()
+                .filter_map(|(index, value)| Some(Station {
+                    macaddr: macaddr(&index)?,
+                    access_point: access_point.addr,
+                    rssi: value.parse().ok(),
+                }))),
+            Err(error) => log::warn!("Could not poll access point {}: {:#}", access_point.addr, error),
+        }
+    }
+    for &switch in &config.switches {
+        match ports(switch, &config.community).await {
+            Ok(ports) => update.ports.extend(ports),
+            Err(error) => log::warn!("Could not poll switch {}: {:#}", switch, error),
+        }
+    }
+    update
+}
+
+async fn ports(switch: Ipv4Addr, community: &str) -> anyhow::Result<Vec<Port>> {
+    let forwarding = walk(switch, community, DOT1D_TP_FDB_PORT_OID).await?;
+    let interfaces = walk(switch, community, DOT1D_BASE_PORT_IF_INDEX_OID).await?;
+    let status: HashMap<Vec<u32>, String> = walk(switch, community, IF_OPER_STATUS_OID).await?
+        .into_iter()
+        .collect();
+    let ports = interfaces.into_iter()
+        .filter_map(|(index, interface)| {
+            let port: u32 = *index.first()?;
+            let interface: u32 = interface.parse().ok()?;
+            let up = status.get(&vec![interface]).map_or(false, |status| status == "1");
+            let macaddrs = forwarding.iter()
+                .filter(|(_, value)| value.parse::<u32>().ok() == Some(port))
+                .filter_map(|(index, _)| macaddr(index))
+                .collect();
+            Some(Port { switch, port, up, macaddrs })
+        })
+        .collect();
+    Ok(ports)
+}
+
+/// Walks a table using `snmpbulkwalk` from Net-SNMP and returns the index of each entry relative to
+/// the provided OID and its value
+async fn walk(addr: Ipv4Addr, community: &str, oid: &str) -> anyhow::Result<Vec<(Vec<u32>, String)>> {
+    let output = Command::new("snmpbulkwalk")
+        .args(&["-v2c", "-c", community, "-On", "-Oq", "-Oe", "-t", "1", "-r", "1"])
+        .arg(addr.to_string())
+        .arg(oid)
+        .kill_on_drop(true)
+        .output().await
+        .context("Could not run snmpbulkwalk")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("snmpbulkwalk failed: {}", error.trim()));
+    }
+    let prefix = format!(".{}.", oid.trim_start_matches('.'));
+    let table = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(2, char::is_whitespace);
+            let index = fields.next()?.strip_prefix(&prefix)?
+                .split('.')
+                .map(|id| id.parse())
+                .collect::<Result<Vec<u32>, _>>()
+                .ok()?;
+            let value = fields.next().unwrap_or_default().trim().trim_matches('"').to_owned();
+            Some((index, value))
+        })
+        .collect();
+    Ok(table)
+}
+
+/// Extracts the MAC address from the first six sub-identifiers of an index
+fn macaddr(index: &[u32]) -> Option<MacAddr6> {
+    let mut octets = [0u8; 6];
+    for (octet, &id) in octets.iter_mut().zip(index.get(..6)?) {
+        *octet = u8::try_from(id).ok()?;
+    }
+    Some(MacAddr6::from(octets))
+}
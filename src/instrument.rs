@@ -0,0 +1,29 @@
+use std::future::Future;
+use tokio::task::JoinHandle;
+
+/// This function initializes the instrumentation of the runtime. When the supervisor is built with the
+/// `console` feature, a subscriber is installed that tokio-console can connect to (on 127.0.0.1:6669 by
+/// default). Otherwise, this function does nothing.
+pub fn init() {
+    #[cfg(feature = "console")]
+    console_subscriber::init();
+}
+
+/// This function spawns a future as a new task. When the supervisor is built with the `console` feature,
+/// the task is given the provided name so that it can be identified in tokio-console. Names should follow
+/// the form `subsystem` or `subsystem/detail`, e.g., `drone/drone1` or `router/192.168.1.10:54321`.
+pub fn spawn<F>(name: impl AsRef<str>, future: F) -> JoinHandle<F::Output>
+    where F: Future + Send + 'static, F::Output: Send + 'static {
+    #[cfg(feature = "console")]
+    {
+        tokio::task::Builder::new()
+            .name(name.as_ref())
+            .spawn(future)
+            .expect("Could not spawn task")
+    }
+    #[cfg(not(feature = "console"))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
@@ -0,0 +1,427 @@
+use anyhow::Context;
+use futures::StreamExt;
+use macaddr::MacAddr6;
+use serde::{Deserialize, Serialize};
+use shared::experiment::Summary;
+use std::{collections::{BTreeMap, HashMap}, net::Ipv4Addr, path::{Path, PathBuf}, str::FromStr, time::Duration};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+use crate::{arena, robot::{builderbot, drone, pipuck}};
+
+/* the interval at which the inventory is written to disk if it has changed */
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> anyhow::Result<Self> {
+        match format {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow::anyhow!("Unknown inventory format \"{}\"", format)),
+        }
+    }
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Csv => "text/csv",
+            Format::Json => "application/json",
+        }
+    }
+}
+
+/// An entry in the inventory. Entries are kept for robots that have been removed from the configuration
+/// so that the inventory also serves as a history of the robots that have been used in the arena
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+/* fields that are missing in inventories written by older versions are set to their defaults */
+#[serde(default)]
+pub struct Record {
+    pub robot: String,
+    pub id: String,
+    pub configured: bool,
+    pub macaddrs: BTreeMap<String, MacAddr6>,
+    pub optitrack_id: Option<i32>,
+    pub apriltag_id: Option<u8>,
+    /* the last time that an update was received from the robot in RFC 3339 format */
+    pub last_seen: Option<String>,
+    pub last_addrs: BTreeMap<String, Ipv4Addr>,
+    pub firmware: Option<String>,
+    pub battery: Option<i32>,
+    /* the last reported uptime and the total time that the robot has been associated in seconds */
+    pub uptime: Option<u64>,
+    pub associated_time: u64,
+    pub associations: u64,
+    pub unexpected_reboots: u64,
+    pub last_unexpected_reboot: Option<String>,
+    /* the number of experiments in which the robot participated and how many of those failed on the robot */
+    pub experiments: u64,
+    pub failed_experiments: u64,
+    pub last_experiment: Option<Summary>,
+}
+
+#[derive(Default)]
+pub struct Inventory {
+    records: BTreeMap<String, Record>,
+    /* the association time of the current session of each robot, this is not persisted */
+    sessions: HashMap<String, u64>,
+}
+
+impl Inventory {
+    /// Returns the path of the inventory, which is stored next to the configuration file
+    pub fn path(configuration: &Path) -> PathBuf {
+        configuration.with_extension("inventory.json")
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Inventory::default());
+        }
+        let contents = std::fs::read(path)
+            .context(format!("Could not read inventory {:?}", path))?;
+        let records: Vec<Record> = serde_json::from_slice(&contents)
+            .context(format!("Could not parse inventory {:?}", path))?;
+        let records = records.into_iter()
+            .map(|record| (record.id.clone(), record))
+            .collect();
+        Ok(Inventory { records, sessions: HashMap::new() })
+    }
+
+    pub async fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let contents = self.export(Format::Json)?;
+        tokio::fs::write(path, contents).await
+            .context(format!("Could not write inventory {:?}", path))
+    }
+
+    /// Updates the descriptor fields of the records from the configuration
+    pub fn configure<'a>(
+        &mut self,
+        builderbots: impl IntoIterator<Item = &'a builderbot::Descriptor>,
+        drones: impl IntoIterator<Item = &'a drone::Descriptor>,
+        pipucks: impl IntoIterator<Item = &'a pipuck::Descriptor>,
+    ) {
+        for record in self.records.values_mut() {
+            record.configured = false;
+        }
+        for descriptor in builderbots {
+            let record = self.record("BuilderBot", &descriptor.id);
+            record.configured = true;
+            record.macaddrs = std::iter::once(("duovero".to_owned(), descriptor.duovero_macaddr)).collect();
+            record.optitrack_id = descriptor.optitrack_id;
+            record.apriltag_id = descriptor.apriltag_id;
+        }
+        for descriptor in drones {
+            let record = self.record("Drone", &descriptor.id);
+            record.configured = true;
+            record.macaddrs = vec![
+                ("upcore".to_owned(), descriptor.upcore_macaddr),
+                ("xbee".to_owned(), descriptor.xbee_macaddr),
+            ].into_iter().collect();
+            record.optitrack_id = descriptor.optitrack_id;
+            record.apriltag_id = None;
+        }
+        for descriptor in pipucks {
+            let record = self.record("Pi-Puck", &descriptor.id);
+            record.configured = true;
+            record.macaddrs = std::iter::once(("rpi".to_owned(), descriptor.rpi_macaddr)).collect();
+            record.optitrack_id = descriptor.optitrack_id;
+            record.apriltag_id = descriptor.apriltag_id;
+        }
+    }
+
+    fn record(&mut self, robot: &str, id: &str) -> &mut Record {
+        let record = self.records.entry(id.to_owned()).or_default();
+        record.robot = robot.to_owned();
+        record.id = id.to_owned();
+        record
+    }
+
+    fn seen(&mut self, id: &str) -> Option<&mut Record> {
+        let record = self.records.get_mut(id)?;
+        record.last_seen = Some(chrono::Local::now().to_rfc3339());
+        Some(record)
+    }
+
+    /// Records that an interface of a robot was associated. Note that the robot tasks resend the connected
+    /// updates when a client subscribes, so only the first update of each session is counted
+    fn associate(&mut self, id: &str, interface: &str, addr: Ipv4Addr, session: bool) {
+        let new_session = session && !self.sessions.contains_key(id);
+        if new_session {
+            self.sessions.insert(id.to_owned(), 0);
+        }
+        if let Some(record) = self.seen(id) {
+            record.last_addrs.insert(interface.to_owned(), addr);
+            if new_session {
+                record.associations += 1;
+            }
+        }
+    }
+
+    fn disassociate(&mut self, id: &str) {
+        self.sessions.remove(id);
+    }
+
+    fn uptime(&mut self, id: &str, robot: u64, association: u64) {
+        let previous = self.sessions.insert(id.to_owned(), association).unwrap_or(0);
+        if let Some(record) = self.seen(id) {
+            record.uptime = Some(robot);
+            record.associated_time += association.saturating_sub(previous);
+        }
+    }
+
+    fn unexpected_reboot(&mut self, id: &str, reboot: String) {
+        if let Some(record) = self.seen(id) {
+            /* the last unexpected reboot is also resent when a client subscribes */
+            if record.last_unexpected_reboot.as_ref() != Some(&reboot) {
+                record.unexpected_reboots += 1;
+                record.last_unexpected_reboot = Some(reboot);
+            }
+        }
+    }
+
+    fn firmware(&mut self, id: &str, firmware: String) {
+        if let Some(record) = self.seen(id) {
+            record.firmware = Some(firmware);
+        }
+    }
+
+    fn battery(&mut self, id: &str, battery: i32) {
+        if let Some(record) = self.seen(id) {
+            record.battery = Some(battery);
+        }
+    }
+
+    fn experiment(&mut self, id: &str, summary: Summary) {
+        if let Some(record) = self.seen(id) {
+            record.experiments += 1;
+            if summary.failed {
+                record.failed_experiments += 1;
+            }
+            record.last_experiment = Some(summary);
+        }
+    }
+
+    /// Returns the summaries of the last experiment of each robot
+    pub fn summaries(&self) -> HashMap<String, Summary> {
+        self.records.values()
+            .filter_map(|record| record.last_experiment.clone()
+                .map(|summary| (record.id.clone(), summary)))
+            .collect()
+    }
+
+    /// Returns true if the update changed the inventory
+    fn update_builderbot(&mut self, id: &str, update: builderbot::Update) -> bool {
+        match update {
+            builderbot::Update::FernbedienungConnected(addr) => self.associate(id, "duovero", addr, true),
+            builderbot::Update::FernbedienungDisconnected => self.disassociate(id),
+            builderbot::Update::Uptime { robot, association } => self.uptime(id, robot, association),
+            builderbot::Update::UnexpectedReboot(reboot) => self.unexpected_reboot(id, reboot),
+            builderbot::Update::Firmware(firmware) => self.firmware(id, firmware),
+            builderbot::Update::Battery(battery) => self.battery(id, battery),
+            builderbot::Update::ExperimentSummary(summary) => self.experiment(id, summary),
+            _ => return false,
+        }
+        true
+    }
+
+    fn update_drone(&mut self, id: &str, update: drone::Update) -> bool {
+        match update {
+            drone::Update::FernbedienungConnected(addr) => self.associate(id, "upcore", addr, true),
+            drone::Update::FernbedienungDisconnected => self.disassociate(id),
+            drone::Update::XbeeConnected(addr) => self.associate(id, "xbee", addr, false),
+            drone::Update::Uptime { robot, association } => self.uptime(id, robot, association),
+            drone::Update::UnexpectedReboot(reboot) => self.unexpected_reboot(id, reboot),
+            drone::Update::Firmware(firmware) => self.firmware(id, firmware),
+            drone::Update::Battery(battery) => self.battery(id, battery),
+            drone::Update::ExperimentSummary(summary) => self.experiment(id, summary),
+            _ => return false,
+        }
+        true
+    }
+
+    fn update_pipuck(&mut self, id: &str, update: pipuck::Update) -> bool {
+        match update {
+            pipuck::Update::FernbedienungConnected(addr) => self.associate(id, "rpi", addr, true),
+            pipuck::Update::FernbedienungDisconnected => self.disassociate(id),
+            pipuck::Update::Uptime { robot, association } => self.uptime(id, robot, association),
+            pipuck::Update::UnexpectedReboot(reboot) => self.unexpected_reboot(id, reboot),
+            pipuck::Update::Firmware(firmware) => self.firmware(id, firmware),
+            pipuck::Update::Battery(battery) => self.battery(id, battery),
+            pipuck::Update::ExperimentSummary(summary) => self.experiment(id, summary),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn export(&self, format: Format) -> anyhow::Result<String> {
+        let records = self.records.values().collect::<Vec<_>>();
+        match format {
+            Format::Json => serde_json::to_string_pretty(&records)
+                .context("Could not serialize inventory"),
+            Format::Csv => {
+                let mut csv = String::from("robot,id,configured,macaddrs,optitrack_id,apriltag_id,last_seen,\
+                    last_addrs,firmware,battery,uptime,associated_time,associations,unexpected_reboots,experiments,\
+                    failed_experiments,last_experiment_finished,last_experiment_exit,last_experiment_output,\
+                    last_experiment_messages,last_experiment_events\n");
+                for record in records {
+                    let join = |entries: Vec<String>| entries.join(";");
+                    let fields = [
+                        record.robot.clone(),
+                        record.id.clone(),
+                        record.configured.to_string(),
+                        join(record.macaddrs.iter().map(|(name, macaddr)| format!("{}={}", name, macaddr)).collect()),
+                        record.optitrack_id.map_or_else(String::new, |id| id.to_string()),
+                        record.apriltag_id.map_or_else(String::new, |id| id.to_string()),
+                        record.last_seen.clone().unwrap_or_default(),
+                        join(record.last_addrs.iter().map(|(name, addr)| format!("{}={}", name, addr)).collect()),
+                        record.firmware.clone().unwrap_or_default(),
+                        record.battery.map_or_else(String::new, |battery| battery.to_string()),
+                        record.uptime.map_or_else(String::new, |uptime| uptime.to_string()),
+                        record.associated_time.to_string(),
+                        record.associations.to_string(),
+                        record.unexpected_reboots.to_string(),
+                        record.experiments.to_string(),
+                        record.failed_experiments.to_string(),
+                        record.last_experiment.as_ref().map_or_else(String::new, |summary| summary.finished.clone()),
+                        record.last_experiment.as_ref().map_or_else(String::new, |summary| summary.exit.clone()),
+                        record.last_experiment.as_ref().map_or_else(String::new, |summary| summary.output.to_string()),
+                        record.last_experiment.as_ref().map_or_else(String::new, |summary| summary.messages.to_string()),
+                        record.last_experiment.as_ref().map_or_else(String::new, |summary| summary.events.join(";")),
+                    ];
+                    let fields = fields.iter()
+                        .map(|field| escape(field))
+                        .collect::<Vec<_>>();
+                    csv.push_str(&fields.join(","));
+                    csv.push('\n');
+                }
+                Ok(csv)
+            }
+        }
+    }
+}
+
+fn escape(field: &str) -> String {
+    if field.contains(|c: char| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    }
+    else {
+        field.to_owned()
+    }
+}
+
+pub enum Action {
+    Export(oneshot::Sender<anyhow::Result<String>>, Format),
+    GetSummaries(oneshot::Sender<HashMap<String, Summary>>),
+}
+
+/// This function represents the main task of the inventory module. It keeps track of the robots that have
+/// been seen by the supervisor by subscribing to their updates and periodically writes the inventory
+/// to disk so that it persists across sessions
+pub async fn new(
+    path: PathBuf,
+    arena_tx: mpsc::Sender<arena::Action>,
+    mut requests: mpsc::Receiver<Action>
+) {
+    let mut inventory = match Inventory::load(&path) {
+        Ok(inventory) => inventory,
+        Err(error) => {
+            /* do not overwrite an inventory that could not be read */
+            log::error!("{:#}", error);
+            return;
+        }
+    };
+    let mut interval = tokio::time::interval(SAVE_INTERVAL);
+    let mut changed;
+    /* the subscription is renewed when robots are added or removed so that their updates are received */
+    loop {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        let subscription = match arena_tx.send(arena::Action::Subscribe(callback_tx)).await {
+            Ok(_) => callback_rx.await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Could not subscribe to arena updates"))),
+            Err(_) => Err(anyhow::anyhow!("Could not communicate with arena")),
+        };
+        let arena::Subscription { snapshot, builderbots: builderbot_updates, drones: drone_updates, pipucks: pipuck_updates, mut roster, .. } =
+            match subscription {
+                Ok(subscription) => subscription,
+                Err(error) => {
+                    log::error!("Could not initialize inventory: {:#}", error);
+                    return;
+                }
+            };
+        inventory.configure(
+            snapshot.builderbots.iter().map(|(desc, _)| desc),
+            snapshot.drones.iter().map(|(desc, _)| desc),
+            snapshot.pipucks.iter().map(|(desc, _)| desc));
+        changed = true;
+        /* bring the inventory up to date with the current state of the robots */
+        for (desc, updates) in snapshot.builderbots {
+            for update in updates {
+                inventory.update_builderbot(&desc.id, update);
+            }
+        }
+        for (desc, updates) in snapshot.drones {
+            for update in updates {
+                inventory.update_drone(&desc.id, update);
+            }
+        }
+        for (desc, updates) in snapshot.pipucks {
+            for update in updates {
+                inventory.update_pipuck(&desc.id, update);
+            }
+        }
+        tokio::pin!(builderbot_updates);
+        tokio::pin!(drone_updates);
+        tokio::pin!(pipuck_updates);
+        loop {
+            tokio::select! {
+                request = requests.recv() => match request {
+                    Some(Action::Export(callback, format)) => {
+                        let _ = callback.send(inventory.export(format));
+                    },
+                    Some(Action::GetSummaries(callback)) => {
+                        let _ = callback.send(inventory.summaries());
+                    },
+                    None => return,
+                },
+                Some((descriptor, update)) = builderbot_updates.next() => match update {
+                    Ok(update) => changed |= inventory.update_builderbot(&descriptor.id, update),
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Inventory missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = drone_updates.next() => match update {
+                    Ok(update) => changed |= inventory.update_drone(&descriptor.id, update),
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Inventory missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = pipuck_updates.next() => match update {
+                    Ok(update) => changed |= inventory.update_pipuck(&descriptor.id, update),
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Inventory missed {} messages for {}", count, descriptor),
+                },
+                /* subscribe again to receive the updates of the added robots */
+                Ok(_) = roster.recv() => break,
+                _ = interval.tick(), if changed => {
+                    changed = false;
+                    if let Err(error) = inventory.save(&path).await {
+                        log::warn!("{:#}", error);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,38 +1,268 @@
 use anyhow::{Result, Context};
 use futures::{Stream, StreamExt, TryFutureExt, TryStreamExt};
-use shared::{builderbot, drone, pipuck};
+use shared::{ambient, builderbot, drone, pipuck};
+use shared::configuration::JournalFormat;
+use shared::experiment::{Acknowledgement, Energy, Excerpt, JournalStatistics, LogEntry, LogSource, SmokeTest, TimelineCategory, TimelineEvent, TrackingGaps, fingerprint::Fingerprint};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
-use std::net::SocketAddr;
-use std::fs::File;
-use std::io::BufWriter;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 use bytes::BytesMut;
 use serde::Serialize;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use chrono::{DateTime, Local};
 use shared::tracking_system;
 
 
-use crate::{optitrack, router};
+use crate::{encryption::{self, Cipher}, optitrack, router};
+
+/// The version of the format of the journal, which must be incremented whenever the events or their
+/// contents change so that analysis tools can reject journals that they do not understand
+pub const SCHEMA_VERSION: u32 = 3;
+
+/* the number of lines from the output of ARGoS that are kept for each robot */
+const EXCERPT_LINES: usize = 40;
+/* the length after which a line is split, in case ARGoS writes a large amount of output without a newline */
+const EXCERPT_LINE_LENGTH: usize = 1024;
+/* the interval in milliseconds at which the poses from the tracking system are summarized in the live log */
+pub const SNAPSHOT_INTERVAL: i64 = 1000;
+
+/// Where a file that was downloaded from a robot is stored
+#[derive(Clone, Debug)]
+pub enum Destination {
+    /* the directory of the robot with the given identifier in the directory for the artifacts of the last experiment */
+    Experiment(String),
+    /* a directory that was requested explicitly, e.g., for the results that are fetched on request */
+    Directory(PathBuf),
+}
 
 pub enum Action {
-    Start(oneshot::Sender<anyhow::Result<()>>),
+    /* the header is written as the first record */
+    Start(Header, oneshot::Sender<anyhow::Result<()>>),
     Stop,
     Record(Event),
+    Subscribe(oneshot::Sender<broadcast::Receiver<JournalStatistics>>),
+    /* note that only the entries of the live log that are recorded after subscribing are sent */
+    SubscribeLog(oneshot::Sender<broadcast::Receiver<LogEntry>>),
+    /* the number of messages that were sent via the router from the given address during the last experiment */
+    MessageCount(IpAddr, oneshot::Sender<u64>),
+    /* a file that was downloaded from a robot, which is stored at the given path relative to the destination */
+    StoreArtifact(Destination, PathBuf, Vec<u8>, oneshot::Sender<anyhow::Result<()>>),
+    /* the last lines that ARGoS wrote on the given robot during the last experiment, None if nothing was recorded */
+    Excerpt(String, oneshot::Sender<Option<Excerpt>>),
+}
+
+/// The first record of each journal, which identifies the experiment that produced the journal
+#[derive(Debug, Serialize)]
+pub struct Header {
+    pub schema_version: u32,
+    pub supervisor_version: String,
+    /* the local time at which the journal was started (RFC 3339), the timestamps of the entries are relative to it */
+    pub start: String,
+    /* the MD5 checksum of the configuration file when the experiment was started */
+    pub configuration_checksum: Option<String>,
+    pub builderbots: Vec<builderbot::Descriptor>,
+    pub drones: Vec<drone::Descriptor>,
+    pub pipucks: Vec<pipuck::Descriptor>,
+    /* the robot type, the name, and the MD5 checksum of each file of the control software */
+    pub software: Vec<(String, String, String)>,
+}
+
+impl Header {
+    pub fn new(
+        builderbots: Vec<builderbot::Descriptor>,
+        drones: Vec<drone::Descriptor>,
+        pipucks: Vec<pipuck::Descriptor>,
+        software: Vec<(String, String, String)>,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            supervisor_version: env!("CARGO_PKG_VERSION").to_owned(),
+            /* only the journal knows when it was started and the path of the configuration file */
+            start: String::new(),
+            configuration_checksum: None,
+            builderbots,
+            drones,
+            pipucks,
+            software,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub enum Event {
+    Header(Header),
     ARGoS(String, ARGoS),
     Message(SocketAddr, crate::router::LuaType),
-    TrackingSystem(Vec<tracking_system::Update>),
-    Descriptors(Vec<builderbot::Descriptor>, Vec<drone::Descriptor>, Vec<pipuck::Descriptor>, )
+    /* the frame number is used to detect frames that were dropped */
+    TrackingSystem {
+        frame: i32,
+        updates: Vec<tracking_system::Update>,
+    },
+    /* the statistics about dropped frames, recorded when the journal is stopped */
+    TrackingGaps(TrackingGaps),
+    /* the category, the description, and the tags of an event on the timeline (since schema version 3) */
+    Timeline(TimelineCategory, String, Vec<String>),
+    Fingerprint(Fingerprint),
+    /* the identifier of a drone and the voltages of the cells of its battery in millivolts */
+    BatteryCells(String, Vec<u16>),
+    /* an item of the checklist and by whom and when it was acknowledged before the experiment */
+    Checklist(String, Acknowledgement),
+    /* the identifier of a robot and the last smoke test of that robot before the experiment */
+    SmokeTest(String, SmokeTest),
+    /* the readings of the ambient sensors in the arena */
+    Ambient(Vec<ambient::Reading>),
+    /* the identifier of a robot and the energy that it used during the experiment */
+    Energy(String, Energy),
+    /* the trace identifier of a request from the user interface that started or stopped the experiment */
+    Request(uuid::Uuid, String),
+    /* the robots that participate in the experiment once it has started, which excludes the robots that could
+       not be set up or started when the operator chose to continue without them */
+    Participants(Vec<String>),
+}
+
+impl Event {
+    /// The name of this event, which is also the name of its CSV file
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Header(_) => "Header",
+            Event::ARGoS(..) => "ARGoS",
+            Event::Message(..) => "Message",
+            Event::TrackingSystem { .. } => "TrackingSystem",
+            Event::TrackingGaps(_) => "TrackingGaps",
+            Event::Timeline(..) => "Timeline",
+            Event::Fingerprint(_) => "Fingerprint",
+            Event::BatteryCells(..) => "BatteryCells",
+            Event::Checklist(..) => "Checklist",
+            Event::SmokeTest(..) => "SmokeTest",
+            Event::Ambient(_) => "Ambient",
+            Event::Energy(..) => "Energy",
+            Event::Request(..) => "Request",
+            Event::Participants(_) => "Participants",
+        }
+    }
+
+    /// The contents of this event for the formats other than pickle. Unlike in the pickle, the fields of all
+    /// events are named and the output of ARGoS is decoded as text
+    fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            Event::Header(header) => json!(header),
+            Event::ARGoS(robot, ARGoS::StandardOutput(data)) =>
+                json!({ "robot": robot, "stream": "stdout", "text": String::from_utf8_lossy(data) }),
+            Event::ARGoS(robot, ARGoS::StandardError(data)) =>
+                json!({ "robot": robot, "stream": "stderr", "text": String::from_utf8_lossy(data) }),
+            Event::ARGoS(robot, ARGoS::Backtrace(backtrace)) =>
+                json!({ "robot": robot, "stream": "backtrace", "text": backtrace }),
+            Event::Message(addr, message) => json!({ "addr": addr, "message": message }),
+            Event::TrackingSystem { frame, updates } => json!({ "frame": frame, "updates": updates }),
+            Event::TrackingGaps(gaps) => json!(gaps),
+            Event::Timeline(category, description, tags) =>
+                json!({ "category": category, "description": description, "tags": tags }),
+            Event::Fingerprint(fingerprint) =>
+                json!({ "digest": fingerprint.digest(), "components": fingerprint.components }),
+            Event::BatteryCells(robot, cells) => json!({ "robot": robot, "cells": cells }),
+            Event::Checklist(item, acknowledgement) => json!({ "item": item, "acknowledgement": acknowledgement }),
+            Event::SmokeTest(robot, smoke_test) => json!({ "robot": robot, "smoke_test": smoke_test }),
+            Event::Ambient(readings) => json!({ "readings": readings }),
+            Event::Energy(robot, energy) => json!({ "robot": robot, "energy": energy }),
+            Event::Request(trace, description) => json!({ "trace": trace, "description": description }),
+            Event::Participants(robots) => json!({ "robots": robots }),
+        }
+    }
+
+    /// The category under which this event is counted in the journal statistics
+    fn category(&self) -> &'static str {
+        match self {
+            Event::ARGoS(_, ARGoS::StandardOutput(_)) => "ARGoS standard output",
+            Event::ARGoS(_, ARGoS::StandardError(_)) => "ARGoS standard error",
+            Event::ARGoS(_, ARGoS::Backtrace(_)) => "ARGoS backtraces",
+            Event::Message(..) => "Messages",
+            Event::TrackingSystem { .. } => "Tracking system",
+            Event::TrackingGaps(_) => "Tracking gaps",
+            Event::Header(_) => "Header",
+            Event::Timeline(..) => "Timeline",
+            Event::Fingerprint(_) => "Fingerprint",
+            Event::BatteryCells(..) => "Battery cells",
+            Event::Checklist(..) => "Checklist",
+            Event::SmokeTest(..) => "Smoke tests",
+            Event::Ambient(_) => "Ambient sensors",
+            Event::Energy(..) => "Energy",
+            Event::Request(..) => "Requests",
+            Event::Participants(_) => "Participants",
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub enum ARGoS {
     StandardOutput(BytesMut),
     StandardError(BytesMut),
+    /* the backtrace retrieved from the core dump after ARGoS crashed */
+    Backtrace(String),
+}
+
+/// The last lines that ARGoS wrote on a robot, which are kept in memory so that they can be shown on the
+/// card of the robot without reading the journal
+#[derive(Default)]
+pub struct Tail {
+    lines: VecDeque<(bool, String)>,
+    omitted: usize,
+    /* the incomplete last lines of standard output and standard error */
+    partial: [Vec<u8>; 2],
+}
+
+impl Tail {
+    /// Appends the output of ARGoS and returns the lines that were completed by it
+    pub fn push(&mut self, error: bool, data: &[u8]) -> Vec<String> {
+        let mut completed = Vec::new();
+        let partial = &mut self.partial[error as usize];
+        partial.extend_from_slice(data);
+        loop {
+            let length = match partial.iter().position(|byte| *byte == b'\n') {
+                Some(index) => index + 1,
+                None if partial.len() > EXCERPT_LINE_LENGTH => EXCERPT_LINE_LENGTH,
+                None => break,
+            };
+            let line = partial.drain(..length).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line).trim_end_matches(&['\r', '\n'][..]).to_owned();
+            self.lines.push_back((error, line.clone()));
+            if self.lines.len() > EXCERPT_LINES {
+                self.lines.pop_front();
+                self.omitted += 1;
+            }
+            completed.push(line);
+        }
+        completed
+    }
+
+    pub fn excerpt(&self) -> Excerpt {
+        /* the incomplete lines are included since ARGoS may have been stopped before finishing them */
+        let partial = self.partial.iter()
+            .enumerate()
+            .filter(|(_, partial)| !partial.is_empty())
+            .map(|(error, partial)| (error == 1, String::from_utf8_lossy(partial).into_owned()));
+        Excerpt {
+            lines: self.lines.iter().cloned().chain(partial).collect(),
+            omitted: self.omitted,
+        }
+    }
+}
+
+/// Summarizes a frame from the tracking system for the live log as the positions of the rigid bodies
+pub fn snapshot(updates: &[tracking_system::Update]) -> String {
+    match updates.is_empty() {
+        true => "No rigid bodies".to_owned(),
+        false => updates.iter()
+            .map(|update| format!("{}: ({:.2}, {:.2}, {:.2})",
+                update.id, update.position[0], update.position[1], update.position[2]))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -41,6 +271,292 @@ struct Entry {
     event: Event,
 }
 
+/// A file of the journal, which is either written directly or through an encryptor
+type Output = BufWriter<Box<dyn Write + Send>>;
+
+/// Creates a file of the journal, which is encrypted at rest if a key has been configured
+fn open(path: &Path, cipher: Option<&Cipher>) -> Result<Output> {
+    encryption::open(path, cipher).map(BufWriter::new)
+}
+
+/// The file or the files to which the entries of a journal are written
+enum Writer {
+    Pickle(Output),
+    JsonLines(Output),
+    /* the files are named after the path of the journal and are created when the first event of each type
+       is recorded */
+    Csv {
+        path: PathBuf,
+        tables: HashMap<&'static str, Table>,
+        cipher: Option<Cipher>,
+    },
+}
+
+/// The CSV file for one type of event, whose columns are taken from the first row that is written to it
+struct Table {
+    writer: Output,
+    columns: Vec<String>,
+    /* whether a row with columns that are not in the file has been reported */
+    truncated: bool,
+}
+
+impl Writer {
+    fn create(format: JournalFormat, path: &Path, cipher: Option<&Cipher>) -> Result<Writer> {
+        let writer = match format {
+            JournalFormat::Pickle => Writer::Pickle(open(path, cipher)?),
+            JournalFormat::JsonLines => Writer::JsonLines(open(path, cipher)?),
+            JournalFormat::Csv => Writer::Csv { path: path.to_owned(), tables: HashMap::new(), cipher: cipher.cloned() },
+        };
+        Ok(writer)
+    }
+
+    /// Writes an entry to the journal and returns the number of bytes that were written
+    fn write(&mut self, entry: &Entry) -> Result<usize> {
+        match self {
+            Writer::Pickle(writer) => {
+                let encoded = serde_pickle::ser::to_vec(entry, true)?;
+                writer.write_all(&encoded)?;
+                Ok(encoded.len())
+            },
+            Writer::JsonLines(writer) => {
+                let record = serde_json::json!({
+                    "timestamp": entry.timestamp,
+                    "event": entry.event.name(),
+                    "data": entry.event.to_json(),
+                });
+                let encoded = format!("{}\n", record);
+                writer.write_all(encoded.as_bytes())?;
+                Ok(encoded.len())
+            },
+            Writer::Csv { path, tables, cipher } => {
+                let name = entry.event.name();
+                let rows = rows(&entry.event.to_json());
+                let first = match rows.first() {
+                    Some(first) => first,
+                    None => return Ok(0),
+                };
+                let mut length = 0;
+                if !tables.contains_key(name) {
+                    let path = path.with_extension(format!("{}.csv", name));
+                    let mut writer = open(&path, cipher.as_ref())?;
+                    let columns = first.iter()
+                        .map(|(column, _)| column.clone())
+                        .collect::<Vec<_>>();
+                    let header = std::iter::once("timestamp")
+                        .chain(columns.iter().map(String::as_str))
+                        .map(escape)
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    writer.write_all(header.as_bytes())?;
+                    writer.write_all(b"\n")?;
+                    length += header.len() + 1;
+                    tables.insert(name, Table { writer, columns, truncated: false });
+                }
+                let table = tables.get_mut(name).unwrap();
+                for row in &rows {
+                    if !table.truncated && row.iter().any(|(column, _)| !table.columns.contains(column)) {
+                        log::warn!("Dropping columns of {} that are not in the CSV file of the journal", name);
+                        table.truncated = true;
+                    }
+                    let timestamp = entry.timestamp.to_string();
+                    let line = std::iter::once(timestamp.as_str())
+                        .chain(table.columns.iter()
+                            .map(|column| row.iter()
+                                .find(|(key, _)| key == column)
+                                .map_or("", |(_, value)| value.as_str())))
+                        .map(escape)
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    table.writer.write_all(line.as_bytes())?;
+                    table.writer.write_all(b"\n")?;
+                    length += line.len() + 1;
+                }
+                Ok(length)
+            },
+        }
+    }
+}
+
+/// Flattens the contents of an event into the rows of a CSV file. The fields of nested objects and the
+/// elements of arrays of numbers become columns named after their path, e.g., `position.0`, while arrays of
+/// strings, e.g., the tags of an event on the timeline, are joined with spaces and empty arrays become an
+/// empty column. The first array of objects, e.g., the rigid bodies in a frame from the
+/// tracking system, is written as one row per object and any other array is written as JSON
+fn rows(contents: &serde_json::Value) -> Vec<Vec<(String, String)>> {
+    let mut row = Vec::new();
+    let mut expanded = None;
+    flatten("", contents, &mut row, Some(&mut expanded));
+    match expanded {
+        Some((name, items)) => items.iter()
+            .map(|item| {
+                let mut row = row.clone();
+                flatten(&name, item, &mut row, None);
+                row
+            })
+            .collect(),
+        None => vec![row],
+    }
+}
+
+fn flatten(
+    name: &str,
+    value: &serde_json::Value,
+    row: &mut Vec<(String, String)>,
+    mut expanded: Option<&mut Option<(String, Vec<serde_json::Value>)>>,
+) {
+    use serde_json::Value;
+    let path = |key: &dyn std::fmt::Display| match name.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", name, key),
+    };
+    match value {
+        Value::Object(fields) => for (key, value) in fields {
+            flatten(&path(key), value, row, expanded.as_deref_mut());
+        },
+        /* otherwise the column would be missing if the first row of a table has an empty array */
+        Value::Array(items) if items.is_empty() => row.push((name.to_owned(), String::new())),
+        Value::Array(items) if items.iter().all(Value::is_number) => for (index, item) in items.iter().enumerate() {
+            row.push((path(&index), item.to_string()));
+        },
+        Value::Array(items) if items.iter().all(Value::is_string) => {
+            let items = items.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+            row.push((name.to_owned(), items.join(" ")));
+        },
+        Value::Array(items) => match expanded {
+            Some(expanded) if expanded.is_none() && items.iter().all(Value::is_object) =>
+                *expanded = Some((name.to_owned(), items.clone())),
+            _ => row.push((name.to_owned(), value.to_string())),
+        },
+        Value::String(string) => row.push((name.to_owned(), string.clone())),
+        Value::Null => row.push((name.to_owned(), String::new())),
+        value => row.push((name.to_owned(), value.to_string())),
+    }
+}
+
+/// Quotes a field of a CSV file if it contains a separator, a quote, or a line break
+fn escape(field: &str) -> Cow<str> {
+    match field.contains(&[',', '"', '\n', '\r'][..]) {
+        true => Cow::Owned(format!("\"{}\"", field.replace('"', "\"\""))),
+        false => Cow::Borrowed(field),
+    }
+}
+
+struct Journal {
+    start: DateTime<Local>,
+    path: PathBuf,
+    writer: Writer,
+    /* the files next to the journal, e.g., the fingerprint, are encrypted with the same key as the journal */
+    cipher: Option<Cipher>,
+    statistics: JournalStatistics,
+    /* the number of the last frame received from the tracking system */
+    last_frame: Option<i32>,
+    log_tx: broadcast::Sender<LogEntry>,
+    /* the timestamp of the last summary of the tracking system in the live log */
+    last_snapshot: Option<i64>,
+}
+
+impl Journal {
+    fn timestamp(&self) -> i64 {
+        Local::now()
+            .signed_duration_since(self.start)
+            .num_milliseconds()
+    }
+
+    fn record(&mut self, event: Event) {
+        let category = event.category();
+        let timestamp = self.timestamp();
+        self.log(timestamp, &event);
+        if let Event::Timeline(kind, description, tags) = &event {
+            self.statistics.timeline.push(TimelineEvent {
+                timestamp,
+                category: *kind,
+                description: description.clone(),
+                tags: tags.clone(),
+            });
+        }
+        if let Event::TrackingSystem { frame, .. } = &event {
+            self.track(*frame);
+        }
+        if let Event::Fingerprint(fingerprint) = &event {
+            self.statistics.fingerprint = Some(fingerprint.clone());
+            /* the fingerprint is also written next to the journal so that runs can be compared later */
+            let path = self.path.with_extension("fingerprint.json");
+            let contents = serde_json::json!({
+                "digest": fingerprint.digest(),
+                "components": fingerprint.components,
+            });
+            if let Err(error) = encryption::write(&path, contents.to_string().as_bytes(), self.cipher.as_ref()) {
+                log::error!("Could not write fingerprint: {:#}", error);
+            }
+        }
+        let entry = Entry { timestamp, event };
+        match self.writer.write(&entry) {
+            Ok(length) => {
+                self.statistics.file_size += length as u64;
+                *self.statistics.events.entry(category.to_owned()).or_default() += 1;
+            },
+            Err(error) => log::error!("Error writing entry {:?} to journal: {}", entry, error),
+        }
+    }
+
+    /// Publishes an event in the live log. The output of ARGoS is published by `output` once it has been split
+    /// into lines, while the messages between the robots and most frames from the tracking system are left out
+    fn log(&mut self, timestamp: i64, event: &Event) {
+        let (source, robot, text) = match event {
+            Event::ARGoS(robot, ARGoS::Backtrace(backtrace)) =>
+                (LogSource::StandardError, Some(robot.clone()), backtrace.clone()),
+            Event::TrackingSystem { updates, .. } => match self.last_snapshot {
+                Some(last_snapshot) if timestamp - last_snapshot < SNAPSHOT_INTERVAL => return,
+                _ => {
+                    self.last_snapshot = Some(timestamp);
+                    (LogSource::Tracking, None, snapshot(updates))
+                }
+            },
+            Event::Timeline(_, description, _) => (LogSource::Timeline, None, description.clone()),
+            Event::Participants(robots) => (LogSource::Participants, None, robots.join(", ")),
+            _ => return,
+        };
+        let _ = self.log_tx.send(LogEntry { timestamp, source, robot, text });
+    }
+
+    /// Publishes the lines that ARGoS wrote on a robot in the live log
+    fn output(&self, robot: &str, error: bool, lines: Vec<String>) {
+        let timestamp = self.timestamp();
+        let source = match error {
+            true => LogSource::StandardError,
+            false => LogSource::StandardOutput,
+        };
+        for text in lines {
+            let _ = self.log_tx.send(LogEntry { timestamp, source, robot: Some(robot.to_owned()), text });
+        }
+    }
+
+    /// Updates the statistics about dropped frames. A frame number that does not increase indicates that
+    /// Motive was restarted, in this case there is no gap
+    fn track(&mut self, frame: i32) {
+        let tracking = &mut self.statistics.tracking;
+        tracking.frames += 1;
+        if let Some(last_frame) = self.last_frame {
+            if frame > last_frame + 1 {
+                let dropped = (frame - last_frame - 1) as u64;
+                tracking.dropped += dropped;
+                tracking.gaps += 1;
+                tracking.longest = tracking.longest.max(dropped);
+            }
+        }
+        self.last_frame = Some(frame);
+    }
+
+    fn statistics(&mut self) -> JournalStatistics {
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        self.statistics.disk_available = fs2::available_space(directory).ok();
+        self.statistics.clone()
+    }
+}
+
 // ideally there would be exactly one way to subscribe to data, however, adding a subscription-style
 // way of getting the data from ARGoS would require changing how the arena works since the proceedure
 // for starting experiments currently prevents the arena from processing such requests
@@ -52,7 +568,10 @@ struct Entry {
 
 // the design flaw is most certainly the arena actor -- there is actually little that this actor does
 // other than create an additional layer of complexity
-pub async fn new(mut requests_rx: mpsc::Receiver<Action>,
+pub async fn new(requests_rx: &mut mpsc::Receiver<Action>,
+                 configuration_path: PathBuf,
+                 format: JournalFormat,
+                 cipher: Option<Cipher>,
                  optitrack_tx: mpsc::Sender<optitrack::Action>,
                  router_tx: mpsc::Sender<router::Action>) -> Result<()> {
     
@@ -60,58 +579,91 @@ pub async fn new(mut requests_rx: mpsc::Receiver<Action>,
     tokio::pin!(optitrack_stream);
     let router_stream = futures::stream::pending().left_stream();
     tokio::pin!(router_stream);
-    let mut journal: Option<(DateTime<Local>, BufWriter<_>)> = None;
+    let mut journal: Option<Journal> = None;
+    /* statistics are pushed to subscribers (e.g., the webui) while recording */
+    let (statistics_tx, _) = broadcast::channel(8);
+    /* the entries of the live log are pushed to subscribers while recording, the output of ARGoS can
+       arrive in bursts */
+    let (log_tx, _) = broadcast::channel(256);
+    let mut statistics_interval = tokio::time::interval(Duration::from_secs(1));
+    /* the number of messages sent by each robot, these are kept after the journal is stopped so that the
+       robots can include them in the summaries of the experiment */
+    let mut message_counts: HashMap<IpAddr, u64> = HashMap::new();
+    /* the directory for the artifacts of the last experiment, this is also kept after the journal is stopped
+       since the robots retrieve their files after the experiment has been stopped */
+    let mut artifacts_dir: Option<PathBuf> = None;
+    /* the output of ARGoS on each robot during the last experiment, which is also kept after the journal is
+       stopped so that it can be inspected after the experiment */
+    let mut tails: HashMap<String, Tail> = HashMap::new();
 
     loop {
         tokio::select! {
             Some(update) = optitrack_stream.next() => match update {
-                Ok(event) => {
-                    let (start, writer) = journal.as_mut().unwrap();
-                    let entry = Entry {
-                        timestamp: Local::now()
-                            .signed_duration_since(*start)
-                            .num_milliseconds(),
-                        event
-                    };
-                    if let Err(error) = serde_pickle::ser::to_writer(writer, &entry, true) {
-                        log::error!("Error writing entry {:?} to journal: {}", entry, error);
-                    }
+                Ok(event) => if let Some(journal) = journal.as_mut() {
+                    journal.record(event);
                 }
                 Err(error) => {
                     log::error!("Error writing entries to journal: {}", error);
                 }
             },
             Some(update) = router_stream.next() => match update {
-                Ok(event) => if let Some((start, writer)) = journal.as_mut() {
-                    let entry = Entry {
-                        timestamp: Local::now()
-                            .signed_duration_since(*start)
-                            .num_milliseconds(),
-                        event
-                    };
-                    if let Err(error) = serde_pickle::ser::to_writer(writer, &entry, true) {
-                        log::error!("Error writing entry {:?} to journal: {}", entry, error);
+                Ok(event) => if let Some(journal) = journal.as_mut() {
+                    if let Event::Message(addr, _) = &event {
+                        *message_counts.entry(addr.ip()).or_default() += 1;
                     }
+                    journal.record(event);
                 }
                 Err(error) => {
                     log::error!("Error writing entries to journal: {}", error);
                 }
             },
+            _ = statistics_interval.tick() => if let Some(journal) = journal.as_mut() {
+                let _ = statistics_tx.send(journal.statistics());
+            },
             request = requests_rx.recv() => match request {
                 None => break,
                 Some(action) => match action {
-                    Action::Start(callback) => {
+                    Action::Start(mut header, callback) => {
                         let now = Local::now();
-                        let log_filename = now.format("%Y%m%d-%H%M%S.pkl").to_string();
-                        let file_result = File::create(log_filename)
+                        let path = PathBuf::from(now.format("%Y%m%d-%H%M%S").to_string())
+                            .with_extension(format.extension());
+                        let file_result = Writer::create(format, &path, cipher.as_ref())
                             .context("Could not create file for journal");
                         let router_result = router(&router_tx).await;
                         let optitrack_result = optitrack(&optitrack_tx).await;
                         match (file_result, router_result, optitrack_result) {
-                            (Ok(file), Ok(router), Ok(optitrack)) => {
-                                journal = Some((now, BufWriter::new(file)));
+                            (Ok(writer), Ok(router), Ok(optitrack)) => {
+                                /* the artifacts are stored in a directory with the same name as the journal */
+                                let artifacts = path.with_extension("");
+                                /* the raw packets from the tracking system are recorded next to the journal if
+                                   this has been configured */
+                                let natnet = path.with_extension("natnet.pcap");
+                                let _ = optitrack_tx.send(optitrack::Action::Record(natnet, cipher.clone())).await;
+                                let mut started = Journal {
+                                    start: now,
+                                    path,
+                                    writer,
+                                    cipher: cipher.clone(),
+                                    statistics: JournalStatistics { encrypted: cipher.is_some(), ..Default::default() },
+                                    last_frame: None,
+                                    log_tx: log_tx.clone(),
+                                    last_snapshot: None,
+                                };
+                                header.start = now.to_rfc3339();
+                                header.configuration_checksum = match std::fs::read(&configuration_path) {
+                                    Ok(contents) => Some(format!("{:x}", md5::compute(contents))),
+                                    Err(error) => {
+                                        log::warn!("Could not read {}: {}", configuration_path.display(), error);
+                                        None
+                                    }
+                                };
+                                started.record(Event::Header(header));
+                                journal = Some(started);
                                 router_stream.set(router.right_stream());
                                 optitrack_stream.set(optitrack.right_stream());
+                                message_counts.clear();
+                                tails.clear();
+                                artifacts_dir = Some(artifacts);
                                 let _ = callback.send(Ok(()));
                             },
                             (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => {
@@ -122,20 +674,56 @@ pub async fn new(mut requests_rx: mpsc::Receiver<Action>,
                     Action::Stop => {
                         optitrack_stream.set(futures::stream::pending().left_stream());
                         router_stream.set(futures::stream::pending().left_stream());
-                        journal = None;
+                        /* push the final statistics before closing the journal */
+                        if let Some(mut journal) = journal.take() {
+                            journal.record(Event::Timeline(TimelineCategory::Phase, "Experiment stopped".to_owned(), Vec::new()));
+                            let tracking = journal.statistics.tracking.clone();
+                            if tracking.dropped > 0 {
+                                log::warn!("{} frames from the tracking system were dropped in {} gaps", tracking.dropped, tracking.gaps);
+                            }
+                            journal.record(Event::TrackingGaps(tracking));
+                            let _ = statistics_tx.send(journal.statistics());
+                        }
                     },
-                    Action::Record(event) => {
-                        let (start, writer) = journal.as_mut().unwrap();
-                        let entry = Entry {
-                            timestamp: Local::now()
-                                .signed_duration_since(*start)
-                                .num_milliseconds(),
-                            event
+                    Action::Record(event) => if let Some(journal) = journal.as_mut() {
+                        let output = match &event {
+                            Event::ARGoS(id, ARGoS::StandardOutput(data)) =>
+                                Some((id.clone(), false, tails.entry(id.clone()).or_default().push(false, data))),
+                            Event::ARGoS(id, ARGoS::StandardError(data)) =>
+                                Some((id.clone(), true, tails.entry(id.clone()).or_default().push(true, data))),
+                            _ => None
                         };
-                        if let Err(error) = serde_pickle::ser::to_writer(writer, &entry, true) {
-                            log::error!("Error writing entry {:?} to journal: {}", entry, error);
+                        journal.record(event);
+                        if let Some((id, error, lines)) = output {
+                            journal.output(&id, error, lines);
                         }
-                    }
+                    },
+                    Action::Subscribe(callback) => {
+                        let _ = callback.send(statistics_tx.subscribe());
+                    },
+                    Action::SubscribeLog(callback) => {
+                        let _ = callback.send(log_tx.subscribe());
+                    },
+                    Action::MessageCount(addr, callback) => {
+                        let _ = callback.send(message_counts.get(&addr).copied().unwrap_or(0));
+                    },
+                    Action::StoreArtifact(destination, path, contents, callback) => {
+                        let directory = match destination {
+                            Destination::Experiment(id) => artifacts_dir.as_ref()
+                                .map(|directory| directory.join(id))
+                                .ok_or(anyhow::anyhow!("No experiment has been started")),
+                            Destination::Directory(directory) => Ok(directory),
+                        };
+                        let result = directory.and_then(|directory| store_artifact(&directory, &path, &contents, cipher.as_ref()))
+                            .context(format!("Could not store {}", path.display()));
+                        if let Err(error) = &result {
+                            log::error!("{:#}", error);
+                        }
+                        let _ = callback.send(result);
+                    },
+                    Action::Excerpt(id, callback) => {
+                        let _ = callback.send(tails.get(&id).map(Tail::excerpt));
+                    },
                 }
             }
         }
@@ -143,6 +731,21 @@ pub async fn new(mut requests_rx: mpsc::Receiver<Action>,
     Ok(())
 }
 
+/// Writes a file that was downloaded from a robot into the given directory, the file is encrypted at rest
+/// like the journal
+fn store_artifact(directory: &Path, path: &Path, contents: &[u8], cipher: Option<&Cipher>) -> Result<()> {
+    /* the path is relative to the directory and must not escape it */
+    if !path.components().all(|component| matches!(component, Component::Normal(_))) {
+        anyhow::bail!("Invalid path");
+    }
+    let path = directory.join(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .context(format!("Could not create directory {}", parent.display()))?;
+    }
+    encryption::write(&path, contents, cipher)
+}
+
 async fn router(
     router_tx: &mpsc::Sender<router::Action>
 ) -> anyhow::Result<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>> {
@@ -166,7 +769,7 @@ async fn optitrack(
             .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates")));
     optitrack_updates.await
         .map(|updates| BroadcastStream::new(updates)
-            .map_ok(Event::TrackingSystem))
+            .map_ok(|(frame, updates, _)| Event::TrackingSystem { frame, updates }))
 }
 
 /* .bashrc
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+use shared::experiment::{library::{self, Bundle, Reference}, software::Software};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/* the file in the directory of the library that lists the stored bundles */
+const INDEX: &str = "library.json";
+
+pub enum Action {
+    /* the reference of the stored version is returned, which is the latest version if the files are unchanged */
+    Store(oneshot::Sender<anyhow::Result<Reference>>, String, Software),
+    Delete(oneshot::Sender<anyhow::Result<()>>, Reference),
+    Load(oneshot::Sender<anyhow::Result<Software>>, Reference),
+    /* the current bundles are returned with the receiver */
+    Subscribe(oneshot::Sender<(Vec<Bundle>, broadcast::Receiver<Vec<Bundle>>)>),
+}
+
+/// The directory in which the library is stored next to the configuration unless configured otherwise
+pub fn path(configuration: &Path) -> PathBuf {
+    configuration.with_extension("library")
+}
+
+fn load(directory: &Path) -> anyhow::Result<Vec<Bundle>> {
+    let path = directory.join(INDEX);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read(&path)
+        .context(format!("Could not read library {:?}", path))?;
+    serde_json::from_slice(&contents)
+        .context(format!("Could not parse library {:?}", path))
+}
+
+async fn save(directory: &Path, bundles: &[Bundle]) -> anyhow::Result<()> {
+    let path = directory.join(INDEX);
+    let contents = serde_json::to_vec_pretty(bundles)
+        .context("Could not serialize library")?;
+    tokio::fs::write(&path, contents).await
+        .context(format!("Could not write library {:?}", path))
+}
+
+/* the checksums of the files in the order of their names, as listed for a bundle */
+fn checksums(software: &Software) -> Vec<(String, String)> {
+    let mut checksums = software.checksums().into_iter()
+        .map(|(filename, checksum)| (filename, format!("{:x}", checksum)))
+        .collect::<Vec<_>>();
+    checksums.sort();
+    checksums
+}
+
+fn bundle_directory(directory: &Path, reference: &Reference) -> PathBuf {
+    directory.join(&reference.name).join(reference.version.to_string())
+}
+
+/// Writes the files of a new version of a bundle, the software must have passed the validation beforehand
+async fn store(directory: &Path, bundles: &[Bundle], name: String, software: Software) -> anyhow::Result<Bundle> {
+    if !library::valid_name(&name) || name == INDEX {
+        anyhow::bail!("\"{}\" is not a valid name, only letters, digits, '-', '_', and '.' can be used", name);
+    }
+    if software.0.is_empty() {
+        anyhow::bail!("The software does not contain any files");
+    }
+    if let Some((filename, error)) = software.validate().into_iter().next() {
+        return Err(match filename {
+            Some(filename) => anyhow::anyhow!("{}: {}", filename, error),
+            None => anyhow::anyhow!(error),
+        });
+    }
+    /* the files are written to the directory of the version, hence they must not contain a path */
+    if let Some((filename, _)) = software.0.iter().find(|(filename, _)| !library::valid_name(filename)) {
+        anyhow::bail!("\"{}\" is not a valid file name", filename);
+    }
+    let version = bundles.iter()
+        .filter(|bundle| bundle.name == name)
+        .map(|bundle| bundle.version)
+        .max()
+        .unwrap_or(0) + 1;
+    let checksums = checksums(&software);
+    let bundle = Bundle { name, version, stored: chrono::Local::now().to_rfc3339(), checksums };
+    let path = bundle_directory(directory, &bundle.reference());
+    tokio::fs::create_dir_all(&path).await
+        .context(format!("Could not create directory {:?}", path))?;
+    for (filename, contents) in &software.0 {
+        tokio::fs::write(path.join(filename), contents).await
+            .context(format!("Could not write {:?}", path.join(filename)))?;
+    }
+    Ok(bundle)
+}
+
+/// Stores the bundles of control software, which are shared by all clients, under the given directory. Each
+/// version of a bundle is kept in its own directory so that earlier versions can still be selected, e.g., to
+/// repeat an experiment
+pub async fn new(directory: PathBuf, mut requests: mpsc::Receiver<Action>) {
+    let mut bundles = match load(&directory) {
+        Ok(bundles) => bundles,
+        Err(error) => {
+            /* do not overwrite a library that could not be read */
+            log::error!("{:#}", error);
+            return;
+        }
+    };
+    let (updates_tx, _) = broadcast::channel(8);
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Store(callback, name, software) => {
+                /* storing the same files again does not create a new version */
+                let checksums = checksums(&software);
+                let latest = bundles.iter()
+                    .filter(|bundle| bundle.name == name)
+                    .max_by_key(|bundle| bundle.version);
+                let result = match latest {
+                    Some(latest) if latest.checksums == checksums => Ok(latest.reference()),
+                    _ => match store(&directory, &bundles, name, software).await {
+                        Ok(bundle) => {
+                            let reference = bundle.reference();
+                            let mut updated = bundles.clone();
+                            updated.push(bundle);
+                            updated.sort_by(|a, b| a.reference().cmp(&b.reference()));
+                            save(&directory, &updated).await.map(|_| {
+                                bundles = updated;
+                                let _ = updates_tx.send(bundles.clone());
+                                reference
+                            })
+                        },
+                        Err(error) => Err(error),
+                    }
+                };
+                let _ = callback.send(result.context("Could not store software in library"));
+            },
+            Action::Delete(callback, reference) => {
+                let mut updated = bundles.clone();
+                updated.retain(|bundle| bundle.reference() != reference);
+                let result = match updated.len() == bundles.len() {
+                    true => Err(anyhow::anyhow!("{} is not in the library", reference)),
+                    false => save(&directory, &updated).await,
+                };
+                if result.is_ok() {
+                    bundles = updated;
+                    let _ = updates_tx.send(bundles.clone());
+                    let path = bundle_directory(&directory, &reference);
+                    if let Err(error) = tokio::fs::remove_dir_all(&path).await {
+                        log::warn!("Could not remove {:?}: {}", path, error);
+                    }
+                }
+                let _ = callback.send(result.context(format!("Could not delete {} from library", reference)));
+            },
+            Action::Load(callback, reference) => {
+                let result = match bundles.iter().find(|bundle| bundle.reference() == reference) {
+                    Some(bundle) => {
+                        let path = bundle_directory(&directory, &reference);
+                        let mut software = Software::default();
+                        let mut result = Ok(());
+                        for (filename, _) in &bundle.checksums {
+                            match tokio::fs::read(path.join(filename)).await {
+                                Ok(contents) => software.add(filename.as_str(), contents),
+                                Err(error) => {
+                                    result = Err(anyhow::anyhow!(error)
+                                        .context(format!("Could not read {:?}", path.join(filename))));
+                                    break;
+                                }
+                            }
+                        }
+                        result.map(|_| software)
+                    },
+                    None => Err(anyhow::anyhow!("{} is not in the library", reference)),
+                };
+                let _ = callback.send(result);
+            },
+            Action::Subscribe(callback) => {
+                let _ = callback.send((bundles.clone(), updates_tx.subscribe()));
+            },
+        }
+    }
+}
@@ -1,22 +1,73 @@
-use std::{net::{Ipv4Addr, SocketAddr}, path::{Path, PathBuf}};
+use std::{collections::{BTreeMap, HashMap}, net::{Ipv4Addr, SocketAddr, SocketAddrV4}, path::{Path, PathBuf}, time::Duration};
 use ipnet::Ipv4Net;
 use structopt::StructOpt;
 use anyhow::Context;
-use tokio::sync::mpsc;
+use futures::{FutureExt, TryFutureExt};
+use tokio::sync::{mpsc, watch};
 
+mod alerts;
+mod ambient;
 mod arena;
+mod encryption;
+mod geofence;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod health;
+mod idempotency;
+mod identification;
 mod robot;
 mod network;
 mod webui;
 mod optitrack;
 mod journal;
+mod library;
 mod router;
+mod instrument;
+mod configuration;
+mod infrastructure;
+mod inventory;
+mod pairing;
+mod profiles;
+mod replay;
+mod replication;
+mod repository;
+mod session;
+mod syntax;
+mod telemetry;
+mod trace;
+
+use network::fernbedienung_ext::{ResourceLimits, Watchdog};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "supervisor", about = "A supervisor for experiments with swarms of robots")]
 struct Options {
     #[structopt(short = "c", long = "configuration")]
     config: PathBuf,
+    /// Replays a journal in the user interface instead of connecting to the robots and the tracking system
+    #[structopt(long = "replay")]
+    replay: Option<PathBuf>,
+    /// The speed at which the journal is replayed relative to the original timing
+    #[structopt(long = "speed", default_value = "1.0")]
+    speed: f64,
+    /// Reloads the robots whenever the configuration file is modified
+    #[structopt(long = "watch")]
+    watch: bool,
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Writes the inventory of the robots to standard output and exits
+    ExportInventory {
+        #[structopt(short = "f", long = "format", default_value = "csv", possible_values = &["csv", "json"])]
+        format: inventory::Format,
+    },
+    /// Writes the contents of a file of a journal to standard output and exits, an encrypted file is decrypted
+    /// with the key from the configuration
+    ExportJournal {
+        path: PathBuf,
+    },
 }
 
 #[tokio::main]
@@ -24,49 +75,313 @@ async fn main() -> anyhow::Result<()> {
     /* initialize the logger */
     let environment = env_logger::Env::default().default_filter_or("supervisor=info");
     env_logger::Builder::from_env(environment).format_timestamp_millis().init();
+    /* initialize the instrumentation (tokio-console) if enabled */
+    instrument::init();
     /* parse the configuration file */
     let options = Options::from_args();
+    let configuration = parse_config(&options.config)
+        .context(format!("Could not parse configuration file {:?}", options.config))?;
+    let inventory_path = inventory::Inventory::path(&options.config);
+    if let Some(Command::ExportInventory { format }) = options.command {
+        let mut inventory = inventory::Inventory::load(&inventory_path)?;
+        inventory.configure(&configuration.builderbots, &configuration.drones, &configuration.pipucks);
+        print!("{}", inventory.export(format)?);
+        return Ok(());
+    }
+    /* the key is loaded before a journal is exported or replayed since these may be encrypted */
+    let journal_cipher = configuration.journal_key.as_ref()
+        .map(|path| encryption::Cipher::load(Path::new(path)))
+        .transpose()
+        .context("Could not load the key for encrypting the journal")?;
+    if let Some(Command::ExportJournal { path }) = &options.command {
+        let contents = std::fs::read(path)
+            .context(format!("Could not read {}", path.display()))?;
+        let contents = encryption::reveal(path, contents, journal_cipher.as_ref())?;
+        std::io::Write::write_all(&mut std::io::stdout(), &contents)?;
+        return Ok(());
+    }
+    let exported_configuration = configuration.export();
     let Configuration {
         optitrack_config,
+        infrastructure_config,
+        ambient_config,
         router_socket,
+        router_topology,
+        router_range,
         webui_socket,
+        webui_advertise,
+        grpc_socket,
         robot_network,
+        probing: probe_configuration,
+        tls_config,
+        journal_format,
+        /* the key has already been loaded */
+        journal_key: _,
+        library,
+        telemetry,
+        checklist,
+        /* the tags are only used by the web interface, which receives them with the configuration */
+        tags: _,
+        camera_shutdown,
+        polling,
+        heartbeat,
+        targeted_probing,
+        return_to_base,
+        geofence,
+        battery_alerts,
+        replication,
+        access,
         builderbots,
         drones,
         pipucks,
-    } = parse_config(&options.config)
-            .context(format!("Could not parse configuration file {:?}", options.config))?;
+        simulated_robots,
+        ros_robots,
+        groups,
+        builderbot_settings,
+        drone_settings,
+        pipuck_settings,
+        xbee_profile,
+        xbee_configurations,
+    } = configuration;
+    /* a journal is loaded before the tasks are created since it replaces the robots of the configuration */
+    let replay = options.replay.as_ref()
+        .map(|path| replay::Journal::load(path, journal_cipher.as_ref()))
+        .transpose()
+        .context("Could not load journal for replaying")?;
+    if !(options.speed > 0.0) {
+        return Err(anyhow::anyhow!("The speed of the replay must be positive"));
+    }
+    let (builderbots, drones, pipucks) = match replay.as_ref() {
+        Some(journal) => {
+            log::info!("Replaying journal {}", journal.start.as_deref().unwrap_or("without start time"));
+            (journal.builderbots.clone(), journal.drones.clone(), journal.pipucks.clone())
+        },
+        None => (builderbots, drones, pipucks),
+    };
     /* channels for task communication */
     let (journal_requests_tx, journal_requests_rx) = mpsc::channel(8);
     let (arena_requests_tx, arena_requests_rx) = mpsc::channel(8);
     let (optitrack_requests_tx, optitrack_requests_rx) = mpsc::channel(8);
     let (router_requests_tx, router_requests_rx) = mpsc::channel(8);
-    /* create journal task */
-    let journal_task =
-        journal::new(journal_requests_rx,
-                     optitrack_requests_tx.clone(),
-                     router_requests_tx);
+    let (configuration_requests_tx, configuration_requests_rx) = mpsc::channel(8);
+    let (infrastructure_requests_tx, infrastructure_requests_rx) = mpsc::channel(8);
+    let (inventory_requests_tx, inventory_requests_rx) = mpsc::channel(8);
+    let (ambient_requests_tx, ambient_requests_rx) = mpsc::channel(8);
+    let (identification_requests_tx, identification_requests_rx) = mpsc::channel(8);
+    let (pairing_requests_tx, pairing_requests_rx) = mpsc::channel(8);
+    let (profiles_requests_tx, profiles_requests_rx) = mpsc::channel(8);
+    let (library_requests_tx, library_requests_rx) = mpsc::channel(8);
+    let (alerts_requests_tx, alerts_requests_rx) = mpsc::channel(8);
+    let (replication_requests_tx, replication_requests_rx) = mpsc::channel(8);
+    let (telemetry_requests_tx, telemetry_requests_rx) = mpsc::channel(8);
+    /* the spans of the traces are recorded from synchronous code, hence this channel is unbounded */
+    let (trace_requests_tx, trace_requests_rx) = mpsc::unbounded_channel();
+    let (idempotency_requests_tx, idempotency_requests_rx) = mpsc::channel(8);
+    let (health_requests_tx, health_requests_rx) = mpsc::channel(8);
+    let (session_requests_tx, session_requests_rx) = mpsc::channel(8);
+    /* create the task that keeps the health of the supervised subsystems */
+    let health_task = instrument::spawn("health", health::new(health_requests_rx));
+    /* a replay does not take part in the replication */
+    let replication = replication.filter(|_| replay.is_none());
+    /* a standby supervisor leaves the robots to the primary until it takes over, while a replay leaves them alone.
+       A primary also starts passive until it is known that no standby has taken over */
+    let standby = replication.is_some() || replay.is_some();
+    let probing = match standby {
+        true => network::Probing::Paused(Vec::new()),
+        false => network::Probing::Enabled,
+    };
+    let (probing_tx, probing_rx) = watch::channel(probing);
+    /* the arena pauses the discovery of robots while an experiment is running */
+    let (discovery_tx, discovery_rx) = watch::channel(network::Discovery::Enabled);
+    /* create journal and optitrack tasks, which are both replaced by the replay of a journal, these tasks and
+       the message router are restarted when they fail so that a fault does not bring down the supervisor */
+    let (journal_task, optitrack_task) = match replay {
+        Some(journal) => {
+            let replay_task = instrument::spawn("replay",
+                replay::new(journal, options.speed, arena_requests_tx.clone(), journal_requests_rx, optitrack_requests_rx));
+            (replay_task, instrument::spawn("optitrack", futures::future::pending::<anyhow::Result<()>>()))
+        },
+        None => {
+            let configuration_path = options.config.clone();
+            let optitrack_tx = optitrack_requests_tx.clone();
+            let router_tx = router_requests_tx.clone();
+            let journal_task = instrument::spawn("journal",
+                health::supervise("journal", health_requests_tx.clone(), journal_requests_rx, move |requests_rx| {
+                    journal::new(requests_rx,
+                                 configuration_path.clone(),
+                                 journal_format,
+                                 journal_cipher.clone(),
+                                 optitrack_tx.clone(),
+                                 router_tx.clone()).boxed()
+                }));
+            let optitrack_config = optitrack_config
+                .ok_or(anyhow::anyhow!("Optitrack configuration must be specified"))?;
+            let optitrack_task = instrument::spawn("optitrack",
+                health::supervise("optitrack", health_requests_tx.clone(), optitrack_requests_rx, move |requests_rx| {
+                    optitrack::new(optitrack_config.clone(), requests_rx).boxed()
+                }));
+            (journal_task, optitrack_task)
+        },
+    };
+    /* the MAC addresses of the configured robots, which are the only ones probed with targeted probing, and
+       their fixed addresses, which are the only addresses probed for them with targeted probing */
+    let (robots_tx, robots_rx) = watch::channel(network::Robots::new(&builderbots, &drones, &pipucks));
     /* create arena task */
-    let arena_task =
+    let arena_task = instrument::spawn("arena",
         arena::new(arena_requests_rx,
-                   journal_requests_tx,
+                   journal_requests_tx.clone(),
+                   probing_tx,
+                   discovery_tx,
+                   probe_configuration.pause_discovery,
+                   robots_tx,
+                   pairing_requests_tx.clone(),
+                   alerts_requests_tx.clone(),
+                   library_requests_tx.clone(),
+                   standby,
+                   checklist,
+                   camera_shutdown,
+                   polling,
+                   heartbeat,
+                   return_to_base,
+                   router_requests_tx.clone(),
                    builderbots,
                    drones,
-                   pipucks);
+                   pipucks,
+                   simulated_robots,
+                   ros_robots,
+                   groups,
+                   builderbot_settings,
+                   drone_settings,
+                   pipuck_settings,
+                   xbee_profile,
+                   xbee_configurations));
+    /* create configuration task */
+    let configuration_task = instrument::spawn("configuration",
+        configuration::new(options.config.clone(),
+                           exported_configuration,
+                           options.watch,
+                           options.replay.is_some(),
+                           arena_requests_tx.clone(),
+                           configuration_requests_rx));
     /* create network task */
-    let network_task = network::new(robot_network, arena_requests_tx.clone());
+    let tls = tls_config
+        .map(|config| network::fernbedienung::TlsConnector::new(
+            Path::new(&config.authority), Path::new(&config.certificate), Path::new(&config.key)))
+        .transpose()
+        .context("Could not configure TLS for the fernbedienung service")?;
+    let network_task = instrument::spawn("network",
+        network::new(robot_network, arena_requests_tx.clone(), probing_rx, discovery_rx, tls, heartbeat, probe_configuration, targeted_probing, robots_rx));
     /* create message router task */
     let router_socket = router_socket
         .ok_or(anyhow::anyhow!("A socket for the message router must be provided"))?;
-    let router_task = router::new(router_socket, router_requests_rx);
-    /* create optitrack task */
-    let optitrack_config = optitrack_config
-        .ok_or(anyhow::anyhow!("Optitrack configuration must be specified"))?;
-    let optitrack_task = optitrack::new(optitrack_config, optitrack_requests_rx);
+    let arena_tx = arena_requests_tx.clone();
+    let optitrack_tx = optitrack_requests_tx.clone();
+    let router_task = instrument::spawn("router",
+        health::supervise("router", health_requests_tx.clone(), router_requests_rx, move |requests_rx| {
+            router::new(router_socket, router_topology.clone(), router_range,
+                requests_rx, arena_tx.clone(), optitrack_tx.clone()).err_into().boxed()
+        }));
+    /* create infrastructure monitoring task */
+    let infrastructure_task = instrument::spawn("infrastructure",
+        infrastructure::new(infrastructure_config, infrastructure_requests_rx));
+    /* create geofence task, the arena decides whether to intervene when a robot leaves the boundary */
+    if let Some(geofence) = geofence {
+        let optitrack_requests_tx = optitrack_requests_tx.clone();
+        let arena_requests_tx = arena_requests_tx.clone();
+        instrument::spawn("geofence", async move {
+            if let Err(error) = geofence::new(geofence, optitrack_requests_tx, arena_requests_tx).await {
+                log::error!("Geofence task aborted: {:#}", error);
+            }
+        });
+    }
+    /* create ambient sensor task */
+    let ambient_task = instrument::spawn("ambient",
+        ambient::new(ambient_config, ambient_requests_rx, journal_requests_tx.clone()));
+    /* create the task that identifies the rigid bodies of the robots */
+    let identification_task = instrument::spawn("identification",
+        identification::new(arena_requests_tx.clone(), optitrack_requests_tx.clone(), identification_requests_rx));
+    /* create the task that holds the unassigned devices so that they can be paired into drones */
+    let pairing_task = instrument::spawn("pairing", pairing::new(pairing_requests_rx, alerts_requests_tx.clone()));
+    /* create the task that stores the experiment profiles */
+    let profiles_task = instrument::spawn("profiles",
+        profiles::new(profiles::path(&options.config), profiles_requests_rx));
+    /* create the task that stores the library of control software */
+    let library_directory = library.map_or_else(|| library::path(&options.config), PathBuf::from);
+    let library_task = instrument::spawn("library", library::new(library_directory, library_requests_rx));
+    /* create inventory task */
+    let inventory_task = instrument::spawn("inventory",
+        inventory::new(inventory_path, arena_requests_tx.clone(), inventory_requests_rx));
+    /* create the task that keeps the recent readings of the robots */
+    let telemetry_task = instrument::spawn("telemetry", telemetry::new(telemetry, arena_requests_tx.clone(), telemetry_requests_rx));
+    /* create the task that alerts the operators about low batteries */
+    let alerts_task = instrument::spawn("alerts",
+        alerts::new(battery_alerts, arena_requests_tx.clone(), alerts_requests_rx));
+    /* create the task that mirrors the arena to the standby supervisors or takes over from the primary */
+    let replication_task = instrument::spawn("replication",
+        replication::new(replication, arena_requests_tx.clone(), replication_requests_rx));
+    /* create the task that answers retried requests with the outcome of the original request */
+    let idempotency_task = instrument::spawn("idempotency",
+        idempotency::new(idempotency_requests_tx.clone(), idempotency_requests_rx));
+    /* create the task that keeps the sessions of the clients of the web interface */
+    let session_task = instrument::spawn("session", session::new(session_requests_rx));
+    /* create the task that keeps the traces of the recent requests */
+    let trace_task = instrument::spawn("trace", trace::new(trace_requests_rx));
+    /* the channels to the components that handle the requests of the web interface and the gRPC interface */
+    let components = webui::Components {
+        arena_tx: arena_requests_tx.clone(),
+        journal_tx: journal_requests_tx,
+        configuration_tx: configuration_requests_tx,
+        inventory_tx: inventory_requests_tx,
+        router_tx: router_requests_tx,
+        identification_tx: identification_requests_tx,
+        pairing_tx: pairing_requests_tx,
+        profiles_tx: profiles_requests_tx,
+        library_tx: library_requests_tx,
+        replication_tx: replication_requests_tx,
+        telemetry_tx: telemetry_requests_tx,
+        trace_tx: trace_requests_tx,
+    };
+    /* create the gRPC interface, which handles the same requests as the web interface */
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_socket) = grpc_socket {
+        let components = components.clone();
+        let optitrack_requests_tx = optitrack_requests_tx.clone();
+        let access = access.clone();
+        instrument::spawn("grpc", async move {
+            if let Err(error) = grpc::new(grpc_socket, components, optitrack_requests_tx, access).await {
+                log::error!("gRPC task aborted: {:#}", error);
+            }
+        });
+    }
+    #[cfg(not(feature = "grpc"))]
+    if grpc_socket.is_some() {
+        log::warn!("The gRPC interface is configured but the supervisor was built without the grpc feature");
+    }
     /* create the backend task */
     let webui_socket = webui_socket
         .ok_or(anyhow::anyhow!("A socket for the web interface must be provided"))?;
-    let webui_task = webui::new(webui_socket, arena_requests_tx.clone(), optitrack_requests_tx.clone());
+    /* advertise the web interface on the local network so that it can be found by the desktop client */
+    let _advertisement = match webui_advertise {
+        Some(name) => match webui::advertise(&name, webui_socket, robot_network) {
+            Ok(daemon) => Some(daemon),
+            Err(error) => {
+                log::warn!("Could not advertise the web interface: {:#}", error);
+                None
+            }
+        },
+        None => None,
+    };
+    let webui_task = instrument::spawn("webui",
+        webui::new(webui_socket,
+                   components,
+                   optitrack_requests_tx.clone(),
+                   infrastructure_requests_tx,
+                   ambient_requests_tx,
+                   alerts_requests_tx,
+                   idempotency_requests_tx,
+                   health_requests_tx,
+                   session_requests_tx,
+                   access));
 
     /* listen for the ctrl-c shutdown signal */
     let sigint_task = tokio::signal::ctrl_c();
@@ -78,6 +393,21 @@ async fn main() -> anyhow::Result<()> {
     tokio::pin!(sigint_task);
     tokio::pin!(router_task);
     tokio::pin!(optitrack_task);
+    tokio::pin!(configuration_task);
+    tokio::pin!(infrastructure_task);
+    tokio::pin!(inventory_task);
+    tokio::pin!(ambient_task);
+    tokio::pin!(identification_task);
+    tokio::pin!(pairing_task);
+    tokio::pin!(profiles_task);
+    tokio::pin!(library_task);
+    tokio::pin!(alerts_task);
+    tokio::pin!(telemetry_task);
+    tokio::pin!(replication_task);
+    tokio::pin!(idempotency_task);
+    tokio::pin!(health_task);
+    tokio::pin!(session_task);
+    tokio::pin!(trace_task);
     /* no point in implementing automatic browser opening */
     /* https://bugzilla.mozilla.org/show_bug.cgi?id=1512438 */
     let server_addr = format!("http://{}/", webui_socket);
@@ -88,18 +418,36 @@ async fn main() -> anyhow::Result<()> {
     
     tokio::select! {
         result = &mut optitrack_task => match result {
-            Ok(_) => log::info!("Optitrack task completed"),
-            Err(error) => log::warn!("Optitrack task aborted: {}", error)
+            Ok(Ok(_)) => log::info!("Optitrack task completed"),
+            Ok(Err(error)) => log::warn!("Optitrack task aborted: {}", error),
+            Err(error) => log::warn!("Optitrack task failed: {}", error),
         },
         _ = &mut arena_task => log::info!("Arena task completed"),
         result = &mut journal_task => match result {
-            Ok(_) => log::info!("Journal task completed"),
-            Err(error) => log::warn!("Journal task aborted: {}", error)
+            Ok(Ok(_)) => log::info!("Journal task completed"),
+            Ok(Err(error)) => log::warn!("Journal task aborted: {}", error),
+            Err(error) => log::warn!("Journal task failed: {}", error),
         },
         _ = &mut network_task => log::info!("Network task completed"),
+        _ = &mut configuration_task => log::info!("Configuration task completed"),
+        _ = &mut infrastructure_task => log::info!("Infrastructure task completed"),
+        _ = &mut inventory_task => log::info!("Inventory task completed"),
+        _ = &mut ambient_task => log::info!("Ambient task completed"),
+        _ = &mut identification_task => log::info!("Identification task completed"),
+        _ = &mut pairing_task => log::info!("Pairing task completed"),
+        _ = &mut profiles_task => log::info!("Profiles task completed"),
+        _ = &mut library_task => log::info!("Library task completed"),
+        _ = &mut alerts_task => log::info!("Alerts task completed"),
+        _ = &mut telemetry_task => log::info!("Telemetry task completed"),
+        _ = &mut replication_task => log::info!("Replication task completed"),
+        _ = &mut idempotency_task => log::info!("Idempotency task completed"),
+        _ = &mut health_task => log::info!("Health task completed"),
+        _ = &mut session_task => log::info!("Session task completed"),
+        _ = &mut trace_task => log::info!("Trace task completed"),
         result = &mut router_task => match result {
-            Ok(_) => log::info!("Router task completed"),
-            Err(error) => log::warn!("Router task aborted: {}", error)
+            Ok(Ok(_)) => log::info!("Router task completed"),
+            Ok(Err(error)) => log::warn!("Router task aborted: {}", error),
+            Err(error) => log::warn!("Router task failed: {}", error),
         },
         _ = &mut webui_task => log::info!("WebUI task completed"),
         _ = &mut sigint_task => {
@@ -116,17 +464,206 @@ async fn main() -> anyhow::Result<()> {
 #[derive(Debug)]
 struct Configuration {
     optitrack_config: Option<optitrack::Configuration>,
+    infrastructure_config: Option<infrastructure::Configuration>,
+    ambient_config: Option<ambient::Configuration>,
     router_socket: Option<SocketAddr>,
+    /* the communication topology that the message router starts with */
+    router_topology: Option<shared::router::Topology>,
+    /* the maximum distance in meters between two tracked robots for their messages to be forwarded */
+    router_range: Option<f32>,
     webui_socket: Option<SocketAddr>,
+    /* the name under which the web interface is advertised via mDNS */
+    webui_advertise: Option<String>,
+    /* the socket of the optional gRPC interface */
+    grpc_socket: Option<SocketAddr>,
     robot_network: Ipv4Net,
+    /* the timeouts, the retry delays, and the maximum number of simultaneous probes of the robot network */
+    probing: shared::configuration::ProbeConfiguration,
+    /* the files for authenticating the fernbedienung service via mutual TLS */
+    tls_config: Option<shared::configuration::Tls>,
+    /* the format in which the journal of each experiment is recorded */
+    journal_format: shared::configuration::JournalFormat,
+    /* the file with the key with which the journal is encrypted at rest */
+    journal_key: Option<String>,
+    /* the directory in which the library of control software is stored, next to the configuration if None */
+    library: Option<String>,
+    /* how long the readings of the robots are kept in memory for the web interface */
+    telemetry: shared::configuration::Telemetry,
+    /* the items that the operators must acknowledge before an experiment can be started */
+    checklist: Vec<String>,
+    /* the tags that are offered when annotating the timeline, which are only used by the web interface */
+    tags: Vec<String>,
+    /* the policy for disabling the camera streams while an experiment is running */
+    camera_shutdown: Option<shared::configuration::CameraShutdown>,
+    /* the factors by which the background polling of the robots is reduced while an experiment is running */
+    polling: shared::configuration::PollingFactors,
+    /* the keepalive of the connections to the Fernbedienung service */
+    heartbeat: shared::configuration::Heartbeat,
+    /* whether only the addresses that are leased to the configured robots are probed */
+    targeted_probing: Option<shared::configuration::TargetedProbing>,
+    /* the flight mode of the drones and the command for the ground robots when they return to base */
+    return_to_base: shared::configuration::ReturnToBase,
+    /* the boundary that the robots must not leave during an experiment */
+    geofence: Option<shared::configuration::Geofence>,
+    /* the thresholds and the policy for alerting the operators about low batteries */
+    battery_alerts: Option<shared::configuration::BatteryAlerts>,
+    /* whether this supervisor is the primary or mirrors a primary as a standby supervisor */
+    replication: Option<shared::configuration::Replication>,
+    /* the users of the web interface and the permissions of their roles */
+    access: Option<shared::access::Access>,
     builderbots: Vec<robot::builderbot::Descriptor>,
     drones: Vec<robot::drone::Descriptor>,
     pipucks: Vec<robot::pipuck::Descriptor>,
+    simulated_robots: Vec<shared::simulation::Descriptor>,
+    /* the ROS-based robots that are controlled via their rosbridge websockets */
+    ros_robots: Vec<shared::ros::Descriptor>,
+    /* named groups of robots to which actions can be issued at once */
+    groups: Vec<shared::group::Group>,
+    /* the resource limits, artifacts, environment, and watchdog of each robot type */
+    builderbot_settings: arena::RobotSettings,
+    drone_settings: arena::RobotSettings,
+    pipuck_settings: arena::RobotSettings,
+    /* the expected values of the parameters of the Xbees in hexadecimal */
+    xbee_profile: BTreeMap<String, String>,
+    /* named sets of parameters that are written to the Xbees of the drones, in the order given */
+    xbee_configurations: BTreeMap<String, Vec<(String, String)>>,
+}
+
+impl Configuration {
+    /// Exports the configuration so that it can be displayed and edited in the web interface
+    fn export(&self) -> shared::configuration::Configuration {
+        let optitrack = self.optitrack_config.as_ref().map(|config| shared::configuration::Optitrack {
+            version: config.version.to_string(),
+            bind_addr: config.bind_addr,
+            bind_port: config.bind_port,
+            multicast_addr: config.multicast_addr,
+            iface_addr: config.iface_addr,
+            server_addr: config.command_addr.map(|addr| *addr.ip()),
+            command_port: config.command_addr
+                .map(|addr| addr.port())
+                .filter(|&port| port != optitrack::DEFAULT_COMMAND_PORT),
+            record: config.record.map(|record| record.as_secs()),
+        });
+        let limits = [("builderbot", &self.builderbot_settings.limits), ("drone", &self.drone_settings.limits), ("pipuck", &self.pipuck_settings.limits)]
+            .iter()
+            .filter(|(_, limits)| limits.nice.is_some() || limits.ionice_class.is_some() || limits.ionice_level.is_some() ||
+                limits.memory_max.is_some() || limits.cpu_quota.is_some())
+            .map(|(robot, limits)| shared::configuration::ResourceLimits {
+                robot: robot.to_string(),
+                nice: limits.nice,
+                ionice_class: limits.ionice_class,
+                ionice_level: limits.ionice_level,
+                memory_max: limits.memory_max.clone(),
+                cpu_quota: limits.cpu_quota.clone(),
+            })
+            .collect();
+        let artifacts = [("builderbot", &self.builderbot_settings.artifacts), ("drone", &self.drone_settings.artifacts), ("pipuck", &self.pipuck_settings.artifacts)]
+            .iter()
+            .flat_map(|(robot, patterns)| patterns.iter()
+                .map(move |pattern| shared::configuration::ArtifactPattern {
+                    robot: robot.to_string(),
+                    pattern: pattern.clone(),
+                }))
+            .collect();
+        let environment = [("builderbot", &self.builderbot_settings.environment), ("drone", &self.drone_settings.environment), ("pipuck", &self.pipuck_settings.environment)]
+            .iter()
+            .flat_map(|(robot, variables)| variables.iter()
+                .map(move |(name, value)| shared::configuration::EnvironmentVariable {
+                    robot: robot.to_string(),
+                    name: name.clone(),
+                    value: value.clone(),
+                }))
+            .collect();
+        let watchdogs = [("builderbot", &self.builderbot_settings.watchdog), ("drone", &self.drone_settings.watchdog), ("pipuck", &self.pipuck_settings.watchdog)]
+            .iter()
+            .filter_map(|(robot, watchdog)| watchdog.as_ref()
+                .map(|watchdog| shared::configuration::Watchdog {
+                    robot: robot.to_string(),
+                    interval: watchdog.interval.as_secs(),
+                    attempts: watchdog.attempts,
+                    service: watchdog.service.clone(),
+                }))
+            .collect();
+        let infrastructure = self.infrastructure_config.as_ref().map(|config| shared::configuration::Infrastructure {
+            community: config.community.clone(),
+            interval: config.interval.as_secs(),
+            access_points: config.access_points.iter()
+                .map(|access_point| shared::configuration::AccessPoint {
+                    addr: access_point.addr,
+                    station_oid: Some(access_point.station_oid.clone())
+                        .filter(|oid| oid != infrastructure::DEFAULT_STATION_OID),
+                })
+                .collect(),
+            switches: config.switches.clone(),
+        });
+        let ambient = self.ambient_config.as_ref().map(|config| shared::configuration::Ambient {
+            interval: config.interval.as_secs(),
+            sensors: config.sensors.iter()
+                .map(|sensor| {
+                    let (url, device) = match &sensor.source {
+                        ambient::Source::Http(url) => (Some(url.clone()), None),
+                        ambient::Source::Serial(device) => (None, Some(device.to_string_lossy().into_owned())),
+                    };
+                    shared::configuration::AmbientSensor {
+                        name: sensor.name.clone(),
+                        unit: sensor.unit.clone(),
+                        url,
+                        device,
+                    }
+                })
+                .collect(),
+        });
+        shared::configuration::Configuration {
+            router_socket: self.router_socket,
+            router_topology: self.router_topology.clone(),
+            router_range: self.router_range,
+            webui_socket: self.webui_socket,
+            webui_advertise: self.webui_advertise.clone(),
+            grpc_socket: self.grpc_socket,
+            optitrack,
+            infrastructure,
+            ambient,
+            robot_network: self.robot_network.to_string(),
+            probing: self.probing,
+            tls: self.tls_config.clone(),
+            journal_format: self.journal_format,
+            journal_key: self.journal_key.clone(),
+            library: self.library.clone(),
+            telemetry: self.telemetry.clone(),
+            checklist: self.checklist.clone(),
+            tags: self.tags.clone(),
+            camera_shutdown: self.camera_shutdown.clone(),
+            polling: self.polling,
+            heartbeat: self.heartbeat,
+            targeted_probing: self.targeted_probing.clone(),
+            return_to_base: self.return_to_base.clone(),
+            geofence: self.geofence.clone(),
+            battery_alerts: self.battery_alerts.clone(),
+            replication: self.replication.clone(),
+            access: self.access.clone(),
+            builderbots: self.builderbots.clone(),
+            drones: self.drones.clone(),
+            pipucks: self.pipucks.clone(),
+            simulated_robots: self.simulated_robots.clone(),
+            ros_robots: self.ros_robots.clone(),
+            groups: self.groups.clone(),
+            limits,
+            artifacts,
+            environment,
+            watchdogs,
+            xbee_profile: self.xbee_profile.clone(),
+            xbee_configurations: self.xbee_configurations.clone(),
+        }
+    }
 }
 
 fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
     let config = std::fs::read_to_string(config)?;
-    let tree = roxmltree::Document::parse(&config)?;
+    parse_config_str(&config)
+}
+
+fn parse_config_str(config: &str) -> anyhow::Result<Configuration> {
+    let tree = roxmltree::Document::parse(config)?;
     let configuration = tree
         .descendants()
         .find(|node| node.tag_name().name() == "configuration")
@@ -166,18 +703,131 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
                     .parse::<Ipv4Addr>()
                     .context("Could not parse attribute \"iface_addr\" in <optitrack>"))
                 .unwrap_or(Ok(Ipv4Addr::UNSPECIFIED))?;
-            Ok(optitrack::Configuration { version, bind_addr, bind_port, multicast_addr, iface_addr })
+            let command_port = node
+                .attribute("command_port")
+                .map(|port| port
+                    .parse::<u16>()
+                    .context("Could not parse attribute \"command_port\" in <optitrack>"))
+                .unwrap_or(Ok(optitrack::DEFAULT_COMMAND_PORT))?;
+            let command_addr = node
+                .attribute("server_addr")
+                .map(|addr| addr
+                    .parse::<Ipv4Addr>()
+                    .context("Could not parse attribute \"server_addr\" in <optitrack>"))
+                .transpose()?
+                .map(|addr| SocketAddrV4::new(addr, command_port));
+            let record = node
+                .attribute("record")
+                .map(|record| record
+                    .parse::<u64>()
+                    .map(Duration::from_secs)
+                    .context("Could not parse attribute \"record\" in <optitrack>"))
+                .transpose()?;
+            Ok(optitrack::Configuration { version, bind_addr, bind_port, multicast_addr, iface_addr, command_addr, record })
+        })
+        .transpose()?;
+    let infrastructure_config = supervisor
+        .descendants()
+        .find(|node| node.tag_name().name() == "infrastructure")
+        .map(|node| -> anyhow::Result<infrastructure::Configuration> {
+            let community = node
+                .attribute("community")
+                .unwrap_or("public")
+                .to_owned();
+            let interval = node
+                .attribute("interval")
+                .map(|interval| interval
+                    .parse::<u64>()
+                    .context("Could not parse attribute \"interval\" in <infrastructure>"))
+                .unwrap_or(Ok(5))?;
+            if interval == 0 {
+                return Err(anyhow::anyhow!("Attribute \"interval\" in <infrastructure> must be positive"));
+            }
+            let access_points = node.children()
+                .filter(|node| node.tag_name().name() == "access_point")
+                .map(|node| -> anyhow::Result<infrastructure::AccessPoint> {
+                    Ok(infrastructure::AccessPoint {
+                        addr: node.attribute("addr")
+                            .ok_or(anyhow::anyhow!("Could not find attribute \"addr\" in <access_point>"))?
+                            .parse()
+                            .context("Could not parse attribute \"addr\" in <access_point>")?,
+                        station_oid: node.attribute("station_oid")
+                            .unwrap_or(infrastructure::DEFAULT_STATION_OID)
+                            .trim_start_matches('.')
+                            .to_owned(),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let switches = node.children()
+                .filter(|node| node.tag_name().name() == "switch")
+                .map(|node| node.attribute("addr")
+                    .ok_or(anyhow::anyhow!("Could not find attribute \"addr\" in <switch>"))?
+                    .parse::<Ipv4Addr>()
+                    .context("Could not parse attribute \"addr\" in <switch>"))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(infrastructure::Configuration {
+                community,
+                interval: Duration::from_secs(interval),
+                access_points,
+                switches,
+            })
         })
         .transpose()?;
-    let webui_socket = supervisor
+    let ambient_config = supervisor
         .descendants()
-        .find(|node| node.tag_name().name() == "webui")
+        .find(|node| node.tag_name().name() == "ambient")
+        .map(|node| -> anyhow::Result<ambient::Configuration> {
+            let interval = node
+                .attribute("interval")
+                .map(|interval| interval
+                    .parse::<u64>()
+                    .context("Could not parse attribute \"interval\" in <ambient>"))
+                .unwrap_or(Ok(10))?;
+            if interval == 0 {
+                return Err(anyhow::anyhow!("Attribute \"interval\" in <ambient> must be positive"));
+            }
+            let sensors = node.children()
+                .filter(|node| node.tag_name().name() == "sensor")
+                .map(|node| -> anyhow::Result<ambient::Sensor> {
+                    let name = node.attribute("name")
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <sensor>"))?;
+                    let source = match (node.attribute("url"), node.attribute("device")) {
+                        (Some(url), None) => ambient::Source::Http(url.to_owned()),
+                        (None, Some(device)) => ambient::Source::Serial(PathBuf::from(device)),
+                        _ => return Err(anyhow::anyhow!("Sensor \"{}\" must have either the attribute \"url\" or \"device\"", name)),
+                    };
+                    Ok(ambient::Sensor {
+                        name: name.to_owned(),
+                        unit: node.attribute("unit").unwrap_or_default().to_owned(),
+                        source,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(ambient::Configuration { interval: Duration::from_secs(interval), sensors })
+        })
+        .transpose()?;
+    let webui = supervisor
+        .descendants()
+        .find(|node| node.tag_name().name() == "webui");
+    let webui_socket = webui
         .map(|node| node
             .attribute("socket")
             .ok_or(anyhow::anyhow!("Could not find attribute \"socket\" in <webui>"))?
             .parse::<SocketAddr>()
             .context("Could not parse attribute \"socket\" in <webui>"))
         .transpose()?;
+    let webui_advertise = webui
+        .and_then(|node| node.attribute("advertise"))
+        .map(str::to_owned);
+    let grpc_socket = supervisor
+        .descendants()
+        .find(|node| node.tag_name().name() == "grpc")
+        .map(|node| node
+            .attribute("socket")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"socket\" in <grpc>"))?
+            .parse::<SocketAddr>()
+            .context("Could not parse attribute \"socket\" in <grpc>"))
+        .transpose()?;
     let router_socket = supervisor
         .descendants()
         .find(|node| node.tag_name().name() == "router")
@@ -187,6 +837,77 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
             .parse::<SocketAddr>()
             .context("Could not parse attribute \"socket\" in <router>"))
         .transpose()?;
+    let router_range = supervisor
+        .descendants()
+        .find(|node| node.tag_name().name() == "router")
+        .and_then(|node| node.attribute("range"))
+        .map(|range| match range.parse::<f32>() {
+            Ok(range) if range > 0.0 => Ok(range),
+            Ok(_) => Err(anyhow::anyhow!("The attribute \"range\" in <router> must be positive")),
+            Err(error) => Err(error)
+                .context(format!("Could not parse attribute \"range\" in <router>: {}", range)),
+        })
+        .transpose()?;
+    let journal_format = match supervisor.children().find(|node| node.tag_name().name() == "journal") {
+        Some(node) => match node.attribute("format") {
+            Some("pickle") | None => shared::configuration::JournalFormat::Pickle,
+            Some("jsonl") => shared::configuration::JournalFormat::JsonLines,
+            Some("csv") => shared::configuration::JournalFormat::Csv,
+            Some(format) => return Err(anyhow::anyhow!("Unknown format \"{}\" in <journal>", format)),
+        },
+        None => Default::default(),
+    };
+    let journal_key = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "journal")
+        .and_then(|node| node.attribute("key"))
+        .map(str::to_owned);
+    let library = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "library")
+        .map(|node| node.attribute("directory")
+            .map(str::to_owned)
+            .ok_or(anyhow::anyhow!("The attribute \"directory\" must be provided in <library>")))
+        .transpose()?;
+    let telemetry = match supervisor.children().find(|node| node.tag_name().name() == "telemetry") {
+        Some(node) => {
+            let retention = node.attribute("retention")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"retention\" in <telemetry>")?
+                .unwrap_or(shared::configuration::Telemetry::default().retention);
+            shared::configuration::Telemetry { retention }
+        },
+        None => Default::default(),
+    };
+    let checklist = supervisor
+        .children()
+        .filter(|node| node.tag_name().name() == "checklist")
+        .flat_map(|node| node.children())
+        .filter(|node| node.tag_name().name() == "item")
+        .map(|node| node
+            .attribute("description")
+            .map(str::to_owned)
+            .ok_or(anyhow::anyhow!("Could not find attribute \"description\" in <item>")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut tags = Vec::new();
+    for node in supervisor.children()
+        .filter(|node| node.tag_name().name() == "tags")
+        .flat_map(|node| node.children())
+        .filter(|node| node.tag_name().name() == "tag") {
+        let tag = node.attribute("name")
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <tag>"))?;
+        /* the tags of an event are separated by spaces in the CSV files of the journal */
+        if tag.contains(char::is_whitespace) {
+            return Err(anyhow::anyhow!("The tag \"{}\" must not contain spaces", tag));
+        }
+        if tags.iter().any(|other| other == tag) {
+            return Err(anyhow::anyhow!("The tag \"{}\" is defined more than once", tag));
+        }
+        tags.push(tag.to_owned());
+    }
     let robots = configuration
         .descendants()
         .find(|node| node.tag_name().name() == "robots")
@@ -196,6 +917,63 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
         .ok_or(anyhow::anyhow!("Could not find attribute \"network\" in <robots>"))?
         .parse::<Ipv4Net>()
         .context("Could not parse attribute \"network\" in <robots>")?;
+    let probing = match robots.children().find(|node| node.tag_name().name() == "probing") {
+        Some(node) => {
+            let defaults = shared::configuration::ProbeConfiguration::default();
+            let concurrency = node.attribute("concurrency")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"concurrency\" in <probing>")?
+                .unwrap_or(defaults.concurrency);
+            if concurrency == 0 {
+                return Err(anyhow::anyhow!("The attribute \"concurrency\" in <probing> must be positive"));
+            }
+            let pause_discovery = match node.attribute("pause_discovery") {
+                Some("true") => true,
+                Some("false") => false,
+                None => defaults.pause_discovery,
+                Some(value) => return Err(anyhow::anyhow!("Could not parse attribute \"pause_discovery\" in <probing>: {}", value)),
+            };
+            let timing = |protocol: &str, default: shared::configuration::ProbeTiming| {
+                let node = match node.children().find(|node| node.tag_name().name() == protocol) {
+                    Some(node) => node,
+                    None => return Ok(default),
+                };
+                let milliseconds = |name: &str, default: u64| node.attribute(name)
+                    .map(|value| value.parse::<u64>())
+                    .transpose()
+                    .context(format!("Could not parse attribute \"{}\" in <{}>", name, protocol))
+                    .map(|value| value.unwrap_or(default));
+                let timeout = milliseconds("timeout", default.timeout)?;
+                if timeout == 0 {
+                    return Err(anyhow::anyhow!("The attribute \"timeout\" in <{}> must be positive", protocol));
+                }
+                let retry_delay = milliseconds("retry_delay", default.retry_delay)?;
+                anyhow::Result::<_>::Ok(shared::configuration::ProbeTiming { timeout, retry_delay })
+            };
+            shared::configuration::ProbeConfiguration {
+                xbee: timing("xbee", defaults.xbee)?,
+                fernbedienung: timing("fernbedienung", defaults.fernbedienung)?,
+                concurrency,
+                pause_discovery,
+            }
+        },
+        None => Default::default(),
+    };
+    let tls_config = robots
+        .children()
+        .find(|node| node.tag_name().name() == "tls")
+        .map(|node| -> anyhow::Result<shared::configuration::Tls> {
+            let attribute = |name: &str| node.attribute(name)
+                .map(str::to_owned)
+                .ok_or(anyhow::anyhow!("Could not find attribute \"{}\" in <tls>", name));
+            Ok(shared::configuration::Tls {
+                authority: attribute("authority")?,
+                certificate: attribute("certificate")?,
+                key: attribute("key")?,
+            })
+        })
+        .transpose()?;
     let builderbots = robots
         .descendants()
         .filter(|node| node.tag_name().name() == "builderbot")
@@ -207,6 +985,10 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
                 .ok_or(anyhow::anyhow!("Could not find attribute \"duovero_macaddr\" for <builderbot>"))?
                 .parse()
                 .context("Could not parse attribute \"duovero_macaddr\" for <builderbot>")?,
+            duovero_addr: node.attribute("duovero_addr")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"duovero_addr\" for <builderbot>")?,
             optitrack_id: node.attribute("optitrack_id")
                 .map(|value| value.parse())
                 .transpose()
@@ -232,10 +1014,16 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
                 .ok_or(anyhow::anyhow!("Could not find attribute \"upcore_macaddr\" for <drone>"))?
                 .parse()
                 .context("Could not parse attribute \"upcore_macaddr\" for <drone>")?,                
+            upcore_addr: node.attribute("upcore_addr")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"upcore_addr\" for <drone>")?,
             optitrack_id: node.attribute("optitrack_id")
                 .map(|value| value.parse())
                 .transpose()
                 .context("Could not parse attribute \"optitrack_id\" for <drone>")?,
+            xbee_configuration: node.attribute("xbee_configuration")
+                .map(|value| value.to_owned()),
         }))
         .collect::<Result<Vec<_>, _>>()?;
     let pipucks = robots
@@ -249,6 +1037,10 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
                 .ok_or(anyhow::anyhow!("Could not find attribute \"rpi_macaddr\" for <pipuck>"))?
                 .parse()
                 .context("Could not parse attribute \"rpi_macaddr\" for <pipuck>")?,
+            rpi_addr: node.attribute("rpi_addr")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"rpi_addr\" for <pipuck>")?,
             optitrack_id: node.attribute("optitrack_id")
                 .map(|value| value.parse())
                 .transpose()
@@ -259,13 +1051,552 @@ fn parse_config(config: &Path) -> anyhow::Result<Configuration> {
                 .context("Could not parse attribute \"apriltag_id\" for <pipuck>")?,
         }))
         .collect::<Result<Vec<_>, _>>()?;
+    let simulated_robots = robots
+        .children()
+        .filter(|node| node.tag_name().name() == "simulated_robot")
+        .map(|node| -> anyhow::Result<shared::simulation::Descriptor> {
+            let id = node.attribute("id")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"id\" for <simulated_robot>"))?;
+            let robot = match node.attribute("robot") {
+                Some(robot @ "builderbot") | Some(robot @ "drone") | Some(robot @ "pipuck") => robot,
+                Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <simulated_robot>", robot)),
+                None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <simulated_robot>")),
+            };
+            Ok(shared::simulation::Descriptor { id: id.to_owned(), robot: robot.to_owned() })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let ros_robots = robots
+        .children()
+        .filter(|node| node.tag_name().name() == "ros_robot")
+        .map(|node| -> anyhow::Result<shared::ros::Descriptor> {
+            let id = node.attribute("id")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"id\" for <ros_robot>"))?;
+            let url = node.attribute("url")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"url\" for <ros_robot>"))?;
+            if !url.starts_with("ws://") && !url.starts_with("wss://") {
+                return Err(anyhow::anyhow!("The attribute \"url\" for <ros_robot> must be a websocket URL: {}", url));
+            }
+            let topics = node.children()
+                .filter(|node| node.tag_name().name() == "topic")
+                .map(|node| -> anyhow::Result<shared::ros::Topic> {
+                    let name = node.attribute("name")
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <topic>"))?;
+                    let message_type = node.attribute("type")
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"type\" in <topic>"))?;
+                    let mapping = match node.attribute("mapping") {
+                        Some("battery") => shared::ros::Mapping::Battery,
+                        Some("pose") => shared::ros::Mapping::Pose,
+                        Some(mapping) => return Err(anyhow::anyhow!("Unknown mapping \"{}\" in <topic>", mapping)),
+                        None => return Err(anyhow::anyhow!("Could not find attribute \"mapping\" in <topic>")),
+                    };
+                    Ok(shared::ros::Topic { name: name.to_owned(), message_type: message_type.to_owned(), mapping })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let launch = node.children()
+                .find(|node| node.tag_name().name() == "launch")
+                .ok_or(anyhow::anyhow!("Could not find <launch> for <ros_robot>"))?;
+            let start_service = launch.attribute("start")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"start\" in <launch>"))?;
+            let stop_service = launch.attribute("stop")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"stop\" in <launch>"))?;
+            Ok(shared::ros::Descriptor {
+                id: id.to_owned(),
+                url: url.to_owned(),
+                topics,
+                start_service: start_service.to_owned(),
+                stop_service: stop_service.to_owned(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    /* the robots are told apart by their identifiers in the user interface and by their MAC addresses on the
+       robot network, neither of which may therefore be shared between two robots */
+    let mut identifiers = HashMap::new();
+    let ids = builderbots.iter().map(|desc| (&desc.id, "builderbot"))
+        .chain(drones.iter().map(|desc| (&desc.id, "drone")))
+        .chain(pipucks.iter().map(|desc| (&desc.id, "pipuck")))
+        .chain(simulated_robots.iter().map(|desc| (&desc.id, "simulated_robot")))
+        .chain(ros_robots.iter().map(|desc| (&desc.id, "ros_robot")));
+    for (id, element) in ids {
+        if let Some(previous) = identifiers.insert(id, element) {
+            return Err(anyhow::anyhow!("The identifier \"{}\" is used by both a <{}> and a <{}>", id, previous, element));
+        }
+    }
+    let mut macaddrs = HashMap::new();
+    let devices = builderbots.iter().map(|desc| (desc.duovero_macaddr, &desc.id, "duovero_macaddr"))
+        .chain(drones.iter().map(|desc| (desc.xbee_macaddr, &desc.id, "xbee_macaddr")))
+        .chain(drones.iter().map(|desc| (desc.upcore_macaddr, &desc.id, "upcore_macaddr")))
+        .chain(pipucks.iter().map(|desc| (desc.rpi_macaddr, &desc.id, "rpi_macaddr")));
+    for (macaddr, id, attribute) in devices {
+        if let Some((previous_id, previous_attribute)) = macaddrs.insert(macaddr, (id, attribute)) {
+            return Err(anyhow::anyhow!("The MAC address {} is used by both \"{}\" of {} and \"{}\" of {}",
+                macaddr, previous_attribute, previous_id, attribute, id));
+        }
+    }
+    /* a fixed address determines which robot may be associated at that address, it may therefore neither be
+       shared between two robots nor lie outside of the robot network */
+    let mut addrs = HashMap::new();
+    let fixed_addrs = builderbots.iter().map(|desc| (desc.duovero_addr, &desc.id, "duovero_addr"))
+        .chain(drones.iter().map(|desc| (desc.upcore_addr, &desc.id, "upcore_addr")))
+        .chain(pipucks.iter().map(|desc| (desc.rpi_addr, &desc.id, "rpi_addr")))
+        .filter_map(|(addr, id, attribute)| addr.map(|addr| (addr, id, attribute)));
+    for (addr, id, attribute) in fixed_addrs {
+        if !robot_network.contains(&addr) {
+            return Err(anyhow::anyhow!("The address {} in \"{}\" of {} is not part of the robot network {}",
+                addr, attribute, id, robot_network));
+        }
+        if let Some((previous_id, previous_attribute)) = addrs.insert(addr, (id, attribute)) {
+            return Err(anyhow::anyhow!("The address {} is used by both \"{}\" of {} and \"{}\" of {}",
+                addr, previous_attribute, previous_id, attribute, id));
+        }
+    }
+    let mut groups: Vec<shared::group::Group> = Vec::new();
+    for node in robots.children().filter(|node| node.tag_name().name() == "group") {
+        let name = node.attribute("name")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <group>"))?;
+        if groups.iter().any(|group| group.name == name) {
+            return Err(anyhow::anyhow!("Group \"{}\" is defined more than once", name));
+        }
+        let members = node.children()
+            .filter(|node| node.tag_name().name() == "member")
+            .map(|node| {
+                let id = node.attribute("id")
+                    .ok_or(anyhow::anyhow!("Could not find attribute \"id\" in <member>"))?;
+                let known = builderbots.iter().any(|desc| desc.id == id) ||
+                    drones.iter().any(|desc| desc.id == id) ||
+                    pipucks.iter().any(|desc| desc.id == id);
+                match known {
+                    true => Ok(id.to_owned()),
+                    false => Err(anyhow::anyhow!("Unknown robot \"{}\" in group \"{}\"", id, name)),
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        groups.push(shared::group::Group { name: name.to_owned(), members });
+    }
+    let camera_shutdown = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "camera_shutdown")
+        .map(|node| -> anyhow::Result<shared::configuration::CameraShutdown> {
+            let restore = match node.attribute("restore") {
+                Some("true") => true,
+                Some("false") | None => false,
+                Some(value) => return Err(anyhow::anyhow!("Could not parse attribute \"restore\" in <camera_shutdown>: {}", value)),
+            };
+            let exempt = node.children()
+                .filter(|node| node.tag_name().name() == "exempt")
+                .map(|node| {
+                    let id = node.attribute("id")
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"id\" in <exempt>"))?;
+                    let known = builderbots.iter().any(|desc| desc.id == id) ||
+                        drones.iter().any(|desc| desc.id == id) ||
+                        pipucks.iter().any(|desc| desc.id == id);
+                    match known {
+                        true => Ok(id.to_owned()),
+                        false => Err(anyhow::anyhow!("Unknown robot \"{}\" in <exempt>", id)),
+                    }
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(shared::configuration::CameraShutdown { restore, exempt })
+        })
+        .transpose()?;
+    let polling = match supervisor.children().find(|node| node.tag_name().name() == "polling") {
+        Some(node) => {
+            let defaults = shared::configuration::PollingFactors::default();
+            let factor = |name: &str, default: u32| -> anyhow::Result<u32> {
+                let factor = node.attribute(name)
+                    .map(|value| value.parse())
+                    .transpose()
+                    .context(format!("Could not parse attribute \"{}\" in <polling>", name))?
+                    .unwrap_or(default);
+                match factor {
+                    0 => Err(anyhow::anyhow!("The attribute \"{}\" in <polling> must be positive", name)),
+                    factor => Ok(factor),
+                }
+            };
+            shared::configuration::PollingFactors {
+                link_strength: factor("link_strength", defaults.link_strength)?,
+                pin_states: factor("pin_states", defaults.pin_states)?,
+                uptime: factor("uptime", defaults.uptime)?,
+            }
+        },
+        None => Default::default(),
+    };
+    let heartbeat = match supervisor.children().find(|node| node.tag_name().name() == "heartbeat") {
+        Some(node) => {
+            let defaults = shared::configuration::Heartbeat::default();
+            let duration = |name: &str, default: u64| -> anyhow::Result<u64> {
+                let duration = node.attribute(name)
+                    .map(|value| value.parse())
+                    .transpose()
+                    .context(format!("Could not parse attribute \"{}\" in <heartbeat>", name))?
+                    .unwrap_or(default);
+                match duration {
+                    0 => Err(anyhow::anyhow!("The attribute \"{}\" in <heartbeat> must be positive", name)),
+                    duration => Ok(duration),
+                }
+            };
+            shared::configuration::Heartbeat {
+                interval: duration("interval", defaults.interval)?,
+                timeout: duration("timeout", defaults.timeout)?,
+                reconnect: duration("reconnect", defaults.reconnect)?,
+            }
+        },
+        None => Default::default(),
+    };
+    let targeted_probing = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "targeted_probing")
+        .map(|node| -> anyhow::Result<shared::configuration::TargetedProbing> {
+            let leases = node.attribute("leases").map(str::to_owned);
+            let arp = match node.attribute("arp") {
+                Some("true") => true,
+                Some("false") | None => false,
+                Some(value) => return Err(anyhow::anyhow!("Could not parse attribute \"arp\" in <targeted_probing>: {}", value)),
+            };
+            if leases.is_none() && !arp {
+                return Err(anyhow::anyhow!("<targeted_probing> requires a lease file or the ARP table"));
+            }
+            let interval = node.attribute("interval")
+                .map(|value| value.parse::<u64>())
+                .transpose()
+                .context("Could not parse attribute \"interval\" in <targeted_probing>")?
+                .unwrap_or(5);
+            if interval == 0 {
+                return Err(anyhow::anyhow!("The attribute \"interval\" in <targeted_probing> must be positive"));
+            }
+            Ok(shared::configuration::TargetedProbing { leases, arp, interval })
+        })
+        .transpose()?;
+    let return_to_base = match supervisor.children().find(|node| node.tag_name().name() == "return_to_base") {
+        Some(node) => {
+            let defaults = shared::configuration::ReturnToBase::default();
+            let drone_mode = match node.attribute("drone_mode") {
+                Some("return") => shared::drone::FlightMode::Return,
+                Some("land") => shared::drone::FlightMode::Land,
+                Some(mode) => return Err(anyhow::anyhow!("Unknown drone mode \"{}\" in <return_to_base>", mode)),
+                None => defaults.drone_mode,
+            };
+            let command = node.attribute("command")
+                .map_or(defaults.command, str::to_owned);
+            shared::configuration::ReturnToBase { drone_mode, command }
+        },
+        None => Default::default(),
+    };
+    let geofence = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "geofence")
+        .map(|node| -> anyhow::Result<shared::configuration::Geofence> {
+            let ceiling = node.attribute("ceiling")
+                .map(|ceiling| ceiling.parse::<f32>()
+                    .context(format!("Could not parse attribute \"ceiling\" in <geofence>: {}", ceiling)))
+                .transpose()?;
+            let vertices = node.children()
+                .filter(|node| node.tag_name().name() == "vertex")
+                .map(|node| {
+                    let coordinate = |name| node.attribute(name)
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"{}\" in <vertex>", name))
+                        .and_then(|value| value.parse::<f32>()
+                            .context(format!("Could not parse attribute \"{}\" in <vertex>: {}", name, value)));
+                    Ok([coordinate("x")?, coordinate("y")?])
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if vertices.len() < 3 {
+                return Err(anyhow::anyhow!("The polygon in <geofence> must have at least three vertices"));
+            }
+            Ok(shared::configuration::Geofence { vertices, ceiling })
+        })
+        .transpose()?;
+    let battery_alerts = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "battery_alerts")
+        .map(|node| -> anyhow::Result<shared::configuration::BatteryAlerts> {
+            let policy = match node.attribute("policy") {
+                Some("notify") | None => shared::configuration::BatteryPolicy::Notify,
+                Some("stop") => shared::configuration::BatteryPolicy::Stop,
+                Some(policy) => return Err(anyhow::anyhow!("Unknown policy \"{}\" in <battery_alerts>", policy)),
+            };
+            let thresholds = node.children()
+                .filter(|node| node.tag_name().name() == "threshold")
+                .map(|node| {
+                    let robot = match node.attribute("robot") {
+                        Some(robot @ "builderbot") | Some(robot @ "drone") | Some(robot @ "pipuck") => robot.to_owned(),
+                        Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <threshold>", robot)),
+                        None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <threshold>")),
+                    };
+                    let level = |name| node.attribute(name)
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"{}\" in <threshold>", name))
+                        .and_then(|value| value.parse::<i32>()
+                            .context(format!("Could not parse attribute \"{}\" in <threshold>: {}", name, value)));
+                    let (warning, critical) = (level("warning")?, level("critical")?);
+                    if critical > warning {
+                        return Err(anyhow::anyhow!("The critical level of {} must not exceed its warning level", robot));
+                    }
+                    Ok(shared::configuration::BatteryThreshold { robot, warning, critical })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(shared::configuration::BatteryAlerts { policy, thresholds })
+        })
+        .transpose()?;
+    let replication = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "replication")
+        .map(|node| -> anyhow::Result<shared::configuration::Replication> {
+            let socket = |name| node.attribute(name)
+                .map(|value| value.parse::<SocketAddr>()
+                    .context(format!("Could not parse attribute \"{}\" in <replication>: {}", name, value)))
+                .transpose();
+            match (socket("socket")?, socket("primary")?) {
+                (Some(socket), None) => Ok(shared::configuration::Replication::Primary { socket }),
+                (None, Some(primary)) => {
+                    let timeout = node.attribute("timeout")
+                        .map(|value| value.parse::<u64>()
+                            .context(format!("Could not parse attribute \"timeout\" in <replication>: {}", value)))
+                        .transpose()?
+                        .unwrap_or(5);
+                    Ok(shared::configuration::Replication::Standby { primary, timeout })
+                },
+                _ => Err(anyhow::anyhow!("Exactly one of the attributes \"socket\" and \"primary\" must be provided in <replication>")),
+            }
+        })
+        .transpose()?;
+    let router_topology = supervisor
+        .descendants()
+        .find(|node| node.tag_name().name() == "router")
+        .and_then(|node| node.children().find(|node| node.tag_name().name() == "topology"))
+        .map(|node| -> anyhow::Result<shared::router::Topology> {
+            let known = |id: &str| builderbots.iter().any(|desc| desc.id == id) ||
+                drones.iter().any(|desc| desc.id == id) ||
+                pipucks.iter().any(|desc| desc.id == id) ||
+                simulated_robots.iter().any(|desc| desc.id == id);
+            let mut topology = shared::router::Topology::default();
+            for node in node.children().filter(|node| node.tag_name().name() == "robot") {
+                let id = node.attribute("id")
+                    .ok_or(anyhow::anyhow!("Could not find attribute \"id\" in <robot>"))?;
+                let neighbors = node.attribute("neighbors")
+                    .ok_or(anyhow::anyhow!("Could not find attribute \"neighbors\" in <robot>"))?
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>();
+                topology.neighbors.entry(id.to_owned()).or_default().extend(neighbors);
+            }
+            match topology.robots().into_iter().find(|id| !known(id)) {
+                Some(id) => Err(anyhow::anyhow!("Unknown robot \"{}\" in <topology>", id)),
+                None => Ok(topology),
+            }
+        })
+        .transpose()?;
+    let access = supervisor
+        .children()
+        .find(|node| node.tag_name().name() == "access")
+        .map(|node| -> anyhow::Result<shared::access::Access> {
+            use shared::access::{Access, Category, Role, User};
+            let role = |node: roxmltree::Node, attribute: &str| -> anyhow::Result<Role> {
+                let name = node.attribute(attribute)
+                    .ok_or(anyhow::anyhow!("Could not find attribute \"{}\" in <{}>", attribute, node.tag_name().name()))?;
+                Role::from_name(name)
+                    .ok_or(anyhow::anyhow!("Unknown role \"{}\" in <{}>", name, node.tag_name().name()))
+            };
+            let default = match node.attribute("default") {
+                Some(_) => role(node, "default")?,
+                None => Role::Observer,
+            };
+            let mut roles = BTreeMap::new();
+            for node in node.children().filter(|node| node.tag_name().name() == "role") {
+                let permissions = node.attribute("permissions").unwrap_or_default()
+                    .split_whitespace()
+                    .map(|name| Category::from_name(name)
+                        .ok_or(anyhow::anyhow!("Unknown permission \"{}\" in <role>", name)))
+                    .collect::<anyhow::Result<std::collections::BTreeSet<_>>>()?;
+                roles.insert(role(node, "name")?, permissions);
+            }
+            let users = node.children()
+                .filter(|node| node.tag_name().name() == "user")
+                .map(|node| {
+                    let name = node.attribute("name")
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <user>"))?;
+                    let token = node.attribute("token")
+                        .filter(|token| !token.is_empty())
+                        .ok_or(anyhow::anyhow!("Could not find attribute \"token\" in <user>"))?;
+                    Ok(User { name: name.to_owned(), token: token.to_owned(), role: role(node, "role")? })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            for (index, user) in users.iter().enumerate() {
+                if users[..index].iter().any(|other| other.token == user.token) {
+                    return Err(anyhow::anyhow!("The token of user \"{}\" is not unique", user.name));
+                }
+            }
+            Ok(Access { default, roles, users })
+        })
+        .transpose()?;
+    let mut builderbot_settings = arena::RobotSettings::default();
+    let mut drone_settings = arena::RobotSettings::default();
+    let mut pipuck_settings = arena::RobotSettings::default();
+    for node in robots.children().filter(|node| node.tag_name().name() == "limits") {
+        let limits = match node.attribute("robot") {
+            Some("builderbot") => &mut builderbot_settings.limits,
+            Some("drone") => &mut drone_settings.limits,
+            Some("pipuck") => &mut pipuck_settings.limits,
+            Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <limits>", robot)),
+            None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <limits>")),
+        };
+        *limits = ResourceLimits {
+            nice: node.attribute("nice")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"nice\" in <limits>")?,
+            ionice_class: node.attribute("ionice_class")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"ionice_class\" in <limits>")?,
+            ionice_level: node.attribute("ionice_level")
+                .map(|value| value.parse())
+                .transpose()
+                .context("Could not parse attribute \"ionice_level\" in <limits>")?,
+            memory_max: node.attribute("memory_max").map(str::to_owned),
+            cpu_quota: node.attribute("cpu_quota").map(str::to_owned),
+        };
+    }
+    for node in robots.children().filter(|node| node.tag_name().name() == "artifact") {
+        let artifacts = match node.attribute("robot") {
+            Some("builderbot") => &mut builderbot_settings.artifacts,
+            Some("drone") => &mut drone_settings.artifacts,
+            Some("pipuck") => &mut pipuck_settings.artifacts,
+            Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <artifact>", robot)),
+            None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <artifact>")),
+        };
+        let pattern = node.attribute("pattern")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"pattern\" in <artifact>"))?;
+        artifacts.push(pattern.to_owned());
+    }
+    for node in robots.children().filter(|node| node.tag_name().name() == "environment") {
+        let environment = match node.attribute("robot") {
+            Some("builderbot") => &mut builderbot_settings.environment,
+            Some("drone") => &mut drone_settings.environment,
+            Some("pipuck") => &mut pipuck_settings.environment,
+            Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <environment>", robot)),
+            None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <environment>")),
+        };
+        let name = node.attribute("name")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <environment>"))?;
+        let value = node.attribute("value")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"value\" in <environment>"))?;
+        if environment.insert(name.to_owned(), value.to_owned()).is_some() {
+            return Err(anyhow::anyhow!("Variable \"{}\" is defined more than once in <environment>", name));
+        }
+    }
+    for node in robots.children().filter(|node| node.tag_name().name() == "watchdog") {
+        let watchdog = match node.attribute("robot") {
+            Some("builderbot") => &mut builderbot_settings.watchdog,
+            Some("drone") => &mut drone_settings.watchdog,
+            Some("pipuck") => &mut pipuck_settings.watchdog,
+            Some(robot) => return Err(anyhow::anyhow!("Unknown robot type \"{}\" in <watchdog>", robot)),
+            None => return Err(anyhow::anyhow!("Could not find attribute \"robot\" in <watchdog>")),
+        };
+        let interval = node.attribute("interval")
+            .map(|value| value.parse())
+            .transpose()
+            .context("Could not parse attribute \"interval\" in <watchdog>")?
+            .unwrap_or(10);
+        let attempts = node.attribute("attempts")
+            .map(|value| value.parse())
+            .transpose()
+            .context("Could not parse attribute \"attempts\" in <watchdog>")?
+            .unwrap_or(3);
+        if interval == 0 || attempts == 0 {
+            return Err(anyhow::anyhow!("The attributes \"interval\" and \"attempts\" in <watchdog> must be positive"));
+        }
+        *watchdog = Some(Watchdog {
+            interval: Duration::from_secs(interval),
+            attempts,
+            service: node.attribute("service").unwrap_or("fernbedienung").to_owned(),
+        });
+    }
+    /* the expected values of the parameters of the Xbees, e.g., the firmware version (VR) */
+    let mut xbee_profile = BTreeMap::new();
+    if let Some(profile) = robots.children().find(|node| node.tag_name().name() == "xbee_profile") {
+        for node in profile.children().filter(|node| node.tag_name().name() == "parameter") {
+            let name = node.attribute("name")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <parameter>"))?;
+            if name.len() != 2 || !name.chars().all(|character| character.is_ascii_alphanumeric()) {
+                return Err(anyhow::anyhow!("The parameter \"{}\" in <xbee_profile> is not an AT command", name));
+            }
+            let value = node.attribute("value")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"value\" in <parameter>"))?;
+            if value.is_empty() || !value.chars().all(|character| character.is_ascii_hexdigit()) {
+                return Err(anyhow::anyhow!("The value of parameter \"{}\" in <xbee_profile> is not hexadecimal", name));
+            }
+            xbee_profile.insert(name.to_ascii_uppercase(), value.to_ascii_uppercase());
+        }
+    }
+    /* the parameters that are written to the Xbees of the drones, e.g., the pin mapping of a hardware revision */
+    let mut xbee_configurations = BTreeMap::new();
+    for configuration in robots.children().filter(|node| node.tag_name().name() == "xbee_configuration") {
+        let name = configuration.attribute("name")
+            .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <xbee_configuration>"))?;
+        let mut parameters = Vec::new();
+        for node in configuration.children().filter(|node| node.tag_name().name() == "parameter") {
+            let parameter = node.attribute("name")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"name\" in <parameter>"))?;
+            if parameter.len() != 2 || !parameter.chars().all(|character| character.is_ascii_alphanumeric()) {
+                return Err(anyhow::anyhow!("The parameter \"{}\" in <xbee_configuration name=\"{}\"> is not an AT command",
+                    parameter, name));
+            }
+            let value = node.attribute("value")
+                .ok_or(anyhow::anyhow!("Could not find attribute \"value\" in <parameter>"))?;
+            if value.is_empty() || !value.chars().all(|character| character.is_ascii_hexdigit()) {
+                return Err(anyhow::anyhow!("The value of parameter \"{}\" in <xbee_configuration name=\"{}\"> is not hexadecimal",
+                    parameter, name));
+            }
+            parameters.push((parameter.to_ascii_uppercase(), value.to_ascii_uppercase()));
+        }
+        if xbee_configurations.insert(name.to_owned(), parameters).is_some() {
+            return Err(anyhow::anyhow!("The Xbee configuration \"{}\" is defined more than once", name));
+        }
+    }
+    for drone in &drones {
+        if let Some(configuration) = &drone.xbee_configuration {
+            if !xbee_configurations.contains_key(configuration) {
+                return Err(anyhow::anyhow!("Drone {} refers to the undefined Xbee configuration \"{}\"", drone.id, configuration));
+            }
+        }
+    }
     Ok(Configuration { 
         optitrack_config,
+        infrastructure_config,
+        ambient_config,
         router_socket,
+        router_topology,
+        router_range,
         webui_socket,
+        webui_advertise,
+        grpc_socket,
         robot_network,
+        probing,
+        tls_config,
+        journal_format,
+        journal_key,
+        library,
+        telemetry,
+        checklist,
+        tags,
+        camera_shutdown,
+        polling,
+        heartbeat,
+        targeted_probing,
+        return_to_base,
+        geofence,
+        battery_alerts,
+        replication,
+        access,
         builderbots,
         pipucks,
         drones,
+        simulated_robots,
+        ros_robots,
+        groups,
+        builderbot_settings,
+        drone_settings,
+        pipuck_settings,
+        xbee_profile,
+        xbee_configurations,
     })
 }
\ No newline at end of file
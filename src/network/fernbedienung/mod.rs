@@ -2,6 +2,7 @@ use std::fmt::Debug;
 use std::net::Ipv4Addr;
 use std::path::PathBuf;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use bytes::BytesMut;
 use macaddr::MacAddr6;
@@ -9,7 +10,7 @@ use regex::Regex;
 use once_cell::sync::Lazy;
 
 use futures::{self, FutureExt, StreamExt, stream::FuturesUnordered};
-use tokio::{net::TcpStream, sync::{mpsc, oneshot}};
+use tokio::{io::{AsyncRead, AsyncWrite}, net::TcpStream, sync::{mpsc, oneshot}};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_serde::{SymmetricallyFramed, formats::SymmetricalJson};
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
@@ -18,6 +19,11 @@ use uuid::Uuid;
 mod protocol;
 pub use protocol::{Upload, process::Process};
 
+mod tls;
+pub use tls::TlsConnector;
+
+pub const PORT: u16 = 17653;
+
 static REGEX_LINK_STRENGTH: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"signal:\s+(-\d+)\s+dBm+").unwrap()
 });
@@ -40,24 +46,45 @@ pub enum Error {
     ResponseError,
     #[error("Could not decode data")]
     DecodeError,
+    #[error("Could not authenticate: {0}")]
+    AuthenticationError(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The connection to the fernbedienung service, i.e., a TCP stream that is optionally wrapped in TLS
+trait Transport: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Transport for T {}
+
+type Stream = Box<dyn Transport>;
+
 type RemoteResponses = SymmetricallyFramed<
-    FramedRead<tokio::io::ReadHalf<TcpStream>, LengthDelimitedCodec>,
+    FramedRead<tokio::io::ReadHalf<Stream>, LengthDelimitedCodec>,
     protocol::Response,
     SymmetricalJson<protocol::Response>>;
 
 pub type RemoteRequests = SymmetricallyFramed<
-    FramedWrite<tokio::io::WriteHalf<TcpStream>, LengthDelimitedCodec>,
+    FramedWrite<tokio::io::WriteHalf<Stream>, LengthDelimitedCodec>,
     protocol::Request,
     SymmetricalJson<protocol::Request>>;
 
+/// The address of a device, which is returned to the network module once the device is dropped
+#[derive(Debug)]
+pub struct Returned {
+    pub addr: Ipv4Addr,
+    /* the connection was lost while the device was associated with a robot, i.e., its heartbeat timed out */
+    pub lost: bool,
+}
+
 pub struct Device {
     pub addr: Ipv4Addr,
-    request_tx: mpsc::Sender<Request>,
-    return_addr_tx: Option<oneshot::Sender<Ipv4Addr>>,
+    tls: Option<TlsConnector>,
+    request_tx: mpsc::Sender<TracedRequest>,
+    /* the secondary connection for processes with high-volume output, opened when it is first used so
+       that the output of these processes can not delay the requests on the control connection */
+    data_request_tx: tokio::sync::Mutex<Option<mpsc::Sender<TracedRequest>>>,
+    return_addr_tx: Option<oneshot::Sender<Returned>>,
+    lost: AtomicBool,
 }
 
 impl Debug for Device {
@@ -69,7 +96,7 @@ impl Debug for Device {
 impl Drop for Device {
     fn drop(&mut self) {
         if let Some(return_addr_tx) = self.return_addr_tx.take() {
-            let _ = return_addr_tx.send(self.addr);
+            let _ = return_addr_tx.send(Returned { addr: self.addr, lost: self.lost.load(Ordering::Relaxed) });
         }
     }
 }
@@ -93,15 +120,94 @@ enum Request {
         upload: protocol::Upload,
         result_tx: oneshot::Sender<Result<()>>
     },
+    Download {
+        download: protocol::Download,
+        result_tx: oneshot::Sender<Result<Vec<u8>>>
+    },
+}
+
+impl Request {
+    fn describe(&self) -> String {
+        match self {
+            Request::Halt { .. } => "halt".to_owned(),
+            Request::Reboot { .. } => "reboot".to_owned(),
+            Request::Run { process, .. } => format!("run {:?}", process.target),
+            Request::Upload { upload, .. } => format!("upload {:?}", upload.path.join(&upload.filename)),
+            Request::Download { download, .. } => format!("download {:?}", download.path),
+        }
+    }
+}
+
+/* requests are sent along with the trace of the task that made them, if any */
+type TracedRequest = (Request, Option<crate::trace::Handle>);
+
+/// Connects to the fernbedienung service, if a TLS connector is provided, the service and the supervisor
+/// authenticate each other using their certificates
+async fn connect(addr: Ipv4Addr, tls: Option<TlsConnector>) -> Result<Stream> {
+    let stream = TcpStream::connect((addr, PORT)).await?;
+    match tls {
+        Some(connector) => connector.connect(addr, stream).await
+            .map(|stream| Box::new(stream) as Stream),
+        None => Ok(Box::new(stream)),
+    }
 }
 
 impl Device {
-    pub async fn new(addr: Ipv4Addr, return_addr_tx: oneshot::Sender<Ipv4Addr>) -> Result<Self> {
+    pub async fn new(addr: Ipv4Addr, return_addr_tx: oneshot::Sender<Returned>, tls: Option<TlsConnector>) -> Result<Self> {
+        let (request_tx, connected_rx) = Device::open(format!("network/fernbedienung@{}", addr), addr, tls.clone());
+        /* the device is created before waiting for the connection so that its address is returned to
+           the network module (when it is dropped) even if the connection fails or times out */
+        let device = Device {
+            addr,
+            tls,
+            request_tx,
+            data_request_tx: Default::default(),
+            return_addr_tx: Some(return_addr_tx),
+            lost: AtomicBool::new(false),
+        };
+        match connected_rx.await {
+            Ok(result) => result.map(|_| device),
+            Err(_) => Err(Error::RequestError),
+        }
+    }
+
+    /// Marks the connection as lost so that the network module probes the address of the device again
+    /// without waiting for the probing of the other addresses once the device is dropped
+    pub fn mark_lost(&self) {
+        self.lost.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the secondary data connection, which is opened again if it has been closed
+    async fn data_connection(&self) -> Result<mpsc::Sender<TracedRequest>> {
+        let mut data_request_tx = self.data_request_tx.lock().await;
+        match data_request_tx.as_ref().filter(|request_tx| !request_tx.is_closed()) {
+            Some(request_tx) => Ok(request_tx.clone()),
+            None => {
+                let name = format!("network/fernbedienung@{}/data", self.addr);
+                let (request_tx, connected_rx) = Device::open(name, self.addr, self.tls.clone());
+                connected_rx.await.map_err(|_| Error::RequestError)??;
+                *data_request_tx = Some(request_tx.clone());
+                Ok(request_tx)
+            }
+        }
+    }
+
+    /// Spawns a task that connects to the fernbedienung service and forwards the requests over that
+    /// connection, the receiver resolves once the connection has been established or has failed
+    fn open(name: String, addr: Ipv4Addr, tls: Option<TlsConnector>)
+        -> (mpsc::Sender<TracedRequest>, oneshot::Receiver<Result<()>>) {
         let (local_request_tx, mut local_request_rx) = mpsc::channel(8);
-        tokio::spawn(async move {
-            let stream = match TcpStream::connect((addr, 17653)).await {
-                Ok(stream) => stream,
-                Err(_) => return,
+        let (connected_tx, connected_rx) = oneshot::channel();
+        crate::instrument::spawn(name, async move {
+            let stream = match connect(addr, tls).await {
+                Ok(stream) => {
+                    let _ = connected_tx.send(Ok(()));
+                    stream
+                },
+                Err(error) => {
+                    let _ = connected_tx.send(Err(error));
+                    return;
+                }
             };
             /* requests and responses from remote */
             let (read, write) = tokio::io::split(stream);
@@ -140,10 +246,14 @@ impl Device {
                         }
                     },
                     request = local_request_rx.recv() => match request {
-                        Some(request) => {
+                        Some((request, trace)) => {
+                            let uuid = Uuid::new_v4();
+                            if let Some(trace) = &trace {
+                                crate::trace::record(trace, "fernbedienung",
+                                    format!("Sent {} to {} as {}", request.describe(), addr, uuid));
+                            }
                             let task = match request {
                                 Request::Halt { result_tx } => {
-                                    let uuid = Uuid::new_v4();
                                     let request = protocol::RequestKind::Halt;
                                     let (halt_status_tx, mut halt_status_rx) = mpsc::channel(8);
                                     status_txs.insert(uuid, halt_status_tx);
@@ -162,7 +272,6 @@ impl Device {
                                     }.boxed()
                                 }
                                 Request::Reboot { result_tx } => {
-                                    let uuid = Uuid::new_v4();
                                     let request = protocol::RequestKind::Reboot;
                                     let (reboot_status_tx, mut reboot_status_rx) = mpsc::channel(8);
                                     status_txs.insert(uuid, reboot_status_tx);
@@ -181,7 +290,6 @@ impl Device {
                                     }.boxed()
                                 }
                                 Request::Upload { upload, result_tx } => {
-                                    let uuid = Uuid::new_v4();
                                     let request = protocol::RequestKind::Upload(upload);
                                     /* subscribe to updates */
                                     let (upload_status_tx, mut upload_status_rx) = mpsc::channel(8);
@@ -202,8 +310,29 @@ impl Device {
                                         uuid
                                     }.boxed()
                                 },
+                                Request::Download { download, result_tx } => {
+                                    let request = protocol::RequestKind::Download(download);
+                                    /* subscribe to updates */
+                                    let (download_status_tx, mut download_status_rx) = mpsc::channel(8);
+                                    status_txs.insert(uuid, download_status_tx);
+                                    /* send the request */
+                                    let request_result = remote_requests_tx
+                                        .send(protocol::Request(uuid, request)).await;
+                                    /* process responses */
+                                    async move {
+                                        let result = match request_result {
+                                            Ok(_) => match download_status_rx.recv().await {
+                                                Some(protocol::ResponseKind::Download(contents)) => Ok(contents.to_vec()),
+                                                Some(protocol::ResponseKind::Error(error)) => Err(Error::RemoteError(error)),
+                                                _ => Err(Error::ResponseError),
+                                            }
+                                            _ => Err(Error::RequestError),
+                                        };
+                                        let _ = result_tx.send(result);
+                                        uuid
+                                    }.boxed()
+                                },
                                 Request::Run { process, terminate_rx, stdin_rx, stdout_tx, stderr_tx, result_tx } => {
-                                    let uuid = Uuid::new_v4();
                                     let request = protocol::RequestKind::Process(protocol::process::Request::Run(process));
                                     /* subscribe to updates */
                                     let (run_status_tx, run_status_rx) = mpsc::channel(8);
@@ -222,6 +351,14 @@ impl Device {
                                     }.boxed()
                                 },
                             };
+                            /* record the completion of the request in the trace */
+                            let task = task.map(move |uuid| {
+                                if let Some(trace) = &trace {
+                                    crate::trace::record(trace, "fernbedienung",
+                                        format!("Completed {} on {}", uuid, addr));
+                                }
+                                uuid
+                            });
                             tasks.push(task);
                         },
                         None => break,
@@ -233,7 +370,7 @@ impl Device {
                 }
             }
         });
-        Ok(Device { request_tx: local_request_tx, addr, return_addr_tx: Some(return_addr_tx) })
+        (local_request_tx, connected_rx)
     }
 
     async fn handle_run_request(uuid: Uuid,
@@ -269,7 +406,7 @@ impl Device {
 
                 },
                 Some(response) = run_status_rx.recv() => match response {
-                    protocol::ResponseKind::Ok => {},
+                    protocol::ResponseKind::Ok | protocol::ResponseKind::Download(_) => {},
                     protocol::ResponseKind::Error(error) => {
                         let status = Err(Error::RemoteError(error));
                         let _ = exit_status_tx.send(status);
@@ -314,7 +451,7 @@ impl Device {
         };
         let (result_tx, result_rx) = oneshot::channel();
         self.request_tx
-            .send(Request::Upload { upload, result_tx }).await
+            .send((Request::Upload { upload, result_tx }, crate::trace::current())).await
             .map_err(|_| Error::RequestError)?;
         result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
     }
@@ -322,7 +459,7 @@ impl Device {
     pub async fn halt(&self) -> Result<()> {
         let (result_tx, result_rx) = oneshot::channel();
         self.request_tx
-            .send(Request::Halt { result_tx }).await
+            .send((Request::Halt { result_tx }, crate::trace::current())).await
             .map_err(|_| Error::RequestError)?;
         result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
     }
@@ -330,7 +467,7 @@ impl Device {
     pub async fn reboot(&self) -> Result<()> {
         let (result_tx, result_rx) = oneshot::channel();
         self.request_tx
-            .send(Request::Reboot { result_tx }).await
+            .send((Request::Reboot { result_tx }, crate::trace::current())).await
             .map_err(|_| Error::RequestError)?;
         result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
     }
@@ -350,7 +487,31 @@ impl Device {
             stderr_tx: stderr_tx.into(),
             result_tx: result_tx.into()
         };
-        self.request_tx.send(request).await.map_err(|_ | Error::RequestError)?;
+        self.request_tx.send((request, crate::trace::current())).await.map_err(|_ | Error::RequestError)?;
+        result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
+    }
+
+    /// Runs a process with high-volume output (e.g., ARGoS or a terminal) over the secondary data
+    /// connection. The output is only read from this connection as fast as it is consumed, so that it
+    /// does not delay the requests and responses on the control connection (e.g., camera control and
+    /// link polls)
+    pub async fn run_bulk(&self,
+                          process: protocol::process::Process,
+                          terminate_rx: impl Into<Option<oneshot::Receiver<()>>>,
+                          stdin_rx: impl Into<Option<mpsc::Receiver<BytesMut>>>,
+                          stdout_tx: impl Into<Option<mpsc::Sender<BytesMut>>>,
+                          stderr_tx: impl Into<Option<mpsc::Sender<BytesMut>>>) -> Result<()> {
+        let data_request_tx = self.data_connection().await?;
+        let (result_tx, result_rx) = oneshot::channel();
+        let request = Request::Run {
+            process,
+            terminate_rx: terminate_rx.into(),
+            stdin_rx: stdin_rx.into(),
+            stdout_tx: stdout_tx.into(),
+            stderr_tx: stderr_tx.into(),
+            result_tx: result_tx.into()
+        };
+        data_request_tx.send((request, crate::trace::current())).await.map_err(|_ | Error::RequestError)?;
         result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
     }
 
@@ -359,6 +520,7 @@ impl Device {
             target: "mktemp".into(),
             working_dir: None,
             args: vec!["-d".to_owned()],
+            environment: Default::default(),
         };
         let (stdout_tx, stdout_rx) = mpsc::channel(8);
         let stdout_stream = ReceiverStream::new(stdout_rx);
@@ -371,6 +533,15 @@ impl Device {
         Ok(temp_dir.trim().to_owned())
     }
 
+    pub async fn download<P: Into<PathBuf>>(&self, path: P) -> Result<Vec<u8>> {
+        let download = protocol::Download { path: path.into() };
+        let (result_tx, result_rx) = oneshot::channel();
+        self.request_tx
+            .send((Request::Download { download, result_tx }, crate::trace::current())).await
+            .map_err(|_| Error::RequestError)?;
+        result_rx.await.map_err(|_| Error::ResponseError).and_then(|result| result)
+    }
+
     // pub async fn hostname(&self) -> Result<String> {
     //     let process = protocol::process::Process {
     //         target: "hostname".into(),
@@ -388,22 +559,83 @@ impl Device {
     //     Ok(hostname.trim().to_owned())
     // }
 
-    // pub async fn kernel_messages(&self) -> Result<String> {
-    //     let process = protocol::process::Process {
-    //         target: "dmesg".into(),
-    //         working_dir: None,
-    //         args: vec![],
-    //     };
-    //     let (stdout_tx, stdout_rx) = mpsc::channel(8);
-    //     let stdout_stream = ReceiverStream::new(stdout_rx);
-    //     let (_, stdout) = tokio::try_join!(
-    //         self.run(process, None, None, stdout_tx, None),
-    //         stdout_stream.concat().map(Result::Ok)
-    //     )?;
-    //     let messages = std::str::from_utf8(stdout.as_ref())
-    //         .map_err(|_| Error::DecodeError)?;
-    //     Ok(messages.trim().to_owned())
-    // }
+    pub async fn kernel_messages(&self) -> Result<String> {
+        let process = protocol::process::Process {
+            target: "dmesg".into(),
+            working_dir: None,
+            args: vec![],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            self.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(Result::Ok)
+        )?;
+        let messages = std::str::from_utf8(stdout.as_ref())
+            .map_err(|_| Error::DecodeError)?;
+        Ok(messages.trim().to_owned())
+    }
+
+    /// Runs a process that exits immediately, which is answered as long as the connection to the robot is alive
+    pub async fn ping(&self) -> Result<()> {
+        let process = protocol::process::Process {
+            target: "true".into(),
+            working_dir: None,
+            args: vec![],
+            environment: Default::default(),
+        };
+        self.run(process, None, None, None, None).await
+    }
+
+    /// The time since the robot booted as reported by `/proc/uptime`
+    pub async fn uptime(&self) -> Result<std::time::Duration> {
+        let process = protocol::process::Process {
+            target: "cat".into(),
+            working_dir: None,
+            args: vec!["/proc/uptime".to_owned()],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            self.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(Result::Ok)
+        )?;
+        let uptime = std::str::from_utf8(stdout.as_ref())
+            .map_err(|_| Error::DecodeError)?;
+        uptime.split_whitespace()
+            .next()
+            .and_then(|seconds| seconds.parse::<f64>().ok())
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .map(std::time::Duration::from_secs_f64)
+            .ok_or(Error::DecodeError)
+    }
+
+    /// The name and version of the operating system image as reported by `/etc/os-release`
+    pub async fn firmware(&self) -> Result<String> {
+        let process = protocol::process::Process {
+            target: "cat".into(),
+            working_dir: None,
+            args: vec!["/etc/os-release".to_owned()],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            self.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(Result::Ok)
+        )?;
+        let os_release = std::str::from_utf8(stdout.as_ref())
+            .map_err(|_| Error::DecodeError)?;
+        let field = |name: &str| os_release.lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(key, _)| key.trim() == name)
+            .map(|(_, value)| value.trim().trim_matches('"').to_owned());
+        field("PRETTY_NAME")
+            .or_else(|| field("VERSION"))
+            .ok_or(Error::DecodeError)
+    }
 
     pub async fn link_strength(&self) -> Result<i32> {
         let process = protocol::process::Process {
@@ -413,6 +645,7 @@ impl Device {
                 .split_whitespace()
                 .map(str::to_owned)
                 .collect(),
+            environment: Default::default(),
         };
         let (stdout_tx, stdout_rx) = mpsc::channel(8);
         let stdout_stream = ReceiverStream::new(stdout_rx);
@@ -437,6 +670,7 @@ impl Device {
                 .split_whitespace()
                 .map(str::to_owned)
                 .collect(),
+            environment: Default::default(),
         };
         let (stdout_tx, stdout_rx) = mpsc::channel(8);
         let stdout_stream = ReceiverStream::new(stdout_rx);
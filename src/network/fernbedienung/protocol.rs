@@ -16,7 +16,7 @@ fn bytesmut_deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<By
 }
 
 pub mod process {
-    use std::path::PathBuf;
+    use std::{collections::BTreeMap, path::PathBuf};
     use bytes::BytesMut;
     use serde::{Deserialize, Serialize};
     
@@ -26,6 +26,10 @@ pub mod process {
         pub target: PathBuf,
         pub working_dir: Option<PathBuf>,
         pub args: Vec<String>,
+        /* variables that are added to the environment of the process, omitted if empty so that
+           older versions of fernbedienung remain compatible */
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        pub environment: BTreeMap<String, String>,
     }
 
     #[derive(Debug, Serialize)]
@@ -53,11 +57,17 @@ pub struct Upload {
     pub contents: Vec<u8>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct Download {
+    pub path: PathBuf,
+}
+
 #[derive(Debug, Serialize)]
 pub enum RequestKind {
     Halt,
     Reboot,
     Upload(Upload),
+    Download(Download),
     Process(process::Request),
 }
 
@@ -68,6 +78,9 @@ pub struct Request(pub Uuid, pub RequestKind);
 pub enum ResponseKind {
     Ok,
     Error(String),
+    /* the contents of a downloaded file */
+    #[serde(deserialize_with = "bytesmut_deserialize")]
+    Download(BytesMut),
     Process(process::Response),
 }
 
@@ -0,0 +1,49 @@
+use std::{net::Ipv4Addr, path::Path};
+use anyhow::Context;
+use tokio::net::TcpStream;
+use tokio_native_tls::TlsStream;
+
+use super::{Error, Result};
+
+/// Establishes mutually authenticated TLS connections to the fernbedienung service. Both the robots and
+/// the supervisor present certificates that have been signed by the certificate authority of the deployment
+#[derive(Clone)]
+pub struct TlsConnector(tokio_native_tls::TlsConnector);
+
+impl std::fmt::Debug for TlsConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TlsConnector")
+    }
+}
+
+impl TlsConnector {
+    /// Creates a connector from PEM files containing the certificate of the authority, the certificate of
+    /// the supervisor, and the private key (PKCS #8) of the supervisor
+    pub fn new(authority: &Path, certificate: &Path, key: &Path) -> anyhow::Result<Self> {
+        let authority = std::fs::read(authority)
+            .with_context(|| format!("Could not read {:?}", authority))?;
+        let authority = native_tls::Certificate::from_pem(&authority)
+            .context("Could not parse the certificate of the authority")?;
+        let certificate = std::fs::read(certificate)
+            .with_context(|| format!("Could not read {:?}", certificate))?;
+        let key = std::fs::read(key)
+            .with_context(|| format!("Could not read {:?}", key))?;
+        let identity = native_tls::Identity::from_pkcs8(&certificate, &key)
+            .context("Could not parse the certificate or the key of the supervisor")?;
+        let connector = native_tls::TlsConnector::builder()
+            .disable_built_in_roots(true)
+            .add_root_certificate(authority)
+            .identity(identity)
+            /* the robots obtain their addresses via DHCP, their certificates are therefore verified against
+               the authority, but not against their addresses */
+            .danger_accept_invalid_hostnames(true)
+            .build()
+            .context("Could not create TLS connector")?;
+        Ok(TlsConnector(connector.into()))
+    }
+
+    pub async fn connect(&self, addr: Ipv4Addr, stream: TcpStream) -> Result<TlsStream<TcpStream>> {
+        self.0.connect(&addr.to_string(), stream).await
+            .map_err(|error| Error::AuthenticationError(error.to_string()))
+    }
+}
@@ -1,13 +1,392 @@
-use std::{pin::Pin, task::{Context, Poll}};
+use std::{path::{Path, PathBuf}, pin::Pin, task::{Context, Poll}, time::Duration};
+use anyhow::Context as _;
 use bytes::Bytes;
-use futures::{Stream, TryFutureExt};
-use tokio::sync::oneshot;
+use futures::{FutureExt, Stream, StreamExt, TryFutureExt};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use shared::{camera, experiment::SmokeTestCheck};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::journal;
 
 use super::fernbedienung;
 
 // TO READ: https://carllerche.com/2021/06/17/six-ways-to-make-async-rust-easier/
 // TO READ: https://rust-lang.github.io/wg-async-foundations/vision.html
 
+static REGEX_OOM_KILLED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Killed process (\d+) \(([^)]+)\)").unwrap()
+});
+
+/// Resource limits that are applied to the processes that the supervisor launches on a robot (ARGoS and
+/// the camera streamers). The limits are configured per robot type and are applied by wrapping the process
+/// with `systemd-run` (cgroup limits), `ionice`, and `nice`. Since `systemd-run --scope` is only permitted
+/// to root (or to the user of the Fernbedienung service with a polkit rule), the memory and CPU limits are
+/// dropped with a warning on robots where it can not be used
+#[derive(Clone, Debug, Default)]
+pub struct ResourceLimits {
+    pub nice: Option<i8>,
+    pub ionice_class: Option<u8>,
+    pub ionice_level: Option<u8>,
+    /* these values are passed verbatim to systemd, e.g., "256M" and "80%" */
+    pub memory_max: Option<String>,
+    pub cpu_quota: Option<String>,
+}
+
+impl ResourceLimits {
+    pub async fn apply(&self, device: &fernbedienung::Device, process: fernbedienung::Process) -> fernbedienung::Process {
+        let mut scope = self.memory_max.is_some() || self.cpu_quota.is_some();
+        if scope && !ResourceLimits::scope_permitted(device).await {
+            log::warn!("Running {} on {} without the memory and CPU limits, since systemd-run --scope requires root",
+                process.target.to_string_lossy(), device.addr);
+            scope = false;
+        }
+        let mut command: Vec<String> = Vec::new();
+        if scope {
+            command.extend(["systemd-run", "--scope", "--quiet"].iter().map(|&arg| arg.to_owned()));
+            if let Some(memory_max) = &self.memory_max {
+                command.push("-p".to_owned());
+                command.push(format!("MemoryMax={}", memory_max));
+            }
+            if let Some(cpu_quota) = &self.cpu_quota {
+                command.push("-p".to_owned());
+                command.push(format!("CPUQuota={}", cpu_quota));
+            }
+            command.push("--".to_owned());
+        }
+        if self.ionice_class.is_some() || self.ionice_level.is_some() {
+            command.push("ionice".to_owned());
+            if let Some(class) = self.ionice_class {
+                command.push("-c".to_owned());
+                command.push(class.to_string());
+            }
+            if let Some(level) = self.ionice_level {
+                command.push("-n".to_owned());
+                command.push(level.to_string());
+            }
+        }
+        if let Some(nice) = self.nice {
+            command.push("nice".to_owned());
+            command.push("-n".to_owned());
+            command.push(nice.to_string());
+        }
+        match command.len() {
+            0 => process,
+            _ => {
+                let target = command.remove(0);
+                command.push(process.target.to_string_lossy().into_owned());
+                command.extend(process.args);
+                fernbedienung::Process {
+                    target: target.into(),
+                    working_dir: process.working_dir,
+                    args: command,
+                    environment: process.environment,
+                }
+            }
+        }
+    }
+
+    /* checks whether transient scopes can be created on the robot by creating one for a process that exits immediately */
+    async fn scope_permitted(device: &fernbedienung::Device) -> bool {
+        let process = fernbedienung::Process {
+            target: "systemd-run".into(),
+            working_dir: None,
+            args: ["--scope", "--quiet", "--", "true"].iter().map(|&arg| arg.to_owned()).collect(),
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await.is_ok()
+    }
+
+    /// After a process terminated abnormally, this function checks the kernel messages to determine
+    /// whether the process was killed for exceeding its memory limit
+    pub async fn violation(&self, device: &fernbedienung::Device, target: &str) -> Option<String> {
+        let memory_max = self.memory_max.as_ref()?;
+        let messages = device.kernel_messages().await.ok()?;
+        messages.lines().rev()
+            .filter_map(|line| REGEX_OOM_KILLED.captures(line))
+            .find(|captures| captures.get(2).map(|name| name.as_str()) == Some(target))
+            .map(|captures| format!("{} (pid {}) was killed for exceeding the memory limit of {}",
+                target, &captures[1], memory_max))
+    }
+}
+
+/// The processes of ARGoS that are left on a robot after an experiment has been stopped, e.g., because ARGoS
+/// did not exit when its controller was stopped
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Leftovers;
+
+impl Leftovers {
+    /// Checks that ARGoS is no longer running on the robot
+    pub async fn verify(device: &fernbedienung::Device) -> anyhow::Result<()> {
+        /* pgrep exits with an error if no process matches */
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), "pgrep -x argos3 || true".to_owned()],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            device.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(fernbedienung::Result::Ok)
+        ).context("Could not list the processes of ARGoS")?;
+        let processes = String::from_utf8_lossy(stdout.as_ref())
+            .split_whitespace()
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        match processes.is_empty() {
+            true => Ok(()),
+            false => Err(anyhow::anyhow!("ARGoS is still running (pid {})", processes.join(", "))),
+        }
+    }
+
+    /// Kills the processes of ARGoS that are still running on the robot
+    pub async fn kill(device: &fernbedienung::Device) -> anyhow::Result<()> {
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), "pkill -KILL -x argos3 || true".to_owned()],
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await
+            .context("Could not kill the processes of ARGoS")
+    }
+}
+
+/// The files that are collected from the working directory of ARGoS on a robot after an experiment. The
+/// patterns are configured per robot type and the files are stored with the artifacts of the experiment
+#[derive(Clone, Debug)]
+pub struct Artifacts {
+    pub patterns: Vec<String>,
+}
+
+impl Artifacts {
+    /// Downloads the files in the working directory that match any of the patterns, passes them to the
+    /// journal, and returns their names
+    pub async fn collect(
+        &self,
+        device: &fernbedienung::Device,
+        working_dir: &str,
+        id: &str,
+        journal_tx: &mpsc::Sender<journal::Action>
+    ) -> anyhow::Result<Vec<String>> {
+        /* find the files in the working directory that match the patterns */
+        let mut args = vec![working_dir.to_owned()];
+        args.extend(["-maxdepth", "1", "-type", "f", "("].iter().map(|&arg| arg.to_owned()));
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if index != 0 {
+                args.push("-o".to_owned());
+            }
+            args.push("-name".to_owned());
+            args.push(pattern.clone());
+        }
+        args.push(")".to_owned());
+        let files = find(device, args).await
+            .context("Could not search for artifacts")?;
+        let files = files.into_iter()
+            .filter_map(|file| filename(&file).map(|filename| (file, filename)));
+        download(device, files, journal::Destination::Experiment(id.to_owned()), journal_tx).await
+    }
+}
+
+/// Files that are fetched from a robot on request, e.g., logs or captured data that are not covered by the
+/// artifact patterns. The files are written into a directory named after the time of the request and the robot
+#[derive(Clone, Debug)]
+pub struct Results {
+    pub paths: Vec<String>,
+    pub directory: PathBuf,
+    pub journal_tx: mpsc::Sender<journal::Action>,
+}
+
+impl Results {
+    pub fn new(id: &str, paths: Vec<String>, journal_tx: mpsc::Sender<journal::Action>) -> Self {
+        let directory = PathBuf::from(format!("{}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), id));
+        Results { paths, directory, journal_tx }
+    }
+
+    /// Downloads the files, passes them to the journal, and returns their names
+    pub async fn fetch(&self, device: &fernbedienung::Device) -> anyhow::Result<Vec<String>> {
+        let files = self.paths.iter()
+            .filter_map(|file| filename(file).map(|filename| (file.clone(), filename)));
+        download(device, files, journal::Destination::Directory(self.directory.clone()), &self.journal_tx).await
+    }
+}
+
+/// Returns the files that are found by running `find` on the robot with the given arguments
+async fn find(device: &fernbedienung::Device, args: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let find = fernbedienung::Process {
+        target: "find".into(),
+        working_dir: None,
+        args,
+        environment: Default::default(),
+    };
+    let (stdout_tx, stdout_rx) = mpsc::channel(8);
+    let stdout_stream = ReceiverStream::new(stdout_rx);
+    let (_, stdout) = tokio::try_join!(
+        device.run(find, None, None, stdout_tx, None),
+        stdout_stream.concat().map(fernbedienung::Result::Ok)
+    )?;
+    let files = String::from_utf8_lossy(stdout.as_ref()).lines()
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .map(str::to_owned)
+        .collect();
+    Ok(files)
+}
+
+/* the name under which a file is stored, files without a name, e.g., "..", are skipped */
+fn filename(file: &str) -> Option<PathBuf> {
+    Path::new(file).file_name().map(PathBuf::from)
+}
+
+/// Downloads files from a robot and passes each of them to the journal, which stores it at the given path
+/// relative to the destination. This is the only way in which files from the robots are written to disk.
+/// The paths of the stored files are returned
+async fn download(
+    device: &fernbedienung::Device,
+    files: impl Iterator<Item = (String, PathBuf)>,
+    destination: journal::Destination,
+    journal_tx: &mpsc::Sender<journal::Action>
+) -> anyhow::Result<Vec<String>> {
+    let mut stored = Vec::new();
+    for (file, path) in files {
+        let contents = device.download(&file).await
+            .context(format!("Could not download {}", file))?;
+        let (callback_tx, callback_rx) = oneshot::channel();
+        journal_tx.send(journal::Action::StoreArtifact(destination.clone(), path.clone(), contents, callback_tx)).await
+            .map_err(|_| anyhow::anyhow!("Could not send {} to the journal", file))?;
+        callback_rx.await
+            .map_err(|_| anyhow::anyhow!("No response from the journal"))??;
+        stored.push(path.to_string_lossy().into_owned());
+    }
+    Ok(stored)
+}
+
+/// The temporary working directory of ARGoS on a robot, which is deleted when the robot is rebooted. After
+/// each experiment, the files in this directory are downloaded and stored with the artifacts of the experiment
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WorkingDirectory;
+
+/* the subdirectory of the artifacts of each robot into which its working directory is retrieved */
+const WORKING_DIRECTORY: &str = "working_directory";
+
+impl WorkingDirectory {
+    /// Downloads the files in the working directory and passes them to the journal, the number of files
+    /// is returned. Core dumps are excluded since they are retrieved separately
+    pub async fn retrieve(
+        device: &fernbedienung::Device,
+        working_dir: &str,
+        id: &str,
+        journal_tx: &mpsc::Sender<journal::Action>
+    ) -> anyhow::Result<usize> {
+        let args = vec![working_dir.to_owned(), "-type".to_owned(), "f".to_owned(),
+            "!".to_owned(), "-name".to_owned(), "core.*".to_owned()];
+        let files = find(device, args).await
+            .context("Could not list the working directory")?;
+        /* the files are stored relative to the working directory */
+        let files = files.into_iter().map(|file| {
+            let path = Path::new(WORKING_DIRECTORY).join(Path::new(&file).strip_prefix(working_dir)
+                .unwrap_or_else(|_| Path::new(&file)));
+            (file, path)
+        });
+        let stored = download(device, files, journal::Destination::Experiment(id.to_owned()), journal_tx).await?;
+        Ok(stored.len())
+    }
+}
+
+/* core dumps are written into the working directory of the crashed process, i.e., the temporary directory of ARGoS */
+const CORE_PATTERN: &str = "core.%e.%p";
+
+/// Retrieval of core dumps and backtraces from processes that crash on a robot. The core pattern of the
+/// robot is set before an experiment and the size limit for core dumps is lifted for the process. After
+/// the process terminated abnormally, the backtrace is generated on the robot using gdb
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CrashDumps;
+
+impl CrashDumps {
+    /// Sets the core pattern of the robot so that core dumps are written into the working directory
+    pub async fn enable(device: &fernbedienung::Device) -> anyhow::Result<()> {
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), format!("echo '{}' > /proc/sys/kernel/core_pattern", CORE_PATTERN)],
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await
+            .context("Could not set the core pattern")
+    }
+
+    /// Lifts the size limit for core dumps, this wrapper must be applied after the resource limits so that
+    /// the limit is inherited by all of the wrapping processes
+    pub fn apply(process: fernbedienung::Process) -> fernbedienung::Process {
+        let mut args = vec![
+            "-c".to_owned(),
+            "ulimit -c unlimited && exec \"$@\"".to_owned(),
+            "sh".to_owned(),
+            process.target.to_string_lossy().into_owned()
+        ];
+        args.extend(process.args);
+        fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: process.working_dir,
+            args,
+            environment: process.environment,
+        }
+    }
+
+    /// Searches the working directory for a core dump of the target and returns the backtraces of all of
+    /// its threads. If the identifier of the robot and the journal are provided, the core dump itself is
+    /// also stored with the artifacts of the experiment
+    pub async fn retrieve(
+        device: &fernbedienung::Device,
+        working_dir: &str,
+        target: &str,
+        artifacts: Option<(&str, &mpsc::Sender<journal::Action>)>,
+    ) -> anyhow::Result<Option<String>> {
+        let args = vec![
+            working_dir.to_owned(),
+            "-maxdepth".to_owned(), "1".to_owned(),
+            "-type".to_owned(), "f".to_owned(),
+            "-name".to_owned(), format!("core.{}.*", target)
+        ];
+        let files = find(device, args).await
+            .context("Could not search for core dumps")?;
+        let core = match files.into_iter().next() {
+            Some(core) => core,
+            None => return Ok(None),
+        };
+        /* generate the backtrace on the robot where the executable and its libraries are available */
+        let gdb = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec![
+                "-c".to_owned(),
+                "exec gdb --batch --quiet -ex 'thread apply all bt' \"$(command -v \"$0\")\" \"$1\"".to_owned(),
+                target.to_owned(),
+                core.clone(),
+            ],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            device.run(gdb, None, None, stdout_tx, None),
+            stdout_stream.concat().map(fernbedienung::Result::Ok)
+        ).context(format!("Could not generate backtrace from {}", core))?;
+        let backtrace = String::from_utf8_lossy(stdout.as_ref()).trim().to_owned();
+        /* download the core dump, note that core dumps can be large */
+        if let Some((id, journal_tx)) = artifacts {
+            let filename = filename(&core)
+                .ok_or(anyhow::anyhow!("Could not determine name of {}", core))?;
+            let files = std::iter::once((core.clone(), filename));
+            download(device, files, journal::Destination::Experiment(id.to_owned()), journal_tx).await?;
+        }
+        Ok(Some(backtrace))
+    }
+}
+
 #[pin_project::pin_project(PinnedDrop)]
 pub struct MjpegStreamerStream<'dev, S> {
     terminate_tx: Option<oneshot::Sender<()>>,
@@ -26,12 +405,44 @@ impl<S> PinnedDrop for MjpegStreamerStream<'_, S> {
 }
 
 impl MjpegStreamerStream<'_, ()> {
+    /// Checks whether mjpg_streamer is installed on the robot and which of the given camera devices are
+    /// present, so that the camera streams are not started on a robot that can not support them
+    pub async fn availability(device: &fernbedienung::Device, cameras: &[&str]) -> anyhow::Result<camera::Availability> {
+        let mut args = vec![
+            "-c".to_owned(),
+            "command -v mjpg_streamer > /dev/null && echo mjpg_streamer; for camera in \"$@\"; do [ -e \"$camera\" ] && echo \"$camera\"; done; true".to_owned(),
+            "sh".to_owned(),
+        ];
+        args.extend(cameras.iter().map(|&camera| camera.to_owned()));
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args,
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            device.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(fernbedienung::Result::Ok)
+        ).context("Could not check the availability of the cameras")?;
+        let stdout = String::from_utf8_lossy(stdout.as_ref());
+        let found = stdout.lines().map(str::trim).collect::<Vec<_>>();
+        Ok(camera::Availability {
+            streamer: found.contains(&"mjpg_streamer"),
+            cameras: cameras.iter()
+                .map(|&camera| (camera.to_owned(), found.contains(&camera)))
+                .collect(),
+        })
+    }
+
     pub fn new<'dev>(
         device: &'dev fernbedienung::Device,
         camera: &str,
         width: u16,
         height: u16,
-        port: u16
+        port: u16,
+        limits: &ResourceLimits,
     ) -> impl Stream<Item = reqwest::Result<Bytes>> + 'dev {
         let mjpg_streamer = fernbedienung::Process {
             target: "mjpg_streamer".into(),
@@ -42,12 +453,15 @@ impl MjpegStreamerStream<'_, ()> {
                 "-o".to_owned(),
                 format!("output_http.so -p {} -l {}", port, device.addr)
             ],
+            environment: Default::default(),
         };
+        let limits = limits.clone();
         let (terminate_tx, terminate_rx) = oneshot::channel::<()>();
-        let mjpg_streamer = device.run(mjpg_streamer, Some(terminate_rx), None, None, None);
         let source = format!("http://{}:{}/?action=snapshot", device.addr, port);
         MjpegStreamerStream {
             device, terminate_tx: Some(terminate_tx), stream: async_stream::stream! {
+                let mjpg_streamer = limits.apply(device, mjpg_streamer).await;
+                let mjpg_streamer = device.run(mjpg_streamer, Some(terminate_rx), None, None, None);
                 tokio::pin!(mjpg_streamer);
                 loop {
                     tokio::select! {
@@ -69,3 +483,273 @@ impl<S: futures::Stream> Stream for MjpegStreamerStream<'_, S>  {
     }
 }
 
+
+/* the watchdog is installed in a directory that survives reboots, the same holds for its log */
+const WATCHDOG_DIR: &str = "/var/tmp/supervisor_watchdog";
+const WATCHDOG_LOG: &str = "/var/tmp/supervisor_watchdog.log";
+const WATCHDOG_UNIT: &str = "supervisor-watchdog";
+
+/// A watchdog that is deployed to a robot when it is associated. The watchdog runs as a transient systemd
+/// unit and restarts the Fernbedienung service (or reboots the robot) if the service stops responding. Its
+/// interventions are logged on the robot and reported when the robot is associated again
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    /* the interval between two checks of the service */
+    pub interval: Duration,
+    /* the number of consecutive failed checks after which the watchdog intervenes */
+    pub attempts: u32,
+    /* the systemd unit of the Fernbedienung service */
+    pub service: String,
+}
+
+impl Watchdog {
+    /// Reads and clears the interventions that were logged by a previously deployed watchdog
+    pub async fn events(device: &fernbedienung::Device) -> anyhow::Result<Vec<String>> {
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), format!("cat {0} 2>/dev/null; rm -f {0}", WATCHDOG_LOG)],
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let stdout_stream = ReceiverStream::new(stdout_rx);
+        let (_, stdout) = tokio::try_join!(
+            device.run(process, None, None, stdout_tx, None),
+            stdout_stream.concat().map(fernbedienung::Result::Ok)
+        ).context("Could not read the log of the watchdog")?;
+        Ok(String::from_utf8_lossy(stdout.as_ref())
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// Uploads the watchdog together with the status LED script of the robot type and (re)starts it,
+    /// replacing the watchdog that was deployed during a previous association
+    pub async fn deploy(&self, device: &fernbedienung::Device, status_leds: &str) -> anyhow::Result<()> {
+        device.upload(WATCHDOG_DIR, "watchdog.py", crate::robot::WATCHDOG_PY).await
+            .context("Could not upload the watchdog")?;
+        device.upload(WATCHDOG_DIR, "status_leds.py", status_leds).await
+            .context("Could not upload the status LED script")?;
+        let script = format!(
+            "systemctl stop {0} 2>/dev/null; systemctl reset-failed {0} 2>/dev/null; exec systemd-run --unit={0} --quiet python3 \"$@\"",
+            WATCHDOG_UNIT);
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec![
+                "-c".to_owned(),
+                script,
+                "sh".to_owned(),
+                format!("{}/watchdog.py", WATCHDOG_DIR),
+                self.interval.as_secs_f64().to_string(),
+                self.attempts.to_string(),
+                self.service.clone(),
+                fernbedienung::PORT.to_string(),
+                format!("{}/status_leds.py", WATCHDOG_DIR),
+                WATCHDOG_LOG.to_owned(),
+            ],
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await
+            .context("Could not start the watchdog")
+    }
+
+    /// Stops a watchdog that was deployed during a previous association, e.g., after the watchdog has been
+    /// removed from the configuration
+    pub async fn stop(device: &fernbedienung::Device) -> anyhow::Result<()> {
+        let process = fernbedienung::Process {
+            target: "sh".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), format!("systemctl stop {} 2>/dev/null || true", WATCHDOG_UNIT)],
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await
+            .context("Could not stop the watchdog")
+    }
+}
+
+/* the prefix of the lines in the output of a smoke test that report a check */
+const SMOKE_TEST_PREFIX: &str = "SMOKE_TEST\t";
+/* the time after which a smoke test is terminated */
+const SMOKE_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A smoke test briefly exercises the actuators and reads the sensors of a robot. The smoke test is a
+/// process (e.g., ARGoS or a Python script) that writes one line per check to standard output in the form
+/// `SMOKE_TEST<tab>PASS|FAIL<tab>check<tab>measured value`, any other output is ignored
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmokeTest;
+
+impl SmokeTest {
+    /// Uploads the files into a temporary directory, which becomes the working directory of the process,
+    /// runs the process, and returns the checks that it reported
+    pub async fn run(
+        device: &fernbedienung::Device,
+        mut process: fernbedienung::Process,
+        files: &[(&str, &[u8])],
+    ) -> anyhow::Result<Vec<SmokeTestCheck>> {
+        if !files.is_empty() {
+            let path = device.create_temp_dir().await
+                .context("Could not create temporary directory")?;
+            for &(filename, contents) in files {
+                device.upload(&path, filename, contents).await
+                    .context("Could not upload smoke test")?;
+            }
+            process.working_dir = Some(path.into());
+        }
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let (stderr_tx, stderr_rx) = mpsc::channel(8);
+        let (terminate_tx, terminate_rx) = oneshot::channel();
+        let run = async move {
+            let run = device.run(process, terminate_rx, None, stdout_tx, stderr_tx);
+            tokio::pin!(run);
+            match tokio::time::timeout(SMOKE_TEST_TIMEOUT, &mut run).await {
+                Ok(result) => result.context("Smoke test failed"),
+                Err(_) => {
+                    let _ = terminate_tx.send(());
+                    let _ = run.await;
+                    Err(anyhow::anyhow!("Smoke test did not finish within {} seconds", SMOKE_TEST_TIMEOUT.as_secs()))
+                }
+            }
+        };
+        let (result, stdout, stderr) = tokio::join!(
+            run,
+            ReceiverStream::new(stdout_rx).concat(),
+            ReceiverStream::new(stderr_rx).concat()
+        );
+        let checks = String::from_utf8_lossy(stdout.as_ref())
+            .lines()
+            .filter_map(|line| line.find(SMOKE_TEST_PREFIX).map(|start| &line[start + SMOKE_TEST_PREFIX.len()..]))
+            .filter_map(|check| {
+                let mut fields = check.splitn(3, '\t');
+                let passed = match fields.next()? {
+                    "PASS" => true,
+                    "FAIL" => false,
+                    _ => return None,
+                };
+                let name = fields.next()?.trim().to_owned();
+                let value = fields.next().unwrap_or_default().trim().to_owned();
+                Some(SmokeTestCheck { name, passed, value })
+            })
+            .collect::<Vec<_>>();
+        match (result, checks.is_empty()) {
+            (Ok(_), false) => Ok(checks),
+            (Ok(_), true) => Err(anyhow::anyhow!("Smoke test did not report any checks")),
+            /* report the checks that were completed before the smoke test failed */
+            (Err(error), false) => {
+                let mut checks = checks;
+                checks.push(SmokeTestCheck { name: "Completion".to_owned(), passed: false, value: format!("{:#}", error) });
+                Ok(checks)
+            },
+            (Err(error), true) => {
+                let stderr = String::from_utf8_lossy(stderr.as_ref());
+                match stderr.lines().map(str::trim).filter(|line| !line.is_empty()).last() {
+                    Some(line) => Err(error).context(line.to_owned()),
+                    None => Err(error),
+                }
+            }
+        }
+    }
+}
+
+/* the port of the iperf3 server that is spawned on the supervisor machine for a bandwidth test */
+const BANDWIDTH_TEST_PORT: u16 = 5201;
+/* the duration of the transfer in each direction and the time after which a transfer is terminated */
+const BANDWIDTH_TEST_DURATION: Duration = Duration::from_secs(3);
+const BANDWIDTH_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+/* the time that the iperf3 server is given to start listening */
+const BANDWIDTH_TEST_STARTUP: Duration = Duration::from_millis(500);
+
+/// A bandwidth test measures the throughput between a robot and the supervisor machine. An iperf3 server is
+/// spawned on the supervisor machine and iperf3 is run on the robot as a client, first sending to the supervisor
+/// and then receiving from it. Both machines must have iperf3 installed. The tests are run one at a time by the
+/// arena since they use the same port on the supervisor machine
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BandwidthTest;
+
+impl BandwidthTest {
+    /// Measures the throughput in both directions and reports each direction as a check
+    pub async fn run(device: &fernbedienung::Device) -> anyhow::Result<Vec<SmokeTestCheck>> {
+        /* the address of the supervisor machine on the network of the robot */
+        let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect((device.addr, 80)).await?;
+        let local_addr = socket.local_addr()?.ip();
+        let mut checks = Vec::new();
+        for (name, reverse) in [("Throughput to supervisor", false), ("Throughput from supervisor", true)] {
+            let check = match BandwidthTest::transfer(device, local_addr, reverse).await {
+                Ok(bits_per_second) => SmokeTestCheck {
+                    name: name.to_owned(),
+                    passed: bits_per_second > 0.0,
+                    value: format!("{:.1} Mbit/s", bits_per_second / 1e6),
+                },
+                Err(error) => SmokeTestCheck {
+                    name: name.to_owned(),
+                    passed: false,
+                    value: format!("{:#}", error),
+                },
+            };
+            checks.push(check);
+        }
+        Ok(checks)
+    }
+
+    /// Transfers data for the duration of the test and returns the throughput in bits per second
+    async fn transfer(
+        device: &fernbedienung::Device,
+        local_addr: std::net::IpAddr,
+        reverse: bool,
+    ) -> anyhow::Result<f64> {
+        /* the server exits after it has served one client */
+        let mut server = tokio::process::Command::new("iperf3")
+            .args(&["--server", "--one-off", "--port", &BANDWIDTH_TEST_PORT.to_string(),
+                    "--bind", &local_addr.to_string()])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Could not start iperf3 server")?;
+        tokio::time::sleep(BANDWIDTH_TEST_STARTUP).await;
+        let mut args = vec![
+            "--client".to_owned(), local_addr.to_string(),
+            "--port".to_owned(), BANDWIDTH_TEST_PORT.to_string(),
+            "--time".to_owned(), BANDWIDTH_TEST_DURATION.as_secs().to_string(),
+            "--json".to_owned(),
+        ];
+        if reverse {
+            args.push("--reverse".to_owned());
+        }
+        let process = fernbedienung::Process {
+            target: "iperf3".into(),
+            working_dir: None,
+            args,
+            environment: Default::default(),
+        };
+        let (stdout_tx, stdout_rx) = mpsc::channel(8);
+        let (terminate_tx, terminate_rx) = oneshot::channel();
+        let run = async move {
+            let run = device.run(process, terminate_rx, None, stdout_tx, None);
+            tokio::pin!(run);
+            match tokio::time::timeout(BANDWIDTH_TEST_TIMEOUT, &mut run).await {
+                Ok(result) => result.context("Could not run iperf3 on robot"),
+                Err(_) => {
+                    let _ = terminate_tx.send(());
+                    let _ = run.await;
+                    Err(anyhow::anyhow!("Transfer did not finish within {} seconds", BANDWIDTH_TEST_TIMEOUT.as_secs()))
+                }
+            }
+        };
+        let (result, stdout) = tokio::join!(run, ReceiverStream::new(stdout_rx).concat());
+        let _ = server.kill().await;
+        /* iperf3 reports its errors in the JSON output as well */
+        let report: serde_json::Value = serde_json::from_slice(stdout.as_ref())
+            .context("Could not parse the report of iperf3")
+            .or_else(|error| result.and(Err(error)))?;
+        if let Some(error) = report["error"].as_str() {
+            return Err(anyhow::anyhow!("iperf3: {}", error));
+        }
+        /* the data that arrived at the receiving end, regardless of the direction */
+        report["end"]["sum_received"]["bits_per_second"].as_f64()
+            .ok_or_else(|| anyhow::anyhow!("The report of iperf3 does not contain the throughput"))
+    }
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, net::Ipv4Addr, path::Path};
+use anyhow::Context;
+use macaddr::MacAddr6;
+use shared::configuration::TargetedProbing;
+
+/* the ARP table of the supervisor machine as maintained by the kernel */
+const ARP_TABLE: &str = "/proc/net/arp";
+/* the flag of an entry in the ARP table whose hardware address has been resolved */
+const ATF_COM: u32 = 0x02;
+
+/// Parses a lease file of dnsmasq, where each line contains the expiry time, the MAC address, and the IP
+/// address of a lease followed by the host name and the client identifier
+fn parse_dnsmasq(contents: &str) -> Vec<(MacAddr6, Ipv4Addr)> {
+    contents.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let macaddr = fields.next()?.parse().ok()?;
+            let addr = fields.next()?.parse().ok()?;
+            Some((macaddr, addr))
+        })
+        .collect()
+}
+
+/// Parses a lease file of the ISC DHCP server, where each lease is a block such as
+/// `lease 192.168.1.10 { ... hardware ethernet 00:11:22:33:44:55; ... }`. Later leases for the same address
+/// replace earlier ones, which is handled by the caller collecting the leases into a map
+fn parse_dhcpd(contents: &str) -> Vec<(MacAddr6, Ipv4Addr)> {
+    let mut leases = Vec::new();
+    let mut addr: Option<Ipv4Addr> = None;
+    for line in contents.lines().map(str::trim) {
+        if let Some(lease) = line.strip_prefix("lease ") {
+            addr = lease.trim_end_matches('{').trim().parse().ok();
+        }
+        else if let Some(hardware) = line.strip_prefix("hardware ethernet ") {
+            let macaddr = hardware.trim_end_matches(';').trim().parse();
+            if let (Some(addr), Ok(macaddr)) = (addr, macaddr) {
+                leases.push((macaddr, addr));
+            }
+        }
+        else if line == "}" {
+            addr = None;
+        }
+    }
+    leases
+}
+
+/// Parses the ARP table of the kernel, only the entries whose hardware address has been resolved are used
+fn parse_arp(contents: &str) -> Vec<(MacAddr6, Ipv4Addr)> {
+    contents.lines()
+        /* skip the header */
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let addr = fields.get(0)?.parse().ok()?;
+            let flags = u32::from_str_radix(fields.get(2)?.trim_start_matches("0x"), 16).ok()?;
+            let macaddr = fields.get(3)?.parse().ok()?;
+            match flags & ATF_COM {
+                0 => None,
+                _ => Some((macaddr, addr)),
+            }
+        })
+        .collect()
+}
+
+async fn read(path: &Path) -> anyhow::Result<String> {
+    tokio::fs::read_to_string(path).await
+        .context(format!("Could not read {:?}", path))
+}
+
+/// Reads the addresses of the devices on the robot network from the DHCP leases and the ARP table, the
+/// format of the lease file is detected from its contents
+pub async fn addresses(configuration: &TargetedProbing) -> anyhow::Result<HashMap<MacAddr6, Ipv4Addr>> {
+    let mut addresses = HashMap::new();
+    if let Some(leases) = &configuration.leases {
+        let contents = read(Path::new(leases)).await?;
+        let leases = match contents.lines().any(|line| line.trim_start().starts_with("lease ")) {
+            true => parse_dhcpd(&contents),
+            false => parse_dnsmasq(&contents),
+        };
+        addresses.extend(leases);
+    }
+    /* the ARP table takes precedence since it reflects the addresses that are currently in use */
+    if configuration.arp {
+        let contents = read(Path::new(ARP_TABLE)).await?;
+        addresses.extend(parse_arp(&contents));
+    }
+    Ok(addresses)
+}
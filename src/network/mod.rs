@@ -1,76 +1,386 @@
 
 use macaddr::MacAddr6;
-use std::{net::Ipv4Addr, time::Duration};
+use std::{collections::{HashMap, HashSet, VecDeque}, net::Ipv4Addr, sync::Arc, time::Duration};
+use shared::{association, builderbot, drone, pipuck, configuration::{Heartbeat, ProbeConfiguration, TargetedProbing}};
 use ipnet::Ipv4Net;
 
-use tokio::sync::{mpsc, oneshot};
+use tokio::{sync::{mpsc, oneshot, watch, OwnedSemaphorePermit, Semaphore}, time::Instant};
 use tokio_stream::StreamExt;
+use tokio_util::time::DelayQueue;
 use futures::stream::FuturesUnordered;
 
 pub mod xbee;
 pub mod fernbedienung;
 pub mod fernbedienung_ext;
+mod leases;
 
 use crate::arena;
 
-/// This function represents the main task of the network module. It takes a network and a channel for
-/// making requests to the arena. IP addresses belonging to this network are repeated probed for an
-/// xbee or for the fernbedienung service until they are associated
-pub async fn new(network: Ipv4Net, arena_request_tx: mpsc::Sender<arena::Action>) {
+/* the delay between two attempts to reconnect to a robot whose connection was lost */
+const REPROBE_DELAY: Duration = Duration::from_millis(250);
+
+/// The probing state of the network module, this is controlled by the arena when maintenance mode is
+/// entered or exited
+#[derive(Clone, Debug)]
+pub enum Probing {
+    Enabled,
+    /* an empty list of addresses pauses the probing of all addresses */
+    Paused(Vec<Ipv4Addr>),
+}
+
+/// The MAC addresses of the devices of the configured robots and the fixed addresses of these devices, which
+/// are controlled by the arena since they change when the robots are reloaded or a device is adopted
+#[derive(Clone, Debug, Default)]
+pub struct Robots {
+    pub macaddrs: HashSet<MacAddr6>,
+    pub fixed_addrs: HashMap<MacAddr6, Ipv4Addr>,
+}
+
+impl Robots {
+    pub fn new<'a>(
+        builderbots: impl IntoIterator<Item = &'a builderbot::Descriptor>,
+        drones: impl IntoIterator<Item = &'a drone::Descriptor>,
+        pipucks: impl IntoIterator<Item = &'a pipuck::Descriptor>,
+    ) -> Self {
+        let mut robots = Robots::default();
+        for desc in builderbots {
+            robots.add(desc.duovero_macaddr, desc.duovero_addr);
+        }
+        for desc in drones {
+            robots.add(desc.xbee_macaddr, None);
+            robots.add(desc.upcore_macaddr, desc.upcore_addr);
+        }
+        for desc in pipucks {
+            robots.add(desc.rpi_macaddr, desc.rpi_addr);
+        }
+        robots
+    }
+
+    fn add(&mut self, macaddr: MacAddr6, fixed_addr: Option<Ipv4Addr>) {
+        self.macaddrs.insert(macaddr);
+        if let Some(fixed_addr) = fixed_addr {
+            self.fixed_addrs.insert(macaddr, fixed_addr);
+        }
+    }
+}
+
+impl Probing {
+    pub fn is_paused(&self, addr: &Ipv4Addr) -> bool {
+        match self {
+            Probing::Enabled => false,
+            Probing::Paused(addrs) => addrs.is_empty() || addrs.contains(addr),
+        }
+    }
+}
+
+/// Whether the network module probes the addresses at which no robot was lost, this is controlled by the arena,
+/// which pauses the discovery of robots while an experiment is running so that probing does not compete with
+/// the robots for the airtime of the wireless network
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Discovery {
+    Enabled,
+    Paused,
+}
+
+impl Discovery {
+    pub fn is_paused(&self) -> bool {
+        matches!(self, Discovery::Paused)
+    }
+}
+
+/// A probe of an address for one of the protocols
+#[derive(Debug)]
+enum Probe {
+    Xbee(Ipv4Addr),
+    Fernbedienung(Ipv4Addr),
+}
+
+/// The probes that are waiting to be made. A probe is ready once its delay has passed and it is only made
+/// once one of the permits that limit the number of simultaneous probes is available, so that probing a
+/// large network does not create a future for every address at once
+struct Queue {
+    ready: VecDeque<Probe>,
+    delayed: DelayQueue<Probe>,
+    permits: Arc<Semaphore>,
+}
+
+impl Queue {
+    fn new(concurrency: usize) -> Self {
+        Queue {
+            ready: VecDeque::new(),
+            delayed: DelayQueue::new(),
+            permits: Arc::new(Semaphore::new(concurrency)),
+        }
+    }
+
+    fn push(&mut self, probe: Probe, delay: Duration) {
+        match delay.is_zero() {
+            true => self.ready.push_back(probe),
+            false => {
+                self.delayed.insert(probe, delay);
+            }
+        }
+    }
+
+    /// The next probe that is ready together with the permit that is held until the probe completes
+    fn pop(&mut self) -> Option<(Probe, OwnedSemaphorePermit)> {
+        if self.ready.is_empty() {
+            return None;
+        }
+        let permit = self.permits.clone().try_acquire_owned().ok()?;
+        self.ready.pop_front().map(|probe| (probe, permit))
+    }
+}
+
+/// The reason why the association of a device failed, if it should be reported to the user. Other errors
+/// are expected when probing addresses at which no robot is present
+fn association_failure(error: &anyhow::Error) -> Option<association::Failure> {
+    match error.downcast_ref::<fernbedienung::Error>() {
+        Some(fernbedienung::Error::AuthenticationError(reason)) =>
+            Some(association::Failure::Authentication(reason.clone())),
+        _ => None,
+    }
+}
+
+/// This function represents the main task of the network module. It takes a network, a channel for
+/// making requests to the arena, receivers for the probing and discovery states, and optionally a connector for
+/// authenticating the fernbedienung service via TLS. IP addresses belonging to this network are
+/// repeated probed for an xbee or for the fernbedienung service until they are associated. At most
+/// `concurrency` probes are made at the same time and each address waits for the retry delay of a protocol
+/// before it is probed with that protocol again. Addresses that are returned while their probing is paused
+/// are held back until probing is resumed. While the discovery of robots is paused, e.g., during an
+/// experiment, only the addresses of the robots whose connection was lost are probed again and the other
+/// addresses are held back until discovery is resumed. The address of a robot whose heartbeat timed out is
+/// only probed for the fernbedienung service until the robot reconnects or the reconnection window of the heartbeat
+/// expires. With targeted probing, only the addresses that the DHCP leases or the ARP table associate with
+/// the MAC addresses of the configured robots are probed, except for the robots with a fixed address, for
+/// which only the fixed address is probed. Changes to the configured robots take effect when the leases are
+/// read again
+pub async fn new(network: Ipv4Net,
+                 arena_request_tx: mpsc::Sender<arena::Action>,
+                 mut probing_rx: watch::Receiver<Probing>,
+                 mut discovery_rx: watch::Receiver<Discovery>,
+                 tls: Option<fernbedienung::TlsConnector>,
+                 heartbeat: Heartbeat,
+                 configuration: ProbeConfiguration,
+                 targeted_probing: Option<TargetedProbing>,
+                 robots_rx: watch::Receiver<Robots>) {
+    let fixed_addrs = robots_rx.borrow().fixed_addrs.clone();
+    /* the addresses that are being probed or that are associated with a device, with targeted probing these
+       are only the addresses of the configured robots, which are found when the leases are first read */
+    let mut probed: HashSet<Ipv4Addr> = match targeted_probing {
+        Some(_) => fixed_addrs.values().copied().collect(),
+        None => network.hosts().collect(),
+    };
+    /* the addresses of the configured robots according to the last reading of the leases and their fixed
+       addresses, which are not taken from the leases */
+    let mut targets: HashSet<Ipv4Addr> = fixed_addrs.values().copied().collect();
+    let mut refresh = tokio::time::interval(Duration::from_secs(targeted_probing.as_ref()
+        .map_or(1, |targeted_probing| targeted_probing.interval)));
+    let xbee_timeout = Duration::from_millis(configuration.xbee.timeout);
+    let xbee_retry_delay = Duration::from_millis(configuration.xbee.retry_delay);
+    let fernbedienung_timeout = Duration::from_millis(configuration.fernbedienung.timeout);
+    let fernbedienung_retry_delay = Duration::from_millis(configuration.fernbedienung.retry_delay);
     /* probe for xbees on all addresses */
-    let (mut xbee_returned_addrs, mut probe_xbee_queue) : (FuturesUnordered<_>, FuturesUnordered<_>) = network
-        .hosts()
-        .map(|addr| {
-            let (return_addr_tx, return_addr_rx) = oneshot::channel();
-            (return_addr_rx, probe_xbee(return_addr_tx, addr))
-        }).unzip();
-    /* empty collections for the fernbedienung tasks */
-    let mut fernbedienung_returned_addrs : FuturesUnordered<oneshot::Receiver<Ipv4Addr>> = Default::default();
+    let mut queue = Queue::new(configuration.concurrency);
+    for &addr in &probed {
+        queue.push(Probe::Xbee(addr), Duration::default());
+    }
+    /* empty collections for the probes that are being made */
+    let mut xbee_returned_addrs : FuturesUnordered<oneshot::Receiver<Ipv4Addr>> = Default::default();
+    let mut probe_xbee_queue: FuturesUnordered<_> = Default::default();
+    let mut fernbedienung_returned_addrs : FuturesUnordered<oneshot::Receiver<fernbedienung::Returned>> = Default::default();
     let mut probe_fernbedienung_queue: FuturesUnordered<_> = Default::default();
+    /* addresses that are not being probed due to maintenance mode */
+    let mut paused_addrs: Vec<Ipv4Addr> = Default::default();
+    /* addresses that are not being probed while the discovery of robots is paused */
+    let mut undiscovered_addrs: Vec<Ipv4Addr> = Default::default();
+    /* the addresses of the associated xbees and of the xbees that were lost with the deadlines for reconnecting
+       to them, which are probed again while the discovery of robots is paused */
+    let mut xbee_addrs: HashSet<Ipv4Addr> = Default::default();
+    let mut lost_xbees: HashMap<Ipv4Addr, Instant> = Default::default();
+    /* addresses at which the association failed for a reason that has been reported to the arena */
+    let mut association_failures: HashMap<Ipv4Addr, association::Failure> = Default::default();
+    /* the addresses of the robots whose connection was lost and the deadlines for reconnecting to them */
+    let mut reconnecting: HashMap<Ipv4Addr, Instant> = Default::default();
     /* main task loop */
     loop {
+        /* make the probes that are ready while permits are available, a permit is returned when its probe
+           completes, which wakes up this loop */
+        while let Some((probe, permit)) = queue.pop() {
+            match probe {
+                Probe::Xbee(addr) => {
+                    let (return_addr_tx, return_addr_rx) = oneshot::channel();
+                    xbee_returned_addrs.push(return_addr_rx);
+                    probe_xbee_queue.push(probe_xbee(return_addr_tx, addr, xbee_timeout, permit));
+                },
+                Probe::Fernbedienung(addr) => {
+                    let (return_addr_tx, return_addr_rx) = oneshot::channel();
+                    fernbedienung_returned_addrs.push(return_addr_rx);
+                    probe_fernbedienung_queue.push(probe_fernbedienung(return_addr_tx, addr, tls.clone(), fernbedienung_timeout, permit));
+                },
+            }
+        }
         tokio::select!{
             Some(result) = probe_xbee_queue.next() => {
                 if let Ok((mac_addr, device)) = result {
+                    lost_xbees.remove(&device.addr);
+                    xbee_addrs.insert(device.addr);
                     let _ = arena_request_tx.send(arena::Action::AddXbee(device, mac_addr)).await;
                 }
             },
             Some(result) = xbee_returned_addrs.next() => match result {
                 Ok(addr) => {
-                    let (return_addr_tx, return_addr_rx) = oneshot::channel();
-                    fernbedienung_returned_addrs.push(return_addr_rx);
-                    probe_fernbedienung_queue.push(probe_fernbedienung(return_addr_tx, addr));
+                    if xbee_addrs.remove(&addr) {
+                        lost_xbees.insert(addr, Instant::now() + Duration::from_secs(heartbeat.reconnect));
+                    }
+                    match probing_rx.borrow().is_paused(&addr) {
+                        true => paused_addrs.push(addr),
+                        false => queue.push(Probe::Fernbedienung(addr), fernbedienung_retry_delay),
+                    }
                 },
                 Err(_) => {
                     log::error!("xbee::Device did not return its IP address");
                 }
             },
-            Some(result) = probe_fernbedienung_queue.next() => {
+            Some((addr, result)) = probe_fernbedienung_queue.next() => {
+                /* only report changes since the address is probed repeatedly */
+                let failure = result.as_ref().err().and_then(association_failure);
+                if association_failures.get(&addr) != failure.as_ref() {
+                    let update = match failure {
+                        Some(failure) => {
+                            log::warn!("Could not associate fernbedienung at {}: {:?}", addr, failure);
+                            association_failures.insert(addr, failure.clone());
+                            association::Update::Failed(addr, failure)
+                        },
+                        None => {
+                            association_failures.remove(&addr);
+                            association::Update::Cleared(addr)
+                        }
+                    };
+                    let _ = arena_request_tx.send(arena::Action::UpdateAssociation(update)).await;
+                }
                 if let Ok((mac_addr, device)) = result {
+                    if reconnecting.remove(&addr).is_some() {
+                        log::info!("Reconnected to fernbedienung at {}", addr);
+                    }
                     let _ = arena_request_tx.send(arena::Action::AddFernbedienung(device, mac_addr)).await;
                 }
             },
             Some(result) = fernbedienung_returned_addrs.next() => match result {
-                Ok(addr) => {
-                    let (return_addr_tx, return_addr_rx) = oneshot::channel();
-                    xbee_returned_addrs.push(return_addr_rx);
-                    probe_xbee_queue.push(probe_xbee(return_addr_tx, addr));
+                Ok(returned) => {
+                    let addr = returned.addr;
+                    if returned.lost {
+                        log::warn!("Lost connection to fernbedienung at {}, probing it again", addr);
+                        reconnecting.insert(addr, Instant::now() + Duration::from_secs(heartbeat.reconnect));
+                    }
+                    if probing_rx.borrow().is_paused(&addr) {
+                        reconnecting.remove(&addr);
+                        paused_addrs.push(addr);
+                    }
+                    else if reconnecting.get(&addr).map_or(false, |deadline| Instant::now() < *deadline) {
+                        /* probe the last known address of a lost robot without probing it for an xbee first */
+                        queue.push(Probe::Fernbedienung(addr), std::cmp::max(REPROBE_DELAY, fernbedienung_retry_delay));
+                    }
+                    else if targeted_probing.is_some() && !targets.contains(&addr) {
+                        /* the address is no longer leased to a configured robot */
+                        reconnecting.remove(&addr);
+                        probed.remove(&addr);
+                    }
+                    else if discovery_rx.borrow().is_paused() &&
+                        !lost_xbees.get(&addr).map_or(false, |deadline| Instant::now() < *deadline) {
+                        reconnecting.remove(&addr);
+                        lost_xbees.remove(&addr);
+                        undiscovered_addrs.push(addr);
+                    }
+                    else {
+                        reconnecting.remove(&addr);
+                        queue.push(Probe::Xbee(addr), xbee_retry_delay);
+                    }
                 },
                 Err(_) => {
                     log::error!("fernbedienung::Device did not return its IP address");
                 }
             },
+            Some(expired) = queue.delayed.next() => match expired {
+                Ok(expired) => queue.ready.push_back(expired.into_inner()),
+                Err(error) => log::error!("Could not delay probe: {}", error),
+            },
+            Ok(_) = probing_rx.changed() => {
+                let probing = probing_rx.borrow().clone();
+                let (paused, resumed) : (Vec<_>, Vec<_>) = paused_addrs
+                    .drain(..)
+                    .partition(|addr| probing.is_paused(addr));
+                paused_addrs = paused;
+                let (resumed, expired) : (Vec<_>, Vec<_>) = resumed
+                    .into_iter()
+                    .partition(|addr| targeted_probing.is_none() || targets.contains(addr));
+                for addr in expired {
+                    probed.remove(&addr);
+                }
+                if resumed.len() > 0 {
+                    log::info!("Resuming probing of {} addresses", resumed.len());
+                }
+                for addr in resumed {
+                    queue.push(Probe::Xbee(addr), Duration::default());
+                }
+            },
+            Ok(_) = discovery_rx.changed() => if !discovery_rx.borrow().is_paused() {
+                let (paused, resumed) : (Vec<_>, Vec<_>) = undiscovered_addrs
+                    .drain(..)
+                    .partition(|addr| probing_rx.borrow().is_paused(addr));
+                paused_addrs.extend(paused);
+                if resumed.len() > 0 {
+                    log::info!("Resuming the discovery of robots at {} addresses", resumed.len());
+                }
+                for addr in resumed {
+                    queue.push(Probe::Xbee(addr), Duration::default());
+                }
+            },
+            _ = refresh.tick(), if targeted_probing.is_some() => if let Some(targeted_probing) = targeted_probing.as_ref() {
+                match leases::addresses(targeted_probing).await {
+                    Ok(addresses) => {
+                        let robots = robots_rx.borrow().clone();
+                        targets = addresses.into_iter()
+                            .filter(|(macaddr, addr)| robots.macaddrs.contains(macaddr) &&
+                                !robots.fixed_addrs.contains_key(macaddr) && network.contains(addr))
+                            .map(|(_, addr)| addr)
+                            .chain(robots.fixed_addrs.values().copied())
+                            .collect();
+                        /* start probing the addresses that have been leased to a configured robot since the
+                           last reading, the other addresses are dropped once they are returned */
+                        let leased = targets.difference(&probed).copied().collect::<Vec<_>>();
+                        for addr in leased {
+                            probed.insert(addr);
+                            if probing_rx.borrow().is_paused(&addr) {
+                                paused_addrs.push(addr);
+                                continue;
+                            }
+                            if discovery_rx.borrow().is_paused() {
+                                undiscovered_addrs.push(addr);
+                                continue;
+                            }
+                            log::info!("Probing {}, which is leased to a configured robot", addr);
+                            queue.push(Probe::Xbee(addr), Duration::default());
+                        }
+                    },
+                    Err(error) => log::warn!("Could not read the addresses of the robots: {:#}", error),
+                }
+            },
             else => break
         }
     }
 }
 
 /// This function attempts to associate an xbee device with a given Ipv4Addr. The function starts the async 
-/// xbee::Device function `new` inside of a tokio::timeout which attempts the connection.
+/// xbee::Device function `new` inside of a tokio::timeout which attempts the connection. The permit is held
+/// until the attempt completes
 async fn probe_xbee(return_addr_tx: oneshot::Sender<Ipv4Addr>,
-                    addr: Ipv4Addr) -> anyhow::Result<(MacAddr6, xbee::Device)> {
-    /* assume address is an xbee and attempt to connect for 500 ms */
-    tokio::time::timeout(Duration::from_millis(500), async {
+                    addr: Ipv4Addr,
+                    timeout: Duration,
+                    _permit: OwnedSemaphorePermit) -> anyhow::Result<(MacAddr6, xbee::Device)> {
+    /* assume address is an xbee and attempt to connect until the timeout expires */
+    tokio::time::timeout(timeout, async {
         let device = xbee::Device::new(addr, return_addr_tx).await?;
         let mac_addr = device.mac().await?;
         Ok((mac_addr, device))
@@ -79,13 +389,20 @@ async fn probe_xbee(return_addr_tx: oneshot::Sender<Ipv4Addr>,
 
 /// This function attempts to associate an instance of the fernbedienung service with a given Ipv4Addr. The
 /// function starts the async fernbedienung::Device function `new` inside of a tokio::timeout which attempts
-/// the connection.
-async fn probe_fernbedienung(return_addr_tx: oneshot::Sender<Ipv4Addr>,
-                             addr: Ipv4Addr) -> anyhow::Result<(MacAddr6, fernbedienung::Device)> {
-    /* assume there is a fernbedienung instance running on `addr` and attempt to connect to it for 500 ms */
-    tokio::time::timeout(Duration::from_millis(500), async {
-        let device = fernbedienung::Device::new(addr, return_addr_tx).await?;
+/// the connection. The address is returned alongside the result so that failures can be reported. The
+/// permit is held until the attempt completes
+async fn probe_fernbedienung(return_addr_tx: oneshot::Sender<fernbedienung::Returned>,
+                             addr: Ipv4Addr,
+                             tls: Option<fernbedienung::TlsConnector>,
+                             timeout: Duration,
+                             _permit: OwnedSemaphorePermit)
+    -> (Ipv4Addr, anyhow::Result<(MacAddr6, fernbedienung::Device)>) {
+    /* assume there is a fernbedienung instance running on `addr` and attempt to connect to it until the
+       timeout expires */
+    let result = tokio::time::timeout(timeout, async {
+        let device = fernbedienung::Device::new(addr, return_addr_tx, tls).await?;
         let mac_addr = device.mac().await?;
-        Ok((mac_addr, device))
-    }).await?
+        anyhow::Result::<_>::Ok((mac_addr, device))
+    }).await;
+    (addr, result.map_err(anyhow::Error::from).and_then(|result| result))
 }
@@ -1,7 +1,7 @@
 use bytes::{BytesMut, Buf, BufMut};
 use bitvec::view::BitView;
 use futures::FutureExt;
-use futures::{StreamExt, TryStreamExt, SinkExt, stream::FuturesUnordered};
+use futures::{StreamExt, TryStreamExt, SinkExt, stream::{FuturesOrdered, FuturesUnordered}};
 use macaddr::MacAddr6;
 use std::fmt::Debug;
 use std::{collections::HashMap, convert::TryFrom, net::SocketAddr, ops::BitXor, time::Duration};
@@ -40,6 +40,9 @@ pub enum Error {
 
     #[error("Remote error: {status}")]
     RemoteError{ frame_id: u8, status: u8},
+
+    #[error("Parameter {0} was not applied")]
+    ParameterNotApplied(String),
 }
 
 #[repr(u8)]
@@ -120,6 +123,32 @@ impl TryFrom<usize> for Pin {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Converts AT commands and their values in hexadecimal, as they are written in the configuration file and
+/// displayed by XCTU, into a profile that can be applied to a device
+pub fn profile<'p, P>(parameters: P) -> Result<Vec<([u8; 2], BytesMut)>>
+    where P: IntoIterator<Item = &'p (String, String)> {
+    parameters.into_iter()
+        .map(|(name, value)| {
+            let parameter = match name.as_bytes() {
+                &[first, second] => [first.to_ascii_uppercase(), second.to_ascii_uppercase()],
+                _ => return Err(Error::DecodeError),
+            };
+            /* pad the value with a leading zero so that it consists of whole bytes */
+            let value = match value.len() % 2 {
+                0 => value.clone(),
+                _ => format!("0{}", value),
+            };
+            let value = (0..value.len())
+                .step_by(2)
+                .map(|index| value.get(index..index + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or(Error::DecodeError))
+                .collect::<Result<Vec<_>>>()?;
+            Ok((parameter, BytesMut::from(&value[..])))
+        })
+        .collect()
+}
+
 struct Codec;
 
 pub struct Device {
@@ -234,7 +263,7 @@ impl Device {
         type RemoteRequest = (Instant, Option<oneshot::Sender<Result<BytesMut>>>, Command, usize);
         /* bind to a random port on any interface */
         let (request_tx, mut request_rx) = mpsc::channel(8);
-        tokio::spawn(async move {
+        crate::instrument::spawn(format!("network/xbee@{}", addr), async move {
             let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await {
                 Ok(socket) => socket,
                 Err(_) => return,
@@ -358,6 +387,48 @@ impl Device {
             .map(|addr| MacAddr6::from(addr))
     }
 
+    /// Reads the raw value of an AT parameter, e.g., the firmware version (VR)
+    pub async fn parameter(&self, parameter: [u8; 2]) -> Result<BytesMut> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let request = Request::GetParameter(parameter, response_tx);
+        self.request_tx.send(request).await.map_err(|_| Error::RequestFailed)?;
+        response_rx.await.map_err(|_| Error::NoResponse)?
+    }
+
+    /// Reads the raw values of several AT parameters, the values are returned in the order of the parameters
+    pub async fn parameters(&self, parameters: &[[u8; 2]]) -> Result<Vec<BytesMut>> {
+        parameters.iter()
+            .map(|&parameter| self.parameter(parameter))
+            .collect::<FuturesOrdered<_>>()
+            .try_collect().await
+    }
+
+    /// Writes a profile of AT parameters, applies the changes, and reads the parameters back to check that
+    /// they were applied. The parameters are written in the order of the profile
+    pub async fn apply_profile(&self, profile: &[([u8; 2], BytesMut)]) -> Result<()> {
+        for (parameter, value) in profile {
+            self.request_tx.send(Request::SetParameter(*parameter, value.clone(), true)).await
+                .map_err(|_| Error::RequestFailed)?;
+        }
+        self.request_tx.send(Request::ApplyChanges).await
+            .map_err(|_| Error::RequestFailed)?;
+        let parameters = profile.iter()
+            .map(|(parameter, _)| *parameter)
+            .collect::<Vec<_>>();
+        let values = self.parameters(&parameters).await?;
+        /* the Xbee responds with the shortest encoding of a value, ignore leading zeros when comparing */
+        let trim = |value: &[u8]| value.iter()
+            .skip_while(|&&byte| byte == 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        for ((parameter, expected), value) in profile.iter().zip(values) {
+            if trim(expected) != trim(&value) {
+                return Err(Error::ParameterNotApplied(String::from_utf8_lossy(parameter).into_owned()));
+            }
+        }
+        Ok(())
+    }
+
     pub async fn link_margin(&self) -> Result<i32> {
         let (response_tx, response_rx) = oneshot::channel();
         let request = Request::GetParameter([b'L',b'M'], response_tx);
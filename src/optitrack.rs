@@ -1,18 +1,34 @@
 use anyhow::Context;
 use bytes::{Buf, BytesMut};
 use natnet_decode::{
+    DataSet,
     NatNet,
     NatNetResponse,
     ParseError,
 };
 use semver::Version;
-use std::{io::Cursor, net::Ipv4Addr};
+use std::{collections::HashMap, io::{BufWriter, Cursor, Write}, net::{Ipv4Addr, SocketAddrV4}, path::PathBuf, time::{Duration, SystemTime}};
 use futures::StreamExt;
-use tokio::{net::UdpSocket, sync::{broadcast, mpsc, oneshot}};
+use tokio::{net::UdpSocket, sync::{broadcast, mpsc, oneshot}, time::Instant};
 use tokio_util::{udp::UdpFramed, codec::Decoder};
-use shared::tracking_system::Update;
+use shared::tracking_system::{RigidBody, Timestamps, Update};
 
-#[derive(Debug)]
+use crate::{encryption::{self, Cipher}, instrument};
+
+/* NatNet message identifier for requesting the model definitions (NAT_REQUEST_MODELDEF) */
+const NAT_REQUEST_MODELDEF: u16 = 4;
+/* default port of the NatNet command channel */
+pub const DEFAULT_COMMAND_PORT: u16 = 1510;
+/* the weight of a new velocity estimate in the exponential filter, smaller weights smooth more */
+const VELOCITY_SMOOTHING: f32 = 0.25;
+/* the estimates are reset if a rigid body has not been tracked for this many seconds */
+const VELOCITY_TIMEOUT: f32 = 0.5;
+/* the largest datagram that can be received from the data stream */
+const MAX_DATAGRAM_SIZE: usize = 65536;
+/* the link type of the records in a recording, which is reserved for private use by the pcap format */
+const LINKTYPE_USER0: u32 = 147;
+
+#[derive(Debug, Clone)]
 struct NatNetCodec {
     version: Version,
 }
@@ -23,13 +39,17 @@ impl NatNetCodec {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Configuration {
     pub version: semver::Version,
     pub bind_addr: Ipv4Addr,
     pub bind_port: u16,
     pub multicast_addr: Ipv4Addr,
     pub iface_addr: Ipv4Addr,
+    /* the address of the command channel of Motive, if provided, model definitions are requested */
+    pub command_addr: Option<SocketAddrV4>,
+    /* the duration for which the raw packets are recorded once an experiment is started, if provided */
+    pub record: Option<Duration>,
 }
 
 impl Decoder for NatNetCodec {
@@ -52,30 +72,191 @@ impl Decoder for NatNetCodec {
     }
 }
 
+/// Estimates the linear and angular velocities of the rigid bodies from their consecutive poses. Since the
+/// poses from the tracking system are noisy, the estimates are smoothed with an exponential filter
+#[derive(Default)]
+struct VelocityFilter {
+    /* the time in milliseconds, the last pose, and the filtered velocities of each rigid body */
+    states: HashMap<i32, (f64, Update)>,
+}
+
+impl VelocityFilter {
+    fn apply(&mut self, time: f64, update: &mut Update) {
+        if let Some((last_time, last)) = self.states.get(&update.id) {
+            let dt = ((time - last_time) / 1e3) as f32;
+            if dt > 0.0 && dt < VELOCITY_TIMEOUT {
+                let linear = [
+                    (update.position[0] - last.position[0]) / dt,
+                    (update.position[1] - last.position[1]) / dt,
+                    (update.position[2] - last.position[2]) / dt,
+                ];
+                /* the rotation since the last pose is the product of the orientation and the conjugate of
+                   the last orientation, for small rotations its vector part is half the rotation vector */
+                let [w0, x0, y0, z0] = last.orientation;
+                let [w1, x1, y1, z1] = update.orientation;
+                let mut rotation = [
+                    w1 * w0 + x1 * x0 + y1 * y0 + z1 * z0,
+                    -w1 * x0 + x1 * w0 - y1 * z0 + z1 * y0,
+                    -w1 * y0 + x1 * z0 + y1 * w0 - z1 * x0,
+                    -w1 * z0 - x1 * y0 + y1 * x0 + z1 * w0,
+                ];
+                /* q and -q represent the same orientation, take the shorter rotation */
+                if rotation[0] < 0.0 {
+                    rotation.iter_mut().for_each(|component| *component = -*component);
+                }
+                let angular = [
+                    2.0 * rotation[1] / dt,
+                    2.0 * rotation[2] / dt,
+                    2.0 * rotation[3] / dt,
+                ];
+                for axis in 0..3 {
+                    update.linear_velocity[axis] = VELOCITY_SMOOTHING * linear[axis] +
+                        (1.0 - VELOCITY_SMOOTHING) * last.linear_velocity[axis];
+                    update.angular_velocity[axis] = VELOCITY_SMOOTHING * angular[axis] +
+                        (1.0 - VELOCITY_SMOOTHING) * last.angular_velocity[axis];
+                }
+            }
+        }
+        self.states.insert(update.id, (time, update.clone()));
+    }
+}
+
 pub enum Action {
-    Subscribe(oneshot::Sender<broadcast::Receiver<Vec<Update>>>),
+    /* each frame is broadcast with its frame number and the times at which it passed through this task */
+    Subscribe(oneshot::Sender<broadcast::Receiver<(i32, Vec<Update>, Timestamps)>>),
+    /* note that upon subscribing, the most recent model definitions are sent */
+    SubscribeModels(oneshot::Sender<broadcast::Receiver<Vec<RigidBody>>>),
+    /* record the raw packets to the given file if recording is configured, this replaces an ongoing recording.
+       The recording is encrypted at rest with the key of the journal if one has been configured */
+    Record(PathBuf, Option<Cipher>),
 }
 
-pub async fn new(config: Configuration, mut requests: mpsc::Receiver<Action>) -> anyhow::Result<()> {
+/// A recording of the raw packets from the NatNet data stream, which is written in the pcap format so that
+/// existing tools can read it. Each record holds the payload of a single UDP datagram instead of an Ethernet
+/// frame, hence the link type is LINKTYPE_USER0
+struct Recording {
+    path: PathBuf,
+    writer: BufWriter<Box<dyn Write + Send>>,
+    deadline: Instant,
+    packets: u64,
+}
+
+impl Recording {
+    fn create(path: PathBuf, duration: Duration, cipher: Option<&Cipher>) -> anyhow::Result<Recording> {
+        let mut writer = BufWriter::new(encryption::open(&path, cipher)?);
+        /* the magic number, version 2.4, the time zone, the accuracy of the timestamps, the maximum length
+           of a record, and the link type */
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&4u16.to_le_bytes());
+        header.extend_from_slice(&0i32.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&(MAX_DATAGRAM_SIZE as u32).to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_USER0.to_le_bytes());
+        writer.write_all(&header)
+            .context(format!("Could not write to {}", path.display()))?;
+        Ok(Recording { path, writer, deadline: Instant::now() + duration, packets: 0 })
+    }
+
+    fn write(&mut self, datagram: &[u8]) -> std::io::Result<()> {
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let mut header = Vec::with_capacity(16);
+        header.extend_from_slice(&(time.as_secs() as u32).to_le_bytes());
+        header.extend_from_slice(&time.subsec_micros().to_le_bytes());
+        header.extend_from_slice(&(datagram.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(datagram.len() as u32).to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(datagram)?;
+        self.packets += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) {
+        match self.writer.flush() {
+            Ok(_) => log::info!("Recorded {} packets from the tracking system to {}", self.packets, self.path.display()),
+            Err(error) => log::error!("Could not write to {}: {}", self.path.display(), error),
+        }
+    }
+}
+
+/// Returns the current time in milliseconds since the UNIX epoch, this is the clock against which the
+/// latency of the tracking pipeline is measured
+pub fn timestamp() -> f64 {
+    chrono::Utc::now().timestamp_nanos() as f64 / 1e6
+}
+
+pub async fn new(config: Configuration, requests: &mut mpsc::Receiver<Action>) -> anyhow::Result<()> {
     let socket = UdpSocket::bind((config.bind_addr, config.bind_port)).await
         .context("Could not bind to port")?;
     socket.join_multicast_v4(config.multicast_addr, config.iface_addr)
         .context("Could not join multicast group")?;
     let (updates_tx, _) = broadcast::channel(32);
-    let mut stream = UdpFramed::new(socket, NatNetCodec::new(config.version));
+    let (models_updates_tx, _) = broadcast::channel(8);
+    let mut models: Vec<RigidBody> = Vec::new();
+    /* the sender is dropped immediately if the command channel is not configured */
+    let (models_tx, mut models_rx) = mpsc::channel(8);
+    if let Some(command_addr) = config.command_addr {
+        let codec = NatNetCodec::new(config.version.clone());
+        instrument::spawn(format!("optitrack/commands@{}", command_addr), async move {
+            if let Err(error) = request_models(command_addr, codec, models_tx).await {
+                log::warn!("Could not request model definitions from {}: {}", command_addr, error);
+            }
+        });
+    }
+    /* the datagrams are decoded here rather than by a framed stream so that the raw packets can be recorded */
+    let mut codec = NatNetCodec::new(config.version);
+    let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+    let mut velocity_filter = VelocityFilter::default();
+    let mut recording: Option<Recording> = None;
     loop {
+        let deadline = recording.as_ref().map(|recording| recording.deadline);
+        let finished = async move {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => futures::future::pending().await,
+            }
+        };
         tokio::select! {
             request = requests.recv() => match request {
                 Some(action) => match action {
                     Action::Subscribe(callback) => {
                         let _ = callback.send(updates_tx.subscribe());
-                    }
+                    },
+                    Action::SubscribeModels(callback) => {
+                        let _ = callback.send(models_updates_tx.subscribe());
+                        if models.len() > 0 {
+                            let _ = models_updates_tx.send(models.clone());
+                        }
+                    },
+                    Action::Record(path, cipher) => if let Some(duration) = config.record {
+                        if let Some(recording) = recording.take() {
+                            recording.finish();
+                        }
+                        match Recording::create(path, duration, cipher.as_ref()) {
+                            Ok(started) => recording = Some(started),
+                            Err(error) => log::error!("Could not record the tracking system: {:#}", error),
+                        }
+                    },
                 },
                 None => break,
             },
-            Some(data) = stream.next() => match data {
-                Ok(decoded) => if let (NatNetResponse::FrameOfData(frame), _) = decoded {
-                    let updates = frame.rigid_bodies.iter()
+            _ = finished => if let Some(recording) = recording.take() {
+                recording.finish();
+            },
+            Some(update) = models_rx.recv() => {
+                if update != models {
+                    log::info!("Received {} rigid body definitions from Motive", update.len());
+                    models = update;
+                    let _ = models_updates_tx.send(models.clone());
+                }
+            },
+            result = socket.recv(&mut buffer) => match result {
+                Ok(length) => if let Some(NatNetResponse::FrameOfData(frame)) = decode(&mut codec, &buffer[..length], &mut recording) {
+                    let mut timestamps = Timestamps { received: timestamp(), ..Default::default() };
+                    let mut updates = frame.rigid_bodies.iter()
                         .map(|body| Update {
                             id: body.id,
                             position: [
@@ -89,15 +270,83 @@ pub async fn new(config: Configuration, mut requests: mpsc::Receiver<Action>) ->
                                 body.orientation.j,
                                 body.orientation.k
                             ],
+                            linear_velocity: [0.0; 3],
+                            angular_velocity: [0.0; 3],
                         })
                         .collect::<Vec<_>>();
-                    let _ = updates_tx.send(updates);
+                    for update in updates.iter_mut() {
+                        velocity_filter.apply(timestamps.received, update);
+                    }
+                    timestamps.broadcast = timestamp();
+                    let _ = updates_tx.send((frame.frame_number, updates, timestamps));
                 }
                 Err(error) => {
-                    log::warn!("Could not decode optitrack data: {}", error);
+                    log::warn!("Could not receive optitrack data: {}", error);
                 }
             }
         }
     }
+    if let Some(recording) = recording.take() {
+        recording.finish();
+    }
+    Ok(())
+}
+
+/// Records a datagram from the data stream, if a recording is in progress, and decodes it
+fn decode(codec: &mut NatNetCodec, datagram: &[u8], recording: &mut Option<Recording>) -> Option<NatNetResponse> {
+    if let Some(started) = recording.as_mut() {
+        if let Err(error) = started.write(datagram) {
+            log::error!("Could not write to {}: {}", started.path.display(), error);
+            *recording = None;
+        }
+    }
+    match codec.decode(&mut BytesMut::from(datagram)) {
+        Ok(response) => response,
+        Err(error) => {
+            log::warn!("Could not decode optitrack data: {}", error);
+            None
+        }
+    }
+}
+
+/// This function periodically requests the model definitions over the NatNet command channel. Motive
+/// replies directly to the socket that sent the request, the rigid body definitions in the reply are
+/// forwarded to the main task of the optitrack module
+async fn request_models(command_addr: SocketAddrV4,
+                        codec: NatNetCodec,
+                        models_tx: mpsc::Sender<Vec<RigidBody>>) -> anyhow::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await
+        .context("Could not bind to port")?;
+    socket.connect(command_addr).await
+        .context("Could not connect to command channel")?;
+    let mut stream = UdpFramed::new(socket, codec);
+    /* model definitions can change while Motive is running, request them every five seconds */
+    let mut interval = tokio::time::interval(Duration::from_secs(5));
+    let mut request = NAT_REQUEST_MODELDEF.to_le_bytes().to_vec();
+    request.extend_from_slice(&0u16.to_le_bytes());
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                stream.get_ref().send(&request).await
+                    .context("Could not send request")?;
+            },
+            Some(data) = stream.next() => match data {
+                Ok((NatNetResponse::ModelDef(data_sets), _)) => {
+                    let models = data_sets.into_iter()
+                        .filter_map(|data_set| match data_set {
+                            DataSet::RigidBody(body) => Some(RigidBody { id: body.id, name: body.name }),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    if let Err(_) = models_tx.send(models).await {
+                        break;
+                    }
+                },
+                Ok(_) => {},
+                Err(error) => log::warn!("Could not decode optitrack command response: {}", error),
+            },
+            _ = models_tx.closed() => break,
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,201 @@
+use std::{collections::HashMap, net::Ipv4Addr, time::{Duration, Instant}};
+use macaddr::MacAddr6;
+use shared::{alert::{Notification, Severity}, pairing::{Fernbedienung, Pairing, Xbee}};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::alerts;
+use crate::network::{fernbedienung, xbee};
+use crate::robot::{drone, StatusLeds};
+
+/* the time for which an unassigned device is held before it is returned to the network module, which
+   probes its address again so that devices that have left the network are eventually removed */
+const HOLD_TIME: Duration = Duration::from_secs(30);
+/* the time after which a device that was returned to the network module and not found again is removed */
+const EXPIRY_TIME: Duration = Duration::from_secs(60);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const DEVICE_TIMEOUT: Duration = Duration::from_secs(2);
+/* the pins of the Xbee of a drone to which its identifier is wired */
+const IDENTIFIER_PINS: &[(xbee::Pin, xbee::PinMode)] = &[
+    (xbee::Pin::DIO0, xbee::PinMode::Input),
+    (xbee::Pin::DIO1, xbee::PinMode::Input),
+    (xbee::Pin::DIO2, xbee::PinMode::Input),
+    (xbee::Pin::DIO3, xbee::PinMode::Input),
+];
+
+pub enum Action {
+    /* the devices that the arena could not associate with any robot, or that claim the MAC address of a robot
+       that is associated with another device, in which case the identifier of that robot is given */
+    AddXbee(xbee::Device, MacAddr6, Option<String>),
+    AddFernbedienung(fernbedienung::Device, MacAddr6, Option<String>),
+    /* start or stop flashing the status LEDs of an unassigned device running the fernbedienung service */
+    Identify(oneshot::Sender<anyhow::Result<()>>, MacAddr6, bool),
+    /* return a held device to the network module right away, e.g., after it was adopted by a robot so that
+       it is probed and associated again */
+    Release(MacAddr6),
+    /* note that upon subscribing, the currently visible unassigned devices are sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Pairing>>),
+}
+
+/// An unassigned device and when it was last found by the network module. The device itself is None once
+/// it has been returned to the network module to be probed again
+struct Held<D> {
+    device: Option<D>,
+    addr: Ipv4Addr,
+    seen: Instant,
+    /* the robot whose MAC address the device claims */
+    conflict: Option<String>,
+}
+
+impl<D> Held<D> {
+    fn new(device: D, addr: Ipv4Addr, conflict: Option<String>) -> Self {
+        Held { device: Some(device), addr, seen: Instant::now(), conflict }
+    }
+}
+
+/// Alerts the operators about a device that claims the MAC address of a robot, unless the device was already
+/// held for the same reason, since a quarantined device is found again every time it is probed
+async fn report<D>(
+    alerts_tx: &mpsc::Sender<alerts::Action>,
+    previous: Option<&Held<D>>,
+    macaddr: MacAddr6,
+    addr: Ipv4Addr,
+    conflict: &Option<String>,
+) {
+    if let Some(robot) = conflict {
+        if previous.map_or(true, |held| held.conflict != *conflict) {
+            let notification = Notification {
+                robot: robot.clone(),
+                severity: Severity::Warning,
+                message: format!("The device at {} claims the MAC address {} of {}, which is associated with another device",
+                    addr, macaddr, robot),
+            };
+            let _ = alerts_tx.send(alerts::Action::Notify(notification)).await;
+        }
+    }
+}
+
+/// Holds the Xbees and the fernbedienung devices that are not assigned to any robot so that the operator can
+/// pair them into drones from the user interface. The identifier of each Xbee is read from its pins and the
+/// status LEDs of the fernbedienung devices can be flashed to find out which devices belong together. Devices
+/// that claim the MAC address of a robot that is associated with another device are also held here, so that
+/// the device that the robot is associated with is not replaced
+pub async fn new(mut requests: mpsc::Receiver<Action>, alerts_tx: mpsc::Sender<alerts::Action>) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut xbees: HashMap<MacAddr6, (Held<xbee::Device>, Option<u8>)> = HashMap::new();
+    let mut fernbedienungs: HashMap<MacAddr6, (Held<fernbedienung::Device>, bool)> = HashMap::new();
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::AddXbee(device, macaddr, conflict)) => {
+                    let identifier = match identifier(&device).await {
+                        Ok(identifier) => Some(identifier),
+                        Err(error) => {
+                            log::warn!("Could not read the identifier of Xbee {}: {:#}", macaddr, error);
+                            None
+                        }
+                    };
+                    let addr = device.addr;
+                    report(&alerts_tx, xbees.get(&macaddr).map(|(held, _)| held), macaddr, addr, &conflict).await;
+                    xbees.insert(macaddr, (Held::new(device, addr, conflict), identifier));
+                    let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                },
+                Some(Action::AddFernbedienung(device, macaddr, conflict)) => {
+                    let addr = device.addr;
+                    report(&alerts_tx, fernbedienungs.get(&macaddr).map(|(held, _)| held), macaddr, addr, &conflict).await;
+                    /* the status LEDs keep flashing while the device is probed again */
+                    let identifying = fernbedienungs.get(&macaddr).map_or(false, |(_, identifying)| *identifying);
+                    fernbedienungs.insert(macaddr, (Held::new(device, addr, conflict), identifying));
+                    let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                },
+                Some(Action::Identify(callback, macaddr, enable)) => {
+                    let result = match fernbedienungs.get_mut(&macaddr) {
+                        Some((Held { device: Some(device), .. }, identifying)) => {
+                            let status = if enable { StatusLeds::Identify } else { StatusLeds::Off };
+                            let result = tokio::time::timeout(DEVICE_TIMEOUT, status.apply(device, drone::STATUS_LEDS_PY)).await
+                                .map_err(|_| anyhow::anyhow!("Timeout while setting the status LEDs of {}", macaddr))
+                                .and_then(|result| result);
+                            if result.is_ok() {
+                                *identifying = enable;
+                            }
+                            result
+                        },
+                        Some((Held { device: None, .. }, _)) =>
+                            Err(anyhow::anyhow!("Device {} is being probed again, please try again shortly", macaddr)),
+                        None => Err(anyhow::anyhow!("Device {} is not visible on the robot network", macaddr)),
+                    };
+                    let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                    let _ = callback.send(result);
+                },
+                Some(Action::Release(macaddr)) => {
+                    /* dropping a device returns its address to the network module */
+                    if xbees.remove(&macaddr).is_some() || fernbedienungs.remove(&macaddr).is_some() {
+                        let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                    }
+                },
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send(updates_tx.subscribe());
+                    let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                },
+                None => break,
+            },
+            _ = refresh.tick() => {
+                let count = xbees.len() + fernbedienungs.len();
+                /* dropping a device returns its address to the network module */
+                for (held, _) in xbees.values_mut() {
+                    if held.seen.elapsed() > HOLD_TIME {
+                        held.device = None;
+                    }
+                }
+                for (held, _) in fernbedienungs.values_mut() {
+                    if held.seen.elapsed() > HOLD_TIME {
+                        held.device = None;
+                    }
+                }
+                xbees.retain(|_, (held, _)| held.seen.elapsed() < EXPIRY_TIME);
+                fernbedienungs.retain(|_, (held, _)| held.seen.elapsed() < EXPIRY_TIME);
+                if xbees.len() + fernbedienungs.len() != count {
+                    let _ = updates_tx.send(pairing(&xbees, &fernbedienungs));
+                }
+            }
+        }
+    }
+}
+
+/// Reads the identifier that is wired to the pins DIO0 to DIO3 of the Xbee of a drone
+async fn identifier(device: &xbee::Device) -> anyhow::Result<u8> {
+    tokio::time::timeout(DEVICE_TIMEOUT, async {
+        device.set_pin_modes(IDENTIFIER_PINS.iter()).await?;
+        let pin_states = device.pin_states().await?;
+        let identifier = IDENTIFIER_PINS.iter()
+            .enumerate()
+            .filter(|(_, (pin, _))| pin_states.get(pin).cloned().unwrap_or_default())
+            .fold(0, |identifier, (bit, _)| identifier | (1 << bit));
+        anyhow::Result::<_>::Ok(identifier)
+    }).await?
+}
+
+fn pairing(
+    xbees: &HashMap<MacAddr6, (Held<xbee::Device>, Option<u8>)>,
+    fernbedienungs: &HashMap<MacAddr6, (Held<fernbedienung::Device>, bool)>,
+) -> Pairing {
+    let mut xbees = xbees.iter()
+        .map(|(macaddr, (held, identifier))| Xbee {
+            macaddr: *macaddr,
+            addr: held.addr,
+            identifier: *identifier,
+            conflict: held.conflict.clone(),
+        })
+        .collect::<Vec<_>>();
+    xbees.sort_by_key(|xbee| xbee.addr);
+    let mut fernbedienungs = fernbedienungs.iter()
+        .map(|(macaddr, (held, identifying))| Fernbedienung {
+            macaddr: *macaddr,
+            addr: held.addr,
+            identifying: *identifying,
+            conflict: held.conflict.clone(),
+        })
+        .collect::<Vec<_>>();
+    fernbedienungs.sort_by_key(|fernbedienung| fernbedienung.addr);
+    Pairing { xbees, fernbedienungs }
+}
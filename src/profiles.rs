@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+use shared::experiment::profile::{self, Profile};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+pub enum Action {
+    Save(oneshot::Sender<anyhow::Result<()>>, Profile),
+    Delete(oneshot::Sender<anyhow::Result<()>>, String),
+    /* note that upon subscribing, the current profiles are sent */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Vec<Profile>>>),
+}
+
+/// The file in which the profiles are stored next to the configuration
+pub fn path(configuration: &Path) -> PathBuf {
+    configuration.with_extension("profiles.json")
+}
+
+fn load(path: &Path) -> anyhow::Result<Vec<Profile>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read(path)
+        .context(format!("Could not read profiles {:?}", path))?;
+    serde_json::from_slice(&contents)
+        .context(format!("Could not parse profiles {:?}", path))
+}
+
+async fn save(path: &Path, profiles: &[Profile]) -> anyhow::Result<()> {
+    let contents = serde_json::to_vec_pretty(profiles)
+        .context("Could not serialize profiles")?;
+    tokio::fs::write(path, contents).await
+        .context(format!("Could not write profiles {:?}", path))
+}
+
+/// Checks that every profile can be resolved, i.e., that all parents exist and that no profile inherits
+/// from itself
+fn validate(profiles: &[Profile]) -> anyhow::Result<()> {
+    for candidate in profiles {
+        profile::resolve(profiles, &candidate.name)
+            .map_err(|error| anyhow::anyhow!(error))?;
+    }
+    Ok(())
+}
+
+/// Stores the experiment profiles, which are shared by all clients, in a file next to the configuration.
+/// A profile is only saved or deleted if every profile can still be resolved afterwards
+pub async fn new(path: PathBuf, mut requests: mpsc::Receiver<Action>) {
+    let mut profiles = match load(&path) {
+        Ok(profiles) => profiles,
+        Err(error) => {
+            /* do not overwrite profiles that could not be read */
+            log::error!("{:#}", error);
+            return;
+        }
+    };
+    let (updates_tx, _) = broadcast::channel(8);
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Save(callback, profile) => {
+                let mut updated = profiles.clone();
+                let result = match profile.name.trim().is_empty() {
+                    true => Err(anyhow::anyhow!("The name of the profile must be provided")),
+                    false => {
+                        updated.retain(|candidate| candidate.name != profile.name);
+                        updated.push(profile);
+                        updated.sort_by(|a, b| a.name.cmp(&b.name));
+                        validate(&updated)
+                    }
+                };
+                let result = match result {
+                    Ok(_) => save(&path, &updated).await,
+                    Err(error) => Err(error),
+                };
+                if result.is_ok() {
+                    profiles = updated;
+                    let _ = updates_tx.send(profiles.clone());
+                }
+                let _ = callback.send(result.context("Could not save profile"));
+            },
+            Action::Delete(callback, name) => {
+                let children = profiles.iter()
+                    .filter(|profile| profile.parent.as_ref() == Some(&name))
+                    .map(|profile| profile.name.clone())
+                    .collect::<Vec<_>>();
+                let result = match children.is_empty() {
+                    true => {
+                        let mut updated = profiles.clone();
+                        updated.retain(|profile| profile.name != name);
+                        match save(&path, &updated).await {
+                            Ok(_) => {
+                                profiles = updated;
+                                let _ = updates_tx.send(profiles.clone());
+                                Ok(())
+                            },
+                            Err(error) => Err(error),
+                        }
+                    },
+                    false => Err(anyhow::anyhow!("Profile \"{}\" is the parent of {}", name, children.join(", "))),
+                };
+                let _ = callback.send(result.context("Could not delete profile"));
+            },
+            Action::Subscribe(callback) => {
+                let _ = callback.send(updates_tx.subscribe());
+                let _ = updates_tx.send(profiles.clone());
+            },
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, net::{IpAddr, SocketAddr}, path::Path, time::Duration};
+use anyhow::Context;
+use shared::experiment::{JournalStatistics, LogEntry, LogSource, TimelineCategory, TimelineEvent};
+use shared::{builderbot, drone, pipuck, journal::{self as recorded, Entry}, tracking_system::{self, Timestamps}};
+use tokio::{sync::{broadcast, mpsc}, time::Instant};
+
+use crate::{arena, encryption::{self, Cipher}, journal::{self, Tail}, optitrack, robot};
+
+/// An entry of a journal that is emitted again while the journal is replayed
+enum Event {
+    TrackingSystem {
+        frame: i32,
+        updates: Vec<tracking_system::Update>,
+    },
+    ARGoS {
+        robot: String,
+        error: bool,
+        data: Vec<u8>,
+    },
+    Message(SocketAddr),
+    Timeline(TimelineCategory, String, Vec<String>),
+    BatteryCells(String, Vec<u16>),
+}
+
+impl Event {
+    /// The category under which this event is counted in the journal statistics, see journal::Event
+    fn category(&self) -> &'static str {
+        match self {
+            Event::TrackingSystem { .. } => "Tracking system",
+            Event::ARGoS { error: false, .. } => "ARGoS standard output",
+            Event::ARGoS { error: true, .. } => "ARGoS standard error",
+            Event::Message(_) => "Messages",
+            Event::Timeline(..) => "Timeline",
+            Event::BatteryCells(..) => "Battery cells",
+        }
+    }
+}
+
+/// A journal that has been loaded for replaying, the events are ordered by their timestamps in milliseconds
+/// since the journal was started
+pub struct Journal {
+    /* the local time at which the journal was started, which is only recorded since schema version 2 */
+    pub start: Option<String>,
+    pub builderbots: Vec<builderbot::Descriptor>,
+    pub drones: Vec<drone::Descriptor>,
+    pub pipucks: Vec<pipuck::Descriptor>,
+    size: u64,
+    encrypted: bool,
+    events: Vec<(i64, Event)>,
+}
+
+impl Journal {
+    /// Reads the entries of a journal that was recorded as a pickle or as JSON Lines with the decoder that is
+    /// shared with the replay in the user interface. Entries that describe the experiment as a whole, e.g., the fingerprint, are not replayed. An encrypted
+    /// journal is decrypted with the key from the configuration
+    pub fn load(path: &Path, cipher: Option<&Cipher>) -> anyhow::Result<Journal> {
+        let contents = std::fs::read(path)
+            .context(format!("Could not read {}", path.display()))?;
+        let size = contents.len() as u64;
+        let encrypted = encryption::is_encrypted(&contents);
+        let contents = encryption::reveal(path, contents, cipher)?;
+        let decoded = recorded::decode(&contents)
+            .context(format!("Could not replay {}", path.display()))?;
+        for timestamp in decoded.skipped {
+            log::warn!("Skipping entry at {} ms that could not be interpreted", timestamp);
+        }
+        let mut header = None;
+        let mut events = Vec::new();
+        for Entry { timestamp, event } in decoded.entries {
+            let event = match event {
+                recorded::Event::Header(contents) => {
+                    if let Some(version) = contents.schema_version {
+                        if version > journal::SCHEMA_VERSION as i64 {
+                            anyhow::bail!("{} uses schema version {} but only versions up to {} are supported",
+                                path.display(), version, journal::SCHEMA_VERSION);
+                        }
+                    }
+                    header = Some(contents);
+                    continue;
+                },
+                recorded::Event::TrackingSystem { frame, updates } => Event::TrackingSystem { frame, updates },
+                recorded::Event::ARGoS { robot, error, data } => Event::ARGoS { robot, error, data },
+                recorded::Event::Message(addr) => match addr.parse() {
+                    Ok(addr) => Event::Message(addr),
+                    Err(_) => {
+                        log::warn!("Skipping entry at {} ms that could not be interpreted", timestamp);
+                        continue;
+                    },
+                },
+                recorded::Event::Timeline(category, description, tags) => Event::Timeline(category, description, tags),
+                recorded::Event::BatteryCells(robot, cells) => Event::BatteryCells(robot, cells),
+                recorded::Event::Other => continue,
+            };
+            events.push((timestamp, event));
+        }
+        let header = header
+            .ok_or(anyhow::anyhow!("{} does not describe the robots of the experiment", path.display()))?;
+        /* the entries are written in the order they were recorded, which is not necessarily the order of their timestamps */
+        events.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(Journal {
+            start: header.start,
+            builderbots: header.builderbots,
+            drones: header.drones,
+            pipucks: header.pipucks,
+            size,
+            encrypted,
+            events,
+        })
+    }
+}
+
+/// Replays a journal in place of the journal and the optitrack tasks. The frames from the tracking system,
+/// the battery cells of the drones, the output of ARGoS, and the timeline are emitted at the time at which
+/// they were recorded, divided by the speed, and are also published in the live log. Experiments can not be started while a journal is replayed
+pub async fn new(
+    journal: Journal,
+    speed: f64,
+    arena_tx: mpsc::Sender<arena::Action>,
+    mut journal_rx: mpsc::Receiver<journal::Action>,
+    mut optitrack_rx: mpsc::Receiver<optitrack::Action>,
+) -> anyhow::Result<()> {
+    let (frames_tx, _) = broadcast::channel(32);
+    let (models_tx, _) = broadcast::channel(8);
+    let (statistics_tx, _) = broadcast::channel(8);
+    let (log_tx, _) = broadcast::channel(256);
+    let mut last_snapshot: Option<i64> = None;
+    let mut statistics_interval = tokio::time::interval(Duration::from_secs(1));
+    let mut statistics = JournalStatistics { file_size: journal.size, encrypted: journal.encrypted, ..Default::default() };
+    let mut tails: HashMap<String, Tail> = HashMap::new();
+    let mut message_counts: HashMap<IpAddr, u64> = HashMap::new();
+    let mut events = journal.events.into_iter().peekable();
+    log::info!("Replaying {} entries at {}x speed", events.len(), speed);
+    let start = Instant::now();
+    loop {
+        let deadline = events.peek()
+            .map(|(timestamp, _)| start + Duration::from_secs_f64(*timestamp as f64 / 1000.0 / speed));
+        let next = async move {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => futures::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = next => if let Some((timestamp, event)) = events.next() {
+                *statistics.events.entry(event.category().to_owned()).or_default() += 1;
+                match event {
+                    Event::TrackingSystem { frame, updates } => {
+                        statistics.tracking.frames += 1;
+                        if last_snapshot.map_or(true, |last_snapshot| timestamp - last_snapshot >= journal::SNAPSHOT_INTERVAL) {
+                            last_snapshot = Some(timestamp);
+                            let text = journal::snapshot(&updates);
+                            let _ = log_tx.send(LogEntry { timestamp, source: LogSource::Tracking, robot: None, text });
+                        }
+                        let now = optitrack::timestamp();
+                        let timestamps = Timestamps { received: now, broadcast: now, ..Default::default() };
+                        let _ = frames_tx.send((frame, updates, timestamps));
+                    },
+                    Event::ARGoS { robot, error, data } => {
+                        let source = match error {
+                            true => LogSource::StandardError,
+                            false => LogSource::StandardOutput,
+                        };
+                        for text in tails.entry(robot.clone()).or_default().push(error, &data) {
+                            let _ = log_tx.send(LogEntry { timestamp, source, robot: Some(robot.clone()), text });
+                        }
+                    },
+                    Event::Message(addr) => *message_counts.entry(addr.ip()).or_default() += 1,
+                    Event::Timeline(category, description, tags) => {
+                        let entry = LogEntry { timestamp, source: LogSource::Timeline, robot: None, text: description.clone() };
+                        let _ = log_tx.send(entry);
+                        statistics.timeline.push(TimelineEvent { timestamp, category, description, tags });
+                    },
+                    Event::BatteryCells(id, cells) => {
+                        let action = robot::drone::Action::Replay(drone::Update::BatteryCells(cells));
+                        let _ = arena_tx.send(arena::Action::ForwardDroneAction(id, action)).await;
+                    },
+                }
+                if events.peek().is_none() {
+                    log::info!("Replay finished");
+                    let _ = statistics_tx.send(statistics.clone());
+                }
+            },
+            _ = statistics_interval.tick() => if events.peek().is_some() {
+                let _ = statistics_tx.send(statistics.clone());
+            },
+            Some(request) = optitrack_rx.recv() => match request {
+                optitrack::Action::Subscribe(callback) => {
+                    let _ = callback.send(frames_tx.subscribe());
+                },
+                /* the definitions of the rigid bodies are not recorded in the journal */
+                optitrack::Action::SubscribeModels(callback) => {
+                    let _ = callback.send(models_tx.subscribe());
+                },
+                /* there are no raw packets to record */
+                optitrack::Action::Record(..) => {},
+            },
+            request = journal_rx.recv() => match request {
+                Some(journal::Action::Start(_, callback)) => {
+                    let _ = callback.send(Err(anyhow::anyhow!("Experiments can not be started while a journal is replayed")));
+                },
+                Some(journal::Action::Subscribe(callback)) => {
+                    let _ = callback.send(statistics_tx.subscribe());
+                    let _ = statistics_tx.send(statistics.clone());
+                },
+                Some(journal::Action::SubscribeLog(callback)) => {
+                    let _ = callback.send(log_tx.subscribe());
+                },
+                Some(journal::Action::MessageCount(addr, callback)) => {
+                    let _ = callback.send(message_counts.get(&addr).copied().unwrap_or(0));
+                },
+                Some(journal::Action::Excerpt(id, callback)) => {
+                    let _ = callback.send(tails.get(&id).map(Tail::excerpt));
+                },
+                Some(journal::Action::StoreArtifact(_, _, _, callback)) => {
+                    let _ = callback.send(Err(anyhow::anyhow!("Files can not be stored while a journal is replayed")));
+                },
+                Some(journal::Action::Stop) | Some(journal::Action::Record(_)) => {},
+                None => break,
+            },
+        }
+    }
+    Ok(())
+}
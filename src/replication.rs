@@ -0,0 +1,318 @@
+use std::{net::SocketAddr, time::{Duration, Instant}};
+use anyhow::Context;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use shared::{configuration::Replication, experiment::Status, replication::Role};
+use tokio::{net::{TcpListener, TcpStream}, sync::{broadcast, mpsc, oneshot}};
+use tokio_serde::{SymmetricallyFramed, formats::SymmetricalJson};
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+
+use crate::arena;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/* the interval at which the standby checks the heartbeats of the primary and tries to reconnect to it */
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+/* how long the primary stays passive after it has been started, which gives a standby that has taken over
+   the time to reconnect and tell the primary before the primary starts probing the robot network */
+const STARTUP_GRACE: Duration = Duration::from_secs(5);
+
+pub enum Action {
+    /* the current role of this supervisor is returned with the receiver, None if replication is not configured */
+    Subscribe(oneshot::Sender<(Option<Role>, broadcast::Receiver<Role>)>),
+    /* the operator confirms that the unresponsive primary has released the robots and that the standby takes over */
+    TakeOver(oneshot::Sender<anyhow::Result<()>>),
+}
+
+/// The messages between the primary and the standby supervisors
+#[derive(Debug, Deserialize, Serialize)]
+enum Message {
+    Heartbeat,
+    Status(Status),
+    /* sent by a standby that has taken over to the primary once it accepts connections again */
+    TakenOver { time: String },
+}
+
+type Messages<T> = SymmetricallyFramed<T, Message, SymmetricalJson<Message>>;
+
+/// Replicates the state of the arena from the primary supervisor to the standby supervisors. A standby
+/// supervisor does not probe the robot network and rejects experiments until it takes over. Since a primary
+/// that stopped sending heartbeats may still be connected to the robots, e.g., after a network partition or
+/// when only its replication has failed, the standby does not take over on its own but waits for an operator
+/// to confirm the takeover. A standby that has taken over keeps telling the primary, so that a restarted
+/// primary stays passive instead of probing and driving the same robots
+pub async fn new(
+    configuration: Option<Replication>,
+    arena_tx: mpsc::Sender<arena::Action>,
+    mut requests: mpsc::Receiver<Action>,
+) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let role = match configuration {
+        Some(Replication::Primary { socket }) =>
+            primary(socket, &arena_tx, &mut requests, &updates_tx).await,
+        Some(Replication::Standby { primary, timeout }) =>
+            standby(primary, Duration::from_secs(timeout), &arena_tx, &mut requests, &updates_tx).await,
+        None => Ok(None),
+    };
+    let role = match role {
+        Ok(role) => role,
+        Err(error) => {
+            log::error!("Replication aborted: {:#}", error);
+            None
+        }
+    };
+    /* keep answering the requests of the web interface */
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Subscribe(callback) => {
+                let _ = callback.send((role.clone(), updates_tx.subscribe()));
+            },
+            Action::TakeOver(callback) => {
+                let _ = callback.send(Err(anyhow::anyhow!("This supervisor is not a standby supervisor")));
+            },
+        }
+    }
+}
+
+async fn activate(arena_tx: &mpsc::Sender<arena::Action>) -> anyhow::Result<()> {
+    arena_tx.send(arena::Action::Activate).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))
+}
+
+/// Accepts the connections from the standby supervisors and mirrors the status of the experiment to them.
+/// The primary only becomes active once no standby has reported a takeover during the startup grace period,
+/// and it releases the robots and stays passive as soon as a standby reports that it has taken over
+async fn primary(
+    socket: SocketAddr,
+    arena_tx: &mpsc::Sender<arena::Action>,
+    requests: &mut mpsc::Receiver<Action>,
+    updates_tx: &broadcast::Sender<Role>,
+) -> anyhow::Result<Option<Role>> {
+    let listener = match TcpListener::bind(socket).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            /* without the listener no standby can connect, i.e., no standby can have taken over */
+            activate(arena_tx).await?;
+            return Err(error).context(format!("Could not bind to {}", socket));
+        }
+    };
+    let (disconnected_tx, mut disconnected_rx) = mpsc::channel(8);
+    let mut standbys = Vec::new();
+    let grace = tokio::time::sleep(STARTUP_GRACE);
+    tokio::pin!(grace);
+    let mut active = false;
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Subscribe(callback)) => {
+                    let role = Role::Primary { standbys: standbys.clone() };
+                    let _ = callback.send((Some(role), updates_tx.subscribe()));
+                },
+                Some(Action::TakeOver(callback)) => {
+                    let _ = callback.send(Err(anyhow::anyhow!("This supervisor is the primary supervisor")));
+                },
+                None => break,
+            },
+            _ = &mut grace, if !active => {
+                log::info!("No standby supervisor has taken over, starting to probe the robot network");
+                active = true;
+                activate(arena_tx).await?;
+            },
+            connection = listener.accept() => match connection {
+                Ok((stream, addr)) => {
+                    log::info!("Standby supervisor {} connected", addr);
+                    standbys.push(addr);
+                    let arena_tx = arena_tx.clone();
+                    let disconnected_tx = disconnected_tx.clone();
+                    crate::instrument::spawn(format!("replication/{}", addr), async move {
+                        let taken_over = match mirror(stream, arena_tx).await {
+                            Ok(taken_over) => taken_over,
+                            Err(error) => {
+                                log::warn!("Standby supervisor {} disconnected: {:#}", addr, error);
+                                None
+                            }
+                        };
+                        let _ = disconnected_tx.send((addr, taken_over)).await;
+                    });
+                },
+                Err(error) => log::warn!("Could not accept connection from standby supervisor: {}", error),
+            },
+            Some((addr, taken_over)) = disconnected_rx.recv() => {
+                standbys.retain(|standby| *standby != addr);
+                if let Some(time) = taken_over {
+                    log::error!("Standby supervisor {} took over at {}, releasing the robots", addr, time);
+                    arena_tx.send(arena::Action::StepDown).await
+                        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))?;
+                    let role = Role::Superseded { standby: addr, time };
+                    let _ = updates_tx.send(role.clone());
+                    return Ok(Some(role));
+                }
+            },
+        }
+        let _ = updates_tx.send(Role::Primary { standbys: standbys.clone() });
+    }
+    Ok(Some(Role::Primary { standbys }))
+}
+
+/// Sends the status of the experiment and heartbeats to a standby supervisor until the connection is lost
+/// or until the standby reports that it has taken over, in which case the time of the takeover is returned
+async fn mirror(stream: TcpStream, arena_tx: mpsc::Sender<arena::Action>) -> anyhow::Result<Option<String>> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))?;
+    let arena::Subscription { snapshot, status, .. } = callback_rx.await
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to arena updates"))??;
+    let mut status_updates = BroadcastStream::new(status);
+    let (reader, writer) = stream.into_split();
+    let mut incoming: Messages<_> = SymmetricallyFramed::new(
+        FramedRead::new(reader, LengthDelimitedCodec::new()),
+        SymmetricalJson::<Message>::default(),
+    );
+    let mut outgoing: Messages<_> = SymmetricallyFramed::new(
+        FramedWrite::new(writer, LengthDelimitedCodec::new()),
+        SymmetricalJson::<Message>::default(),
+    );
+    outgoing.send(Message::Status(snapshot.status)).await
+        .context("Could not send status")?;
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        tokio::select! {
+            message = incoming.next() => match message {
+                Some(Ok(Message::TakenOver { time })) => return Ok(Some(time)),
+                Some(Ok(message)) => log::warn!("Ignoring unexpected message from standby: {:?}", message),
+                Some(Err(error)) => return Err(error).context("Could not receive message"),
+                None => return Ok(None),
+            },
+            Some(update) = status_updates.next() => match update {
+                Ok(status) => outgoing.send(Message::Status(status)).await
+                    .context("Could not send status")?,
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Replication missed {} status updates", count),
+            },
+            _ = heartbeat.tick() => outgoing.send(Message::Heartbeat).await
+                .context("Could not send heartbeat")?,
+        }
+    }
+}
+
+/// Mirrors the status of the experiment on the primary. Once the primary has sent no heartbeat for the
+/// timeout, the standby reports it as unresponsive and takes over when an operator confirms the takeover
+async fn standby(
+    primary: SocketAddr,
+    timeout: Duration,
+    arena_tx: &mpsc::Sender<arena::Action>,
+    requests: &mut mpsc::Receiver<Action>,
+    updates_tx: &broadcast::Sender<Role>,
+) -> anyhow::Result<Option<Role>> {
+    let mut messages: Option<Messages<_>> = None;
+    let mut status = Status::default();
+    /* the time of the last message from the primary, None until the standby has connected to the primary */
+    let mut last_message: Option<Instant> = None;
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    let mut reported: Option<Role> = None;
+    let callback = loop {
+        /* a standby only takes over after it has been connected to the primary, so that starting the
+           standby before the primary does not allow a takeover */
+        let unresponsive = last_message.map_or(false, |last_message| last_message.elapsed() > timeout);
+        let role = Role::Standby { primary, connected: messages.is_some(), unresponsive, status: status.clone() };
+        if reported.as_ref() != Some(&role) {
+            if unresponsive {
+                log::warn!("Primary supervisor {} stopped responding, waiting for an operator to confirm the takeover", primary);
+            }
+            let _ = updates_tx.send(role.clone());
+            reported = Some(role.clone());
+        }
+        let message = async {
+            match messages.as_mut() {
+                Some(messages) => messages.next().await,
+                None => futures::future::pending().await,
+            }
+        };
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send((Some(role), updates_tx.subscribe()));
+                },
+                Some(Action::TakeOver(callback)) => match unresponsive {
+                    true => break callback,
+                    false => {
+                        let error = anyhow::anyhow!("The primary supervisor {} is still responding or has never been connected", primary);
+                        let _ = callback.send(Err(error));
+                    }
+                },
+                None => return Ok(None),
+            },
+            message = message => match message {
+                Some(Ok(message)) => {
+                    last_message = Some(Instant::now());
+                    if let Message::Status(update) = message {
+                        status = update;
+                    }
+                },
+                Some(Err(error)) => {
+                    log::warn!("Lost connection to primary supervisor {}: {}", primary, error);
+                    messages = None;
+                },
+                None => {
+                    log::warn!("Lost connection to primary supervisor {}", primary);
+                    messages = None;
+                },
+            },
+            _ = check.tick() => {
+                if messages.is_some() {
+                    continue;
+                }
+                match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(primary)).await {
+                    Ok(Ok(stream)) => {
+                        log::info!("Connected to primary supervisor {}", primary);
+                        last_message = Some(Instant::now());
+                        messages = Some(SymmetricallyFramed::new(
+                            FramedRead::new(stream, LengthDelimitedCodec::new()),
+                            SymmetricalJson::<Message>::default(),
+                        ));
+                    },
+                    /* the primary is expected to be unreachable until it is started */
+                    Ok(Err(_)) | Err(_) => continue,
+                }
+            },
+        }
+    };
+    log::warn!("The operator confirmed the takeover from primary supervisor {}", primary);
+    if arena_tx.send(arena::Action::TakeOver(status.clone())).await.is_err() {
+        let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with arena")));
+        return Err(anyhow::anyhow!("Could not communicate with arena"));
+    }
+    let _ = callback.send(Ok(()));
+    let time = chrono::Local::now().to_rfc3339();
+    let role = Role::TakenOver { primary, time: time.clone(), status };
+    let _ = updates_tx.send(role.clone());
+    /* tell the primary whenever it accepts connections again, e.g., after it was restarted or the network
+       partition has healed, so that it releases the robots and stays passive */
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Action::Subscribe(callback)) => {
+                    let _ = callback.send((Some(role.clone()), updates_tx.subscribe()));
+                },
+                Some(Action::TakeOver(callback)) => {
+                    let _ = callback.send(Err(anyhow::anyhow!("This supervisor has already taken over")));
+                },
+                None => return Ok(Some(role)),
+            },
+            _ = check.tick() => {
+                if let Ok(Ok(stream)) = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(primary)).await {
+                    let mut messages: Messages<_> = SymmetricallyFramed::new(
+                        FramedWrite::new(stream, LengthDelimitedCodec::new()),
+                        SymmetricalJson::<Message>::default(),
+                    );
+                    match messages.send(Message::TakenOver { time: time.clone() }).await {
+                        Ok(_) => log::warn!("Told primary supervisor {} that this supervisor has taken over", primary),
+                        Err(error) => log::warn!("Could not tell primary supervisor {} about the takeover: {}", primary, error),
+                    }
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Context;
+use std::{collections::HashMap, path::{Path, PathBuf}};
+use tokio::process::Command;
+use shared::experiment::software::{Repository, Software};
+
+/// A local cache of the git repositories from which the control software is deployed. Each repository is
+/// cloned once and fetched whenever an experiment is started. The software is only rebuilt from the
+/// repository when its reference resolves to a different commit
+pub struct Cache {
+    directory: PathBuf,
+    /* the commit that each repository was last built from and the resulting software */
+    bundles: HashMap<Repository, (String, Software)>,
+}
+
+impl Cache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Cache { directory: directory.into(), bundles: HashMap::new() }
+    }
+
+    /// Fetches the repository and returns the commit that the reference resolves to together with the
+    /// software built from the files in the directory of the repository at that commit
+    pub async fn checkout(&mut self, repository: &Repository) -> anyhow::Result<(String, Software)> {
+        /* each repository is cloned into a directory derived from its url */
+        let name = uuid::Uuid::new_v3(&uuid::Uuid::NAMESPACE_URL, repository.url.as_bytes());
+        let path = self.directory.join(name.to_string());
+        match path.exists() {
+            true => git(&path, &["fetch", "--quiet", "--force", "--tags", "origin"]).await
+                .context(format!("Could not fetch {}", repository.url))?,
+            false => {
+                tokio::fs::create_dir_all(&self.directory).await
+                    .context(format!("Could not create directory {:?}", self.directory))?;
+                let destination = name.to_string();
+                git(&self.directory, &["clone", "--quiet", "--no-checkout", &repository.url, &destination]).await
+                    .context(format!("Could not clone {}", repository.url))?
+            }
+        };
+        let commit = resolve(&path, &repository.reference).await?;
+        if let Some((built, software)) = self.bundles.get(repository) {
+            if built == &commit {
+                return Ok((commit, software.clone()));
+            }
+        }
+        let software = build(&path, &commit, &repository.directory).await?;
+        log::info!("Built software from {} at {} ({} files)", repository.url, commit, software.0.len());
+        self.bundles.insert(repository.clone(), (commit.clone(), software.clone()));
+        Ok((commit, software))
+    }
+}
+
+/// Resolves a reference to a commit, branches are resolved against the remote so that the most recently
+/// fetched commit is used
+async fn resolve(path: &Path, reference: &str) -> anyhow::Result<String> {
+    let reference = match reference.trim() {
+        "" => "HEAD",
+        reference => reference,
+    };
+    for candidate in &[format!("origin/{}^{{commit}}", reference), format!("{}^{{commit}}", reference)] {
+        if let Ok(commit) = git(path, &["rev-parse", "--verify", "--quiet", candidate]).await {
+            return Ok(String::from_utf8_lossy(&commit).trim().to_owned());
+        }
+    }
+    Err(anyhow::anyhow!("Could not resolve {}", reference))
+}
+
+/// Builds the software from the files (but not the subdirectories) in a directory of the repository
+async fn build(path: &Path, commit: &str, directory: &str) -> anyhow::Result<Software> {
+    let tree = match directory.trim_matches('/') {
+        "" => commit.to_owned(),
+        directory => format!("{}:{}", commit, directory),
+    };
+    let entries = git(path, &["ls-tree", "-z", &tree]).await
+        .context(format!("Could not list the files in {}", tree))?;
+    let mut software = Software::default();
+    for entry in entries.split(|&byte| byte == 0).filter(|entry| !entry.is_empty()) {
+        /* each entry has the format "<mode> <type> <object>\t<filename>" */
+        let entry = String::from_utf8_lossy(entry);
+        let mut fields = entry.splitn(2, '\t');
+        let (info, filename) = match (fields.next(), fields.next()) {
+            (Some(info), Some(filename)) => (info, filename),
+            _ => continue,
+        };
+        let object = match info.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", object] => object.to_owned(),
+            _ => continue,
+        };
+        let contents = git(path, &["cat-file", "blob", &object]).await
+            .context(format!("Could not read {}", filename))?;
+        software.add(filename, contents);
+    }
+    Ok(software)
+}
+
+/// Runs git in a directory and returns its standard output
+async fn git(path: &Path, args: &[&str]) -> anyhow::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .kill_on_drop(true)
+        .output().await
+        .context("Could not run git")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("git {} failed: {}", args.join(" "), error.trim()));
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,24 @@
+# Sets the LEDs of the BuilderBot to reflect the state of the robot in the supervisor. The LEDs are
+# exposed by the kernel under /sys/class/leds, blinking is done by the kernel using the timer trigger.
+import glob, sys
+
+LED_PATTERN = '/sys/class/leds/builderbot*:{}'
+COLOURS = { 'off': (), 'red': ('red',), 'green': ('green',), 'blue': ('blue',) }
+# blinking statuses, 'alert' is used by the watchdog when the Fernbedienung service stops responding
+BLINKING = { 'blink': ('blue',), 'alert': ('red',) }
+
+def write(path, value):
+    with open(path, 'w') as attribute:
+        attribute.write(value)
+
+status = sys.argv[1]
+channels = BLINKING[status] if status in BLINKING else COLOURS[status]
+for channel in ('red', 'green', 'blue'):
+    for led in glob.glob(LED_PATTERN.format(channel)):
+        if channel in channels and status in BLINKING:
+            write(led + '/trigger', 'timer')
+            write(led + '/delay_on', '250')
+            write(led + '/delay_off', '250')
+        else:
+            write(led + '/trigger', 'none')
+            write(led + '/brightness', '255' if channel in channels else '0')
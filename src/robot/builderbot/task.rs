@@ -1,14 +1,15 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{collections::BTreeMap, net::SocketAddr, sync::{Arc, atomic::{AtomicU64, Ordering}}, time::Duration};
 use anyhow::Context;
 use bytes::BytesMut;
-use tokio::{net::UdpSocket, sync::{broadcast, mpsc, oneshot}};
+use tokio::{net::UdpSocket, sync::{broadcast, mpsc, oneshot, watch}};
 use futures::{FutureExt, Stream, StreamExt, TryStreamExt};
 use tokio_stream::{self, wrappers::ReceiverStream};
 use tokio_util::sync::PollSender;
 
-use crate::network::{fernbedienung, fernbedienung_ext::MjpegStreamerStream};
-use crate::robot::{FernbedienungAction, TerminalAction};
-use crate::journal;
+use crate::network::{fernbedienung, fernbedienung_ext::{Artifacts, BandwidthTest, CrashDumps, Leftovers, MjpegStreamerStream, ResourceLimits, SmokeTest, Watchdog, WorkingDirectory}};
+use crate::robot::{ActionQueue, ArgosOptions, Decimator, EventLog, FernbedienungAction, StatusLeds, TerminalInput, TERMINAL_INPUT_INTERVAL, TerminalAction, UptimeMonitor, Polling, LINK_STRENGTH_INTERVAL, fernbedienung_heartbeat, fernbedienung_uptime_stream, reduced_polling};
+use crate::{journal, trace};
+use shared::{camera, configuration::Heartbeat, event_log::Category, experiment::{Energy, Summary, TimelineCategory}};
 
 pub use shared::{
     builderbot::{Descriptor, Update},
@@ -19,25 +20,69 @@ const IDENTIFY_BUILDERBOT_ARGOS: (&'static str, &'static [u8]) =
     ("identify_builderbot.argos", include_bytes!("identify_builderbot.argos"));
 const IDENTIFY_BUILDERBOT_LUA: (&'static str, &'static [u8]) = 
     ("identify_builderbot.lua", include_bytes!("identify_builderbot.lua"));
+const SPIN_BUILDERBOT_ARGOS: (&'static str, &'static [u8]) =
+    ("spin_builderbot.argos", include_bytes!("spin_builderbot.argos"));
+const SPIN_BUILDERBOT_LUA: (&'static str, &'static [u8]) =
+    ("spin_builderbot.lua", include_bytes!("spin_builderbot.lua"));
+const SMOKE_TEST_BUILDERBOT_ARGOS: (&'static str, &'static [u8]) =
+    ("smoke_test_builderbot.argos", include_bytes!("smoke_test_builderbot.argos"));
+const SMOKE_TEST_BUILDERBOT_LUA: (&'static str, &'static [u8]) =
+    ("smoke_test_builderbot.lua", include_bytes!("smoke_test_builderbot.lua"));
+const STATUS_LEDS_PY: &'static str = include_str!("status_leds.py");
 
 const BUILDERBOT_CAMERAS_CONFIG: &[(&str, u16, u16, u16)] = &[];
 
 #[derive(Debug)]
 pub enum Action {
-    AssociateFernbedienung(fernbedienung::Device),
+    /* the device is returned if the robot is associated with another device at a different address */
+    AssociateFernbedienung(fernbedienung::Device, oneshot::Sender<Result<(), fernbedienung::Device>>),
     ExecuteFernbedienungAction(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction),
-    Subscribe(oneshot::Sender<broadcast::Receiver<Update>>),
+    CancelQueuedAction(oneshot::Sender<anyhow::Result<()>>, u64),
+    /* subscribe to the updates, the current state of the robot is returned as a sequence of updates */
+    Subscribe(oneshot::Sender<(Vec<Update>, broadcast::Receiver<Update>)>),
     // its good to keep this one seperate since start exp need to interact with xbee and fernbedienung
-    SetupExperiment(oneshot::Sender<anyhow::Result<()>>, String, Software, mpsc::Sender<journal::Action>),
+    SetupExperiment(oneshot::Sender<anyhow::Result<()>>, String, Software, mpsc::Sender<journal::Action>, Option<Artifacts>, BTreeMap<String, String>, Decimator),
     StartExperiment(oneshot::Sender<anyhow::Result<()>>),
     StopExperiment,
+    /* an action that was caused by a request from the user interface and the trace of that request */
+    Traced(trace::Handle, Box<Action>),
 }
 
-pub type Sender = mpsc::Sender<Action>;
+impl Action {
+    /// Attaches the trace of a request to the action, if the action was caused by a request
+    pub fn traced(self, trace: Option<trace::Handle>) -> Self {
+        match trace {
+            Some(trace) => Action::Traced(trace, Box::new(self)),
+            None => self,
+        }
+    }
+
+    /// Removes the trace from an action, the receipt and the completion of the action are recorded in the trace,
+    /// which is returned so that the requests to the fernbedienung service can be traced
+    fn untrace(self) -> (Self, Option<trace::Handle>) {
+        match self {
+            Action::Traced(trace, action) => match *action {
+                    Action::ExecuteFernbedienungAction(callback, action) => {
+                        trace::record(&trace, "builderbot", format!("Received {:?}", action));
+                        (Action::ExecuteFernbedienungAction(trace::callback(Some(trace.clone()), "builderbot".to_owned(), callback), action), Some(trace))
+                    },
+                    Action::CancelQueuedAction(callback, queued) => {
+                        trace::record(&trace, "builderbot", format!("Received cancellation of queued action {}", queued));
+                        (Action::CancelQueuedAction(trace::callback(Some(trace.clone()), "builderbot".to_owned(), callback), queued), Some(trace))
+                    },
+                    action => action.untrace(),
+                },
+            action => (action, None),
+        }
+    }
+}
+
+pub type Sender = crate::robot::Outbound<Action>;
 pub type Receiver = mpsc::Receiver<Action>;
 
 fn fernbedienung_link_strength_stream<'dev>(
-    device: &'dev fernbedienung::Device
+    device: &'dev fernbedienung::Device,
+    mut polling_rx: watch::Receiver<Polling>,
 ) -> impl Stream<Item = anyhow::Result<i32>> + 'dev {
     async_stream::stream! {
         let mut attempts : u8 = 0;
@@ -49,6 +94,7 @@ fn fernbedienung_link_strength_stream<'dev>(
                 Ok(response) => {
                     attempts = 0;
                     yield Ok(response);
+                    reduced_polling(&mut polling_rx, LINK_STRENGTH_INTERVAL, |factors| factors.link_strength).await;
                 },
                 Err(error) => match attempts {
                     0..=4 => attempts += 1,
@@ -69,9 +115,13 @@ async fn bash(
     let stderr = futures::stream::pending().left_stream();
     let mut stdin = None;
     let mut terminate = None;
+    /* input is sent to the terminal in paced chunks to avoid flooding the connection to the robot */
+    let mut input = TerminalInput::default();
+    let pacing = tokio::time::sleep(Duration::default());
     tokio::pin!(process);
     tokio::pin!(stdout);
     tokio::pin!(stderr);
+    tokio::pin!(pacing);
     loop {
         tokio::select! {
             Some((callback, action)) = rx.recv() => match action {
@@ -90,31 +140,52 @@ async fn bash(
                         target: "bash".into(),
                         working_dir: None,
                         args: vec!["-li".to_owned()],
+                        environment: Default::default(),
                     };
-                    process.set(device.run(bash, terminate_rx, stdin_rx, stdout_tx, stderr_tx).right_future());
+                    process.set(device.run_bulk(bash, terminate_rx, stdin_rx, stdout_tx, stderr_tx).right_future());
                     let _ = callback.send(Ok(()));
                 },
-                TerminalAction::Run(mut command) => if let Some(tx) = stdin.as_ref() {
+                TerminalAction::Run(mut command) => if stdin.is_some() {
                     command.push_str("\r");
-                    let result = tx.send(BytesMut::from(command.as_bytes())).await
-                        .map_err(|_| {
-                            /* remove the "\r" before including the command in the error message */
-                            command.pop();
-                            anyhow::anyhow!("Could not send \"{}\" to Bash terminal", command)
-                        });
-                    let _ = callback.send(result);
+                    let _ = callback.send(input.push(&command));
                 },
                 TerminalAction::Stop => if let Some(tx) = terminate.take() {
                     let _ = tx.send(());
+                    if !input.is_empty() {
+                        input.clear();
+                        if let Some((sent, total)) = input.progress() {
+                            let _ = updates_tx.send(Update::BashProgress { sent, total });
+                        }
+                    }
                     let _ = callback.send(Ok(()));
                 }
             },
+            _ = &mut pacing, if !input.is_empty() => {
+                pacing.as_mut().reset(tokio::time::Instant::now() + TERMINAL_INPUT_INTERVAL);
+                match (stdin.as_ref(), input.next()) {
+                    (Some(tx), Some(chunk)) => match tx.try_send(chunk) {
+                        Ok(_) => {},
+                        Err(mpsc::error::TrySendError::Full(chunk)) => input.retry(chunk),
+                        Err(mpsc::error::TrySendError::Closed(_)) => input.clear(),
+                    },
+                    _ => input.clear(),
+                }
+                if let Some((sent, total)) = input.progress() {
+                    let _ = updates_tx.send(Update::BashProgress { sent, total });
+                }
+            },
             result = &mut process => {
                 process.set(futures::future::pending().left_future());
                 stdout.set(futures::stream::pending().left_stream());
                 stderr.set(futures::stream::pending().left_stream());
                 stdin = None;
                 terminate = None;
+                if !input.is_empty() {
+                    input.clear();
+                    if let Some((sent, total)) = input.progress() {
+                        let _ = updates_tx.send(Update::BashProgress { sent, total });
+                    }
+                }
                 log::info!("Remote Bash instance terminated with {:?}", result);
             }
             Some(stdout) = stdout.next() => {
@@ -130,6 +201,8 @@ async fn bash(
 }
 
 async fn argos(device: &fernbedienung::Device,
+    options: ArgosOptions,
+    updates_tx: broadcast::Sender<Update>,
     callback: oneshot::Sender<anyhow::Result<()>>,
     software: Software,
     id: impl Into<Option<String>>,
@@ -141,6 +214,7 @@ async fn argos(device: &fernbedienung::Device,
     let id = id.into();
     let router_socket = router_socket.into();
     let journal = journal.into();
+    let ArgosOptions { limits, artifacts, environment, mut decimator } = options;
     let wait_rx = wait_rx.into();
     /* create temp directory */
     let path = match device.create_temp_dir().await {
@@ -171,6 +245,12 @@ async fn argos(device: &fernbedienung::Device,
             }
         }
     }
+    /* core dumps are only retrieved during experiments, a failure here is not fatal */
+    if journal.is_some() {
+        if let Err(error) = CrashDumps::enable(device).await {
+            log::warn!("Could not enable core dumps: {:#}", error);
+        }
+    }
     if let Err(_) = callback.send(Ok(())) {
         /* abort if the callback was dropped before we
            could signal that we are ready */
@@ -196,9 +276,15 @@ async fn argos(device: &fernbedienung::Device,
     args.extend(id.iter().flat_map(|id| vec!["--id".to_owned(), id.clone()]));
     let process = fernbedienung::Process {
         target: "argos3".into(),
-        working_dir: Some(path.into()),
+        working_dir: Some(path.clone().into()),
         args,
+        environment,
     };
+    let process = CrashDumps::apply(limits.apply(device, process).await);
+    /* safety events and disconnects are recorded on the timeline of the experiment */
+    let timeline = journal.clone().zip(id.clone());
+    /* the amount of output from ARGoS is included in the summary of the experiment */
+    let output = Arc::new(AtomicU64::new(0));
     let (stdout_tx, mut forward_stdout, stderr_tx, mut forward_stderr) = match (journal, id) {
         (Some(journal), Some(id)) => {
             use journal::{ARGoS, Event, Action};
@@ -208,13 +294,29 @@ async fn argos(device: &fernbedienung::Device,
             let stderr_stream = ReceiverStream::new(stderr_rx);
             let journal_sink = PollSender::new(journal.clone());
             let stdout_robot_id = id.clone();
-            let forward_stdout = stdout_stream.map(move |data: BytesMut| 
-                Ok(Action::Record(Event::ARGoS(stdout_robot_id.clone(), ARGoS::StandardOutput(data)))))
-                    .forward(journal_sink).right_future();
+            let stdout_output = output.clone();
+            /* the output is also shown live on the card of the robot */
+            let stdout_updates_tx = updates_tx.clone();
+            let forward_stdout = stdout_stream.filter_map(move |data: BytesMut| {
+                stdout_output.fetch_add(data.len() as u64, Ordering::Relaxed);
+                let text = String::from_utf8_lossy(&data).into_owned();
+                let _ = stdout_updates_tx.send(Update::ArgosOutput { error: false, text });
+                /* the output is shown in full, but only the output that is kept by the decimation is journaled */
+                let data = decimator.apply(data);
+                futures::future::ready(match data.is_empty() {
+                    true => None,
+                    false => Some(Ok(Action::Record(Event::ARGoS(stdout_robot_id.clone(), ARGoS::StandardOutput(data))))),
+                })
+            }).forward(journal_sink).right_future();
             let journal_sink = PollSender::new(journal);
-            let forward_stderr = stderr_stream.map(move |data: BytesMut| 
-                Ok(Action::Record(Event::ARGoS(id.clone(), ARGoS::StandardError(data)))))
-                    .forward(journal_sink).right_future();
+            let stderr_output = output.clone();
+            let stderr_updates_tx = updates_tx.clone();
+            let forward_stderr = stderr_stream.map(move |data: BytesMut| {
+                stderr_output.fetch_add(data.len() as u64, Ordering::Relaxed);
+                let text = String::from_utf8_lossy(&data).into_owned();
+                let _ = stderr_updates_tx.send(Update::ArgosOutput { error: true, text });
+                Ok(Action::Record(Event::ARGoS(id.clone(), ARGoS::StandardError(data))))
+            }).forward(journal_sink).right_future();
             (Some(stdout_tx), forward_stdout, Some(stderr_tx), forward_stderr)
         },
         (_, _) => {
@@ -223,8 +325,12 @@ async fn argos(device: &fernbedienung::Device,
         }
     };
     let (terminate_tx, terminate_rx) = oneshot::channel();      
-    let argos = device.run(process, terminate_rx, None, stdout_tx, stderr_tx);
+    let argos = device.run_bulk(process, terminate_rx, None, stdout_tx, stderr_tx);
     tokio::pin!(argos);
+    let mut summary = Summary::default();
+    let mut crashed = false;
+    /* the battery readings while ARGoS is running are used to estimate the energy that was used */
+    let mut battery_updates = updates_tx.subscribe();
     loop {
         tokio::select! {
             _ = &mut forward_stdout => {
@@ -235,21 +341,110 @@ async fn argos(device: &fernbedienung::Device,
                 /* disable while we wait for the other futures to finish */
                 forward_stderr = futures::future::pending().left_future();
             },
+            Ok(update) = battery_updates.recv() => match update {
+                Update::Battery(reading) => Energy::battery(&mut summary.energy, reading),
+                _ => {},
+            },
             /* local shutdown */
             _ = &mut stop_rx => {
                 let _ = terminate_tx.send(());
+                /* give ARGoS time to exit so that the artifacts are complete before collecting them */
+                if artifacts.is_some() || timeline.is_some() {
+                    let _ = tokio::time::timeout(Duration::from_secs(5), &mut argos).await;
+                }
+                summary.exit = "Stopped by the supervisor".to_owned();
                 break;
             }
             /* argos finished */
-            _ = &mut argos => break,
+            result = &mut argos => {
+                summary.exit = match &result {
+                    Ok(_) => "Exited".to_owned(),
+                    Err(error) => error.to_string(),
+                };
+                summary.failed = result.is_err();
+                crashed = matches!(result, Err(fernbedienung::Error::AbnormalTerminationError));
+                let event = match result {
+                    Ok(_) => None,
+                    Err(fernbedienung::Error::AbnormalTerminationError) =>
+                        match limits.violation(device, "argos3").await {
+                            Some(violation) => {
+                                log::warn!("{}", violation);
+                                let _ = updates_tx.send(Update::ResourceLimitViolation(violation.clone()));
+                                Some((TimelineCategory::Safety, violation))
+                            },
+                            None => None,
+                        },
+                    Err(error) => Some((TimelineCategory::Disconnect, format!("Lost connection to ARGoS ({})", error))),
+                };
+                if let Some((_, description)) = &event {
+                    summary.events.push(description.clone());
+                }
+                if let (Some((journal_tx, id)), Some((category, description))) = (timeline.as_ref(), event) {
+                    let event = journal::Event::Timeline(category, format!("{}: {}", id, description), Vec::new());
+                    let _ = journal_tx.send(journal::Action::Record(event)).await;
+                }
+                break;
+            },
+        }
+    }
+    /* retrieve the backtrace from the core dump if ARGoS crashed during an experiment */
+    if let (true, Some((journal_tx, id))) = (crashed, timeline.as_ref()) {
+        /* the core dump is only kept if artifacts are collected from the robot */
+        let store = artifacts.as_ref().map(|_| (id.as_str(), journal_tx));
+        match CrashDumps::retrieve(device, &path, "argos3", store).await {
+            Ok(Some(backtrace)) => {
+                let event = journal::Event::ARGoS(id.clone(), journal::ARGoS::Backtrace(backtrace.clone()));
+                let _ = journal_tx.send(journal::Action::Record(event)).await;
+                summary.backtrace = Some(backtrace);
+            },
+            Ok(None) => {},
+            Err(error) => log::warn!("Could not retrieve crash dump: {:#}", error),
+        }
+    }
+    /* store the working directory next to the journal, since it is lost when the robot is rebooted */
+    if let Some((journal_tx, id)) = timeline.as_ref() {
+        match WorkingDirectory::retrieve(device, &path, id, journal_tx).await {
+            Ok(count) => log::info!("Retrieved {} files from the working directory of {}", count, id),
+            Err(error) => log::warn!("Could not retrieve the working directory of {}: {:#}", id, error),
+        }
+    }
+    /* collect the artifacts from the working directory */
+    if let (Some(artifacts), Some((journal_tx, id))) = (artifacts, timeline.as_ref()) {
+        let result = artifacts.collect(device, &path, id, journal_tx).await
+            .map_err(|error| format!("{:#}", error));
+        if let Err(error) = &result {
+            log::warn!("Could not collect artifacts: {}", error);
+        }
+        let _ = updates_tx.send(Update::Artifacts(result));
+    }
+    /* summarize the experiment, this is not done when ARGoS is used to identify the robot */
+    if let Some((journal_tx, id)) = timeline {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        if let Ok(_) = journal_tx.send(journal::Action::MessageCount(device.addr.into(), callback_tx)).await {
+            summary.messages = callback_rx.await.unwrap_or(0);
+        }
+        summary.finished = chrono::Local::now().to_rfc3339();
+        summary.output = output.load(Ordering::Relaxed);
+        if let Some(energy) = summary.energy.clone() {
+            let event = journal::Event::Energy(id.clone(), energy);
+            let _ = journal_tx.send(journal::Action::Record(event)).await;
         }
+        let _ = updates_tx.send(Update::ExperimentSummary(summary));
     }
 }
 
 async fn fernbedienung(
     device: fernbedienung::Device,
-    mut rx: mpsc::Receiver<(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction)>,
-    updates_tx: broadcast::Sender<Update>
+    limits: ResourceLimits,
+    watchdog: Option<Watchdog>,
+    mut rx: mpsc::Receiver<(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction, Option<trace::Handle>)>,
+    updates_tx: broadcast::Sender<Update>,
+    uptime_tx: mpsc::Sender<Duration>,
+    firmware_tx: mpsc::Sender<String>,
+    cameras_tx: mpsc::Sender<camera::Availability>,
+    watchdog_tx: mpsc::Sender<Vec<String>>,
+    polling_rx: watch::Receiver<Polling>,
+    heartbeat: Heartbeat,
 ) {
     /* ARGos task */
     let argos_task = futures::future::pending().left_future();
@@ -261,14 +456,71 @@ async fn fernbedienung(
     let bash_task = bash(&device, bash_rx, updates_tx.clone());
     tokio::pin!(bash_task);
     /* link strength stream */
-    let link_strength_stream = fernbedienung_link_strength_stream(&device)
+    let link_strength_stream = fernbedienung_link_strength_stream(&device, polling_rx.clone())
         .map_ok(Update::FernbedienungSignal);
     let link_strength_stream_throttled =
-        tokio_stream::StreamExt::throttle(link_strength_stream, Duration::from_millis(1000));
+        tokio_stream::StreamExt::throttle(link_strength_stream, LINK_STRENGTH_INTERVAL);
     tokio::pin!(link_strength_stream_throttled);
+    /* uptime stream */
+    let uptime_stream = fernbedienung_uptime_stream(&device, polling_rx);
+    tokio::pin!(uptime_stream);
+    /* the connection to the robot is considered lost once a heartbeat is not answered */
+    let heartbeat_task = fernbedienung_heartbeat(&device, heartbeat);
+    tokio::pin!(heartbeat_task);
+    /* the firmware is only read once per association */
+    match tokio::time::timeout(Duration::from_millis(1000), device.firmware()).await {
+        Ok(Ok(firmware)) => {
+            let _ = firmware_tx.try_send(firmware);
+        },
+        Ok(Err(error)) => log::warn!("Could not read firmware version of DuoVero: {}", error),
+        Err(_) => log::warn!("Timeout while reading firmware version of DuoVero"),
+    }
+    /* the cameras are only checked once per association, the cameras are assumed to be available if the
+       check fails so that the camera streams are attempted as before */
+    let cameras = BUILDERBOT_CAMERAS_CONFIG.iter().map(|&(camera, ..)| camera).collect::<Vec<_>>();
+    let cameras = match tokio::time::timeout(Duration::from_millis(1000), MjpegStreamerStream::availability(&device, &cameras)).await {
+        Ok(Ok(cameras)) => {
+            /* robot types without cameras are not reported */
+            if let (false, Some(reason)) = (cameras.cameras.is_empty(), cameras.reason()) {
+                log::warn!("Cameras of {} are not supported: {}", device.addr, reason);
+            }
+            let _ = cameras_tx.try_send(cameras.clone());
+            Some(cameras)
+        },
+        Ok(Err(error)) => {
+            log::warn!("{:#}", error);
+            None
+        },
+        Err(_) => {
+            log::warn!("Timeout while checking the availability of the cameras");
+            None
+        },
+    };
+    /* report what the watchdog did since the previous association before it is redeployed */
+    match watchdog.as_ref() {
+        Some(watchdog) => {
+            match Watchdog::events(&device).await {
+                Ok(events) => {
+                    for event in &events {
+                        log::warn!("Watchdog on {}: {}", device.addr, event);
+                    }
+                    let _ = watchdog_tx.try_send(events);
+                },
+                Err(error) => log::warn!("{:#}", error),
+            }
+            if let Err(error) = watchdog.deploy(&device, STATUS_LEDS_PY).await {
+                log::warn!("Could not deploy watchdog to {}: {:#}", device.addr, error);
+            }
+        },
+        None => if let Err(error) = Watchdog::stop(&device).await {
+            log::debug!("{:#}", error);
+        },
+    }
     /* camera stream */
     let mut cameras_stream: tokio_stream::StreamMap<String, _> =
         tokio_stream::StreamMap::new();
+    /* whether the camera streams were enabled before they were suspended */
+    let mut cameras_suspended = false;
     
     loop {
         tokio::select! {
@@ -277,22 +529,55 @@ async fn fernbedienung(
                 let update = Update::Camera { camera, result: result.map_err(|e| e.to_string()) };
                 let _ = updates_tx.send(update);
             },
+            error = &mut heartbeat_task => {
+                log::warn!("{:#}", error);
+                device.mark_lost();
+                break;
+            },
             Some(response) = link_strength_stream_throttled.next() => match response {
                 Ok(update) => {
                     let _ = updates_tx.send(update);
                 },
-                Err(error) => {
-                    log::warn!("{}", error);
-                    break;
-                },
+                Err(error) => log::warn!("{}", error),
+            },
+            Some(uptime) = uptime_stream.next() => {
+                /* use try_send to avoid a deadlock with the robot task, which awaits sending actions to this task */
+                let _ = uptime_tx.try_send(uptime);
             },
             recv = rx.recv() => match recv {
-                Some((callback, action)) => match action {
+                Some((callback, action, trace)) => match action {
                     FernbedienungAction::SetCameraStream(enable) => {
                         cameras_stream.clear();
+                        cameras_suspended = false;
+                        let reason = cameras.as_ref().and_then(camera::Availability::reason);
+                        if let (true, Some(reason)) = (enable, reason) {
+                            let _ = callback.send(Err(anyhow::anyhow!("Could not stream cameras: {}", reason)));
+                            continue;
+                        }
                         if enable {
                             for &(camera, width, height, port) in BUILDERBOT_CAMERAS_CONFIG {
-                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port);
+                                if !cameras.as_ref().map_or(true, |cameras| cameras.is_available(camera)) {
+                                    continue;
+                                }
+                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port, &limits);
+                                let stream = tokio_stream::StreamExt::throttle(stream, Duration::from_millis(200));
+                                cameras_stream.insert(camera.to_owned(), Box::pin(stream));
+                            }
+                        }
+                        let _ = callback.send(Ok(()));
+                    },
+                    FernbedienungAction::SuspendCameraStream(true) => {
+                        cameras_suspended |= !cameras_stream.is_empty();
+                        cameras_stream.clear();
+                        let _ = callback.send(Ok(()));
+                    },
+                    FernbedienungAction::SuspendCameraStream(false) => {
+                        if std::mem::take(&mut cameras_suspended) {
+                            for &(camera, width, height, port) in BUILDERBOT_CAMERAS_CONFIG {
+                                if !cameras.as_ref().map_or(true, |cameras| cameras.is_available(camera)) {
+                                    continue;
+                                }
+                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port, &limits);
                                 let stream = tokio_stream::StreamExt::throttle(stream, Duration::from_millis(200));
                                 cameras_stream.insert(camera.to_owned(), Box::pin(stream));
                             }
@@ -300,12 +585,12 @@ async fn fernbedienung(
                         let _ = callback.send(Ok(()));
                     },
                     FernbedienungAction::Halt => {
-                        let result = device.halt().await
+                        let result = trace::within(trace, device.halt()).await
                             .context("Could not halt DuoVero");
                         let _ = callback.send(result);
                     },
                     FernbedienungAction::Reboot => {
-                        let result = device.reboot().await
+                        let result = trace::within(trace, device.reboot()).await
                             .context("Could not reboot DuoVero");
                         let _ = callback.send(result);
                     },
@@ -320,7 +605,7 @@ async fn fernbedienung(
                             anyhow::anyhow!("Could not send {:?} to Bash terminal: channel is {}", action, reason);
                         let _ = callback.send(Err(error));
                     },
-                    FernbedienungAction::SetupExperiment(id, software, journal) => match argos_stop_tx.as_ref() {
+                    FernbedienungAction::SetupExperiment(id, software, journal, artifacts, environment, decimator) => match argos_stop_tx.as_ref() {
                         Some(_) => {
                             let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already setup or running")));
                         }
@@ -343,6 +628,8 @@ async fn fernbedienung(
                                     let (stop_tx, stop_rx) = oneshot::channel();
                                     let task = argos(
                                         &device,
+                                        ArgosOptions { limits: limits.clone(), artifacts, environment, decimator },
+                                        updates_tx.clone(),
                                         callback,
                                         software,
                                         id,
@@ -375,11 +662,89 @@ async fn fernbedienung(
                             let _ = callback.send(Err(anyhow::anyhow!("Experiment has not been set up")));
                         }
                     },
+                    FernbedienungAction::PixhawkFailsafe => {
+                        let _ = callback.send(Err(anyhow::anyhow!("BuilderBot does not have a Pixhawk")));
+                    },
+                    FernbedienungAction::SetStatusLeds(status) => {
+                        let _ = callback.send(trace::within(trace, status.apply(&device, STATUS_LEDS_PY)).await);
+                    },
+                    FernbedienungAction::FetchResults(results) => {
+                        let result = trace::within(trace, results.fetch(&device)).await;
+                        if let Ok(files) = &result {
+                            log::info!("Fetched {} files from {} into {:?}", files.len(), device.addr, results.directory);
+                            let _ = updates_tx.send(Update::Artifacts(Ok(files.clone())));
+                        }
+                        let _ = callback.send(result.map(|_| ()).context("Could not fetch results"));
+                    },
+                    FernbedienungAction::VerifyStopped => {
+                        let _ = callback.send(trace::within(trace, Leftovers::verify(&device)).await);
+                    },
+                    FernbedienungAction::KillArgos => {
+                        let _ = callback.send(trace::within(trace, Leftovers::kill(&device)).await);
+                    },
+                    FernbedienungAction::SmokeTest(checks_tx) => match argos_stop_tx.as_ref() {
+                        Some(_) => {
+                            let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
+                        }
+                        None => {
+                            let process = fernbedienung::Process {
+                                target: "argos3".into(),
+                                working_dir: None,
+                                args: vec!["--config".to_owned(), SMOKE_TEST_BUILDERBOT_ARGOS.0.to_owned()],
+                                environment: Default::default(),
+                            };
+                            let files = [SMOKE_TEST_BUILDERBOT_ARGOS, SMOKE_TEST_BUILDERBOT_LUA];
+                            let result = trace::within(trace, SmokeTest::run(&device, limits.apply(&device, process).await, &files)).await
+                                .map(|checks| {
+                                    let _ = checks_tx.send(checks);
+                                });
+                            let _ = callback.send(result);
+                        }
+                    },
+                    FernbedienungAction::BandwidthTest(checks_tx) => match argos_stop_tx.as_ref() {
+                        Some(_) => {
+                            let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
+                        }
+                        None => {
+                            let result = trace::within(trace, BandwidthTest::run(&device)).await
+                                .map(|checks| {
+                                    let _ = checks_tx.send(checks);
+                                });
+                            let _ = callback.send(result);
+                        }
+                    },
+                    FernbedienungAction::Spin => match argos_stop_tx.as_ref() {
+                        Some(_) => {
+                            let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
+                        }
+                        None => {
+                            let software = Software(vec![
+                                (SPIN_BUILDERBOT_ARGOS.0.to_owned(), SPIN_BUILDERBOT_ARGOS.1.to_vec()),
+                                (SPIN_BUILDERBOT_LUA.0.to_owned(), SPIN_BUILDERBOT_LUA.1.to_vec())
+                            ]);
+                            match software.check_config() {
+                                Err(error) => {
+                                    let _ = callback.send(Err(error).context("Spin software error"));
+                                }
+                                Ok(_) => {
+                                    let (start_tx, start_rx) = oneshot::channel();
+                                    start_tx.send(()).unwrap();
+                                    let (stop_tx, stop_rx) = oneshot::channel();
+                                    let task = argos(&device, ArgosOptions { limits: limits.clone(), ..Default::default() }, updates_tx.clone(), callback, software, None, None, None, start_rx, stop_rx);
+                                    argos_task.set(task.right_future().right_future());
+                                    argos_stop_tx = Some(stop_tx);
+                                }
+                            }
+                        }
+                    },
                     FernbedienungAction::Identify => match argos_stop_tx.as_ref() {
                         Some(_) => {
                             let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
                         }
                         None => {
+                            if let Err(error) = StatusLeds::Identify.apply(&device, STATUS_LEDS_PY).await {
+                                log::warn!("{}", error);
+                            }
                             let software = Software(vec![
                                 (IDENTIFY_BUILDERBOT_ARGOS.0.to_owned(), IDENTIFY_BUILDERBOT_ARGOS.1.to_vec()),
                                 (IDENTIFY_BUILDERBOT_LUA.0.to_owned(), IDENTIFY_BUILDERBOT_LUA.1.to_vec())
@@ -392,7 +757,7 @@ async fn fernbedienung(
                                     let (start_tx, start_rx) = oneshot::channel();
                                     start_tx.send(()).unwrap();
                                     let (stop_tx, stop_rx) = oneshot::channel();
-                                    let task = argos(&device, callback, software, None, None, None, start_rx, stop_rx);
+                                    let task = argos(&device, ArgosOptions { limits: limits.clone(), ..Default::default() }, updates_tx.clone(), callback, software, None, None, None, start_rx, stop_rx);
                                     argos_task.set(task.right_future().right_future());
                                     argos_stop_tx = Some(stop_tx);
                                 }
@@ -418,51 +783,151 @@ async fn fernbedienung(
     }
 }
 
-pub async fn new(mut action_rx: Receiver) {
+pub async fn new(
+    mut action_rx: Receiver,
+    limits: ResourceLimits,
+    watchdog: Option<Watchdog>,
+    polling_rx: watch::Receiver<Polling>,
+    heartbeat: Heartbeat,
+) {
     /* fernbedienung task state */
     let fernbedienung_task = futures::future::pending().left_future();
     let mut fernbedienung_tx = Option::default();
     let mut fernbedienung_addr = Option::default();
     tokio::pin!(fernbedienung_task);
+    /* the address of the robot while waiting for it to reconnect after its heartbeat timed out */
+    let mut reconnecting = Option::default();
+    let reconnect_timeout = futures::future::pending().left_future();
+    tokio::pin!(reconnect_timeout);
     /* updates_tx is for sending changes in state to subscribers (e.g., the webui) */
     let (updates_tx, _) = broadcast::channel(16);
+    /* uptime of the robot as reported by the fernbedienung task */
+    let (uptime_tx, mut uptime_rx) = mpsc::channel(8);
+    let mut uptime_monitor = UptimeMonitor::default();
+    /* firmware of the robot as reported by the fernbedienung task */
+    let (firmware_tx, mut firmware_rx) = mpsc::channel(1);
+    let mut firmware = Option::default();
+    /* availability of the cameras as reported by the fernbedienung task */
+    let (cameras_tx, mut cameras_rx) = mpsc::channel(1);
+    let mut cameras = Option::default();
+    /* interventions of the watchdog as reported by the fernbedienung task */
+    let (watchdog_tx, mut watchdog_rx) = mpsc::channel(1);
+    let mut watchdog_events: Vec<String> = Vec::new();
+    /* actions that are executed once the robot is associated again */
+    let mut action_queue = ActionQueue::default();
+    /* the event log of the robot, the errors that the tasks of the robot report are recorded from their updates */
+    let mut event_log = EventLog::default();
+    let mut task_updates_rx = updates_tx.subscribe();
     
     // TODO: for a clean shutdown we may want to consider the case where updates_tx hangs up
     loop {
         tokio::select! {
-            Some(action) = action_rx.recv() => match action {
-                Action::AssociateFernbedienung(device) => {
+            Some((action, trace)) = action_rx.recv().map(|action| action.map(Action::untrace)) => match action {
+                /* a second device that claims to be this robot, e.g., after its SD card was cloned, is returned
+                   instead of replacing the device that the robot is associated with */
+                Action::AssociateFernbedienung(device, callback) if fernbedienung_addr.is_some() && fernbedienung_addr != Some(device.addr) => {
+                    let _ = callback.send(Err(device));
+                },
+                Action::AssociateFernbedienung(device, callback) => {
+                    let _ = callback.send(Ok(()));
                     let (tx, rx) = mpsc::channel(8);
                     fernbedienung_tx = Some(tx);
                     fernbedienung_addr = Some(device.addr);
+                    reconnecting = None;
+                    reconnect_timeout.set(futures::future::pending().left_future());
                     let _ = updates_tx.send(Update::FernbedienungConnected(device.addr));
-                    let task = tokio::spawn(fernbedienung(device, rx, updates_tx.clone()));
+                    let event = format!("DuoVero connected at {}", device.addr);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+                    let name = format!("builderbot/fernbedienung@{}", device.addr);
+                    let task = crate::instrument::spawn(name, fernbedienung(device, limits.clone(), watchdog.clone(), rx, updates_tx.clone(), uptime_tx.clone(), firmware_tx.clone(), cameras_tx.clone(), watchdog_tx.clone(), polling_rx.clone(), heartbeat));
                     fernbedienung_task.set(task.right_future());
+                    uptime_monitor.associate();
+                    /* execute the actions that were queued while the robot was disconnected */
+                    if let (Some(tx), Some(addr), false) = (fernbedienung_tx.as_ref(), fernbedienung_addr, action_queue.is_empty()) {
+                        for action in action_queue.take() {
+                            if matches!(action, FernbedienungAction::Halt | FernbedienungAction::Reboot) {
+                                uptime_monitor.expect_reboot();
+                            }
+                            if let Some(event) = action.power_event() {
+                                let event = format!("{} (queued)", event);
+                                let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                            }
+                            let (callback_tx, callback_rx) = oneshot::channel();
+                            if let Ok(_) = tx.send((callback_tx, action, None)).await {
+                                let name = format!("builderbot/queued@{}", addr);
+                                crate::instrument::spawn(name, async move {
+                                    if let Ok(Err(error)) = callback_rx.await {
+                                        log::warn!("Could not execute queued action: {:#}", error);
+                                    }
+                                });
+                            }
+                        }
+                        let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                    }
                 },
                 Action::ExecuteFernbedienungAction(callback, action) => match fernbedienung_tx.as_ref() {
                     Some(tx) => {
-                        if let Err(mpsc::error::SendError((callback, _))) = tx.send((callback, action)).await {
+                        if matches!(action, FernbedienungAction::Halt | FernbedienungAction::Reboot) {
+                            uptime_monitor.expect_reboot();
+                        }
+                        if let Some(event) = action.power_event() {
+                            let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                        }
+                        if let Err(mpsc::error::SendError((callback, _, _))) = tx.send((callback, action, trace)).await {
                             let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with Fernbedienung task")));
                         }
                     },
-                    None => {
+                    None => if action.queueable() {
+                        if let Some(event) = action.power_event() {
+                            let event = format!("{}, queued until DuoVero reconnects", event);
+                            let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                        }
+                        action_queue.push(action);
+                        let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                        let _ = callback.send(Ok(()));
+                    }
+                    else {
                         let error = anyhow::anyhow!("Could not execute {:?}: Fernbedienung is not connected.", action);
                         let _ = callback.send(Err(error));
                     }
                 },
+                Action::CancelQueuedAction(callback, id) => {
+                    let result = action_queue.cancel(id);
+                    let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                    let _ = callback.send(result);
+                },
                 Action::Subscribe(callback) => {
-                    /* note that upon subscribing all updates should be sent to ensure
-                       that new clients are in sync */
-                    if let Ok(_) = callback.send(updates_tx.subscribe()) {
-                        if let Some(addr) = fernbedienung_addr {
-                            let _ = updates_tx.send(Update::FernbedienungConnected(addr));
-                        }
+                    /* the current state is only sent to the new subscriber so that the other subscribers
+                       do not receive duplicate updates */
+                    let mut state = Vec::new();
+                    if let Some(addr) = fernbedienung_addr {
+                        state.push(Update::FernbedienungConnected(addr));
+                    }
+                    if let Some(addr) = reconnecting {
+                        state.push(Update::FernbedienungReconnecting(addr));
+                    }
+                    if let Some(reboot) = uptime_monitor.unexpected_reboot.clone() {
+                        state.push(Update::UnexpectedReboot(reboot));
+                    }
+                    if !watchdog_events.is_empty() {
+                        state.push(Update::Watchdog(watchdog_events.clone()));
                     }
+                    if let Some(firmware) = firmware.clone() {
+                        state.push(Update::Firmware(firmware));
+                    }
+                    if let Some(cameras) = cameras.clone() {
+                        state.push(Update::Cameras(cameras));
+                    }
+                    if !action_queue.is_empty() {
+                        state.push(Update::QueuedActions(action_queue.summary()));
+                    }
+                    state.push(Update::EventLog(event_log.entries()));
+                    let _ = callback.send((state, updates_tx.subscribe()));
                 },
-                Action::SetupExperiment(callback, id, software, journal) => match fernbedienung_tx.as_ref() {
+                Action::SetupExperiment(callback, id, software, journal, artifacts, environment, decimator) => match fernbedienung_tx.as_ref() {
                     Some(tx) => {
-                        let action = FernbedienungAction::SetupExperiment(id, software, journal);
-                        if let Err(mpsc::error::SendError((callback, _))) = tx.send((callback, action)).await {
+                        let action = FernbedienungAction::SetupExperiment(id, software, journal, artifacts, environment, decimator);
+                        if let Err(mpsc::error::SendError((callback, _, _))) = tx.send((callback, action, None)).await {
                             let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with Fernbedienung task")));
                         }
                     }
@@ -477,20 +942,28 @@ pub async fn new(mut action_rx: Receiver) {
                             .ok_or(anyhow::anyhow!("Fernbedienung is not connected"))?;
                         let (fernbedienung_callback_tx, fernbedienung_callback_rx) = oneshot::channel();
                         // as above with fernbedienung
-                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StartExperiment)).await
+                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StartExperiment, None)).await
                             .context("Could not communicate with Fernbedienung task")?;
                         fernbedienung_callback_rx.await
                             .context("Fernbedienung did not respond")??;
                         anyhow::Result::<()>::Ok(())
                     };
-                    let _ = callback.send(result.await.context("Could not start experiment"));
+                    let result = result.await.context("Could not start experiment");
+                    let entry = match &result {
+                        Ok(_) => event_log.record(Category::Experiment, "Experiment started"),
+                        Err(error) => event_log.record(Category::Error, format!("{:#}", error)),
+                    };
+                    let _ = updates_tx.send(Update::Event(entry));
+                    let _ = callback.send(result);
                 },
+                /* the trace is removed when the action is received */
+                Action::Traced(..) => {},
                 Action::StopExperiment => {
                     let terminate_argos = async {
                         let fernbedienung_tx = fernbedienung_tx.as_ref()
                             .ok_or(anyhow::anyhow!("Fernbedienung is not connected"))?;
                         let (fernbedienung_callback_tx, fernbedienung_callback_rx) = oneshot::channel();
-                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StopExperiment)).await
+                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StopExperiment, None)).await
                             .context("Fernbedienung is not available")?;
                         fernbedienung_callback_rx.await
                             .context("Fernbedienung did not respond")??;
@@ -499,13 +972,73 @@ pub async fn new(mut action_rx: Receiver) {
                     if let Err(error) = terminate_argos.await {
                         log::warn!("{}", error);
                     }
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Experiment, "Experiment stopped")));
                 },
             },
+            Some(version) = firmware_rx.recv() => {
+                firmware = Some(version.clone());
+                let _ = updates_tx.send(Update::Firmware(version));
+            },
+            Some(availability) = cameras_rx.recv() => {
+                cameras = Some(availability.clone());
+                let _ = updates_tx.send(Update::Cameras(availability));
+            },
+            Some(events) = watchdog_rx.recv() => {
+                for event in &events {
+                    let event = format!("Watchdog: {}", event);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Error, event)));
+                }
+                watchdog_events = events.clone();
+                let _ = updates_tx.send(Update::Watchdog(events));
+            },
+            Some(uptime) = uptime_rx.recv() => {
+                if let Some(reboot) = uptime_monitor.update(uptime) {
+                    if let Some(addr) = fernbedienung_addr {
+                        log::warn!("Robot at {}: {}", addr, reboot);
+                    }
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Error, reboot.clone())));
+                    let _ = updates_tx.send(Update::UnexpectedReboot(reboot));
+                }
+                let association = uptime_monitor.association();
+                let _ = updates_tx.send(Update::Uptime { robot: uptime.as_secs(), association: association.as_secs() });
+            },
+            Ok(update) = task_updates_rx.recv() => {
+                let entry = match update {
+                    Update::ResourceLimitViolation(violation) =>
+                        Some(event_log.record(Category::Error, violation)),
+                    Update::Artifacts(Err(error)) =>
+                        Some(event_log.record(Category::Error, format!("Could not collect artifacts: {}", error))),
+                    Update::ExperimentSummary(summary) => Some(match summary.failed {
+                        true => event_log.record(Category::Error, format!("Experiment failed: {}", summary.exit)),
+                        false => event_log.record(Category::Experiment, format!("Experiment finished: {}", summary.exit)),
+                    }),
+                    _ => None,
+                };
+                if let Some(entry) = entry {
+                    let _ = updates_tx.send(Update::Event(entry));
+                }
+            },
+            _ = &mut reconnect_timeout => {
+                reconnecting = None;
+                reconnect_timeout.set(futures::future::pending().left_future());
+                let _ = updates_tx.send(Update::FernbedienungDisconnected);
+                let event = "DuoVero did not reconnect in time";
+                let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+            },
             _ = &mut fernbedienung_task => {
                 fernbedienung_tx = None;
-                fernbedienung_addr = None;
+                /* the robot is only reported as disconnected if it does not reconnect in time */
+                reconnecting = fernbedienung_addr.take();
+                reconnect_timeout.set(tokio::time::sleep(Duration::from_secs(heartbeat.reconnect)).right_future());
+                uptime_monitor.disassociate();
+                firmware = None;
+                cameras = None;
                 fernbedienung_task.set(futures::future::pending().left_future());
-                let _ = updates_tx.send(Update::FernbedienungDisconnected);
+                if let Some(addr) = reconnecting {
+                    let _ = updates_tx.send(Update::FernbedienungReconnecting(addr));
+                    let event = format!("Lost connection to DuoVero at {}, waiting for it to reconnect", addr);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+                }
             },
         }
     }
@@ -0,0 +1,49 @@
+# Switches the Pixhawk into position control mode (the off-board failsafe) via the serial port of the
+# Up Core. The supervisor runs this script when the Xbee is not available to disable autonomous mode.
+# The serial port can only be used once ARGoS has exited and no MAVLink terminal has it open, and the
+# mode is verified from the heartbeats of the Pixhawk since the acknowledgement of the command alone
+# does not tell whether the Pixhawk has switched
+import os, sys, time
+from pymavlink import mavutil
+
+# the main mode of PX4 is stored in bits 16 to 23 of the custom mode in the heartbeat
+PX4_CUSTOM_MAIN_MODE_POSCTL = 3
+# how long to wait (seconds) for the serial port to be released and for the mode to change
+RELEASE_TIMEOUT = 10
+MODE_TIMEOUT = 5
+
+def in_use(path):
+    # whether another process, e.g., ARGoS or a MAVLink terminal, has the serial port open
+    for pid in filter(str.isdigit, os.listdir('/proc')):
+        if int(pid) == os.getpid():
+            continue
+        try:
+            fds = os.listdir('/proc/{}/fd'.format(pid))
+        except OSError:
+            continue
+        for fd in fds:
+            try:
+                if os.readlink('/proc/{}/fd/{}'.format(pid, fd)) == path:
+                    return True
+            except OSError:
+                pass
+    return False
+
+device, baud = sys.argv[1].rsplit(':', 1)
+deadline = time.time() + RELEASE_TIMEOUT
+while in_use(os.path.realpath(device)):
+    if time.time() > deadline:
+        sys.exit('The serial port of the Pixhawk is still in use')
+    time.sleep(0.1)
+connection = mavutil.mavlink_connection(device, baud=int(baud), source_system=255)
+if connection.wait_heartbeat(timeout=5) is None:
+    sys.exit('No heartbeat from the Pixhawk')
+deadline = time.time() + MODE_TIMEOUT
+while True:
+    connection.set_mode('POSCTL')
+    heartbeat = connection.recv_match(type='HEARTBEAT', blocking=True, timeout=1)
+    if heartbeat is not None and heartbeat.get_srcComponent() == mavutil.mavlink.MAV_COMP_ID_AUTOPILOT1 and \
+        (heartbeat.custom_mode >> 16) & 0xff == PX4_CUSTOM_MAIN_MODE_POSCTL:
+        break
+    if time.time() > deadline:
+        sys.exit('The Pixhawk did not switch to position control mode')
@@ -0,0 +1,59 @@
+# Smoke test of the drone that is run by the supervisor before an experiment. THE PROPELLERS MUST BE
+# REMOVED before running this test since each motor is spun briefly at idle throttle using the motor test
+# command of the Pixhawk. The response of a motor is measured from the output of the Pixhawk and from the
+# current drawn from the battery. Each check is written to standard output as
+# SMOKE_TEST<tab>PASS|FAIL<tab>check<tab>measured value
+import sys, time
+from pymavlink import mavutil
+
+MOTORS = 4
+# the throttle (percent) and duration (seconds) of the test of each motor
+THROTTLE = 5
+DURATION = 1.5
+# the PWM value above which a motor is considered to be driven
+IDLE_PWM = 1050
+
+def report(passed, check, value):
+    print('SMOKE_TEST\t{}\t{}\t{}'.format('PASS' if passed else 'FAIL', check, value), flush=True)
+
+device, baud = sys.argv[1].rsplit(':', 1)
+connection = mavutil.mavlink_connection(device, baud=int(baud), source_system=255)
+heartbeat = connection.wait_heartbeat(timeout=5)
+if heartbeat is None:
+    report(False, 'Pixhawk', 'no heartbeat')
+    sys.exit()
+armed = heartbeat.base_mode & mavutil.mavlink.MAV_MODE_FLAG_SAFETY_ARMED
+report(not armed, 'Pixhawk', 'armed' if armed else 'disarmed')
+if armed:
+    sys.exit()
+
+def measure(motor, duration):
+    # returns the highest output and the highest current (A) while the motor is tested
+    pwm, current = 0, None
+    deadline = time.time() + duration
+    while time.time() < deadline:
+        message = connection.recv_match(type=['SERVO_OUTPUT_RAW', 'SYS_STATUS'], blocking=True, timeout=0.1)
+        if message is None:
+            continue
+        if message.get_type() == 'SERVO_OUTPUT_RAW':
+            pwm = max(pwm, getattr(message, 'servo{}_raw'.format(motor)))
+        elif message.current_battery >= 0:
+            current = max(current or 0, message.current_battery / 100)
+    return pwm, current
+
+for motor in range(1, MOTORS + 1):
+    connection.mav.command_long_send(connection.target_system, connection.target_component,
+        mavutil.mavlink.MAV_CMD_DO_MOTOR_TEST, 0,
+        motor, mavutil.mavlink.MOTOR_TEST_THROTTLE_PERCENT, THROTTLE, DURATION, 1,
+        mavutil.mavlink.MOTOR_TEST_ORDER_BOARD, 0)
+    ack = connection.recv_match(type='COMMAND_ACK', blocking=True, timeout=3)
+    if ack is None or ack.result != mavutil.mavlink.MAV_RESULT_ACCEPTED:
+        report(False, 'motor {}'.format(motor), 'motor test rejected')
+        continue
+    pwm, current = measure(motor, DURATION)
+    value = 'PWM {}'.format(pwm)
+    if current is not None:
+        value += ', {:.2f} A'.format(current)
+    report(pwm > IDLE_PWM, 'motor {}'.format(motor), value)
+    # let the motor spin down before testing the next one
+    time.sleep(0.5)
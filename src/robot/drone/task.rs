@@ -1,20 +1,21 @@
-use std::{collections::HashMap, net::SocketAddr, sync::atomic::{AtomicU8, Ordering}, time::Duration};
+use std::{collections::{BTreeMap, HashMap}, net::SocketAddr, sync::{Arc, atomic::{AtomicU64, AtomicU8, Ordering}}, time::{Duration, Instant}};
 use anyhow::Context;
 use ansi_parser::{Output, AnsiParser};
 use bytes::BytesMut;
 use mavlink::{MavHeader, common::{self, MavMessage, SerialControlDev, SerialControlFlag}, error::MessageReadError};
-use tokio::{net::{TcpStream, UdpSocket}, sync::{broadcast, mpsc, oneshot}};
+use tokio::{net::{TcpStream, UdpSocket}, sync::{broadcast, mpsc, oneshot, watch}};
 use futures::{FutureExt, Sink, SinkExt, Stream, StreamExt, TryStreamExt};
 use tokio_stream::{self, wrappers::ReceiverStream};
 use tokio_util::{codec::Framed, sync::PollSender};
 
-use crate::network::{fernbedienung, fernbedienung_ext::MjpegStreamerStream, xbee};
-use crate::robot::{FernbedienungAction, XbeeAction, TerminalAction};
-use crate::journal;
+use crate::network::{fernbedienung, fernbedienung_ext::{Artifacts, BandwidthTest, CrashDumps, Leftovers, MjpegStreamerStream, ResourceLimits, SmokeTest, Watchdog, WorkingDirectory}, xbee};
+use crate::robot::{ActionQueue, ArgosOptions, Decimator, EventLog, FernbedienungAction, StatusLeds, TerminalInput, TERMINAL_INPUT_INTERVAL, MAVLINK_TERMINAL_INPUT_MAX_SIZE, XbeeAction, TerminalAction, UptimeMonitor, Polling, LINK_STRENGTH_INTERVAL, fernbedienung_heartbeat, fernbedienung_uptime_stream, reduced_polling};
+use crate::{journal, trace};
+use shared::{camera, configuration::Heartbeat, event_log::Category, experiment::{Energy, Summary, TimelineCategory}};
 use super::codec;
 
 pub use shared::{
-    drone::{Descriptor, Update},
+    drone::{CELL_IMBALANCE_THRESHOLD_MV, ControlPath, Descriptor, FlightMode, FlightState, MavlinkCommand, MavlinkTemplate, Update, XbeeAudit, XbeeParameter, XBEE_AUDIT_PARAMETERS, cell_imbalance},
     experiment::software::Software
 };
 
@@ -22,6 +23,7 @@ const IDENTIFY_DRONE_ARGOS: (&'static str, &'static [u8]) =
     ("identify_drone.argos", include_bytes!("identify_drone.argos"));
 const IDENTIFY_DRONE_LUA: (&'static str, &'static [u8]) = 
     ("identify_drone.lua", include_bytes!("identify_drone.lua"));
+pub const STATUS_LEDS_PY: &'static str = include_str!("status_leds.py");
 
 const DRONE_BATT_FULL_MV: f32 = 4050.0;
 const DRONE_BATT_EMPTY_MV: f32 = 3500.0;
@@ -34,6 +36,13 @@ const DRONE_CAMERAS_CONFIG: &[(&str, u16, u16, u16)] = &[
 ];
 
 const PIXHAWK_PORT: &'static str = "/dev/ttyS1:921600";
+/* the system and component identifiers of the Pixhawk */
+const PIXHAWK_SYSTEM_ID: u8 = 1;
+const PIXHAWK_COMPONENT_ID: u8 = 1;
+/* the time after which a command that has not been acknowledged by the Pixhawk is considered to have failed */
+const PIXHAWK_ACK_TIMEOUT: Duration = Duration::from_secs(3);
+const PIXHAWK_FAILSAFE_PY: &'static str = include_str!("pixhawk_failsafe.py");
+const SMOKE_TEST_PY: &'static str = include_str!("smoke_test.py");
 
 const XBEE_DEFAULT_PIN_CONFIG: &[(xbee::Pin, xbee::PinMode)] = &[
     /* UART pins: TX: DOUT, RTS: DIO6, RX: DIN, CTS: DIO7 */
@@ -55,18 +64,58 @@ const XBEE_DEFAULT_PIN_CONFIG: &[(xbee::Pin, xbee::PinMode)] = &[
 
 #[derive(Debug)]
 pub enum Action {
-    AssociateFernbedienung(fernbedienung::Device),
-    AssociateXbee(xbee::Device),
+    /* the device is returned if the robot is associated with another device at a different address */
+    AssociateFernbedienung(fernbedienung::Device, oneshot::Sender<Result<(), fernbedienung::Device>>),
+    AssociateXbee(xbee::Device, oneshot::Sender<Result<(), xbee::Device>>),
     ExecuteXbeeAction(oneshot::Sender<anyhow::Result<()>>, XbeeAction),
     ExecuteFernbedienungAction(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction),
-    Subscribe(oneshot::Sender<broadcast::Receiver<Update>>),
+    CancelQueuedAction(oneshot::Sender<anyhow::Result<()>>, u64),
+    /* subscribe to the updates, the current state of the robot is returned as a sequence of updates */
+    Subscribe(oneshot::Sender<(Vec<Update>, broadcast::Receiver<Update>)>),
     // its good to keep this one seperate since start exp need to interact with xbee and fernbedienung
-    SetupExperiment(oneshot::Sender<anyhow::Result<()>>, String, Software, mpsc::Sender<journal::Action>),
+    SetupExperiment(oneshot::Sender<anyhow::Result<()>>, String, Software, mpsc::Sender<journal::Action>, Option<Artifacts>, BTreeMap<String, String>, Decimator),
     StartExperiment(oneshot::Sender<anyhow::Result<()>>),
     StopExperiment,
+    /* an update from a journal that is being replayed, which is sent to the subscribers as is */
+    Replay(Update),
+    /* an action that was caused by a request from the user interface and the trace of that request */
+    Traced(trace::Handle, Box<Action>),
 }
 
-pub type Sender = mpsc::Sender<Action>;
+impl Action {
+    /// Attaches the trace of a request to the action, if the action was caused by a request
+    pub fn traced(self, trace: Option<trace::Handle>) -> Self {
+        match trace {
+            Some(trace) => Action::Traced(trace, Box::new(self)),
+            None => self,
+        }
+    }
+
+    /// Removes the trace from an action, the receipt and the completion of the action are recorded in the trace,
+    /// which is returned so that the requests to the fernbedienung service can be traced
+    fn untrace(self) -> (Self, Option<trace::Handle>) {
+        match self {
+            Action::Traced(trace, action) => match *action {
+                    Action::ExecuteXbeeAction(callback, action) => {
+                        trace::record(&trace, "drone", format!("Received {:?}", action));
+                        (Action::ExecuteXbeeAction(trace::callback(Some(trace.clone()), "drone".to_owned(), callback), action), Some(trace))
+                    },
+                    Action::ExecuteFernbedienungAction(callback, action) => {
+                        trace::record(&trace, "drone", format!("Received {:?}", action));
+                        (Action::ExecuteFernbedienungAction(trace::callback(Some(trace.clone()), "drone".to_owned(), callback), action), Some(trace))
+                    },
+                    Action::CancelQueuedAction(callback, queued) => {
+                        trace::record(&trace, "drone", format!("Received cancellation of queued action {}", queued));
+                        (Action::CancelQueuedAction(trace::callback(Some(trace.clone()), "drone".to_owned(), callback), queued), Some(trace))
+                    },
+                    action => action.untrace(),
+                },
+            action => (action, None),
+        }
+    }
+}
+
+pub type Sender = crate::robot::Outbound<Action>;
 pub type Receiver = mpsc::Receiver<Action>;
 
 async fn mavlink<'dev>(
@@ -104,7 +153,8 @@ async fn mavlink<'dev>(
 }
 
 fn xbee_pin_states_stream<'dev>(
-    device: &'dev xbee::Device
+    device: &'dev xbee::Device,
+    mut polling_rx: watch::Receiver<Polling>,
 ) -> impl Stream<Item = anyhow::Result<HashMap<xbee::Pin, bool>>> + 'dev {
     async_stream::stream! {
         let mut attempts: u8 = 0;
@@ -116,6 +166,7 @@ fn xbee_pin_states_stream<'dev>(
                 Ok(response) => {
                     attempts = 0;
                     yield Ok(response);
+                    reduced_polling(&mut polling_rx, LINK_STRENGTH_INTERVAL, |factors| factors.pin_states).await;
                 },
                 Err(error) => match attempts {
                     0..=4 => attempts += 1,
@@ -127,7 +178,8 @@ fn xbee_pin_states_stream<'dev>(
 }
 
 fn xbee_link_margin_stream<'dev>(
-    device: &'dev xbee::Device
+    device: &'dev xbee::Device,
+    mut polling_rx: watch::Receiver<Polling>,
 ) -> impl Stream<Item = anyhow::Result<i32>> + 'dev {
     async_stream::stream! {
         let mut attempts: u8 = 0;
@@ -139,6 +191,7 @@ fn xbee_link_margin_stream<'dev>(
                 Ok(response) => {
                     attempts = 0;
                     yield Ok(response);
+                    reduced_polling(&mut polling_rx, LINK_STRENGTH_INTERVAL, |factors| factors.link_strength).await;
                 },
                 Err(error) => match attempts {
                     0..=4 => attempts += 1,
@@ -152,8 +205,16 @@ fn xbee_link_margin_stream<'dev>(
 async fn xbee(
     device: xbee::Device,
     mut rx: mpsc::Receiver<(oneshot::Sender<anyhow::Result<()>>, XbeeAction)>,
-    updates_tx: broadcast::Sender<Update>
+    updates_tx: broadcast::Sender<Update>,
+    battery_cells_tx: mpsc::Sender<Vec<u16>>,
+    polling_rx: watch::Receiver<Polling>,
+    configuration: Option<Vec<(String, String)>>,
 ) -> anyhow::Result<()> {
+    /* the profile of the hardware revision of the drone replaces the default pin configuration */
+    let profile = configuration.as_ref()
+        .map(xbee::profile)
+        .transpose()
+        .context("Could not parse Xbee configuration")?;
     /* autonomous mode: this variable tracks whether or not we are in autonomous mode */
     let mut autonomous_mode = false;
     /* mavlink sink and stream */
@@ -161,40 +222,44 @@ async fn xbee(
         .context("Could not connect to MAVLink")?
         .split();
     /* link margin stream */
-    let link_margin_stream = xbee_link_margin_stream(&device);
+    let link_margin_stream = xbee_link_margin_stream(&device, polling_rx.clone());
     let link_margin_stream_throttled =
-        tokio_stream::StreamExt::throttle(link_margin_stream, Duration::from_millis(1000));
+        tokio_stream::StreamExt::throttle(link_margin_stream, LINK_STRENGTH_INTERVAL);
     tokio::pin!(link_margin_stream_throttled);
     /* pin states stream */
-    let pin_states_stream = xbee_pin_states_stream(&device);
+    let pin_states_stream = xbee_pin_states_stream(&device, polling_rx);
     let pin_states_stream_throttled =
-        tokio_stream::StreamExt::throttle(pin_states_stream, Duration::from_millis(1000));
+        tokio_stream::StreamExt::throttle(pin_states_stream, LINK_STRENGTH_INTERVAL);
     tokio::pin!(pin_states_stream_throttled);
     /* since we may be just reconnecting to the xbee, do not turn off the upcore and
        pixhawk power if they are currently switched on */
+    let mut switched_on = Vec::new();
     if let Some(Ok(pin_states)) = pin_states_stream_throttled.next().await {
         /* initialise autonomous mode based on current pin states */
         autonomous_mode =
             pin_states.get(&xbee::Pin::DIO4).cloned().unwrap_or_default();
-        let upcore_power =
-            pin_states.get(&xbee::Pin::DIO11).cloned().unwrap_or_default();
-        let pixhawk_power =
-            pin_states.get(&xbee::Pin::DIO12).cloned().unwrap_or_default();
-        let pin_modes = XBEE_DEFAULT_PIN_CONFIG.iter()
-            .filter(|&(pin, _)| match pin {
-                /* if a pin is already set to true, then it should be removed from
-                   the default pin configuration */
-                xbee::Pin::DIO4 => !autonomous_mode,
-                xbee::Pin::DIO11 => !upcore_power,
-                xbee::Pin::DIO12 => !pixhawk_power,
-                _ => true,
-            });
-        device.set_pin_modes(pin_modes).await
-            .context("Could not set Xbee pin modes")?;
+        /* if a pin is already set to true, then it should be removed from
+           the pin configuration */
+        switched_on.extend([xbee::Pin::DIO4, xbee::Pin::DIO11, xbee::Pin::DIO12].iter()
+            .filter(|&pin| pin_states.get(pin).cloned().unwrap_or_default()));
     }
-    else {
-        device.set_pin_modes(XBEE_DEFAULT_PIN_CONFIG.into_iter()).await
-            .context("Could not set Xbee pin modes")?;
+    match profile {
+        Some(profile) => {
+            let switched_on = switched_on.into_iter()
+                .map(<[u8; 2]>::from)
+                .collect::<Vec<_>>();
+            let profile = profile.into_iter()
+                .filter(|(parameter, _)| !switched_on.contains(parameter))
+                .collect::<Vec<_>>();
+            device.apply_profile(&profile).await
+                .context("Could not apply Xbee configuration")?;
+        },
+        None => {
+            let pin_modes = XBEE_DEFAULT_PIN_CONFIG.iter()
+                .filter(|(pin, _)| !switched_on.contains(pin));
+            device.set_pin_modes(pin_modes).await
+                .context("Could not set Xbee pin modes")?;
+        }
     }
     /* mavlink heartbeat stream */
     let mavlink_heartbeat_stream = futures::stream::iter(std::iter::repeat(
@@ -210,6 +275,11 @@ async fn xbee(
     let mavlink_heartbeat_stream_throttled =
         tokio_stream::StreamExt::throttle(mavlink_heartbeat_stream, Duration::from_millis(500));
     tokio::pin!(mavlink_heartbeat_stream_throttled);
+    /* the state of the Pixhawk as reported by its last heartbeat */
+    let mut flight_state: Option<FlightState> = None;
+    /* the commands that await an acknowledgement from the Pixhawk and the times at which they were sent */
+    let mut pending_acks: Vec<(common::MavCmd, oneshot::Sender<anyhow::Result<()>>, Instant)> = Vec::new();
+    let mut ack_timeouts = tokio::time::interval(Duration::from_secs(1));
     /* poll all streams, sinks, channels, and futures */
     loop {
         tokio::select! {
@@ -219,14 +289,40 @@ async fn xbee(
                     let _ = mavlink_sink.send(heartbeat).await;
                 }
             },
-            Some(Ok((_header, body))) = mavlink_stream.next() => match body {
+            Some(Ok((header, body))) = mavlink_stream.next() => match body {
+                /* heartbeats from other components, e.g., a companion computer, do not have an autopilot */
+                MavMessage::HEARTBEAT(data) if header.system_id == PIXHAWK_SYSTEM_ID &&
+                    data.autopilot != common::MavAutopilot::MAV_AUTOPILOT_INVALID => {
+                    let state = FlightState {
+                        armed: data.base_mode.contains(common::MavModeFlag::MAV_MODE_FLAG_SAFETY_ARMED),
+                        mode: match data.base_mode.contains(common::MavModeFlag::MAV_MODE_FLAG_CUSTOM_MODE_ENABLED) {
+                            true => FlightMode::from_custom_mode(data.custom_mode),
+                            false => None,
+                        },
+                    };
+                    if flight_state.as_ref() != Some(&state) {
+                        log::info!("Pixhawk is {} in mode {}", if state.armed { "armed" } else { "disarmed" },
+                            state.mode.map_or("unknown", |mode| mode.name()));
+                    }
+                    let _ = updates_tx.send(Update::FlightState(state.clone()));
+                    flight_state = Some(state);
+                },
                 MavMessage::BATTERY_STATUS(data) => {
-                    let mut battery_reading = data.voltages[0] as f32;
+                    /* unused elements are set to u16::MAX, if the Pixhawk does not monitor the individual
+                       cells, the first element contains the voltage of the whole battery */
+                    let cells = data.voltages.iter()
+                        .copied()
+                        .take_while(|&voltage| voltage != u16::MAX)
+                        .collect::<Vec<_>>();
+                    let mut battery_reading = cells.iter().map(|&voltage| voltage as f32).sum::<f32>();
                     battery_reading /= DRONE_BATT_NUM_CELLS;
                     battery_reading -= DRONE_BATT_EMPTY_MV;
                     battery_reading /= DRONE_BATT_FULL_MV - DRONE_BATT_EMPTY_MV;
                     let battery_reading = (battery_reading.max(0.0).min(1.0) * 100.0) as i32;
                     let _ = updates_tx.send(Update::Battery(battery_reading));
+                    if cells.len() > 1 {
+                        let _ = battery_cells_tx.send(cells).await;
+                    }
                 },
                 MavMessage::SERIAL_CONTROL(common::SERIAL_CONTROL_DATA { data, count, .. }) => {
                     let data = match std::str::from_utf8(&data[..count as usize]) {
@@ -243,9 +339,28 @@ async fn xbee(
                         });
                     let _  = updates_tx.send(Update::Mavlink(parsed));
                 },
+                MavMessage::COMMAND_ACK(data) => {
+                    if let Some(index) = pending_acks.iter().position(|(command, ..)| *command == data.command) {
+                        let (_, callback, _) = pending_acks.remove(index);
+                        let result = match data.result {
+                            common::MavResult::MAV_RESULT_ACCEPTED => Ok(()),
+                            result => Err(anyhow::anyhow!("The Pixhawk rejected {:?} with {:?}", data.command, result)),
+                        };
+                        let _ = callback.send(result);
+                    }
+                    let _ = updates_tx.send(Update::MavlinkResponse(format!("{:?}", data)));
+                },
                 /* ignore other MAVLink messages */
                 _ => {}
             },
+            _ = ack_timeouts.tick() => {
+                let (expired, pending) = pending_acks.into_iter()
+                    .partition::<Vec<_>, _>(|(_, _, sent)| sent.elapsed() > PIXHAWK_ACK_TIMEOUT);
+                pending_acks = pending;
+                for (command, callback, _) in expired {
+                    let _ = callback.send(Err(anyhow::anyhow!("The Pixhawk did not acknowledge {:?}", command)));
+                }
+            },
             Some(response) = link_margin_stream_throttled.next() => {
                 let update = Update::XbeeSignal(response?);
                 let _ = updates_tx.send(update);
@@ -306,6 +421,11 @@ async fn xbee(
                                         .map_err(|_| anyhow::anyhow!("Could not start MAVLink terminal"));
                                     let _ = callback.send(result);
                                 },
+                                /* a SERIAL_CONTROL message carries at most 70 bytes, including the line feed */
+                                TerminalAction::Run(command) if command.len() + 1 > MAVLINK_TERMINAL_INPUT_MAX_SIZE => {
+                                    let _ = callback.send(Err(anyhow::anyhow!("Command exceeds the {} byte limit of the MAVLink terminal",
+                                        MAVLINK_TERMINAL_INPUT_MAX_SIZE - 1)));
+                                },
                                 TerminalAction::Run(command) => {
                                     let mut command_padded = command.as_bytes().to_vec();
                                     command_padded.push(0x0a); // add a line feed to the command
@@ -329,7 +449,77 @@ async fn xbee(
                                 },
                             }
                         }
-                    }
+                    },
+                    XbeeAction::MavlinkCommand(command) => {
+                        let result = match autonomous_mode {
+                            true => Err(anyhow::anyhow!("MAVLink console is not available in autonomous mode")),
+                            false => match mavlink_console_message(&command) {
+                                Ok(message) => mavlink_sink.send(message).await
+                                    .map_err(|_| anyhow::anyhow!("Could not send {}", command.template.name())),
+                                Err(error) => Err(error),
+                            }
+                        };
+                        let _ = callback.send(result);
+                    },
+                    XbeeAction::Arm(arm) => {
+                        let command = MavlinkCommand {
+                            template: MavlinkTemplate::ComponentArmDisarm,
+                            params: vec![arm as u8 as f32],
+                        };
+                        let result = match flight_command(&command, autonomous_mode, flight_state.as_ref()) {
+                            Ok(message) => mavlink_sink.send(message).await
+                                .map_err(|_| anyhow::anyhow!("Could not send {}", command.template.name())),
+                            Err(error) => Err(error),
+                        };
+                        match result {
+                            Ok(_) => pending_acks.push((common::MavCmd::MAV_CMD_COMPONENT_ARM_DISARM, callback, Instant::now())),
+                            Err(error) => {
+                                let _ = callback.send(Err(error));
+                            }
+                        }
+                    },
+                    XbeeAction::SetFlightMode(mode) => {
+                        let (main_mode, sub_mode) = mode.px4_mode();
+                        let command = MavlinkCommand {
+                            template: MavlinkTemplate::DoSetMode,
+                            /* the base mode only enables the custom mode, which selects the mode of PX4 */
+                            params: vec![common::MavModeFlag::MAV_MODE_FLAG_CUSTOM_MODE_ENABLED.bits() as f32,
+                                main_mode as f32, sub_mode as f32],
+                        };
+                        let result = match flight_command(&command, autonomous_mode, flight_state.as_ref()) {
+                            Ok(message) => mavlink_sink.send(message).await
+                                .map_err(|_| anyhow::anyhow!("Could not send {}", command.template.name())),
+                            Err(error) => Err(error),
+                        };
+                        match result {
+                            Ok(_) => pending_acks.push((common::MavCmd::MAV_CMD_DO_SET_MODE, callback, Instant::now())),
+                            Err(error) => {
+                                let _ = callback.send(Err(error));
+                            }
+                        }
+                    },
+                    XbeeAction::Audit(profile) => {
+                        let audit = xbee_audit(&device, &profile).await;
+                        let discrepancies = audit.discrepancies()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>();
+                        let _ = updates_tx.send(Update::XbeeAudit(audit));
+                        let result = match discrepancies.len() {
+                            0 => Ok(()),
+                            _ => Err(anyhow::anyhow!("{}", discrepancies.join(", "))),
+                        };
+                        let _ = callback.send(result);
+                    },
+                    XbeeAction::VerifyAutonomousModeDisabled => {
+                        let result = match device.pin_states().await {
+                            Ok(pin_states) => match pin_states.get(&xbee::Pin::DIO4).cloned().unwrap_or_default() {
+                                true => Err(anyhow::anyhow!("The autonomous mode is still enabled")),
+                                false => Ok(()),
+                            },
+                            Err(error) => Err(error).context("Could not read the pin states of the Xbee"),
+                        };
+                        let _ = callback.send(result);
+                    },
                 },
                 None => break Ok(()), // normal shutdown
             },
@@ -337,8 +527,102 @@ async fn xbee(
     }
 }
 
+/// Reads the firmware and hardware versions of the Xbee and the parameters in the profile
+async fn xbee_audit(device: &xbee::Device, profile: &BTreeMap<String, String>) -> XbeeAudit {
+    let names = XBEE_AUDIT_PARAMETERS.iter()
+        .map(|&name| name.to_owned())
+        .chain(profile.keys()
+            .filter(|name| !XBEE_AUDIT_PARAMETERS.contains(&name.as_str()))
+            .cloned())
+        .collect::<Vec<_>>();
+    let mut parameters = Vec::new();
+    for name in names {
+        let value = match name.as_bytes() {
+            &[first, second] => device.parameter([first, second]).await
+                .map(|value| {
+                    /* format the value as XCTU does, i.e., in hexadecimal without leading zeros */
+                    let value = value.iter().map(|byte| format!("{:02X}", byte)).collect::<String>();
+                    match value.trim_start_matches('0') {
+                        "" => "0".to_owned(),
+                        value => value.to_owned(),
+                    }
+                })
+                .map_err(|error| error.to_string()),
+            _ => Err("Invalid parameter".to_owned()),
+        };
+        parameters.push(XbeeParameter { expected: profile.get(&name).cloned(), name, value });
+    }
+    XbeeAudit { time: chrono::Local::now().to_rfc3339(), parameters }
+}
+
+/// Builds the message for arming or disarming the Pixhawk or for changing its flight mode. These commands are
+/// only sent while the Xbee is connected to the Pixhawk and once the Pixhawk has reported its state
+fn flight_command(command: &MavlinkCommand, autonomous_mode: bool, flight_state: Option<&FlightState>)
+    -> anyhow::Result<MavMessage> {
+    if autonomous_mode {
+        return Err(anyhow::anyhow!("The Pixhawk can not be commanded via the Xbee in autonomous mode"));
+    }
+    if flight_state.is_none() {
+        return Err(anyhow::anyhow!("The Pixhawk has not reported its state"));
+    }
+    mavlink_console_message(command)
+}
+
+/// Builds the message for a command from the MAVLink console after validating its parameters
+fn mavlink_console_message(command: &MavlinkCommand) -> anyhow::Result<MavMessage> {
+    command.template.validate(&command.params)
+        .map_err(|error| anyhow::anyhow!(error))?;
+    let param = |index: usize| command.params.get(index).copied().unwrap_or_default();
+    let command_long = |command: common::MavCmd| MavMessage::COMMAND_LONG(common::COMMAND_LONG_DATA {
+        param1: param(0),
+        param2: param(1),
+        param3: param(2),
+        param4: param(3),
+        param5: param(4),
+        param6: param(5),
+        param7: param(6),
+        command,
+        target_system: PIXHAWK_SYSTEM_ID,
+        target_component: PIXHAWK_COMPONENT_ID,
+        confirmation: 0,
+    });
+    let message = match command.template {
+        MavlinkTemplate::ComponentArmDisarm => command_long(common::MavCmd::MAV_CMD_COMPONENT_ARM_DISARM),
+        MavlinkTemplate::PreflightRebootShutdown => command_long(common::MavCmd::MAV_CMD_PREFLIGHT_REBOOT_SHUTDOWN),
+        MavlinkTemplate::PreflightCalibration => command_long(common::MavCmd::MAV_CMD_PREFLIGHT_CALIBRATION),
+        MavlinkTemplate::DoSetMode => command_long(common::MavCmd::MAV_CMD_DO_SET_MODE),
+        MavlinkTemplate::DoMotorTest => command_long(common::MavCmd::MAV_CMD_DO_MOTOR_TEST),
+        MavlinkTemplate::RequestMessage => command_long(common::MavCmd::MAV_CMD_REQUEST_MESSAGE),
+        MavlinkTemplate::SetMessageInterval => command_long(common::MavCmd::MAV_CMD_SET_MESSAGE_INTERVAL),
+        MavlinkTemplate::SetMode => {
+            /* the base mode of SET_MODE is restricted to the values of MAV_MODE */
+            let base_mode = match param(0) as u8 {
+                0 => common::MavMode::MAV_MODE_PREFLIGHT,
+                64 => common::MavMode::MAV_MODE_MANUAL_DISARMED,
+                66 => common::MavMode::MAV_MODE_TEST_DISARMED,
+                80 => common::MavMode::MAV_MODE_STABILIZE_DISARMED,
+                88 => common::MavMode::MAV_MODE_GUIDED_DISARMED,
+                92 => common::MavMode::MAV_MODE_AUTO_DISARMED,
+                192 => common::MavMode::MAV_MODE_MANUAL_ARMED,
+                194 => common::MavMode::MAV_MODE_TEST_ARMED,
+                208 => common::MavMode::MAV_MODE_STABILIZE_ARMED,
+                216 => common::MavMode::MAV_MODE_GUIDED_ARMED,
+                220 => common::MavMode::MAV_MODE_AUTO_ARMED,
+                base_mode => return Err(anyhow::anyhow!("Base mode {} is not a value of MAV_MODE", base_mode)),
+            };
+            MavMessage::SET_MODE(common::SET_MODE_DATA {
+                custom_mode: param(1) as u32,
+                target_system: PIXHAWK_SYSTEM_ID,
+                base_mode,
+            })
+        },
+    };
+    Ok(message)
+}
+
 fn fernbedienung_link_strength_stream<'dev>(
-    device: &'dev fernbedienung::Device
+    device: &'dev fernbedienung::Device,
+    mut polling_rx: watch::Receiver<Polling>,
 ) -> impl Stream<Item = anyhow::Result<i32>> + 'dev {
     async_stream::stream! {
         let mut attempts : u8 = 0;
@@ -350,6 +634,7 @@ fn fernbedienung_link_strength_stream<'dev>(
                 Ok(response) => {
                     attempts = 0;
                     yield Ok(response);
+                    reduced_polling(&mut polling_rx, LINK_STRENGTH_INTERVAL, |factors| factors.link_strength).await;
                 },
                 Err(error) => match attempts {
                     0..=4 => attempts += 1,
@@ -370,9 +655,13 @@ async fn bash(
     let stderr = futures::stream::pending().left_stream();
     let mut stdin = None;
     let mut terminate = None;
+    /* input is sent to the terminal in paced chunks to avoid flooding the connection to the robot */
+    let mut input = TerminalInput::default();
+    let pacing = tokio::time::sleep(Duration::default());
     tokio::pin!(process);
     tokio::pin!(stdout);
     tokio::pin!(stderr);
+    tokio::pin!(pacing);
     loop {
         tokio::select! {
             Some((callback, action)) = rx.recv() => match action {
@@ -391,31 +680,52 @@ async fn bash(
                         target: "bash".into(),
                         working_dir: None,
                         args: vec!["-li".to_owned()],
+                        environment: Default::default(),
                     };
-                    process.set(device.run(bash, terminate_rx, stdin_rx, stdout_tx, stderr_tx).right_future());
+                    process.set(device.run_bulk(bash, terminate_rx, stdin_rx, stdout_tx, stderr_tx).right_future());
                     let _ = callback.send(Ok(()));
                 },
-                TerminalAction::Run(mut command) => if let Some(tx) = stdin.as_ref() {
+                TerminalAction::Run(mut command) => if stdin.is_some() {
                     command.push_str("\r");
-                    let result = tx.send(BytesMut::from(command.as_bytes())).await
-                        .map_err(|_| {
-                            /* remove the "\r" before including the command in the error message */
-                            command.pop();
-                            anyhow::anyhow!("Could not send \"{}\" to Bash terminal", command)
-                        });
-                    let _ = callback.send(result);
+                    let _ = callback.send(input.push(&command));
                 },
                 TerminalAction::Stop => if let Some(tx) = terminate.take() {
                     let _ = tx.send(());
+                    if !input.is_empty() {
+                        input.clear();
+                        if let Some((sent, total)) = input.progress() {
+                            let _ = updates_tx.send(Update::BashProgress { sent, total });
+                        }
+                    }
                     let _ = callback.send(Ok(()));
                 }
             },
+            _ = &mut pacing, if !input.is_empty() => {
+                pacing.as_mut().reset(tokio::time::Instant::now() + TERMINAL_INPUT_INTERVAL);
+                match (stdin.as_ref(), input.next()) {
+                    (Some(tx), Some(chunk)) => match tx.try_send(chunk) {
+                        Ok(_) => {},
+                        Err(mpsc::error::TrySendError::Full(chunk)) => input.retry(chunk),
+                        Err(mpsc::error::TrySendError::Closed(_)) => input.clear(),
+                    },
+                    _ => input.clear(),
+                }
+                if let Some((sent, total)) = input.progress() {
+                    let _ = updates_tx.send(Update::BashProgress { sent, total });
+                }
+            },
             result = &mut process => {
                 process.set(futures::future::pending().left_future());
                 stdout.set(futures::stream::pending().left_stream());
                 stderr.set(futures::stream::pending().left_stream());
                 stdin = None;
                 terminate = None;
+                if !input.is_empty() {
+                    input.clear();
+                    if let Some((sent, total)) = input.progress() {
+                        let _ = updates_tx.send(Update::BashProgress { sent, total });
+                    }
+                }
                 log::info!("Remote Bash instance terminated with {:?}", result);
             }
             Some(stdout) = stdout.next() => {
@@ -431,6 +741,8 @@ async fn bash(
 }
 
 async fn argos(device: &fernbedienung::Device,
+    options: ArgosOptions,
+    updates_tx: broadcast::Sender<Update>,
     callback: oneshot::Sender<anyhow::Result<()>>,
     software: Software,
     id: impl Into<Option<String>>,
@@ -444,6 +756,7 @@ async fn argos(device: &fernbedienung::Device,
     let router_socket = router_socket.into();
     let pixhawk_port = pixhawk_port.into();
     let journal = journal.into();
+    let ArgosOptions { limits, artifacts, environment, mut decimator } = options;
     let wait_rx = wait_rx.into();
     /* create temp directory */
     let path = match device.create_temp_dir().await {
@@ -474,6 +787,12 @@ async fn argos(device: &fernbedienung::Device,
             }
         }
     }
+    /* core dumps are only retrieved during experiments, a failure here is not fatal */
+    if journal.is_some() {
+        if let Err(error) = CrashDumps::enable(device).await {
+            log::warn!("Could not enable core dumps: {:#}", error);
+        }
+    }
     if let Err(_) = callback.send(Ok(())) {
         /* abort if the callback was dropped before we
            could signal that we are ready */
@@ -500,9 +819,15 @@ async fn argos(device: &fernbedienung::Device,
     args.extend(pixhawk_port.into_iter().flat_map(|port| vec!["--pixhawk".to_owned(), port]));
     let process = fernbedienung::Process {
         target: "argos3".into(),
-        working_dir: Some(path.into()),
+        working_dir: Some(path.clone().into()),
         args,
+        environment,
     };
+    let process = CrashDumps::apply(limits.apply(device, process).await);
+    /* safety events and disconnects are recorded on the timeline of the experiment */
+    let timeline = journal.clone().zip(id.clone());
+    /* the amount of output from ARGoS is included in the summary of the experiment */
+    let output = Arc::new(AtomicU64::new(0));
     let (stdout_tx, mut forward_stdout, stderr_tx, mut forward_stderr) = match (journal, id) {
         (Some(journal), Some(id)) => {
             use journal::{ARGoS, Event, Action};
@@ -512,13 +837,29 @@ async fn argos(device: &fernbedienung::Device,
             let stderr_stream = ReceiverStream::new(stderr_rx);
             let journal_sink = PollSender::new(journal.clone());
             let stdout_robot_id = id.clone();
-            let forward_stdout = stdout_stream.map(move |data: BytesMut| 
-                Ok(Action::Record(Event::ARGoS(stdout_robot_id.clone(), ARGoS::StandardOutput(data)))))
-                    .forward(journal_sink).right_future();
+            let stdout_output = output.clone();
+            /* the output is also shown live on the card of the robot */
+            let stdout_updates_tx = updates_tx.clone();
+            let forward_stdout = stdout_stream.filter_map(move |data: BytesMut| {
+                stdout_output.fetch_add(data.len() as u64, Ordering::Relaxed);
+                let text = String::from_utf8_lossy(&data).into_owned();
+                let _ = stdout_updates_tx.send(Update::ArgosOutput { error: false, text });
+                /* the output is shown in full, but only the output that is kept by the decimation is journaled */
+                let data = decimator.apply(data);
+                futures::future::ready(match data.is_empty() {
+                    true => None,
+                    false => Some(Ok(Action::Record(Event::ARGoS(stdout_robot_id.clone(), ARGoS::StandardOutput(data))))),
+                })
+            }).forward(journal_sink).right_future();
             let journal_sink = PollSender::new(journal);
-            let forward_stderr = stderr_stream.map(move |data: BytesMut| 
-                Ok(Action::Record(Event::ARGoS(id.clone(), ARGoS::StandardError(data)))))
-                    .forward(journal_sink).right_future();
+            let stderr_output = output.clone();
+            let stderr_updates_tx = updates_tx.clone();
+            let forward_stderr = stderr_stream.map(move |data: BytesMut| {
+                stderr_output.fetch_add(data.len() as u64, Ordering::Relaxed);
+                let text = String::from_utf8_lossy(&data).into_owned();
+                let _ = stderr_updates_tx.send(Update::ArgosOutput { error: true, text });
+                Ok(Action::Record(Event::ARGoS(id.clone(), ARGoS::StandardError(data))))
+            }).forward(journal_sink).right_future();
             (Some(stdout_tx), forward_stdout, Some(stderr_tx), forward_stderr)
         },
         (_, _) => {
@@ -527,8 +868,12 @@ async fn argos(device: &fernbedienung::Device,
         }
     };
     let (terminate_tx, terminate_rx) = oneshot::channel();      
-    let argos = device.run(process, terminate_rx, None, stdout_tx, stderr_tx);
+    let argos = device.run_bulk(process, terminate_rx, None, stdout_tx, stderr_tx);
     tokio::pin!(argos);
+    let mut summary = Summary::default();
+    let mut crashed = false;
+    /* the battery readings while ARGoS is running are used to estimate the energy that was used */
+    let mut battery_updates = updates_tx.subscribe();
     loop {
         tokio::select! {
             _ = &mut forward_stdout => {
@@ -539,21 +884,113 @@ async fn argos(device: &fernbedienung::Device,
                 /* disable while we wait for the other futures to finish */
                 forward_stderr = futures::future::pending().left_future();
             },
+            Ok(update) = battery_updates.recv() => match update {
+                Update::Battery(reading) => Energy::battery(&mut summary.energy, reading),
+                Update::BatteryCells(cells) => if let Some(energy) = summary.energy.as_mut() {
+                    energy.voltage(cells.iter().map(|&voltage| voltage as u32).sum());
+                },
+                _ => {},
+            },
             /* local shutdown */
             _ = &mut stop_rx => {
                 let _ = terminate_tx.send(());
+                /* give ARGoS time to exit so that the artifacts are complete before collecting them */
+                if artifacts.is_some() || timeline.is_some() {
+                    let _ = tokio::time::timeout(Duration::from_secs(5), &mut argos).await;
+                }
+                summary.exit = "Stopped by the supervisor".to_owned();
                 break;
             }
             /* argos finished */
-            _ = &mut argos => break,
+            result = &mut argos => {
+                summary.exit = match &result {
+                    Ok(_) => "Exited".to_owned(),
+                    Err(error) => error.to_string(),
+                };
+                summary.failed = result.is_err();
+                crashed = matches!(result, Err(fernbedienung::Error::AbnormalTerminationError));
+                let event = match result {
+                    Ok(_) => None,
+                    Err(fernbedienung::Error::AbnormalTerminationError) =>
+                        match limits.violation(device, "argos3").await {
+                            Some(violation) => {
+                                log::warn!("{}", violation);
+                                let _ = updates_tx.send(Update::ResourceLimitViolation(violation.clone()));
+                                Some((TimelineCategory::Safety, violation))
+                            },
+                            None => None,
+                        },
+                    Err(error) => Some((TimelineCategory::Disconnect, format!("Lost connection to ARGoS ({})", error))),
+                };
+                if let Some((_, description)) = &event {
+                    summary.events.push(description.clone());
+                }
+                if let (Some((journal_tx, id)), Some((category, description))) = (timeline.as_ref(), event) {
+                    let event = journal::Event::Timeline(category, format!("{}: {}", id, description), Vec::new());
+                    let _ = journal_tx.send(journal::Action::Record(event)).await;
+                }
+                break;
+            },
+        }
+    }
+    /* retrieve the backtrace from the core dump if ARGoS crashed during an experiment */
+    if let (true, Some((journal_tx, id))) = (crashed, timeline.as_ref()) {
+        /* the core dump is only kept if artifacts are collected from the robot */
+        let store = artifacts.as_ref().map(|_| (id.as_str(), journal_tx));
+        match CrashDumps::retrieve(device, &path, "argos3", store).await {
+            Ok(Some(backtrace)) => {
+                let event = journal::Event::ARGoS(id.clone(), journal::ARGoS::Backtrace(backtrace.clone()));
+                let _ = journal_tx.send(journal::Action::Record(event)).await;
+                summary.backtrace = Some(backtrace);
+            },
+            Ok(None) => {},
+            Err(error) => log::warn!("Could not retrieve crash dump: {:#}", error),
+        }
+    }
+    /* store the working directory next to the journal, since it is lost when the robot is rebooted */
+    if let Some((journal_tx, id)) = timeline.as_ref() {
+        match WorkingDirectory::retrieve(device, &path, id, journal_tx).await {
+            Ok(count) => log::info!("Retrieved {} files from the working directory of {}", count, id),
+            Err(error) => log::warn!("Could not retrieve the working directory of {}: {:#}", id, error),
+        }
+    }
+    /* collect the artifacts from the working directory */
+    if let (Some(artifacts), Some((journal_tx, id))) = (artifacts, timeline.as_ref()) {
+        let result = artifacts.collect(device, &path, id, journal_tx).await
+            .map_err(|error| format!("{:#}", error));
+        if let Err(error) = &result {
+            log::warn!("Could not collect artifacts: {}", error);
         }
+        let _ = updates_tx.send(Update::Artifacts(result));
+    }
+    /* summarize the experiment, this is not done when ARGoS is used to identify the robot */
+    if let Some((journal_tx, id)) = timeline {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        if let Ok(_) = journal_tx.send(journal::Action::MessageCount(device.addr.into(), callback_tx)).await {
+            summary.messages = callback_rx.await.unwrap_or(0);
+        }
+        summary.finished = chrono::Local::now().to_rfc3339();
+        summary.output = output.load(Ordering::Relaxed);
+        if let Some(energy) = summary.energy.clone() {
+            let event = journal::Event::Energy(id.clone(), energy);
+            let _ = journal_tx.send(journal::Action::Record(event)).await;
+        }
+        let _ = updates_tx.send(Update::ExperimentSummary(summary));
     }
 }
 
 async fn fernbedienung(
     device: fernbedienung::Device,
-    mut rx: mpsc::Receiver<(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction)>,
-    updates_tx: broadcast::Sender<Update>
+    limits: ResourceLimits,
+    watchdog: Option<Watchdog>,
+    mut rx: mpsc::Receiver<(oneshot::Sender<anyhow::Result<()>>, FernbedienungAction, Option<trace::Handle>)>,
+    updates_tx: broadcast::Sender<Update>,
+    uptime_tx: mpsc::Sender<Duration>,
+    firmware_tx: mpsc::Sender<String>,
+    cameras_tx: mpsc::Sender<camera::Availability>,
+    watchdog_tx: mpsc::Sender<Vec<String>>,
+    polling_rx: watch::Receiver<Polling>,
+    heartbeat: Heartbeat,
 ) {
     /* ARGos task */
     let argos_task = futures::future::pending().left_future();
@@ -565,14 +1002,71 @@ async fn fernbedienung(
     let bash_task = bash(&device, bash_rx, updates_tx.clone());
     tokio::pin!(bash_task);
     /* link strength stream */
-    let link_strength_stream = fernbedienung_link_strength_stream(&device)
+    let link_strength_stream = fernbedienung_link_strength_stream(&device, polling_rx.clone())
         .map_ok(Update::FernbedienungSignal);
     let link_strength_stream_throttled =
-        tokio_stream::StreamExt::throttle(link_strength_stream, Duration::from_millis(1000));
+        tokio_stream::StreamExt::throttle(link_strength_stream, LINK_STRENGTH_INTERVAL);
     tokio::pin!(link_strength_stream_throttled);
+    /* uptime stream */
+    let uptime_stream = fernbedienung_uptime_stream(&device, polling_rx);
+    tokio::pin!(uptime_stream);
+    /* the connection to the robot is considered lost once a heartbeat is not answered */
+    let heartbeat_task = fernbedienung_heartbeat(&device, heartbeat);
+    tokio::pin!(heartbeat_task);
+    /* the firmware is only read once per association */
+    match tokio::time::timeout(Duration::from_millis(1000), device.firmware()).await {
+        Ok(Ok(firmware)) => {
+            let _ = firmware_tx.try_send(firmware);
+        },
+        Ok(Err(error)) => log::warn!("Could not read firmware version of UpCore: {}", error),
+        Err(_) => log::warn!("Timeout while reading firmware version of UpCore"),
+    }
+    /* the cameras are only checked once per association, the cameras are assumed to be available if the
+       check fails so that the camera streams are attempted as before */
+    let cameras = DRONE_CAMERAS_CONFIG.iter().map(|&(camera, ..)| camera).collect::<Vec<_>>();
+    let cameras = match tokio::time::timeout(Duration::from_millis(1000), MjpegStreamerStream::availability(&device, &cameras)).await {
+        Ok(Ok(cameras)) => {
+            /* robot types without cameras are not reported */
+            if let (false, Some(reason)) = (cameras.cameras.is_empty(), cameras.reason()) {
+                log::warn!("Cameras of {} are not supported: {}", device.addr, reason);
+            }
+            let _ = cameras_tx.try_send(cameras.clone());
+            Some(cameras)
+        },
+        Ok(Err(error)) => {
+            log::warn!("{:#}", error);
+            None
+        },
+        Err(_) => {
+            log::warn!("Timeout while checking the availability of the cameras");
+            None
+        },
+    };
+    /* report what the watchdog did since the previous association before it is redeployed */
+    match watchdog.as_ref() {
+        Some(watchdog) => {
+            match Watchdog::events(&device).await {
+                Ok(events) => {
+                    for event in &events {
+                        log::warn!("Watchdog on {}: {}", device.addr, event);
+                    }
+                    let _ = watchdog_tx.try_send(events);
+                },
+                Err(error) => log::warn!("{:#}", error),
+            }
+            if let Err(error) = watchdog.deploy(&device, STATUS_LEDS_PY).await {
+                log::warn!("Could not deploy watchdog to {}: {:#}", device.addr, error);
+            }
+        },
+        None => if let Err(error) = Watchdog::stop(&device).await {
+            log::debug!("{:#}", error);
+        },
+    }
     /* camera stream */
     let mut cameras_stream: tokio_stream::StreamMap<String, _> =
         tokio_stream::StreamMap::new();
+    /* whether the camera streams were enabled before they were suspended */
+    let mut cameras_suspended = false;
     
     loop {
         tokio::select! {
@@ -581,22 +1075,55 @@ async fn fernbedienung(
                 let update = Update::Camera { camera, result: result.map_err(|e| e.to_string()) };
                 let _ = updates_tx.send(update);
             },
+            error = &mut heartbeat_task => {
+                log::warn!("{:#}", error);
+                device.mark_lost();
+                break;
+            },
             Some(response) = link_strength_stream_throttled.next() => match response {
                 Ok(update) => {
                     let _ = updates_tx.send(update);
                 },
-                Err(error) => {
-                    log::warn!("{}", error);
-                    break;
-                },
+                Err(error) => log::warn!("{}", error),
+            },
+            Some(uptime) = uptime_stream.next() => {
+                /* use try_send to avoid a deadlock with the robot task, which awaits sending actions to this task */
+                let _ = uptime_tx.try_send(uptime);
             },
             recv = rx.recv() => match recv {
-                Some((callback, action)) => match action {
+                Some((callback, action, trace)) => match action {
                     FernbedienungAction::SetCameraStream(enable) => {
                         cameras_stream.clear();
+                        cameras_suspended = false;
+                        let reason = cameras.as_ref().and_then(camera::Availability::reason);
+                        if let (true, Some(reason)) = (enable, reason) {
+                            let _ = callback.send(Err(anyhow::anyhow!("Could not stream cameras: {}", reason)));
+                            continue;
+                        }
                         if enable {
                             for &(camera, width, height, port) in DRONE_CAMERAS_CONFIG {
-                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port);
+                                if !cameras.as_ref().map_or(true, |cameras| cameras.is_available(camera)) {
+                                    continue;
+                                }
+                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port, &limits);
+                                let stream = tokio_stream::StreamExt::throttle(stream, Duration::from_millis(200));
+                                cameras_stream.insert(camera.to_owned(), Box::pin(stream));
+                            }
+                        }
+                        let _ = callback.send(Ok(()));
+                    },
+                    FernbedienungAction::SuspendCameraStream(true) => {
+                        cameras_suspended |= !cameras_stream.is_empty();
+                        cameras_stream.clear();
+                        let _ = callback.send(Ok(()));
+                    },
+                    FernbedienungAction::SuspendCameraStream(false) => {
+                        if std::mem::take(&mut cameras_suspended) {
+                            for &(camera, width, height, port) in DRONE_CAMERAS_CONFIG {
+                                if !cameras.as_ref().map_or(true, |cameras| cameras.is_available(camera)) {
+                                    continue;
+                                }
+                                let stream = MjpegStreamerStream::new(&device, camera, width, height, port, &limits);
                                 let stream = tokio_stream::StreamExt::throttle(stream, Duration::from_millis(200));
                                 cameras_stream.insert(camera.to_owned(), Box::pin(stream));
                             }
@@ -604,12 +1131,12 @@ async fn fernbedienung(
                         let _ = callback.send(Ok(()));
                     },
                     FernbedienungAction::Halt => {
-                        let result = device.halt().await
+                        let result = trace::within(trace, device.halt()).await
                             .context("Could not halt Up Core");
                         let _ = callback.send(result);
                     },
                     FernbedienungAction::Reboot => {
-                        let result = device.reboot().await
+                        let result = trace::within(trace, device.reboot()).await
                             .context("Could not reboot Up Core");
                         let _ = callback.send(result);
                     },
@@ -624,7 +1151,7 @@ async fn fernbedienung(
                             anyhow::anyhow!("Could not send {:?} to Bash terminal: channel is {}", action, reason);
                         let _ = callback.send(Err(error));
                     },
-                    FernbedienungAction::SetupExperiment(id, software, journal) => match argos_stop_tx.as_ref() {
+                    FernbedienungAction::SetupExperiment(id, software, journal, artifacts, environment, decimator) => match argos_stop_tx.as_ref() {
                         Some(_) => {
                             let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already setup or running")));
                         }
@@ -647,6 +1174,8 @@ async fn fernbedienung(
                                     let (stop_tx, stop_rx) = oneshot::channel();
                                     let task = argos(
                                         &device,
+                                        ArgosOptions { limits: limits.clone(), artifacts, environment, decimator },
+                                        updates_tx.clone(),
                                         callback,
                                         software,
                                         id,
@@ -680,11 +1209,91 @@ async fn fernbedienung(
                             let _ = callback.send(Ok(()));
                         }
                     },
+                    FernbedienungAction::PixhawkFailsafe => {
+                        /* ARGoS holds the serial port of the Pixhawk, hence it is stopped and kept running until
+                           it has exited while the script waits for the serial port to be released */
+                        if let Some(stop_tx) = argos_stop_tx.take() {
+                            let _ = stop_tx.send(());
+                        }
+                        let process = fernbedienung::Process {
+                            target: "python3".into(),
+                            working_dir: None,
+                            args: vec!["-c".to_owned(), PIXHAWK_FAILSAFE_PY.to_owned(), PIXHAWK_PORT.to_owned()],
+                            environment: Default::default(),
+                        };
+                        let failsafe = device.run(process, None, None, None, None);
+                        tokio::pin!(failsafe);
+                        let result = loop {
+                            tokio::select! {
+                                result = &mut failsafe => break result,
+                                _ = &mut argos_task => {
+                                    argos_task.set(futures::future::pending().left_future());
+                                    argos_start_tx = None;
+                                    argos_stop_tx = None;
+                                },
+                            }
+                        };
+                        let _ = callback.send(result.context("Could not switch Pixhawk to position control via Up Core"));
+                    },
+                    FernbedienungAction::SetStatusLeds(status) => {
+                        let _ = callback.send(trace::within(trace, status.apply(&device, STATUS_LEDS_PY)).await);
+                    },
+                    FernbedienungAction::FetchResults(results) => {
+                        let result = trace::within(trace, results.fetch(&device)).await;
+                        if let Ok(files) = &result {
+                            log::info!("Fetched {} files from {} into {:?}", files.len(), device.addr, results.directory);
+                            let _ = updates_tx.send(Update::Artifacts(Ok(files.clone())));
+                        }
+                        let _ = callback.send(result.map(|_| ()).context("Could not fetch results"));
+                    },
+                    FernbedienungAction::VerifyStopped => {
+                        let _ = callback.send(trace::within(trace, Leftovers::verify(&device)).await);
+                    },
+                    FernbedienungAction::KillArgos => {
+                        let _ = callback.send(trace::within(trace, Leftovers::kill(&device)).await);
+                    },
+                    /* the motors are spun by the smoke test, the propellers must have been removed */
+                    FernbedienungAction::SmokeTest(checks_tx) => match argos_stop_tx.as_ref() {
+                        Some(_) => {
+                            let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
+                        }
+                        None => {
+                            let process = fernbedienung::Process {
+                                target: "python3".into(),
+                                working_dir: None,
+                                args: vec!["-c".to_owned(), SMOKE_TEST_PY.to_owned(), PIXHAWK_PORT.to_owned()],
+                                environment: Default::default(),
+                            };
+                            let result = trace::within(trace, SmokeTest::run(&device, process, &[])).await
+                                .map(|checks| {
+                                    let _ = checks_tx.send(checks);
+                                });
+                            let _ = callback.send(result);
+                        }
+                    },
+                    FernbedienungAction::BandwidthTest(checks_tx) => match argos_stop_tx.as_ref() {
+                        Some(_) => {
+                            let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
+                        }
+                        None => {
+                            let result = trace::within(trace, BandwidthTest::run(&device)).await
+                                .map(|checks| {
+                                    let _ = checks_tx.send(checks);
+                                });
+                            let _ = callback.send(result);
+                        }
+                    },
+                    FernbedienungAction::Spin => {
+                        let _ = callback.send(Err(anyhow::anyhow!("Drones can not spin in place on the ground")));
+                    },
                     FernbedienungAction::Identify => match argos_stop_tx.as_ref() {
                         Some(_) => {
                             let _ = callback.send(Err(anyhow::anyhow!("ARGoS is already running")));
                         }
                         None => {
+                            if let Err(error) = StatusLeds::Identify.apply(&device, STATUS_LEDS_PY).await {
+                                log::warn!("{}", error);
+                            }
                             let software = Software(vec![
                                 (IDENTIFY_DRONE_ARGOS.0.to_owned(), IDENTIFY_DRONE_ARGOS.1.to_vec()),
                                 (IDENTIFY_DRONE_LUA.0.to_owned(), IDENTIFY_DRONE_LUA.1.to_vec())
@@ -697,7 +1306,7 @@ async fn fernbedienung(
                                     let (start_tx, start_rx) = oneshot::channel();
                                     start_tx.send(()).unwrap();
                                     let (stop_tx, stop_rx) = oneshot::channel();
-                                    let task = argos(&device, callback, software, None, None, None, None, start_rx, stop_rx);
+                                    let task = argos(&device, ArgosOptions { limits: limits.clone(), ..Default::default() }, updates_tx.clone(), callback, software, None, None, None, None, start_rx, stop_rx);
                                     argos_task.set(task.right_future().right_future());
                                     argos_stop_tx = Some(stop_tx);
                                 }
@@ -723,12 +1332,32 @@ async fn fernbedienung(
     }
 }
 
-pub async fn new(mut action_rx: Receiver) {
+/// Safety-critical commands are sent via the Xbee when possible and are otherwise relayed through the Up Core
+fn control_path(xbee_connected: bool, fernbedienung_connected: bool) -> ControlPath {
+    match (xbee_connected, fernbedienung_connected) {
+        (true, _) => ControlPath::Xbee,
+        (false, true) => ControlPath::UpCore,
+        (false, false) => ControlPath::Unavailable,
+    }
+}
+
+pub async fn new(
+    mut action_rx: Receiver,
+    limits: ResourceLimits,
+    watchdog: Option<Watchdog>,
+    polling_rx: watch::Receiver<Polling>,
+    heartbeat: Heartbeat,
+    xbee_configuration: Option<Vec<(String, String)>>,
+) {
     /* fernbedienung task state */
     let fernbedienung_task = futures::future::pending().left_future();
     let mut fernbedienung_tx = Option::default();
     let mut fernbedienung_addr = Option::default();
     tokio::pin!(fernbedienung_task);
+    /* the address of the robot while waiting for it to reconnect after its heartbeat timed out */
+    let mut reconnecting = Option::default();
+    let reconnect_timeout = futures::future::pending().left_future();
+    tokio::pin!(reconnect_timeout);
     /* xbee task state */
     let xbee_task = futures::future::pending().left_future();
     let mut xbee_tx = Option::default();
@@ -736,27 +1365,98 @@ pub async fn new(mut action_rx: Receiver) {
     tokio::pin!(xbee_task);
     /* updates_tx is for sending changes in state to subscribers (e.g., the webui) */
     let (updates_tx, _) = broadcast::channel(16);
+    /* uptime of the robot as reported by the fernbedienung task */
+    let (uptime_tx, mut uptime_rx) = mpsc::channel(8);
+    let mut uptime_monitor = UptimeMonitor::default();
+    /* firmware of the robot as reported by the fernbedienung task */
+    let (firmware_tx, mut firmware_rx) = mpsc::channel(1);
+    let mut firmware = Option::default();
+    /* availability of the cameras as reported by the fernbedienung task */
+    let (cameras_tx, mut cameras_rx) = mpsc::channel(1);
+    let mut cameras = Option::default();
+    /* interventions of the watchdog as reported by the fernbedienung task */
+    let (watchdog_tx, mut watchdog_rx) = mpsc::channel(1);
+    let mut watchdog_events: Vec<String> = Vec::new();
+    /* actions that are executed once the robot is associated again */
+    let mut action_queue = ActionQueue::default();
+    /* the event log of the robot, the errors that the tasks of the robot report are recorded from their updates */
+    let mut event_log = EventLog::default();
+    let mut task_updates_rx = updates_tx.subscribe();
+    /* cell voltages of the battery as reported by the xbee task */
+    let (battery_cells_tx, mut battery_cells_rx) = mpsc::channel(8);
+    let mut battery_imbalanced = false;
+    /* the journal and identifier of the last experiment that was set up for recording the cell voltages */
+    let mut experiment: Option<(mpsc::Sender<journal::Action>, String)> = None;
     loop {
         tokio::select! {
-            Some(action) = action_rx.recv() => match action {
-                Action::AssociateFernbedienung(device) => {
+            Some((action, trace)) = action_rx.recv().map(|action| action.map(Action::untrace)) => match action {
+                /* a second device that claims to be this robot, e.g., after its SD card was cloned, is returned
+                   instead of replacing the device that the robot is associated with */
+                Action::AssociateFernbedienung(device, callback) if fernbedienung_addr.is_some() && fernbedienung_addr != Some(device.addr) => {
+                    let _ = callback.send(Err(device));
+                },
+                Action::AssociateFernbedienung(device, callback) => {
+                    let _ = callback.send(Ok(()));
                     let (tx, rx) = mpsc::channel(8);
                     fernbedienung_tx = Some(tx);
                     fernbedienung_addr = Some(device.addr);
+                    reconnecting = None;
+                    reconnect_timeout.set(futures::future::pending().left_future());
                     let _ = updates_tx.send(Update::FernbedienungConnected(device.addr));
-                    let task = tokio::spawn(fernbedienung(device, rx, updates_tx.clone()));
+                    let event = format!("Up Core connected at {}", device.addr);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+                    let name = format!("drone/fernbedienung@{}", device.addr);
+                    let task = crate::instrument::spawn(name, fernbedienung(device, limits.clone(), watchdog.clone(), rx, updates_tx.clone(), uptime_tx.clone(), firmware_tx.clone(), cameras_tx.clone(), watchdog_tx.clone(), polling_rx.clone(), heartbeat));
                     fernbedienung_task.set(task.right_future());
+                    uptime_monitor.associate();
+                    /* execute the actions that were queued while the robot was disconnected */
+                    if let (Some(tx), Some(addr), false) = (fernbedienung_tx.as_ref(), fernbedienung_addr, action_queue.is_empty()) {
+                        for action in action_queue.take() {
+                            if matches!(action, FernbedienungAction::Halt | FernbedienungAction::Reboot) {
+                                uptime_monitor.expect_reboot();
+                            }
+                            if let Some(event) = action.power_event() {
+                                let event = format!("{} (queued)", event);
+                                let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                            }
+                            let (callback_tx, callback_rx) = oneshot::channel();
+                            if let Ok(_) = tx.send((callback_tx, action, None)).await {
+                                let name = format!("drone/queued@{}", addr);
+                                crate::instrument::spawn(name, async move {
+                                    if let Ok(Err(error)) = callback_rx.await {
+                                        log::warn!("Could not execute queued action: {:#}", error);
+                                    }
+                                });
+                            }
+                        }
+                        let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                    }
+                    let _ = updates_tx.send(Update::ControlPath(control_path(xbee_tx.is_some(), true)));
+                },
+                Action::AssociateXbee(device, callback) if xbee_addr.is_some() && xbee_addr != Some(device.addr) => {
+                    let _ = callback.send(Err(device));
                 },
-                Action::AssociateXbee(device) => {
+                Action::AssociateXbee(device, callback) => {
+                    let _ = callback.send(Ok(()));
                     let (tx, rx) = mpsc::channel(8);
                     xbee_tx = Some(tx);
                     xbee_addr = Some(device.addr);
                     let _ = updates_tx.send(Update::XbeeConnected(device.addr));
-                    let task = tokio::spawn(xbee(device, rx, updates_tx.clone()));
+                    let event = format!("Xbee connected at {}", device.addr);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+                    let name = format!("drone/xbee@{}", device.addr);
+                    let task = crate::instrument::spawn(name, xbee(device, rx, updates_tx.clone(), battery_cells_tx.clone(), polling_rx.clone(), xbee_configuration.clone()));
                     xbee_task.set(task.right_future());
+                    let _ = updates_tx.send(Update::ControlPath(ControlPath::Xbee));
                 },
                 Action::ExecuteXbeeAction(callback, action) => match xbee_tx.as_ref() {
                     Some(tx) => {
+                        if let XbeeAction::SetUpCorePower(_) = action {
+                            uptime_monitor.expect_reboot();
+                        }
+                        if let Some(event) = action.power_event() {
+                            let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                        }
                         if let Err(mpsc::error::SendError((callback, _))) = tx.send((callback, action)).await {
                             let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with Xbee task")));
                         }
@@ -768,31 +1468,73 @@ pub async fn new(mut action_rx: Receiver) {
                 },
                 Action::ExecuteFernbedienungAction(callback, action) => match fernbedienung_tx.as_ref() {
                     Some(tx) => {
-                        if let Err(mpsc::error::SendError((callback, _))) = tx.send((callback, action)).await {
+                        if matches!(action, FernbedienungAction::Halt | FernbedienungAction::Reboot) {
+                            uptime_monitor.expect_reboot();
+                        }
+                        if let Some(event) = action.power_event() {
+                            let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                        }
+                        if let Err(mpsc::error::SendError((callback, _, _))) = tx.send((callback, action, trace)).await {
                             let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with Fernbedienung task")));
                         }
                     },
-                    None => {
+                    None => if action.queueable() {
+                        if let Some(event) = action.power_event() {
+                            let event = format!("{}, queued until Up Core reconnects", event);
+                            let _ = updates_tx.send(Update::Event(event_log.record(Category::Power, event)));
+                        }
+                        action_queue.push(action);
+                        let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                        let _ = callback.send(Ok(()));
+                    }
+                    else {
                         let error = anyhow::anyhow!("Could not execute {:?}: Fernbedienung is not connected.", action);
                         let _ = callback.send(Err(error));
                     }
                 },
+                Action::CancelQueuedAction(callback, id) => {
+                    let result = action_queue.cancel(id);
+                    let _ = updates_tx.send(Update::QueuedActions(action_queue.summary()));
+                    let _ = callback.send(result);
+                },
                 Action::Subscribe(callback) => {
-                    /* note that upon subscribing all updates should be sent to ensure
-                       that new clients are in sync */
-                    if let Ok(_) = callback.send(updates_tx.subscribe()) {
-                        if let Some(addr) = xbee_addr {
-                            let _ = updates_tx.send(Update::XbeeConnected(addr));
-                        }
-                        if let Some(addr) = fernbedienung_addr {
-                            let _ = updates_tx.send(Update::FernbedienungConnected(addr));
-                        }
+                    /* the current state is only sent to the new subscriber so that the other subscribers
+                       do not receive duplicate updates */
+                    let mut state = Vec::new();
+                    if let Some(addr) = xbee_addr {
+                        state.push(Update::XbeeConnected(addr));
                     }
+                    if let Some(addr) = fernbedienung_addr {
+                        state.push(Update::FernbedienungConnected(addr));
+                    }
+                    if let Some(addr) = reconnecting {
+                        state.push(Update::FernbedienungReconnecting(addr));
+                    }
+                    if let Some(reboot) = uptime_monitor.unexpected_reboot.clone() {
+                        state.push(Update::UnexpectedReboot(reboot));
+                    }
+                    if !watchdog_events.is_empty() {
+                        state.push(Update::Watchdog(watchdog_events.clone()));
+                    }
+                    if let Some(firmware) = firmware.clone() {
+                        state.push(Update::Firmware(firmware));
+                    }
+                    if let Some(cameras) = cameras.clone() {
+                        state.push(Update::Cameras(cameras));
+                    }
+                    if !action_queue.is_empty() {
+                        state.push(Update::QueuedActions(action_queue.summary()));
+                    }
+                    let path = control_path(xbee_tx.is_some(), fernbedienung_tx.is_some());
+                    state.push(Update::ControlPath(path));
+                    state.push(Update::EventLog(event_log.entries()));
+                    let _ = callback.send((state, updates_tx.subscribe()));
                 },
-                Action::SetupExperiment(callback, id, software, journal) => match fernbedienung_tx.as_ref() {
+                Action::SetupExperiment(callback, id, software, journal, artifacts, environment, decimator) => match fernbedienung_tx.as_ref() {
                     Some(tx) => {
-                        let action = FernbedienungAction::SetupExperiment(id, software, journal);
-                        if let Err(mpsc::error::SendError((callback, _))) = tx.send((callback, action)).await {
+                        experiment = Some((journal.clone(), id.clone()));
+                        let action = FernbedienungAction::SetupExperiment(id, software, journal, artifacts, environment, decimator);
+                        if let Err(mpsc::error::SendError((callback, _, _))) = tx.send((callback, action, None)).await {
                             let _ = callback.send(Err(anyhow::anyhow!("Could not communicate with Fernbedienung task")));
                         }
                     }
@@ -814,40 +1556,71 @@ pub async fn new(mut action_rx: Receiver) {
                             .ok_or(anyhow::anyhow!("Fernbedienung is not connected"))?;
                         let (fernbedienung_callback_tx, fernbedienung_callback_rx) = oneshot::channel();
                         // as above with fernbedienung
-                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StartExperiment)).await
+                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StartExperiment, None)).await
                             .context("Could not communicate with Fernbedienung task")?;
                         fernbedienung_callback_rx.await
                             .context("Fernbedienung did not respond")??;
                         anyhow::Result::<()>::Ok(())
                     };
-                    let _ = callback.send(result.await.context("Could not start experiment"));
+                    let result = result.await.context("Could not start experiment");
+                    let entry = match &result {
+                        Ok(_) => event_log.record(Category::Experiment, "Experiment started"),
+                        Err(error) => event_log.record(Category::Error, format!("{:#}", error)),
+                    };
+                    let _ = updates_tx.send(Update::Event(entry));
+                    let _ = callback.send(result);
                 },
+                Action::Replay(update) => {
+                    let _ = updates_tx.send(update);
+                },
+                /* the trace is removed when the action is received */
+                Action::Traced(..) => {},
                 Action::StopExperiment => {
                     let terminate_argos = async {
                         let fernbedienung_tx = fernbedienung_tx.as_ref()
                             .ok_or(anyhow::anyhow!("Fernbedienung is not connected"))?;
                         let (fernbedienung_callback_tx, fernbedienung_callback_rx) = oneshot::channel();
-                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StopExperiment)).await
+                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::StopExperiment, None)).await
                             .context("Fernbedienung is not available")?;
                         fernbedienung_callback_rx.await
                             .context("Fernbedienung did not respond")??;
                         anyhow::Result::<()>::Ok(())
                     };
                     let disable_autonomous_mode = async {
-                        let xbee_tx = xbee_tx.as_ref()
-                            .ok_or(anyhow::anyhow!("Xbee is not connected"))?;
-                        let (xbee_callback_tx, xbee_callback_rx) = oneshot::channel();
-                        xbee_tx.send((xbee_callback_tx, XbeeAction::SetAutonomousMode(false))).await
-                            .context("Xbee is not available")?;
-                        xbee_callback_rx.await
-                            .context("Xbee did not respond")??;
+                        let via_xbee = async {
+                            let xbee_tx = xbee_tx.as_ref()
+                                .ok_or(anyhow::anyhow!("Xbee is not connected"))?;
+                            let (xbee_callback_tx, xbee_callback_rx) = oneshot::channel();
+                            xbee_tx.send((xbee_callback_tx, XbeeAction::SetAutonomousMode(false))).await
+                                .context("Xbee is not available")?;
+                            xbee_callback_rx.await
+                                .context("Xbee did not respond")??;
+                            anyhow::Result::<()>::Ok(())
+                        };
+                        let xbee_error = match via_xbee.await {
+                            Ok(_) => return Ok(()),
+                            Err(error) => error,
+                        };
+                        /* fall back to switching the Pixhawk to position control via the Up Core */
+                        log::warn!("{}, relaying failsafe via Up Core", xbee_error);
+                        let _ = updates_tx.send(Update::ControlPath(ControlPath::UpCore));
+                        let fernbedienung_tx = fernbedienung_tx.as_ref()
+                            .ok_or(anyhow::anyhow!("Fernbedienung is not connected"))
+                            .context(xbee_error)?;
+                        let (fernbedienung_callback_tx, fernbedienung_callback_rx) = oneshot::channel();
+                        fernbedienung_tx.send((fernbedienung_callback_tx, FernbedienungAction::PixhawkFailsafe, None)).await
+                            .context("Fernbedienung is not available")?;
+                        fernbedienung_callback_rx.await
+                            .context("Fernbedienung did not respond")??;
                         anyhow::Result::<()>::Ok(())
                     };
                     // !!! this logic will impact safety during experiments -- modify with caution !!!
                     // the Pixhawk is programmed to go into the off-board fail safe, so just disable autonomous
                     // mode here. Be careful that we are not sending heartbeat messages or the drone will keep
                     // flying. Using tokio::join! below we simulatenously shutdown ARGoS and disable autonomous
-                    // mode.
+                    // mode. If the Xbee is not available, the Pixhawk is switched to position control (the
+                    // off-board fail safe) by a MAVLink utility that runs on the Up Core once ARGoS has released
+                    // the serial port, and the mode is only reported as changed once the Pixhawk reports it.
                     let result = tokio::join!(terminate_argos, disable_autonomous_mode);
                     if let Err(error) = result.0 {
                         log::warn!("{}", error);
@@ -855,19 +1628,103 @@ pub async fn new(mut action_rx: Receiver) {
                     if let Err(error) = result.1 {
                         log::warn!("{}", error);
                     }
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Experiment, "Experiment stopped")));
                 },
             },
+            Some(cells) = battery_cells_rx.recv() => {
+                let imbalance = cell_imbalance(&cells);
+                let imbalanced = imbalance > CELL_IMBALANCE_THRESHOLD_MV;
+                /* only warn once each time the battery becomes imbalanced */
+                if imbalanced && !battery_imbalanced {
+                    if let Some(addr) = xbee_addr {
+                        log::warn!("Drone at {}: battery cells deviate by {} mV", addr, imbalance);
+                    }
+                    if let Some((journal_tx, id)) = experiment.as_ref() {
+                        let description = format!("{}: battery cells deviate by {} mV", id, imbalance);
+                        let event = journal::Event::Timeline(TimelineCategory::Safety, description, vec![String::from("battery")]);
+                        let _ = journal_tx.send(journal::Action::Record(event)).await;
+                    }
+                }
+                battery_imbalanced = imbalanced;
+                if let Some((journal_tx, id)) = experiment.as_ref() {
+                    let event = journal::Event::BatteryCells(id.clone(), cells.clone());
+                    let _ = journal_tx.send(journal::Action::Record(event)).await;
+                }
+                let _ = updates_tx.send(Update::BatteryCells(cells));
+            },
+            Some(version) = firmware_rx.recv() => {
+                firmware = Some(version.clone());
+                let _ = updates_tx.send(Update::Firmware(version));
+            },
+            Some(availability) = cameras_rx.recv() => {
+                cameras = Some(availability.clone());
+                let _ = updates_tx.send(Update::Cameras(availability));
+            },
+            Some(events) = watchdog_rx.recv() => {
+                for event in &events {
+                    let event = format!("Watchdog: {}", event);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Error, event)));
+                }
+                watchdog_events = events.clone();
+                let _ = updates_tx.send(Update::Watchdog(events));
+            },
+            Some(uptime) = uptime_rx.recv() => {
+                if let Some(reboot) = uptime_monitor.update(uptime) {
+                    if let Some(addr) = fernbedienung_addr {
+                        log::warn!("Robot at {}: {}", addr, reboot);
+                    }
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Error, reboot.clone())));
+                    let _ = updates_tx.send(Update::UnexpectedReboot(reboot));
+                }
+                let association = uptime_monitor.association();
+                let _ = updates_tx.send(Update::Uptime { robot: uptime.as_secs(), association: association.as_secs() });
+            },
+            Ok(update) = task_updates_rx.recv() => {
+                let entry = match update {
+                    Update::ResourceLimitViolation(violation) =>
+                        Some(event_log.record(Category::Error, violation)),
+                    Update::Artifacts(Err(error)) =>
+                        Some(event_log.record(Category::Error, format!("Could not collect artifacts: {}", error))),
+                    Update::ExperimentSummary(summary) => Some(match summary.failed {
+                        true => event_log.record(Category::Error, format!("Experiment failed: {}", summary.exit)),
+                        false => event_log.record(Category::Experiment, format!("Experiment finished: {}", summary.exit)),
+                    }),
+                    _ => None,
+                };
+                if let Some(entry) = entry {
+                    let _ = updates_tx.send(Update::Event(entry));
+                }
+            },
+            _ = &mut reconnect_timeout => {
+                reconnecting = None;
+                reconnect_timeout.set(futures::future::pending().left_future());
+                let _ = updates_tx.send(Update::FernbedienungDisconnected);
+                let event = "Up Core did not reconnect in time";
+                let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+            },
             _ = &mut fernbedienung_task => {
                 fernbedienung_tx = None;
-                fernbedienung_addr = None;
+                /* the robot is only reported as disconnected if it does not reconnect in time */
+                reconnecting = fernbedienung_addr.take();
+                reconnect_timeout.set(tokio::time::sleep(Duration::from_secs(heartbeat.reconnect)).right_future());
+                uptime_monitor.disassociate();
+                firmware = None;
+                cameras = None;
                 fernbedienung_task.set(futures::future::pending().left_future());
-                let _ = updates_tx.send(Update::FernbedienungDisconnected);
+                if let Some(addr) = reconnecting {
+                    let _ = updates_tx.send(Update::FernbedienungReconnecting(addr));
+                    let event = format!("Lost connection to Up Core at {}, waiting for it to reconnect", addr);
+                    let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, event)));
+                }
+                let _ = updates_tx.send(Update::ControlPath(control_path(xbee_tx.is_some(), false)));
             },
             join_result = &mut xbee_task => {
                 xbee_tx = None;
                 xbee_addr = None;
                 xbee_task.set(futures::future::pending().left_future());
                 let _ = updates_tx.send(Update::XbeeDisconnected);
+                let _ = updates_tx.send(Update::Event(event_log.record(Category::Connection, "Xbee disconnected")));
+                let _ = updates_tx.send(Update::ControlPath(control_path(false, fernbedienung_tx.is_some())));
                 match join_result {
                     Ok(task_result) => if let Err(error) = task_result {
                         log::warn!("xbee terminated with: {}", error);
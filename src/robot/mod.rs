@@ -1,10 +1,42 @@
 pub mod builderbot;
 pub mod drone;
 pub mod pipuck;
+pub mod ros;
 
-use shared::experiment::software::Software;
-use tokio::sync::mpsc;
+mod outbound;
+
+pub use outbound::Outbound;
+
+use std::{collections::{BTreeMap, VecDeque}, time::{Duration, Instant}};
+use anyhow::Context;
+use bytes::BytesMut;
+use futures::Stream;
+use regex::Regex;
+use shared::{configuration::{Heartbeat, PollingFactors}, event_log::{Category, Entry}, experiment::{SmokeTestCheck, profile::Decimation, software::Software}};
+use tokio::sync::{mpsc, oneshot, watch};
 use crate::journal;
+use crate::network::{fernbedienung, fernbedienung_ext::{Artifacts, ResourceLimits, Results}};
+
+/* the interval at which the uptime of a robot is requested */
+const UPTIME_INTERVAL: Duration = Duration::from_secs(5);
+/* the interval at which the link strength and the pin states of a robot are requested */
+pub const LINK_STRENGTH_INTERVAL: Duration = Duration::from_millis(1000);
+/* the amount by which the boot time can move forward before it is considered a reboot */
+const UPTIME_TOLERANCE: Duration = Duration::from_secs(30);
+/* the maximum amount of input that can be queued for a terminal */
+const TERMINAL_INPUT_MAX_SIZE: usize = 64 * 1024;
+/* input for a terminal is sent in chunks of this size with the given interval between them, this limits
+   the bandwidth used by a terminal to 25 KiB/s so that it does not starve the other traffic to the robot */
+const TERMINAL_INPUT_CHUNK_SIZE: usize = 512;
+pub const TERMINAL_INPUT_INTERVAL: Duration = Duration::from_millis(20);
+/* the interval at which the progress of sending input to a terminal is reported */
+const TERMINAL_INPUT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/* the maximum size of a command for the MAVLink shell (the size of the data field of SERIAL_CONTROL) */
+pub const MAVLINK_TERMINAL_INPUT_MAX_SIZE: usize = 70;
+/* the watchdog that supervises the Fernbedienung service, see fernbedienung_ext::Watchdog */
+pub const WATCHDOG_PY: &str = include_str!("watchdog.py");
+/* the number of events that are kept in the event log of each robot */
+const EVENT_LOG_CAPACITY: usize = 200;
 
 #[derive(Debug)]
 pub enum FernbedienungAction {
@@ -12,10 +44,141 @@ pub enum FernbedienungAction {
     Reboot,
     Bash(TerminalAction),
     SetCameraStream(bool),
-    SetupExperiment(String, Software, mpsc::Sender<journal::Action>),
+    /* suspends the camera streams while an experiment is running, resuming only restarts suspended streams */
+    SuspendCameraStream(bool),
+    SetupExperiment(String, Software, mpsc::Sender<journal::Action>, Option<Artifacts>, BTreeMap<String, String>, Decimator),
     StartExperiment,
     StopExperiment,
     Identify,
+    /* ground robots only: spin in place so that the rigid body of the robot can be identified */
+    Spin,
+    /* drones only: switch the Pixhawk to position control via its serial port */
+    PixhawkFailsafe,
+    SetStatusLeds(StatusLeds),
+    /* the checks of the smoke test are sent to the sender once the smoke test has finished */
+    SmokeTest(oneshot::Sender<Vec<SmokeTestCheck>>),
+    /* the throughput to and from the supervisor is sent to the sender as checks once it has been measured */
+    BandwidthTest(oneshot::Sender<Vec<SmokeTestCheck>>),
+    /* download files from the robot, e.g., output files of ARGoS, logs, or captured data */
+    FetchResults(Results),
+    /* fails if ARGoS is still running after an experiment has been stopped */
+    VerifyStopped,
+    /* kills ARGoS if it did not exit after an experiment has been stopped */
+    KillArgos,
+}
+
+impl FernbedienungAction {
+    /// Whether the action can be queued while the robot is disconnected. Experiment and safety-related
+    /// actions are never queued since executing them later could be harmful
+    pub fn queueable(&self) -> bool {
+        matches!(self, FernbedienungAction::Identify | FernbedienungAction::Halt | FernbedienungAction::Reboot)
+    }
+
+    /// A description of the action for the event log of the robot if the action changes its power state
+    pub fn power_event(&self) -> Option<String> {
+        match self {
+            FernbedienungAction::Halt => Some(String::from("Halt requested")),
+            FernbedienungAction::Reboot => Some(String::from("Reboot requested")),
+            _ => None,
+        }
+    }
+}
+
+/// Actions that were requested while the Fernbedienung service of a robot was not connected. These actions
+/// are executed in order once the robot is associated again unless they are cancelled beforehand
+#[derive(Default)]
+pub struct ActionQueue {
+    next_id: u64,
+    actions: Vec<(u64, FernbedienungAction)>,
+}
+
+impl ActionQueue {
+    pub fn push(&mut self, action: FernbedienungAction) {
+        self.actions.push((self.next_id, action));
+        self.next_id += 1;
+    }
+
+    pub fn cancel(&mut self, id: u64) -> anyhow::Result<()> {
+        match self.actions.iter().position(|(queued, _)| *queued == id) {
+            Some(index) => {
+                self.actions.remove(index);
+                Ok(())
+            },
+            None => Err(anyhow::anyhow!("Queued action {} does not exist", id)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub fn take(&mut self) -> Vec<FernbedienungAction> {
+        self.actions.drain(..).map(|(_, action)| action).collect()
+    }
+
+    /// The identifiers and descriptions of the queued actions for the user interface
+    pub fn summary(&self) -> Vec<(u64, String)> {
+        self.actions.iter()
+            .map(|(id, action)| (*id, format!("{:?}", action)))
+            .collect()
+    }
+}
+
+/// The recent events of a robot, e.g., its connections, the experiments that it participated in, and the
+/// errors that occurred on it. Only the most recent events are kept
+#[derive(Default)]
+pub struct EventLog(VecDeque<Entry>);
+
+impl EventLog {
+    /// Records an event and returns its entry so that it can be sent to the subscribers
+    pub fn record(&mut self, category: Category, description: impl Into<String>) -> Entry {
+        let entry = Entry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            category,
+            description: description.into(),
+        };
+        self.0.push_back(entry.clone());
+        if self.0.len() > EVENT_LOG_CAPACITY {
+            self.0.pop_front();
+        }
+        entry
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        self.0.iter().cloned().collect()
+    }
+}
+
+/// The state of a robot in the supervisor as shown on its LEDs. Note that ARGoS takes control of the
+/// LEDs while an experiment is running
+#[derive(Clone, Copy, Debug)]
+pub enum StatusLeds {
+    Ready,
+    Setup,
+    Error,
+    Identify,
+    Off,
+}
+
+impl StatusLeds {
+    /// Sets the LEDs using the helper script for the robot type
+    pub async fn apply(self, device: &fernbedienung::Device, script: &str) -> anyhow::Result<()> {
+        let status = match self {
+            StatusLeds::Ready => "green",
+            StatusLeds::Setup => "blue",
+            StatusLeds::Error => "red",
+            StatusLeds::Identify => "blink",
+            StatusLeds::Off => "off",
+        };
+        let process = fernbedienung::Process {
+            target: "python3".into(),
+            working_dir: None,
+            args: vec!["-c".to_owned(), script.to_owned(), status.to_owned()],
+            environment: Default::default(),
+        };
+        device.run(process, None, None, None, None).await
+            .map_err(|error| anyhow::anyhow!("Could not set status LEDs to {:?}: {}", self, error))
+    }
 }
 
 #[derive(Debug)]
@@ -24,6 +187,31 @@ pub enum XbeeAction {
     SetUpCorePower(bool),
     SetPixhawkPower(bool),
     Mavlink(TerminalAction),
+    /* a command from the MAVLink console, the acknowledgement is sent as an update */
+    MavlinkCommand(shared::drone::MavlinkCommand),
+    /* arm or disarm the Pixhawk and change its flight mode, these actions complete once the Pixhawk has
+       acknowledged the command */
+    Arm(bool),
+    SetFlightMode(shared::drone::FlightMode),
+    /* read the parameters of the Xbee and compare them to the expected values, the audit is sent as an update */
+    Audit(BTreeMap<String, String>),
+    /* fails if the autonomous mode pin is still high after an experiment has been stopped */
+    VerifyAutonomousModeDisabled,
+}
+
+impl XbeeAction {
+    /// A description of the action for the event log of the drone if the action changes its power state
+    pub fn power_event(&self) -> Option<String> {
+        let state = |enable: bool| match enable {
+            true => "on",
+            false => "off",
+        };
+        match self {
+            XbeeAction::SetUpCorePower(enable) => Some(format!("Up Core switched {}", state(*enable))),
+            XbeeAction::SetPixhawkPower(enable) => Some(format!("Pixhawk switched {}", state(*enable))),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -31,4 +219,248 @@ pub enum TerminalAction {
     Start,
     Run(String),
     Stop,
+}
+
+/// Input for a terminal that is waiting to be sent to a robot. Large inputs (e.g., a pasted script) are
+/// split into chunks that are paced by the terminal task so that they do not flood the connection
+#[derive(Default)]
+pub struct TerminalInput {
+    chunks: VecDeque<BytesMut>,
+    sent: usize,
+    total: usize,
+    reported: Option<Instant>,
+}
+
+impl TerminalInput {
+    pub fn push(&mut self, input: &str) -> anyhow::Result<()> {
+        let queued = self.total - self.sent;
+        if queued + input.len() > TERMINAL_INPUT_MAX_SIZE {
+            return Err(anyhow::anyhow!("Could not send {} bytes to terminal: at most {} bytes can be queued ({} bytes are already queued)",
+                input.len(), TERMINAL_INPUT_MAX_SIZE, queued));
+        }
+        if queued == 0 {
+            self.sent = 0;
+            self.total = 0;
+        }
+        self.chunks.extend(input.as_bytes()
+            .chunks(TERMINAL_INPUT_CHUNK_SIZE)
+            .map(BytesMut::from));
+        self.total += input.len();
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn next(&mut self) -> Option<BytesMut> {
+        let chunk = self.chunks.pop_front()?;
+        self.sent += chunk.len();
+        Some(chunk)
+    }
+
+    /// Puts a chunk that could not be sent back at the front of the queue
+    pub fn retry(&mut self, chunk: BytesMut) {
+        self.sent -= chunk.len();
+        self.chunks.push_front(chunk);
+    }
+
+    /// Discards the queued input, e.g., when the terminal is stopped
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.total = self.sent;
+    }
+
+    /// Returns the number of bytes sent and the total number of bytes if the progress should be reported.
+    /// Progress is only reported for inputs that are split into multiple chunks
+    pub fn progress(&mut self) -> Option<(usize, usize)> {
+        if self.total <= TERMINAL_INPUT_CHUNK_SIZE {
+            return None;
+        }
+        let due = self.reported.map_or(true, |reported| reported.elapsed() >= TERMINAL_INPUT_PROGRESS_INTERVAL);
+        if due || self.chunks.is_empty() {
+            self.reported = Some(Instant::now());
+            Some((self.sent, self.total))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// The options with which ARGoS is run on a robot. The defaults, apart from the resource limits of the robot
+/// type, are used when ARGoS is only run to identify or to test a robot
+#[derive(Default)]
+pub struct ArgosOptions {
+    pub limits: ResourceLimits,
+    /* the files that are collected from the working directory after an experiment */
+    pub artifacts: Option<Artifacts>,
+    pub environment: BTreeMap<String, String>,
+    pub decimator: Decimator,
+}
+
+/// Reduces the standard output of ARGoS before it is journaled according to the decimation of the experiment.
+/// The output is decimated line by line as it is received, the lines that do not match any rule are kept
+#[derive(Clone, Debug, Default)]
+pub struct Decimator {
+    /* the pattern of each rule, the interval at which its lines are kept, and the position within that interval */
+    rules: Vec<(Regex, u32, u32)>,
+}
+
+impl Decimator {
+    pub fn new(decimation: &[Decimation]) -> anyhow::Result<Self> {
+        let rules = decimation.iter()
+            .map(|rule| {
+                if rule.every == 0 {
+                    return Err(anyhow::anyhow!("Decimation of \"{}\" must keep every n-th line with n > 0", rule.pattern));
+                }
+                let pattern = Regex::new(&rule.pattern)
+                    .with_context(|| format!("Invalid decimation pattern \"{}\"", rule.pattern))?;
+                Ok((pattern, rule.every, 0))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Decimator { rules })
+    }
+
+    /// Returns the output that is journaled, which is empty if all lines were dropped
+    pub fn apply(&mut self, data: BytesMut) -> BytesMut {
+        if self.rules.is_empty() {
+            return data;
+        }
+        let mut kept = BytesMut::with_capacity(data.len());
+        for line in data.split_inclusive(|&byte| byte == b'\n') {
+            let text = String::from_utf8_lossy(line);
+            let rule = self.rules.iter_mut()
+                .find(|(pattern, _, _)| pattern.is_match(text.trim_end()));
+            let keep = match rule {
+                Some((_, every, count)) => {
+                    let keep = *count == 0;
+                    *count = (*count + 1) % *every;
+                    keep
+                },
+                None => true,
+            };
+            if keep {
+                kept.extend_from_slice(line);
+            }
+        }
+        kept
+    }
+}
+
+/// The background polling of the robots, which is reduced by the arena while an experiment is running
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Polling {
+    Normal,
+    Reduced(PollingFactors),
+}
+
+impl Polling {
+    /// The interval of a poll given its normal interval and the factor that applies to it
+    pub fn interval(&self, interval: Duration, factor: fn(&PollingFactors) -> u32) -> Duration {
+        match self {
+            Polling::Normal => interval,
+            Polling::Reduced(factors) => interval * factor(factors),
+        }
+    }
+}
+
+/// Waits for the time by which the interval of a poll is extended while the polling is reduced. The normal
+/// interval is awaited by the caller, the wait ends early once the polling returns to normal
+pub async fn reduced_polling(
+    polling_rx: &mut watch::Receiver<Polling>,
+    interval: Duration,
+    factor: fn(&PollingFactors) -> u32
+) {
+    let extension = polling_rx.borrow().interval(interval, factor).saturating_sub(interval);
+    if extension > Duration::default() {
+        tokio::select! {
+            _ = tokio::time::sleep(extension) => {},
+            _ = polling_rx.changed() => {},
+        }
+    }
+}
+
+/// Requests the uptime of a robot every few seconds. Failed requests are skipped since losing the
+/// connection to a robot is already detected by the heartbeat
+pub fn fernbedienung_uptime_stream<'dev>(
+    device: &'dev fernbedienung::Device,
+    mut polling_rx: watch::Receiver<Polling>,
+) -> impl Stream<Item = Duration> + 'dev {
+    async_stream::stream! {
+        let mut interval = tokio::time::interval(UPTIME_INTERVAL);
+        loop {
+            interval.tick().await;
+            reduced_polling(&mut polling_rx, UPTIME_INTERVAL, |factors| factors.uptime).await;
+            match tokio::time::timeout(Duration::from_millis(1000), device.uptime()).await {
+                Ok(Ok(uptime)) => yield uptime,
+                Ok(Err(error)) => log::debug!("Could not get uptime from {}: {}", device.addr, error),
+                Err(_) => log::debug!("Timeout while getting uptime from {}", device.addr),
+            }
+        }
+    }
+}
+
+/// Sends a heartbeat to the Fernbedienung service at the configured interval and resolves with an error once
+/// a heartbeat has not been answered within the timeout, i.e., once the connection to the robot has been lost
+pub async fn fernbedienung_heartbeat(device: &fernbedienung::Device, heartbeat: Heartbeat) -> anyhow::Error {
+    let timeout = Duration::from_secs(heartbeat.timeout);
+    let mut interval = tokio::time::interval(Duration::from_secs(heartbeat.interval));
+    loop {
+        interval.tick().await;
+        match tokio::time::timeout(timeout, device.ping()).await {
+            Ok(Ok(_)) => {},
+            Ok(Err(error)) => break anyhow::Error::new(error)
+                .context(format!("Heartbeat of {} failed", device.addr)),
+            Err(_) => break anyhow::anyhow!("Heartbeat of {} was not answered within {}s",
+                device.addr, heartbeat.timeout),
+        }
+    }
+}
+
+/// Tracks the boot time of a robot (from its reported uptime) and how long it has been associated with
+/// the supervisor. If the boot time moves forward without the operator having requested a halt, reboot,
+/// or power cycle, the robot rebooted by itself, e.g., after a brown-out caused by a failing battery
+#[derive(Default)]
+pub struct UptimeMonitor {
+    /* note that the clock of the supervisor may have started after the robot booted, hence chrono */
+    boot: Option<chrono::DateTime<chrono::Local>>,
+    associated: Option<Instant>,
+    reboot_expected: bool,
+    /* the most recent unexpected reboot, kept so that it can be sent to new subscribers */
+    pub unexpected_reboot: Option<String>,
+}
+
+impl UptimeMonitor {
+    pub fn associate(&mut self) {
+        self.associated = Some(Instant::now());
+    }
+
+    pub fn disassociate(&mut self) {
+        self.associated = None;
+    }
+
+    pub fn expect_reboot(&mut self) {
+        self.reboot_expected = true;
+    }
+
+    /// The time since the robot was associated with the supervisor
+    pub fn association(&self) -> Duration {
+        self.associated.map_or(Duration::default(), |associated| associated.elapsed())
+    }
+
+    /// Updates the boot time using the reported uptime and returns a description of the reboot if the
+    /// robot rebooted without the operator requesting it
+    pub fn update(&mut self, uptime: Duration) -> Option<String> {
+        let boot = chrono::Local::now() - chrono::Duration::from_std(uptime).ok()?;
+        let previous = self.boot.replace(boot)?;
+        let tolerance = chrono::Duration::from_std(UPTIME_TOLERANCE).ok()?;
+        if boot <= previous + tolerance || std::mem::take(&mut self.reboot_expected) {
+            return None;
+        }
+        let description = format!("Rebooted unexpectedly around {} without a request from the operator",
+            boot.format("%H:%M:%S"));
+        self.unexpected_reboot = Some(description.clone());
+        Some(description)
+    }
 }
\ No newline at end of file
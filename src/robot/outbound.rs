@@ -0,0 +1,88 @@
+use std::sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/* the maximum number of actions that are queued for a robot whose task does not keep up, further actions
+   are rejected until the queue has drained */
+const CAPACITY: usize = 256;
+
+struct Shared {
+    name: String,
+    /* the number of actions in the queue that have not yet been handed over to the task of the robot */
+    queued: AtomicUsize,
+    /* whether actions have been rejected since the queue was last empty, used to only warn once */
+    overloaded: AtomicBool,
+}
+
+/// The sending half of the channel to the task of a robot. Sending never waits on the task of the robot,
+/// so that a robot whose task is busy or wedged cannot hold up the arena and thereby the rest of the fleet.
+/// Actions are handed over directly while the channel has capacity and otherwise queued and forwarded in
+/// order by a dedicated task
+pub struct Outbound<A> {
+    action_tx: mpsc::Sender<A>,
+    queue_tx: mpsc::UnboundedSender<A>,
+    shared: Arc<Shared>,
+}
+
+impl<A> Clone for Outbound<A> {
+    fn clone(&self) -> Self {
+        Self {
+            action_tx: self.action_tx.clone(),
+            queue_tx: self.queue_tx.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<A: Send + 'static> Outbound<A> {
+    /// Wraps the channel to the task of a robot and spawns the task that forwards the queued actions. The
+    /// forwarder ends once every clone of the returned sender has been dropped and the queue has drained
+    pub fn new(name: String, action_tx: mpsc::Sender<A>) -> Self {
+        let (queue_tx, mut queue_rx) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            name,
+            queued: AtomicUsize::new(0),
+            overloaded: AtomicBool::new(false),
+        });
+        let forwarder_action_tx = action_tx.clone();
+        let forwarder_shared = shared.clone();
+        crate::instrument::spawn(format!("{}/outbound", shared.name), async move {
+            while let Some(action) = queue_rx.recv().await {
+                let result = forwarder_action_tx.send(action).await;
+                /* the action is only accounted for once it has been handed over so that new actions are
+                   not sent directly while older actions are still waiting */
+                if forwarder_shared.queued.fetch_sub(1, Ordering::AcqRel) == 1 &&
+                    forwarder_shared.overloaded.swap(false, Ordering::AcqRel) {
+                    log::info!("Queue of {} has drained", forwarder_shared.name);
+                }
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+        Self { action_tx, queue_tx, shared }
+    }
+
+    /// Hands an action over to the task of the robot without waiting. Fails if the task has ended or if
+    /// the task of the robot has fallen so far behind that its queue is full
+    pub fn try_send(&self, action: A) -> Result<(), TrySendError<A>> {
+        /* bypass the queue only when it is empty, otherwise the action would overtake the queued actions */
+        let action = match self.shared.queued.load(Ordering::Acquire) {
+            0 => match self.action_tx.try_send(action) {
+                Err(TrySendError::Full(action)) => action,
+                result => return result,
+            },
+            _ => action,
+        };
+        if self.shared.queued.fetch_add(1, Ordering::AcqRel) >= CAPACITY {
+            self.shared.queued.fetch_sub(1, Ordering::AcqRel);
+            if !self.shared.overloaded.swap(true, Ordering::AcqRel) {
+                log::warn!("Queue of {} is full, rejecting actions until it has drained", self.shared.name);
+            }
+            return Err(TrySendError::Full(action));
+        }
+        self.queue_tx.send(action).map_err(|mpsc::error::SendError(action)| {
+            self.shared.queued.fetch_sub(1, Ordering::AcqRel);
+            TrySendError::Closed(action)
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use tokio::{self, sync::mpsc, task::JoinHandle};
+use shared::configuration::Heartbeat;
+use tokio::{self, sync::{mpsc, watch}, task::JoinHandle};
+use crate::network::fernbedienung_ext::{ResourceLimits, Watchdog};
+use crate::robot::{Outbound, Polling};
 
 mod task;
 
@@ -11,13 +14,26 @@ pub struct Instance {
     _task: JoinHandle<()>
 }
 
-impl Default for Instance {
-    fn default() -> Self {
+impl Instance {
+    pub fn new(
+        descriptor: &Descriptor,
+        limits: ResourceLimits,
+        watchdog: Option<Watchdog>,
+        polling_rx: watch::Receiver<Polling>,
+        heartbeat: Heartbeat,
+    ) -> Self {
         let (action_tx, action_rx) = mpsc::channel(8);
-        let _task = tokio::spawn(task::new(action_rx));
+        let action_tx = Outbound::new(format!("pipuck/{}", descriptor.id), action_tx);
+        let _task = crate::instrument::spawn(format!("pipuck/{}", descriptor.id), task::new(action_rx, limits, watchdog, polling_rx, heartbeat));
         Self { 
             action_tx,
             _task
         }
     }
+
+    /// Stops the task of the robot, which disconnects the devices of the robot and thereby returns their
+    /// addresses to the network module
+    pub fn shutdown(self) {
+        self._task.abort();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+-- Smoke test of the Pi-Puck that is run by the supervisor before an experiment. The robot drives forward
+-- briefly while its LEDs flash and its rangefinders are read. Each check is written to standard output as
+-- SMOKE_TEST<tab>PASS|FAIL<tab>check<tab>measured value
+
+-- the target speed of the wheels and the minimum speed that must be measured (m/s)
+local SPEED = 0.05
+local MIN_SPEED = 0.025
+-- the wheels are driven for these ticks, the first ticks are not measured while the wheels accelerate
+local DRIVE_START = 1
+local MEASURE_START = 6
+local DRIVE_END = 20
+
+function report(passed, check, value)
+   print(string.format("SMOKE_TEST\t%s\t%s\t%s", passed and "PASS" or "FAIL", check, value))
+end
+
+function init()
+   count = 0
+   speed = { left = 0, right = 0, samples = 0 }
+   rangefinders = {}
+end
+
+function step()
+   count = count + 1
+   -- flash the ring LEDs
+   robot.leds.set_ring_leds(count % 4 < 2)
+   -- drive forward
+   if count >= DRIVE_START and count < DRIVE_END then
+      robot.differential_drive.set_target_velocity(SPEED, SPEED)
+   else
+      robot.differential_drive.set_target_velocity(0, 0)
+   end
+   if count >= MEASURE_START and count < DRIVE_END then
+      speed.left = speed.left + robot.differential_drive.encoders.left
+      speed.right = speed.right + robot.differential_drive.encoders.right
+      speed.samples = speed.samples + 1
+   end
+   -- track the range of the readings of each rangefinder
+   for id, rangefinder in pairs(robot.rangefinders) do
+      local reading = rangefinders[id] or { min = math.huge, max = -math.huge }
+      reading.min = math.min(reading.min, rangefinder.proximity)
+      reading.max = math.max(reading.max, rangefinder.proximity)
+      rangefinders[id] = reading
+   end
+end
+
+function reset()
+end
+
+function destroy()
+   robot.differential_drive.set_target_velocity(0, 0)
+   robot.leds.set_ring_leds(false)
+   for _, wheel in ipairs({ "left", "right" }) do
+      local measured = speed.samples > 0 and speed[wheel] / speed.samples or 0
+      report(measured >= MIN_SPEED, wheel .. " wheel", string.format("%.3f m/s", measured))
+   end
+   local sensors = 0
+   for id, reading in pairs(rangefinders) do
+      sensors = sensors + 1
+      report(true, "rangefinder " .. id, string.format("%.3f to %.3f", reading.min, reading.max))
+   end
+   report(sensors > 0, "rangefinders", string.format("%d sensors", sensors))
+   report(true, "ring LEDs", "flashed")
+end
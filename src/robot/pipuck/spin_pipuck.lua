@@ -0,0 +1,28 @@
+-- Spins the Pi-Puck in place so that its rigid body can be identified in the tracking system. The wheels
+-- turn in opposite directions until the last tick, when the robot is stopped
+
+-- the speed of the wheels (m/s)
+local SPEED = 0.03
+local SPIN_END = 58
+
+function init()
+   count = 0
+   robot.leds.set_ring_leds(true)
+end
+
+function step()
+   count = count + 1
+   if count < SPIN_END then
+      robot.differential_drive.set_target_velocity(-SPEED, SPEED)
+   else
+      robot.differential_drive.set_target_velocity(0, 0)
+      robot.leds.set_ring_leds(false)
+   end
+end
+
+function reset()
+end
+
+function destroy()
+   robot.differential_drive.set_target_velocity(0, 0)
+end
@@ -0,0 +1,42 @@
+# Sets the RGB LEDs of the Pi-Puck extension board to reflect the state of the robot in the supervisor.
+# The LEDs are controlled by the FT903 microcontroller on the extension board via I2C. Since the FT903
+# cannot blink the LEDs by itself, blinking is done by a detached process that is stopped on the next call.
+import os, signal, sys, time
+from smbus2 import SMBus
+
+I2C_BUS = 3
+FT903_ADDRESS = 0x1C
+LED_REGISTERS = [0x00, 0x01, 0x02]
+COLOURS = { 'off': 0x00, 'red': 0x01, 'green': 0x02, 'blue': 0x04 }
+PID_FILE = '/tmp/supervisor_status_leds.pid'
+# blinking statuses, 'alert' is used by the watchdog when the Fernbedienung service stops responding
+BLINKING = { 'blink': 'blue', 'alert': 'red' }
+
+def set_leds(colour):
+    with SMBus(I2C_BUS) as bus:
+        for register in LED_REGISTERS:
+            bus.write_byte_data(FT903_ADDRESS, register, COLOURS[colour])
+
+try:
+    with open(PID_FILE) as pid_file:
+        os.kill(int(pid_file.read()), signal.SIGTERM)
+    os.remove(PID_FILE)
+except (OSError, ValueError):
+    pass
+
+status = sys.argv[1]
+if status not in BLINKING:
+    set_leds(status)
+elif os.fork() == 0:
+    # detach from Fernbedienung so that the request completes while the LEDs keep blinking
+    os.setsid()
+    devnull = os.open(os.devnull, os.O_RDWR)
+    for fd in (0, 1, 2):
+        os.dup2(devnull, fd)
+    with open(PID_FILE, 'w') as pid_file:
+        pid_file.write(str(os.getpid()))
+    while True:
+        set_leds(BLINKING[status])
+        time.sleep(0.25)
+        set_leds('off')
+        time.sleep(0.25)
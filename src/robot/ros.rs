@@ -0,0 +1,262 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+use anyhow::Context;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::{net::TcpStream, sync::{broadcast, mpsc, oneshot}, task::JoinHandle};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+pub use shared::ros::{Descriptor, Mapping, Pose, Robot, Update};
+
+/* the time between two attempts to connect to the rosbridge websocket */
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/* the time after which a service call that has not been answered is considered to have failed */
+const SERVICE_TIMEOUT: Duration = Duration::from_secs(10);
+/* the minimum time in milliseconds between two messages of a topic, the throttling is done by rosbridge */
+const THROTTLE_RATE: u64 = 500;
+
+#[derive(Debug)]
+pub enum Action {
+    GetState(oneshot::Sender<Robot>),
+    /* the launch files are started on the robot itself, setting up only checks that the robot is connected */
+    SetupExperiment(oneshot::Sender<anyhow::Result<()>>),
+    StartExperiment(oneshot::Sender<anyhow::Result<()>>),
+    StopExperiment(oneshot::Sender<anyhow::Result<()>>),
+}
+
+pub type Sender = super::Outbound<Action>;
+pub type Receiver = mpsc::Receiver<Action>;
+
+pub struct Instance {
+    pub action_tx: Sender,
+    _task: JoinHandle<()>
+}
+
+impl Instance {
+    pub fn new(descriptor: &Descriptor, updates_tx: broadcast::Sender<Update>) -> Self {
+        let (action_tx, action_rx) = mpsc::channel(8);
+        let action_tx = super::Outbound::new(format!("ros/{}", descriptor.id), action_tx);
+        let _task = crate::instrument::spawn(format!("ros/{}", descriptor.id),
+            new(descriptor.clone(), action_rx, updates_tx));
+        Self {
+            action_tx,
+            _task
+        }
+    }
+}
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Maintains the connection to the rosbridge websocket of a ROS robot, the connection is re-established
+/// whenever it is lost. The task ends once the arena drops the instance of the robot
+async fn new(descriptor: Descriptor, mut action_rx: Receiver, updates_tx: broadcast::Sender<Update>) {
+    let mut robot = Robot::new(descriptor);
+    loop {
+        let connection = tokio::time::timeout(CONNECT_TIMEOUT, tokio_tungstenite::connect_async(&robot.descriptor.url)).await
+            .context("Timeout while connecting to rosbridge")
+            .and_then(|result| result.context("Could not connect to rosbridge"));
+        match connection {
+            Ok((websocket, _)) => {
+                log::info!("Connected to rosbridge of {}", robot.descriptor);
+                robot.connected = true;
+                robot.error = None;
+                let _ = updates_tx.send(Update::Robot(robot.clone()));
+                let result = run(websocket, &mut robot, &mut action_rx, &updates_tx).await;
+                robot.connected = false;
+                robot.battery = None;
+                robot.pose = None;
+                match result {
+                    /* the instance was dropped */
+                    Ok(()) => break,
+                    Err(error) => {
+                        log::warn!("Disconnected from rosbridge of {}: {:#}", robot.descriptor, error);
+                        robot.error = Some(format!("{:#}", error));
+                    }
+                }
+                let _ = updates_tx.send(Update::Robot(robot.clone()));
+            },
+            Err(error) => {
+                let error = format!("{:#}", error);
+                if robot.error.as_ref() != Some(&error) {
+                    log::debug!("{}: {}", robot.descriptor, error);
+                    robot.error = Some(error);
+                    let _ = updates_tx.send(Update::Robot(robot.clone()));
+                }
+            }
+        }
+        /* answer the actions while waiting to reconnect */
+        let reconnect = tokio::time::sleep(RECONNECT_INTERVAL);
+        tokio::pin!(reconnect);
+        loop {
+            tokio::select! {
+                _ = &mut reconnect => break,
+                action = action_rx.recv() => match action {
+                    Some(Action::GetState(callback)) => {
+                        let _ = callback.send(robot.clone());
+                    },
+                    Some(Action::SetupExperiment(callback)) |
+                    Some(Action::StartExperiment(callback)) |
+                    Some(Action::StopExperiment(callback)) => {
+                        let error = anyhow::anyhow!("{} is not connected to rosbridge", robot.descriptor);
+                        let _ = callback.send(Err(error));
+                    },
+                    None => return,
+                }
+            }
+        }
+    }
+}
+
+/// Subscribes to the topics of the robot and handles the actions while connected. This returns an error
+/// if the connection is lost and returns Ok once the action channel has been closed
+async fn run(
+    websocket: WebSocket,
+    robot: &mut Robot,
+    action_rx: &mut Receiver,
+    updates_tx: &broadcast::Sender<Update>,
+) -> anyhow::Result<()> {
+    let (mut sink, mut stream) = websocket.split();
+    for topic in &robot.descriptor.topics {
+        let subscribe = json!({
+            "op": "subscribe",
+            "topic": topic.name,
+            "type": topic.message_type,
+            "throttle_rate": THROTTLE_RATE,
+        });
+        sink.send(Message::Text(subscribe.to_string())).await
+            .context(format!("Could not subscribe to {}", topic.name))?;
+    }
+    /* the pending service calls, the state of the launch files once they succeed, and their deadlines */
+    let mut calls: HashMap<String, (oneshot::Sender<anyhow::Result<()>>, bool, Instant)> = HashMap::new();
+    let mut call_count: u64 = 0;
+    let mut timeouts = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            action = action_rx.recv() => match action {
+                Some(Action::GetState(callback)) => {
+                    let _ = callback.send(robot.clone());
+                },
+                Some(Action::SetupExperiment(callback)) => {
+                    let _ = callback.send(Ok(()));
+                },
+                Some(Action::StartExperiment(callback)) => {
+                    let (id, request) = call_service(&mut call_count, &robot.descriptor.start_service);
+                    sink.send(Message::Text(request)).await
+                        .context(format!("Could not call {}", robot.descriptor.start_service))?;
+                    calls.insert(id, (callback, true, Instant::now() + SERVICE_TIMEOUT));
+                },
+                Some(Action::StopExperiment(callback)) => {
+                    let (id, request) = call_service(&mut call_count, &robot.descriptor.stop_service);
+                    sink.send(Message::Text(request)).await
+                        .context(format!("Could not call {}", robot.descriptor.stop_service))?;
+                    calls.insert(id, (callback, false, Instant::now() + SERVICE_TIMEOUT));
+                },
+                None => return Ok(()),
+            },
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Value>(&text) {
+                    Ok(message) => match message["op"].as_str() {
+                        Some("publish") => {
+                            let mapping = robot.descriptor.topics.iter()
+                                .find(|topic| Some(topic.name.as_str()) == message["topic"].as_str())
+                                .map(|topic| topic.mapping);
+                            if let Some(mapping) = mapping {
+                                if apply(robot, mapping, &message["msg"]) {
+                                    let _ = updates_tx.send(Update::Robot(robot.clone()));
+                                }
+                            }
+                        },
+                        Some("service_response") => {
+                            let call = message["id"].as_str().and_then(|id| calls.remove(id));
+                            if let Some((callback, running, _)) = call {
+                                let result = service_result(&message);
+                                if result.is_ok() {
+                                    robot.running = running;
+                                    let _ = updates_tx.send(Update::Robot(robot.clone()));
+                                }
+                                let _ = callback.send(result);
+                            }
+                        },
+                        Some("status") => log::warn!("rosbridge of {}: {}", robot.descriptor, message["msg"]),
+                        _ => {},
+                    },
+                    Err(error) => log::warn!("Could not decode message from rosbridge of {}: {}", robot.descriptor, error),
+                },
+                Some(Ok(Message::Close(_))) | None => return Err(anyhow::anyhow!("Connection closed")),
+                Some(Ok(_)) => {},
+                Some(Err(error)) => return Err(error).context("Could not receive message"),
+            },
+            _ = timeouts.tick() => {
+                let now = Instant::now();
+                let expired = calls.iter()
+                    .filter(|(_, (_, _, deadline))| *deadline < now)
+                    .map(|(id, _)| id.clone())
+                    .collect::<Vec<_>>();
+                for id in expired {
+                    if let Some((callback, ..)) = calls.remove(&id) {
+                        let _ = callback.send(Err(anyhow::anyhow!("Timeout while calling service")));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A request to call a service of type std_srvs/Trigger and the identifier of the call
+fn call_service(call_count: &mut u64, service: &str) -> (String, String) {
+    *call_count += 1;
+    let id = format!("supervisor:{}", call_count);
+    let request = json!({
+        "op": "call_service",
+        "id": id,
+        "service": service,
+        "args": {},
+    });
+    (id, request.to_string())
+}
+
+/// The result of a call to a service of type std_srvs/Trigger, which reports its success and a message
+fn service_result(message: &Value) -> anyhow::Result<()> {
+    let values = &message["values"];
+    match (message["result"].as_bool(), values["success"].as_bool()) {
+        (Some(false), _) => Err(anyhow::anyhow!("Service {} failed: {}", message["service"], values)),
+        (_, Some(false)) => Err(anyhow::anyhow!("Service {} reported failure: {}",
+            message["service"], values["message"].as_str().unwrap_or_default())),
+        _ => Ok(()),
+    }
+}
+
+/// Maps a message to the state of the robot, returning whether the state has changed
+fn apply(robot: &mut Robot, mapping: Mapping, message: &Value) -> bool {
+    match mapping {
+        Mapping::Battery => {
+            /* the percentage of sensor_msgs/BatteryState is in the range [0, 1] and NaN if unmeasured */
+            let battery = message["percentage"].as_f64()
+                .filter(|percentage| percentage.is_finite())
+                .map(|percentage| (percentage * 100.0) as f32);
+            let changed = robot.battery != battery;
+            robot.battery = battery;
+            changed
+        },
+        Mapping::Pose => {
+            /* nav_msgs/Odometry nests a geometry_msgs/PoseWithCovariance, geometry_msgs/PoseStamped nests
+               a geometry_msgs/Pose */
+            let pose = match (&message["pose"]["pose"], &message["pose"]) {
+                (pose, _) if pose.is_object() => pose,
+                (_, pose) if pose.is_object() => pose,
+                _ => message,
+            };
+            let value = |value: &Value| value.as_f64().unwrap_or_default() as f32;
+            let position = &pose["position"];
+            let orientation = &pose["orientation"];
+            let pose = Some(Pose {
+                position: [value(&position["x"]), value(&position["y"]), value(&position["z"])],
+                orientation: [value(&orientation["w"]), value(&orientation["x"]),
+                    value(&orientation["y"]), value(&orientation["z"])],
+            });
+            let changed = robot.pose != pose;
+            robot.pose = pose;
+            changed
+        },
+    }
+}
@@ -0,0 +1,49 @@
+# Supervises the Fernbedienung service on a robot. The watchdog is deployed by the supervisor when the
+# robot is associated and runs as a transient systemd unit so that it outlives the service it supervises.
+# If the service stops accepting connections, it is restarted and, if that does not help, the robot is
+# rebooted. Each intervention is appended to a log in /var/tmp (which survives reboots) that the supervisor
+# collects and clears on the next association.
+import datetime, socket, subprocess, sys, time
+
+INTERVAL = float(sys.argv[1])
+ATTEMPTS = int(sys.argv[2])
+SERVICE = sys.argv[3]
+PORT = int(sys.argv[4])
+STATUS_LEDS = sys.argv[5]
+LOG = sys.argv[6]
+
+def log(message):
+    timestamp = datetime.datetime.now().isoformat(timespec='seconds')
+    with open(LOG, 'a') as log_file:
+        log_file.write('{} {}\n'.format(timestamp, message))
+
+def responsive():
+    if subprocess.call(['systemctl', '--quiet', 'is-active', SERVICE]) != 0:
+        return False
+    try:
+        with socket.create_connection(('127.0.0.1', PORT), timeout=INTERVAL):
+            return True
+    except OSError:
+        return False
+
+failures = 0
+restarted = False
+while True:
+    time.sleep(INTERVAL)
+    if responsive():
+        failures = 0
+        restarted = False
+        continue
+    failures += 1
+    if failures < ATTEMPTS:
+        continue
+    failures = 0
+    subprocess.call(['python3', STATUS_LEDS, 'alert'])
+    if not restarted:
+        log('{} did not respond to {} checks, restarting the service'.format(SERVICE, ATTEMPTS))
+        subprocess.call(['systemctl', 'restart', SERVICE])
+        restarted = True
+    else:
+        log('{} did not respond after being restarted, rebooting the robot'.format(SERVICE))
+        subprocess.call(['systemctl', 'reboot'])
+        break
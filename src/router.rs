@@ -1,16 +1,20 @@
 use anyhow::{Context, Result};
 use bytes::{BytesMut, Bytes, BufMut, Buf};
-use std::{io, collections::HashMap, sync::Arc, net::SocketAddr};
+use std::{io, collections::HashMap, sync::Arc, net::{IpAddr, SocketAddr}, time::{Duration, Instant}};
 use log;
 use serde::Serialize;
 
 use tokio::{net::{TcpListener, TcpStream}, sync::{Mutex, broadcast, mpsc, oneshot}};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream, errors::BroadcastStreamRecvError};
 use tokio_util::codec::{Decoder, Encoder, Framed};
 use futures::StreamExt;
 
 use std::mem::size_of;
 
+use shared::{builderbot, drone, pipuck, router, simulation, tracking_system};
+
+use crate::{arena, optitrack};
+
 const LUA_TNIL: i8 = 0;
 const LUA_TBOOLEAN: i8 = 1;
 //const LUA_TLIGHTUSERDATA: i8 = 2;
@@ -25,6 +29,11 @@ const LUA_TUSERDATA_VECTOR3: u8 = 2;
 const LUA_TUSERDATA_QUATERNION: u8 = 3;
 const MAX_MANTISSA: f64 = 9223372036854775806.0;
 
+/* the optitrack identifiers and the addresses of the robots can be edited from the web interface and are refreshed periodically */
+const IDENTIFIERS_INTERVAL: Duration = Duration::from_secs(5);
+/* the position of a robot that has not been tracked for longer than this is considered unknown */
+const POSITION_TIMEOUT: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(untagged)]
 pub enum LuaType {
@@ -181,6 +190,19 @@ fn decode_lua_table(buf: &mut impl Buf) -> Result<LuaType> {
     Ok(LuaType::Table(table))
 }
 
+/// Encodes a table with a single entry whose key and value are strings, which is how the supervisor itself
+/// sends messages to the robots
+fn encode_lua_entry(key: &str, value: &str) -> Bytes {
+    let mut buf = BytesMut::with_capacity(key.len() + value.len() + 5);
+    for string in &[key, value] {
+        buf.put_i8(LUA_TSTRING);
+        buf.put_slice(string.as_bytes());
+        buf.put_u8(0);
+    }
+    buf.put_i8(LUA_TNIL);
+    buf.freeze()
+}
+
 #[derive(Debug, Default)]
 struct ByteArrayCodec {
     len: Option<usize>
@@ -225,34 +247,231 @@ impl Encoder<Bytes> for ByteArrayCodec {
     }
 }
 
-type Peers = Arc<Mutex<HashMap<SocketAddr, mpsc::Sender<Bytes>>>>;
+/* messages are forwarded to a peer with the address of the sender and the time at which they were received */
+type Peers = Arc<Mutex<HashMap<SocketAddr, mpsc::Sender<(SocketAddr, Instant, Bytes)>>>>;
+
+/// The messages forwarded between each pair of peers, this is updated by the tasks that write to the
+/// connections of the peers and is therefore not behind the asynchronous mutex
+type Traffic = Arc<std::sync::Mutex<HashMap<(SocketAddr, SocketAddr), Counters>>>;
+
+#[derive(Debug, Default)]
+struct Counters {
+    messages: u64,
+    bytes: u64,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+/// Decides which messages are forwarded according to the communication topology and the communication range.
+/// The peers are resolved to robots via the addresses of the fernbedienung connections of the real robots and
+/// via the registrations of the simulated robots. Until the fernbedienung connection of a real robot has been
+/// established, it is resolved via the address reserved for it in the configuration
+#[derive(Debug, Default)]
+struct Filter {
+    topology: Option<router::Topology>,
+    range: Option<f32>,
+    robots: HashMap<IpAddr, String>,
+    static_robots: HashMap<IpAddr, String>,
+    simulated_robots: HashMap<SocketAddr, String>,
+    /* the robots of the rigid bodies and the last tracked position of each robot */
+    optitrack_ids: HashMap<i32, String>,
+    positions: HashMap<String, ([f32; 3], Instant)>,
+    dropped: u64,
+}
+
+impl Filter {
+    fn resolve(&self, addr: &SocketAddr) -> Option<&str> {
+        self.simulated_robots.get(addr)
+            .or_else(|| self.robots.get(&addr.ip()))
+            .or_else(|| self.static_robots.get(&addr.ip())
+                /* the static address is stale if the robot connected from another address */
+                .filter(|id| !self.robots.values().any(|robot| robot == *id)))
+            .map(String::as_str)
+    }
+
+    /// The addresses of the real robots and whether they were learned or reserved in the configuration
+    fn routes(&self) -> Vec<router::Route> {
+        let learned = self.robots.iter()
+            .map(|(addr, robot)| router::Route {
+                robot: robot.clone(),
+                addr: *addr,
+                origin: router::Origin::Learned
+            });
+        let reserved = self.static_robots.iter()
+            .filter(|(addr, id)| !self.robots.contains_key(*addr) && !self.robots.values().any(|robot| robot == *id))
+            .map(|(addr, robot)| router::Route {
+                robot: robot.clone(),
+                addr: *addr,
+                origin: router::Origin::Static
+            });
+        let mut routes = learned.chain(reserved).collect::<Vec<_>>();
+        routes.sort_by(|left, right| left.robot.cmp(&right.robot));
+        routes
+    }
+
+    /// Whether two robots are within communication range, which is never the case if one of them is not tracked
+    fn within_range(&self, from: &str, to: &str, range: f32) -> bool {
+        let position = |id| self.positions.get(id)
+            .filter(|(_, tracked)| tracked.elapsed() < POSITION_TIMEOUT)
+            .map(|(position, _)| position);
+        match (position(from), position(to)) {
+            (Some(from), Some(to)) => from.iter().zip(to)
+                .map(|(from, to)| (from - to) * (from - to))
+                .sum::<f32>()
+                .sqrt() <= range,
+            _ => false,
+        }
+    }
+
+    /// Whether a message is forwarded from one peer to another, messages from and to peers that can not be
+    /// resolved to a robot are only forwarded if there is neither a topology nor a range
+    fn permits(&mut self, from: &SocketAddr, to: &SocketAddr) -> bool {
+        let permitted = match (&self.topology, self.range) {
+            (None, None) => true,
+            (topology, range) => match (self.resolve(from), self.resolve(to)) {
+                (Some(from), Some(to)) =>
+                    topology.as_ref().map_or(true, |topology| topology.permits(from, to)) &&
+                    range.map_or(true, |range| self.within_range(from, to, range)),
+                _ => false,
+            }
+        };
+        if !permitted {
+            self.dropped += 1;
+        }
+        permitted
+    }
+
+    fn update(&mut self, id: &str, addr: Option<IpAddr>) {
+        self.robots.retain(|_, robot| robot != id);
+        if let Some(addr) = addr {
+            self.robots.insert(addr, id.to_owned());
+        }
+    }
+
+    fn track(&mut self, updates: &[tracking_system::Update]) {
+        let tracked = Instant::now();
+        for update in updates {
+            if let Some(id) = self.optitrack_ids.get(&update.id) {
+                self.positions.insert(id.clone(), (update.position, tracked));
+            }
+        }
+    }
+}
+
+/// The filter is consulted by the tasks that read from the connections of the peers
+type SharedFilter = Arc<std::sync::Mutex<Filter>>;
+
+fn statistics(traffic: &Traffic, filter: &SharedFilter) -> router::Statistics {
+    let (topology, range, dropped, routes) = {
+        let filter = filter.lock().unwrap();
+        (filter.topology.clone(), filter.range, filter.dropped, filter.routes())
+    };
+    let traffic = traffic.lock().unwrap();
+    let mut links = traffic.iter()
+        .map(|(&(from, to), counters)| router::Link {
+            from,
+            to,
+            messages: counters.messages,
+            bytes: counters.bytes,
+            mean_latency: match counters.messages {
+                0 => 0.0,
+                messages => counters.total_latency.as_secs_f64() * 1000.0 / messages as f64,
+            },
+            max_latency: counters.max_latency.as_secs_f64() * 1000.0,
+        })
+        .collect::<Vec<_>>();
+    links.sort_by_key(|link| (link.from, link.to));
+    router::Statistics { links, routes, topology, range, dropped }
+}
+
+/// Returns the identifier of the robot if the message registers a simulated robot
+fn registration(message: &LuaType) -> Option<&str> {
+    match message {
+        LuaType::Table(entries) => match &entries[..] {
+            [(LuaType::String(key), LuaType::String(id))] if key == simulation::REGISTRATION_KEY => Some(id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
+async fn register(arena_tx: &mpsc::Sender<arena::Action>, id: &str, addr: SocketAddr) -> Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let action = arena::Action::RegisterSimulatedRobot { callback: callback_tx, id: id.to_owned(), addr };
+    arena_tx.send(action).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
 
 async fn client_handler(stream: TcpStream,
                         addr: SocketAddr,
                         peers: Peers,
-                        updates_tx: broadcast::Sender<(SocketAddr, LuaType)>) {
+                        traffic: Traffic,
+                        filter: SharedFilter,
+                        updates_tx: broadcast::Sender<(SocketAddr, LuaType)>,
+                        arena_tx: mpsc::Sender<arena::Action>) {
     log::info!("{} connected to message router", addr);
     /* set up a channel for communicating with other robot sockets */
-    let (tx, rx) = mpsc::channel::<Bytes>(32);
+    let (tx, rx) = mpsc::channel::<(SocketAddr, Instant, Bytes)>(32);
     /* wrap up socket in our ByteArrayCodec */
     let (sink, mut stream) = Framed::new(stream, ByteArrayCodec::default()).split();
     {
         peers.lock().await.insert(addr, tx);
     }
     /* send and receive messages concurrently */
-    let mut forward = tokio::spawn(ReceiverStream::new(rx).map(Result::Ok).forward(sink));
+    let forward_traffic = traffic.clone();
+    let mut forward = crate::instrument::spawn(format!("router/{}/forward", addr),
+        ReceiverStream::new(rx).map(move |(from, received, message): (SocketAddr, Instant, Bytes)| {
+            let latency = received.elapsed();
+            let mut traffic = forward_traffic.lock().unwrap();
+            let counters = traffic.entry((from, addr)).or_default();
+            counters.messages += 1;
+            counters.bytes += message.len() as u64;
+            counters.total_latency += latency;
+            counters.max_latency = counters.max_latency.max(latency);
+            Ok(message)
+        }).forward(sink));
+    /* the simulated robot that was registered via this connection */
+    let mut registered: Option<String> = None;
     loop {
         tokio::select! {
             Some(message) = stream.next() => match message {
-                Ok(mut message) => {
-                    for (peer_addr, tx) in peers.lock().await.iter() {
-                        /* do not send messages to the sending robot */   
-                        if peer_addr != &addr {
-                            let _ = tx.send(message.clone()).await;
+                Ok(message) => {
+                    let received = Instant::now();
+                    let decoded = decode_lua_table(&mut message.clone()).ok();
+                    /* registrations of simulated robots are handled by the arena and are not forwarded */
+                    if let Some(id) = decoded.as_ref().and_then(registration) {
+                        match registered.as_deref() {
+                            Some(current) if current == id => {},
+                            Some(current) =>
+                                log::warn!("{} already registered simulated robot {}, ignoring {}", addr, current, id),
+                            None => match register(&arena_tx, id, addr).await {
+                                Ok(_) => {
+                                    log::info!("{} registered simulated robot {}", addr, id);
+                                    filter.lock().unwrap().simulated_robots.insert(addr, id.to_owned());
+                                    registered = Some(id.to_owned());
+                                },
+                                Err(error) => {
+                                    log::warn!("{} could not register simulated robot {}: {:#}", addr, id, error);
+                                    break;
+                                }
+                            }
+                        }
+                        continue;
+                    }
+                    let recipients = peers.lock().await.iter()
+                        /* do not send messages to the sending robot */
+                        .filter(|(peer_addr, _)| *peer_addr != &addr)
+                        .map(|(peer_addr, tx)| (*peer_addr, tx.clone()))
+                        .collect::<Vec<_>>();
+                    for (peer_addr, tx) in recipients {
+                        let permitted = filter.lock().unwrap().permits(&addr, &peer_addr);
+                        if permitted {
+                            let _ = tx.send((addr, received, message.clone())).await;
                         }
                     }
-                    if let Ok(decoded) = decode_lua_table(&mut message) {
+                    if let Some(decoded) = decoded {
                         let _ = updates_tx.send((addr, decoded));
                     }
                 },
@@ -264,19 +483,145 @@ async fn client_handler(stream: TcpStream,
     {
         peers.lock().await.remove(&addr);
     }
+    traffic.lock().unwrap().retain(|&(from, to), _| from != addr && to != addr);
+    filter.lock().unwrap().simulated_robots.remove(&addr);
+    if let Some(id) = registered {
+        let _ = arena_tx.send(arena::Action::UnregisterSimulatedRobot(id)).await;
+    }
     log::info!("{} disconnected from message router", addr);
 }
 
+/// Subscribes to the updates of the robots, which report the addresses of their fernbedienung connections
+async fn subscribe(arena_tx: &mpsc::Sender<arena::Action>, filter: &SharedFilter) -> Result<arena::Subscription> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))?;
+    let subscription = callback_rx.await
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to arena updates"))??;
+    resolve(&subscription, filter);
+    Ok(subscription)
+}
+
+/// Resolves the addresses of the robots that were already connected when subscribing
+fn resolve(subscription: &arena::Subscription, filter: &SharedFilter) {
+    let mut filter = filter.lock().unwrap();
+    for (descriptor, updates) in &subscription.snapshot.builderbots {
+        for update in updates {
+            if let builderbot::Update::FernbedienungConnected(addr) = update {
+                filter.update(&descriptor.id, Some((*addr).into()));
+            }
+        }
+    }
+    for (descriptor, updates) in &subscription.snapshot.drones {
+        for update in updates {
+            if let drone::Update::FernbedienungConnected(addr) = update {
+                filter.update(&descriptor.id, Some((*addr).into()));
+            }
+        }
+    }
+    for (descriptor, updates) in &subscription.snapshot.pipucks {
+        for update in updates {
+            if let pipuck::Update::FernbedienungConnected(addr) = update {
+                filter.update(&descriptor.id, Some((*addr).into()));
+            }
+        }
+    }
+}
+
+/// Maps the optitrack identifiers to the robots, which is required to look up the positions of the robots,
+/// and the addresses reserved for the robots to the robots, which resolves the robots before they connect
+async fn identifiers(arena_tx: &mpsc::Sender<arena::Action>)
+    -> Result<(HashMap<i32, String>, HashMap<IpAddr, String>)> {
+    let (builderbots_tx, builderbots_rx) = oneshot::channel();
+    let (drones_tx, drones_rx) = oneshot::channel();
+    let (pipucks_tx, pipucks_rx) = oneshot::channel();
+    for action in vec![
+        arena::Action::GetBuilderBotDescriptors(builderbots_tx),
+        arena::Action::GetDroneDescriptors(drones_tx),
+        arena::Action::GetPiPuckDescriptors(pipucks_tx),
+    ] {
+        arena_tx.send(action).await
+            .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    }
+    let no_response = |_| anyhow::anyhow!("No response from arena");
+    let builderbots = builderbots_rx.await.map_err(no_response)?;
+    let drones = drones_rx.await.map_err(no_response)?;
+    let pipucks = pipucks_rx.await.map_err(no_response)?;
+    let optitrack_ids = builderbots.iter()
+        .map(|desc| (desc.optitrack_id, &desc.id))
+        .chain(drones.iter().map(|desc| (desc.optitrack_id, &desc.id)))
+        .chain(pipucks.iter().map(|desc| (desc.optitrack_id, &desc.id)))
+        .filter_map(|(optitrack_id, id)| optitrack_id.map(|optitrack_id| (optitrack_id, id.clone())))
+        .collect();
+    let addrs = builderbots.iter()
+        .map(|desc| (desc.duovero_addr, &desc.id))
+        .chain(drones.iter().map(|desc| (desc.upcore_addr, &desc.id)))
+        .chain(pipucks.iter().map(|desc| (desc.rpi_addr, &desc.id)))
+        .filter_map(|(addr, id)| addr.map(|addr| (IpAddr::V4(addr), id.clone())))
+        .collect();
+    Ok((optitrack_ids, addrs))
+}
+
+/// Subscribes to the tracking system, which reports the positions of the rigid bodies of the robots
+async fn track(optitrack_tx: &mpsc::Sender<optitrack::Action>)
+    -> Result<broadcast::Receiver<(i32, Vec<tracking_system::Update>, tracking_system::Timestamps)>> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    optitrack_tx.send(optitrack::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with the optitrack task"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates"))
+}
+
 pub enum Action {
     Subscribe(oneshot::Sender<broadcast::Receiver<(SocketAddr, LuaType)>>),
+    /* the messages forwarded between the peers that are currently connected */
+    GetStatistics(oneshot::Sender<router::Statistics>),
+    /* replace the communication topology, all peers communicate if None */
+    SetTopology(Option<router::Topology>),
+    /* replace the communication range in meters, robots communicate regardless of their distance if None */
+    SetRange(Option<f32>),
+    /* send a command from the supervisor to all peers regardless of the topology and the range */
+    Broadcast(String),
 }
 
-pub async fn new(addr: SocketAddr, mut requests_rx: mpsc::Receiver<Action>) -> io::Result<()> {
+pub async fn new(
+    addr: SocketAddr,
+    topology: Option<router::Topology>,
+    range: Option<f32>,
+    requests_rx: &mut mpsc::Receiver<Action>,
+    arena_tx: mpsc::Sender<arena::Action>,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+) -> io::Result<()> {
     
     let listener = TcpListener::bind(addr).await?;
-    log::info!("Message router running on: {:?}", listener.local_addr());
+    let local_addr = listener.local_addr()?;
+    log::info!("Message router running on: {:?}", local_addr);
     /* create an atomic map of all peers */
     let peers = Peers::default();
+    let traffic = Traffic::default();
+    let filter = SharedFilter::new(std::sync::Mutex::new(Filter { topology, range, ..Default::default() }));
+    /* the addresses of the robots are only required to resolve the peers in a topology */
+    let (builderbot_updates, drone_updates, pipuck_updates) = match subscribe(&arena_tx, &filter).await {
+        Ok(subscription) => (subscription.builderbots, subscription.drones, subscription.pipucks),
+        Err(error) => {
+            log::error!("The message router can not resolve the addresses of the robots: {:#}", error);
+            Default::default()
+        }
+    };
+    tokio::pin!(builderbot_updates);
+    tokio::pin!(drone_updates);
+    tokio::pin!(pipuck_updates);
+    /* the positions of the robots are only required to check whether they are within range */
+    let frames = match track(&optitrack_tx).await {
+        Ok(frames) => Some(BroadcastStream::new(frames)),
+        Err(error) => {
+            log::error!("The message router can not track the positions of the robots: {:#}", error);
+            None
+        }
+    };
+    let frames = futures::stream::iter(frames).flatten();
+    tokio::pin!(frames);
+    let mut identifiers_interval = tokio::time::interval(IDENTIFIERS_INTERVAL);
     /* update channel (for the journal) */
     let (updates_tx, _) = broadcast::channel(32);
     /* start the main loop */
@@ -285,8 +630,11 @@ pub async fn new(addr: SocketAddr, mut requests_rx: mpsc::Receiver<Action>) -> i
             result = listener.accept() => match result {
                 Ok((stream, addr)) => {
                     let peers = Arc::clone(&peers);
+                    let traffic = Arc::clone(&traffic);
+                    let filter = Arc::clone(&filter);
                     /* spawn a handler for the newly connected client */
-                    tokio::spawn(client_handler(stream, addr, peers, updates_tx.clone()));
+                    crate::instrument::spawn(format!("router/{}", addr),
+                        client_handler(stream, addr, peers, traffic, filter, updates_tx.clone(), arena_tx.clone()));
                 }
                 Err(err) => {
                     log::error!("Error accepting incoming connection: {}", err);
@@ -297,9 +645,89 @@ pub async fn new(addr: SocketAddr, mut requests_rx: mpsc::Receiver<Action>) -> i
                     Action::Subscribe(callback) => {
                         let _ = callback.send(updates_tx.subscribe());
                     },
+                    Action::GetStatistics(callback) => {
+                        let _ = callback.send(statistics(&traffic, &filter));
+                    },
+                    Action::SetTopology(update) => {
+                        match &update {
+                            Some(update) => log::info!("Message router topology set for {} robots", update.neighbors.len()),
+                            None => log::info!("Message router topology cleared"),
+                        }
+                        let mut filter = filter.lock().unwrap();
+                        filter.topology = update;
+                        filter.dropped = 0;
+                    },
+                    Action::SetRange(update) => {
+                        match update {
+                            Some(update) => log::info!("Message router range set to {} m", update),
+                            None => log::info!("Message router range cleared"),
+                        }
+                        let mut filter = filter.lock().unwrap();
+                        filter.range = update;
+                        filter.dropped = 0;
+                    },
+                    Action::Broadcast(command) => {
+                        let message = encode_lua_entry(router::COMMAND_KEY, &command);
+                        let recipients = peers.lock().await.values().cloned().collect::<Vec<_>>();
+                        log::info!("Message router broadcasting command \"{}\" to {} peers", command, recipients.len());
+                        for tx in recipients {
+                            let _ = tx.send((local_addr, Instant::now(), message.clone())).await;
+                        }
+                        /* the command is recorded in the journal like the messages between the robots */
+                        let decoded = LuaType::Table(vec![
+                            (LuaType::String(router::COMMAND_KEY.to_owned()), LuaType::String(command))
+                        ]);
+                        let _ = updates_tx.send((local_addr, decoded));
+                    },
                 },
                 None => break,
-            }
+            },
+            Some((descriptor, update)) = builderbot_updates.next() => match update {
+                Ok(builderbot::Update::FernbedienungConnected(addr)) =>
+                    filter.lock().unwrap().update(&descriptor.id, Some(addr.into())),
+                Ok(builderbot::Update::FernbedienungDisconnected) =>
+                    filter.lock().unwrap().update(&descriptor.id, None),
+                Ok(_) => {},
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Message router missed {} messages for {}", count, descriptor),
+            },
+            Some((descriptor, update)) = drone_updates.next() => match update {
+                Ok(drone::Update::FernbedienungConnected(addr)) =>
+                    filter.lock().unwrap().update(&descriptor.id, Some(addr.into())),
+                Ok(drone::Update::FernbedienungDisconnected) =>
+                    filter.lock().unwrap().update(&descriptor.id, None),
+                Ok(_) => {},
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Message router missed {} messages for {}", count, descriptor),
+            },
+            Some((descriptor, update)) = pipuck_updates.next() => match update {
+                Ok(pipuck::Update::FernbedienungConnected(addr)) =>
+                    filter.lock().unwrap().update(&descriptor.id, Some(addr.into())),
+                Ok(pipuck::Update::FernbedienungDisconnected) =>
+                    filter.lock().unwrap().update(&descriptor.id, None),
+                Ok(_) => {},
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Message router missed {} messages for {}", count, descriptor),
+            },
+            Some(frame) = frames.next() => match frame {
+                Ok((_, updates, _)) => filter.lock().unwrap().track(&updates),
+                Err(BroadcastStreamRecvError::Lagged(count)) =>
+                    log::warn!("Message router missed {} frames from the tracking system", count),
+            },
+            _ = identifiers_interval.tick() => {
+                let arena_tx = arena_tx.clone();
+                let filter = Arc::clone(&filter);
+                crate::instrument::spawn("router/identifiers", async move {
+                    match identifiers(&arena_tx).await {
+                        Ok((optitrack_ids, addrs)) => {
+                            let mut filter = filter.lock().unwrap();
+                            filter.optitrack_ids = optitrack_ids;
+                            filter.static_robots = addrs;
+                        },
+                        Err(error) => log::warn!("The message router can not resolve the robots: {:#}", error),
+                    }
+                });
+            },
         }
     }
     Ok(())
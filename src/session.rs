@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use shared::access::{self, Session};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use uuid::Uuid;
+
+pub enum Action {
+    /* note that the current sessions are not sent upon subscribing, a client receives them once its own
+       session begins */
+    Subscribe(oneshot::Sender<broadcast::Receiver<Vec<Session>>>),
+    /* a client has connected to the web interface with the given identifier for its session */
+    Begin(Uuid, access::Update),
+    /* a client has logged in with a different token */
+    Update(Uuid, access::Update),
+    End(Uuid),
+}
+
+/// Keeps the sessions of the clients that are connected to the web interface, which are sent to the
+/// subscribers whenever a client connects, disconnects, or logs in
+pub async fn new(mut requests: mpsc::Receiver<Action>) {
+    let (updates_tx, _) = broadcast::channel(8);
+    let mut sessions: BTreeMap<Uuid, Session> = BTreeMap::new();
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Subscribe(callback) => {
+                let _ = callback.send(updates_tx.subscribe());
+            },
+            Action::Begin(id, client) => {
+                let session = Session {
+                    id,
+                    user: client.user,
+                    role: client.role,
+                    connected: chrono::Local::now().to_rfc3339(),
+                };
+                log::info!("Client connected as {}", describe(&session));
+                sessions.insert(id, session);
+                let _ = updates_tx.send(sorted(&sessions));
+            },
+            Action::Update(id, client) => if let Some(session) = sessions.get_mut(&id) {
+                session.user = client.user;
+                session.role = client.role;
+                log::info!("Client logged in as {}", describe(session));
+                let _ = updates_tx.send(sorted(&sessions));
+            },
+            Action::End(id) => if let Some(session) = sessions.remove(&id) {
+                log::info!("Client {} disconnected", describe(&session));
+                let _ = updates_tx.send(sorted(&sessions));
+            },
+        }
+    }
+}
+
+fn sorted(sessions: &BTreeMap<Uuid, Session>) -> Vec<Session> {
+    let mut sessions = sessions.values().cloned().collect::<Vec<_>>();
+    sessions.sort_by(|left, right| left.connected.cmp(&right.connected));
+    sessions
+}
+
+fn describe(session: &Session) -> String {
+    match &session.user {
+        Some(user) => format!("{} ({})", user, session.role.name()),
+        None => session.role.name().to_owned(),
+    }
+}
@@ -0,0 +1,60 @@
+use std::{io::ErrorKind, process::Stdio};
+use shared::experiment::software::Software;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Checks the ARGoS configuration, the sizes of the files, and the syntax of the Lua scripts of the software so
+/// that trivial typos are caught before any robot is touched. The scripts are only parsed using `luac -p` and each error is reported
+/// with the file and the line on which it occurred. The scripts are not checked if `luac` is not installed
+pub async fn check(software: &Software) -> anyhow::Result<()> {
+    let mut errors = software.validate().into_iter()
+        .map(|(_, error)| error.to_string())
+        .collect::<Vec<_>>();
+    let scripts = software.0.iter()
+        .filter(|(filename, _)| filename.ends_with(".lua"));
+    for (filename, contents) in scripts {
+        match luac(filename, contents).await {
+            Ok(Some(error)) => errors.push(error),
+            Ok(None) => {},
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                log::warn!("Could not check the syntax of the Lua scripts: luac is not installed");
+                break;
+            },
+            Err(error) => log::warn!("Could not check the syntax of {}: {}", filename, error),
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(anyhow::anyhow!("{}", errors.join("\n"))),
+    }
+}
+
+/// Parses a Lua script with `luac -p`, returning the syntax error if there is one
+async fn luac(filename: &str, contents: &[u8]) -> std::io::Result<Option<String>> {
+    let mut luac = Command::new("luac")
+        .args(&["-p", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    if let Some(mut stdin) = luac.stdin.take() {
+        /* luac stops reading at the first syntax error */
+        if let Err(error) = stdin.write_all(contents).await {
+            if error.kind() != ErrorKind::BrokenPipe {
+                return Err(error);
+            }
+        }
+    }
+    let output = luac.wait_with_output().await?;
+    match output.status.success() {
+        true => Ok(None),
+        false => {
+            /* the errors are reported as "luac: stdin:12: message" */
+            let error = String::from_utf8_lossy(&output.stderr);
+            let error = error.trim()
+                .trim_start_matches("luac: ")
+                .replacen("stdin:", &format!("{}:", filename), 1);
+            Ok(Some(error))
+        }
+    }
+}
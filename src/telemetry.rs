@@ -0,0 +1,182 @@
+use std::collections::{HashMap, VecDeque};
+use futures::StreamExt;
+use shared::{configuration::Telemetry, telemetry::History};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+use crate::{arena, robot::{builderbot, drone, pipuck}};
+
+/* the maximum number of bytes of the output of each terminal that are kept per robot */
+const TERMINAL_LENGTH: usize = 16 * 1024;
+
+pub enum Action {
+    /* the recent readings of the robot with the given identifier */
+    History(String, oneshot::Sender<History>),
+}
+
+#[derive(Default)]
+struct Record {
+    battery: VecDeque<(i64, i32)>,
+    signal: VecDeque<(i64, i32)>,
+    xbee_signal: VecDeque<(i64, i32)>,
+    bash: VecDeque<(i64, String)>,
+    mavlink: VecDeque<(i64, String)>,
+}
+
+enum Reading {
+    Battery(i32),
+    Signal(i32),
+    XbeeSignal(i32),
+    Bash(String),
+    Mavlink(String),
+}
+
+impl Reading {
+    fn builderbot(update: &builderbot::Update) -> Option<Reading> {
+        match update {
+            builderbot::Update::Battery(level) => Some(Reading::Battery(*level)),
+            builderbot::Update::FernbedienungSignal(strength) => Some(Reading::Signal(*strength)),
+            builderbot::Update::Bash(output) => Some(Reading::Bash(output.clone())),
+            _ => None,
+        }
+    }
+
+    fn drone(update: &drone::Update) -> Option<Reading> {
+        match update {
+            drone::Update::Battery(level) => Some(Reading::Battery(*level)),
+            drone::Update::FernbedienungSignal(strength) => Some(Reading::Signal(*strength)),
+            drone::Update::XbeeSignal(strength) => Some(Reading::XbeeSignal(*strength)),
+            drone::Update::Bash(output) => Some(Reading::Bash(output.clone())),
+            drone::Update::Mavlink(output) => Some(Reading::Mavlink(output.clone())),
+            _ => None,
+        }
+    }
+
+    fn pipuck(update: &pipuck::Update) -> Option<Reading> {
+        match update {
+            pipuck::Update::Battery(level) => Some(Reading::Battery(*level)),
+            pipuck::Update::FernbedienungSignal(strength) => Some(Reading::Signal(*strength)),
+            pipuck::Update::Bash(output) => Some(Reading::Bash(output.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Records a reading of a robot and discards the readings that are older than the retention period in milliseconds
+fn record(records: &mut HashMap<String, Record>, retention: i64, id: &str, reading: Reading) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let record = records.entry(id.to_owned()).or_default();
+    match reading {
+        Reading::Battery(level) => record.battery.push_back((now, level)),
+        Reading::Signal(strength) => record.signal.push_back((now, strength)),
+        Reading::XbeeSignal(strength) => record.xbee_signal.push_back((now, strength)),
+        Reading::Bash(output) => record.bash.push_back((now, output)),
+        Reading::Mavlink(output) => record.mavlink.push_back((now, output)),
+    }
+    let expired = now - retention;
+    for readings in vec![&mut record.battery, &mut record.signal, &mut record.xbee_signal] {
+        while readings.front().map_or(false, |(time, _)| *time < expired) {
+            readings.pop_front();
+        }
+    }
+    for output in vec![&mut record.bash, &mut record.mavlink] {
+        let mut length = output.iter().map(|(_, output)| output.len()).sum::<usize>();
+        while let Some((time, oldest)) = output.front() {
+            if *time >= expired && length <= TERMINAL_LENGTH {
+                break;
+            }
+            length -= oldest.len();
+            output.pop_front();
+        }
+    }
+}
+
+/// The recent readings of a robot, which are empty if nothing has been recorded for the robot
+fn history(records: &HashMap<String, Record>, id: &str) -> History {
+    match records.get(id) {
+        Some(record) => History {
+            battery: record.battery.iter().cloned().collect(),
+            signal: record.signal.iter().cloned().collect(),
+            xbee_signal: record.xbee_signal.iter().cloned().collect(),
+            bash: record.bash.iter().map(|(_, output)| output.as_str()).collect(),
+            mavlink: record.mavlink.iter().map(|(_, output)| output.as_str()).collect(),
+        },
+        None => History::default(),
+    }
+}
+
+/// Keeps the recent battery levels, signal strengths, and terminal output of the robots in memory, so that
+/// the web interface can show them to clients that open the card of a robot after the readings were sent
+pub async fn new(configuration: Telemetry, arena_tx: mpsc::Sender<arena::Action>, mut requests: mpsc::Receiver<Action>) {
+    let retention = configuration.retention as i64 * 1000;
+    let mut records: HashMap<String, Record> = HashMap::new();
+    /* the subscription is renewed when robots are added or removed so that their updates are received */
+    loop {
+        let (callback_tx, callback_rx) = oneshot::channel();
+        let subscription = match arena_tx.send(arena::Action::Subscribe(callback_tx)).await {
+            Ok(_) => callback_rx.await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Could not subscribe to arena updates"))),
+            Err(_) => Err(anyhow::anyhow!("Could not communicate with arena")),
+        };
+        let arena::Subscription { snapshot, builderbots: builderbot_updates, drones: drone_updates, pipucks: pipuck_updates, mut roster, .. } =
+            match subscription {
+                Ok(subscription) => subscription,
+                Err(error) => {
+                    log::error!("Could not initialize telemetry: {:#}", error);
+                    return;
+                }
+            };
+        /* record the last readings of the robots */
+        for (desc, updates) in snapshot.builderbots {
+            for reading in updates.iter().filter_map(Reading::builderbot) {
+                record(&mut records, retention, &desc.id, reading);
+            }
+        }
+        for (desc, updates) in snapshot.drones {
+            for reading in updates.iter().filter_map(Reading::drone) {
+                record(&mut records, retention, &desc.id, reading);
+            }
+        }
+        for (desc, updates) in snapshot.pipucks {
+            for reading in updates.iter().filter_map(Reading::pipuck) {
+                record(&mut records, retention, &desc.id, reading);
+            }
+        }
+        tokio::pin!(builderbot_updates);
+        tokio::pin!(drone_updates);
+        tokio::pin!(pipuck_updates);
+        loop {
+            tokio::select! {
+                Some((descriptor, update)) = builderbot_updates.next() => match update {
+                    Ok(update) => if let Some(reading) = Reading::builderbot(&update) {
+                        record(&mut records, retention, &descriptor.id, reading);
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Telemetry missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = drone_updates.next() => match update {
+                    Ok(update) => if let Some(reading) = Reading::drone(&update) {
+                        record(&mut records, retention, &descriptor.id, reading);
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Telemetry missed {} messages for {}", count, descriptor),
+                },
+                Some((descriptor, update)) = pipuck_updates.next() => match update {
+                    Ok(update) => if let Some(reading) = Reading::pipuck(&update) {
+                        record(&mut records, retention, &descriptor.id, reading);
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) =>
+                        log::warn!("Telemetry missed {} messages for {}", count, descriptor),
+                },
+                Some(action) = requests.recv() => match action {
+                    Action::History(id, callback) => {
+                        let _ = callback.send(history(&records, &id));
+                    },
+                },
+                /* subscribe again to receive the updates of the added robots */
+                Ok(_) = roster.recv() => break,
+                else => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{collections::VecDeque, future::Future, time::Instant};
+use shared::trace::{Span, Trace};
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+/* the number of requests whose traces are kept */
+const CAPACITY: usize = 256;
+
+pub enum Action {
+    /* starts a trace, the instant at which the request was received is used to measure the spans */
+    Begin(Instant, Trace),
+    /* records a span of the trace with the given identifier, which ended at the given instant */
+    Record(Uuid, Instant, String, String),
+    Get(Uuid, oneshot::Sender<Option<Trace>>),
+}
+
+/// The trace of a request, which is passed along with the actions that were caused by the request so
+/// that the components that handle these actions can record their spans
+#[derive(Clone, Debug)]
+pub struct Handle {
+    pub id: Uuid,
+    /* the channel is unbounded since the spans are also recorded from synchronous code */
+    trace_tx: mpsc::UnboundedSender<Action>,
+}
+
+impl Handle {
+    pub fn new(id: Uuid, trace_tx: mpsc::UnboundedSender<Action>) -> Self {
+        Handle { id, trace_tx }
+    }
+}
+
+tokio::task_local! {
+    /* the trace of the request that is being handled by the current task */
+    static CURRENT: Handle;
+}
+
+/// Keeps the traces of the most recent requests, the oldest trace is discarded once the capacity has
+/// been reached
+pub async fn new(mut requests: mpsc::UnboundedReceiver<Action>) {
+    let mut traces: VecDeque<(Instant, Trace)> = VecDeque::with_capacity(CAPACITY);
+    while let Some(action) = requests.recv().await {
+        match action {
+            Action::Begin(received, trace) => {
+                if traces.len() == CAPACITY {
+                    traces.pop_front();
+                }
+                traces.push_back((received, trace));
+            },
+            Action::Record(id, instant, component, description) => {
+                if let Some((received, trace)) = traces.iter_mut().rev().find(|(_, trace)| trace.id == id) {
+                    trace.spans.push(Span {
+                        elapsed: instant.saturating_duration_since(*received).as_secs_f64() * 1000.0,
+                        component,
+                        description,
+                    });
+                }
+            },
+            Action::Get(id, callback) => {
+                let trace = traces.iter()
+                    .find(|(_, trace)| trace.id == id)
+                    .map(|(_, trace)| trace.clone());
+                let _ = callback.send(trace);
+            },
+        }
+    }
+}
+
+/// Starts the trace of a request from the user interface
+pub fn begin(trace: &Handle, request: String) {
+    log::debug!("{} received: {}", trace.id, request);
+    let received = Trace {
+        id: trace.id,
+        request,
+        received: chrono::Local::now().to_rfc3339(),
+        spans: Vec::new(),
+    };
+    let _ = trace.trace_tx.send(Action::Begin(Instant::now(), received));
+}
+
+/// Records that a component has handled a request, this is also logged so that the logs of the
+/// components can be correlated by the identifier of the trace
+pub fn record(trace: &Handle, component: &str, description: impl Into<String>) {
+    let description = description.into();
+    log::debug!("{} {}: {}", trace.id, component, description);
+    let _ = trace.trace_tx.send(Action::Record(trace.id, Instant::now(), component.to_owned(), description));
+}
+
+pub async fn get(trace_tx: &mpsc::UnboundedSender<Action>, id: Uuid) -> Option<Trace> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    trace_tx.send(Action::Get(id, callback_tx)).ok()?;
+    callback_rx.await.ok().flatten()
+}
+
+/// The trace of the request that is being handled by the current task
+pub fn current() -> Option<Handle> {
+    CURRENT.try_with(Handle::clone).ok()
+}
+
+/// Handles a request within its trace so that the actions that are sent to other tasks can be traced
+pub async fn scope<F: Future>(trace: Handle, future: F) -> F::Output {
+    CURRENT.scope(trace, future).await
+}
+
+/// Handles a request within its trace if it has one, so that the requests to the fernbedienung service
+/// can be traced
+pub async fn within<F: Future>(trace: Option<Handle>, future: F) -> F::Output {
+    match trace {
+        Some(trace) => scope(trace, future).await,
+        None => future.await,
+    }
+}
+
+/// Wraps the callback of an action so that its completion is recorded in the trace
+pub fn callback<T: Send + 'static>(
+    trace: Option<Handle>,
+    component: String,
+    callback: oneshot::Sender<anyhow::Result<T>>,
+) -> oneshot::Sender<anyhow::Result<T>> {
+    let trace = match trace {
+        Some(trace) => trace,
+        None => return callback,
+    };
+    let (traced_tx, traced_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        match traced_rx.await {
+            Ok(result) => {
+                match &result {
+                    Ok(_) => record(&trace, &component, "Completed"),
+                    Err(error) => record(&trace, &component, format!("Failed: {:#}", error)),
+                }
+                let _ = callback.send(result);
+            },
+            Err(_) => record(&trace, &component, "Dropped without a response"),
+        }
+    });
+    traced_tx
+}
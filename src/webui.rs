@@ -1,25 +1,69 @@
 use anyhow::Context;
-use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt, TryStreamExt, stream::{self, FuturesUnordered}};
-use shared::{BackEndRequest, DownMessage, FrontEndRequest, UpMessage, tracking_system};
-use std::{net::SocketAddr, ops::Deref, sync::Arc};
-use tokio::{self, sync::{mpsc, oneshot}};
-use tokio_stream::{StreamMap, wrappers::{BroadcastStream, errors::BroadcastStreamRecvError}};
+use futures::{FutureExt, SinkExt, StreamExt, TryFutureExt, TryStreamExt};
+use shared::{BackEndRequest, DownMessage, FrontEndRequest, UpMessage, access::{self, Access, Category, Role}, association, experiment, maintenance, ros, simulation, snapshot::Snapshot, subscription::{self, Capability}, tracking_system};
+use ipnet::Ipv4Net;
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, net::{IpAddr, SocketAddr, UdpSocket}};
+use tokio::{self, sync::{mpsc, oneshot, watch}};
+use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 use warp::Filter;
 use uuid::Uuid;
 
-use crate::{arena, optitrack, robot::{self, builderbot, drone, pipuck}};
+use crate::{alerts, ambient, arena, configuration, health, idempotency, identification, infrastructure, inventory, journal, library, optitrack, pairing, profiles, replication, robot::{self, builderbot, drone, pipuck}, router, session, syntax, telemetry, trace};
+use crate::network::fernbedienung_ext::Results;
 
 // down message (from backend to the client)
 // up message (from client to the backend)
 
+/* the service type under which the web interface is advertised, this must match the desktop client */
+pub const SERVICE_TYPE: &'static str = "_supervisor._tcp.local.";
+
 /* embed the client js and wasm into this binary */
 const CLIENT_WASM_BYTES: &'static [u8] = include_bytes!(env!("CLIENT_WASM"));
 const CLIENT_JS_BYTES: &'static [u8] = include_bytes!(env!("CLIENT_JS"));
 
+/* the interval at which the traffic statistics of the message router are polled for each client */
+const ROUTER_STATISTICS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Advertises the web interface via mDNS so that the desktop client can discover it. If the web interface
+/// is bound to all interfaces, the address of the interface that faces the robot network is advertised
+pub fn advertise(name: &str, socket: SocketAddr, robot_network: Ipv4Net) -> anyhow::Result<mdns_sd::ServiceDaemon> {
+    let addr = match socket.ip() {
+        IpAddr::V4(addr) if addr.is_loopback() =>
+            return Err(anyhow::anyhow!("The web interface is only accessible from this machine")),
+        IpAddr::V4(addr) if addr.is_unspecified() => {
+            let gateway = robot_network.hosts().next()
+                .ok_or(anyhow::anyhow!("Robot network {} does not contain any hosts", robot_network))?;
+            let socket = UdpSocket::bind("0.0.0.0:0")?;
+            socket.connect((gateway, 80))?;
+            socket.local_addr()?.ip()
+        },
+        addr => addr,
+    };
+    let host_name = format!("supervisor-{}.local.", addr.to_string().replace(|c: char| !c.is_ascii_alphanumeric(), "-"));
+    let properties = vec![
+        ("version".to_owned(), env!("CARGO_PKG_VERSION").to_owned()),
+    ].into_iter().collect::<HashMap<_, _>>();
+    let service = mdns_sd::ServiceInfo::new(SERVICE_TYPE, name, &host_name, addr.to_string().as_str(), socket.port(), Some(properties))
+        .map_err(|error| anyhow::anyhow!("Could not create service: {}", error))?;
+    let daemon = mdns_sd::ServiceDaemon::new()
+        .map_err(|error| anyhow::anyhow!("Could not start mDNS daemon: {}", error))?;
+    daemon.register(service)
+        .map_err(|error| anyhow::anyhow!("Could not register service: {}", error))?;
+    log::info!("Advertising the web interface as \"{}\" at {}:{}", name, addr, socket.port());
+    Ok(daemon)
+}
+
 pub async fn new(
     server_addr: SocketAddr,
-    arena_tx: mpsc::Sender<arena::Action>,
-    optitrack_tx: mpsc::Sender<optitrack::Action>
+    components: Components,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    infrastructure_tx: mpsc::Sender<infrastructure::Action>,
+    ambient_tx: mpsc::Sender<ambient::Action>,
+    alerts_tx: mpsc::Sender<alerts::Action>,
+    idempotency_tx: mpsc::Sender<idempotency::Action>,
+    health_tx: mpsc::Sender<health::Action>,
+    session_tx: mpsc::Sender<session::Action>,
+    access: Option<Access>,
 ) {
     /* start the server */
     let wasm_route = warp::path("client_bg.wasm")
@@ -28,50 +72,461 @@ pub async fn new(
     let js_route = warp::path("client.js")
         .and(warp::path::end())
         .map(|| warp::reply::with_header(CLIENT_JS_BYTES, "content-type", "application/javascript"));
-    let arena_tx = warp::any().map(move || arena_tx.clone());
+    /* the components are passed as a single filter since warp only accepts up to sixteen arguments */
+    let inventory_tx = components.inventory_tx.clone();
+    let inventory_tx = warp::any().map(move || inventory_tx.clone());
+    let components = warp::any().map(move || components.clone());
     let optitrack_tx = warp::any().map(move || optitrack_tx.clone());
+    let infrastructure_tx = warp::any().map(move || infrastructure_tx.clone());
+    let ambient_tx = warp::any().map(move || ambient_tx.clone());
+    let alerts_tx = warp::any().map(move || alerts_tx.clone());
+    let idempotency_tx = warp::any().map(move || idempotency_tx.clone());
+    let health_tx = warp::any().map(move || health_tx.clone());
+    let session_tx = warp::any().map(move || session_tx.clone());
+    let access = warp::any().map(move || access.clone());
+    let inventory_route = warp::path("inventory")
+        .and(warp::path::param::<inventory::Format>())
+        .and(warp::path::end())
+        .and(inventory_tx)
+        .and_then(|format: inventory::Format, inventory_tx: mpsc::Sender<inventory::Action>| async move {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            let export = inventory_tx.send(inventory::Action::Export(callback_tx, format))
+                .map_err(|_| anyhow::anyhow!("Could not communicate with inventory"))
+                .and_then(|_| callback_rx
+                    .map(|result| result.context("Could not export inventory")))
+                .await
+                .and_then(|result| result);
+            let reply: Box<dyn warp::Reply> = match export {
+                Ok(contents) => {
+                    let disposition = format!("attachment; filename=\"robots.{}\"", format.extension());
+                    let reply = warp::reply::with_header(contents, "content-type", format.content_type());
+                    Box::new(warp::reply::with_header(reply, "content-disposition", disposition))
+                },
+                Err(error) => {
+                    log::warn!("{:#}", error);
+                    Box::new(warp::reply::with_status(format!("{:#}", error),
+                        warp::http::StatusCode::INTERNAL_SERVER_ERROR))
+                }
+            };
+            Result::<_, std::convert::Infallible>::Ok(reply)
+        });
     let socket_route = warp::path("socket")
         .and(warp::path::end())
         .and(warp::ws())
-        .and(arena_tx)
+        .and(warp::query::<HashMap<String, String>>())
+        .and(access)
+        .and(components)
         .and(optitrack_tx)
-        .map(|websocket: warp::ws::Ws, arena_tx, optitrack_tx| {
-            websocket.on_upgrade(move |socket| handle_client(socket, arena_tx, optitrack_tx))
+        .and(infrastructure_tx)
+        .and(ambient_tx)
+        .and(alerts_tx)
+        .and(idempotency_tx)
+        .and(health_tx)
+        .and(session_tx)
+        .map(|websocket: warp::ws::Ws, query: HashMap<String, String>, access: Option<Access>, components, optitrack_tx, infrastructure_tx, ambient_tx, alerts_tx, idempotency_tx, health_tx, session_tx| {
+            /* the client passes its token as a query parameter when connecting */
+            let client = authenticate(access.as_ref(), query.get("token").map(String::as_str));
+            websocket.on_upgrade(move |socket|
+                handle_client(socket, client, access, components, optitrack_tx, infrastructure_tx, ambient_tx, alerts_tx, idempotency_tx, health_tx, session_tx))
         });
     let static_route = warp::get()
         .and(static_dir::static_dir!("client/public/"));
-    warp::serve(js_route.or(wasm_route).or(socket_route).or(static_route))
+    warp::serve(js_route.or(wasm_route).or(inventory_route).or(socket_route).or(static_route))
         .run(server_addr).await   
 }
 
+/// The channels to the components that handle the requests of the clients, these are shared by the web
+/// interface and the gRPC interface
+#[derive(Clone)]
+pub struct Components {
+    pub arena_tx: mpsc::Sender<arena::Action>,
+    pub journal_tx: mpsc::Sender<journal::Action>,
+    pub configuration_tx: mpsc::Sender<configuration::Action>,
+    pub inventory_tx: mpsc::Sender<inventory::Action>,
+    pub router_tx: mpsc::Sender<router::Action>,
+    pub identification_tx: mpsc::Sender<identification::Action>,
+    pub pairing_tx: mpsc::Sender<pairing::Action>,
+    pub profiles_tx: mpsc::Sender<profiles::Action>,
+    pub library_tx: mpsc::Sender<library::Action>,
+    pub replication_tx: mpsc::Sender<replication::Action>,
+    pub telemetry_tx: mpsc::Sender<telemetry::Action>,
+    pub trace_tx: mpsc::UnboundedSender<trace::Action>,
+}
+
+/// Handles a request that does not depend on the connection that it was received on, i.e., every request
+/// except for subscription and login requests. Some requests are answered with an update, e.g., a snapshot
+/// or a trace, which is returned to the caller. The permissions of the client must be checked beforehand
+pub async fn dispatch(components: &Components, request: BackEndRequest) -> anyhow::Result<Option<FrontEndRequest>> {
+    let Components { arena_tx, journal_tx, configuration_tx, inventory_tx, router_tx, identification_tx, pairing_tx, profiles_tx, library_tx, replication_tx, telemetry_tx, trace_tx } =
+        components;
+    match request {
+        BackEndRequest::BuilderBotRequest(id, request) =>
+            handle_builderbot_request(arena_tx, journal_tx, id, request).await.map(|_| None),
+        BackEndRequest::DroneRequest(id, request) =>
+            handle_drone_request(arena_tx, journal_tx, id, request).await.map(|_| None),
+        BackEndRequest::PiPuckRequest(id, request) =>
+            handle_pipuck_request(arena_tx, journal_tx, id, request).await.map(|_| None),
+        BackEndRequest::ExperimentRequest(request) =>
+            handle_experiment_request(arena_tx, journal_tx, request).await.map(|_| None),
+        BackEndRequest::ProfileRequest(request) =>
+            handle_profile_request(profiles_tx, request).await.map(|_| None),
+        BackEndRequest::LibraryRequest(request) =>
+            handle_library_request(library_tx, request).await.map(|_| None),
+        BackEndRequest::MaintenanceRequest(request) =>
+            handle_maintenance_request(arena_tx, request).await.map(|_| None),
+        BackEndRequest::GroupRequest(group, action) =>
+            handle_group_request(arena_tx, group, action).await.map(|_| None),
+        BackEndRequest::ConfigurationRequest(request) =>
+            handle_configuration_request(configuration_tx, request).await.map(|_| None),
+        BackEndRequest::XbeeAuditRequest =>
+            handle_xbee_audit_request(arena_tx).await.map(|_| None),
+        BackEndRequest::ReturnToBaseRequest =>
+            handle_return_to_base_request(arena_tx).await.map(|_| None),
+        BackEndRequest::TakeOverRequest =>
+            handle_take_over_request(replication_tx).await.map(|_| None),
+        BackEndRequest::IdentificationRequest(robots) =>
+            handle_identification_request(identification_tx, robots).await.map(|_| None),
+        BackEndRequest::PairingRequest(request) =>
+            handle_pairing_request(pairing_tx, request).await.map(|_| None),
+        BackEndRequest::AssociationRequest(request) =>
+            handle_association_request(arena_tx, request).await.map(|_| None),
+        BackEndRequest::TopologyRequest(topology) =>
+            router_tx.send(router::Action::SetTopology(topology)).await
+                .map(|_| None)
+                .map_err(|_| anyhow::anyhow!("Could not send action to message router")),
+        BackEndRequest::RangeRequest(range) =>
+            router_tx.send(router::Action::SetRange(range)).await
+                .map(|_| None)
+                .map_err(|_| anyhow::anyhow!("Could not send action to message router")),
+        BackEndRequest::TraceRequest(id) => trace::get(trace_tx, id).await
+            .map(|trace| Some(FrontEndRequest::UpdateTrace(trace)))
+            .ok_or_else(|| anyhow::anyhow!("The trace of request {} is not available", id)),
+        BackEndRequest::ExcerptRequest(id) => handle_excerpt_request(journal_tx, &id).await
+            .map(|excerpt| Some(FrontEndRequest::UpdateExcerpt(id, excerpt))),
+        BackEndRequest::TelemetryRequest(id) => handle_telemetry_request(telemetry_tx, &id).await
+            .map(|history| Some(FrontEndRequest::UpdateTelemetry(id, history))),
+        BackEndRequest::SyncRequest => handle_sync_request(arena_tx, inventory_tx).await
+            .map(|snapshot| Some(FrontEndRequest::Snapshot(snapshot))),
+        BackEndRequest::SubscriptionRequest(_) | BackEndRequest::LoginRequest(_) =>
+            Err(anyhow::anyhow!("Subscription and login requests are specific to the connection of a client")),
+    }
+}
+
+/// The identity and the permissions of a client, all clients have full access if access control is not configured
+pub(crate) fn authenticate(access: Option<&Access>, token: Option<&str>) -> access::Update {
+    match access {
+        Some(access) => {
+            let (user, role) = access.authenticate(token);
+            access::Update { user, role, permissions: access.permissions(role) }
+        },
+        None => access::Update {
+            user: None,
+            role: Role::Admin,
+            permissions: Category::ALL.iter().copied().collect(),
+        },
+    }
+}
+
 async fn handle_client(
     ws: warp::ws::WebSocket,
-    arena_tx: mpsc::Sender<arena::Action>,
-    optitrack_tx: mpsc::Sender<optitrack::Action>
+    mut client: access::Update,
+    access: Option<Access>,
+    components: Components,
+    optitrack_tx: mpsc::Sender<optitrack::Action>,
+    infrastructure_tx: mpsc::Sender<infrastructure::Action>,
+    ambient_tx: mpsc::Sender<ambient::Action>,
+    alerts_tx: mpsc::Sender<alerts::Action>,
+    idempotency_tx: mpsc::Sender<idempotency::Action>,
+    health_tx: mpsc::Sender<health::Action>,
+    session_tx: mpsc::Sender<session::Action>,
 ) {
-    /* subscribe to builderbot updates and map them to websocket messages */
-    let builderbot_updates = match subscribe_builderbot_updates(&arena_tx).await {
-        Ok(updates) => {
-            let add_builderbot_messages = updates.keys()
-                .cloned()
-                .map(|desc| DownMessage::Request(Uuid::new_v4(), FrontEndRequest::AddBuilderBot(desc.deref().clone())))
-                .collect::<Vec<_>>();
-            let update_builderbot_messages = updates
-                .filter_map(|(desc, update)| async move {
-                    match update {
+    let Components { arena_tx, journal_tx, configuration_tx, inventory_tx, router_tx, identification_tx, pairing_tx, profiles_tx, library_tx, replication_tx, trace_tx, .. } =
+        components.clone();
+    let summaries = summaries(&inventory_tx).await;
+    /* the capabilities that this connection is subscribed to, updates for other capabilities are dropped */
+    let (subscriptions_tx, subscriptions_rx) = watch::channel(Capability::ALL.iter().copied().collect::<BTreeSet<_>>());
+    /* subscribe to the updates of the robots and the experiment, starting from a snapshot of their state */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let subscription = arena_tx.send(arena::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to arena updates")))
+        .await
+        .and_then(|result| result);
+    let arena::Subscription { mut snapshot, builderbots, drones, pipucks, checklist, smoke_tests, status, mut roster } = match subscription {
+        Ok(subscription) => subscription,
+        Err(error) => {
+            log::error!("Could not initialize client: {:#}", error);
+            return;
+        }
+    };
+    append_summaries(&mut snapshot, &summaries);
+    /* map the builderbot updates to websocket messages */
+    let subscriptions = subscriptions_rx.clone();
+    let builderbot_updates = builderbots
+        .filter_map(move |(desc, update)| {
+            let subscribed = update.as_ref()
+                .map_or(true, |update| subscriptions.borrow().contains(&update.capability()));
+            async move {
+                match update {
+                    Ok(update) => match subscribed {
+                        true => Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateBuilderBot(desc.id.clone(), update))),
+                        false => None,
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) => {
+                        log::warn!("Client missed {} messages for {}", count, desc);
+                        None
+                    }
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize BuilderBot message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* map the drone updates to websocket messages */
+    let subscriptions = subscriptions_rx.clone();
+    let drone_updates = drones
+        .filter_map(move |(desc, update)| {
+            let subscribed = update.as_ref()
+                .map_or(true, |update| subscriptions.borrow().contains(&update.capability()));
+            async move {
+                match update {
+                    Ok(update) => match subscribed {
+                        true => Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateDrone(desc.id.clone(), update))),
+                        false => None,
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) => {
+                        log::warn!("Client missed {} messages for {}", count, desc);
+                        None
+                    }
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize drone message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* map the pipuck updates to websocket messages */
+    let subscriptions = subscriptions_rx.clone();
+    let pipuck_updates = pipucks
+        .filter_map(move |(desc, update)| {
+            let subscribed = update.as_ref()
+                .map_or(true, |update| subscriptions.borrow().contains(&update.capability()));
+            async move {
+                match update {
+                    Ok(update) => match subscribed {
+                        true => Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdatePiPuck(desc.id.clone(), update))),
+                        false => None,
+                    },
+                    Err(BroadcastStreamRecvError::Lagged(count)) => {
+                        log::warn!("Client missed {} messages for {}", count, desc);
+                        None
+                    }
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize Pi-Puck message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* map the checklist updates to websocket messages */
+    let checklist_stream = BroadcastStream::new(checklist)
+        .filter_map(|item: Result<Vec<experiment::ChecklistItem>, BroadcastStreamRecvError>| async move {
+            match item {
+                Ok(checklist) => {
+                    let update = experiment::Update::Checklist(checklist);
+                    Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateExperiment(update)))
+                }
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("Client missed {} checklist messages", count);
+                    None
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize checklist message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* map the smoke test updates to websocket messages */
+    let smoke_test_stream = BroadcastStream::new(smoke_tests)
+        .filter_map(|item: Result<BTreeMap<String, Option<experiment::SmokeTest>>, BroadcastStreamRecvError>| async move {
+            match item {
+                Ok(smoke_tests) => {
+                    let update = experiment::Update::SmokeTests(smoke_tests);
+                    Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateExperiment(update)))
+                }
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("Client missed {} smoke test messages", count);
+                    None
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize smoke test message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* map the experiment status updates to websocket messages */
+    let status_stream = BroadcastStream::new(status)
+        .filter_map(|item: Result<experiment::Status, BroadcastStreamRecvError>| async move {
+            match item {
+                Ok(status) => {
+                    let update = experiment::Update::Status(status);
+                    Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateExperiment(update)))
+                }
+                Err(BroadcastStreamRecvError::Lagged(count)) => {
+                    log::warn!("Client missed {} experiment status messages", count);
+                    None
+                }
+            }
+        })
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize experiment status message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* subscribe to optitrack updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let optitrack_updates = optitrack_tx.send(optitrack::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates")));
+    let optitrack_stream = match optitrack_updates.await {
+        Ok(optitrack_updates) => {
+            let subscriptions = subscriptions_rx.clone();
+            BroadcastStream::new(optitrack_updates)
+                .filter(move |_| futures::future::ready(subscriptions.borrow().contains(&Capability::Tracking)))
+                .filter_map(|item: Result<(i32, Vec<tracking_system::Update>, tracking_system::Timestamps), BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok((_, update, mut timestamps)) => {
+                            timestamps.sent = optitrack::timestamp();
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateTrackingSystem(update, timestamps)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} tracking system messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize tracking system message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to optitrack model definitions */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let optitrack_models = optitrack_tx.send(optitrack::Action::SubscribeModels(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system models"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system models")));
+    let optitrack_models_stream = match optitrack_models.await {
+        Ok(optitrack_models) => {
+            let subscriptions = subscriptions_rx.clone();
+            BroadcastStream::new(optitrack_models)
+                .filter(move |_| futures::future::ready(subscriptions.borrow().contains(&Capability::Tracking)))
+                .filter_map(|item: Result<Vec<tracking_system::RigidBody>, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(models) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateTrackingSystemModels(models)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} tracking system model messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize tracking system model message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to journal statistics */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let journal_updates = journal_tx.send(journal::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to journal statistics"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to journal statistics")));
+    let journal_stream = match journal_updates.await {
+        Ok(journal_updates) => {
+            BroadcastStream::new(journal_updates)
+                .filter_map(|item: Result<experiment::JournalStatistics, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(statistics) => {
+                            let update = experiment::Update::Journal(statistics);
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateExperiment(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} journal messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize journal message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the live log of the experiment */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let log_updates = journal_tx.send(journal::Action::SubscribeLog(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to journal log"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to journal log")));
+    let log_stream = match log_updates.await {
+        Ok(log_updates) => {
+            let subscriptions = subscriptions_rx.clone();
+            BroadcastStream::new(log_updates)
+                .filter(move |_| futures::future::ready(subscriptions.borrow().contains(&Capability::Log)))
+                .filter_map(|item: Result<experiment::LogEntry, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(entry) => {
+                            let update = experiment::Update::Log(entry);
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateExperiment(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} log messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize log message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to simulated robot updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let simulation_updates = arena_tx.send(arena::Action::SubscribeSimulation(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to simulation updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to simulation updates")));
+    let simulation_stream = match simulation_updates.await {
+        Ok((robots, simulation_updates)) => {
+            /* the current state of the simulated robots is sent first */
+            futures::stream::iter(std::iter::once(Ok(simulation::Update::Robots(robots))))
+                .chain(BroadcastStream::new(simulation_updates))
+                .filter_map(|item: Result<simulation::Update, BroadcastStreamRecvError>| async move {
+                    match item {
                         Ok(update) => {
-                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateBuilderBot(desc.id.clone(), update)))
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateSimulation(update)))
                         }
                         Err(BroadcastStreamRecvError::Lagged(count)) => {
-                            log::warn!("Client missed {} messages for {}", count, desc);
+                            log::warn!("Client missed {} simulation messages", count);
                             None
                         }
                     }
-                });
-            /* send the add builderbot messages first, then stream the builderbot updates */
-            stream::iter(add_builderbot_messages).chain(update_builderbot_messages)
+                })
                 .map(|message| bincode::serialize(&message)
-                    .context("Could not serialize BuilderBot message"))
+                    .context("Could not serialize simulation message"))
                 .map_ok(|encoded| warp::ws::Message::binary(encoded))
         },
         Err(error) => {
@@ -79,151 +534,847 @@ async fn handle_client(
             return;
         }
     };
-    /* subscribe to drone updates and map them to websocket messages */
-    let drone_updates = match subscribe_drone_updates(&arena_tx).await {
-        Ok(updates) => {
-            let add_drone_messages = updates.keys()
-                .cloned()
-                .map(|desc| DownMessage::Request(Uuid::new_v4(), FrontEndRequest::AddDrone(desc.deref().clone())))
-                .collect::<Vec<_>>();
-            let update_drone_messages = updates
-                .filter_map(|(desc, update)| async move {
-                    match update {
+    /* subscribe to ROS robot updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let ros_updates = arena_tx.send(arena::Action::SubscribeRos(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to ROS robot updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to ROS robot updates")));
+    let ros_stream = match ros_updates.await {
+        Ok((robots, ros_updates)) => {
+            /* the current state of the robots is sent first */
+            futures::stream::iter(robots.into_iter().map(|robot| Ok(ros::Update::Robot(robot))))
+                .chain(BroadcastStream::new(ros_updates))
+                .filter_map(|item: Result<ros::Update, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateRos(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} ROS robot messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize ROS robot message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to maintenance mode updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let maintenance_updates = arena_tx.send(arena::Action::SubscribeMaintenance(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to maintenance updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to maintenance updates")));
+    let maintenance_stream = match maintenance_updates.await {
+        Ok((current, maintenance_updates)) => {
+            /* the current maintenance mode is sent first */
+            futures::stream::iter(current.map(Ok))
+                .chain(BroadcastStream::new(maintenance_updates))
+                .filter_map(|item: Result<maintenance::Update, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateMaintenance(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} maintenance messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize maintenance message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to association failures */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let association_updates = arena_tx.send(arena::Action::SubscribeAssociation(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to association updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to association updates")));
+    let association_stream = match association_updates.await {
+        Ok((current, association_updates)) => {
+            /* the current state is sent first */
+            futures::stream::iter(current.into_iter().map(Ok))
+                .chain(BroadcastStream::new(association_updates))
+                .filter_map(|item: Result<association::Update, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(association::Update::Unknown(device)) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UnknownDevice(device)))
+                        }
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateAssociation(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} association messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize association message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* whether the tokens of the users are redacted from the configuration, this changes when the client logs in */
+    let (redact_tx, redact_rx) = watch::channel(!client.permissions.contains(&Category::Configuration));
+    /* subscribe to configuration updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let configuration_updates = configuration_tx.send(configuration::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to configuration updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to configuration updates")));
+    let configuration_stream = match configuration_updates.await {
+        Ok(configuration_updates) => {
+            /* the tokens of the users are only sent to clients that can edit the configuration */
+            let redact = redact_rx.clone();
+            BroadcastStream::new(configuration_updates)
+                .filter_map(move |item: Result<shared::configuration::Update, BroadcastStreamRecvError>| {
+                    let redact = *redact.borrow();
+                    async move {
+                        match item {
+                            Ok(mut update) => {
+                                if let (true, shared::configuration::Update::Configuration { configuration, .. }) = (redact, &mut update) {
+                                    for user in configuration.access.iter_mut().flat_map(|access| access.users.iter_mut()) {
+                                        user.token.clear();
+                                    }
+                                }
+                                Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateConfiguration(update)))
+                            }
+                            Err(BroadcastStreamRecvError::Lagged(count)) => {
+                                log::warn!("Client missed {} configuration messages", count);
+                                None
+                            }
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize configuration message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to infrastructure updates */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let infrastructure_updates = infrastructure_tx.send(infrastructure::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to infrastructure updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to infrastructure updates")));
+    let infrastructure_stream = match infrastructure_updates.await {
+        Ok(infrastructure_updates) => {
+            BroadcastStream::new(infrastructure_updates)
+                .filter_map(|item: Result<shared::infrastructure::Update, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateInfrastructure(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} infrastructure messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize infrastructure message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the readings of the ambient sensors */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let ambient_updates = ambient_tx.send(ambient::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to ambient sensor updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to ambient sensor updates")));
+    let ambient_stream = match ambient_updates.await {
+        Ok(ambient_updates) => {
+            BroadcastStream::new(ambient_updates)
+                .filter_map(|item: Result<shared::ambient::Update, BroadcastStreamRecvError>| async move {
+                    match item {
                         Ok(update) => {
-                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateDrone(desc.id.clone(), update)))
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateAmbient(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} ambient sensor messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize ambient sensor message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the progress and the results of identifying the rigid bodies of the robots */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let identification_updates = identification_tx.send(identification::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to identification updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to identification updates")));
+    let identification_stream = match identification_updates.await {
+        Ok(identification_updates) => {
+            BroadcastStream::new(identification_updates)
+                .filter_map(|item: Result<shared::identification::Identification, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateIdentification(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} identification messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize identification message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the unassigned devices that can be paired into drones */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let pairing_updates = pairing_tx.send(pairing::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to pairing updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to pairing updates")));
+    let pairing_stream = match pairing_updates.await {
+        Ok(pairing_updates) => {
+            BroadcastStream::new(pairing_updates)
+                .filter_map(|item: Result<shared::pairing::Pairing, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdatePairing(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} pairing messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize pairing message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the experiment profiles */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let profiles_updates = profiles_tx.send(profiles::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to profile updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to profile updates")));
+    let profiles_stream = match profiles_updates.await {
+        Ok(profiles_updates) => {
+            BroadcastStream::new(profiles_updates)
+                .filter_map(|item: Result<Vec<shared::experiment::profile::Profile>, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateProfiles(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} profile messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize profile message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the library of control software */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let library_updates = library_tx.send(library::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to library updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to library updates")));
+    let library_stream = match library_updates.await {
+        Ok((bundles, library_updates)) => {
+            /* the current bundles are sent first */
+            futures::stream::once(async move { Ok(bundles) })
+                .chain(BroadcastStream::new(library_updates))
+                .filter_map(|item: Result<Vec<shared::experiment::library::Bundle>, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(update) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateLibrary(update)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} library messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize library message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the alerts about the robots, e.g., low batteries */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let alerts_updates = alerts_tx.send(alerts::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to alerts"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to alerts")));
+    let alerts_stream = match alerts_updates.await {
+        Ok(alerts_updates) => {
+            BroadcastStream::new(alerts_updates)
+                .filter_map(|item: Result<shared::alert::Notification, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(notification) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::Notification(notification)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} alerts", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize alert"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the role of this supervisor, i.e., whether it is the primary or a standby supervisor */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let replication_updates = replication_tx.send(replication::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to replication updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to replication updates")));
+    let replication_stream = match replication_updates.await {
+        Ok((role, replication_updates)) => {
+            /* the current role is sent first */
+            futures::stream::iter(role.map(Ok))
+                .chain(BroadcastStream::new(replication_updates))
+                .filter_map(|item: Result<shared::replication::Role, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(role) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateReplication(role)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} replication messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize replication message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the sessions of the clients that are connected to the web interface */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let sessions_updates = session_tx.send(session::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to sessions"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to sessions")));
+    let sessions_stream = match sessions_updates.await {
+        Ok(sessions_updates) => {
+            BroadcastStream::new(sessions_updates)
+                .filter_map(|item: Result<Vec<access::Session>, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(sessions) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateSessions(sessions)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} session messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize session message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* subscribe to the health of the subsystems, the current health is sent first */
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let health_updates = health_tx.send(health::Action::Subscribe(callback_tx))
+        .map_err(|_| anyhow::anyhow!("Could not subscribe to health updates"))
+        .and_then(move |_| callback_rx
+            .map_err(|_| anyhow::anyhow!("Could not subscribe to health updates")));
+    let health_stream = match health_updates.await {
+        Ok((health, health_updates)) => {
+            futures::stream::once(async move { Ok(health) })
+                .chain(BroadcastStream::new(health_updates))
+                .filter_map(|item: Result<Vec<shared::health::Subsystem>, BroadcastStreamRecvError>| async move {
+                    match item {
+                        Ok(subsystems) => {
+                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateHealth(subsystems)))
+                        }
+                        Err(BroadcastStreamRecvError::Lagged(count)) => {
+                            log::warn!("Client missed {} health messages", count);
+                            None
+                        }
+                    }
+                })
+                .map(|message| bincode::serialize(&message)
+                    .context("Could not serialize health message"))
+                .map_ok(|encoded| warp::ws::Message::binary(encoded))
+        },
+        Err(error) => {
+            log::error!("Could not initialize client: {}", error);
+            return;
+        }
+    };
+    /* poll the traffic statistics of the message router, these are only sent to the client when they change */
+    let statistics_tx = router_tx.clone();
+    let router_stream = async_stream::stream! {
+        let mut interval = tokio::time::interval(ROUTER_STATISTICS_INTERVAL);
+        let mut previous = None;
+        loop {
+            interval.tick().await;
+            let (callback_tx, callback_rx) = oneshot::channel();
+            if statistics_tx.send(router::Action::GetStatistics(callback_tx)).await.is_err() {
+                break;
+            }
+            match callback_rx.await {
+                Ok(statistics) => if previous.as_ref() != Some(&statistics) {
+                    previous = Some(statistics.clone());
+                    yield DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateRouter(statistics));
+                },
+                Err(_) => break,
+            }
+        }
+    }
+        .map(|message| bincode::serialize(&message)
+            .context("Could not serialize router message"))
+        .map_ok(|encoded| warp::ws::Message::binary(encoded));
+    /* response to client requests and forward updates to client */
+    tokio::pin!(optitrack_stream);
+    tokio::pin!(optitrack_models_stream);
+    tokio::pin!(journal_stream);
+    tokio::pin!(log_stream);
+    tokio::pin!(checklist_stream);
+    tokio::pin!(smoke_test_stream);
+    tokio::pin!(status_stream);
+    tokio::pin!(simulation_stream);
+    tokio::pin!(ros_stream);
+    tokio::pin!(maintenance_stream);
+    tokio::pin!(association_stream);
+    tokio::pin!(configuration_stream);
+    tokio::pin!(infrastructure_stream);
+    tokio::pin!(ambient_stream);
+    tokio::pin!(router_stream);
+    tokio::pin!(identification_stream);
+    tokio::pin!(pairing_stream);
+    tokio::pin!(profiles_stream);
+    tokio::pin!(library_stream);
+    tokio::pin!(alerts_stream);
+    tokio::pin!(replication_stream);
+    tokio::pin!(sessions_stream);
+    tokio::pin!(health_stream);
+    tokio::pin!(builderbot_updates);
+    tokio::pin!(pipuck_updates);
+    tokio::pin!(drone_updates);
+    let (mut websocket_tx, mut websocket_rx) = ws.split();
+    /* inform the client about its role so that it can hide the controls that it is not permitted to use */
+    let message = DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateAccess(client.clone()));
+    match bincode::serialize(&message) {
+        Ok(encoded) => if let Err(error) = websocket_tx.send(warp::ws::Message::binary(encoded)).await {
+            log::warn!("Could not send permissions to client: {}", error);
+            return;
+        },
+        Err(error) => log::error!("Could not serialize permissions: {}", error),
+    }
+    /* send the snapshot before any of the updates that follow it */
+    let message = DownMessage::Request(Uuid::new_v4(), FrontEndRequest::Snapshot(snapshot));
+    match bincode::serialize(&message) {
+        Ok(encoded) => if let Err(error) = websocket_tx.send(warp::ws::Message::binary(encoded)).await {
+            log::warn!("Could not send snapshot to client: {}", error);
+            return;
+        },
+        Err(error) => log::error!("Could not serialize snapshot: {}", error),
+    }
+    let session = Uuid::new_v4();
+    let _ = session_tx.send(session::Action::Begin(session, client.clone())).await;
+    loop {
+        tokio::select! {
+            /* handle requests from client */
+            rx = websocket_rx.next() => match rx {
+                Some(Ok(message)) => {
+                    if message.is_close() {
+                        break;
+                    }
+                    match bincode::deserialize::<UpMessage>(message.as_bytes()) {
+                        Ok(message) => match message {
+                            UpMessage::Request(uuid, request) => {
+                                /* requests that the role of the client does not permit are rejected */
+                                let forbidden = request.category()
+                                    .filter(|category| !client.permissions.contains(category));
+                                /* a request that is retried with the same identifier, e.g., after a timeout, is
+                                   answered with the outcome of the original request instead of being executed
+                                   again. Requests for traces only read and the subscription and login requests
+                                   change the state of the connection, hence they are always executed */
+                                let claim = match (forbidden, &request) {
+                                    (Some(_), _) |
+                                    (None, BackEndRequest::TraceRequest(_)) |
+                                    (None, BackEndRequest::SubscriptionRequest(_)) |
+                                    (None, BackEndRequest::LoginRequest(_)) => None,
+                                    (None, _) => idempotency::claim(&idempotency_tx, uuid).await,
+                                };
+                                /* the identifier of the request is the identifier of its trace */
+                                let trace = trace::Handle::new(uuid, trace_tx.clone());
+                                let ticket = match claim {
+                                    Some(idempotency::Claim::Duplicate((result, update))) => {
+                                        log::info!("Request {} was received again, resending its response", uuid);
+                                        trace::record(&trace, "webui", "Resent the response to a duplicate");
+                                        respond(&mut websocket_tx, uuid, result, update).await;
+                                        continue;
+                                    },
+                                    Some(idempotency::Claim::New(ticket)) => Some(ticket),
+                                    None => None,
+                                };
+                                /* requests for traces are not traced themselves */
+                                if !matches!(request, BackEndRequest::TraceRequest(_)) {
+                                    trace::begin(&trace, request.summary());
+                                }
+                                /* the responses to trace, excerpt, login, and sync requests are sent as updates */
+                                let mut update = None;
+                                let mut login = None;
+                                let result = trace::scope(trace.clone(), async { match (forbidden, request) {
+                                    (Some(category), _) => Err(anyhow::anyhow!("The role \"{}\" is not permitted to make {} requests",
+                                        client.role.name(), category.name())),
+                                    (None, BackEndRequest::SubscriptionRequest(request)) =>
+                                        handle_subscription_request(&subscriptions_tx, request),
+                                    (None, BackEndRequest::LoginRequest(token)) =>
+                                        handle_login_request(access.as_ref(), token.as_deref())
+                                            .map(|authenticated| {
+                                                update = Some(FrontEndRequest::UpdateAccess(authenticated.clone()));
+                                                login = Some(authenticated);
+                                            }),
+                                    (None, request) => dispatch(&components, request).await
+                                        .map(|response| update = response),
+                                }}).await;
+                                if let Some(authenticated) = login {
+                                    client = authenticated;
+                                    let _ = session_tx.send(session::Action::Update(session, client.clone())).await;
+                                    let _ = redact_tx.send(!client.permissions.contains(&Category::Configuration));
+                                    /* subscribing resends the configuration, which is then redacted for the new role */
+                                    let (callback_tx, _) = oneshot::channel();
+                                    let _ = configuration_tx.send(configuration::Action::Subscribe(callback_tx)).await;
+                                }
+                                match result.as_ref() {
+                                    Ok(_) => trace::record(&trace, "webui", "Responded"),
+                                    Err(error) => {
+                                        log::warn!("Error processing request {}: {}", uuid, error);
+                                        trace::record(&trace, "webui", format!("Responded with error: {}", error));
+                                    }
+                                }
+                                let result = result.map_err(|e| e.to_string());
+                                if let Some(ticket) = ticket {
+                                    ticket.complete((result.clone(), update.clone())).await;
+                                }
+                                respond(&mut websocket_tx, uuid, result, update).await;
+                            },
+                            UpMessage::Response(uuid, result) => if let Err(error) = result {
+                                log::error!("Request {} failed: {}", uuid, error);
+                            }
+                        },
+                        Err(_) => {
+                            log::warn!("Could not deserialize UpMessage");
+                        },
+                    }
+                }
+                Some(Err(error)) => {
+                    log::warn!("{}", error);
+                }
+                /* the connection was lost without a close message */
+                None => break,
+            },
+            /* stream optitrack updated to client */
+            Some(result) = optitrack_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            }
+            /* stream optitrack model definitions to client */
+            Some(result) = optitrack_models_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream journal statistics to client */
+            Some(result) = journal_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the live log to client */
+            Some(result) = log_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream checklist updates to client */
+            Some(result) = checklist_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream smoke test updates to client */
+            Some(result) = smoke_test_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream experiment status updates to client */
+            Some(result) = status_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream simulated robot updates to client */
+            Some(result) = simulation_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream ROS robot updates to client */
+            Some(result) = ros_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream maintenance mode updates to client */
+            Some(result) = maintenance_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream association failures to client */
+            Some(result) = association_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream configuration updates to client */
+            Some(result) = configuration_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream infrastructure updates to client */
+            Some(result) = infrastructure_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the readings of the ambient sensors to client */
+            Some(result) = ambient_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the progress of the identification to client */
+            Some(result) = identification_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the alerts to client */
+            Some(result) = alerts_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                        Err(BroadcastStreamRecvError::Lagged(count)) => {
-                            log::warn!("Client missed {} messages for {}", count, desc);
-                            None
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the sessions of the connected clients to client */
+            Some(result) = sessions_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                    }
-                });
-            /* send the add drone messages first, then stream the drone updates */
-            stream::iter(add_drone_messages).chain(update_drone_messages)
-                .map(|message| bincode::serialize(&message)
-                    .context("Could not serialize drone message"))
-                .map_ok(|encoded| warp::ws::Message::binary(encoded))
-        },
-        Err(error) => {
-            log::error!("Could not initialize client: {}", error);
-            return;
-        }
-    };
-    /* subscribe to pipuck updates and map them to websocket messages */
-    let pipuck_updates = match subscribe_pipuck_updates(&arena_tx).await {
-        Ok(updates) => {
-            let add_pipuck_messages = updates.keys()
-                .cloned()
-                .map(|desc| DownMessage::Request(Uuid::new_v4(), FrontEndRequest::AddPiPuck(desc.deref().clone())))
-                .collect::<Vec<_>>();
-            let update_pipuck_messages = updates
-                .filter_map(|(desc, update)| async move {
-                    match update {
-                        Ok(update) => {
-                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdatePiPuck(desc.id.clone(), update)))
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the health of the subsystems to client */
+            Some(result) = health_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                        Err(BroadcastStreamRecvError::Lagged(count)) => {
-                            log::warn!("Client missed {} messages for {}", count, desc);
-                            None
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the role of this supervisor to client */
+            Some(result) = replication_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                    }
-                });
-            /* send the add pipuck messages first, then stream the pipuck updates */
-            stream::iter(add_pipuck_messages).chain(update_pipuck_messages)
-                .map(|message| bincode::serialize(&message)
-                    .context("Could not serialize Pi-Puck message"))
-                .map_ok(|encoded| warp::ws::Message::binary(encoded))
-        },
-        Err(error) => {
-            log::error!("Could not initialize client: {}", error);
-            return;
-        }
-    };
-    /* subscribe to optitrack updates */
-    let (callback_tx, callback_rx) = oneshot::channel();
-    let optitrack_updates = optitrack_tx.send(optitrack::Action::Subscribe(callback_tx))
-        .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates"))
-        .and_then(move |_| callback_rx
-            .map_err(|_| anyhow::anyhow!("Could not subscribe to tracking system updates")));
-    let optitrack_stream = match optitrack_updates.await {
-        Ok(optitrack_updates) => {
-            BroadcastStream::new(optitrack_updates)
-                .filter_map(|item: Result<Vec<tracking_system::Update>, BroadcastStreamRecvError>| async move {
-                    match item {
-                        Ok(update) => {
-                            Some(DownMessage::Request(Uuid::new_v4(), FrontEndRequest::UpdateTrackingSystem(update)))
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the unassigned devices to client */
+            Some(result) = pairing_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                        Err(BroadcastStreamRecvError::Lagged(count)) => {
-                            log::warn!("Client missed {} tracking system messages", count);
-                            None
+                    },
+                    Err(error) => log::error!("{}", error),
+                }
+            },
+            /* stream the experiment profiles to client */
+            Some(result) = profiles_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
                         }
-                    }
-                })
-                .map(|message| bincode::serialize(&message)
-                    .context("Could not serialize tracking system message"))
-                .map_ok(|encoded| warp::ws::Message::binary(encoded))
-        },
-        Err(error) => {
-            log::error!("Could not initialize client: {}", error);
-            return;
-        }
-    };
-    /* response to client requests and forward updates to client */
-    tokio::pin!(optitrack_stream);
-    tokio::pin!(builderbot_updates);
-    tokio::pin!(pipuck_updates);
-    tokio::pin!(drone_updates);
-    let (mut websocket_tx, mut websocket_rx) = ws.split();
-    loop {
-        tokio::select! {
-            /* handle requests from client */
-            Some(rx) = websocket_rx.next() => match rx {
-                Ok(message) => {
-                    if message.is_close() {
-                        break;
-                    }
-                    match bincode::deserialize::<UpMessage>(message.as_bytes()) {
-                        Ok(message) => match message {
-                            UpMessage::Request(uuid, request) => {
-                                let result = match request {
-                                    BackEndRequest::BuilderBotRequest(id, request) =>  
-                                        handle_builderbot_request(&arena_tx, id, request).await,
-                                    BackEndRequest::DroneRequest(id, request) => 
-                                        handle_drone_request(&arena_tx, id, request).await,
-                                    BackEndRequest::PiPuckRequest(id, request) =>  
-                                        handle_pipuck_request(&arena_tx, id, request).await,
-                                    BackEndRequest::ExperimentRequest(request) => 
-                                        handle_experiment_request(&arena_tx, request).await,
-                                };
-                                if let Err(error) = result.as_ref() {
-                                    log::warn!("Error processing request: {}", error);
-                                }
-                                let response = DownMessage::Response(uuid, result.map_err(|e| e.to_string()));
-                                match bincode::serialize(&response) {
-                                    Ok(encoded) => {
-                                        let message = warp::ws::Message::binary(encoded);
-                                        if let Err(error) = websocket_tx.send(message).await {
-                                            log::error!("Could not send response to client: {}", error);
-                                        }
-                                    }
-                                    Err(error) => log::error!("Could not serialize response: {}", error),
-                                }
-                            },
-                            UpMessage::Response(uuid, result) => if let Err(error) = result {
-                                log::error!("Request {} failed: {}", uuid, error);
-                            }
-                        },
-                        Err(_) => {
-                            log::warn!("Could not deserialize UpMessage");
-                        },
-                    }
+                    },
+                    Err(error) => log::error!("{}", error),
                 }
-                Err(error) => {
-                    log::warn!("{}", error);
+            },
+            /* stream the library of control software to client */
+            Some(result) = library_stream.next() => {
+                match result {
+                    Ok(message) => {
+                        if let Err(error) = websocket_tx.send(message).await {
+                            log::error!("Could not send message to client: {}", error);
+                        }
+                    },
+                    Err(error) => log::error!("{}", error),
                 }
             },
-            /* stream optitrack updated to client */
-            Some(result) = optitrack_stream.next() => {
+            /* stream the traffic statistics of the message router to client */
+            Some(result) = router_stream.next() => {
                 match result {
                     Ok(message) => {
                         if let Err(error) = websocket_tx.send(message).await {
@@ -232,7 +1383,14 @@ async fn handle_client(
                     },
                     Err(error) => log::error!("{}", error),
                 }
-            }
+            },
+            /* the updates of added robots are only sent to new subscriptions, close the connection so that the
+               client reconnects and receives a snapshot with the current robots */
+            Ok(_) = roster.recv() => {
+                log::info!("Robots have been added or removed, closing the connection to the client");
+                let _ = websocket_tx.close().await;
+                break;
+            },
             /* stream builderbot updates to client */
             Some(result) = builderbot_updates.next() => {
                 match result {
@@ -266,96 +1424,40 @@ async fn handle_client(
             }
         }
     }
+    let _ = session_tx.send(session::Action::End(session)).await;
 }
 
-async fn subscribe_builderbot_updates(
-    arena_tx: &mpsc::Sender<arena::Action>
-) -> anyhow::Result<StreamMap<Arc<builderbot::Descriptor>, BroadcastStream<builderbot::Update>>> {
-    let (callback_tx, callback_rx) = oneshot::channel();
-    let update_streams = arena_tx.send(arena::Action::GetBuilderBotDescriptors(callback_tx))
-        .map_err(|_| anyhow::anyhow!("Could not communicate with BuilderBot"))
-        .and_then(|_| callback_rx
-            .map(|result| result.context("Could not get BuilderBot descriptors")))
-        .and_then(|builderbot_descs| builderbot_descs.into_iter()
-            .map(|builderbot_desc| {
-                let (callback_tx, callback_rx) = oneshot::channel();
-                let action = builderbot::Action::Subscribe(callback_tx);
-                arena_tx.send(arena::Action::ForwardBuilderBotAction(builderbot_desc.id.clone(), action))
-                    .map_err(|_| anyhow::anyhow!("Could not communicate with BuilderBot"))
-                    .and_then(|_| callback_rx
-                        .map(|result| result.context("Could not subscribe to BuilderBot updates"))
-                        .map_ok(|receiver| (builderbot_desc, BroadcastStream::new(receiver))))
-            })
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-        ).await?;
-    
-    let mut builderbot_update_stream_map = StreamMap::new();
-    for (desc, update_stream) in update_streams {
-        builderbot_update_stream_map.insert(desc, update_stream);
-    }
-    Ok(builderbot_update_stream_map)
-}
-
-async fn subscribe_drone_updates(
-    arena_tx: &mpsc::Sender<arena::Action>
-) -> anyhow::Result<StreamMap<Arc<drone::Descriptor>, BroadcastStream<drone::Update>>> {
-    let (callback_tx, callback_rx) = oneshot::channel();
-    let update_streams = arena_tx.send(arena::Action::GetDroneDescriptors(callback_tx))
-        .map_err(|_| anyhow::anyhow!("Could not communicate with drone"))
-        .and_then(|_| callback_rx
-            .map(|result| result.context("Could not get drone descriptors")))
-        .and_then(|drone_descs| drone_descs.into_iter()
-            .map(|drone_desc| {
-                let (callback_tx, callback_rx) = oneshot::channel();
-                let action = drone::Action::Subscribe(callback_tx);
-                arena_tx.send(arena::Action::ForwardDroneAction(drone_desc.id.clone(), action))
-                    .map_err(|_| anyhow::anyhow!("Could not communicate with drone"))
-                    .and_then(|_| callback_rx
-                        .map(|result| result.context("Could not subscribe to drone updates"))
-                        .map_ok(|receiver| (drone_desc, BroadcastStream::new(receiver))))
-            })
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-        ).await?;
-    let mut drone_update_stream_map = StreamMap::new();
-    for (desc, update_stream) in update_streams {
-        drone_update_stream_map.insert(desc, update_stream);
+/// Sends the update with which a request was answered, if any, followed by the response to the request
+async fn respond(
+    websocket_tx: &mut futures::stream::SplitSink<warp::ws::WebSocket, warp::ws::Message>,
+    uuid: Uuid,
+    result: Result<(), String>,
+    update: Option<FrontEndRequest>
+) {
+    if let Some(update) = update {
+        let message = DownMessage::Request(Uuid::new_v4(), update);
+        match bincode::serialize(&message) {
+            Ok(encoded) => if let Err(error) = websocket_tx.send(warp::ws::Message::binary(encoded)).await {
+                log::error!("Could not send update to client: {}", error);
+            },
+            Err(error) => log::error!("Could not serialize update: {}", error),
+        }
     }
-    Ok(drone_update_stream_map)
-}
-
-async fn subscribe_pipuck_updates(
-    arena_tx: &mpsc::Sender<arena::Action>
-) -> anyhow::Result<StreamMap<Arc<pipuck::Descriptor>, BroadcastStream<pipuck::Update>>> {
-    let (callback_tx, callback_rx) = oneshot::channel();
-    let update_streams = arena_tx.send(arena::Action::GetPiPuckDescriptors(callback_tx))
-        .map_err(|_| anyhow::anyhow!("Could not communicate with Pi-Puck"))
-        .and_then(|_| callback_rx
-            .map(|result| result.context("Could not get Pi-Puck descriptors")))
-        .and_then(|pipuck_descs| pipuck_descs.into_iter()
-            .map(|pipuck_desc| {
-                let (callback_tx, callback_rx) = oneshot::channel();
-                let action = pipuck::Action::Subscribe(callback_tx);
-                arena_tx.send(arena::Action::ForwardPiPuckAction(pipuck_desc.id.clone(), action))
-                    .map_err(|_| anyhow::anyhow!("Could not communicate with Pi-Puck"))
-                    .and_then(|_| callback_rx
-                        .map(|result| result.context("Could not subscribe to Pi-Puck updates"))
-                        .map_ok(|receiver| (pipuck_desc, BroadcastStream::new(receiver))))
-            })
-            .collect::<FuturesUnordered<_>>()
-            .try_collect::<Vec<_>>()
-        ).await?;
-    
-    let mut pipuck_update_stream_map = StreamMap::new();
-    for (desc, update_stream) in update_streams {
-        pipuck_update_stream_map.insert(desc, update_stream);
+    let response = DownMessage::Response(uuid, result);
+    match bincode::serialize(&response) {
+        Ok(encoded) => {
+            let message = warp::ws::Message::binary(encoded);
+            if let Err(error) = websocket_tx.send(message).await {
+                log::error!("Could not send response to client: {}", error);
+            }
+        }
+        Err(error) => log::error!("Could not serialize response: {}", error),
     }
-    Ok(pipuck_update_stream_map)
 }
 
 async fn handle_builderbot_request(
     arena_tx: &mpsc::Sender<arena::Action>,
+    journal_tx: &mpsc::Sender<journal::Action>,
     id: String,
     request: shared::builderbot::Request,
 ) -> anyhow::Result<()> {
@@ -364,6 +1466,8 @@ async fn handle_builderbot_request(
     use builderbot::Action;
     let (callback_tx, callback_rx) = oneshot::channel();
     let action = match request {
+        Request::CancelQueuedAction(id) =>
+            Action::CancelQueuedAction(callback_tx, id),
         Request::BashTerminalStart => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Bash(TerminalAction::Start)),
         Request::BashTerminalStop => 
@@ -374,18 +1478,21 @@ async fn handle_builderbot_request(
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::SetCameraStream(on)),
         Request::Identify => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Identify),
+        Request::FetchResults(paths) =>
+            Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::FetchResults(Results::new(&id, paths, journal_tx.clone()))),
         Request::DuoVeroHalt => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Halt),
         Request::DuoVeroReboot =>
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Reboot),
     };
-    arena_tx.send(arena::Action::ForwardBuilderBotAction(id, action)).await
+    arena_tx.send(arena::Action::ForwardBuilderBotAction(id, action).traced(trace::current())).await
         .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
     callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
 }
 
 async fn handle_drone_request(
     arena_tx: &mpsc::Sender<arena::Action>,
+    journal_tx: &mpsc::Sender<journal::Action>,
     id: String,
     request: shared::drone::Request
 ) -> anyhow::Result<()> {
@@ -394,6 +1501,8 @@ async fn handle_drone_request(
     use drone::Action;
     let (callback_tx, callback_rx) = oneshot::channel();
     let action = match request {
+        Request::CancelQueuedAction(id) =>
+            Action::CancelQueuedAction(callback_tx, id),
         Request::BashTerminalStart => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Bash(TerminalAction::Start)),
         Request::BashTerminalStop => 
@@ -404,6 +1513,8 @@ async fn handle_drone_request(
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::SetCameraStream(on)),
         Request::Identify => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Identify),
+        Request::FetchResults(paths) =>
+            Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::FetchResults(Results::new(&id, paths, journal_tx.clone()))),
         Request::PixhawkPowerEnable(on) => 
             Action::ExecuteXbeeAction(callback_tx, XbeeAction::SetPixhawkPower(on)),
         Request::MavlinkTerminalStart => 
@@ -412,6 +1523,14 @@ async fn handle_drone_request(
             Action::ExecuteXbeeAction(callback_tx, XbeeAction::Mavlink(TerminalAction::Stop)),
         Request::MavlinkTerminalRun(command) => 
             Action::ExecuteXbeeAction(callback_tx, XbeeAction::Mavlink(TerminalAction::Run(command))),
+        Request::MavlinkCommand(command) =>
+            Action::ExecuteXbeeAction(callback_tx, XbeeAction::MavlinkCommand(command)),
+        Request::Arm =>
+            Action::ExecuteXbeeAction(callback_tx, XbeeAction::Arm(true)),
+        Request::Disarm =>
+            Action::ExecuteXbeeAction(callback_tx, XbeeAction::Arm(false)),
+        Request::SetFlightMode(mode) =>
+            Action::ExecuteXbeeAction(callback_tx, XbeeAction::SetFlightMode(mode)),
         Request::UpCorePowerEnable(on) => 
             Action::ExecuteXbeeAction(callback_tx, XbeeAction::SetUpCorePower(on)),
         Request::UpCoreHalt => 
@@ -419,13 +1538,14 @@ async fn handle_drone_request(
         Request::UpCoreReboot =>
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Reboot),
     };
-    arena_tx.send(arena::Action::ForwardDroneAction(id, action)).await
+    arena_tx.send(arena::Action::ForwardDroneAction(id, action).traced(trace::current())).await
         .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
     callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
 }
 
 async fn handle_pipuck_request(
     arena_tx: &mpsc::Sender<arena::Action>,
+    journal_tx: &mpsc::Sender<journal::Action>,
     id: String,
     request: shared::pipuck::Request,
 ) -> anyhow::Result<()> {
@@ -434,6 +1554,8 @@ async fn handle_pipuck_request(
     use pipuck::Action;
     let (callback_tx, callback_rx) = oneshot::channel();
     let action = match request {
+        Request::CancelQueuedAction(id) =>
+            Action::CancelQueuedAction(callback_tx, id),
         Request::BashTerminalStart => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Bash(TerminalAction::Start)),
         Request::BashTerminalStop => 
@@ -444,30 +1566,312 @@ async fn handle_pipuck_request(
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::SetCameraStream(on)),
         Request::Identify => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Identify),
+        Request::FetchResults(paths) =>
+            Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::FetchResults(Results::new(&id, paths, journal_tx.clone()))),
         Request::RaspberryPiHalt => 
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Halt),
         Request::RaspberryPiReboot =>
             Action::ExecuteFernbedienungAction(callback_tx, FernbedienungAction::Reboot),
     };
-    arena_tx.send(arena::Action::ForwardPiPuckAction(id, action)).await
+    arena_tx.send(arena::Action::ForwardPiPuckAction(id, action).traced(trace::current())).await
         .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
     callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
 }
 
 async fn handle_experiment_request(
     arena_tx: &mpsc::Sender<arena::Action>,
+    journal_tx: &mpsc::Sender<journal::Action>,
     request: shared::experiment::Request,
 ) -> anyhow::Result<()> {
-    use shared::experiment::Request;
+    use shared::experiment::{Request, TimelineCategory};
     use arena::Action;
     let (callback_tx, callback_rx) = oneshot::channel();
     let action = match request {
-        Request::Start { builderbot_software, drone_software, pipuck_software } => 
-            Action::StartExperiment { callback: callback_tx, builderbot_software, drone_software, pipuck_software },
+        Request::Start { builderbot_software, drone_software, pipuck_software, overrides, partial_start, settings } => 
+            Action::StartExperiment {
+                callback: callback_tx, builderbot_software, drone_software, pipuck_software, overrides, robots: None, partial_start, settings
+            },
         Request::Stop =>
-            Action::StopExperiment { callback: callback_tx },
+            Action::StopExperiment { callback: callback_tx, robots: None },
+        Request::Acknowledge { item, operator } =>
+            Action::AcknowledgeChecklistItem { callback: callback_tx, item, operator },
+        Request::SmokeTest(robot) =>
+            Action::RunSmokeTests { callback: callback_tx, robot },
+        Request::BandwidthTest(robot) =>
+            Action::RunBandwidthTests { callback: callback_tx, robot },
+        /* the software is checked here since checking it does not involve the robots */
+        Request::Check { builderbot_software, drone_software, pipuck_software, overrides } =>
+            return handle_check_request(builderbot_software, drone_software, pipuck_software, overrides).await,
+        /* annotations go directly to the journal and are ignored if no experiment is being recorded */
+        Request::Annotate { note, tags } => {
+            let mut tags = tags.into_iter()
+                .map(|tag| tag.trim().to_owned())
+                .filter(|tag| !tag.is_empty())
+                .collect::<Vec<_>>();
+            if let Some(tag) = tags.iter().find(|tag| tag.contains(char::is_whitespace)) {
+                return Err(anyhow::anyhow!("The tag \"{}\" must not contain spaces", tag));
+            }
+            tags.sort();
+            tags.dedup();
+            let event = journal::Event::Timeline(TimelineCategory::Annotation, note, tags);
+            return journal_tx.send(journal::Action::Record(event)).await
+                .map_err(|_| anyhow::anyhow!("Could not send annotation to journal"));
+        },
+    };
+    arena_tx.send(action.traced(trace::current())).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
+
+/// Checks the software that was uploaded for each robot type and for individual robots. The software in a
+/// repository is only checked once it has been fetched when the experiment starts, while the bundles in the
+/// library were validated when they were stored
+async fn handle_check_request(
+    builderbot_software: experiment::software::Source,
+    drone_software: experiment::software::Source,
+    pipuck_software: experiment::software::Source,
+    overrides: BTreeMap<String, experiment::software::Software>,
+) -> anyhow::Result<()> {
+    use shared::experiment::software::Source;
+    let defaults = vec![
+        ("BuilderBot software", builderbot_software),
+        ("Drone software", drone_software),
+        ("Pi-Puck software", pipuck_software),
+    ];
+    let defaults = defaults.into_iter()
+        .filter_map(|(label, source)| match source {
+            Source::Files(software) if !software.0.is_empty() => Some((label.to_owned(), software)),
+            _ => None,
+        });
+    let overrides = overrides.into_iter()
+        .map(|(id, software)| (format!("Software for {}", id), software));
+    let mut errors = Vec::new();
+    for (label, software) in defaults.chain(overrides) {
+        if let Err(error) = syntax::check(&software).await {
+            errors.push(format!("{}:\n{}", label, error));
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(anyhow::anyhow!("{}", errors.join("\n"))),
+    }
+}
+
+async fn handle_excerpt_request(
+    journal_tx: &mpsc::Sender<journal::Action>,
+    id: &str,
+) -> anyhow::Result<experiment::Excerpt> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    journal_tx.send(journal::Action::Excerpt(id.to_owned(), callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to journal"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from journal"))?
+        .ok_or_else(|| anyhow::anyhow!("No output from ARGoS on {} has been recorded since the supervisor was started", id))
+}
+
+async fn handle_telemetry_request(
+    telemetry_tx: &mpsc::Sender<telemetry::Action>,
+    id: &str,
+) -> anyhow::Result<shared::telemetry::History> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    telemetry_tx.send(telemetry::Action::History(id.to_owned(), callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to telemetry"))?;
+    callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from telemetry"))
+}
+
+/// The summaries of the last experiments, these are persisted in the inventory so that they remain visible
+/// across sessions
+pub(crate) async fn summaries(inventory_tx: &mpsc::Sender<inventory::Action>) -> HashMap<String, experiment::Summary> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    match inventory_tx.send(inventory::Action::GetSummaries(callback_tx)).await {
+        Ok(_) => callback_rx.await.unwrap_or_default(),
+        Err(_) => Default::default(),
+    }
+}
+
+/// Appends the summaries of the last experiments to the state of the robots
+pub(crate) fn append_summaries(snapshot: &mut Snapshot, summaries: &HashMap<String, experiment::Summary>) {
+    for (desc, updates) in snapshot.builderbots.iter_mut() {
+        updates.extend(summaries.get(&desc.id).cloned().map(builderbot::Update::ExperimentSummary));
+    }
+    for (desc, updates) in snapshot.drones.iter_mut() {
+        updates.extend(summaries.get(&desc.id).cloned().map(drone::Update::ExperimentSummary));
+    }
+    for (desc, updates) in snapshot.pipucks.iter_mut() {
+        updates.extend(summaries.get(&desc.id).cloned().map(pipuck::Update::ExperimentSummary));
+    }
+}
+
+/// A snapshot of the robots and the experiment that replaces the state of a client, e.g., after the client
+/// has reconnected. The updates that follow the snapshot are already streamed to the client
+async fn handle_sync_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+    inventory_tx: &mpsc::Sender<inventory::Action>,
+) -> anyhow::Result<Snapshot> {
+    let summaries = summaries(inventory_tx).await;
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::Subscribe(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not communicate with arena"))?;
+    let arena::Subscription { mut snapshot, .. } = callback_rx.await
+        .map_err(|_| anyhow::anyhow!("No response from arena"))??;
+    append_summaries(&mut snapshot, &summaries);
+    Ok(snapshot)
+}
+
+/// Authenticates a client that logs in after connecting. Unlike the token that is passed when connecting,
+/// an unknown token is rejected so that the operator notices a typo instead of silently becoming an observer
+fn handle_login_request(access: Option<&Access>, token: Option<&str>) -> anyhow::Result<access::Update> {
+    let access = access
+        .ok_or_else(|| anyhow::anyhow!("Access control is not configured, all clients have full access"))?;
+    match token {
+        Some(token) if !access.users.iter().any(|user| user.token == token) =>
+            Err(anyhow::anyhow!("Unknown token")),
+        token => Ok(authenticate(Some(access), token)),
+    }
+}
+
+fn handle_subscription_request(
+    subscriptions_tx: &watch::Sender<BTreeSet<Capability>>,
+    request: subscription::Request,
+) -> anyhow::Result<()> {
+    match request {
+        subscription::Request::Subscribe(capabilities) => subscriptions_tx
+            .send(capabilities.into_iter().collect())
+            .map_err(|_| anyhow::anyhow!("Could not update subscriptions")),
+    }
+}
+
+async fn handle_maintenance_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+    request: maintenance::Request,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::SetMaintenanceMode(callback_tx, request).traced(trace::current())).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
+
+async fn handle_group_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+    group: String,
+    action: shared::group::Action,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::GroupAction { callback: callback_tx, group, action }.traced(trace::current())).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
+
+async fn handle_xbee_audit_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::AuditXbees(callback_tx).traced(trace::current())).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
+
+async fn handle_return_to_base_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::ReturnToBase(callback_tx).traced(trace::current())).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
+}
+
+async fn handle_take_over_request(
+    replication_tx: &mpsc::Sender<replication::Action>,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    replication_tx.send(replication::Action::TakeOver(callback_tx)).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to replication"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from replication"))?
+}
+
+async fn handle_identification_request(
+    identification_tx: &mpsc::Sender<identification::Action>,
+    robots: Option<Vec<String>>,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    identification_tx.send(identification::Action::Start(callback_tx, robots)).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to identification task"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from identification task"))?
+}
+
+async fn handle_pairing_request(
+    pairing_tx: &mpsc::Sender<pairing::Action>,
+    request: shared::pairing::Request,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let action = match request {
+        shared::pairing::Request::Identify(macaddr, enable) => pairing::Action::Identify(callback_tx, macaddr, enable),
     };
-    arena_tx.send(action).await
+    pairing_tx.send(action).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to pairing task"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from pairing task"))?
+}
+
+async fn handle_association_request(
+    arena_tx: &mpsc::Sender<arena::Action>,
+    request: association::Request,
+) -> anyhow::Result<()> {
+    let (callback_tx, callback_rx) = oneshot::channel();
+    arena_tx.send(arena::Action::ResolveQuarantine(callback_tx, request).traced(trace::current())).await
         .map_err(|_| anyhow::anyhow!("Could not send action to arena"))?;
     callback_rx.await.map_err(|_| anyhow::anyhow!("No response from arena"))?
-}
\ No newline at end of file
+}
+
+async fn handle_profile_request(
+    profiles_tx: &mpsc::Sender<profiles::Action>,
+    request: shared::experiment::profile::Request,
+) -> anyhow::Result<()> {
+    use shared::experiment::profile::Request;
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let action = match request {
+        Request::Save(profile) => profiles::Action::Save(callback_tx, profile),
+        Request::Delete(name) => profiles::Action::Delete(callback_tx, name),
+    };
+    profiles_tx.send(action).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to profiles task"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from profiles task"))?
+}
+
+async fn handle_library_request(
+    library_tx: &mpsc::Sender<library::Action>,
+    request: shared::experiment::library::Request,
+) -> anyhow::Result<()> {
+    use shared::experiment::library::Request;
+    match request {
+        Request::Store(name, software) => {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            library_tx.send(library::Action::Store(callback_tx, name, software)).await
+                .map_err(|_| anyhow::anyhow!("Could not send action to library task"))?;
+            callback_rx.await.map_err(|_| anyhow::anyhow!("No response from library task"))?.map(|_| ())
+        },
+        Request::Delete(reference) => {
+            let (callback_tx, callback_rx) = oneshot::channel();
+            library_tx.send(library::Action::Delete(callback_tx, reference)).await
+                .map_err(|_| anyhow::anyhow!("Could not send action to library task"))?;
+            callback_rx.await.map_err(|_| anyhow::anyhow!("No response from library task"))?
+        },
+    }
+}
+
+async fn handle_configuration_request(
+    configuration_tx: &mpsc::Sender<configuration::Action>,
+    request: shared::configuration::Request,
+) -> anyhow::Result<()> {
+    use shared::configuration::Request;
+    use configuration::Action;
+    let (callback_tx, callback_rx) = oneshot::channel();
+    let action = match request {
+        Request::Update(configuration) => Action::Update(callback_tx, configuration),
+        Request::Save => Action::Save(callback_tx),
+        Request::Reload => Action::Reload(callback_tx),
+    };
+    configuration_tx.send(action).await
+        .map_err(|_| anyhow::anyhow!("Could not send action to configuration task"))?;
+    callback_rx.await.map_err(|_| anyhow::anyhow!("No response from configuration task"))?
+}
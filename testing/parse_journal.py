@@ -1,10 +1,22 @@
+import argparse
+import csv
+import math
+import os
 import pickle
-import sys
 import re
 
-if len(sys.argv) < 2:
-   print('usage: python ' + sys.argv[0] + ' JOURNAL_FILE')
-   exit()
+parser = argparse.ArgumentParser(description='Parse a journal recorded by the supervisor')
+parser.add_argument('journal', metavar='JOURNAL_FILE')
+parser.add_argument('--csv', metavar='DIRECTORY',
+   help='export the tracking data of each rigid body to a CSV file in DIRECTORY')
+parser.add_argument('--interpolate', metavar='FRAMES', type=int, default=0,
+   help='fill gaps of at most FRAMES missing frames with interpolated poses in the CSV export')
+parser.add_argument('--tag', metavar='TAG', action='append', default=[],
+   help='only keep the timeline events with TAG (may be repeated to keep the events with any of the tags)')
+args = parser.parse_args()
+
+# the version of the journal format that this script understands
+SCHEMA_VERSION = 3
 
 class Drone:
    def __init__(self, xbee_macaddr, upcore_macaddr, optitrack_id):
@@ -93,49 +105,81 @@ argos_logs = {}
 messages = {}
 # global dictionary of tracking data (indexed by rigid body id)
 tracking_system = {}
+# global list of timeline events (phases, safety events, disconnects, and annotations)
+timeline = []
+# global dictionary of fingerprint components (indexed by component name)
+fingerprint = {}
+# global dictionary of battery cell voltages in millivolts (indexed by drone id)
+battery_cells = {}
+# global dictionary of the energy used by each robot during the experiment (indexed by robot id)
+energy = {}
+# statistics about the frames from the tracking system that were dropped
+tracking_gaps = None
+# the robots that participated once the experiment started (None for journals recorded before this was added)
+participants = None
+# the header that identifies the experiment (None for journals recorded before headers were added)
+header = None
+
+def load_descriptors(builderbot_descriptors, drone_descriptors, pipuck_descriptors):
+   global builderbots, drones, pipucks
+   builderbots = {
+      builderbot['id']: BuilderBot(
+            builderbot['duovero_macaddr'],
+            builderbot['apriltag_id'],
+            builderbot['optitrack_id']
+       ) for builderbot in builderbot_descriptors
+   }
+   drones = {
+      drone['id']: Drone(
+            drone['xbee_macaddr'],
+            drone['upcore_macaddr'],
+            drone['optitrack_id']
+      ) for drone in drone_descriptors
+   }
+   pipucks = {
+      pipuck['id']: PiPuck(
+            pipuck['rpi_macaddr'],
+            pipuck['apriltag_id'],
+            pipuck['optitrack_id']
+       ) for pipuck in pipuck_descriptors
+   }
 
 # load journal file into local data structures
-journal_file = open(sys.argv[1], 'rb')
+journal_file = open(args.journal, 'rb')
 while True:
    try:
       entry = pickle.load(journal_file)
       timestamp = entry['timestamp']
       event_type, event = entry['event']
       if event_type == 'TrackingSystem':
-         for update in event:
+         # note: journals recorded before frame numbers were added only contain the updates
+         if isinstance(event, dict):
+            frame, updates = event['frame'], event['updates']
+         else:
+            frame, updates = None, event
+         for update in updates:
             rigid_body_id = update['id']
             entry = {
                'timestamp': timestamp,
+               'frame': frame,
                'position': update['position'],
-               'orientation': update['orientation']
+               'orientation': update['orientation'],
+               'interpolated': False
             }
             if rigid_body_id in tracking_system:
                tracking_system[rigid_body_id].append(entry)
             else:
                tracking_system[rigid_body_id] = [entry]
+      elif event_type == 'Header':
+         # note: this message is the first entry of the journal
+         if event['schema_version'] > SCHEMA_VERSION:
+            raise SystemExit('[error] journal uses schema version {} but only versions up to {} are supported'
+               .format(event['schema_version'], SCHEMA_VERSION))
+         header = event
+         load_descriptors(event['builderbots'], event['drones'], event['pipucks'])
       elif event_type == 'Descriptors':
-         # note: this message should only be present once
-         builderbots = {
-            builderbot['id']: BuilderBot(
-                  builderbot['duovero_macaddr'],
-                  builderbot['apriltag_id'],
-                  builderbot['optitrack_id']
-             ) for builderbot in event[0]
-         }
-         drones = {
-            drone['id']: Drone(
-                  drone['xbee_macaddr'],
-                  drone['upcore_macaddr'],
-                  drone['optitrack_id']
-            ) for drone in event[1]
-         }
-         pipucks = {
-            pipuck['id']: PiPuck(
-                  pipuck['rpi_macaddr'],
-                  pipuck['apriltag_id'],
-                  pipuck['optitrack_id']
-             ) for pipuck in event[2]
-         }
+         # note: journals recorded before headers were added contain the descriptors in this message
+         load_descriptors(event[0], event[1], event[2])
       elif event_type == 'ARGoS':
          robot_id = event[0]
          if robot_id not in argos_logs:
@@ -157,6 +201,40 @@ while True:
             messages[source].append(message)
          else:
             messages[source] = [message]
+      elif event_type == 'Timeline':
+         category = event[0]
+         # note: unit variants may be encoded as a tuple containing only the variant name
+         if isinstance(category, tuple):
+            category = category[0]
+         # note: the tags are only recorded since schema version 3
+         tags = list(event[2]) if len(event) > 2 else []
+         if args.tag and not any(tag in tags for tag in args.tag):
+            continue
+         timeline.append({
+            'timestamp': timestamp,
+            'category': category,
+            'description': event[1],
+            'tags': tags,
+         })
+      elif event_type == 'Fingerprint':
+         # note: the digest over all components is also written to the .fingerprint.json file
+         fingerprint = event['components']
+      elif event_type == 'TrackingGaps':
+         tracking_gaps = event
+      elif event_type == 'BatteryCells':
+         drone_id = event[0]
+         reading = {
+            'timestamp': timestamp,
+            'cells': event[1],
+         }
+         if drone_id in battery_cells:
+            battery_cells[drone_id].append(reading)
+         else:
+            battery_cells[drone_id] = [reading]
+      elif event_type == 'Energy':
+         energy[event[0]] = event[1]
+      elif event_type == 'Participants':
+         participants = event
    except EOFError:
       break
 
@@ -228,3 +306,68 @@ for pipuck_id, pipuck_obj in pipucks.items():
    # get the tracking system data
    if pipuck_obj.optitrack_id in tracking_system:
       pipuck_obj.optitrack_data = tracking_system[pipuck_obj.optitrack_id]
+
+# report the frames from the tracking system that were dropped while recording
+if tracking_gaps is not None and tracking_gaps['dropped'] > 0:
+   print('[warning] {} frames from the tracking system were dropped in {} gaps (longest {})'.format(
+      tracking_gaps['dropped'], tracking_gaps['gaps'], tracking_gaps['longest']))
+
+# report the robots that did not participate since they could not be set up or started
+if participants is not None:
+   missing = sorted((set(builderbots) | set(drones) | set(pipucks)) - set(participants))
+   if missing:
+      print('[warning] the experiment continued without {}'.format(', '.join(missing)))
+
+# report the fraction of the battery capacity that each robot used during the experiment
+for robot_id, robot_energy in sorted(energy.items()):
+   print('[info] {} used {}% of its battery ({}% to {}%)'.format(robot_id,
+      max(robot_energy['start'] - robot_energy['end'], 0), robot_energy['start'], robot_energy['end']))
+
+# linearly interpolates the poses between two entries, the orientation is normalized after interpolation
+def interpolate(before, after, frame):
+   t = (frame - before['frame']) / (after['frame'] - before['frame'])
+   lerp = lambda a, b: a + (b - a) * t
+   # use the shortest path between the two orientations
+   dot = sum(a * b for a, b in zip(before['orientation'], after['orientation']))
+   sign = -1.0 if dot < 0.0 else 1.0
+   orientation = [lerp(a, sign * b) for a, b in zip(before['orientation'], after['orientation'])]
+   norm = math.sqrt(sum(component * component for component in orientation))
+   return {
+      'timestamp': int(round(lerp(before['timestamp'], after['timestamp']))),
+      'frame': frame,
+      'position': [lerp(a, b) for a, b in zip(before['position'], after['position'])],
+      'orientation': [component / norm for component in orientation],
+      'interpolated': True
+   }
+
+# fills the gaps of at most max_gap missing frames with interpolated entries
+def fill_gaps(entries, max_gap):
+   filled = []
+   for before, after in zip(entries, entries[1:] + [None]):
+      filled.append(before)
+      if after is None or before['frame'] is None or after['frame'] is None:
+         continue
+      missing = after['frame'] - before['frame'] - 1
+      if 0 < missing <= max_gap:
+         filled.extend(interpolate(before, after, frame) for frame in range(before['frame'] + 1, after['frame']))
+   return filled
+
+# export the tracking data of each rigid body, interpolated entries are flagged in the last column
+if args.csv is not None:
+   os.makedirs(args.csv, exist_ok=True)
+   for rigid_body_id, entries in tracking_system.items():
+      if args.interpolate > 0:
+         entries = fill_gaps(entries, args.interpolate)
+      path = os.path.join(args.csv, 'rigid_body_{}.csv'.format(rigid_body_id))
+      with open(path, 'w', newline='') as csv_file:
+         writer = csv.writer(csv_file)
+         writer.writerow(['timestamp', 'frame', 'x', 'y', 'z', 'qw', 'qx', 'qy', 'qz', 'interpolated'])
+         for entry in entries:
+            writer.writerow([entry['timestamp'], entry['frame']] + list(entry['position']) +
+               list(entry['orientation']) + [int(entry['interpolated'])])
+   # export the (filtered) timeline, the tags of each event are separated by spaces
+   with open(os.path.join(args.csv, 'timeline.csv'), 'w', newline='') as csv_file:
+      writer = csv.writer(csv_file)
+      writer.writerow(['timestamp', 'category', 'description', 'tags'])
+      for entry in timeline:
+         writer.writerow([entry['timestamp'], entry['category'], entry['description'], ' '.join(entry['tags'])])